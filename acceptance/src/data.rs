@@ -1,15 +1,19 @@
-use std::{path::Path, sync::Arc};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use delta_kernel::arrow::array::{Array, RecordBatch};
 use delta_kernel::arrow::compute::{
     concat_batches, filter_record_batch, lexsort_to_indices, take, SortColumn,
 };
-use delta_kernel::arrow::datatypes::{DataType, Schema};
+use delta_kernel::arrow::datatypes::DataType;
+use delta_kernel::arrow::util::display::array_value_to_string;
 
 use delta_kernel::object_store::{local::LocalFileSystem, ObjectStore};
 use delta_kernel::parquet::arrow::async_reader::{
     ParquetObjectReader, ParquetRecordBatchStreamBuilder,
 };
+use delta_kernel::parquet::arrow::ArrowWriter;
 use delta_kernel::snapshot::Snapshot;
 use delta_kernel::{engine::arrow_data::ArrowEngineData, DeltaResult, Engine, Error};
 use futures::{stream::TryStreamExt, StreamExt};
@@ -17,8 +21,12 @@ use itertools::Itertools;
 
 use crate::{TestCaseInfo, TestResult};
 
+fn golden_root(path: &Path) -> PathBuf {
+    path.join("expected").join("latest").join("table_content")
+}
+
 pub async fn read_golden(path: &Path, _version: Option<&str>) -> DeltaResult<RecordBatch> {
-    let expected_root = path.join("expected").join("latest").join("table_content");
+    let expected_root = golden_root(path);
     let store = Arc::new(LocalFileSystem::new_with_prefix(&expected_root)?);
     let files: Vec<_> = store.list(None).try_collect().await?;
     let mut batches = vec![];
@@ -65,23 +73,8 @@ pub fn sort_record_batch(batch: RecordBatch) -> DeltaResult<RecordBatch> {
     Ok(RecordBatch::try_new(batch.schema(), columns)?)
 }
 
-// Ensure that two schema have the same field names, and dict_is_ordered
-// We ignore:
-//  - data type: This is checked already in `assert_columns_match`
-//  - nullability: parquet marks many things as nullable that we don't in our schema
-//  - metadata: because that diverges from the real data to the golden tabled data
-fn assert_schema_fields_match(schema: &Schema, golden: &Schema) {
-    for (schema_field, golden_field) in schema.fields.iter().zip(golden.fields.iter()) {
-        assert!(
-            schema_field.name() == golden_field.name(),
-            "Field names don't match"
-        );
-        assert!(
-            schema_field.dict_is_ordered() == golden_field.dict_is_ordered(),
-            "Field dict_is_ordered doesn't match"
-        );
-    }
-}
+/// The maximum number of mismatched rows to describe per column in a [`diff_report`].
+const MAX_REPORTED_ROWS: usize = 5;
 
 // some things are equivalent, but don't show up as equivalent for `==`, so we normalize here
 fn normalize_col(col: Arc<dyn Array>) -> Arc<dyn Array> {
@@ -97,23 +90,86 @@ fn normalize_col(col: Arc<dyn Array>) -> Arc<dyn Array> {
     }
 }
 
-fn assert_columns_match(actual: &[Arc<dyn Array>], expected: &[Arc<dyn Array>]) {
-    for (actual, expected) in actual.iter().zip(expected) {
-        let actual = normalize_col(actual.clone());
-        let expected = normalize_col(expected.clone());
-        // note that array equality includes data_type equality
-        // See: https://arrow.apache.org/rust/arrow_data/equal/fn.equal.html
-        assert_eq!(
-            &actual, &expected,
-            "Column data didn't match. Got {actual:?}, expected {expected:?}"
-        );
+fn format_value(array: &dyn Array, row: usize) -> String {
+    array_value_to_string(array, row).unwrap_or_else(|e| format!("<unprintable: {e}>"))
+}
+
+/// Compare the (sorted) data read by kernel against the (sorted) golden data, returning a
+/// human-readable report of every difference found, or `None` if they match.
+///
+/// We compare field names, `dict_is_ordered`, row counts, and column data (which includes data
+/// types). We ignore:
+///  - nullability: parquet marks many things as nullable that we don't in our schema
+///  - metadata: because that diverges from the real data to the golden tabled data
+pub fn diff_report(actual: &RecordBatch, expected: &RecordBatch) -> Option<String> {
+    let mut report = vec![];
+    let (actual_schema, expected_schema) = (actual.schema(), expected.schema());
+    if actual.num_columns() != expected.num_columns() {
+        report.push(format!(
+            "Number of columns differs: got {}, expected {}",
+            actual.num_columns(),
+            expected.num_columns()
+        ));
+    }
+    if actual.num_rows() != expected.num_rows() {
+        report.push(format!(
+            "Number of rows differs: got {}, expected {}",
+            actual.num_rows(),
+            expected.num_rows()
+        ));
+    }
+    let fields = actual_schema.fields().iter().zip(expected_schema.fields());
+    let columns = actual.columns().iter().zip(expected.columns());
+    for ((actual_field, expected_field), (actual_col, expected_col)) in fields.zip(columns) {
+        let name = actual_field.name();
+        if name != expected_field.name() {
+            report.push(format!(
+                "Field names don't match: got {name:?}, expected {:?}",
+                expected_field.name()
+            ));
+        }
+        if actual_field.dict_is_ordered() != expected_field.dict_is_ordered() {
+            report.push(format!("Field {name:?}: dict_is_ordered doesn't match"));
+        }
+        let actual_col = normalize_col(actual_col.clone());
+        let expected_col = normalize_col(expected_col.clone());
+        if actual_col.data_type() != expected_col.data_type() {
+            report.push(format!(
+                "Column {name:?}: data type differs: got {}, expected {}",
+                actual_col.data_type(),
+                expected_col.data_type()
+            ));
+        } else if actual_col.as_ref() != expected_col.as_ref() {
+            // note that array equality includes data_type equality
+            // See: https://arrow.apache.org/rust/arrow_data/equal/fn.equal.html
+            let num_rows = actual_col.len().min(expected_col.len());
+            let mismatches = (0..num_rows)
+                .filter(|&row| {
+                    actual_col.slice(row, 1).as_ref() != expected_col.slice(row, 1).as_ref()
+                })
+                .collect_vec();
+            report.push(format!(
+                "Column {name:?}: {} of {num_rows} compared rows differ",
+                mismatches.len()
+            ));
+            for &row in mismatches.iter().take(MAX_REPORTED_ROWS) {
+                report.push(format!(
+                    "  row {row}: got {}, expected {}",
+                    format_value(&actual_col, row),
+                    format_value(&expected_col, row)
+                ));
+            }
+        }
     }
+    (!report.is_empty()).then(|| report.join("\n"))
 }
 
-pub async fn assert_scan_metadata(
+/// Read all the data of the latest version of the test case's table via a kernel scan, sorted by
+/// all (sortable) columns.
+pub async fn read_table_data(
     engine: Arc<dyn Engine>,
     test_case: &TestCaseInfo,
-) -> TestResult<()> {
+) -> TestResult<RecordBatch> {
     let table_root = test_case.table_root()?;
     let snapshot = Snapshot::try_new(table_root, engine.as_ref(), None)?;
     let scan = snapshot.into_scan_builder().build()?;
@@ -140,16 +196,45 @@ pub async fn assert_scan_metadata(
         })
         .try_collect()?;
     let all_data = concat_batches(&schema.unwrap(), batches.iter()).map_err(Error::from)?;
-    let all_data = sort_record_batch(all_data)?;
+    Ok(sort_record_batch(all_data)?)
+}
+
+pub async fn assert_scan_metadata(
+    engine: Arc<dyn Engine>,
+    test_case: &TestCaseInfo,
+) -> TestResult<()> {
+    let all_data = read_table_data(engine, test_case).await?;
     let golden = read_golden(test_case.root_dir(), None).await?;
     let golden = sort_record_batch(golden)?;
 
-    assert_columns_match(all_data.columns(), golden.columns());
-    assert_schema_fields_match(all_data.schema().as_ref(), golden.schema().as_ref());
-    assert!(
-        all_data.num_rows() == golden.num_rows(),
-        "Didn't have same number of rows"
-    );
+    if let Some(report) = diff_report(&all_data, &golden) {
+        panic!(
+            "Data read from {} didn't match golden data:\n{report}",
+            test_case.root_dir().display()
+        );
+    }
+    Ok(())
+}
 
+/// Regenerate the expected table content of the test case from kernel's own scan of the table,
+/// replacing any existing parquet files under `expected/latest/table_content`.
+pub async fn write_golden(engine: Arc<dyn Engine>, test_case: &TestCaseInfo) -> TestResult<()> {
+    let all_data = read_table_data(engine, test_case).await?;
+    let expected_root = golden_root(test_case.root_dir());
+    std::fs::create_dir_all(&expected_root).map_err(Error::from)?;
+    for entry in std::fs::read_dir(&expected_root).map_err(Error::from)? {
+        let path = entry.map_err(Error::from)?.path();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        if file_name.ends_with(".parquet") || file_name.ends_with(".parquet.crc") {
+            std::fs::remove_file(&path).map_err(Error::from)?;
+        }
+    }
+    let file = File::create(expected_root.join("part-00000.parquet")).map_err(Error::from)?;
+    let mut writer = ArrowWriter::try_new(file, all_data.schema(), None).map_err(Error::from)?;
+    writer.write(&all_data).map_err(Error::from)?;
+    writer.close().map_err(Error::from)?;
     Ok(())
 }
@@ -3,3 +3,13 @@
 pub mod data;
 pub mod meta;
 pub use meta::*;
+
+/// When this environment variable is set, the acceptance tests regenerate the expected outputs of
+/// each test case from kernel's own reads instead of asserting against them.
+pub const REGENERATE_GOLDEN_ENV_VAR: &str = "DAT_REGENERATE_GOLDEN";
+
+/// True if the acceptance tests should regenerate expected outputs. See
+/// [`REGENERATE_GOLDEN_ENV_VAR`].
+pub fn regenerate_golden() -> bool {
+    std::env::var_os(REGENERATE_GOLDEN_ENV_VAR).is_some()
+}
@@ -83,18 +83,7 @@ impl TestCaseInfo {
         assert_eq!(snapshot.version(), case.version);
 
         // assert correct metadata is read
-        let metadata = snapshot.metadata();
-        let protocol = snapshot.protocol();
-        let tvm = TableVersionMetaData {
-            version: snapshot.version(),
-            properties: metadata
-                .configuration()
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect(),
-            min_reader_version: protocol.min_reader_version() as u32,
-            min_writer_version: protocol.min_writer_version() as u32,
-        };
+        let tvm = TableVersionMetaData::from_snapshot(snapshot);
         assert_eq!(&tvm, case);
         Ok(())
     }
@@ -114,6 +103,40 @@ impl TestCaseInfo {
 
         Ok(())
     }
+
+    /// Regenerate every expected `table_version_metadata.json` of this test case (the latest one
+    /// as well as any pinned versions) from what kernel reads for the corresponding snapshot.
+    pub async fn write_metadata(&self, engine: Arc<dyn Engine>) -> TestResult<()> {
+        let engine = engine.as_ref();
+        let expected_root = self.root_dir.join("expected");
+        let store = LocalFileSystem::new_with_prefix(&expected_root).map_err(Error::from)?;
+        let files: Vec<_> = store.list(None).try_collect().await.map_err(Error::from)?;
+        let case_files = files
+            .into_iter()
+            .filter(|meta| meta.location.filename() == Some("table_version_metadata.json"));
+
+        for case in case_files {
+            let case_file = expected_root.join(case.location.as_ref());
+            let is_latest = case
+                .location
+                .prefix_matches(&object_store::path::Path::from("latest"));
+            let version = match is_latest {
+                true => None,
+                false => {
+                    let file =
+                        File::open(&case_file).map_err(|_| AssertionError::InvalidTestCase)?;
+                    let info: TableVersionMetaData = serde_json::from_reader(file)
+                        .map_err(|_| AssertionError::InvalidTestCase)?;
+                    Some(info.version)
+                }
+            };
+            let snapshot = Snapshot::try_new(self.table_root()?, engine, version)?;
+            let file = File::create(case_file).map_err(|_| AssertionError::InvalidTestCase)?;
+            serde_json::to_writer(file, &TableVersionMetaData::from_snapshot(&snapshot))
+                .map_err(|_| AssertionError::InvalidTestCase)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -124,6 +147,23 @@ pub struct TableVersionMetaData {
     min_writer_version: u32,
 }
 
+impl TableVersionMetaData {
+    fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let metadata = snapshot.metadata();
+        let protocol = snapshot.protocol();
+        Self {
+            version: snapshot.version(),
+            properties: metadata
+                .configuration()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            min_reader_version: protocol.min_reader_version() as u32,
+            min_writer_version: protocol.min_writer_version() as u32,
+        }
+    }
+}
+
 pub fn read_dat_case(case_root: impl AsRef<Path>) -> TestResult<TestCaseInfo> {
     let info_path = case_root.as_ref().join("test_case_info.json");
     let file = File::open(info_path).map_err(|_| AssertionError::InvalidTestCase)?;
@@ -25,7 +25,7 @@ fn reader_test(path: &Path) -> datatest_stable::Result<()> {
         .enable_all()
         .build()?
         .block_on(async {
-            let case = read_dat_case(root_dir).unwrap();
+            let case = read_dat_case(&root_dir).unwrap();
             let table_root = case.table_root().unwrap();
             let engine = Arc::new(
                 DefaultEngine::try_new(
@@ -36,6 +36,15 @@ fn reader_test(path: &Path) -> datatest_stable::Result<()> {
                 .unwrap(),
             );
 
+            if acceptance::regenerate_golden() {
+                eprintln!("Regenerating expected data for: {root_dir}");
+                case.write_metadata(engine.clone()).await.unwrap();
+                acceptance::data::write_golden(engine.clone(), &case)
+                    .await
+                    .unwrap();
+                return;
+            }
+
             case.assert_metadata(engine.clone()).await.unwrap();
             acceptance::data::assert_scan_metadata(engine.clone(), &case)
                 .await
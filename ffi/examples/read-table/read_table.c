@@ -326,6 +326,16 @@ int main(int argc, char* argv[])
     }
   }
 
+  ScanMetrics metrics = scan_metadata_metrics(data_iter);
+  print_diag(
+    "Scan listed %" PRIu64 " files, pruned %" PRIu64 " by partition and %" PRIu64
+    " by stats, selected %" PRIu64 " files (%" PRIu64 " bytes)\n",
+    metrics.files_listed,
+    metrics.files_pruned_by_partition,
+    metrics.files_pruned_by_stats,
+    metrics.files_selected,
+    metrics.bytes_scanned);
+
   print_diag("All done reading table data\n");
 
 #ifdef PRINT_ARROW_DATA
@@ -32,6 +32,8 @@ enum OpType {
   Equal,
   Distinct,
   In,
+  StartsWith,
+  Like,
 };
 enum LitType {
   Integer,
@@ -178,6 +180,8 @@ DEFINE_BINOP(visit_expr_gt, GreaterThan)
 DEFINE_BINOP(visit_expr_eq, Equal)
 DEFINE_BINOP(visit_expr_distinct, Distinct)
 DEFINE_BINOP(visit_expr_in, In)
+DEFINE_BINOP(visit_expr_starts_with, StartsWith)
+DEFINE_BINOP(visit_expr_like, Like)
 #undef DEFINE_BINOP
 
 /*************************************************************
@@ -400,6 +404,8 @@ ExpressionItemList construct_expression(SharedExpression* expression) {
     .visit_eq = visit_expr_eq,
     .visit_distinct = visit_expr_distinct,
     .visit_in = visit_expr_in,
+    .visit_starts_with = visit_expr_starts_with,
+    .visit_like = visit_expr_like,
     .visit_add = visit_expr_add,
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
@@ -446,6 +452,8 @@ ExpressionItemList construct_predicate(SharedPredicate* predicate) {
     .visit_eq = visit_expr_eq,
     .visit_distinct = visit_expr_distinct,
     .visit_in = visit_expr_in,
+    .visit_starts_with = visit_expr_starts_with,
+    .visit_like = visit_expr_like,
     .visit_add = visit_expr_add,
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
@@ -0,0 +1,71 @@
+//! ABI version negotiation and capability discovery.
+//!
+//! Engines built against one version of the kernel headers may be linked against a different
+//! build of the kernel library. An engine should call [`kernel_abi_version`] before anything else
+//! and refuse to continue if it does not match the `KERNEL_ABI_VERSION` it was compiled against.
+//! Once it has an engine, it can call [`engine_capabilities`] to find out which optional feature
+//! sets (the `KERNEL_CAPABILITY_*` bits) are usable through this library.
+
+use crate::handle::Handle;
+use crate::SharedExternEngine;
+
+/// The version of the C ABI exposed by this library. This is bumped whenever a change is made that
+/// is not backwards compatible for engines, e.g. changing the layout of a `#[repr(C)]` type or the
+/// signature of an exported function.
+pub const KERNEL_ABI_VERSION: u32 = 1;
+
+/// Reading change data feed (CDF) is supported.
+pub const KERNEL_CAPABILITY_CDF: u64 = 1 << 0;
+/// Writing to tables (transactions and commits) is supported.
+pub const KERNEL_CAPABILITY_WRITES: u64 = 1 << 1;
+/// Writing checkpoints is supported.
+pub const KERNEL_CAPABILITY_CHECKPOINTING: u64 = 1 << 2;
+/// Building and evaluating expressions is supported.
+pub const KERNEL_CAPABILITY_EXPRESSIONS: u64 = 1 << 3;
+
+/// The capabilities exposed through the FFI by this build of the library. Capabilities supported
+/// by the kernel but not yet exposed through the FFI are not reported.
+const SUPPORTED_CAPABILITIES: u64 = KERNEL_CAPABILITY_CHECKPOINTING | KERNEL_CAPABILITY_EXPRESSIONS;
+
+/// Get the ABI version of this library. Engines should compare this against the
+/// `KERNEL_ABI_VERSION` constant from the headers they were compiled with.
+#[no_mangle]
+pub extern "C" fn kernel_abi_version() -> u32 {
+    KERNEL_ABI_VERSION
+}
+
+/// Get the capabilities of this library, as a bitmask of `KERNEL_CAPABILITY_*` flags.
+#[no_mangle]
+pub extern "C" fn kernel_capabilities() -> u64 {
+    SUPPORTED_CAPABILITIES
+}
+
+/// Get the capabilities available for the given engine, as a bitmask of `KERNEL_CAPABILITY_*`
+/// flags. Engines should call this right after creating an engine to find out which features they
+/// may use with it.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn engine_capabilities(engine: Handle<SharedExternEngine>) -> u64 {
+    // Every engine currently supports everything the library exposes; the engine is still taken
+    // so that engine-specific restrictions can be reported without an ABI change.
+    let _engine = unsafe { engine.as_ref() };
+    kernel_capabilities()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abi_version_and_capabilities() {
+        assert_eq!(kernel_abi_version(), KERNEL_ABI_VERSION);
+        let capabilities = kernel_capabilities();
+        assert_ne!(capabilities & KERNEL_CAPABILITY_EXPRESSIONS, 0);
+        assert_ne!(capabilities & KERNEL_CAPABILITY_CHECKPOINTING, 0);
+        assert_eq!(capabilities & KERNEL_CAPABILITY_CDF, 0);
+        assert_eq!(capabilities & KERNEL_CAPABILITY_WRITES, 0);
+    }
+}
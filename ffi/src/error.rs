@@ -56,6 +56,8 @@ pub enum KernelError {
     LiteralExpressionTransformError,
     CheckpointWriteError,
     SchemaError,
+    RetryableError,
+    ConstraintViolationError,
 }
 
 impl From<Error> for KernelError {
@@ -119,6 +121,8 @@ impl From<Error> for KernelError {
                 KernelError::LiteralExpressionTransformError
             }
             Error::Schema(_) => KernelError::SchemaError,
+            Error::Retryable(_) => KernelError::RetryableError,
+            Error::ConstraintViolation(_) => KernelError::ConstraintViolationError,
             _ => KernelError::UnknownError,
         }
     }
@@ -147,6 +147,12 @@ pub struct EngineExpressionVisitor {
     /// Visits the `In` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_in: VisitBinaryFn,
+    /// Visits the `StartsWith` binary operator belonging to the list identified by `sibling_list_id`.
+    /// The operands will be in a _two_ item list identified by `child_list_id`
+    pub visit_starts_with: VisitBinaryFn,
+    /// Visits the `Like` binary operator belonging to the list identified by `sibling_list_id`.
+    /// The operands will be in a _two_ item list identified by `child_list_id`
+    pub visit_like: VisitBinaryFn,
     /// Visits the `Add` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_add: VisitBinaryFn,
@@ -507,16 +513,20 @@ fn visit_predicate_impl(
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
         Predicate::Binary(BinaryPredicate { op, left, right }) => {
-            let child_list_id = call!(visitor, make_field_list, 2);
-            visit_expression_impl(visitor, left, child_list_id);
-            visit_expression_impl(visitor, right, child_list_id);
             let visit_fn = match op {
                 BinaryPredicateOp::LessThan => visitor.visit_lt,
                 BinaryPredicateOp::GreaterThan => visitor.visit_gt,
                 BinaryPredicateOp::Equal => visitor.visit_eq,
                 BinaryPredicateOp::Distinct => visitor.visit_distinct,
                 BinaryPredicateOp::In => visitor.visit_in,
+                BinaryPredicateOp::StartsWith => visitor.visit_starts_with,
+                BinaryPredicateOp::Like => visitor.visit_like,
+                // An op this visitor has no callback for is reported as unknown
+                _ => return visit_unknown(visitor, sibling_list_id, &predicate.to_string()),
             };
+            let child_list_id = call!(visitor, make_field_list, 2);
+            visit_expression_impl(visitor, left, child_list_id);
+            visit_expression_impl(visitor, right, child_list_id);
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
         Predicate::Junction(JunctionPredicate { op, preds }) => {
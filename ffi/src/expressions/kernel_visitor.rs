@@ -191,6 +191,24 @@ pub extern "C" fn visit_predicate_ne(
     visit_predicate_not(state, p)
 }
 
+#[no_mangle]
+pub extern "C" fn visit_predicate_starts_with(
+    state: &mut KernelExpressionVisitorState,
+    a: usize,
+    b: usize,
+) -> usize {
+    visit_predicate_binary(state, BinaryPredicateOp::StartsWith, a, b)
+}
+
+#[no_mangle]
+pub extern "C" fn visit_predicate_like(
+    state: &mut KernelExpressionVisitorState,
+    a: usize,
+    b: usize,
+) -> usize {
+    visit_predicate_binary(state, BinaryPredicateOp::Like, a, b)
+}
+
 #[no_mangle]
 pub extern "C" fn visit_predicate_unknown(
     state: &mut KernelExpressionVisitorState,
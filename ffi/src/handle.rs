@@ -19,6 +19,23 @@
 //!
 //! NOTE: While shared handles could conceptually impl [`Clone`], cloning would require unsafe code
 //! and so we can't actually implement the trait. Use [`Handle::clone_handle`] instead.
+//!
+//! # Handle auditing
+//!
+//! Debug builds record every live handle, and abort with a message naming the handle type as soon
+//! as external code uses a handle that was already dropped or consumed (including a double free),
+//! or passes a handle as the wrong handle type. Without this audit, such mistakes are undefined
+//! behavior. The audit cannot detect a stale handle whose address was reused by a newer handle of
+//! the same type.
+//!
+//! Embedders can also call [`enable_handle_tracking`] (not needed for debug builds) and then
+//! [`visit_live_handles`], e.g. at the end of a test suite, to report handles that were leaked.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use crate::{KernelStringSlice, NullableCvoid};
 
 /// Describes the kind of handle a given opaque pointer type represents.
 ///
@@ -143,6 +160,7 @@ mod private {
         ///
         /// * No mutable references can overlap with the returned reference.
         pub unsafe fn as_ref(&self) -> &H::Target {
+            self.audit(audit::check);
             H::as_ref(self.ptr.cast().as_ptr())
         }
 
@@ -153,6 +171,7 @@ mod private {
         ///
         /// Caller asserts that the handle is [valid][Handle#Validity].
         pub unsafe fn into_inner(self) -> H::From {
+            self.audit(audit::unregister);
             H::into_inner(self.ptr.cast().as_ptr())
         }
         /// Drops this handle. Dropping a mutable handle always drops the underlying object as well;
@@ -175,6 +194,18 @@ mod private {
         }
     }
 
+    impl<H: HandleDescriptor> Handle<H> {
+        // Records (or checks) this handle with the handle audit, aborting if the audit finds that
+        // the handle is invalid.
+        fn audit(&self, op: fn(usize, &'static str) -> Result<(), String>) {
+            let type_name = std::any::type_name::<H>();
+            if let Err(msg) = op(self.ptr.as_ptr() as usize, type_name) {
+                eprintln!("delta_kernel_ffi: {msg}");
+                std::process::abort();
+            }
+        }
+    }
+
     // [`Handle`] operations applicable only to mutable handles, with implementations forwarded to
     // the appropriate specialization of `MutableHandleOps`.
     impl<T, S, H> Handle<H>
@@ -197,6 +228,7 @@ mod private {
         ///   reference. In particular, this means the caller must ensure no other thread can access this
         ///   handle while the returned reference is still alive.
         pub unsafe fn as_mut(&mut self) -> &mut T {
+            self.audit(audit::check);
             H::as_mut(self.ptr.cast().as_ptr())
         }
     }
@@ -228,6 +260,7 @@ mod private {
         ///
         /// Caller asserts that the handle is [valid][Handle#Validity].
         pub unsafe fn clone_as_arc(&self) -> Arc<T> {
+            self.audit(audit::check);
             H::clone_arc(self.ptr.cast().as_ptr())
         }
     }
@@ -243,7 +276,9 @@ mod private {
     {
         fn from(val: Box<T>) -> Handle<H> {
             let ptr = H::into_handle_ptr(val).cast();
-            Handle { ptr }
+            let handle = Handle { ptr };
+            handle.audit(audit::register);
+            handle
         }
     }
 
@@ -255,7 +290,9 @@ mod private {
     {
         fn from(val: Arc<T>) -> Handle<H> {
             let ptr = H::into_handle_ptr(val).cast();
-            Handle { ptr }
+            let handle = Handle { ptr };
+            handle.audit(audit::register);
+            handle
         }
     }
 
@@ -493,11 +530,138 @@ mod private {
 
 pub use private::{Boolean, False, Handle, True, Unconstructable};
 
+// Bookkeeping for the handle audit (see the module docs). Debug builds always track handles and
+// treat any inconsistency as fatal. Release builds only track handles once the embedder opts in,
+// and never fail, because handles created before tracking was enabled are unknown to the audit.
+mod audit {
+    use super::*;
+
+    static TRACKING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+    // The number of live handles for each (address, handle type). Shared handles to a sized type
+    // all point to the same `Arc` allocation, so one address can hold several live handles.
+    static LIVE_HANDLES: LazyLock<Mutex<HashMap<(usize, &'static str), usize>>> =
+        LazyLock::new(Default::default);
+
+    pub(super) fn enable() {
+        TRACKING_ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    fn with_live_handles<R>(f: impl FnOnce(&mut HashMap<(usize, &'static str), usize>) -> R) -> R {
+        // A panic while holding the lock cannot leave the map inconsistent, so ignore poisoning.
+        let mut live = LIVE_HANDLES.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut live)
+    }
+
+    fn is_tracking() -> bool {
+        cfg!(debug_assertions) || TRACKING_ENABLED.load(Ordering::Relaxed)
+    }
+
+    // Explains why the handle at `addr` is not a live handle of type `type_name`.
+    fn invalid_handle(
+        live: &HashMap<(usize, &'static str), usize>,
+        addr: usize,
+        type_name: &str,
+        action: &str,
+    ) -> Result<(), String> {
+        if !cfg!(debug_assertions) {
+            return Ok(());
+        }
+        match live.keys().find(|(live_addr, _)| *live_addr == addr) {
+            Some((_, actual)) => Err(format!(
+                "attempted to {action} a {actual} handle as a {type_name} handle"
+            )),
+            None => Err(format!(
+                "attempted to {action} a {type_name} handle that was already dropped or consumed \
+                (use after free or double free)"
+            )),
+        }
+    }
+
+    pub(super) fn register(addr: usize, type_name: &'static str) -> Result<(), String> {
+        if is_tracking() {
+            with_live_handles(|live| *live.entry((addr, type_name)).or_default() += 1);
+        }
+        Ok(())
+    }
+
+    pub(super) fn check(addr: usize, type_name: &'static str) -> Result<(), String> {
+        if !is_tracking() {
+            return Ok(());
+        }
+        with_live_handles(|live| match live.contains_key(&(addr, type_name)) {
+            true => Ok(()),
+            false => invalid_handle(live, addr, type_name, "use"),
+        })
+    }
+
+    pub(super) fn unregister(addr: usize, type_name: &'static str) -> Result<(), String> {
+        if !is_tracking() {
+            return Ok(());
+        }
+        with_live_handles(|live| match live.get_mut(&(addr, type_name)) {
+            Some(1) => {
+                live.remove(&(addr, type_name));
+                Ok(())
+            }
+            Some(count) => {
+                *count -= 1;
+                Ok(())
+            }
+            None => invalid_handle(live, addr, type_name, "drop"),
+        })
+    }
+
+    /// The number of live handles of each handle type, sorted by type name.
+    pub(super) fn live_handles() -> Vec<(&'static str, usize)> {
+        let mut counts: HashMap<&'static str, usize> = HashMap::new();
+        with_live_handles(|live| {
+            for ((_, type_name), count) in live.iter() {
+                *counts.entry(type_name).or_default() += count;
+            }
+        });
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_unstable();
+        counts
+    }
+}
+
+/// Starts tracking live handles in release builds, so that [`visit_live_handles`] can report
+/// leaked handles. Debug builds always track handles. Only handles created after this call are
+/// tracked.
+#[no_mangle]
+pub extern "C" fn enable_handle_tracking() {
+    audit::enable();
+}
+
+/// Visits each handle type that has live handles (created but not yet dropped or consumed),
+/// passing the handle type name and its number of live handles to `visitor`. Returns the total
+/// number of live handles. Embedders can call this when their test suite finishes to detect leaked
+/// handles. Reports nothing unless handles are tracked, see [`enable_handle_tracking`].
+///
+/// The type name slice is only valid for the duration of the `visitor` call.
+#[no_mangle]
+pub extern "C" fn visit_live_handles(
+    context: NullableCvoid,
+    visitor: extern "C" fn(context: NullableCvoid, type_name: KernelStringSlice, count: usize),
+) -> usize {
+    let live = audit::live_handles();
+    for (type_name, count) in &live {
+        // Safety: type names are static strings, which outlive the slice.
+        let type_name = unsafe { KernelStringSlice::new_unsafe(type_name) };
+        visitor(context, type_name, *count);
+    }
+    live.iter().map(|(_, count)| count).sum()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::ffi::c_void;
+    use std::ptr::NonNull;
     use std::sync::Arc;
 
     use super::*;
+    use crate::TryFromStringSlice;
     use delta_kernel_ffi_macros::handle_descriptor;
 
     #[allow(dead_code)]
@@ -556,6 +720,63 @@ mod tests {
         t.compile_fail("tests/invalid-handle-code/*.rs");
     }
 
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_audit_detects_invalid_handles() {
+        let type_name = "test::AuditedHandle";
+        let other_type_name = "test::OtherHandle";
+        let addr = &type_name as *const _ as usize;
+
+        audit::register(addr, type_name).unwrap();
+        audit::register(addr, type_name).unwrap();
+        audit::check(addr, type_name).unwrap();
+        let err = audit::check(addr, other_type_name).unwrap_err();
+        assert!(err.contains("as a test::OtherHandle handle"), "{err}");
+
+        audit::unregister(addr, type_name).unwrap();
+        audit::check(addr, type_name).unwrap();
+        audit::unregister(addr, type_name).unwrap();
+
+        let err = audit::check(addr, type_name).unwrap_err();
+        assert!(err.contains("use after free"), "{err}");
+        let err = audit::unregister(addr, type_name).unwrap_err();
+        assert!(err.contains("double free"), "{err}");
+    }
+
+    #[test]
+    fn test_live_handles() {
+        #[handle_descriptor(target=Bar, mutable=false, sized=true)]
+        pub struct LeakedBar;
+
+        extern "C" fn visit(context: NullableCvoid, type_name: KernelStringSlice, count: usize) {
+            let type_name: &str =
+                unsafe { TryFromStringSlice::try_from_slice(&type_name) }.unwrap();
+            if type_name.ends_with("LeakedBar") {
+                let found = context.unwrap().as_ptr().cast::<usize>();
+                unsafe { *found = count };
+            }
+        }
+        let live_bars = || {
+            let mut found = 0usize;
+            let context = NonNull::new(&mut found as *mut usize as *mut c_void);
+            visit_live_handles(context, visit);
+            found
+        };
+
+        enable_handle_tracking();
+        let bar = Arc::new(Bar {
+            x: 1,
+            y: "leaked".to_string(),
+        });
+        let h: Handle<LeakedBar> = bar.into();
+        let h2 = unsafe { h.clone_handle() };
+        assert_eq!(live_bars(), 2);
+        unsafe { h.drop_handle() };
+        assert_eq!(live_bars(), 1);
+        unsafe { h2.drop_handle() };
+        assert_eq!(live_bars(), 0);
+    }
+
     #[test]
     fn test_handle_use_cases_compile() {
         let s = NotSync {
@@ -11,6 +11,8 @@ use std::sync::Arc;
 use tracing::debug;
 use url::Url;
 
+#[cfg(feature = "default-engine-base")]
+use delta_kernel::engine::default::config::DefaultEngineConfig;
 use delta_kernel::schema::Schema;
 use delta_kernel::snapshot::Snapshot;
 use delta_kernel::Version;
@@ -32,6 +34,7 @@ use handle::Handle;
 // relies on `crate::`
 extern crate self as delta_kernel_ffi;
 
+pub mod abi;
 pub mod engine_data;
 pub mod engine_funcs;
 pub mod error;
@@ -413,6 +416,7 @@ pub struct EngineBuilder {
     url: Url,
     allocate_fn: AllocateErrorFn,
     options: HashMap<String, String>,
+    config: DefaultEngineConfig,
 }
 
 #[cfg(feature = "default-engine-base")]
@@ -447,6 +451,7 @@ fn get_engine_builder_impl(
         url: url?,
         allocate_fn,
         options: HashMap::default(),
+        config: DefaultEngineConfig::default(),
     });
     Ok(Box::into_raw(builder))
 }
@@ -469,6 +474,37 @@ pub unsafe extern "C" fn set_builder_option(
     builder.set_option(key.unwrap(), value.unwrap());
 }
 
+/// Set one of the default engine's buffer or batch sizes on the builder, e.g. `json_batch_size`.
+/// Unlike [`set_builder_option`], which passes options through to the object store, the key must
+/// be one of the names listed in [`DefaultEngineConfig::KEYS`] and the value must be a positive
+/// integer. Returns an error (and leaves the builder unchanged) otherwise.
+///
+/// # Safety
+///
+/// Caller must pass a valid EngineBuilder pointer, and valid slices for key and value
+#[cfg(feature = "default-engine-base")]
+#[no_mangle]
+pub unsafe extern "C" fn set_builder_config_option(
+    builder: &mut EngineBuilder,
+    key: KernelStringSlice,
+    value: KernelStringSlice,
+) -> ExternResult<bool> {
+    let key = unsafe { TryFromStringSlice::try_from_slice(&key) };
+    let value = unsafe { TryFromStringSlice::try_from_slice(&value) };
+    let allocate_fn = builder.allocate_fn;
+    set_builder_config_option_impl(builder, key, value).into_extern_result(&allocate_fn)
+}
+
+#[cfg(feature = "default-engine-base")]
+fn set_builder_config_option_impl(
+    builder: &mut EngineBuilder,
+    key: DeltaResult<&str>,
+    value: DeltaResult<&str>,
+) -> DeltaResult<bool> {
+    builder.config.set(key?, value?)?;
+    Ok(true)
+}
+
 /// Consume the builder and return a `default` engine. After calling, the passed pointer is _no
 /// longer valid_. Note that this _consumes_ and frees the builder, so there is no need to
 /// drop/free it afterwards.
@@ -486,6 +522,7 @@ pub unsafe extern "C" fn builder_build(
     get_default_engine_impl(
         builder_box.url,
         builder_box.options,
+        builder_box.config,
         builder_box.allocate_fn,
     )
     .into_extern_result(&builder_box.allocate_fn)
@@ -510,7 +547,7 @@ fn get_default_default_engine_impl(
     url: DeltaResult<Url>,
     allocate_error: AllocateErrorFn,
 ) -> DeltaResult<Handle<SharedExternEngine>> {
-    get_default_engine_impl(url?, Default::default(), allocate_error)
+    get_default_engine_impl(url?, Default::default(), Default::default(), allocate_error)
 }
 
 #[cfg(feature = "default-engine-base")]
@@ -529,6 +566,7 @@ fn engine_to_handle(
 fn get_default_engine_impl(
     url: Url,
     options: HashMap<String, String>,
+    config: DefaultEngineConfig,
     allocate_error: AllocateErrorFn,
 ) -> DeltaResult<Handle<SharedExternEngine>> {
     use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
@@ -537,8 +575,9 @@ fn get_default_engine_impl(
         &url,
         options,
         Arc::new(TokioBackgroundExecutor::new()),
-    );
-    Ok(engine_to_handle(Arc::new(engine?), allocate_error))
+    )?
+    .with_config(config)?;
+    Ok(engine_to_handle(Arc::new(engine), allocate_error))
 }
 
 /// # Safety
@@ -605,6 +644,32 @@ pub unsafe extern "C" fn free_snapshot(snapshot: Handle<SharedSnapshot>) {
     snapshot.drop_handle();
 }
 
+/// Write a checkpoint of the specified snapshot to the table's `_delta_log` as a single parquet
+/// file, and update the `_last_checkpoint` file to point to it.
+///
+/// # Safety
+///
+/// Caller is responsible for passing valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn checkpoint_table(
+    engine: Handle<SharedExternEngine>,
+    snapshot: Handle<SharedSnapshot>,
+) -> ExternResult<bool> {
+    let snapshot = unsafe { snapshot.clone_as_arc() };
+    let engine = unsafe { engine.as_ref() };
+    checkpoint_table_impl(snapshot, engine).into_extern_result(&engine)
+}
+
+fn checkpoint_table_impl(
+    snapshot: Arc<Snapshot>,
+    extern_engine: &dyn ExternEngine,
+) -> DeltaResult<bool> {
+    snapshot
+        .checkpoint()?
+        .write(extern_engine.engine().as_ref())?;
+    Ok(true)
+}
+
 /// Get the version of the specified snapshot
 ///
 /// # Safety
@@ -774,7 +839,12 @@ impl<T> Default for ReferenceSet<T> {
 mod tests {
     use delta_kernel::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
     use delta_kernel::object_store::memory::InMemory;
-    use test_utils::{actions_to_string, actions_to_string_partitioned, add_commit, TestAction};
+    use delta_kernel::object_store::path::Path;
+    use delta_kernel::object_store::ObjectStore;
+    use test_utils::{
+        actions_to_string, actions_to_string_partitioned, add_commit, delta_path_for_version,
+        TestAction,
+    };
 
     use super::*;
     use crate::error::{EngineError, KernelError};
@@ -843,6 +913,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn engine_builder_config_option() {
+        let path = "memory:///doesntmatter/foo";
+        let path = kernel_string_slice!(path);
+        let builder = unsafe { ok_or_panic(get_engine_builder(path, allocate_err)) };
+        let set = |key: &str, value: &str| unsafe {
+            set_builder_config_option(
+                &mut *builder,
+                kernel_string_slice!(key),
+                kernel_string_slice!(value),
+            )
+        };
+        assert!(ok_or_panic(set("json_batch_size", "10")));
+        assert!(ok_or_panic(set("list_buffer_size", "100")));
+        for (key, value) in [("json_batch_size", "0"), ("request_timeout", "10")] {
+            let ExternResult::Err(err) = set(key, value) else {
+                panic!("expected {key}={value} to be rejected");
+            };
+            let err = unsafe { recover_error(err) };
+            assert!(matches!(err.etype, KernelError::GenericError));
+        }
+        assert_eq!(
+            unsafe { &(*builder).config },
+            &DefaultEngineConfig::default()
+                .with_json_batch_size(10)
+                .with_list_buffer_size(100)
+        );
+        let engine = unsafe { ok_or_panic(builder_build(builder)) };
+        unsafe {
+            free_engine(engine);
+        }
+    }
+
     #[tokio::test]
     async fn test_snapshot() -> Result<(), Box<dyn std::error::Error>> {
         let storage = Arc::new(InMemory::new());
@@ -895,6 +998,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_checkpoint_table() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = Arc::new(InMemory::new());
+        add_commit(
+            storage.as_ref(),
+            0,
+            actions_to_string(vec![TestAction::Metadata]),
+        )
+        .await?;
+        add_commit(
+            storage.as_ref(),
+            1,
+            actions_to_string(vec![TestAction::Add("file1.parquet".into())]),
+        )
+        .await?;
+        let engine = DefaultEngine::new(storage.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let engine = engine_to_handle(Arc::new(engine), allocate_err);
+        let path = "memory:///";
+
+        let snapshot1 =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        let written = unsafe {
+            ok_or_panic(checkpoint_table(
+                engine.shallow_copy(),
+                snapshot1.shallow_copy(),
+            ))
+        };
+        assert!(written);
+        storage
+            .head(&delta_path_for_version(1, "checkpoint.parquet"))
+            .await?;
+        let last_checkpoint = storage
+            .get(&Path::from("_delta_log/_last_checkpoint"))
+            .await?
+            .bytes()
+            .await?;
+        let last_checkpoint = String::from_utf8(last_checkpoint.to_vec())?;
+        assert!(last_checkpoint.contains(r#""version":1,"#));
+
+        // The new snapshot is read from the checkpoint
+        let snapshot2 =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        assert_eq!(unsafe { version(snapshot2.shallow_copy()) }, 1);
+
+        unsafe { free_snapshot(snapshot1) }
+        unsafe { free_snapshot(snapshot2) }
+        unsafe { free_engine(engine) }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_snapshot_partition_cols() -> Result<(), Box<dyn std::error::Error>> {
         let storage = Arc::new(InMemory::new());
@@ -5,7 +5,7 @@ use std::ffi::c_void;
 use std::sync::{Arc, Mutex};
 
 use delta_kernel::scan::state::DvInfo;
-use delta_kernel::scan::{Scan, ScanMetadata};
+use delta_kernel::scan::{Scan, ScanMetadata, ScanMetricsHandle};
 use delta_kernel::snapshot::Snapshot;
 use delta_kernel::{DeltaResult, Error, Expression, ExpressionRef};
 use delta_kernel_ffi_macros::handle_descriptor;
@@ -153,6 +153,48 @@ pub unsafe extern "C" fn scan_physical_schema(scan: Handle<SharedScan>) -> Handl
     scan.physical_schema().clone().into()
 }
 
+/// Counts the files listed, pruned and selected while producing the metadata of a scan. See
+/// [`scan_metadata_metrics`].
+#[repr(C)]
+pub struct ScanMetrics {
+    /// The number of add actions read from the log.
+    pub files_listed: u64,
+    /// The number of files pruned because their partition values don't satisfy the predicate.
+    pub files_pruned_by_partition: u64,
+    /// The number of files pruned because their statistics show that no row satisfies the
+    /// predicate.
+    pub files_pruned_by_stats: u64,
+    /// The number of files selected for reading.
+    pub files_selected: u64,
+    /// The total size in bytes of the files selected for reading.
+    pub bytes_scanned: u64,
+}
+
+impl From<delta_kernel::scan::ScanMetrics> for ScanMetrics {
+    fn from(metrics: delta_kernel::scan::ScanMetrics) -> Self {
+        Self {
+            files_listed: metrics.files_listed,
+            files_pruned_by_partition: metrics.files_pruned_by_partition,
+            files_pruned_by_stats: metrics.files_pruned_by_stats,
+            files_selected: metrics.files_selected,
+            bytes_scanned: metrics.bytes_scanned,
+        }
+    }
+}
+
+/// Get the metrics of a scan metadata iterator (see [`scan_metadata_iter_init`]). The metrics are
+/// only complete once [`scan_metadata_next`] has returned `false`.
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedScanMetadataIterator` handle
+#[no_mangle]
+pub unsafe extern "C" fn scan_metadata_metrics(
+    data: Handle<SharedScanMetadataIterator>,
+) -> ScanMetrics {
+    let data = unsafe { data.as_ref() };
+    data.metrics.metrics().into()
+}
+
 // Intentionally opaque to the engine.
 //
 // TODO: This approach liberates the engine from having to worry about mutual exclusion, but that
@@ -165,6 +207,9 @@ pub struct ScanMetadataIterator {
     // Item = DeltaResult<ScanMetadata>
     data: Mutex<Box<dyn Iterator<Item = DeltaResult<ScanMetadata>> + Send>>,
 
+    // The metrics of the iteration, see `scan_metadata_metrics`
+    metrics: ScanMetricsHandle,
+
     // Also keep a reference to the external engine for its error allocator. The default Parquet and
     // Json handlers don't hold any reference to the tokio reactor they rely on, so the iterator
     // terminates early if the last engine goes out of scope.
@@ -201,9 +246,10 @@ fn scan_metadata_iter_init_impl(
     engine: &Arc<dyn ExternEngine>,
     scan: &Scan,
 ) -> DeltaResult<Handle<SharedScanMetadataIterator>> {
-    let scan_metadata = scan.scan_metadata(engine.engine().as_ref())?;
+    let (scan_metadata, metrics) = scan.scan_metadata_with_metrics(engine.engine().as_ref())?;
     let data = ScanMetadataIterator {
         data: Mutex::new(Box::new(scan_metadata)),
+        metrics,
         engine: engine.clone(),
     };
     Ok(Arc::new(data).into())
@@ -333,6 +379,11 @@ pub unsafe extern "C" fn get_from_string_map(
 /// `get_transform_for_row` returns `NULL` no expression need be applied and the data read from disk
 /// is already in the correct logical state.
 ///
+/// Files of the same partition share a single transform expression, so the memory used by the
+/// transforms grows with the number of partitions rather than the number of files. Only the
+/// transforms are shared: the partition values passed to a scan callback (see
+/// [`visit_scan_metadata`]) are decoded for each file, and only live for the duration of the call.
+///
 /// NB: If you are using `visit_scan_metadata` you don't need to worry about dealing with probing
 /// `CTransforms`. The callback will be invoked with the correct transform for you.
 pub struct CTransforms {
@@ -423,7 +474,6 @@ fn rust_callback(
     transform: Option<ExpressionRef>,
     partition_values: HashMap<String, String>,
 ) {
-    let transform = transform.map(|e| e.as_ref().clone());
     let partition_map = CStringMap {
         values: partition_values,
     };
@@ -436,7 +486,7 @@ fn rust_callback(
         size,
         stats.as_ref(),
         &dv_info,
-        transform.as_ref(),
+        transform.as_deref(),
         &partition_map,
     );
 }
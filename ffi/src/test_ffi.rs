@@ -203,6 +203,8 @@ pub unsafe extern "C" fn get_testing_kernel_predicate() -> Handle<SharedPredicat
             Pred::gt,
             Pred::ge,
             Pred::distinct,
+            Pred::starts_with,
+            Pred::like,
         ]
         .into_iter()
         .map(|op_fn| op_fn(Expr::literal(0), Expr::literal(0))),
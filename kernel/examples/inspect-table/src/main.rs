@@ -1,22 +1,29 @@
 use common::LocationArgs;
 use delta_kernel::actions::visitors::{
-    visit_metadata_at, visit_protocol_at, AddVisitor, CdcVisitor, RemoveVisitor,
-    SetTransactionVisitor,
+    visit_commit_info_at, visit_metadata_at, visit_protocol_at, AddVisitor, CdcVisitor,
+    RemoveVisitor, SetTransactionVisitor,
 };
 use delta_kernel::actions::{
-    get_log_schema, ADD_NAME, CDC_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
-    SET_TRANSACTION_NAME,
+    get_log_schema, ADD_NAME, CDC_NAME, COMMIT_INFO_NAME, METADATA_NAME, PROTOCOL_NAME,
+    REMOVE_NAME, SET_TRANSACTION_NAME,
 };
+use delta_kernel::arrow::compute::{cast, filter_record_batch};
+use delta_kernel::arrow::datatypes::SchemaRef as ArrowSchemaRef;
+use delta_kernel::arrow::record_batch::RecordBatch;
+use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine_data::{GetData, RowVisitor, TypedGetData as _};
 use delta_kernel::expressions::ColumnName;
+use delta_kernel::parquet::arrow::ArrowWriter;
 use delta_kernel::scan::state::{DvInfo, Stats};
 use delta_kernel::scan::ScanBuilder;
 use delta_kernel::schema::{ColumnNamesAndTypes, DataType};
-use delta_kernel::{DeltaResult, Error, ExpressionRef, Snapshot};
+use delta_kernel::{DeltaResult, Engine, Error, ExpressionRef, Snapshot};
 
 use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 use clap::{Parser, Subcommand};
 
@@ -47,6 +54,19 @@ enum Commands {
         #[arg(short, long)]
         oldest_first: bool,
     },
+    /// Show the commits of the table with their commit info, newest first
+    History {
+        /// Show at most this many commits
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// Write the reconciled state of the table (the actions a checkpoint of the latest version
+    /// would contain, including the live add files with their deletion vectors) to a parquet file
+    StateDump {
+        /// The parquet file to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
 }
 
 fn main() -> ExitCode {
@@ -67,6 +87,7 @@ enum Action {
     Add(delta_kernel::actions::Add),
     SetTransaction(delta_kernel::actions::SetTransaction),
     Cdc(delta_kernel::actions::Cdc),
+    CommitInfo(delta_kernel::actions::CommitInfo),
 }
 
 static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
@@ -104,7 +125,7 @@ impl RowVisitor for LogVisitor {
         NAMES_AND_TYPES.as_ref()
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
-        if getters.len() != 55 {
+        if getters.len() != 70 {
             return Err(Error::InternalError(format!(
                 "Wrong number of LogVisitor getters: {}",
                 getters.len()
@@ -116,6 +137,7 @@ impl RowVisitor for LogVisitor {
         let (protocol_start, protocol_end) = self.offsets[PROTOCOL_NAME];
         let (txn_start, txn_end) = self.offsets[SET_TRANSACTION_NAME];
         let (cdc_start, cdc_end) = self.offsets[CDC_NAME];
+        let (commit_info_start, commit_info_end) = self.offsets[COMMIT_INFO_NAME];
         for i in 0..row_count {
             let action = if let Some(path) = getters[add_start].get_opt(i, "add.path")? {
                 let add = AddVisitor::visit_add(i, path, &getters[add_start..add_end])?;
@@ -139,8 +161,11 @@ impl RowVisitor for LogVisitor {
             } else if let Some(path) = getters[cdc_start].get_opt(i, "cdc.path")? {
                 let cdc = CdcVisitor::visit_cdc(i, path, &getters[cdc_start..cdc_end])?;
                 Action::Cdc(cdc)
+            } else if let Some(commit_info) =
+                visit_commit_info_at(i, &getters[commit_info_start..commit_info_end])?
+            {
+                Action::CommitInfo(commit_info)
             } else {
-                // TODO: Add CommitInfo support (tricky because all fields are optional)
                 continue;
             };
             self.actions.push((action, self.previous_rows_seen + i));
@@ -177,6 +202,42 @@ fn print_scan_file(
     );
 }
 
+// Write the actions a checkpoint of `snapshot` would contain to a parquet file at `output`, and
+// return the number of actions written
+fn write_state_dump(snapshot: Snapshot, engine: &dyn Engine, output: &Path) -> DeltaResult<usize> {
+    let writer = Arc::new(snapshot).checkpoint()?;
+    let file = File::create(output)?;
+    let mut parquet_writer: Option<(ArrowWriter<File>, ArrowSchemaRef)> = None;
+    let mut num_actions = 0;
+    for data in writer.checkpoint_data(engine)? {
+        let data = data?;
+        let batch = ArrowEngineData::try_from_engine_data(data.data)?;
+        let batch = filter_record_batch(batch.record_batch(), &data.selection_vector.into())?;
+        if parquet_writer.is_none() {
+            let schema = batch.schema();
+            let writer = ArrowWriter::try_new(file.try_clone()?, schema.clone(), None)?;
+            parquet_writer = Some((writer, schema));
+        }
+        let Some((parquet_writer, schema)) = parquet_writer.as_mut() else {
+            unreachable!("the parquet writer was created above");
+        };
+        // Batches read from commits and from checkpoints can differ in nullability, so align them
+        // with the schema of the file
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(schema.fields())
+            .map(|(column, field)| cast(column, field.data_type()))
+            .collect::<Result<_, _>>()?;
+        parquet_writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+        num_actions += batch.num_rows();
+    }
+    let (parquet_writer, _) =
+        parquet_writer.ok_or_else(|| Error::generic("The table has no actions to write"))?;
+    parquet_writer.close()?;
+    Ok(num_actions)
+}
+
 fn try_main() -> DeltaResult<()> {
     let cli = Cli::parse();
 
@@ -227,9 +288,28 @@ fn try_main() -> DeltaResult<()> {
                     Action::Add(a) => println!("\nAction {row}:\n{a:#?}"),
                     Action::SetTransaction(t) => println!("\nAction {row}:\n{t:#?}"),
                     Action::Cdc(c) => println!("\nAction {row}:\n{c:#?}"),
+                    Action::CommitInfo(ci) => println!("\nAction {row}:\n{ci:#?}"),
+                }
+            }
+        }
+        Commands::History { limit } => {
+            for entry in snapshot.history(&engine, limit)? {
+                let entry = entry?;
+                println!("\nVersion {} at {}:", entry.version(), entry.timestamp());
+                match entry.commit_info() {
+                    Some(commit_info) => println!("{commit_info:#?}"),
+                    None => println!("[no commit info]"),
                 }
             }
         }
+        Commands::StateDump { output } => {
+            let version = snapshot.version();
+            let num_actions = write_state_dump(snapshot, &engine, &output)?;
+            println!(
+                "Wrote {num_actions} actions of version {version} to {}",
+                output.display()
+            );
+        }
     };
     Ok(())
 }
@@ -19,7 +19,9 @@ use url::Url;
 
 /// An example program that reads a table using multiple threads. This shows the use of the
 /// scan_metadata method on a Scan, that can be used to partition work to either
-/// multiple threads, or workers (in the case of a distributed engine).
+/// multiple threads, or workers (in the case of a distributed engine). Engines that read files
+/// with kernel's parquet handler can instead split a scan with `Scan::partitions` and read each
+/// partition with `scan::execute_partition`.
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
@@ -1,15 +1,25 @@
 //! CRC (version checksum) file
-use std::sync::LazyLock;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
 
 use super::visitors::{visit_metadata_at, visit_protocol_at};
 use super::{Add, DomainMetadata, Metadata, Protocol, SetTransaction};
 use crate::actions::PROTOCOL_NAME;
 use crate::engine_data::GetData;
+use crate::expressions::Scalar;
 use crate::schema::ToSchema as _;
-use crate::schema::{ColumnName, ColumnNamesAndTypes, DataType};
+use crate::schema::{
+    ColumnName, ColumnNamesAndTypes, DataType, SchemaRef, StructField, StructType,
+};
 use crate::utils::require;
-use crate::{DeltaResult, Error, RowVisitor};
+use crate::{
+    DeltaResult, Engine, Error, EvaluationHandlerExtension as _, FileMeta, RowVisitor,
+    StorageHandler,
+};
 use delta_kernel_derive::ToSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use url::Url;
 
 /// Though technically not an action, we include the CRC (version checksum) file here. A [CRC file]
 /// must:
@@ -138,6 +148,149 @@ impl RowVisitor for CrcProtocolMetadataVisitor {
     }
 }
 
+/// The CRC file a transaction writes for the version it committed. Kernel only writes the required
+/// fields (and the in-commit timestamp), leaving out the optional ones it doesn't track, such as
+/// the file size histogram.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrcWriter<'a> {
+    pub(crate) txn_id: String,
+    pub(crate) table_size_bytes: i64,
+    pub(crate) num_files: i64,
+    pub(crate) num_metadata: i64,
+    pub(crate) num_protocol: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) in_commit_timestamp_opt: Option<i64>,
+    pub(crate) metadata: &'a Metadata,
+    pub(crate) protocol: &'a Protocol,
+}
+
+impl CrcWriter<'_> {
+    /// Write the CRC file to `crc_path`, failing if it already exists. The JSON object is parsed
+    /// into the [`Crc`] schema and written through the engine's JSON handler, like log files.
+    pub(crate) fn write(&self, engine: &dyn Engine, crc_path: &Url) -> DeltaResult<()> {
+        static JSON_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+            Arc::new(StructType::new([StructField::nullable(
+                "json",
+                DataType::STRING,
+            )]))
+        });
+        let json = serde_json::to_string(self)?;
+        let json = engine
+            .evaluation_handler()
+            .create_one(JSON_SCHEMA.clone(), &[Scalar::String(json)])?;
+        let crc = engine
+            .json_handler()
+            .parse_json(json, Arc::new(Crc::to_schema()))?;
+        engine
+            .json_handler()
+            .write_json_file(crc_path, Box::new(std::iter::once(Ok(crc))), false)
+    }
+}
+
+/// The parts of a CRC file a snapshot uses to answer queries without log replay: the file count
+/// and table size, and the live domain metadata if the CRC file records it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrcSummary {
+    pub(crate) table_size_bytes: i64,
+    pub(crate) num_files: i64,
+    pub(crate) domain_metadata: Option<Vec<DomainMetadata>>,
+}
+
+impl CrcSummary {
+    /// Read the summary from the CRC file at `crc_file`.
+    pub(crate) fn try_read(storage: &dyn StorageHandler, crc_file: &FileMeta) -> DeltaResult<Self> {
+        read_crc_file(storage, crc_file)
+    }
+
+    /// The configuration of every domain that is not removed according to this CRC file. Returns
+    /// `None` if the CRC file does not record domain metadata.
+    pub(crate) fn domain_configurations(&self) -> Option<HashMap<String, String>> {
+        self.domain_metadata
+            .as_deref()
+            .map(live_domain_configurations)
+    }
+
+    /// The configuration of `domain` according to this CRC file. Returns `None` if the CRC file
+    /// does not record domain metadata (so the domain must be looked up by log replay), and
+    /// `Some(None)` if the domain does not exist.
+    pub(crate) fn domain_configuration(&self, domain: &str) -> Option<Option<String>> {
+        let domain_metadata = self.domain_metadata.as_ref()?;
+        Some(
+            domain_metadata
+                .iter()
+                .find(|dm| dm.domain == domain && !dm.removed)
+                .map(|dm| dm.configuration.clone()),
+        )
+    }
+}
+
+/// The fields of a CRC file that describe the table state, which [`Snapshot::verify_checksum`]
+/// checks against log replay.
+///
+/// [`Snapshot::verify_checksum`]: crate::Snapshot::verify_checksum
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrcTableState {
+    pub(crate) table_size_bytes: i64,
+    pub(crate) num_files: i64,
+    pub(crate) num_metadata: i64,
+    pub(crate) num_protocol: i64,
+    pub(crate) metadata: Metadata,
+    pub(crate) protocol: Protocol,
+    pub(crate) set_transactions: Option<Vec<SetTransaction>>,
+    pub(crate) domain_metadata: Option<Vec<DomainMetadata>>,
+}
+
+impl CrcTableState {
+    /// Read the table state from the CRC file at `crc_file`.
+    pub(crate) fn try_read(storage: &dyn StorageHandler, crc_file: &FileMeta) -> DeltaResult<Self> {
+        read_crc_file(storage, crc_file)
+    }
+
+    /// The configuration of every domain that is not removed according to this CRC file. Returns
+    /// `None` if the CRC file does not record domain metadata.
+    pub(crate) fn domain_configurations(&self) -> Option<HashMap<String, String>> {
+        self.domain_metadata
+            .as_deref()
+            .map(live_domain_configurations)
+    }
+
+    /// The version of every application transaction according to this CRC file. Returns `None` if
+    /// the CRC file does not record application transactions.
+    pub(crate) fn transaction_versions(&self) -> Option<HashMap<String, i64>> {
+        let set_transactions = self.set_transactions.as_ref()?;
+        Some(
+            set_transactions
+                .iter()
+                .map(|txn| (txn.app_id.clone(), txn.version))
+                .collect(),
+        )
+    }
+}
+
+fn live_domain_configurations(domain_metadata: &[DomainMetadata]) -> HashMap<String, String> {
+    domain_metadata
+        .iter()
+        .filter(|dm| !dm.removed)
+        .map(|dm| (dm.domain.clone(), dm.configuration.clone()))
+        .collect()
+}
+
+/// Unlike actions, a CRC file holds a single JSON object with nested arrays of actions, so it is
+/// parsed directly rather than through the engine's JSON handler.
+fn read_crc_file<T: DeserializeOwned>(
+    storage: &dyn StorageHandler,
+    crc_file: &FileMeta,
+) -> DeltaResult<T> {
+    let data = storage
+        .read_files(vec![(crc_file.location.clone(), None)])?
+        .next()
+        .ok_or_else(|| Error::generic(format!("Empty CRC file {}", crc_file.location)))??;
+    Ok(serde_json::from_slice(&data)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,4 +427,32 @@ mod tests {
         assert_eq!(visitor.protocol, expected_protocol);
         assert_eq!(visitor.metadata, expected_metadata);
     }
+
+    #[test]
+    fn test_crc_summary_domain_configuration() {
+        let crc: CrcSummary = serde_json::from_str(
+            r#"{
+                "tableSizeBytes": 100,
+                "numFiles": 2,
+                "numMetadata": 1,
+                "domainMetadata": [
+                    {"domain": "a", "configuration": "conf_a", "removed": false},
+                    {"domain": "b", "configuration": "conf_b", "removed": true}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!((crc.num_files, crc.table_size_bytes), (2, 100));
+        assert_eq!(
+            crc.domain_configuration("a"),
+            Some(Some("conf_a".to_string()))
+        );
+        assert_eq!(crc.domain_configuration("b"), Some(None));
+        assert_eq!(crc.domain_configuration("c"), Some(None));
+
+        // without recorded domain metadata, domains must be found elsewhere
+        let crc: CrcSummary =
+            serde_json::from_str(r#"{"tableSizeBytes": 100, "numFiles": 2}"#).unwrap();
+        assert_eq!(crc.domain_configuration("a"), None);
+    }
 }
@@ -5,15 +5,21 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use roaring::RoaringTreemap;
+use serde::{Deserialize, Serialize};
 use url::Url;
+use uuid::Uuid;
 
 use delta_kernel_derive::ToSchema;
 
 use crate::utils::require;
 use crate::{DeltaResult, Error, StorageHandler};
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(test, derive(serde::Serialize), serde(rename_all = "camelCase"))]
+/// The magic number which starts a deletion vector serialized in the portable `RoaringBitmapArray`
+/// format
+const PORTABLE_ROARING_BITMAP_MAGIC: u32 = 1681511377;
+
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DeletionVectorDescriptor {
     /// A single character to indicate how to access the DV. Legal options are: ['u', 'i', 'p'].
     pub storage_type: String,
@@ -108,72 +114,79 @@ impl DeletionVectorDescriptor {
         parent: &Url,
     ) -> DeltaResult<RoaringTreemap> {
         match self.absolute_path(parent)? {
-            None => {
-                let byte_slice = z85::decode(&self.path_or_inline_dv)
-                    .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
-                let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
-                match magic {
-                    1681511377 => RoaringTreemap::deserialize_from(&byte_slice[4..])
-                        .map_err(|err| Error::DeletionVector(err.to_string())),
-                    1681511376 => {
-                        todo!("Don't support native serialization in inline bitmaps yet");
-                    }
-                    _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
-                }
-            }
+            None => self.read_inline(),
             Some(path) => {
-                let offset = self.offset;
-                let size_in_bytes = self.size_in_bytes;
-
                 let dv_data = storage
                     .read_files(vec![(path, None)])?
                     .next()
                     .ok_or(Error::missing_data("No deletion vector data"))??;
+                self.read_file_data(dv_data)
+            }
+        }
+    }
 
-                let mut cursor = Cursor::new(dv_data);
-                let mut version_buf = [0; 1];
-                cursor
-                    .read(&mut version_buf)
-                    .map_err(|err| Error::DeletionVector(err.to_string()))?;
-                let version = u8::from_be_bytes(version_buf);
-                require!(
-                    version == 1,
-                    Error::DeletionVector(format!("Invalid version: {version}"))
-                );
-
-                if let Some(offset) = offset {
-                    cursor.set_position(offset as u64);
-                }
-                let dv_size = read_u32(&mut cursor, Endian::Big)?;
-                require!(
-                    dv_size == size_in_bytes as u32,
-                    Error::DeletionVector(format!(
-                        "DV size mismatch. Log indicates {size_in_bytes}, file says: {dv_size}"
-                    ))
-                );
-                let magic = read_u32(&mut cursor, Endian::Little)?;
-                require!(
-                    magic == 1681511377,
-                    Error::DeletionVector(format!("Invalid magic: {magic}"))
-                );
-
-                // get the Bytes back out and limit it to dv_size
-                let position = cursor.position();
-                let mut bytes = cursor.into_inner();
-                let truncate_pos = position + dv_size as u64;
-                assert!(
-                    truncate_pos <= usize::MAX as u64,
-                    "Can't truncate as truncate_pos is > usize::MAX"
-                );
-                bytes.truncate(truncate_pos as usize);
-                let mut cursor = Cursor::new(bytes);
-                cursor.set_position(position);
-                RoaringTreemap::deserialize_from(cursor)
-                    .map_err(|err| Error::DeletionVector(err.to_string()))
+    /// Decode an inline dv into a [`RoaringTreemap`]
+    pub(crate) fn read_inline(&self) -> DeltaResult<RoaringTreemap> {
+        let byte_slice = z85::decode(&self.path_or_inline_dv)
+            .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
+        let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
+        match magic {
+            PORTABLE_ROARING_BITMAP_MAGIC => RoaringTreemap::deserialize_from(&byte_slice[4..])
+                .map_err(|err| Error::DeletionVector(err.to_string())),
+            1681511376 => {
+                todo!("Don't support native serialization in inline bitmaps yet");
             }
+            _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
         }
     }
 
+    /// Parse this dv out of `dv_data`, the full contents of the file at [`Self::absolute_path`]
+    pub(crate) fn read_file_data(&self, dv_data: Bytes) -> DeltaResult<RoaringTreemap> {
+        let offset = self.offset;
+        let size_in_bytes = self.size_in_bytes;
+
+        let mut cursor = Cursor::new(dv_data);
+        let mut version_buf = [0; 1];
+        cursor
+            .read(&mut version_buf)
+            .map_err(|err| Error::DeletionVector(err.to_string()))?;
+        let version = u8::from_be_bytes(version_buf);
+        require!(
+            version == 1,
+            Error::DeletionVector(format!("Invalid version: {version}"))
+        );
+
+        if let Some(offset) = offset {
+            cursor.set_position(offset as u64);
+        }
+        let dv_size = read_u32(&mut cursor, Endian::Big)?;
+        require!(
+            dv_size == size_in_bytes as u32,
+            Error::DeletionVector(format!(
+                "DV size mismatch. Log indicates {size_in_bytes}, file says: {dv_size}"
+            ))
+        );
+        let magic = read_u32(&mut cursor, Endian::Little)?;
+        require!(
+            magic == PORTABLE_ROARING_BITMAP_MAGIC,
+            Error::DeletionVector(format!("Invalid magic: {magic}"))
+        );
+
+        // get the Bytes back out and limit it to dv_size
+        let position = cursor.position();
+        let mut bytes = cursor.into_inner();
+        let truncate_pos = position + dv_size as u64;
+        assert!(
+            truncate_pos <= usize::MAX as u64,
+            "Can't truncate as truncate_pos is > usize::MAX"
+        );
+        bytes.truncate(truncate_pos as usize);
+        let mut cursor = Cursor::new(bytes);
+        cursor.set_position(position);
+        RoaringTreemap::deserialize_from(cursor)
+            .map_err(|err| Error::DeletionVector(err.to_string()))
+    }
+
     /// Materialize the row indexes of the deletion vector as a `Vec<u64>` in which each element
     /// represents a row index that is deleted from the table.
     pub fn row_indexes(
@@ -185,6 +198,72 @@ impl DeletionVectorDescriptor {
     }
 }
 
+/// A deletion vector file to write, as returned by [`Transaction::delete_rows`].
+///
+/// [`Transaction::delete_rows`]: crate::transaction::Transaction::delete_rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionVectorFile {
+    /// The location to write the file to, in the table root.
+    pub path: Url,
+    /// The contents of the file.
+    pub data: Vec<u8>,
+}
+
+/// Serializes deletion vectors into a single file in the table root, in the [Deletion Vector
+/// Format]: a version byte followed, for each deletion vector, by its size (big endian), its
+/// data, and the CRC-32 checksum of its data (big endian).
+///
+/// [Deletion Vector Format]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#Deletion-Vector-Format
+#[derive(Debug)]
+pub(crate) struct DeletionVectorWriter {
+    /// The z85-encoded id of the file, from which readers derive its path
+    encoded_id: String,
+    file: DeletionVectorFile,
+}
+
+impl DeletionVectorWriter {
+    /// Create a writer for the file `deletion_vector_<id>.bin` in `table_root`
+    pub(crate) fn try_new(table_root: &Url, id: Uuid) -> DeltaResult<Self> {
+        let path = table_root.join(&format!("deletion_vector_{id}.bin"))?;
+        Ok(Self {
+            encoded_id: z85::encode(id.as_bytes()),
+            file: DeletionVectorFile {
+                path,
+                data: vec![1], // format version
+            },
+        })
+    }
+
+    /// Append a deletion vector of `deleted_rows` to the file, returning its descriptor
+    pub(crate) fn write(
+        &mut self,
+        deleted_rows: &RoaringTreemap,
+    ) -> DeltaResult<DeletionVectorDescriptor> {
+        let offset = self.file.data.len();
+        let mut dv = PORTABLE_ROARING_BITMAP_MAGIC.to_le_bytes().to_vec();
+        deleted_rows
+            .serialize_into(&mut dv)
+            .map_err(|err| Error::DeletionVector(err.to_string()))?;
+        let too_large = |_| Error::deletion_vector("Deletion vector file too large");
+        let size_in_bytes = u32::try_from(dv.len()).map_err(too_large)?;
+        self.file.data.extend(size_in_bytes.to_be_bytes());
+        self.file.data.extend(&dv);
+        self.file.data.extend(crc32fast::hash(&dv).to_be_bytes());
+        Ok(DeletionVectorDescriptor {
+            storage_type: "u".to_string(),
+            path_or_inline_dv: self.encoded_id.clone(),
+            offset: Some(i32::try_from(offset).map_err(too_large)?),
+            size_in_bytes: i32::try_from(size_in_bytes).map_err(too_large)?,
+            cardinality: deleted_rows.len() as i64,
+        })
+    }
+
+    /// The deletion vector file, once all deletion vectors are written
+    pub(crate) fn finish(self) -> DeletionVectorFile {
+        self.file
+    }
+}
+
 enum Endian {
     Big,
     Little,
@@ -451,4 +530,33 @@ mod tests {
         assert_eq!(row_idx.len(), 6);
         assert_eq!(&row_idx, &[3, 4, 7, 11, 18, 29]);
     }
+
+    #[test]
+    fn test_deletion_vector_writer() {
+        let table_root = Url::parse("memory:///table/").unwrap();
+        let id = Uuid::from_u128(42);
+        let mut writer = DeletionVectorWriter::try_new(&table_root, id).unwrap();
+        let first = RoaringTreemap::from_iter([0, 3, 5]);
+        let second = RoaringTreemap::from_iter([1, 1 << 33]);
+        let first_dv = writer.write(&first).unwrap();
+        let second_dv = writer.write(&second).unwrap();
+        let file = writer.finish();
+
+        // both deletion vectors are in the file their descriptors point to
+        assert_eq!(
+            file.path,
+            table_root
+                .join("deletion_vector_00000000-0000-0000-0000-00000000002a.bin")
+                .unwrap()
+        );
+        assert_eq!(
+            first_dv.absolute_path(&table_root).unwrap(),
+            Some(file.path.clone())
+        );
+        assert_eq!(first_dv.offset, Some(1));
+        assert_eq!((first_dv.cardinality, second_dv.cardinality), (3, 2));
+        let data = Bytes::from(file.data);
+        assert_eq!(first_dv.read_file_data(data.clone()).unwrap(), first);
+        assert_eq!(second_dv.read_file_data(data).unwrap(), second);
+    }
 }
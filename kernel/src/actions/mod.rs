@@ -10,7 +10,8 @@ use std::sync::{Arc, LazyLock};
 use self::deletion_vector::DeletionVectorDescriptor;
 use crate::schema::{SchemaRef, StructField, StructType, ToSchema as _};
 use crate::table_features::{
-    ReaderFeature, WriterFeature, SUPPORTED_READER_FEATURES, SUPPORTED_WRITER_FEATURES,
+    ReaderFeature, WriterFeature, WriterFeatureSet, SUPPORTED_READER_FEATURES,
+    SUPPORTED_WRITER_FEATURES,
 };
 use crate::table_properties::TableProperties;
 use crate::utils::require;
@@ -22,6 +23,7 @@ use visitors::{MetadataVisitor, ProtocolVisitor};
 use delta_kernel_derive::{internal_api, IntoEngineData, ToSchema};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 pub mod deletion_vector;
 pub mod set_transaction;
@@ -123,12 +125,8 @@ pub(crate) fn get_log_domain_metadata_schema() -> &'static SchemaRef {
     &LOG_DOMAIN_METADATA_SCHEMA
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(
-    any(test, feature = "internal-api"),
-    derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
-)]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Format {
     /// Name of the encoding for files in this table
@@ -146,12 +144,8 @@ impl Default for Format {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(
-    any(test, feature = "internal-api"),
-    derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
-)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Metadata {
     /// Unique identifier for this table
@@ -210,6 +204,8 @@ impl Metadata {
         &self.configuration
     }
 
+    /// Parse the table schema. Types kernel doesn't know are parsed as
+    /// [`DataType::Unsupported`](crate::schema::DataType::Unsupported).
     #[internal_api]
     pub(crate) fn parse_schema(&self) -> DeltaResult<StructType> {
         Ok(serde_json::from_str(&self.schema_string)?)
@@ -348,6 +344,16 @@ impl Protocol {
             .is_some_and(|features| features.contains(feature))
     }
 
+    /// The known writer features this protocol supports: either explicitly, for writer version 7,
+    /// or implicitly by a legacy writer version which includes the feature.
+    pub(crate) fn supported_writer_features(&self) -> WriterFeatureSet {
+        match self.min_writer_version() {
+            7 => self.writer_features().unwrap_or_default().iter().collect(),
+            version if version < 7 => WriterFeatureSet::legacy(version),
+            _ => WriterFeatureSet::default(),
+        }
+    }
+
     /// Check if reading a table with this protocol is supported. That is: does the kernel support
     /// the specified protocol reader version and all enabled reader features? If yes, returns unit
     /// type, otherwise will return an error.
@@ -444,10 +450,13 @@ where
     )))
 }
 
+/// The `commitInfo` action of a commit, which records how and by whom the commit was written. All
+/// fields are optional, since writers are free to omit them. See [`Snapshot::history`].
+///
+/// [`Snapshot::history`]: crate::Snapshot::history
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[internal_api]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
-pub(crate) struct CommitInfo {
+pub struct CommitInfo {
     /// The time this logical file was created, as milliseconds since the epoch.
     /// Read: optional, write: required (that is, kernel always writes).
     pub(crate) timestamp: Option<i64>,
@@ -463,6 +472,10 @@ pub(crate) struct CommitInfo {
     /// Map of arbitrary string key-value pairs that provide additional information about the
     /// operation. This is specified by the engine. For now this is always empty on write.
     pub(crate) operation_parameters: Option<HashMap<String, String>>,
+    /// Map of metrics about the operation, e.g. `numFiles`, `numOutputRows` and `numOutputBytes`
+    /// for writes. Metric values are encoded as strings; see [`CommitInfo::operation_metrics`] for
+    /// the parsed metrics. Kernel does not write this field.
+    pub(crate) operation_metrics: Option<HashMap<String, String>>,
     /// The version of the delta_kernel crate used to write this commit. The kernel will always
     /// write this field, but it is optional since many tables will not have this field (i.e. any
     /// tables not written by kernel).
@@ -470,6 +483,123 @@ pub(crate) struct CommitInfo {
     /// A place for the engine to store additional metadata associated with this commit encoded as
     /// a map of strings.
     pub(crate) engine_commit_info: Option<HashMap<String, String>>,
+    /// Arbitrary metadata the user attached to this commit, as set by Spark's
+    /// `spark.databricks.delta.commitInfo.userMetadata`.
+    pub(crate) user_metadata: Option<String>,
+    /// The engine that wrote this commit, e.g. `Apache-Spark/3.5.0 Delta-Lake/3.2.0`.
+    pub(crate) engine_info: Option<String>,
+    /// A unique identifier of the transaction that wrote this commit. Kernel writes a UUID, which
+    /// it uses to recognize its own commit when the outcome of a commit write is unknown.
+    pub(crate) txn_id: Option<String>,
+}
+
+impl CommitInfo {
+    /// The time this commit was written, as milliseconds since the epoch.
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    /// The in-commit timestamp of this commit, as milliseconds since the epoch. Present iff
+    /// in-commit timestamps were enabled when the commit was written.
+    pub fn in_commit_timestamp(&self) -> Option<i64> {
+        self.in_commit_timestamp
+    }
+
+    /// The operation that produced this commit, e.g. `WRITE` or `MERGE`.
+    pub fn operation(&self) -> Option<&str> {
+        self.operation.as_deref()
+    }
+
+    /// The parameters of the operation that produced this commit.
+    pub fn operation_parameters(&self) -> Option<&HashMap<String, String>> {
+        self.operation_parameters.as_ref()
+    }
+
+    /// The metrics of the operation that produced this commit, or `None` if the commit does not
+    /// record any.
+    pub fn operation_metrics(&self) -> Option<OperationMetrics> {
+        self.operation_metrics
+            .as_ref()
+            .map(OperationMetrics::from_string_map)
+    }
+
+    /// The version of delta_kernel that wrote this commit, if it was written by kernel.
+    pub fn kernel_version(&self) -> Option<&str> {
+        self.kernel_version.as_deref()
+    }
+
+    /// The additional commit metadata provided by the engine that wrote this commit.
+    pub fn engine_commit_info(&self) -> Option<&HashMap<String, String>> {
+        self.engine_commit_info.as_ref()
+    }
+
+    /// The metadata the user attached to this commit.
+    pub fn user_metadata(&self) -> Option<&str> {
+        self.user_metadata.as_deref()
+    }
+
+    /// The engine that wrote this commit, e.g. `Apache-Spark/3.5.0 Delta-Lake/3.2.0`.
+    pub fn engine_info(&self) -> Option<&str> {
+        self.engine_info.as_deref()
+    }
+
+    /// The unique identifier of the transaction that wrote this commit.
+    pub fn txn_id(&self) -> Option<&str> {
+        self.txn_id.as_deref()
+    }
+}
+
+/// The metrics of the operation that produced a commit, parsed from the `operationMetrics` map of
+/// its [`CommitInfo`]. These are the metrics shown by Spark's `DESCRIBE HISTORY`, e.g. the number
+/// of files, rows and bytes written. Which metrics are present depends on the operation and on the
+/// writer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationMetrics {
+    metrics: HashMap<String, i64>,
+}
+
+impl OperationMetrics {
+    // Writers encode all metrics as strings. Metrics that aren't integers are skipped.
+    fn from_string_map(metrics: &HashMap<String, String>) -> Self {
+        let metrics = metrics
+            .iter()
+            .filter_map(|(name, value)| match value.parse() {
+                Ok(value) => Some((name.clone(), value)),
+                Err(_) => {
+                    warn!("Ignoring non-integer operation metric {name}: {value}");
+                    None
+                }
+            })
+            .collect();
+        Self { metrics }
+    }
+
+    /// The value of the metric called `name`, if present.
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.metrics.get(name).copied()
+    }
+
+    /// Iterate over the names and values of all metrics, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.metrics
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// The number of files written by the operation.
+    pub fn num_files(&self) -> Option<i64> {
+        self.get("numFiles")
+    }
+
+    /// The number of rows written by the operation.
+    pub fn num_output_rows(&self) -> Option<i64> {
+        self.get("numOutputRows")
+    }
+
+    /// The number of bytes written by the operation.
+    pub fn num_output_bytes(&self) -> Option<i64> {
+        self.get("numOutputBytes")
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
@@ -588,6 +718,13 @@ pub(crate) struct Remove {
     /// First commit version in which an add action with the same path was committed to the table.
     #[cfg_attr(test, serde(skip_serializing_if = "Option::is_none"))]
     pub(crate) default_row_commit_version: Option<i64>,
+
+    /// Contains [statistics] (e.g., count, min/max values for columns) about the data in this
+    /// logical file encoded as a JSON string, copied from the file's add action.
+    ///
+    /// [statistics]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#Per-file-Statistics
+    #[cfg_attr(test, serde(skip_serializing_if = "Option::is_none"))]
+    pub(crate) stats: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
@@ -625,7 +762,8 @@ pub(crate) struct Cdc {
     pub tags: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema, IntoEngineData)]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, IntoEngineData, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct SetTransaction {
     /// A unique identifier for the application performing the transaction.
@@ -718,7 +856,7 @@ pub(crate) struct CheckpointMetadata {
 /// Note that the `delta.*` domain is reserved for internal use.
 ///
 /// [DomainMetadata]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#domain-metadata
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, ToSchema, IntoEngineData)]
 #[internal_api]
 pub(crate) struct DomainMetadata {
     domain: String,
@@ -727,10 +865,25 @@ pub(crate) struct DomainMetadata {
 }
 
 impl DomainMetadata {
+    pub(crate) fn new(domain: String, configuration: String, removed: bool) -> Self {
+        Self {
+            domain,
+            configuration,
+            removed,
+        }
+    }
+
+    pub(crate) fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub(crate) fn is_removed(&self) -> bool {
+        self.removed
+    }
+
     // returns true if the domain metadata is an system-controlled domain (all domains that start
     // with "delta.")
-    #[allow(unused)]
-    fn is_internal(&self) -> bool {
+    pub(crate) fn is_internal(&self) -> bool {
         self.domain.starts_with(INTERNAL_DOMAIN_PREFIX)
     }
 }
@@ -852,6 +1005,7 @@ mod tests {
                 deletion_vector_field(),
                 StructField::nullable("baseRowId", DataType::LONG),
                 StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+                StructField::nullable("stats", DataType::STRING),
             ]),
         )]));
         assert_eq!(schema, expected);
@@ -943,11 +1097,18 @@ mod tests {
                     "operationParameters",
                     MapType::new(DataType::STRING, DataType::STRING, false),
                 ),
+                StructField::nullable(
+                    "operationMetrics",
+                    MapType::new(DataType::STRING, DataType::STRING, false),
+                ),
                 StructField::nullable("kernelVersion", DataType::STRING),
                 StructField::nullable(
                     "engineCommitInfo",
                     MapType::new(DataType::STRING, DataType::STRING, false),
                 ),
+                StructField::nullable("userMetadata", DataType::STRING),
+                StructField::nullable("engineInfo", DataType::STRING),
+                StructField::nullable("txnId", DataType::STRING),
             ]),
         )]));
         assert_eq!(schema, expected);
@@ -1105,12 +1266,39 @@ mod tests {
             3,
             7,
             Some([ReaderFeature::DeletionVectors]),
-            Some([WriterFeature::RowTracking]),
+            Some([WriterFeature::IdentityColumns]),
         )
         .unwrap();
         assert!(protocol.ensure_write_supported().is_err());
     }
 
+    #[test]
+    fn test_supported_writer_features() {
+        let legacy = |version| {
+            Protocol::try_new(1, version, None::<Vec<String>>, None::<Vec<String>>)
+                .unwrap()
+                .supported_writer_features()
+        };
+        assert!(!legacy(1).contains(&WriterFeature::AppendOnly));
+        assert!(legacy(2).contains(&WriterFeature::AppendOnly));
+        assert!(!legacy(4).contains(&WriterFeature::ColumnMapping));
+        assert!(legacy(6).contains(&WriterFeature::ColumnMapping));
+        assert!(!legacy(6).contains(&WriterFeature::DeletionVectors));
+
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some(["deletionVectors", "cool_feature"]),
+        )
+        .unwrap();
+        let supported = protocol.supported_writer_features();
+        assert!(supported.contains(&WriterFeature::DeletionVectors));
+        // writer version 7 only supports the listed features, and never unknown ones
+        assert!(!supported.contains(&WriterFeature::AppendOnly));
+        assert!(!supported.contains(&WriterFeature::unknown("cool_feature")));
+    }
+
     #[test]
     fn test_ensure_supported_features() {
         let supported_features = [ReaderFeature::ColumnMapping, ReaderFeature::DeletionVectors];
@@ -7,9 +7,10 @@ use std::sync::{Arc, LazyLock};
 use delta_kernel_derive::internal_api;
 
 use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{Expression, Predicate};
 use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType, Schema, StructField};
 use crate::utils::require;
-use crate::{DeltaResult, Error};
+use crate::{DeltaResult, Engine, EngineData, Error};
 
 use super::deletion_vector::DeletionVectorDescriptor;
 use super::domain_metadata::DomainMetadataMap;
@@ -178,7 +179,7 @@ impl RemoveVisitor {
         getters: &[&'a dyn GetData<'a>],
     ) -> DeltaResult<Remove> {
         require!(
-            getters.len() == 14,
+            getters.len() == 15,
             Error::InternalError(format!(
                 "Wrong number of RemoveVisitor getters: {}",
                 getters.len()
@@ -202,6 +203,7 @@ impl RemoveVisitor {
         let base_row_id: Option<i64> = getters[12].get_opt(row_index, "remove.baseRowId")?;
         let default_row_commit_version: Option<i64> =
             getters[13].get_opt(row_index, "remove.defaultRowCommitVersion")?;
+        let stats: Option<String> = getters[14].get_opt(row_index, "remove.stats")?;
 
         Ok(Remove {
             path,
@@ -214,6 +216,7 @@ impl RemoveVisitor {
             deletion_vector,
             base_row_id,
             default_row_commit_version,
+            stats,
         })
     }
     pub(crate) fn names_and_types() -> (&'static [ColumnName], &'static [DataType]) {
@@ -618,14 +621,12 @@ pub(crate) fn visit_protocol_at<'a>(
 ///
 /// Only the a single row of the engine data is checked (the first row). This is because in-commit
 /// timestamps requires that the CommitInfo containing the ICT be the first action in the log.
-#[allow(unused)]
 #[derive(Default)]
 pub(crate) struct InCommitTimestampVisitor {
     pub(crate) in_commit_timestamp: Option<i64>,
 }
 
 impl InCommitTimestampVisitor {
-    #[allow(unused)]
     /// Get the schema that the visitor expects the data to have.
     pub(crate) fn schema() -> Arc<Schema> {
         static SCHEMA: LazyLock<Arc<Schema>> = LazyLock::new(|| {
@@ -677,6 +678,115 @@ impl RowVisitor for InCommitTimestampVisitor {
     }
 }
 
+/// Get a CommitInfo out of some engine data. Note that Ok(None) is returned if there is no
+/// CommitInfo found. The caller is responsible for slicing the `getters` slice such that the first
+/// element contains the `timestamp` element of the commit info.
+#[internal_api]
+pub(crate) fn visit_commit_info_at<'a>(
+    row_index: usize,
+    getters: &[&'a dyn GetData<'a>],
+) -> DeltaResult<Option<CommitInfo>> {
+    let commit_info = read_commit_info_at(row_index, getters)?;
+    // No field of a commit info is required, so a row has one iff any field is set
+    let is_present = commit_info.timestamp.is_some()
+        || commit_info.in_commit_timestamp.is_some()
+        || commit_info.operation.is_some()
+        || commit_info.operation_parameters.is_some()
+        || commit_info.operation_metrics.is_some()
+        || commit_info.kernel_version.is_some()
+        || commit_info.engine_commit_info.is_some()
+        || commit_info.user_metadata.is_some()
+        || commit_info.engine_info.is_some()
+        || commit_info.txn_id.is_some();
+    Ok(is_present.then_some(commit_info))
+}
+
+// Read the fields of the commit info at `row_index`, whether or not the row has a commit info
+fn read_commit_info_at<'a>(
+    row_index: usize,
+    getters: &[&'a dyn GetData<'a>],
+) -> DeltaResult<CommitInfo> {
+    let i = row_index;
+    Ok(CommitInfo {
+        timestamp: getters[0].get_opt(i, "commitInfo.timestamp")?,
+        in_commit_timestamp: getters[1].get_opt(i, "commitInfo.inCommitTimestamp")?,
+        operation: getters[2].get_opt(i, "commitInfo.operation")?,
+        operation_parameters: getters[3].get_opt(i, "commitInfo.operationParameters")?,
+        operation_metrics: getters[4].get_opt(i, "commitInfo.operationMetrics")?,
+        kernel_version: getters[5].get_opt(i, "commitInfo.kernelVersion")?,
+        engine_commit_info: getters[6].get_opt(i, "commitInfo.engineCommitInfo")?,
+        user_metadata: getters[7].get_opt(i, "commitInfo.userMetadata")?,
+        engine_info: getters[8].get_opt(i, "commitInfo.engineInfo")?,
+        txn_id: getters[9].get_opt(i, "commitInfo.txnId")?,
+    })
+}
+
+/// Collects the [`CommitInfo`] actions of the visited rows, e.g. to describe the history of a
+/// table.
+///
+/// The leaf columns of a row can't tell a missing commit info apart from one that only has fields
+/// kernel doesn't read (e.g. `isolationLevel`), so [`RowVisitor::visit_rows_of`] only collects
+/// commit infos with some known field set. Use [`CommitInfoVisitor::visit_commit_infos`] to also
+/// collect the others.
+#[derive(Debug, Default)]
+#[internal_api]
+pub(crate) struct CommitInfoVisitor {
+    pub(crate) commit_infos: Vec<CommitInfo>,
+    // Whether each row of the batch being visited has a commit info, if known
+    present: Vec<bool>,
+}
+
+impl CommitInfoVisitor {
+    /// Collects the commit infos of `data`, which must have a `commitInfo` column. Every non-null
+    /// commit info is collected, even if none of its fields are known to kernel.
+    #[internal_api]
+    pub(crate) fn visit_commit_infos(
+        &mut self,
+        engine: &dyn Engine,
+        data: &dyn EngineData,
+    ) -> DeltaResult<()> {
+        static PRESENT_PRED: LazyLock<Predicate> =
+            LazyLock::new(|| Predicate::is_not_null(Expression::column([COMMIT_INFO_NAME])));
+        let present = engine
+            .evaluation_handler()
+            .new_predicate_evaluator(get_log_commit_info_schema().clone(), PRESENT_PRED.clone())
+            .evaluate(data)?;
+        let mut present_visitor = SelectionVectorVisitor::default();
+        present_visitor.visit_rows_of(present.as_ref())?;
+        self.present = present_visitor.selection_vector;
+        let result = self.visit_rows_of(data);
+        self.present.clear();
+        result
+    }
+}
+
+impl RowVisitor for CommitInfoVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| CommitInfo::to_schema().leaves(COMMIT_INFO_NAME));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 10,
+            Error::InternalError(format!(
+                "Wrong number of CommitInfoVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let commit_info = match self.present.get(i) {
+                Some(true) => Some(read_commit_info_at(i, getters)?),
+                Some(false) => None,
+                None => visit_commit_info_at(i, getters)?,
+            };
+            self.commit_infos.extend(commit_info);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -703,6 +813,67 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_commit_info() -> DeltaResult<()> {
+        let data = action_batch();
+        let mut visitor = CommitInfoVisitor::default();
+        visitor.visit_rows_of(data.as_ref())?;
+        let [commit_info] = visitor.commit_infos.as_slice() else {
+            panic!("expected one commit info, got {:?}", visitor.commit_infos);
+        };
+        assert_eq!(commit_info.timestamp, Some(1677811178585));
+        assert_eq!(commit_info.operation.as_deref(), Some("WRITE"));
+        assert_eq!(
+            commit_info.engine_info.as_deref(),
+            Some("Databricks-Runtime/<unknown>")
+        );
+        assert_eq!(commit_info.user_metadata, None);
+        assert_eq!(
+            commit_info.txn_id.as_deref(),
+            Some("a6a94671-55ef-450e-9546-b8465b9147de")
+        );
+        let metrics = commit_info.operation_metrics().unwrap();
+        assert_eq!(metrics.num_files(), Some(1));
+        assert_eq!(metrics.num_output_rows(), Some(10));
+        assert_eq!(metrics.num_output_bytes(), Some(635));
+        assert_eq!(metrics.get("numRemovedFiles"), None);
+        assert_eq!(metrics.iter().count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_sparse_commit_info() -> DeltaResult<()> {
+        let json_strings: StringArray = vec![
+            r#"{"commitInfo":{"inCommitTimestamp":1670892998177}}"#,
+            r#"{"add":{"path":"part-00000.parquet","partitionValues":{},"size":452,"modificationTime":1670892998135,"dataChange":true}}"#,
+            r#"{"commitInfo":{"engineInfo":"my-engine","isBlindAppend":true}}"#,
+            r#"{"commitInfo":{"isolationLevel":"Serializable"}}"#,
+        ]
+        .into();
+        let batch = parse_json_batch(json_strings);
+        let expected = [
+            CommitInfo {
+                in_commit_timestamp: Some(1670892998177),
+                ..Default::default()
+            },
+            CommitInfo {
+                engine_info: Some("my-engine".to_string()),
+                ..Default::default()
+            },
+            // only has fields kernel doesn't read
+            CommitInfo::default(),
+        ];
+        let mut visitor = CommitInfoVisitor::default();
+        visitor.visit_commit_infos(&SyncEngine::new(), batch.as_ref())?;
+        assert_eq!(visitor.commit_infos, expected);
+
+        // the leaf columns alone can't tell the last commit info apart from a missing one
+        let mut visitor = CommitInfoVisitor::default();
+        visitor.visit_rows_of(batch.as_ref())?;
+        assert_eq!(visitor.commit_infos, expected[..2]);
+        Ok(())
+    }
+
     #[test]
     fn test_parse_cdc() -> DeltaResult<()> {
         let data = action_batch();
@@ -823,7 +994,7 @@ mod tests {
         };
         let expected = vec![add1, add2, add3];
         assert_eq!(add_visitor.adds.len(), expected.len());
-        for (add, expected) in add_visitor.adds.into_iter().zip(expected.into_iter()) {
+        for (add, expected) in add_visitor.adds.into_iter().zip(expected) {
             assert_eq!(add, expected);
         }
     }
@@ -0,0 +1,228 @@
+//! Resolving tables by name through a catalog (e.g. Unity Catalog, Glue, Polaris or a Hive
+//! metastore) instead of by storage location.
+//!
+//! A [`Catalog`] maps a table name to a [`CatalogTable`]: where the table is stored, the commits
+//! the catalog knows of that may not be in the `_delta_log` yet, and the storage options (e.g.
+//! temporary credentials) an engine needs to access the table. The entry point for this API is
+//! [`Snapshot::try_from_catalog`].
+//!
+//! [`Snapshot::try_from_catalog`]: crate::Snapshot::try_from_catalog
+use std::collections::HashMap;
+
+use url::Url;
+
+use crate::path::ParsedLogPath;
+use crate::{DeltaResult, Error, FileMeta};
+
+/// A catalog of Delta tables. See the [module-level documentation](self).
+pub trait Catalog: Send + Sync {
+    /// Look up the table named `table_name`. Fails if the catalog has no such table.
+    fn resolve_table(&self, table_name: &str) -> DeltaResult<CatalogTable>;
+}
+
+/// A table resolved by a [`Catalog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogTable {
+    table_root: Url,
+    log_tail: Vec<FileMeta>,
+    storage_options: HashMap<String, String>,
+}
+
+impl CatalogTable {
+    /// A table stored at `table_root`, whose commits are all published to its `_delta_log`.
+    pub fn new(table_root: Url) -> Self {
+        Self {
+            table_root,
+            log_tail: vec![],
+            storage_options: HashMap::new(),
+        }
+    }
+
+    /// Set the latest commits of the table, in ascending version order, which listing the
+    /// `_delta_log` may not find, e.g. commits the catalog has accepted but not yet published.
+    /// Each must be named like a commit file (`<version>.json`), but may be stored anywhere.
+    pub fn with_log_tail(mut self, log_tail: Vec<FileMeta>) -> Self {
+        self.log_tail = log_tail;
+        self
+    }
+
+    /// Set the object store options (e.g. credentials) needed to access the table's storage.
+    pub fn with_storage_options<K, V>(mut self, options: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        self.storage_options = options
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.into()))
+            .collect();
+        self
+    }
+
+    /// The URL of the table root (where the `_delta_log` folder is located).
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// The latest commits of the table, see [`Self::with_log_tail`].
+    pub fn log_tail(&self) -> &[FileMeta] {
+        &self.log_tail
+    }
+
+    /// The object store options needed to access the table's storage, e.g. to create the
+    /// default engine with.
+    pub fn storage_options(&self) -> &HashMap<String, String> {
+        &self.storage_options
+    }
+
+    // Parse the log tail, which must be commit files of contiguous, ascending versions
+    pub(crate) fn parsed_log_tail(&self) -> DeltaResult<Vec<ParsedLogPath>> {
+        let log_tail = self
+            .log_tail
+            .iter()
+            .map(|file| match ParsedLogPath::try_from(file.clone())? {
+                Some(path) if path.is_commit() => Ok(path),
+                _ => Err(Error::invalid_log_path(&file.location)),
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        if let Some(pair) = log_tail
+            .windows(2)
+            .find(|pair| pair[0].version + 1 != pair[1].version)
+        {
+            return Err(Error::generic(format!(
+                "Catalog log tail is not contiguous: version {} is followed by {}",
+                pair[0].version, pair[1].version
+            )));
+        }
+        Ok(log_tail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use test_utils::{actions_to_string, TestAction};
+
+    use super::*;
+    use crate::engine::sync::SyncEngine;
+    use crate::{Snapshot, Version};
+
+    // A catalog of a single table named "test"
+    struct TestCatalog(CatalogTable);
+
+    impl Catalog for TestCatalog {
+        fn resolve_table(&self, table_name: &str) -> DeltaResult<CatalogTable> {
+            match table_name {
+                "test" => Ok(self.0.clone()),
+                _ => Err(Error::generic(format!("Table {table_name} not found"))),
+            }
+        }
+    }
+
+    fn metadata() -> String {
+        actions_to_string(vec![TestAction::Metadata])
+    }
+
+    fn add(path: &str) -> String {
+        actions_to_string(vec![TestAction::Add(path.to_string())])
+    }
+
+    // Write the commit `version` to `dir`, returning its file metadata
+    fn write_commit(dir: &Path, version: Version, content: &str) -> FileMeta {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(format!("{version:020}.json"));
+        std::fs::write(&path, content).unwrap();
+        FileMeta::new(Url::from_file_path(&path).unwrap(), 0, content.len() as u64)
+    }
+
+    #[test]
+    fn test_snapshot_from_catalog() -> DeltaResult<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        let staging_dir = dir.path().join("_staging");
+        write_commit(&log_dir, 0, &metadata());
+        let commit1 = write_commit(&log_dir, 1, &add("a.parquet"));
+        // Commit 2 is only known to the catalog
+        let commit2 = write_commit(&staging_dir, 2, &add("b.parquet"));
+        let table = CatalogTable::new(table_root.clone())
+            .with_log_tail(vec![commit1, commit2.clone()])
+            .with_storage_options([("key", "value")]);
+        assert_eq!(table.storage_options()["key"], "value");
+        let catalog = TestCatalog(table);
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::try_from_catalog(&catalog, "test", &engine, None)?;
+        assert_eq!(snapshot.version(), 2);
+        let commits = &snapshot.log_segment().ascending_commit_files;
+        // The listed commit 1 takes precedence over the catalog's
+        assert_eq!(
+            commits[1].location.location,
+            table_root.join("_delta_log/00000000000000000001.json")?
+        );
+        assert_eq!(commits[2].location, commit2);
+        let snapshot = Snapshot::try_from_catalog(&catalog, "test", &engine, Some(1))?;
+        assert_eq!(snapshot.version(), 1);
+
+        // Without the catalog, only the published commits are found
+        let snapshot = Snapshot::try_new(table_root, &engine, None)?;
+        assert_eq!(snapshot.version(), 1);
+
+        assert!(Snapshot::try_from_catalog(&catalog, "other", &engine, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_with_unpublished_commits() -> DeltaResult<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        write_commit(&log_dir, 0, &metadata());
+        write_commit(&log_dir, 1, &add("a.parquet"));
+        let commit2 = write_commit(&dir.path().join("_staging"), 2, &add("b.parquet"));
+        let catalog = TestCatalog(CatalogTable::new(table_root).with_log_tail(vec![commit2]));
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_from_catalog(&catalog, "test", &engine, None)?);
+        assert_eq!(snapshot.version(), 2);
+
+        // the history and timestamps of the table include the unpublished commit
+        let versions: Vec<_> = snapshot
+            .history(&engine, None)?
+            .map(|entry| entry.map(|entry| entry.version()))
+            .collect::<DeltaResult<_>>()?;
+        assert_eq!(versions, [2, 1, 0]);
+        let version = crate::history_manager::latest_version_as_of(&snapshot, &engine, i64::MAX)?;
+        assert_eq!(version, 2);
+
+        // refreshing the snapshot keeps the unpublished commit
+        let refreshed = snapshot.try_refresh(&engine)?;
+        assert!(Arc::ptr_eq(&refreshed, &snapshot));
+        write_commit(&log_dir, 2, &add("b.parquet"));
+        write_commit(&log_dir, 3, &add("c.parquet"));
+        let refreshed = snapshot.try_refresh(&engine)?;
+        assert_eq!(refreshed.version(), 3);
+        let refreshed = Snapshot::try_new_from(snapshot.clone(), &engine, None)?;
+        assert_eq!(refreshed.version(), 3);
+
+        // the table must be written through the catalog
+        assert!(matches!(snapshot.transaction(), Err(Error::Unsupported(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_log_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let commit0 = write_commit(dir.path(), 0, &metadata());
+        let commit2 = write_commit(dir.path(), 2, &add("b.parquet"));
+        let table = CatalogTable::new(table_root.clone()).with_log_tail(vec![commit0, commit2]);
+        let err = table.parsed_log_tail().unwrap_err();
+        assert!(err.to_string().contains("not contiguous"));
+
+        let data_file = FileMeta::new(table_root.join("part-0.parquet").unwrap(), 0, 1);
+        let table = CatalogTable::new(table_root).with_log_tail(vec![data_file]);
+        assert!(table.parsed_log_tail().is_err());
+    }
+}
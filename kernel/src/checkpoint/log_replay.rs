@@ -11,7 +11,9 @@
 //!    and metadata actions.
 //! 2. **Txn Actions**: Keeps exactly one `txn` action for each unique app ID, always selecting
 //!    the latest one encountered.
-//! 3. **File Actions**: Resolves file actions to produce the latest state of the table, keeping
+//! 3. **Domain Metadata Actions**: Keeps the latest `domainMetadata` action for each domain,
+//!    unless it removes the domain.
+//! 4. **File Actions**: Resolves file actions to produce the latest state of the table, keeping
 //!    the most recent valid add actions and unexpired remove actions (tombstones) that are newer
 //!    than `minimum_file_retention_timestamp`.
 //!
@@ -56,6 +58,8 @@ pub(crate) struct CheckpointLogReplayProcessor {
     seen_metadata: bool,
     /// Set of transaction app IDs that have been processed to avoid duplicates.
     seen_txns: HashSet<String>,
+    /// Set of metadata domains that have been processed to avoid duplicates.
+    seen_domains: HashSet<String>,
     /// Minimum timestamp for file retention, used for filtering expired tombstones.
     minimum_file_retention_timestamp: i64,
     /// Transaction expiration timestamp for filtering old transactions
@@ -108,6 +112,7 @@ impl LogReplayProcessor for CheckpointLogReplayProcessor {
             self.seen_protocol,
             self.seen_metadata,
             &mut self.seen_txns,
+            &mut self.seen_domains,
             self.txn_expiration_timestamp,
         );
         visitor.visit_rows_of(actions.as_ref())?;
@@ -144,6 +149,7 @@ impl CheckpointLogReplayProcessor {
             seen_protocol: false,
             seen_metadata: false,
             seen_txns: Default::default(),
+            seen_domains: Default::default(),
             minimum_file_retention_timestamp,
             txn_expiration_timestamp,
         }
@@ -173,6 +179,7 @@ impl CheckpointLogReplayProcessor {
 /// - Keeps only the first protocol action (newest version)
 /// - Keeps only the first metadata action (most recent table metadata)
 /// - Keeps only the first txn action for each unique app ID
+/// - Keeps only the first domainMetadata action for each domain, unless it removes the domain
 ///
 /// # Excluded Actions
 /// - CommitInfo, CDC, and CheckpointMetadata actions should not appear in the action
@@ -185,7 +192,7 @@ impl CheckpointLogReplayProcessor {
 ///
 /// # Memory Usage
 /// This struct has O(N + M) memory usage where:
-/// - N = number of txn actions with unique appIds
+/// - N = number of txn actions with unique appIds and domainMetadata actions with unique domains
 /// - M = number of file actions with unique (path, dvId) pairs
 ///
 /// The resulting filtered set of actions are the actions which should be written to a
@@ -212,6 +219,8 @@ pub(crate) struct CheckpointVisitor<'seen> {
     // Set of transaction IDs to deduplicate by appId
     // This set has O(N) memory usage where N = number of txn actions with unique appIds
     seen_txns: &'seen mut HashSet<String>,
+    // Set of metadata domains to deduplicate domain metadata by domain
+    seen_domains: &'seen mut HashSet<String>,
     /// Transaction expiration timestamp for filtering old transactions
     txn_expiration_timestamp: Option<i64>,
 }
@@ -240,6 +249,7 @@ impl CheckpointVisitor<'_> {
         seen_protocol: bool,
         seen_metadata: bool,
         seen_txns: &'seen mut HashSet<String>,
+        seen_domains: &'seen mut HashSet<String>,
         txn_expiration_timestamp: Option<i64>,
     ) -> CheckpointVisitor<'seen> {
         CheckpointVisitor {
@@ -258,6 +268,7 @@ impl CheckpointVisitor<'_> {
             seen_protocol,
             seen_metadata,
             seen_txns,
+            seen_domains,
             txn_expiration_timestamp,
         }
     }
@@ -404,13 +415,40 @@ impl CheckpointVisitor<'_> {
         Ok(true)
     }
 
+    /// Processes a potential domainMetadata action to determine if it should be included in the
+    /// checkpoint. Removed domains are excluded, since the checkpoint only needs to reproduce the
+    /// domains that exist.
+    ///
+    /// Returns Ok(true) if the row contains a valid domainMetadata action.
+    /// Returns Ok(false) if the row doesn't contain a domainMetadata action, is a duplicate, or
+    /// removes its domain.
+    /// Returns Err(...) if there was an error processing the action.
+    fn check_domain_metadata_action<'a>(
+        &mut self,
+        i: usize,
+        getter: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<bool> {
+        let Some(domain) = getter[13].get_str(i, "domainMetadata.domain")? else {
+            return Ok(false); // Not a domainMetadata action
+        };
+
+        // If the domain already exists in the set, the insertion will return false, indicating
+        // that this is a duplicate. Removed domains are recorded too, to skip their older actions.
+        if !self.seen_domains.insert(domain.to_string()) {
+            return Ok(false);
+        }
+        let removed: bool = getter[14].get(i, "domainMetadata.removed")?;
+        Ok(!removed)
+    }
+
     /// Determines if a row in the batch should be included in the checkpoint.
     ///
     /// This method checks each action type in sequence, short-circuiting as soon as a valid action is found.
     /// Actions are checked in order of expected frequency of occurrence to optimize performance:
     /// 1. File actions (most frequent)
     /// 2. Txn actions
-    /// 3. Protocol & Metadata actions (least frequent)
+    /// 3. Domain metadata actions
+    /// 4. Protocol & Metadata actions (least frequent)
     ///
     /// Returns Ok(true) if the row should be included in the checkpoint.
     /// Returns Ok(false) if the row should be skipped.
@@ -424,6 +462,7 @@ impl CheckpointVisitor<'_> {
         // the rest will not be evaluated.
         let is_valid = self.check_file_action(i, getters)?
             || self.check_txn_action(i, getters)?
+            || self.check_domain_metadata_action(i, getters)?
             || self.check_protocol_action(i, getters[10])?
             || self.check_metadata_action(i, getters[9])?;
 
@@ -443,10 +482,12 @@ impl RowVisitor for CheckpointVisitor<'_> {
         // 3. METADATA
         // 4. PROTOCOL
         // 5. TXN
+        // 6. DOMAIN METADATA
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             const STRING: DataType = DataType::STRING;
             const INTEGER: DataType = DataType::INTEGER;
             const LONG: DataType = DataType::LONG;
+            const BOOLEAN: DataType = DataType::BOOLEAN;
             let types_and_names = vec![
                 // File action columns
                 (STRING, column_name!("add.path")),
@@ -463,6 +504,8 @@ impl RowVisitor for CheckpointVisitor<'_> {
                 (INTEGER, column_name!("protocol.minReaderVersion")),
                 (STRING, column_name!("txn.appId")),
                 (LONG, column_name!("txn.lastUpdated")),
+                (STRING, column_name!("domainMetadata.domain")),
+                (BOOLEAN, column_name!("domainMetadata.removed")),
             ];
             let (types, names) = types_and_names.into_iter().unzip();
             (names, types).into()
@@ -472,7 +515,7 @@ impl RowVisitor for CheckpointVisitor<'_> {
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 13,
+            getters.len() == 15,
             Error::InternalError(format!(
                 "Wrong number of visitor getters: {}",
                 getters.len()
@@ -524,6 +567,7 @@ mod tests {
         let data = action_batch();
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         let mut visitor = CheckpointVisitor::new(
             &mut seen_file_keys,
             true,
@@ -532,6 +576,7 @@ mod tests {
             false,
             false,
             &mut seen_txns,
+            &mut seen_domains,
             None,
         );
 
@@ -579,6 +624,7 @@ mod tests {
 
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         let mut visitor = CheckpointVisitor::new(
             &mut seen_file_keys,
             true,
@@ -587,6 +633,7 @@ mod tests {
             false,
             false,
             &mut seen_txns,
+            &mut seen_domains,
             None,
         );
 
@@ -610,6 +657,7 @@ mod tests {
 
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         let mut visitor = CheckpointVisitor::new(
             &mut seen_file_keys,
             false, // is_log_batch = false (checkpoint batch)
@@ -618,6 +666,7 @@ mod tests {
             false,
             false,
             &mut seen_txns,
+            &mut seen_domains,
             None,
         );
 
@@ -649,6 +698,7 @@ mod tests {
 
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         let mut visitor = CheckpointVisitor::new(
             &mut seen_file_keys,
             true,
@@ -657,6 +707,7 @@ mod tests {
             false,
             false,
             &mut seen_txns,
+            &mut seen_domains,
             None,
         );
 
@@ -682,6 +733,7 @@ mod tests {
         // Pre-populate with txn app1
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         seen_txns.insert("app1".to_string());
 
         let mut visitor = CheckpointVisitor::new(
@@ -692,6 +744,7 @@ mod tests {
             true,           // The visior has already seen a protocol action
             true,           // The visitor has already seen a metadata action
             &mut seen_txns, // Pre-populated transaction
+            &mut seen_domains,
             None,
         );
 
@@ -705,6 +758,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_checkpoint_visitor_domain_metadata() -> DeltaResult<()> {
+        let json_strings: StringArray = vec![
+            r#"{"domainMetadata":{"domain":"domain1","configuration":"v2","removed":false}}"#,
+            r#"{"domainMetadata":{"domain":"domain1","configuration":"v1","removed":false}}"#, // Duplicate domain
+            r#"{"domainMetadata":{"domain":"domain2","configuration":"v2","removed":true}}"#, // Removed domain
+            r#"{"domainMetadata":{"domain":"domain2","configuration":"v1","removed":false}}"#, // Older than the removal
+            r#"{"domainMetadata":{"domain":"domain3","configuration":"v1","removed":false}}"#,
+        ]
+        .into();
+        let batch = parse_json_batch(json_strings);
+
+        let mut seen_file_keys = HashSet::new();
+        let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
+        let mut visitor = CheckpointVisitor::new(
+            &mut seen_file_keys,
+            true,
+            vec![true; 5],
+            0,
+            false,
+            false,
+            &mut seen_txns,
+            &mut seen_domains,
+            None,
+        );
+
+        visitor.visit_rows_of(batch.as_ref())?;
+
+        let expected = vec![true, false, false, false, true];
+        assert_eq!(visitor.selection_vector, expected);
+        assert_eq!(visitor.actions_count, 2);
+        assert_eq!(visitor.seen_domains.len(), 3);
+        Ok(())
+    }
+
     #[test]
     fn test_checkpoint_visitor_duplicate_non_file_actions() -> DeltaResult<()> {
         let json_strings: StringArray = vec![
@@ -722,6 +811,7 @@ mod tests {
 
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         let mut visitor = CheckpointVisitor::new(
             &mut seen_file_keys,
             true, // is_log_batch
@@ -730,6 +820,7 @@ mod tests {
             false,
             false,
             &mut seen_txns,
+            &mut seen_domains,
             None,
         );
 
@@ -876,6 +967,7 @@ mod tests {
 
         let mut seen_file_keys = HashSet::new();
         let mut seen_txns = HashSet::new();
+        let mut seen_domains = HashSet::new();
         let mut visitor = CheckpointVisitor::new(
             &mut seen_file_keys,
             true,
@@ -884,6 +976,7 @@ mod tests {
             false,
             false,
             &mut seen_txns,
+            &mut seen_domains,
             Some(1000), // expiration timestamp
         );
 
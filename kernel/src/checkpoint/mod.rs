@@ -3,12 +3,28 @@
 //! The entry point for this API is [`Snapshot::checkpoint`].
 //!
 //! ## Checkpoint Types and Selection Logic
-//! This API supports two checkpoint types, selected based on table features:
+//! This API supports two checkpoint types, selected based on table features and the table's
+//! `delta.checkpointPolicy` property:
 //!
-//! | Table Feature    | Resulting Checkpoint Type    | Description                                                                 |
-//! |------------------|-------------------------------|-----------------------------------------------------------------------------|
-//! | No v2Checkpoints | Single-file Classic-named V1 | Follows V1 specification without [`CheckpointMetadata`] action             |
-//! | v2Checkpoints    | Single-file Classic-named V2 | Follows V2 specification with [`CheckpointMetadata`] action while maintaining backward compatibility via classic naming |
+//! | Table Feature    | Checkpoint Policy | Resulting Checkpoint Type    | Description                                                                 |
+//! |------------------|-------------------|------------------------------|-----------------------------------------------------------------------------|
+//! | No v2Checkpoints | any               | Single-file Classic-named V1 | Follows V1 specification without [`CheckpointMetadata`] action             |
+//! | v2Checkpoints    | `v2` or unset     | Single-file Classic-named V2 | Follows V2 specification with [`CheckpointMetadata`] action while maintaining backward compatibility via classic naming |
+//! | v2Checkpoints    | `classic`         | Single-file Classic-named V1 | Follows V1 specification, which readers of V2 checkpoint tables must also accept |
+//!
+//! Engines can override the table's policy with [`CheckpointWriter::with_policy`], e.g. to keep
+//! writing V1 checkpoints for older readers. Requesting the `v2` policy for a table without the
+//! `v2Checkpoints` feature is an error.
+//!
+//! V2 spec checkpoints can also move their file actions (`add` and `remove`) into sidecar files in
+//! the `_delta_log/_sidecars/` directory, see [`CheckpointWriter::with_sidecars`]. This keeps the
+//! top-level checkpoint file small for tables with many files, and lets engines write the sidecar
+//! files in parallel.
+//!
+//! Either way, the `_last_checkpoint` file only records the classic fields (`version`, `size`,
+//! `sizeInBytes`, ...) and never the V2-only `v2Checkpoint` field, which describes UUID-named
+//! checkpoints and their sidecars. Readers that do not understand V2 checkpoints can therefore
+//! still use it to find the checkpoint.
 //!
 //! For more information on the V1/V2 specifications, see the following protocol section:
 //! <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#checkpoint-specs>
@@ -17,15 +33,19 @@
 //!
 //! - [`CheckpointWriter`] - Core component that manages the checkpoint creation workflow
 //! - [`CheckpointDataIterator`] - Iterator over the checkpoint data to be written
+//! - [`SidecarDataIterator`] - Iterator over the sidecar files to be written, if any
 //!
 //! ## Usage
 //!
-//! The following steps outline the process of creating a checkpoint:
+//! [`CheckpointWriter::write`] writes a checkpoint with the engine's [`ParquetHandler`]. Engines
+//! can also write the checkpoint data themselves: the following steps outline the process of
+//! creating a checkpoint:
 //!
 //! 1. Create a [`CheckpointWriter`] using [`Snapshot::checkpoint`]
 //! 2. Get the checkpoint path from [`CheckpointWriter::checkpoint_path`]
 //! 2. Get the checkpoint data from [`CheckpointWriter::checkpoint_data`]
-//! 3. Write the data to the path in object storage (engine-specific)
+//! 3. Write the data to the path in object storage with [`CheckpointWriter::output_schema`]
+//!    (engine-specific)
 //! 4. Collect metadata ([`FileMeta`]) from the write operation
 //! 5. Pass the metadata and exhausted data iterator to [`CheckpointWriter::finalize`]
 //!
@@ -66,10 +86,41 @@
 //! # Ok::<_, Error>(())
 //! ```
 //!
+//! To write a checkpoint with sidecars, configure the writer with
+//! [`CheckpointWriter::with_sidecars`], write each file returned by
+//! [`CheckpointWriter::sidecar_data`] first, then get the data of the top-level checkpoint file from
+//! [`CheckpointWriter::checkpoint_data_with_sidecars`] and continue as above:
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use delta_kernel::checkpoint::{CheckpointDataIterator, SidecarData};
+//! # use delta_kernel::{DeltaResult, Engine, Error, FileMeta, Snapshot};
+//! # use url::Url;
+//! # fn write_checkpoint_file(path: Url, data: &CheckpointDataIterator) -> DeltaResult<FileMeta> { todo!() }
+//! # fn write_sidecar_file(sidecar: SidecarData) -> DeltaResult<FileMeta> { todo!() }
+//! # let engine: &dyn Engine = todo!();
+//! # let snapshot: Arc<Snapshot> = todo!();
+//! // Move file actions into sidecar files of about 100,000 actions each
+//! let writer = snapshot.checkpoint()?.with_sidecars(100_000)?;
+//!
+//! let mut sidecar_data = writer.sidecar_data(engine)?;
+//! let mut sidecar_files = vec![];
+//! for sidecar in sidecar_data.by_ref() {
+//!     sidecar_files.push(write_sidecar_file(sidecar?)?);
+//! }
+//!
+//! let checkpoint_data = writer.checkpoint_data_with_sidecars(engine, sidecar_data, &sidecar_files)?;
+//! let metadata = write_checkpoint_file(writer.checkpoint_path()?, &checkpoint_data)?;
+//! writer.finalize(engine, &metadata, checkpoint_data)?;
+//! # Ok::<_, Error>(())
+//! ```
+//!
 //! ## Warning
 //! Multi-part (V1) checkpoints are DEPRECATED and UNSAFE.
 //!
 //! ## Note
+//! The top-level checkpoint file is always classic-named, even when it references sidecars; only
+//! the sidecar files themselves are UUID-named.
 //! We currently do not plan to support UUID-named V2 checkpoints, since S3's put-if-absent
 //! semantics remove the need for UUIDs to ensure uniqueness. Supporting only classic-named
 //! checkpoints avoids added complexity, such as coordinating naming decisions between kernel and
@@ -77,6 +128,7 @@
 //! in the future, we can revisit this decision.
 //!
 //! [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+//! [`ParquetHandler`]: crate::ParquetHandler
 //! [`LastCheckpointHint`]: crate::snapshot::LastCheckpointHint
 // Future extensions:
 // - TODO(#837): Multi-file V2 checkpoints are not supported yet. The API is designed to be extensible for future
@@ -85,22 +137,28 @@ use std::sync::{Arc, LazyLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::actions::{
-    Add, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME, CHECKPOINT_METADATA_NAME,
-    METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME, SET_TRANSACTION_NAME, SIDECAR_NAME,
+    Add, DomainMetadata, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME,
+    CHECKPOINT_METADATA_NAME, DOMAIN_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
+    SET_TRANSACTION_NAME, SIDECAR_NAME,
 };
+use crate::clock::{KernelClock, SystemClock};
 use crate::engine_data::FilteredEngineData;
 use crate::expressions::Scalar;
 use crate::log_replay::LogReplayProcessor;
 use crate::path::ParsedLogPath;
 use crate::schema::{DataType, SchemaRef, StructField, StructType, ToSchema as _};
 use crate::snapshot::{Snapshot, LAST_CHECKPOINT_FILE_NAME};
+use crate::table_properties::CheckpointPolicy;
 use crate::utils::calculate_transaction_expiration_timestamp;
 use crate::{DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension, FileMeta};
 use log_replay::{CheckpointBatch, CheckpointLogReplayProcessor};
 
 use url::Url;
 
+pub use sidecar::{SidecarData, SidecarDataIterator};
+
 mod log_replay;
+mod sidecar;
 #[cfg(test)]
 mod tests;
 
@@ -109,7 +167,12 @@ const MINUTES_PER_HOUR: u64 = 60;
 const HOURS_PER_DAY: u64 = 24;
 /// The default retention period for deleted files in seconds.
 /// This is set to 7 days, which is the default in delta-spark.
-const DEFAULT_RETENTION_SECS: u64 = 7 * HOURS_PER_DAY * MINUTES_PER_HOUR * SECONDS_PER_MINUTE;
+pub(crate) const DEFAULT_RETENTION_SECS: u64 =
+    7 * HOURS_PER_DAY * MINUTES_PER_HOUR * SECONDS_PER_MINUTE;
+/// The default retention period for log files in seconds.
+/// This is set to 30 days, which is the default in delta-spark.
+pub(crate) const DEFAULT_LOG_RETENTION_SECS: u64 =
+    30 * HOURS_PER_DAY * MINUTES_PER_HOUR * SECONDS_PER_MINUTE;
 
 /// Schema of the `_last_checkpoint` file
 /// We cannot use `LastCheckpointInfo::to_schema()` as it would include the 'checkpoint_schema'
@@ -133,6 +196,7 @@ static CHECKPOINT_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
         StructField::nullable(METADATA_NAME, Metadata::to_schema()),
         StructField::nullable(PROTOCOL_NAME, Protocol::to_schema()),
         StructField::nullable(SET_TRANSACTION_NAME, SetTransaction::to_schema()),
+        StructField::nullable(DOMAIN_METADATA_NAME, DomainMetadata::to_schema()),
         StructField::nullable(SIDECAR_NAME, Sidecar::to_schema()),
     ]))
 });
@@ -147,6 +211,49 @@ static CHECKPOINT_METADATA_ACTION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(||
     )]))
 });
 
+// Schema of the [`Sidecar`] actions that reference the sidecar files of a V2 checkpoint. Like
+// CHECKPOINT_METADATA_ACTION_SCHEMA, it leaves out the 'tags' field TODO(#880).
+static SIDECAR_ACTION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        SIDECAR_NAME,
+        DataType::struct_type([
+            StructField::not_null("path", DataType::STRING),
+            StructField::not_null("sizeInBytes", DataType::LONG),
+            StructField::not_null("modificationTime", DataType::LONG),
+        ]),
+    )]))
+});
+
+// Schema of the top-level file of a V1 spec checkpoint, see [`CheckpointWriter::output_schema`]
+static CHECKPOINT_V1_OUTPUT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(
+        CHECKPOINT_ACTIONS_SCHEMA
+            .fields()
+            .filter(|field| field.name() != SIDECAR_NAME)
+            .cloned(),
+    ))
+});
+
+// Schema of the top-level file of a V2 spec checkpoint, which can also hold sidecar actions and
+// holds the checkpoint metadata action
+static CHECKPOINT_V2_OUTPUT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(
+        CHECKPOINT_V1_OUTPUT_SCHEMA
+            .fields()
+            .chain(SIDECAR_ACTION_SCHEMA.fields())
+            .chain(CHECKPOINT_METADATA_ACTION_SCHEMA.fields())
+            .cloned(),
+    ))
+});
+
+// Schema of the sidecar files of a V2 spec checkpoint, which only hold file actions
+static SIDECAR_FILE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([
+        StructField::nullable(ADD_NAME, Add::to_schema()),
+        StructField::nullable(REMOVE_NAME, Remove::to_schema()),
+    ]))
+});
+
 /// An iterator over the checkpoint data to be written to the file.
 ///
 /// This iterator yields filtered checkpoint data batches ([`FilteredEngineData`]) and
@@ -187,7 +294,8 @@ impl Iterator for CheckpointDataIterator {
 ///
 /// The [`CheckpointWriter`] is the entry point for generating checkpoint data for a Delta table.
 /// It automatically selects the appropriate checkpoint format (V1/V2) based on whether the table
-/// supports the `v2Checkpoints` reader/writer feature.
+/// supports the `v2Checkpoints` reader/writer feature and the table's `delta.checkpointPolicy`.
+/// Use [`CheckpointWriter::with_policy`] to override the policy.
 ///
 /// # Warning
 /// The checkpoint data must be fully written to storage before calling [`CheckpointWriter::finalize`].
@@ -203,10 +311,26 @@ pub struct CheckpointWriter {
     /// Note: Although the version is stored as a u64 in the snapshot, it is stored as an i64
     /// field here to avoid multiple type conversions.
     version: i64,
+
+    /// Whether to write a V2 spec checkpoint, i.e. one that includes a [`CheckpointMetadata`]
+    /// action.
+    ///
+    /// [`CheckpointMetadata`]: crate::actions::CheckpointMetadata
+    is_v2_spec: bool,
+
+    /// The target number of actions per sidecar file, if file actions are written to sidecars.
+    /// See [`CheckpointWriter::with_sidecars`].
+    actions_per_sidecar: Option<usize>,
+
+    /// The source of the current time, used to enforce the table's log retention.
+    clock: Arc<dyn KernelClock>,
 }
 
 impl CheckpointWriter {
     /// Creates a new [`CheckpointWriter`] for the given snapshot.
+    ///
+    /// Returns an error if the table supports the `checkpointProtection` writer feature and the
+    /// snapshot's version is protected, i.e. before `delta.requireCheckpointProtectionBeforeVersion`.
     pub(crate) fn try_new(snapshot: Arc<Snapshot>) -> DeltaResult<Self> {
         let version = i64::try_from(snapshot.version()).map_err(|e| {
             Error::CheckpointWrite(format!(
@@ -216,7 +340,145 @@ impl CheckpointWriter {
             ))
         })?;
 
-        Ok(Self { snapshot, version })
+        // Checkpoints of protected versions may only be rewritten by writers that can truncate all
+        // protected history at once, which kernel never does.
+        if let Some(protected) = snapshot
+            .table_configuration()
+            .checkpoint_protection_version()
+            .filter(|protected| snapshot.version() < *protected)
+        {
+            return Err(Error::checkpoint_write(format!(
+                "Cannot checkpoint version {version}: the table's checkpointProtection feature \
+                protects versions before {protected}"
+            )));
+        }
+
+        // Tables that support V2 checkpoints write them unless the table explicitly asks for
+        // classic checkpoints (e.g. while the v2Checkpoints feature is being dropped).
+        let is_v2_spec = snapshot
+            .table_configuration()
+            .is_v2_checkpoint_write_supported()
+            && snapshot.table_properties().checkpoint_policy != Some(CheckpointPolicy::Classic);
+
+        Ok(Self {
+            snapshot,
+            version,
+            is_v2_spec,
+            actions_per_sidecar: None,
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    /// Use `clock` as the source of the current time, instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn KernelClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the checkpoint policy for this checkpoint, for tables that don't set
+    /// `delta.checkpointPolicy`. Returns an error if the table sets a different policy.
+    ///
+    /// [`CheckpointPolicy::Classic`] writes a V1 spec checkpoint, which any reader can use, even if
+    /// the table supports the `v2Checkpoints` feature. [`CheckpointPolicy::V2`] writes a V2 spec
+    /// checkpoint, and returns an error if the table does not support the `v2Checkpoints` feature.
+    pub fn with_policy(mut self, policy: CheckpointPolicy) -> DeltaResult<Self> {
+        if let Some(table_policy) = &self.snapshot.table_properties().checkpoint_policy {
+            if *table_policy != policy {
+                return Err(Error::checkpoint_write(format!(
+                    "Cannot write a {policy:?} checkpoint for a table whose checkpoint policy is \
+                    {table_policy:?}"
+                )));
+            }
+        }
+        self.is_v2_spec = match policy {
+            CheckpointPolicy::Classic if self.actions_per_sidecar.is_some() => {
+                return Err(Error::checkpoint_write(
+                    "Cannot write a classic checkpoint with sidecars",
+                ));
+            }
+            CheckpointPolicy::Classic => false,
+            CheckpointPolicy::V2 => {
+                if !self
+                    .snapshot
+                    .table_configuration()
+                    .is_v2_checkpoint_write_supported()
+                {
+                    return Err(Error::checkpoint_write(
+                        "Cannot write a V2 checkpoint for a table that does not support the \
+                        v2Checkpoint feature",
+                    ));
+                }
+                true
+            }
+        };
+        Ok(self)
+    }
+
+    /// Moves the file actions (`add` and `remove`) of the checkpoint into sidecar files of about
+    /// `actions_per_sidecar` actions each. The target is approximate: batches of actions are never
+    /// split, and batches that also hold other actions stay in the top-level checkpoint file.
+    ///
+    /// Sidecars are part of the V2 checkpoint spec, so this returns an error unless the writer
+    /// writes a V2 spec checkpoint (see [`Self::policy`]), or if `actions_per_sidecar` is zero.
+    /// Once configured, the checkpoint data must be produced with [`Self::sidecar_data`] and
+    /// [`Self::checkpoint_data_with_sidecars`] rather than [`Self::checkpoint_data`].
+    pub fn with_sidecars(mut self, actions_per_sidecar: usize) -> DeltaResult<Self> {
+        if !self.is_v2_spec {
+            return Err(Error::checkpoint_write(
+                "Sidecar files can only be written for V2 checkpoints",
+            ));
+        }
+        if actions_per_sidecar == 0 {
+            return Err(Error::checkpoint_write(
+                "The number of actions per sidecar file must be greater than zero",
+            ));
+        }
+        self.actions_per_sidecar = Some(actions_per_sidecar);
+        Ok(self)
+    }
+
+    /// The checkpoint policy this writer follows: [`CheckpointPolicy::V2`] if it writes a V2 spec
+    /// checkpoint, otherwise [`CheckpointPolicy::Classic`].
+    pub fn policy(&self) -> CheckpointPolicy {
+        match self.is_v2_spec {
+            true => CheckpointPolicy::V2,
+            false => CheckpointPolicy::Classic,
+        }
+    }
+
+    /// Refuses to checkpoint a version whose commit is older than the table's log retention
+    /// (`delta.logRetentionDuration`), unless it is the latest version of the table: log cleanup
+    /// never removes the latest version, but a checkpoint of an older version outside the
+    /// retention window would immediately be eligible for cleanup.
+    fn ensure_within_log_retention(&self, engine: &dyn Engine) -> DeltaResult<()> {
+        let version = self.snapshot.version();
+        let log_segment = self.snapshot.log_segment();
+        // The commit file's modification time approximates the commit timestamp. If there is no
+        // commit file for this version, the table was already checkpointed at this version.
+        let Some(commit) = log_segment
+            .ascending_commit_files
+            .last()
+            .filter(|commit| commit.version == version)
+        else {
+            return Ok(());
+        };
+        let result = ensure_within_log_retention(
+            commit.location.last_modified,
+            self.snapshot.table_properties().log_retention_duration,
+            self.clock.now_millis()?,
+        );
+        if result.is_ok() {
+            return Ok(());
+        }
+
+        let next_version = log_segment.log_root.join(&format!("{:020}", version + 1))?;
+        for file in engine.storage_handler().list_from(&next_version)? {
+            let path = ParsedLogPath::try_from(file?)?;
+            if path.is_some_and(|path| path.is_commit() && path.version > version) {
+                return result;
+            }
+        }
+        Ok(())
     }
 
     fn get_transaction_expiration_timestamp(&self) -> DeltaResult<Option<i64>> {
@@ -248,19 +510,171 @@ impl CheckpointWriter {
     /// - `engine`: Implementation of [`Engine`] APIs.
     ///
     /// # Returns: [`CheckpointDataIterator`] containing the checkpoint data
+    ///
+    /// Returns an error if the snapshot is not the latest version of the table and its commit is
+    /// older than the table's log retention (`delta.logRetentionDuration`).
     // This method is the core of the checkpoint generation process. It:
-    // 1. Determines whether to write a V1 or V2 checkpoint based on the table's
-    //    `v2Checkpoints` feature support
-    // 2. Reads actions from the log segment using the checkpoint read schema
-    // 3. Filters and deduplicates actions for the checkpoint
-    // 4. Chains the checkpoint metadata action if writing a V2 spec checkpoint
-    //    (i.e., if `v2Checkpoints` feature is supported by table)
-    // 5. Generates the appropriate checkpoint path
+    // 1. Reads actions from the log segment using the checkpoint read schema
+    // 2. Filters and deduplicates actions for the checkpoint
+    // 3. Chains the checkpoint metadata action if writing a V2 spec checkpoint
+    //    (see [`Self::policy`])
     pub fn checkpoint_data(&self, engine: &dyn Engine) -> DeltaResult<CheckpointDataIterator> {
-        let is_v2_checkpoints_supported = self
-            .snapshot
-            .table_configuration()
-            .is_v2_checkpoint_write_supported();
+        if self.actions_per_sidecar.is_some() {
+            return Err(Error::checkpoint_write(
+                "Use sidecar_data and checkpoint_data_with_sidecars for a checkpoint with sidecars",
+            ));
+        }
+        let checkpoint_data = self.checkpoint_batches(engine)?;
+        let checkpoint_metadata = self
+            .is_v2_spec
+            .then(|| self.create_checkpoint_metadata_batch(engine));
+
+        // Wrap the iterator in a CheckpointDataIterator to track action counts
+        Ok(CheckpointDataIterator {
+            checkpoint_batch_iterator: Box::new(checkpoint_data.chain(checkpoint_metadata)),
+            actions_count: 0,
+            add_actions_count: 0,
+        })
+    }
+
+    /// Returns the sidecar files to write for a checkpoint configured with
+    /// [`Self::with_sidecars`]. Each [`SidecarData`] must be written as a parquet file to its path.
+    ///
+    /// Once the iterator is exhausted and all files are written, pass it to
+    /// [`Self::checkpoint_data_with_sidecars`] to get the data of the top-level checkpoint file.
+    ///
+    /// Returns an error if the writer is not configured with sidecars, or under the same
+    /// conditions as [`Self::checkpoint_data`].
+    pub fn sidecar_data(&self, engine: &dyn Engine) -> DeltaResult<SidecarDataIterator> {
+        let Some(actions_per_sidecar) = self.actions_per_sidecar else {
+            return Err(Error::checkpoint_write(
+                "The checkpoint writer is not configured with sidecars",
+            ));
+        };
+        let sidecars_dir = self.snapshot.log_segment().log_root.join("_sidecars/")?;
+        Ok(SidecarDataIterator::new(
+            Box::new(self.checkpoint_batches(engine)?),
+            sidecars_dir,
+            actions_per_sidecar,
+        ))
+    }
+
+    /// Returns the data of the top-level checkpoint file of a checkpoint with sidecars: the
+    /// actions that were not moved to sidecar files, a [`Sidecar`] action for each sidecar file,
+    /// and the checkpoint metadata action. Write it to [`Self::checkpoint_path`] and pass it to
+    /// [`Self::finalize`] as for [`Self::checkpoint_data`].
+    ///
+    /// # Parameters
+    /// - `engine`: Implementation of [`Engine`] APIs.
+    /// - `sidecar_data`: The exhausted iterator returned by [`Self::sidecar_data`]
+    /// - `sidecar_files`: The metadata of every sidecar file written, in any order
+    ///
+    /// Returns an error if the sidecar iterator is not exhausted, or if any sidecar file it
+    /// returned is missing from `sidecar_files`.
+    ///
+    /// [`Sidecar`]: crate::actions::Sidecar
+    pub fn checkpoint_data_with_sidecars(
+        &self,
+        engine: &dyn Engine,
+        sidecar_data: SidecarDataIterator,
+        sidecar_files: &[FileMeta],
+    ) -> DeltaResult<CheckpointDataIterator> {
+        if !sidecar_data.exhausted {
+            return Err(Error::checkpoint_write(
+                "The sidecar data iterator must be fully consumed and written to storage before \
+                writing the checkpoint",
+            ));
+        }
+        let sidecars_dir = self.snapshot.log_segment().log_root.join("_sidecars/")?;
+        let sidecar_batches = sidecar_data
+            .sidecar_names
+            .iter()
+            .map(|name| {
+                let location = sidecars_dir.join(name)?;
+                let file = sidecar_files
+                    .iter()
+                    .find(|file| file.location == location)
+                    .ok_or_else(|| {
+                        Error::checkpoint_write(format!("Missing metadata for sidecar {location}"))
+                    })?;
+                self.create_sidecar_batch(engine, name, file)
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        let sidecar_count = sidecar_batches.len() as i64;
+
+        let checkpoint_batches = sidecar_data
+            .checkpoint_batches
+            .into_iter()
+            .chain(sidecar_batches)
+            .map(|filtered_data| {
+                // The action counts of these batches are already included in the totals below
+                Ok(CheckpointBatch {
+                    filtered_data,
+                    actions_count: 0,
+                    add_actions_count: 0,
+                })
+            });
+        let checkpoint_metadata = self.create_checkpoint_metadata_batch(engine);
+
+        Ok(CheckpointDataIterator {
+            checkpoint_batch_iterator: Box::new(
+                checkpoint_batches.chain(std::iter::once(checkpoint_metadata)),
+            ),
+            actions_count: sidecar_data.actions_count + sidecar_count,
+            add_actions_count: sidecar_data.add_actions_count,
+        })
+    }
+
+    /// Returns the schema to write the top-level checkpoint file with: a nullable column for each
+    /// action the file can hold. The batches of checkpoint data don't all have the same columns
+    /// (e.g. the checkpoint metadata action is a batch of its own), and the columns a batch
+    /// doesn't have must be written as nulls.
+    pub fn output_schema(&self) -> SchemaRef {
+        if self.is_v2_spec {
+            CHECKPOINT_V2_OUTPUT_SCHEMA.clone()
+        } else {
+            CHECKPOINT_V1_OUTPUT_SCHEMA.clone()
+        }
+    }
+
+    /// Writes the checkpoint, including its sidecar files if the writer is configured with
+    /// [`Self::with_sidecars`], with the engine's [`ParquetHandler`] and finalizes it. This
+    /// performs all the steps of the [module-level documentation](self) for engines which
+    /// implement [`ParquetHandler::write_parquet_data`].
+    ///
+    /// [`ParquetHandler`]: crate::ParquetHandler
+    /// [`ParquetHandler::write_parquet_data`]: crate::ParquetHandler::write_parquet_data
+    pub fn write(self, engine: &dyn Engine) -> DeltaResult<()> {
+        let parquet_handler = engine.parquet_handler();
+        let mut checkpoint_data = if self.actions_per_sidecar.is_some() {
+            let mut sidecar_data = self.sidecar_data(engine)?;
+            let mut sidecar_files = vec![];
+            for sidecar in sidecar_data.by_ref() {
+                let sidecar = sidecar?;
+                sidecar_files.push(parquet_handler.write_parquet_data(
+                    &sidecar.path,
+                    SIDECAR_FILE_SCHEMA.clone(),
+                    Box::new(sidecar.data.into_iter().map(Ok)),
+                )?);
+            }
+            self.checkpoint_data_with_sidecars(engine, sidecar_data, &sidecar_files)?
+        } else {
+            self.checkpoint_data(engine)?
+        };
+        let metadata = parquet_handler.write_parquet_data(
+            &self.checkpoint_path()?,
+            self.output_schema(),
+            Box::new(checkpoint_data.by_ref()),
+        )?;
+        self.finalize(engine, &metadata, checkpoint_data)
+    }
+
+    /// Reads the actions of the log segment, and filters and deduplicates them for the checkpoint.
+    fn checkpoint_batches(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<CheckpointBatch>> + Send> {
+        self.ensure_within_log_retention(engine)?;
 
         let actions = self.snapshot.log_segment().read_actions(
             engine,
@@ -269,22 +683,11 @@ impl CheckpointWriter {
             None,
         )?;
 
-        // Create iterator over actions for checkpoint data
-        let checkpoint_data = CheckpointLogReplayProcessor::new(
+        Ok(CheckpointLogReplayProcessor::new(
             self.deleted_file_retention_timestamp()?,
             self.get_transaction_expiration_timestamp()?,
         )
-        .process_actions_iter(actions);
-
-        let checkpoint_metadata =
-            is_v2_checkpoints_supported.then(|| self.create_checkpoint_metadata_batch(engine));
-
-        // Wrap the iterator in a CheckpointDataIterator to track action counts
-        Ok(CheckpointDataIterator {
-            checkpoint_batch_iterator: Box::new(checkpoint_data.chain(checkpoint_metadata)),
-            actions_count: 0,
-            add_actions_count: 0,
-        })
+        .process_actions_iter(actions))
     }
 
     /// Finalizes checkpoint creation by saving metadata about the checkpoint.
@@ -385,6 +788,36 @@ impl CheckpointWriter {
         })
     }
 
+    /// Creates the single-row batch of the [`Sidecar`] action that references a sidecar file,
+    /// by its file name relative to the `_delta_log/_sidecars/` directory.
+    ///
+    /// [`Sidecar`]: crate::actions::Sidecar
+    fn create_sidecar_batch(
+        &self,
+        engine: &dyn Engine,
+        name: &str,
+        file: &FileMeta,
+    ) -> DeltaResult<FilteredEngineData> {
+        let size_in_bytes = i64::try_from(file.size).map_err(|e| {
+            Error::CheckpointWrite(format!(
+                "Failed to convert sidecar size in bytes from u64 {} to i64: {}",
+                file.size, e
+            ))
+        })?;
+        let data = engine.evaluation_handler().create_one(
+            SIDECAR_ACTION_SCHEMA.clone(),
+            &[
+                Scalar::from(name),
+                Scalar::from(size_in_bytes),
+                Scalar::from(file.last_modified),
+            ],
+        )?;
+        Ok(FilteredEngineData {
+            data,
+            selection_vector: vec![true],
+        })
+    }
+
     /// This function determines the minimum timestamp before which deleted files
     /// are eligible for permanent removal during VACUUM operations. It is used
     /// during checkpointing to decide whether to include `remove` actions.
@@ -443,6 +876,32 @@ fn deleted_file_retention_timestamp_with_time(
     Ok(now_ms - retention_ms)
 }
 
+/// Returns an error if `commit_timestamp` is older than the table's log retention
+/// (`delta.logRetentionDuration`). This is factored out to allow testing with an injectable time and duration parameter.
+///
+/// # Parameters
+/// - `commit_timestamp`: The timestamp (in milliseconds since epoch) of the version to checkpoint.
+/// - `retention_duration`: The table property `log_retention_duration`. If `None`, defaults to
+///   30 days.
+/// - `now_ms`: The current time in milliseconds since epoch.
+fn ensure_within_log_retention(
+    commit_timestamp: i64,
+    retention_duration: Option<Duration>,
+    now_ms: i64,
+) -> DeltaResult<()> {
+    let retention_duration =
+        retention_duration.unwrap_or_else(|| Duration::from_secs(DEFAULT_LOG_RETENTION_SECS));
+    let retention_ms = i64::try_from(retention_duration.as_millis())
+        .map_err(|_| Error::checkpoint_write("Log retention exceeds i64 millisecond range"))?;
+    if commit_timestamp < now_ms.saturating_sub(retention_ms) {
+        return Err(Error::checkpoint_write(format!(
+            "Cannot checkpoint a version committed at {commit_timestamp}, which is older than the \
+            table's log retention (delta.logRetentionDuration = {retention_duration:?})"
+        )));
+    }
+    Ok(())
+}
+
 /// Creates the data for the _last_checkpoint file containing checkpoint
 /// metadata with the `create_one` method. Factored out to facilitate testing.
 ///
@@ -464,7 +923,9 @@ fn deleted_file_retention_timestamp_with_time(
 /// TODO(#838): Add `checksum` field to `_last_checkpoint` file
 /// TODO(#839): Add `checkpoint_schema` field to `_last_checkpoint` file
 /// TODO(#1054): Add `tags` field to `_last_checkpoint` file
-/// TODO(#1052): Add `v2Checkpoint` field to `_last_checkpoint` file
+///
+/// The `v2Checkpoint` field is deliberately never written: it describes UUID-named V2 checkpoints,
+/// which kernel does not write, and omitting it keeps the file readable by classic readers.
 pub(crate) fn create_last_checkpoint_data(
     engine: &dyn Engine,
     version: i64,
@@ -0,0 +1,169 @@
+//! Splitting the file actions of a V2 spec checkpoint into sidecar files, see
+//! [`CheckpointWriter::with_sidecars`].
+//!
+//! [`CheckpointWriter::with_sidecars`]: super::CheckpointWriter::with_sidecars
+use std::sync::LazyLock;
+
+use url::Url;
+use uuid::Uuid;
+
+use super::log_replay::CheckpointBatch;
+use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// A sidecar file to write, as returned by [`SidecarDataIterator`].
+pub struct SidecarData {
+    /// The location to write the sidecar file to, in the `_delta_log/_sidecars/` directory.
+    pub path: Url,
+    /// The file actions to write to the sidecar file. Only the selected rows of each batch must be
+    /// written.
+    pub data: Vec<FilteredEngineData>,
+}
+
+/// An iterator over the sidecar files of a V2 spec checkpoint, created by
+/// [`CheckpointWriter::sidecar_data`].
+///
+/// Each [`SidecarData`] must be written to its path as a parquet file. Once the iterator is
+/// exhausted, pass it to [`CheckpointWriter::checkpoint_data_with_sidecars`] along with the
+/// metadata of the written files, to get the data of the top-level checkpoint file.
+///
+/// Batches which only contain file actions (`add` and `remove`) are grouped into sidecar files.
+/// Batches which also contain other actions are kept, whole, for the top-level checkpoint file:
+/// the protocol allows it to hold file actions too.
+///
+/// [`CheckpointWriter::sidecar_data`]: super::CheckpointWriter::sidecar_data
+/// [`CheckpointWriter::checkpoint_data_with_sidecars`]: super::CheckpointWriter::checkpoint_data_with_sidecars
+pub struct SidecarDataIterator {
+    checkpoint_batch_iterator: Box<dyn Iterator<Item = DeltaResult<CheckpointBatch>> + Send>,
+    sidecars_dir: Url,
+    actions_per_sidecar: usize,
+    /// The file actions of the sidecar file being assembled, and their number
+    pending: Vec<FilteredEngineData>,
+    pending_actions_count: usize,
+    /// The batches to write to the top-level checkpoint file
+    pub(super) checkpoint_batches: Vec<FilteredEngineData>,
+    /// The file names of the sidecar files returned so far
+    pub(super) sidecar_names: Vec<String>,
+    /// Running totals of the actions and add actions of the checkpoint, including the top-level
+    /// checkpoint batches
+    pub(super) actions_count: i64,
+    pub(super) add_actions_count: i64,
+    pub(super) exhausted: bool,
+}
+
+impl SidecarDataIterator {
+    pub(super) fn new(
+        checkpoint_batch_iterator: Box<dyn Iterator<Item = DeltaResult<CheckpointBatch>> + Send>,
+        sidecars_dir: Url,
+        actions_per_sidecar: usize,
+    ) -> Self {
+        Self {
+            checkpoint_batch_iterator,
+            sidecars_dir,
+            actions_per_sidecar,
+            pending: vec![],
+            pending_actions_count: 0,
+            checkpoint_batches: vec![],
+            sidecar_names: vec![],
+            actions_count: 0,
+            add_actions_count: 0,
+            exhausted: false,
+        }
+    }
+
+    // Returns the pending file actions as a new sidecar file
+    fn take_sidecar(&mut self) -> DeltaResult<SidecarData> {
+        let name = format!("{}.parquet", Uuid::new_v4());
+        let path = self.sidecars_dir.join(&name)?;
+        self.sidecar_names.push(name);
+        self.pending_actions_count = 0;
+        Ok(SidecarData {
+            path,
+            data: std::mem::take(&mut self.pending),
+        })
+    }
+
+    fn next_sidecar(&mut self) -> DeltaResult<Option<SidecarData>> {
+        while let Some(batch) = self.checkpoint_batch_iterator.next() {
+            let batch = batch?;
+            self.actions_count += batch.actions_count;
+            self.add_actions_count += batch.add_actions_count;
+            if has_non_file_actions(&batch.filtered_data)? {
+                self.checkpoint_batches.push(batch.filtered_data);
+                continue;
+            }
+            self.pending.push(batch.filtered_data);
+            self.pending_actions_count += usize::try_from(batch.actions_count)
+                .map_err(|_| Error::internal_error("negative checkpoint action count"))?;
+            if self.pending_actions_count >= self.actions_per_sidecar {
+                return self.take_sidecar().map(Some);
+            }
+        }
+        self.exhausted = true;
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+        self.take_sidecar().map(Some)
+    }
+}
+
+impl Iterator for SidecarDataIterator {
+    type Item = DeltaResult<SidecarData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            return None;
+        }
+        self.next_sidecar().transpose()
+    }
+}
+
+// Whether any selected row of `data` is an action other than an `add` or `remove`
+fn has_non_file_actions(data: &FilteredEngineData) -> DeltaResult<bool> {
+    let mut visitor = NonFileActionVisitor {
+        selection_vector: &data.selection_vector,
+        found: false,
+    };
+    visitor.visit_rows_of(data.data.as_ref())?;
+    Ok(visitor.found)
+}
+
+/// Finds selected rows which are neither `add` nor `remove` actions.
+struct NonFileActionVisitor<'a> {
+    selection_vector: &'a [bool],
+    found: bool,
+}
+
+impl RowVisitor for NonFileActionVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let names = vec![column_name!("add.path"), column_name!("remove.path")];
+            (names, vec![DataType::STRING; 2]).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 2,
+            Error::InternalError(format!(
+                "Wrong number of NonFileActionVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if !self.selection_vector[i] {
+                continue;
+            }
+            let add_path: Option<&str> = getters[0].get_opt(i, "add.path")?;
+            let remove_path: Option<&str> = getters[1].get_opt(i, "remove.path")?;
+            if add_path.is_none() && remove_path.is_none() {
+                self.found = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
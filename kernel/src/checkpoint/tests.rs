@@ -2,20 +2,26 @@ use std::{sync::Arc, time::Duration};
 
 use super::DEFAULT_RETENTION_SECS;
 use crate::actions::{Add, Metadata, Protocol, Remove};
-use crate::arrow::array::{ArrayRef, StructArray};
+use crate::arrow::array::{ArrayRef, AsArray as _, StructArray};
 use crate::arrow::datatypes::{DataType, Schema};
-use crate::checkpoint::{create_last_checkpoint_data, deleted_file_retention_timestamp_with_time};
+use crate::checkpoint::{
+    create_last_checkpoint_data, deleted_file_retention_timestamp_with_time,
+    ensure_within_log_retention,
+};
+use crate::clock::FixedClock;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
 use crate::object_store::{memory::InMemory, path::Path, ObjectStore};
+use crate::table_properties::CheckpointPolicy;
 use crate::utils::test_utils::Action;
-use crate::{DeltaResult, FileMeta, Snapshot};
+use crate::{DeltaResult, Error, FileMeta, Snapshot};
 
 use arrow_55::{
     array::{create_array, RecordBatch},
     datatypes::Field,
 };
 
+use itertools::Itertools as _;
 use serde_json::{from_slice, json, Value};
 use test_utils::delta_path_for_version;
 use url::Url;
@@ -53,6 +59,128 @@ fn test_deleted_file_retention_timestamp() -> DeltaResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_ensure_within_log_retention() -> DeltaResult<()> {
+    let now_millis = 10_000_000;
+
+    // ( commit timestamp, retention duration, expected ok )
+    let test_cases = [
+        // None = Default retention (30 days)
+        (0, None, true),
+        (now_millis, Some(Duration::from_secs(0)), true),
+        (now_millis - 1, Some(Duration::from_secs(0)), false),
+        (
+            now_millis - 2_000_000,
+            Some(Duration::from_secs(2_000)),
+            true,
+        ),
+        (
+            now_millis - 2_000_001,
+            Some(Duration::from_secs(2_000)),
+            false,
+        ),
+    ];
+
+    for (commit_timestamp, retention, expected_ok) in test_cases {
+        let result = ensure_within_log_retention(commit_timestamp, retention, now_millis);
+        assert_eq!(
+            result.is_ok(),
+            expected_ok,
+            "{commit_timestamp} {retention:?}"
+        );
+    }
+
+    Ok(())
+}
+
+/// Tests that checkpointing a version older than the log retention is refused, unless it is the
+/// latest version
+#[test]
+fn test_checkpoint_outside_log_retention() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    let mut metadata = create_metadata_action();
+    if let Action::Metadata(metadata) = &mut metadata {
+        metadata.configuration.insert(
+            "delta.logRetentionDuration".to_string(),
+            "interval 0 seconds".to_string(),
+        );
+    }
+    write_commit_to_store(&store, vec![metadata, create_basic_protocol_action()], 0)?;
+    write_commit_to_store(&store, vec![create_add_action("fake_path_1")], 1)?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, Some(0))?);
+    // Check as of just after version 0 was committed, which the zero retention already excludes
+    let commit_time = snapshot.log_segment().ascending_commit_files[0]
+        .location
+        .last_modified;
+    let writer = snapshot
+        .checkpoint()?
+        .with_clock(Arc::new(FixedClock::new(commit_time + 1)));
+    assert!(matches!(
+        writer.checkpoint_data(&engine),
+        Err(Error::CheckpointWrite(msg)) if msg.contains("log retention")
+    ));
+
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    let writer = snapshot.checkpoint()?;
+    assert!(writer.checkpoint_data(&engine).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_protected_version() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    let mut metadata = create_metadata_action();
+    if let Action::Metadata(metadata) = &mut metadata {
+        metadata.configuration.insert(
+            "delta.requireCheckpointProtectionBeforeVersion".to_string(),
+            "1".to_string(),
+        );
+    }
+    let protocol = Action::Protocol(
+        Protocol::try_new(
+            3,
+            7,
+            Some(Vec::<String>::new()),
+            Some(["checkpointProtection"]),
+        )
+        .unwrap(),
+    );
+    write_commit_to_store(&store, vec![metadata, protocol], 0)?;
+    write_commit_to_store(&store, vec![create_add_action("fake_path_1")], 1)?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, Some(0))?);
+    assert!(
+        !snapshot
+            .table_configuration()
+            .capabilities()
+            .checkpoint_write
+    );
+    assert!(matches!(
+        snapshot.checkpoint(),
+        Err(Error::CheckpointWrite(msg)) if msg.contains("checkpointProtection")
+    ));
+
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    assert!(
+        snapshot
+            .table_configuration()
+            .capabilities()
+            .checkpoint_write
+    );
+    let writer = snapshot.checkpoint()?;
+    assert!(writer.checkpoint_data(&engine).is_ok());
+
+    Ok(())
+}
+
 #[test]
 fn test_create_checkpoint_metadata_batch() -> DeltaResult<()> {
     let (store, _) = new_in_memory_store();
@@ -211,6 +339,17 @@ fn create_metadata_action() -> Action {
     })
 }
 
+/// Create a Metadata action with the given `delta.checkpointPolicy`
+fn create_metadata_action_with_policy(policy: &str) -> Action {
+    let Action::Metadata(metadata) = create_metadata_action() else {
+        unreachable!()
+    };
+    Action::Metadata(Metadata {
+        configuration: [("delta.checkpointPolicy".to_string(), policy.to_string())].into(),
+        ..metadata
+    })
+}
+
 /// Create an Add action with the specified path
 fn create_add_action(path: &str) -> Action {
     Action::Add(Add {
@@ -505,3 +644,349 @@ fn test_v2_checkpoint_supported_table() -> DeltaResult<()> {
 
     Ok(())
 }
+
+/// Tests that a table which supports v2Checkpoint but sets `delta.checkpointPolicy` to `classic`
+/// gets a V1 spec checkpoint (no CheckpointMetadata action) and a classic `_last_checkpoint`.
+#[test]
+fn test_v2_checkpoint_table_with_classic_policy() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    write_commit_to_store(
+        &store,
+        vec![
+            create_metadata_action_with_policy("classic"),
+            create_v2_checkpoint_protocol_action(),
+            create_add_action("fake_path_1"),
+        ],
+        0,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    let writer = snapshot.checkpoint()?;
+    assert_eq!(writer.policy(), CheckpointPolicy::Classic);
+    assert_eq!(
+        writer.checkpoint_path()?,
+        Url::parse("memory:///_delta_log/00000000000000000000.checkpoint.parquet")?
+    );
+
+    let mut data_iter = writer.checkpoint_data(&engine)?;
+    // The only batch holds the metadata, protocol and add actions; no CheckpointMetadata follows.
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true, true, true]);
+    assert!(data_iter.next().is_none());
+
+    let metadata = FileMeta {
+        location: Url::parse("memory:///fake_path_2")?,
+        last_modified: 0,
+        size: 10,
+    };
+    writer.finalize(&engine, &metadata, data_iter)?;
+    // size: 1 metadata + 1 protocol + 1 add action
+    assert_last_checkpoint_contents(&store, 0, 3, 1, 10)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_with_policy() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    // Version 0 does not support v2Checkpoint, version 1 does, with the `v2` policy, and version 2
+    // drops the policy.
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), create_metadata_action()],
+        0,
+    )?;
+    write_commit_to_store(
+        &store,
+        vec![
+            create_v2_checkpoint_protocol_action(),
+            create_metadata_action_with_policy("v2"),
+        ],
+        1,
+    )?;
+    write_commit_to_store(&store, vec![create_metadata_action()], 2)?;
+    let table_root = Url::parse("memory:///")?;
+
+    // A V2 checkpoint needs the v2Checkpoint feature, but a classic one is always allowed.
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, Some(0))?);
+    let writer = snapshot.checkpoint()?;
+    assert_eq!(writer.policy(), CheckpointPolicy::Classic);
+    let writer = writer.with_policy(CheckpointPolicy::Classic)?;
+    assert_eq!(writer.policy(), CheckpointPolicy::Classic);
+    let err = writer
+        .with_policy(CheckpointPolicy::V2)
+        .err()
+        .expect("V2 checkpoint should be rejected");
+    assert!(matches!(err, Error::CheckpointWrite(_)), "{err}");
+
+    // The table's `v2` policy can't be overridden
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, Some(1))?);
+    let writer = snapshot.checkpoint()?;
+    assert_eq!(writer.policy(), CheckpointPolicy::V2);
+    let writer = writer.with_policy(CheckpointPolicy::V2)?;
+    let err = writer
+        .with_policy(CheckpointPolicy::Classic)
+        .err()
+        .expect("classic checkpoint should conflict with the table's policy");
+    assert!(matches!(err, Error::CheckpointWrite(_)), "{err}");
+
+    // Without a policy, a table that supports v2Checkpoint writes V2 checkpoints by default. A
+    // classic checkpoint drops the CheckpointMetadata action, leaving only the latest metadata
+    // (from version 2) and protocol (from version 1).
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    let writer = snapshot.checkpoint()?;
+    assert_eq!(writer.policy(), CheckpointPolicy::V2);
+    let writer = writer.with_policy(CheckpointPolicy::Classic)?;
+    let data_iter = writer.checkpoint_data(&engine)?;
+    let selected: Vec<_> = data_iter
+        .map(|batch| Ok(batch?.selection_vector))
+        .collect::<DeltaResult<_>>()?;
+    assert_eq!(selected, vec![vec![true], vec![true, false]]);
+
+    Ok(())
+}
+
+#[test]
+fn test_v2_checkpoint_with_sidecars() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    write_commit_to_store(
+        &store,
+        vec![
+            create_metadata_action(),
+            create_v2_checkpoint_protocol_action(),
+        ],
+        0,
+    )?;
+    write_commit_to_store(
+        &store,
+        vec![create_add_action("file1"), create_add_action("file2")],
+        1,
+    )?;
+    write_commit_to_store(&store, vec![create_add_action("file3")], 2)?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+
+    // Each commit's file actions are a batch, and batches are never split, so a target of 2
+    // actions groups both batches into one sidecar.
+    let writer = snapshot.clone().checkpoint()?.with_sidecars(2)?;
+    let sidecars: Vec<_> = writer.sidecar_data(&engine)?.try_collect()?;
+    assert_eq!(sidecars.len(), 1);
+    assert_eq!(sidecars[0].data.len(), 2);
+
+    // A target of 1 action writes a sidecar per batch.
+    let writer = snapshot.checkpoint()?.with_sidecars(1)?;
+    let mut sidecar_data = writer.sidecar_data(&engine)?;
+    let mut sidecar_files = vec![];
+    for sidecar in sidecar_data.by_ref() {
+        let sidecar = sidecar?;
+        assert!(sidecar
+            .path
+            .as_str()
+            .starts_with("memory:///_delta_log/_sidecars/"));
+        assert!(sidecar.path.as_str().ends_with(".parquet"));
+        assert_eq!(sidecar.data.len(), 1);
+        sidecar_files.push(FileMeta {
+            location: sidecar.path,
+            last_modified: 0,
+            size: 100,
+        });
+    }
+    assert_eq!(sidecar_files.len(), 2);
+
+    let mut data_iter =
+        writer.checkpoint_data_with_sidecars(&engine, sidecar_data, &sidecar_files)?;
+    // The metadata and protocol actions stay in the top-level checkpoint file, followed by a
+    // sidecar action per sidecar file and the CheckpointMetadata action.
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true, true]);
+    for sidecar_file in &sidecar_files {
+        let batch = data_iter.next().unwrap()?;
+        let batch = ArrowEngineData::try_from_engine_data(batch.data)?;
+        let sidecar = batch.record_batch().column(0).as_struct();
+        let path = sidecar.column_by_name("path").unwrap().as_string::<i32>();
+        let name = sidecar_file
+            .location
+            .path_segments()
+            .unwrap()
+            .next_back()
+            .unwrap();
+        assert_eq!(path.value(0), name);
+    }
+    let batch = data_iter.next().unwrap()?;
+    assert_eq!(batch.selection_vector, [true]);
+    assert!(data_iter.next().is_none());
+
+    let metadata = FileMeta {
+        location: writer.checkpoint_path()?,
+        last_modified: 0,
+        size: 10,
+    };
+    writer.finalize(&engine, &metadata, data_iter)?;
+    // size: 1 metadata + 1 protocol + 3 add actions + 2 sidecars + 1 checkpointMetadata
+    assert_last_checkpoint_contents(&store, 2, 8, 3, 10)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sidecar_errors() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), create_metadata_action()],
+        0,
+    )?;
+    write_commit_to_store(
+        &store,
+        vec![
+            create_v2_checkpoint_protocol_action(),
+            create_metadata_action(),
+            create_add_action("file1"),
+        ],
+        1,
+    )?;
+    let table_root = Url::parse("memory:///")?;
+    let assert_checkpoint_write_err = |result: DeltaResult<_>| match result {
+        Err(Error::CheckpointWrite(_)) => {}
+        Err(err) => panic!("unexpected error: {err}"),
+        Ok(_) => panic!("expected an error"),
+    };
+
+    // Sidecars need a V2 checkpoint
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, Some(0))?);
+    assert_checkpoint_write_err(snapshot.checkpoint()?.with_sidecars(10).map(|_| ()));
+
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    assert_checkpoint_write_err(snapshot.clone().checkpoint()?.with_sidecars(0).map(|_| ()));
+    assert_checkpoint_write_err(
+        snapshot
+            .clone()
+            .checkpoint()?
+            .sidecar_data(&engine)
+            .map(|_| ()),
+    );
+    let writer = snapshot.clone().checkpoint()?.with_sidecars(10)?;
+    assert_checkpoint_write_err(writer.checkpoint_data(&engine).map(|_| ()));
+    assert_checkpoint_write_err(writer.with_policy(CheckpointPolicy::Classic).map(|_| ()));
+
+    let writer = snapshot.checkpoint()?.with_sidecars(10)?;
+    // The sidecar iterator must be exhausted...
+    let sidecar_data = writer.sidecar_data(&engine)?;
+    assert_checkpoint_write_err(
+        writer
+            .checkpoint_data_with_sidecars(&engine, sidecar_data, &[])
+            .map(|_| ()),
+    );
+    // ...and every sidecar file written. The add action shares its batch with the protocol and
+    // metadata, so it stays in the top-level checkpoint file and no sidecar is needed.
+    let mut sidecar_data = writer.sidecar_data(&engine)?;
+    assert!(sidecar_data.next().is_none());
+    writer.checkpoint_data_with_sidecars(&engine, sidecar_data, &[])?;
+
+    Ok(())
+}
+
+/// Counts the files of the latest snapshot of the table, and returns them with the version of
+/// the checkpoint the snapshot was read from
+fn count_files_and_checkpoint_version(
+    engine: &DefaultEngine<TokioBackgroundExecutor>,
+) -> DeltaResult<(usize, Option<u64>)> {
+    let snapshot = Snapshot::try_new(Url::parse("memory:///")?, engine, None)?;
+    let checkpoint_version = snapshot
+        .log_segment()
+        .checkpoint_parts
+        .first()
+        .map(|part| part.version);
+    let scan = snapshot.into_scan_builder().build()?;
+    let files = scan.scan_metadata(engine)?.try_fold(0, |files, res| {
+        let selection_vector = res?.scan_files.selection_vector;
+        Ok::<_, Error>(files + selection_vector.into_iter().filter(|s| *s).count())
+    })?;
+    Ok((files, checkpoint_version))
+}
+
+#[test]
+fn test_write_checkpoint() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    write_commit_to_store(
+        &store,
+        vec![create_metadata_action(), create_basic_protocol_action()],
+        0,
+    )?;
+    write_commit_to_store(&store, vec![create_add_action("file1")], 1)?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None)?);
+    snapshot.checkpoint()?.write(&engine)?;
+    assert_eq!(count_files_and_checkpoint_version(&engine)?, (1, Some(1)));
+
+    // The next checkpoint is written from both the first checkpoint and a commit, whose batches
+    // differ in nullability.
+    write_commit_to_store(
+        &store,
+        vec![create_add_action("file2"), create_remove_action("file1")],
+        2,
+    )?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    snapshot.checkpoint()?.write(&engine)?;
+    assert_eq!(count_files_and_checkpoint_version(&engine)?, (1, Some(2)));
+    // size: 1 metadata + 1 protocol + 1 add action + 1 remove action
+    let last_checkpoint = read_last_checkpoint_file(&store)?;
+    assert_eq!(last_checkpoint["version"], 2);
+    assert_eq!(last_checkpoint["size"], 4);
+    assert_eq!(last_checkpoint["numOfAddFiles"], 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_write_v2_checkpoint_with_sidecars() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    write_commit_to_store(
+        &store,
+        vec![
+            create_metadata_action(),
+            create_v2_checkpoint_protocol_action(),
+        ],
+        0,
+    )?;
+    write_commit_to_store(
+        &store,
+        vec![create_add_action("file1"), create_add_action("file2")],
+        1,
+    )?;
+    write_commit_to_store(&store, vec![create_add_action("file3")], 2)?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+    snapshot.checkpoint()?.with_sidecars(1)?.write(&engine)?;
+
+    use futures::TryStreamExt as _;
+    let rt = tokio::runtime::Runtime::new().expect("create tokio runtime");
+    let sidecars: Vec<_> = rt.block_on(async {
+        store
+            .list(Some(&Path::from("_delta_log/_sidecars")))
+            .try_collect()
+            .await
+    })?;
+    assert_eq!(sidecars.len(), 2);
+    assert_eq!(count_files_and_checkpoint_version(&engine)?, (3, Some(2)));
+    // size: 1 metadata + 1 protocol + 3 add actions + 2 sidecars + 1 checkpointMetadata
+    assert_eq!(read_last_checkpoint_file(&store)?["size"], 8);
+
+    Ok(())
+}
@@ -0,0 +1,365 @@
+//! Conversions between kernel scan metadata and staged writes, and the `Add` and `Remove` action
+//! structs used by [delta-rs]. This eases migrating code that works with delta-rs file lists to the
+//! kernel one piece at a time.
+//!
+//! - [`adds_from_scan_metadata`] turns the files of a [`ScanMetadata`] into [`Add`]s.
+//! - [`Add::to_remove`] builds the [`Remove`] that deletes a file.
+//! - [`stage_adds`] stages [`Add`]s for new data files in a [`Transaction`].
+//!
+//! [delta-rs]: https://github.com/delta-io/delta-rs
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::visitors::visit_deletion_vector_at;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{ColumnName, MapData, Scalar};
+use crate::scan::log_replay::SCAN_ROW_SCHEMA;
+use crate::scan::{Scan, ScanMetadata};
+use crate::schema::{ColumnNamesAndTypes, DataType, MapType};
+use crate::transaction::{Transaction, ADD_FILES_SCHEMA};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension as _};
+
+/// A file in the table, shaped like the delta-rs `Add` action.
+///
+/// Unlike the kernel, delta-rs keeps null partition values in `partition_values` as `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Add {
+    /// A relative path to a data file from the root of the table or an absolute URI.
+    pub path: String,
+    /// The partition values of this file, keyed by the physical partition column name.
+    pub partition_values: HashMap<String, Option<String>>,
+    /// The size of this data file in bytes.
+    pub size: i64,
+    /// The time this file was created, as milliseconds since the epoch.
+    pub modification_time: i64,
+    /// Whether this file contains new data (as opposed to e.g. rewritten data of a compaction).
+    pub data_change: bool,
+    /// Statistics about the data in this file, encoded as a JSON string.
+    pub stats: Option<String>,
+    /// Metadata about this file.
+    pub tags: Option<HashMap<String, Option<String>>>,
+    /// The deletion vector of this file, if any.
+    pub deletion_vector: Option<DeletionVectorDescriptor>,
+    /// Default generated Row ID of the first row in the file.
+    pub base_row_id: Option<i64>,
+    /// First commit version in which an add action with the same path was committed.
+    pub default_row_commit_version: Option<i64>,
+    /// The name of the clustering implementation.
+    pub clustering_provider: Option<String>,
+}
+
+/// The removal of a file from the table, shaped like the delta-rs `Remove` action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remove {
+    /// A relative path to a data file from the root of the table or an absolute URI.
+    pub path: String,
+    /// Whether this removal deletes data (as opposed to e.g. a compaction rewriting it).
+    pub data_change: bool,
+    /// The time the file was removed, as milliseconds since the epoch.
+    pub deletion_timestamp: Option<i64>,
+    /// When true the fields `partition_values`, `size`, and `tags` are present.
+    pub extended_file_metadata: Option<bool>,
+    /// The partition values of the removed file.
+    pub partition_values: Option<HashMap<String, Option<String>>>,
+    /// The size of the removed file in bytes.
+    pub size: Option<i64>,
+    /// Metadata about the removed file.
+    pub tags: Option<HashMap<String, Option<String>>>,
+    /// The deletion vector of the removed file, if any.
+    pub deletion_vector: Option<DeletionVectorDescriptor>,
+    /// Default generated Row ID of the first row in the removed file.
+    pub base_row_id: Option<i64>,
+    /// First commit version in which an add action with the same path was committed.
+    pub default_row_commit_version: Option<i64>,
+}
+
+impl Add {
+    /// The [`Remove`] that deletes this file at `deletion_timestamp` (milliseconds since the
+    /// epoch). The file's metadata is carried over, as delta-rs does.
+    pub fn to_remove(&self, deletion_timestamp: i64) -> Remove {
+        Remove {
+            path: self.path.clone(),
+            data_change: true,
+            deletion_timestamp: Some(deletion_timestamp),
+            extended_file_metadata: Some(true),
+            partition_values: Some(self.partition_values.clone()),
+            size: Some(self.size),
+            tags: self.tags.clone(),
+            deletion_vector: self.deletion_vector.clone(),
+            base_row_id: self.base_row_id,
+            default_row_commit_version: self.default_row_commit_version,
+        }
+    }
+}
+
+/// Convert the selected files of `scan_metadata`, produced by `scan`, into [`Add`]s.
+///
+/// Scan metadata only carries part of each file's add action. The files already exist in the
+/// table, so `data_change` is `false`. `clustering_provider` is always `None`.
+pub fn adds_from_scan_metadata(scan: &Scan, scan_metadata: &ScanMetadata) -> DeltaResult<Vec<Add>> {
+    let schema = scan.snapshot().schema();
+    let partition_columns = scan
+        .snapshot()
+        .metadata()
+        .partition_columns()
+        .iter()
+        .map(|name| {
+            let field = schema
+                .field(name)
+                .ok_or_else(|| Error::missing_column(name))?;
+            Ok(field.physical_name().to_string())
+        })
+        .collect::<DeltaResult<_>>()?;
+    let scan_files = &scan_metadata.scan_files;
+    let mut visitor = AddVisitor {
+        partition_columns,
+        selection_vector: &scan_files.selection_vector,
+        adds: vec![],
+    };
+    visitor.visit_rows_of(scan_files.data.as_ref())?;
+    Ok(visitor.adds)
+}
+
+/// Stage the data files described by `adds` in `txn`, as with [`Transaction::add_files`].
+///
+/// Only the fields of [`add_files_schema`] are staged, so the `tags` and the row tracking fields of
+/// each add are ignored. New files cannot have a deletion vector.
+///
+/// [`add_files_schema`]: crate::transaction::add_files_schema
+pub fn stage_adds(
+    txn: &mut Transaction,
+    engine: &dyn Engine,
+    adds: impl IntoIterator<Item = Add>,
+) -> DeltaResult<()> {
+    for add in adds {
+        txn.add_files(write_metadata(engine, &add)?);
+    }
+    Ok(())
+}
+
+// Create a single-row batch of `add_files_schema` write metadata for `add`
+fn write_metadata(engine: &dyn Engine, add: &Add) -> DeltaResult<Box<dyn EngineData>> {
+    if add.deletion_vector.is_some() {
+        return Err(Error::unsupported(format!(
+            "Cannot stage new file {} with a deletion vector",
+            add.path
+        )));
+    }
+    let partition_values = add.partition_values.iter().map(|(column, value)| {
+        let value = value
+            .clone()
+            .map_or(Scalar::Null(DataType::STRING), Scalar::from);
+        (column.clone(), value)
+    });
+    let map_type = MapType::new(DataType::STRING, DataType::STRING, true);
+    let values = [
+        add.path.clone().into(),
+        Scalar::Map(MapData::try_new(map_type, partition_values)?),
+        add.size.into(),
+        add.modification_time.into(),
+        add.data_change.into(),
+        add.stats
+            .clone()
+            .map_or(Scalar::Null(DataType::STRING), Scalar::from),
+    ];
+    engine
+        .evaluation_handler()
+        .create_one(ADD_FILES_SCHEMA.clone(), &values)
+}
+
+/// Collects an [`Add`] from each selected scan row (see [`SCAN_ROW_SCHEMA`]).
+pub(crate) struct AddVisitor<'a> {
+    // physical names of the partition columns, whose null values are kept as `None`
+    pub(crate) partition_columns: Vec<String>,
+    pub(crate) selection_vector: &'a [bool],
+    pub(crate) adds: Vec<Add>,
+}
+
+impl RowVisitor for AddVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| SCAN_ROW_SCHEMA.leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 13,
+            Error::InternalError(format!(
+                "Wrong number of AddVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for row_index in 0..row_count {
+            if !self.selection_vector[row_index] {
+                continue;
+            }
+            let Some(path) = getters[0].get_opt(row_index, "scanFile.path")? else {
+                continue;
+            };
+            let partition_values: HashMap<String, String> =
+                getters[9].get(row_index, "scanFile.fileConstantValues.partitionValues")?;
+            // the kernel drops null partition values, but delta-rs keeps them
+            let mut partition_values: HashMap<_, _> = partition_values
+                .into_iter()
+                .map(|(column, value)| (column, Some(value)))
+                .collect();
+            for column in &self.partition_columns {
+                partition_values.entry(column.clone()).or_insert(None);
+            }
+            self.adds.push(Add {
+                path,
+                partition_values,
+                size: getters[1].get(row_index, "scanFile.size")?,
+                modification_time: getters[2].get(row_index, "scanFile.modificationTime")?,
+                data_change: false,
+                stats: getters[3].get_opt(row_index, "scanFile.stats")?,
+                tags: getters[12]
+                    .get_opt(row_index, "scanFile.fileConstantValues.tags")?
+                    .map(|tags: HashMap<String, String>| {
+                        tags.into_iter()
+                            .map(|(key, value)| (key, Some(value)))
+                            .collect()
+                    }),
+                deletion_vector: visit_deletion_vector_at(row_index, &getters[4..])?,
+                base_row_id: getters[10]
+                    .get_opt(row_index, "scanFile.fileConstantValues.baseRowId")?,
+                default_row_commit_version: getters[11].get_opt(
+                    row_index,
+                    "scanFile.fileConstantValues.defaultRowCommitVersion",
+                )?,
+                clustering_provider: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::arrow::array::{Array as _, AsArray as _, MapArray};
+    use crate::arrow::datatypes::Int64Type;
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::sync::SyncEngine;
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn snapshot(table: &str) -> (Arc<Snapshot>, SyncEngine) {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data").join(table)).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+        (snapshot, engine)
+    }
+
+    fn scan_adds(snapshot: Arc<Snapshot>, engine: &SyncEngine) -> Vec<Add> {
+        let scan = snapshot.scan_builder().build().unwrap();
+        let mut adds = vec![];
+        for scan_metadata in scan.scan_metadata(engine).unwrap() {
+            adds.extend(adds_from_scan_metadata(&scan, &scan_metadata.unwrap()).unwrap());
+        }
+        adds.sort_by(|a, b| a.path.cmp(&b.path));
+        adds
+    }
+
+    #[test]
+    fn test_adds_from_scan_metadata() {
+        let (snapshot, engine) = snapshot("basic_partitioned");
+        let adds = scan_adds(snapshot, &engine);
+        assert_eq!(adds.len(), 6);
+
+        let null_partition = &adds[0];
+        assert!(null_partition
+            .path
+            .starts_with("letter=__HIVE_DEFAULT_PARTITION__/"));
+        assert_eq!(
+            null_partition.partition_values,
+            HashMap::from([("letter".to_string(), None)])
+        );
+        let add = &adds[1];
+        assert!(add.path.starts_with("letter=a/"));
+        assert_eq!(
+            add.partition_values,
+            HashMap::from([("letter".to_string(), Some("a".to_string()))])
+        );
+        assert!(add.size > 0);
+        assert!(add.modification_time > 0);
+        assert!(!add.data_change);
+        assert!(add.stats.as_ref().unwrap().contains("numRecords"));
+        assert_eq!(add.deletion_vector, None);
+
+        let remove = add.to_remove(1234);
+        assert_eq!(remove.path, add.path);
+        assert_eq!(remove.deletion_timestamp, Some(1234));
+        assert_eq!(remove.partition_values, Some(add.partition_values.clone()));
+        assert_eq!(remove.size, Some(add.size));
+    }
+
+    #[test]
+    fn test_adds_from_scan_metadata_with_dv() {
+        let (snapshot, engine) = snapshot("table-with-dv-small");
+        let adds = scan_adds(snapshot, &engine);
+        assert_eq!(adds.len(), 1);
+        let dv = adds[0].deletion_vector.as_ref().unwrap();
+        assert_eq!(dv.storage_type, "u");
+        assert_eq!(dv.cardinality, 2);
+    }
+
+    #[test]
+    fn test_write_metadata() {
+        let engine = SyncEngine::new();
+        let add = Add {
+            path: "part=__HIVE_DEFAULT_PARTITION__/a.parquet".to_string(),
+            partition_values: HashMap::from([("part".to_string(), None)]),
+            size: 10,
+            modification_time: 20,
+            data_change: true,
+            stats: None,
+            tags: None,
+            deletion_vector: None,
+            base_row_id: None,
+            default_row_commit_version: None,
+            clustering_provider: None,
+        };
+        let data = write_metadata(&engine, &add).unwrap();
+        let batch = data
+            .into_any()
+            .downcast::<ArrowEngineData>()
+            .unwrap()
+            .record_batch()
+            .clone();
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(
+            batch.column(0).as_string::<i32>().value(0),
+            "part=__HIVE_DEFAULT_PARTITION__/a.parquet"
+        );
+        let partition_values = batch.column(1).as_any().downcast_ref::<MapArray>().unwrap();
+        let values = partition_values.values();
+        assert!(values.as_string::<i32>().is_null(0));
+        assert_eq!(batch.column(2).as_primitive::<Int64Type>().value(0), 10);
+        assert_eq!(batch.column(3).as_primitive::<Int64Type>().value(0), 20);
+        assert!(batch.column(4).as_boolean().value(0));
+
+        let with_dv = Add {
+            deletion_vector: Some(DeletionVectorDescriptor {
+                storage_type: "i".to_string(),
+                path_or_inline_dv: "wi5b=000010000siXQKl0rr91000f55c8Xg0@@D72lkbi5=-{L".to_string(),
+                offset: None,
+                size_in_bytes: 44,
+                cardinality: 6,
+            }),
+            ..add
+        };
+        assert!(matches!(
+            write_metadata(&engine, &with_dv),
+            Err(Error::Unsupported(_))
+        ));
+    }
+}
@@ -161,6 +161,9 @@ impl TryFromKernel<&DataType> for ArrowDataType {
                 Arc::new(m.as_ref().try_into_arrow()?),
                 false,
             )),
+            DataType::Unsupported(name) => Err(ArrowError::SchemaError(format!(
+                "Unsupported data type '{name}' has no arrow equivalent"
+            ))),
         }
     }
 }
@@ -5,9 +5,12 @@ use crate::{DeltaResult, Error};
 use crate::arrow::array::cast::AsArray;
 use crate::arrow::array::types::{Int32Type, Int64Type};
 use crate::arrow::array::{
-    Array, ArrayRef, GenericListArray, MapArray, OffsetSizeTrait, RecordBatch, StructArray,
+    Array, ArrayRef, BooleanArray, GenericListArray, MapArray, OffsetSizeTrait, RecordBatch,
+    StructArray,
 };
+use crate::arrow::compute::filter_record_batch;
 use crate::arrow::datatypes::{DataType as ArrowDataType, FieldRef};
+use crate::utils::require;
 use tracing::debug;
 
 use std::collections::{HashMap, HashSet};
@@ -152,6 +155,26 @@ impl EngineData for ArrowEngineData {
         self.data.num_rows()
     }
 
+    fn approximate_memory_usage(&self) -> usize {
+        self.data.get_array_memory_size()
+    }
+
+    fn apply_selection_vector(
+        self: Box<Self>,
+        selection_vector: Vec<bool>,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        require!(
+            selection_vector.len() == self.data.num_rows(),
+            Error::generic(format!(
+                "Selection vector of length {} does not match data of {} rows",
+                selection_vector.len(),
+                self.data.num_rows()
+            ))
+        );
+        let data = filter_record_batch(&self.data, &BooleanArray::from(selection_vector))?;
+        Ok(Box::new(ArrowEngineData::new(data)))
+    }
+
     fn visit_rows(
         &self,
         leaf_columns: &[ColumnName],
@@ -252,7 +275,16 @@ impl ArrowEngineData {
             }
             &DataType::STRING => {
                 debug!("Pushing string array for {}", ColumnName::new(path));
-                col.as_string_opt().map(|a| a as _).ok_or("string")
+                let col_as_string = || -> Option<&'a dyn GetData<'a>> {
+                    if let Some(array) = col.as_string_opt::<i32>() {
+                        Some(array)
+                    } else if let Some(array) = col.as_string_opt::<i64>() {
+                        Some(array)
+                    } else {
+                        col.as_string_view_opt().map(|array| array as _)
+                    }
+                };
+                col_as_string().ok_or("string")
             }
             &DataType::INTEGER => {
                 debug!("Pushing int32 array for {}", ColumnName::new(path));
@@ -294,13 +326,43 @@ impl ArrowEngineData {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{Arc, LazyLock};
+
+    use super::ArrowEngineData;
     use crate::actions::{get_log_schema, Metadata, Protocol};
-    use crate::arrow::array::StringArray;
+    use crate::arrow::array::{
+        Array as _, ArrayRef, LargeStringArray, RecordBatch, StringArray, StringViewArray,
+    };
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
     use crate::engine::sync::SyncEngine;
+    use crate::engine_data::{EngineData, GetData, RowVisitor, TypedGetData as _};
+    use crate::expressions::{column_name, ColumnName};
+    use crate::schema::{ColumnNamesAndTypes, DataType};
     use crate::table_features::{ReaderFeature, WriterFeature};
     use crate::utils::test_utils::string_array_to_engine_data;
     use crate::{DeltaResult, Engine as _};
 
+    #[test]
+    fn test_apply_selection_vector() -> DeltaResult<()> {
+        let data: Box<dyn EngineData> =
+            Box::new(ArrowEngineData::new(RecordBatch::try_from_iter([(
+                "a",
+                Arc::new(StringArray::from(vec!["x", "y", "z"])) as ArrayRef,
+            )])?));
+        let filtered = data.apply_selection_vector(vec![true, false, true])?;
+        let filtered = ArrowEngineData::try_from_engine_data(filtered)?;
+        let expected = StringArray::from(vec!["x", "z"]);
+        let column = filtered.record_batch().column(0);
+        assert_eq!(
+            column.as_any().downcast_ref::<StringArray>(),
+            Some(&expected)
+        );
+        assert!(filtered
+            .apply_selection_vector(vec![true])
+            .is_err_and(|e| e.to_string().contains("does not match")));
+        Ok(())
+    }
+
     #[test]
     fn test_md_extract() -> DeltaResult<()> {
         let engine = SyncEngine::new();
@@ -320,6 +382,78 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_large_and_view_strings() -> DeltaResult<()> {
+        struct Visitor(Vec<(String, String)>);
+        impl RowVisitor for Visitor {
+            fn selected_column_names_and_types(
+                &self,
+            ) -> (&'static [ColumnName], &'static [DataType]) {
+                static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+                    let names = [column_name!("large"), column_name!("view")];
+                    (names.to_vec(), vec![DataType::STRING; 2]).into()
+                });
+                NAMES_AND_TYPES.as_ref()
+            }
+            fn visit<'a>(
+                &mut self,
+                row_count: usize,
+                getters: &[&'a dyn GetData<'a>],
+            ) -> DeltaResult<()> {
+                for i in 0..row_count {
+                    let large: String = getters[0].get(i, "large")?;
+                    let view: String = getters[1].get(i, "view")?;
+                    self.0.push((large, view));
+                }
+                Ok(())
+            }
+        }
+
+        let schema = ArrowSchema::new(vec![
+            Field::new("large", ArrowDataType::LargeUtf8, false),
+            Field::new("view", ArrowDataType::Utf8View, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(LargeStringArray::from(vec!["a", "b"])),
+                Arc::new(StringViewArray::from(vec!["c", "d"])),
+            ],
+        )?;
+        let mut visitor = Visitor(vec![]);
+        visitor.visit_rows_of(&ArrowEngineData::new(batch))?;
+        let expected = [("a", "c"), ("b", "d")].map(|(l, v)| (l.to_string(), v.to_string()));
+        assert_eq!(visitor.0, expected);
+
+        // JSON can be parsed from large and view strings as well
+        let engine = SyncEngine::new();
+        let json = r#"{"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}"#;
+        let json_strings: [ArrayRef; 2] = [
+            Arc::new(LargeStringArray::from(vec![json])),
+            Arc::new(StringViewArray::from(vec![json])),
+        ];
+        for json_strings in json_strings {
+            let field = Field::new("json", json_strings.data_type().clone(), false);
+            let batch =
+                RecordBatch::try_new(Arc::new(ArrowSchema::new(vec![field])), vec![json_strings])?;
+            let parsed = engine.json_handler().parse_json(
+                Box::new(ArrowEngineData::new(batch)),
+                get_log_schema().project(&["protocol"])?,
+            )?;
+            let protocol = Protocol::try_new_from_data(parsed.as_ref())?.unwrap();
+            assert_eq!(protocol.min_writer_version(), 2);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_approximate_memory_usage() {
+        let small = string_array_to_engine_data(vec!["a"].into());
+        let large = string_array_to_engine_data(StringArray::from(vec!["a".repeat(1024); 1024]));
+        assert!(small.approximate_memory_usage() > 0);
+        assert!(large.approximate_memory_usage() > 1024 * 1024);
+    }
+
     #[test]
     fn test_protocol_extract() -> DeltaResult<()> {
         let engine = SyncEngine::new();
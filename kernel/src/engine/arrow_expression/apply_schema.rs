@@ -7,11 +7,12 @@ use itertools::Itertools;
 use crate::arrow::array::{
     Array, ArrayRef, AsArray, ListArray, MapArray, RecordBatch, StructArray,
 };
+use crate::arrow::compute::cast;
 use crate::arrow::datatypes::Schema as ArrowSchema;
 use crate::arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField};
 
 use super::super::arrow_utils::make_arrow_error;
-use crate::engine::ensure_data_types::ensure_data_types;
+use crate::engine::ensure_data_types::{ensure_data_types, DataTypeCompat};
 use crate::error::{DeltaResult, Error};
 use crate::schema::{ArrayType, DataType, MapType, Schema, StructField};
 
@@ -176,10 +177,10 @@ pub(crate) fn apply_schema_to(array: &ArrayRef, schema: &DataType) -> DeltaResul
         Struct(stype) => Arc::new(apply_schema_to_struct(array, stype)?),
         Array(atype) => Arc::new(apply_schema_to_list(array, atype)?),
         Map(mtype) => Arc::new(apply_schema_to_map(array, mtype)?),
-        _ => {
-            ensure_data_types(schema, array.data_type(), true)?;
-            array.clone()
-        }
+        _ => match ensure_data_types(schema, array.data_type(), true)? {
+            DataTypeCompat::NeedsCast(target) => cast(array, &target)?,
+            DataTypeCompat::Identical | DataTypeCompat::Nested => array.clone(),
+        },
     };
     Ok(array)
 }
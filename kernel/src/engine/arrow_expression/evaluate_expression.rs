@@ -4,17 +4,18 @@ use crate::arrow::array::{
     Array, ArrayRef, AsArray, BooleanArray, Datum, RecordBatch, StructArray,
 };
 use crate::arrow::compute::kernels::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq, not_distinct};
-use crate::arrow::compute::kernels::comparison::in_list_utf8;
+use crate::arrow::compute::kernels::comparison::{in_list_utf8, like, nlike, starts_with};
 use crate::arrow::compute::kernels::numeric::{add, div, mul, sub};
-use crate::arrow::compute::{and_kleene, is_not_null, is_null, not, or_kleene};
+use crate::arrow::compute::{and_kleene, cast, is_not_null, is_null, not, or_kleene};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, IntervalUnit, TimeUnit,
+    DECIMAL128_MAX_PRECISION,
 };
 use crate::arrow::error::ArrowError;
 use crate::engine::arrow_expression::opaque::{
     ArrowOpaqueExpressionOpAdaptor, ArrowOpaquePredicateOpAdaptor,
 };
-use crate::engine::arrow_utils::prim_array_cmp;
+use crate::engine::arrow_utils::{normalize_array, prim_array_cmp};
 use crate::error::{DeltaResult, Error};
 use crate::expressions::{
     BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, Expression,
@@ -77,6 +78,34 @@ fn extract_column(mut parent: &dyn ProvidesColumnByName, col: &[String]) -> Delt
     }
 }
 
+// Arrow comparison kernels require both sides to have the same type, but decimals of differing
+// precision and scale are still comparable once their scales are aligned. Casts both sides to the
+// narrowest decimal type that represents every value of either side exactly, falling back to
+// Decimal256 when that needs more than 38 digits. Any other pair of arrays passes through as-is.
+fn coerce_decimals(left: ArrayRef, right: ArrayRef) -> DeltaResult<(ArrayRef, ArrayRef)> {
+    use ArrowDataType::{Decimal128, Decimal256};
+    let (&Decimal128(p1, s1), &Decimal128(p2, s2)) = (left.data_type(), right.data_type()) else {
+        return Ok((left, right));
+    };
+    if (p1, s1) == (p2, s2) {
+        return Ok((left, right));
+    }
+    let scale = s1.max(s2);
+    let integer_digits = (p1 as i16 - s1 as i16).max(p2 as i16 - s2 as i16);
+    let precision = u8::try_from(integer_digits + scale as i16)
+        .map_err(|_| Error::generic(format!("Cannot compare decimals {p1},{s1} and {p2},{s2}")))?;
+    let common_type = match precision {
+        p if p <= DECIMAL128_MAX_PRECISION => Decimal128(p, scale),
+        p => Decimal256(p, scale),
+    };
+    Ok((cast(&left, &common_type)?, cast(&right, &common_type)?))
+}
+
+// Arrow has no negated `starts_with` kernel (unlike `nlike` for `like`)
+fn not_starts_with(left: &dyn Datum, right: &dyn Datum) -> Result<BooleanArray, ArrowError> {
+    not(&starts_with(left, right)?)
+}
+
 /// Evaluates a kernel expression over a record batch
 pub fn evaluate_expression(
     expression: &Expression,
@@ -87,7 +116,7 @@ pub fn evaluate_expression(
     use Expression::*;
     match (expression, result_type) {
         (Literal(scalar), _) => Ok(scalar.to_array(batch.num_rows())?),
-        (Column(name), _) => extract_column(batch, name),
+        (Column(name), _) => normalize_array(&extract_column(batch, name)?),
         (Struct(fields), Some(DataType::Struct(output_schema))) => {
             let columns = fields
                 .iter()
@@ -253,11 +282,16 @@ pub fn evaluate_predicate(
                 (Equal, true) => neq,
                 (Distinct, false) => distinct,
                 (Distinct, true) => not_distinct,
+                (StartsWith, false) => starts_with,
+                (StartsWith, true) => not_starts_with,
+                (Like, false) => like,
+                (Like, true) => nlike,
                 (In, _) => return Ok(maybe_inverted(Cow::Owned(eval_in()?))?),
             };
 
             let left = evaluate_expression(left, batch, None)?;
             let right = evaluate_expression(right, batch, None)?;
+            let (left, right) = coerce_decimals(left, right)?;
             Ok(eval_fn(&left, &right)?)
         }
         Junction(JunctionPredicate { op, preds }) => {
@@ -7,12 +7,15 @@ use crate::arrow::datatypes::{
 };
 
 use super::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
+use crate::arrow::compute::{sort_to_indices, take, SortOptions};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::error::{DeltaResult, Error};
-use crate::expressions::{Expression, Predicate, Scalar};
+use crate::expressions::{Aggregate, ColumnName, Expression, Predicate, Scalar};
 use crate::schema::{DataType, PrimitiveType, SchemaRef};
 use crate::utils::require;
-use crate::{EngineData, EvaluationHandler, ExpressionEvaluator, PredicateEvaluator};
+use crate::{
+    AggregateEvaluator, EngineData, EvaluationHandler, ExpressionEvaluator, PredicateEvaluator,
+};
 
 use itertools::Itertools;
 use tracing::debug;
@@ -261,6 +264,11 @@ impl Scalar {
                     builder.append(false)?;
                 }
             }
+            DataType::Unsupported(ref name) => {
+                return Err(Error::unsupported(format!(
+                    "Cannot create nulls of unsupported data type '{name}'"
+                )))
+            }
         }
         Ok(())
     }
@@ -306,6 +314,19 @@ impl EvaluationHandler for ArrowEvaluationHandler {
             RecordBatch::try_new(Arc::new(output_schema.as_ref().try_into_arrow()?), arrays)?;
         Ok(Box::new(ArrowEngineData::new(record_batch)))
     }
+
+    fn new_aggregate_evaluator(
+        &self,
+        schema: SchemaRef,
+        aggregates: Vec<Aggregate>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Arc<dyn AggregateEvaluator>> {
+        Ok(Arc::new(DefaultAggregateEvaluator {
+            input_schema: schema,
+            aggregates,
+            output_schema,
+        }))
+    }
 }
 
 #[derive(Debug)]
@@ -378,3 +399,76 @@ impl PredicateEvaluator for DefaultPredicateEvaluator {
         Ok(Box::new(ArrowEngineData::new(batch)))
     }
 }
+
+#[derive(Debug)]
+pub struct DefaultAggregateEvaluator {
+    input_schema: SchemaRef,
+    aggregates: Vec<Aggregate>,
+    output_schema: SchemaRef,
+}
+
+impl AggregateEvaluator for DefaultAggregateEvaluator {
+    fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>> {
+        debug!("Arrow evaluator evaluating: {:#?}", self.aggregates);
+        let batch = batch
+            .any_ref()
+            .downcast_ref::<ArrowEngineData>()
+            .ok_or_else(|| Error::engine_data_type("ArrowEngineData"))?
+            .record_batch();
+        let _input_schema: ArrowSchema = self.input_schema.as_ref().try_into_arrow()?;
+        let num_fields = self.output_schema.fields().count();
+        require!(
+            self.aggregates.len() == num_fields,
+            Error::generic(format!(
+                "Expected one output field per aggregate, but got {num_fields} fields for {} \
+                 aggregates",
+                self.aggregates.len()
+            ))
+        );
+        let (fields, columns): (Vec<_>, Vec<_>) = self
+            .aggregates
+            .iter()
+            .zip(self.output_schema.fields())
+            .map(|(aggregate, field)| {
+                let column = evaluate_aggregate(aggregate, batch)?;
+                let field = ArrowField::new(field.name(), column.data_type().clone(), true);
+                Ok::<_, Error>((field, column))
+            })
+            .process_results(|iter| iter.unzip())?;
+        let result = array::StructArray::try_new(fields.into(), columns, None)?;
+        let output_type = DataType::Struct(Box::new(self.output_schema.as_ref().clone()));
+        Ok(Box::new(ArrowEngineData::new(apply_schema(
+            &result,
+            &output_type,
+        )?)))
+    }
+}
+
+// Compute `aggregate` over all rows of `batch`, as a single-element array
+fn evaluate_aggregate(aggregate: &Aggregate, batch: &RecordBatch) -> DeltaResult<ArrayRef> {
+    let column =
+        |name: &ColumnName| evaluate_expression(&Expression::Column(name.clone()), batch, None);
+    // The first value when sorted with nulls last, which is only null if all values are null
+    let first_sorted = |name: &ColumnName, descending: bool| -> DeltaResult<ArrayRef> {
+        let column = column(name)?;
+        let options = SortOptions {
+            descending,
+            nulls_first: false,
+        };
+        let indices = sort_to_indices(&column, Some(options), Some(1))?;
+        if indices.is_empty() {
+            return Ok(array::new_null_array(column.data_type(), 1));
+        }
+        Ok(take(&column, &indices, None)?)
+    };
+    let count = |count: usize| -> DeltaResult<ArrayRef> {
+        let count = i64::try_from(count).map_err(|_| Error::generic("Count overflows i64"))?;
+        Ok(Arc::new(array::Int64Array::from(vec![count])))
+    };
+    match aggregate {
+        Aggregate::Min(name) => first_sorted(name, false),
+        Aggregate::Max(name) => first_sorted(name, true),
+        Aggregate::NullCount(name) => count(column(name)?.null_count()),
+        Aggregate::RowCount => count(batch.num_rows()),
+    }
+}
@@ -1,8 +1,9 @@
 use std::ops::{Add, Div, Mul, Sub};
 
 use crate::arrow::array::{
-    create_array, Array, ArrayRef, BooleanArray, GenericStringArray, Int32Array, Int32Builder,
-    ListArray, MapArray, MapBuilder, MapFieldNames, StringBuilder, StructArray,
+    create_array, Array, ArrayRef, BinaryViewArray, BooleanArray, Decimal128Array,
+    GenericStringArray, Int32Array, Int32Builder, LargeStringArray, ListArray, MapArray,
+    MapBuilder, MapFieldNames, StringBuilder, StringViewArray, StructArray,
 };
 use crate::arrow::buffer::{OffsetBuffer, ScalarBuffer};
 use crate::arrow::compute::kernels::cmp::{gt_eq, lt};
@@ -307,6 +308,72 @@ fn test_str_arrays() {
     assert_eq!(result, in_expected);
 }
 
+#[test]
+fn test_large_and_view_arrays() {
+    let item = Arc::new(Field::new("item", DataType::LargeUtf8, true));
+    let schema = Schema::new(vec![
+        Field::new("large", DataType::LargeUtf8, true),
+        Field::new("view", DataType::Utf8View, true),
+        Field::new("binary", DataType::BinaryView, true),
+        Field::new("list", DataType::List(item.clone()), true),
+    ]);
+    let list = ListArray::new(
+        item,
+        OffsetBuffer::new(ScalarBuffer::from(vec![0, 2, 3])),
+        Arc::new(LargeStringArray::from(vec!["a", "b", "c"])),
+        None,
+    );
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(LargeStringArray::from(vec!["a", "b"])),
+            Arc::new(StringViewArray::from(vec!["a", "c"])),
+            Arc::new(BinaryViewArray::from(vec![b"x".as_slice(), b"y"])),
+            Arc::new(list),
+        ],
+    )
+    .unwrap();
+
+    let pred = Pred::and_from([
+        Pred::eq(column_expr!("large"), Expr::literal("a")),
+        Pred::eq(column_expr!("view"), column_expr!("large")),
+    ]);
+    let result = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(result, BooleanArray::from(vec![true, false]));
+
+    let pred = Pred::binary(
+        BinaryPredicateOp::In,
+        Expr::literal("b"),
+        column_expr!("list"),
+    );
+    let result = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(result, BooleanArray::from(vec![true, false]));
+
+    // the output uses the types kernel works with
+    let output_schema = Arc::new(StructType::new([
+        StructField::nullable("large", KernelDataType::STRING),
+        StructField::nullable("view", KernelDataType::STRING),
+        StructField::nullable("binary", KernelDataType::BINARY),
+        StructField::nullable("list", ArrayType::new(KernelDataType::STRING, true)),
+    ]));
+    let expr = Expr::struct_from([
+        column_expr!("large"),
+        column_expr!("view"),
+        column_expr!("binary"),
+        column_expr!("list"),
+    ]);
+    let result = evaluate_expression(&expr, &batch, Some(&output_schema.clone().into())).unwrap();
+    let result = apply_schema(&result, &output_schema.into()).unwrap();
+    let item = Arc::new(Field::new("item", DataType::Utf8, true));
+    let expected = Schema::new(vec![
+        Field::new("large", DataType::Utf8, true),
+        Field::new("view", DataType::Utf8, true),
+        Field::new("binary", DataType::Binary, true),
+        Field::new("list", DataType::List(item), true),
+    ]);
+    assert_eq!(result.schema().as_ref(), &expected);
+}
+
 #[test]
 fn test_extract_column() {
     let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
@@ -456,6 +523,79 @@ fn test_binary_cmp() {
     assert_eq!(results, expected_eq);
 }
 
+#[test]
+fn test_string_match() {
+    let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+    let values = GenericStringArray::<i32>::from(vec![Some("abc"), Some("b_c"), None]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+    let column = column_expr!("a");
+
+    let predicate = column.clone().starts_with(Expr::literal("ab"));
+    let results = evaluate_predicate(&predicate, &batch, false).unwrap();
+    assert_eq!(
+        results,
+        BooleanArray::from(vec![Some(true), Some(false), None])
+    );
+    let results = evaluate_predicate(&predicate, &batch, true).unwrap();
+    assert_eq!(
+        results,
+        BooleanArray::from(vec![Some(false), Some(true), None])
+    );
+
+    let predicate = column.like(Expr::literal(r"_\_%"));
+    let results = evaluate_predicate(&predicate, &batch, false).unwrap();
+    assert_eq!(
+        results,
+        BooleanArray::from(vec![Some(false), Some(true), None])
+    );
+    let results = evaluate_predicate(&predicate, &batch, true).unwrap();
+    assert_eq!(
+        results,
+        BooleanArray::from(vec![Some(true), Some(false), None])
+    );
+}
+
+#[test]
+fn test_decimal_cmp() {
+    // decimal(5,2) column: [-999.99, 1.00, 111.11]
+    let values = Decimal128Array::from(vec![-99999, 100, 11111])
+        .with_precision_and_scale(5, 2)
+        .unwrap();
+    let schema = Schema::new(vec![Field::new("a", values.data_type().clone(), false)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+    let column = column_expr!("a");
+    let dec =
+        |bits, precision, scale| Expr::literal(Scalar::decimal(bits, precision, scale).unwrap());
+
+    // 1.0 as decimal(2,1) has a lower scale than the column
+    let pred = column.clone().eq(dec(10, 2, 1));
+    let results = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![false, true, false]));
+
+    // 1.001 as decimal(4,3) has a higher scale than the column
+    let pred = column.clone().lt(dec(1001, 4, 3));
+    let results = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![true, true, false]));
+    let results = evaluate_predicate(&pred, &batch, true).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![false, false, true]));
+
+    // Aligning the scales of decimal(38,0) and decimal(5,2) needs 40 digits of precision
+    let pred = column.clone().gt(dec(-(10i128.pow(38) - 1), 38, 0));
+    let results = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![true, true, true]));
+    let pred = column.clone().gt(dec(1, 38, 38));
+    let results = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(results, BooleanArray::from(vec![false, true, true]));
+
+    // Arithmetic across scales: 111.11 + 0.001 = 111.111
+    let expr = column.add(dec(1, 4, 3));
+    let results = evaluate_expression(&expr, &batch, None).unwrap();
+    let expected = Decimal128Array::from(vec![-999989, 1001, 111111])
+        .with_precision_and_scale(7, 3)
+        .unwrap();
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+}
+
 #[test]
 fn test_logical() {
     let t = Some(true);
@@ -694,6 +834,108 @@ fn test_null_row_err() {
     assert!(handler.null_row(not_null_schema).is_err());
 }
 
+#[test]
+fn test_aggregates() {
+    let input_schema = Arc::new(StructType::new(vec![
+        StructField::nullable("i", KernelDataType::INTEGER),
+        StructField::nullable("s", KernelDataType::STRING),
+        StructField::nullable("n", KernelDataType::INTEGER),
+    ]));
+    let batch = RecordBatch::try_new(
+        Arc::new(input_schema.as_ref().try_into_arrow().unwrap()),
+        vec![
+            create_array!(Int32, [Some(3), None, Some(-1), Some(7)]),
+            create_array!(Utf8, [Some("b"), Some("a"), None, Some("c")]),
+            create_array!(Int32, [None::<i32>, None, None, None]),
+        ],
+    )
+    .unwrap();
+    let aggregates = vec![
+        Aggregate::Min(column_name!("i")),
+        Aggregate::Max(column_name!("i")),
+        Aggregate::Min(column_name!("s")),
+        Aggregate::Max(column_name!("s")),
+        Aggregate::NullCount(column_name!("i")),
+        Aggregate::Min(column_name!("n")),
+        Aggregate::NullCount(column_name!("n")),
+        Aggregate::RowCount,
+    ];
+    let output_schema = Arc::new(StructType::new(vec![
+        StructField::nullable("min_i", KernelDataType::INTEGER),
+        StructField::nullable("max_i", KernelDataType::INTEGER),
+        StructField::nullable("min_s", KernelDataType::STRING),
+        StructField::nullable("max_s", KernelDataType::STRING),
+        StructField::nullable("null_count_i", KernelDataType::LONG),
+        StructField::nullable("min_n", KernelDataType::INTEGER),
+        StructField::nullable("null_count_n", KernelDataType::LONG),
+        StructField::nullable("row_count", KernelDataType::LONG),
+    ]));
+    let evaluator = ArrowEvaluationHandler
+        .new_aggregate_evaluator(input_schema, aggregates, output_schema.clone())
+        .unwrap();
+    let result: RecordBatch = evaluator
+        .evaluate(&ArrowEngineData::new(batch))
+        .unwrap()
+        .into_any()
+        .downcast::<ArrowEngineData>()
+        .unwrap()
+        .into();
+    let expected = RecordBatch::try_new(
+        Arc::new(output_schema.as_ref().try_into_arrow().unwrap()),
+        vec![
+            create_array!(Int32, [-1]),
+            create_array!(Int32, [7]),
+            create_array!(Utf8, ["a"]),
+            create_array!(Utf8, ["c"]),
+            create_array!(Int64, [1]),
+            create_array!(Int32, [None::<i32>]),
+            create_array!(Int64, [4]),
+            create_array!(Int64, [4]),
+        ],
+    )
+    .unwrap();
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn test_aggregates_err() {
+    let input_schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "i",
+        KernelDataType::INTEGER,
+    )]));
+    let batch = RecordBatch::try_new(
+        Arc::new(input_schema.as_ref().try_into_arrow().unwrap()),
+        vec![create_array!(Int32, [1])],
+    )
+    .unwrap();
+    let output_schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "row_count",
+        KernelDataType::LONG,
+    )]));
+
+    // one output field per aggregate
+    let evaluator = ArrowEvaluationHandler
+        .new_aggregate_evaluator(
+            input_schema.clone(),
+            vec![Aggregate::RowCount, Aggregate::RowCount],
+            output_schema.clone(),
+        )
+        .unwrap();
+    assert!(evaluator
+        .evaluate(&ArrowEngineData::new(batch.clone()))
+        .is_err());
+
+    // missing column
+    let evaluator = ArrowEvaluationHandler
+        .new_aggregate_evaluator(
+            input_schema,
+            vec![Aggregate::NullCount(column_name!("missing"))],
+            output_schema,
+        )
+        .unwrap();
+    assert!(evaluator.evaluate(&ArrowEngineData::new(batch)).is_err());
+}
+
 // helper to take values/schema to pass to `create_one` and assert the result = expected
 fn assert_create_one(values: &[Scalar], schema: SchemaRef, expected: RecordBatch) {
     let handler = ArrowEvaluationHandler;
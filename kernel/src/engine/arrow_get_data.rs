@@ -1,7 +1,7 @@
 use crate::arrow::array::{
     types::{GenericStringType, Int32Type, Int64Type},
     Array, BooleanArray, GenericByteArray, GenericListArray, MapArray, OffsetSizeTrait,
-    PrimitiveArray,
+    PrimitiveArray, StringViewArray,
 };
 
 use crate::{
@@ -41,7 +41,20 @@ impl GetData<'_> for PrimitiveArray<Int64Type> {
     }
 }
 
-impl<'a> GetData<'a> for GenericByteArray<GenericStringType<i32>> {
+impl<'a, OffsetSize> GetData<'a> for GenericByteArray<GenericStringType<OffsetSize>>
+where
+    OffsetSize: OffsetSizeTrait,
+{
+    fn get_str(&'a self, row_index: usize, _field_name: &str) -> DeltaResult<Option<&'a str>> {
+        if self.is_valid(row_index) {
+            Ok(Some(self.value(row_index)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<'a> GetData<'a> for StringViewArray {
     fn get_str(&'a self, row_index: usize, _field_name: &str) -> DeltaResult<Option<&'a str>> {
         if self.is_valid(row_index) {
             Ok(Some(self.value(row_index)))
@@ -2,6 +2,7 @@
 
 use std::collections::HashSet;
 use std::io::{BufRead, BufReader};
+use std::ops::Range;
 use std::sync::Arc;
 
 use crate::engine::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
@@ -10,20 +11,22 @@ use crate::{
     engine::arrow_data::ArrowEngineData,
     schema::{DataType, Schema, SchemaRef, StructField, StructType},
     utils::require,
-    DeltaResult, EngineData, Error,
+    DeltaResult, EngineData, Error, FileMeta, ParquetFooter, ParquetSplit,
 };
 
 use crate::arrow::array::{
-    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray, MapArray,
-    OffsetSizeTrait, RecordBatch, StringArray, StructArray,
+    cast::AsArray, make_array, new_null_array, Array as ArrowArray, ArrayRef, GenericListArray,
+    Int64Array, MapArray, OffsetSizeTrait, RecordBatch, StringArray, StructArray,
 };
 use crate::arrow::buffer::NullBuffer;
-use crate::arrow::compute::concat_batches;
+use crate::arrow::compute::{cast_with_options, concat_batches, CastOptions};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, FieldRef as ArrowFieldRef, Fields,
     Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
 };
-use crate::arrow::json::{LineDelimitedWriter, ReaderBuilder};
+use crate::arrow::json::writer::LineDelimited;
+use crate::arrow::json::{LineDelimitedWriter, ReaderBuilder, WriterBuilder};
+use crate::parquet::file::metadata::{ParquetMetaData, RowGroupMetaData};
 use crate::parquet::{arrow::ProjectionMask, schema::types::SchemaDescriptor};
 use delta_kernel_derive::internal_api;
 use itertools::Itertools;
@@ -58,6 +61,80 @@ macro_rules! prim_array_cmp {
 
 pub(crate) use prim_array_cmp;
 
+/// Controls how the arrow engine casts data read from parquet files to the requested types, e.g.
+/// when a column's type was widened or a file stores timestamps at a higher precision than the
+/// table schema.
+///
+/// The default policy is "safe": values which can't be represented in the target type become null,
+/// and timestamps are truncated to the target precision. [`CastPolicy::strict`] instead fails the
+/// read in both cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CastPolicy {
+    safe: bool,
+    truncate_timestamps: bool,
+}
+
+impl Default for CastPolicy {
+    fn default() -> Self {
+        Self {
+            safe: true,
+            truncate_timestamps: true,
+        }
+    }
+}
+
+impl CastPolicy {
+    /// A policy which errors on overflow and on loss of timestamp precision.
+    pub fn strict() -> Self {
+        Self {
+            safe: false,
+            truncate_timestamps: false,
+        }
+    }
+
+    /// If `safe`, values which overflow the target type are replaced by null. Otherwise the cast
+    /// fails.
+    pub fn with_safe(mut self, safe: bool) -> Self {
+        self.safe = safe;
+        self
+    }
+
+    /// If `truncate`, casting timestamps to a coarser unit (e.g. nanoseconds to microseconds)
+    /// silently drops the extra precision. Otherwise the cast fails if any value would change.
+    pub fn with_timestamp_truncation(mut self, truncate: bool) -> Self {
+        self.truncate_timestamps = truncate;
+        self
+    }
+
+    /// Cast `array` to `target` according to this policy.
+    pub(crate) fn cast(
+        &self,
+        array: &dyn ArrowArray,
+        target: &ArrowDataType,
+    ) -> DeltaResult<ArrayRef> {
+        let options = CastOptions {
+            safe: self.safe,
+            ..Default::default()
+        };
+        let result = cast_with_options(array, target, &options)?;
+        if let (ArrowDataType::Timestamp(from, _), ArrowDataType::Timestamp(to, _)) =
+            (array.data_type(), target)
+        {
+            if !self.truncate_timestamps && to < from {
+                // the cast is lossless iff casting back yields the original values
+                let round_trip = cast_with_options(&result, array.data_type(), &options)?;
+                if round_trip.as_ref() != array {
+                    return Err(Error::generic(format!(
+                        "Casting {} to {target} would lose timestamp precision",
+                        array.data_type()
+                    )));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
 /// Get the indices in `parquet_schema` of the specified columns in `requested_schema`. This
 /// returns a tuples of (mask_indices: Vec<parquet_schema_index>, reorder_indices:
 /// Vec<requested_index>). `mask_indices` is used for generating the mask for reading from the
@@ -74,15 +151,73 @@ pub(crate) fn make_arrow_error(s: impl Into<String>) -> Error {
 pub(crate) fn fixup_parquet_read<T>(
     batch: RecordBatch,
     requested_ordering: &[ReorderIndex],
+    cast_policy: &CastPolicy,
+    row_indexes: &mut RowIndexes,
 ) -> DeltaResult<T>
 where
     StructArray: Into<T>,
 {
-    let data = reorder_struct_array(batch.into(), requested_ordering)?;
+    let data = reorder_struct_array(batch.into(), requested_ordering, cast_policy)?;
     let data = fix_nested_null_masks(data);
+    let data = row_indexes.fill(data)?;
     Ok(data.into())
 }
 
+/// Fills in the [`ROW_INDEX_COLUMN_NAME`] column of the batches read from a parquet file, if it
+/// was requested and the file doesn't contain it. Batches must be passed in file order, and none of
+/// the file's rows may be skipped (e.g. by row group filtering), see [`RowIndexes::is_requested`].
+///
+/// [`ROW_INDEX_COLUMN_NAME`]: crate::scan::ROW_INDEX_COLUMN_NAME
+pub(crate) struct RowIndexes {
+    // position of the row index column in the reordered batches
+    column: Option<usize>,
+    next_row_index: i64,
+}
+
+impl RowIndexes {
+    pub(crate) fn new(requested_ordering: &[ReorderIndex]) -> Self {
+        let column =
+            requested_ordering
+                .iter()
+                .find_map(|reorder_index| match &reorder_index.transform {
+                    ReorderIndexTransform::Missing(field)
+                        if field.name() == crate::scan::ROW_INDEX_COLUMN_NAME =>
+                    {
+                        Some(reorder_index.index)
+                    }
+                    _ => None,
+                });
+        Self {
+            column,
+            next_row_index: 0,
+        }
+    }
+
+    /// Start filling in row indexes at `first_row_index`, e.g. when reading starts at a later row
+    /// group of the file.
+    pub(crate) fn start_at(&mut self, first_row_index: i64) {
+        self.next_row_index = first_row_index;
+    }
+
+    /// Whether row indexes must be filled in, in which case no rows of the file may be skipped.
+    pub(crate) fn is_requested(&self) -> bool {
+        self.column.is_some()
+    }
+
+    fn fill(&mut self, data: StructArray) -> DeltaResult<StructArray> {
+        let Some(column) = self.column else {
+            return Ok(data);
+        };
+        let num_rows = i64::try_from(data.len()).map_err(Error::generic_err)?;
+        let row_indexes =
+            Int64Array::from_iter_values(self.next_row_index..self.next_row_index + num_rows);
+        self.next_row_index += num_rows;
+        let (fields, mut columns, nulls) = data.into_parts();
+        columns[column] = Arc::new(row_indexes);
+        Ok(StructArray::try_new(fields, columns, nulls)?)
+    }
+}
+
 /*
 * The code below implements proper pruning of columns when reading parquet, reordering of columns to
 * match the specified schema, and insertion of null columns if the requested schema includes a
@@ -481,7 +616,9 @@ pub(crate) fn get_requested_indices(
 }
 
 /// Create a mask that will only select the specified indices from the parquet. `indices` can be
-/// computed from a [`Schema`] using [`get_requested_indices`]
+/// computed from a [`Schema`] using [`get_requested_indices`]. Since the indices are leaf column
+/// indices, nested columns are projected at the leaf level: requesting only `s.a` from a struct `s`
+/// decodes just that leaf, not every leaf of `s`.
 pub(crate) fn generate_mask(
     _requested_schema: &SchemaRef,
     _parquet_schema: &ArrowSchemaRef,
@@ -496,6 +633,67 @@ pub(crate) fn generate_mask(
     ))
 }
 
+/// Get the [`ParquetFooter`] of a parquet file from its metadata.
+pub(crate) fn parquet_footer(metadata: &ParquetMetaData) -> DeltaResult<ParquetFooter> {
+    let num_rows = metadata.file_metadata().num_rows();
+    Ok(ParquetFooter {
+        num_rows: num_rows
+            .try_into()
+            .map_err(|_| Error::generic(format!("Invalid parquet row count: {num_rows}")))?,
+    })
+}
+
+/// The byte range of a row group within its parquet file, spanning all of its column chunks.
+pub(crate) fn row_group_byte_range(row_group: &RowGroupMetaData) -> Range<u64> {
+    let (start, end) = row_group
+        .columns()
+        .iter()
+        .map(|column| {
+            let (start, len) = column.byte_range();
+            (start, start + len)
+        })
+        .fold((u64::MAX, 0), |(min_start, max_end), (start, end)| {
+            (min_start.min(start), max_end.max(end))
+        });
+    start.min(end)..end
+}
+
+/// Split a parquet file into [`ParquetSplit`]s of consecutive row groups, of at least
+/// `target_split_size` (compressed) bytes each except for the last one. Each split's range starts
+/// at its first row group and ends at the end of its last row group.
+pub(crate) fn plan_parquet_splits(
+    file: &FileMeta,
+    metadata: &ParquetMetaData,
+    target_split_size: u64,
+) -> Vec<ParquetSplit> {
+    let mut splits = vec![];
+    let mut current: Option<Range<u64>> = None;
+    for row_group in metadata.row_groups() {
+        let range = row_group_byte_range(row_group);
+        let range = match current.take() {
+            Some(current) => current.start..range.end,
+            None => range,
+        };
+        if range.end - range.start >= target_split_size {
+            splits.push(ParquetSplit {
+                file: file.clone(),
+                range: Some(range),
+            });
+        } else {
+            current = Some(range);
+        }
+    }
+    splits.extend(current.map(|range| ParquetSplit {
+        file: file.clone(),
+        range: Some(range),
+    }));
+    // a file without row groups is read as a whole, so that it is still read at all
+    if splits.is_empty() {
+        splits.push(ParquetSplit::whole_file(file.clone()));
+    }
+    splits
+}
+
 /// Check if an ordering requires transforming the data in any way.  This is true if the indices are
 /// NOT in ascending order (so we have to reorder things), or if we need to do any transformation on
 /// the data read from parquet. We check the ordering here, and also call
@@ -525,6 +723,7 @@ type FieldArrayOpt = Option<(Arc<ArrowField>, Arc<dyn ArrowArray>)>;
 pub(crate) fn reorder_struct_array(
     input_data: StructArray,
     requested_ordering: &[ReorderIndex],
+    cast_policy: &CastPolicy,
 ) -> DeltaResult<StructArray> {
     debug!("Reordering {input_data:?} with ordering: {requested_ordering:?}");
     if !ordering_needs_transform(requested_ordering) {
@@ -544,7 +743,7 @@ pub(crate) fn reorder_struct_array(
             match &reorder_index.transform {
                 ReorderIndexTransform::Cast(target) => {
                     let col = input_cols[parquet_position].as_ref();
-                    let col = Arc::new(crate::arrow::compute::cast(col, target)?);
+                    let col = cast_policy.cast(col, target)?;
                     let new_field = Arc::new(
                         input_fields[parquet_position]
                             .as_ref()
@@ -558,8 +757,11 @@ pub(crate) fn reorder_struct_array(
                     match input_cols[parquet_position].data_type() {
                         ArrowDataType::Struct(_) => {
                             let struct_array = input_cols[parquet_position].as_struct().clone();
-                            let result_array =
-                                Arc::new(reorder_struct_array(struct_array, children)?);
+                            let result_array = Arc::new(reorder_struct_array(
+                                struct_array,
+                                children,
+                                cast_policy,
+                            )?);
                             // create the new field specifying the correct order for the struct
                             let new_field = Arc::new(ArrowField::new_struct(
                                 input_field_name,
@@ -572,17 +774,17 @@ pub(crate) fn reorder_struct_array(
                         ArrowDataType::List(_) => {
                             let list_array = input_cols[parquet_position].as_list::<i32>().clone();
                             final_fields_cols[reorder_index.index] =
-                                reorder_list(list_array, input_field_name, children)?;
+                                reorder_list(list_array, input_field_name, children, cast_policy)?;
                         }
                         ArrowDataType::LargeList(_) => {
                             let list_array = input_cols[parquet_position].as_list::<i64>().clone();
                             final_fields_cols[reorder_index.index] =
-                                reorder_list(list_array, input_field_name, children)?;
+                                reorder_list(list_array, input_field_name, children, cast_policy)?;
                         }
                         ArrowDataType::Map(_, _) => {
                             let map_array = input_cols[parquet_position].as_map().clone();
                             final_fields_cols[reorder_index.index] =
-                                reorder_map(map_array, input_field_name, children)?;
+                                reorder_map(map_array, input_field_name, children, cast_policy)?;
                         }
                         _ => {
                             return Err(Error::internal_error(
@@ -623,11 +825,12 @@ fn reorder_list<O: OffsetSizeTrait>(
     list_array: GenericListArray<O>,
     input_field_name: &str,
     children: &[ReorderIndex],
+    cast_policy: &CastPolicy,
 ) -> DeltaResult<FieldArrayOpt> {
     let (list_field, offset_buffer, maybe_sa, null_buf) = list_array.into_parts();
     if let Some(struct_array) = maybe_sa.as_struct_opt() {
         let struct_array = struct_array.clone();
-        let result_array = Arc::new(reorder_struct_array(struct_array, children)?);
+        let result_array = Arc::new(reorder_struct_array(struct_array, children, cast_policy)?);
         let new_list_field = Arc::new(ArrowField::new_struct(
             list_field.name(),
             result_array.fields().clone(),
@@ -656,9 +859,10 @@ fn reorder_map(
     map_array: MapArray,
     input_field_name: &str,
     children: &[ReorderIndex],
+    cast_policy: &CastPolicy,
 ) -> DeltaResult<FieldArrayOpt> {
     let (map_field, offset_buffer, struct_array, null_buf, ordered) = map_array.into_parts();
-    let result_array = reorder_struct_array(struct_array, children)?;
+    let result_array = reorder_struct_array(struct_array, children, cast_policy)?;
     let result_fields = result_array.fields();
     let new_map_field = Arc::new(ArrowField::new_struct(
         map_field.name(),
@@ -732,23 +936,88 @@ fn compute_nested_null_masks(sa: StructArray, parent_nulls: Option<&NullBuffer>)
     unsafe { StructArray::new_unchecked(fields, columns, nulls) }
 }
 
+// The type to normalize an arrow type to, if it is or contains a large or view string or binary
+// type. Returns `None` if the type needs no normalization.
+fn normalized_data_type(data_type: &ArrowDataType) -> Option<ArrowDataType> {
+    fn normalized_field(field: &ArrowFieldRef) -> Option<ArrowFieldRef> {
+        let data_type = normalized_data_type(field.data_type())?;
+        Some(Arc::new(field.as_ref().clone().with_data_type(data_type)))
+    }
+    match data_type {
+        ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View => Some(ArrowDataType::Utf8),
+        ArrowDataType::LargeBinary | ArrowDataType::BinaryView => Some(ArrowDataType::Binary),
+        ArrowDataType::List(field) => Some(ArrowDataType::List(normalized_field(field)?)),
+        ArrowDataType::LargeList(field) => Some(ArrowDataType::LargeList(normalized_field(field)?)),
+        ArrowDataType::Map(field, sorted) => {
+            Some(ArrowDataType::Map(normalized_field(field)?, *sorted))
+        }
+        ArrowDataType::Struct(fields) => {
+            let normalized: Vec<_> = fields.iter().map(normalized_field).collect();
+            if normalized.iter().all(Option::is_none) {
+                return None;
+            }
+            let fields: Fields = fields
+                .iter()
+                .zip(normalized)
+                .map(|(field, normalized)| normalized.unwrap_or_else(|| field.clone()))
+                .collect();
+            Some(ArrowDataType::Struct(fields))
+        }
+        _ => None,
+    }
+}
+
+/// Casts large and view string and binary arrays (`LargeUtf8`, `Utf8View`, `LargeBinary` and
+/// `BinaryView`), also when nested in structs, lists or maps, to the `Utf8` and `Binary` arrays
+/// kernel works with. Other arrays are returned as-is.
+pub(crate) fn normalize_array(array: &ArrayRef) -> DeltaResult<ArrayRef> {
+    match normalized_data_type(array.data_type()) {
+        Some(data_type) => Ok(cast_with_options(
+            array,
+            &data_type,
+            &CastOptions::default(),
+        )?),
+        None => Ok(array.clone()),
+    }
+}
+
 /// Arrow lacks the functionality to json-parse a string column into a struct column -- even tho the
 /// JSON file reader does exactly the same thing. This function is a hack to work around that gap.
 #[internal_api]
 pub(crate) fn parse_json(
     json_strings: Box<dyn EngineData>,
     schema: SchemaRef,
+) -> DeltaResult<Box<dyn EngineData>> {
+    parse_json_with(json_strings, schema, parse_json_impl)
+}
+
+/// Like [`parse_json`], but for file statistics: integers encoded as floats are accepted, see
+/// [`JsonHandler::parse_json_stats`].
+///
+/// [`JsonHandler::parse_json_stats`]: crate::JsonHandler::parse_json_stats
+#[internal_api]
+pub(crate) fn parse_json_stats(
+    json_strings: Box<dyn EngineData>,
+    schema: SchemaRef,
+) -> DeltaResult<Box<dyn EngineData>> {
+    parse_json_with(json_strings, schema, parse_json_stats_impl)
+}
+
+fn parse_json_with(
+    json_strings: Box<dyn EngineData>,
+    schema: SchemaRef,
+    parse: impl FnOnce(&StringArray, ArrowSchemaRef) -> DeltaResult<RecordBatch>,
 ) -> DeltaResult<Box<dyn EngineData>> {
     let json_strings: RecordBatch = ArrowEngineData::try_from_engine_data(json_strings)?.into();
+    let json_strings = normalize_array(json_strings.column(0))?;
     let json_strings = json_strings
-        .column(0)
         .as_any()
         .downcast_ref::<StringArray>()
         .ok_or_else(|| {
             Error::generic("Expected json_strings to be a StringArray, found something else")
         })?;
     let schema = Arc::new(ArrowSchema::try_from_kernel(schema.as_ref())?);
-    let result = parse_json_impl(json_strings, schema)?;
+    let result = parse(json_strings, schema)?;
     Ok(Box::new(ArrowEngineData::new(result)))
 }
 
@@ -758,6 +1027,32 @@ pub(crate) fn parse_json(
 // StringArray -> StructArray JSON parsing. See https://github.com/apache/arrow-rs/issues/6522. If
 // that shortcoming gets fixed upstream, this method can simplify or hopefully even disappear.
 fn parse_json_impl(json_strings: &StringArray, schema: ArrowSchemaRef) -> DeltaResult<RecordBatch> {
+    decode_json_rows(json_strings, schema, false)
+}
+
+// Like `parse_json_impl`, but integer columns are decoded as strings and converted afterwards, see
+// `parse_json_integer`.
+fn parse_json_stats_impl(
+    json_strings: &StringArray,
+    schema: ArrowSchemaRef,
+) -> DeltaResult<RecordBatch> {
+    let decode_schema = Arc::new(ArrowSchema::new(json_decode_fields(schema.fields())));
+    // integers are decoded into string columns, which only accept JSON numbers when coercing
+    let batch = decode_json_rows(json_strings, decode_schema, true)?;
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(column, field)| parse_json_integers(column.clone(), field.data_type()))
+        .try_collect()?;
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+fn decode_json_rows(
+    json_strings: &StringArray,
+    schema: ArrowSchemaRef,
+    coerce_primitive: bool,
+) -> DeltaResult<RecordBatch> {
     if json_strings.is_empty() {
         return Ok(RecordBatch::new_empty(schema));
     }
@@ -765,6 +1060,7 @@ fn parse_json_impl(json_strings: &StringArray, schema: ArrowSchemaRef) -> DeltaR
     // Use batch size of 1 to force one record per string input
     let mut decoder = ReaderBuilder::new(schema.clone())
         .with_batch_size(1)
+        .with_coerce_primitive(coerce_primitive)
         .build_decoder()?;
     let parse_one = |json_string: Option<&str>| -> DeltaResult<RecordBatch> {
         let mut reader = BufReader::new(json_string.unwrap_or("{}").as_bytes());
@@ -784,20 +1080,170 @@ fn parse_json_impl(json_strings: &StringArray, schema: ArrowSchemaRef) -> DeltaR
     Ok(concat_batches(&schema, output.iter())?)
 }
 
+// The fields used to decode JSON for `fields`: integer fields (including those nested in structs)
+// are decoded as strings instead, so that `parse_json_integers` can convert them.
+fn json_decode_fields(fields: &Fields) -> Fields {
+    fields
+        .iter()
+        .map(|field| {
+            let data_type = match field.data_type() {
+                data_type if data_type.is_integer() => ArrowDataType::Utf8,
+                ArrowDataType::Struct(children) => {
+                    ArrowDataType::Struct(json_decode_fields(children))
+                }
+                data_type => data_type.clone(),
+            };
+            Arc::new(field.as_ref().clone().with_data_type(data_type))
+        })
+        .collect()
+}
+
+// Convert integer columns decoded as strings by `json_decode_fields` back to `data_type`.
+fn parse_json_integers(array: ArrayRef, data_type: &ArrowDataType) -> DeltaResult<ArrayRef> {
+    match data_type {
+        data_type if data_type.is_integer() => {
+            let values: Int64Array = array
+                .as_string::<i32>()
+                .iter()
+                .map(|value| value.and_then(parse_json_integer))
+                .collect();
+            // a safe cast turns values which overflow a narrower integer type into nulls
+            Ok(cast_with_options(
+                &values,
+                data_type,
+                &CastOptions::default(),
+            )?)
+        }
+        ArrowDataType::Struct(fields) => {
+            let array = array.as_struct();
+            let columns = array
+                .columns()
+                .iter()
+                .zip(fields)
+                .map(|(column, field)| parse_json_integers(column.clone(), field.data_type()))
+                .try_collect()?;
+            let nulls = array.nulls().cloned();
+            Ok(Arc::new(StructArray::try_new(
+                fields.clone(),
+                columns,
+                nulls,
+            )?))
+        }
+        _ => Ok(array),
+    }
+}
+
+/// Parse a JSON number for an integer column. Other writers sometimes encode integers as floats
+/// (`1.0`) or with exponents (`1e3`, common for file stats), which are accepted as long as they
+/// exactly represent an integer. Values which are not integral, overflow an `i64`, or are too large
+/// to be exactly represented by the float they were encoded as are unknown (`None`) -- truncating
+/// or rounding them could make e.g. a `maxValues` stat too small and incorrectly skip files.
+fn parse_json_integer(value: &str) -> Option<i64> {
+    if let Ok(value) = value.parse::<i64>() {
+        return Some(value);
+    }
+    // integers below 2^53 are exactly representable as f64; 2^53 itself may be a rounded 2^53+1
+    const MAX_EXACT: f64 = (1u64 << f64::MANTISSA_DIGITS) as f64;
+    let value = value.parse::<f64>().ok()?;
+    (value.fract() == 0.0 && value.abs() < MAX_EXACT).then_some(value as i64)
+}
+
 /// serialize an arrow RecordBatch to a JSON string by appending to a buffer.
+///
+/// Null struct fields are omitted, but null map values are written as `null`: a map entry with a
+/// null value (e.g. a null partition value) is not the same as a missing entry.
 // TODO (zach): this should stream data to the JSON writer and output an iterator.
 #[internal_api]
 pub(crate) fn to_json_bytes(
     data: impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send,
 ) -> DeltaResult<Vec<u8>> {
-    let mut writer = LineDelimitedWriter::new(Vec::new());
+    let mut buf = Vec::new();
     for chunk in data {
         let arrow_data = ArrowEngineData::try_from_engine_data(chunk?)?;
         let record_batch = arrow_data.record_batch();
-        writer.write(record_batch)?;
+        if record_batch.columns().iter().any(has_null_map_values) {
+            write_json_with_null_map_values(record_batch, &mut buf)?;
+        } else {
+            let mut writer = LineDelimitedWriter::new(&mut buf);
+            writer.write(record_batch)?;
+            writer.finish()?;
+        }
+    }
+    Ok(buf)
+}
+
+fn has_null_map_values(array: &ArrayRef) -> bool {
+    match array.data_type() {
+        ArrowDataType::Struct(_) => array.as_struct().columns().iter().any(has_null_map_values),
+        ArrowDataType::List(_) => has_null_map_values(array.as_list::<i32>().values()),
+        ArrowDataType::LargeList(_) => has_null_map_values(array.as_list::<i64>().values()),
+        ArrowDataType::Map(_, _) => {
+            let values = array.as_map().values();
+            values.null_count() > 0 || has_null_map_values(values)
+        }
+        _ => false,
     }
+}
+
+// The arrow JSON writer either omits or writes every null, so write them all and drop the null
+// struct fields again.
+fn write_json_with_null_map_values(batch: &RecordBatch, out: &mut Vec<u8>) -> DeltaResult<()> {
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, LineDelimited>(Vec::new());
+    writer.write(batch)?;
     writer.finish()?;
-    Ok(writer.into_inner())
+    let row_type = ArrowDataType::Struct(batch.schema().fields().clone());
+    for line in writer.into_inner().split(|b| *b == b'\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut row: serde_json::Value = serde_json::from_slice(line)?;
+        drop_null_struct_fields(&mut row, &row_type);
+        serde_json::to_writer(&mut *out, &row)?;
+        out.push(b'\n');
+    }
+    Ok(())
+}
+
+fn drop_null_struct_fields(value: &mut serde_json::Value, data_type: &ArrowDataType) {
+    use serde_json::Value;
+    match (value, data_type) {
+        (Value::Object(object), ArrowDataType::Struct(fields)) => {
+            object.retain(|_, field_value| !field_value.is_null());
+            for field in fields {
+                if let Some(field_value) = object.get_mut(field.name()) {
+                    drop_null_struct_fields(field_value, field.data_type());
+                }
+            }
+        }
+        (Value::Object(object), ArrowDataType::Map(entries, _)) => {
+            if let ArrowDataType::Struct(key_value) = entries.data_type() {
+                for map_value in object.values_mut() {
+                    drop_null_struct_fields(map_value, key_value[1].data_type());
+                }
+            }
+        }
+        (Value::Array(items), ArrowDataType::List(item) | ArrowDataType::LargeList(item)) => {
+            for item_value in items {
+                drop_null_struct_fields(item_value, item.data_type());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The UTF-8 byte order mark. Some writers (notably on Windows) start text files with it, but it is
+/// not valid JSON so must be skipped before decoding. CRLF line endings need no such handling, as
+/// the JSON decoder treats the `\r` as whitespace.
+pub(crate) const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Skip the [`UTF8_BOM`] at the start of `reader`, if any.
+pub(crate) fn skip_utf8_bom<R: BufRead>(mut reader: R) -> std::io::Result<R> {
+    if reader.fill_buf()?.starts_with(UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+    }
+    Ok(reader)
 }
 
 #[cfg(test)]
@@ -809,8 +1255,8 @@ mod tests {
         MapArray, MapBuilder, StructArray, StructBuilder,
     };
     use crate::arrow::datatypes::{
-        DataType as ArrowDataType, Field as ArrowField, Fields, Schema as ArrowSchema,
-        SchemaRef as ArrowSchemaRef,
+        DataType as ArrowDataType, Field as ArrowField, Fields, Int32Type, Int64Type,
+        Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
     };
     use crate::arrow::{
         array::AsArray,
@@ -818,6 +1264,7 @@ mod tests {
     };
 
     use crate::schema::{ArrayType, DataType, MapType, StructField, StructType};
+    use crate::utils::test_utils::string_array_to_engine_data;
 
     use super::*;
 
@@ -886,6 +1333,62 @@ mod tests {
         assert_eq!(result.column(2).null_count(), 2);
     }
 
+    #[test]
+    fn test_json_parsing_permissive_integers() {
+        let nested = Fields::from(vec![ArrowField::new("long", ArrowDataType::Int64, true)]);
+        let requested_schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("int", ArrowDataType::Int32, true),
+            ArrowField::new("nested", ArrowDataType::Struct(nested), true),
+        ]));
+        let input: Vec<Option<&str>> = vec![
+            Some(r#"{"int": 1, "nested": {"long": 9223372036854775807}}"#),
+            Some(r#"{"int": 2.0, "nested": {"long": -3E+2}}"#),
+            Some(r#"{"int": 1e3, "nested": {"long": 9007199254740991.0}}"#),
+            // not integral
+            Some(r#"{"int": 1.5, "nested": {"long": 1e-3}}"#),
+            // overflow
+            Some(r#"{"int": 2147483648, "nested": {"long": 9223372036854775808}}"#),
+            // too large to be exactly represented by the encoded float
+            Some(r#"{"int": 1, "nested": {"long": 9007199254740993.0}}"#),
+        ];
+        let result = parse_json_stats_impl(&input.into(), requested_schema).unwrap();
+        let ints = result.column(0).as_primitive::<Int32Type>();
+        assert_eq!(
+            ints.iter().collect_vec(),
+            [Some(1), Some(2), Some(1000), None, None, Some(1)]
+        );
+        let longs = result
+            .column(1)
+            .as_struct()
+            .column(0)
+            .as_primitive::<Int64Type>();
+        assert_eq!(
+            longs.iter().collect_vec(),
+            [
+                Some(i64::MAX),
+                Some(-300),
+                Some((1 << 53) - 1),
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_parsing_does_not_coerce_primitives() {
+        let schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "string",
+            ArrowDataType::Utf8,
+            true,
+        )]));
+        let input: Vec<Option<&str>> = vec![Some(r#"{"string": 1}"#)];
+        assert!(parse_json_impl(&input.clone().into(), schema.clone()).is_err());
+        // only file statistics are parsed permissively
+        let result = parse_json_stats_impl(&input.into(), schema).unwrap();
+        assert_eq!(result.column(0).as_string::<i32>().value(0), "1");
+    }
+
     #[test]
     fn simple_mask_indices() {
         let requested_schema = Arc::new(StructType::new([
@@ -1466,10 +1969,64 @@ mod tests {
     fn simple_reorder_struct() {
         let arry = make_struct_array();
         let reorder = vec![ReorderIndex::identity(1), ReorderIndex::identity(0)];
-        let ordered = reorder_struct_array(arry, &reorder).unwrap();
+        let ordered = reorder_struct_array(arry, &reorder, &CastPolicy::default()).unwrap();
         assert_eq!(ordered.column_names(), vec!["c", "b"]);
     }
 
+    #[test]
+    fn cast_policy_timestamp_precision() {
+        use crate::arrow::array::{TimestampMicrosecondArray, TimestampNanosecondArray};
+        use crate::arrow::datatypes::TimeUnit;
+
+        let target = ArrowDataType::Timestamp(TimeUnit::Microsecond, None);
+        let exact = TimestampNanosecondArray::from(vec![Some(2_000), None]);
+        let lossy = TimestampNanosecondArray::from(vec![Some(1_500), None]);
+
+        // default policy truncates
+        let result = CastPolicy::default().cast(&lossy, &target).unwrap();
+        let expected = TimestampMicrosecondArray::from(vec![Some(1), None]);
+        assert_eq!(result.as_primitive(), &expected);
+
+        // strict policy only allows lossless casts
+        let strict = CastPolicy::strict();
+        let result = strict.cast(&exact, &target).unwrap();
+        let expected = TimestampMicrosecondArray::from(vec![Some(2), None]);
+        assert_eq!(result.as_primitive(), &expected);
+        assert!(strict.cast(&lossy, &target).is_err());
+    }
+
+    #[test]
+    fn cast_policy_overflow() {
+        use crate::arrow::array::TimestampSecondArray;
+        use crate::arrow::datatypes::TimeUnit;
+
+        let target = ArrowDataType::Timestamp(TimeUnit::Nanosecond, None);
+        let input = TimestampSecondArray::from(vec![Some(1), Some(i64::MAX)]);
+
+        // default policy nulls out values that overflow
+        let result = CastPolicy::default().cast(&input, &target).unwrap();
+        assert_eq!(result.null_count(), 1);
+
+        // unsafe casts fail instead
+        let policy = CastPolicy::default().with_safe(false);
+        assert!(policy.cast(&input, &target).is_err());
+    }
+
+    #[test]
+    fn reorder_struct_with_cast_policy() {
+        use crate::arrow::array::TimestampNanosecondArray;
+        use crate::arrow::datatypes::TimeUnit;
+
+        let ts: ArrowArrayRef = Arc::new(TimestampNanosecondArray::from(vec![1_500]));
+        let arry = StructArray::try_from(vec![("ts", ts)]).unwrap();
+        let target = ArrowDataType::Timestamp(TimeUnit::Microsecond, None);
+        let reorder = vec![ReorderIndex::cast(0, target.clone())];
+
+        let ordered = reorder_struct_array(arry.clone(), &reorder, &CastPolicy::default()).unwrap();
+        assert_eq!(ordered.column(0).data_type(), &target);
+        assert!(reorder_struct_array(arry, &reorder, &CastPolicy::strict()).is_err());
+    }
+
     #[test]
     fn nested_reorder_struct() {
         let arry1 = Arc::new(make_struct_array());
@@ -1514,7 +2071,7 @@ mod tests {
                 ],
             ),
         ];
-        let ordered = reorder_struct_array(nested, &reorder).unwrap();
+        let ordered = reorder_struct_array(nested, &reorder, &CastPolicy::default()).unwrap();
         assert_eq!(ordered.column_names(), vec!["struct2", "struct1"]);
         let ordered_s2 = ordered.column(0).as_struct();
         assert_eq!(ordered_s2.column_names(), vec!["b", "c", "s"]);
@@ -1561,7 +2118,7 @@ mod tests {
             0,
             vec![ReorderIndex::identity(1), ReorderIndex::identity(0)],
         )];
-        let ordered = reorder_struct_array(struct_array, &reorder).unwrap();
+        let ordered = reorder_struct_array(struct_array, &reorder, &CastPolicy::default()).unwrap();
         let ordered_list_col = ordered.column(0).as_list::<i32>();
         for i in 0..ordered_list_col.len() {
             let array_item = ordered_list_col.value(i);
@@ -1627,7 +2184,7 @@ mod tests {
                 ],
             ),
         ];
-        let ordered = reorder_struct_array(struct_array, &reorder).unwrap();
+        let ordered = reorder_struct_array(struct_array, &reorder, &CastPolicy::default()).unwrap();
         assert_eq!(ordered.column_names(), vec!["map", "i"]);
         if let ArrowDataType::Map(field, _) = ordered.column(0).data_type() {
             if let ArrowDataType::Struct(fields) = field.data_type() {
@@ -1709,6 +2266,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_json_null_map_values() -> DeltaResult<()> {
+        let schema = Arc::new(StructType::new([
+            StructField::nullable("id", DataType::INTEGER),
+            StructField::nullable(
+                "partitionValues",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+        ]));
+        let json_strings = StringArray::from(vec![
+            r#"{"id": 1, "partitionValues": {"a": "x", "b": null}}"#,
+            r#"{"partitionValues": {}}"#,
+        ]);
+        let data = parse_json(string_array_to_engine_data(json_strings), schema)?;
+        let json = to_json_bytes(Box::new(std::iter::once(Ok(data))))?;
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            "{\"id\":1,\"partitionValues\":{\"a\":\"x\",\"b\":null}}\n{\"partitionValues\":{}}\n"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_arrow_broken_nested_null_masks() {
         use crate::arrow::datatypes::{DataType, Field, Fields, Schema};
@@ -0,0 +1,272 @@
+//! Tuning knobs for the [`DefaultEngine`](super::DefaultEngine).
+//!
+//! [`DefaultEngineConfig`] collects the buffer and batch sizes used by the default engine's
+//! handlers, so they can be tuned without recompiling. Start from [`DefaultEngineConfig::default`]
+//! and override individual values, either with the `with_*` methods or by name with
+//! [`DefaultEngineConfig::set`], then apply the result with
+//! [`DefaultEngine::with_config`](super::DefaultEngine::with_config).
+//!
+//! Timeouts and other network settings belong to the object store, and are configured through the
+//! options passed to [`DefaultEngine::try_new`](super::DefaultEngine::try_new).
+
+use crate::{DeltaResult, Error};
+
+use super::filesystem::{DEFAULT_LIST_BUFFER_SIZE, DEFAULT_READAHEAD as DEFAULT_STORAGE_READAHEAD};
+use super::json::{DEFAULT_BATCH_SIZE as DEFAULT_JSON_BATCH_SIZE, DEFAULT_BUFFER_SIZE};
+use super::parquet::{
+    DEFAULT_BATCH_SIZE as DEFAULT_PARQUET_BATCH_SIZE,
+    DEFAULT_READAHEAD as DEFAULT_PARQUET_READAHEAD,
+};
+
+/// Buffer and batch sizes used by the handlers of a [`DefaultEngine`](super::DefaultEngine).
+///
+/// Every size must be non-zero; [`Self::validate`] checks this, and the engine refuses to apply an
+/// invalid config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultEngineConfig {
+    json_buffer_size: usize,
+    json_batch_size: usize,
+    parquet_batch_size: usize,
+    parquet_readahead: usize,
+    memory_budget: Option<usize>,
+    parquet_footer_cache_size: Option<usize>,
+    storage_readahead: usize,
+    list_buffer_size: usize,
+}
+
+impl Default for DefaultEngineConfig {
+    fn default() -> Self {
+        Self {
+            json_buffer_size: DEFAULT_BUFFER_SIZE,
+            json_batch_size: DEFAULT_JSON_BATCH_SIZE,
+            parquet_batch_size: DEFAULT_PARQUET_BATCH_SIZE,
+            parquet_readahead: DEFAULT_PARQUET_READAHEAD,
+            memory_budget: None,
+            parquet_footer_cache_size: None,
+            storage_readahead: DEFAULT_STORAGE_READAHEAD,
+            list_buffer_size: DEFAULT_LIST_BUFFER_SIZE,
+        }
+    }
+}
+
+impl DefaultEngineConfig {
+    /// The names accepted by [`Self::set`].
+    pub const KEYS: &'static [&'static str] = &[
+        "json_buffer_size",
+        "json_batch_size",
+        "parquet_batch_size",
+        "parquet_readahead",
+        "memory_budget",
+        "parquet_footer_cache_size",
+        "storage_readahead",
+        "list_buffer_size",
+    ];
+
+    /// The maximum number of JSON file read requests to buffer at once. See
+    /// [`DefaultJsonHandler::with_buffer_size`](super::json::DefaultJsonHandler::with_buffer_size).
+    ///
+    /// Defaults to 1000.
+    pub fn with_json_buffer_size(mut self, json_buffer_size: usize) -> Self {
+        self.json_buffer_size = json_buffer_size;
+        self
+    }
+
+    /// The maximum number of rows per batch read from JSON files. See
+    /// [`DefaultJsonHandler::with_batch_size`](super::json::DefaultJsonHandler::with_batch_size).
+    ///
+    /// Defaults to 1000.
+    pub fn with_json_batch_size(mut self, json_batch_size: usize) -> Self {
+        self.json_batch_size = json_batch_size;
+        self
+    }
+
+    /// The maximum number of rows per batch read from parquet files. See
+    /// [`DefaultParquetHandler::with_batch_size`](super::parquet::DefaultParquetHandler::with_batch_size).
+    ///
+    /// Defaults to 1024.
+    pub fn with_parquet_batch_size(mut self, parquet_batch_size: usize) -> Self {
+        self.parquet_batch_size = parquet_batch_size;
+        self
+    }
+
+    /// The maximum number of parquet batches to read ahead of the consumer. See
+    /// [`DefaultParquetHandler::with_readahead`](super::parquet::DefaultParquetHandler::with_readahead).
+    ///
+    /// Defaults to 10.
+    pub fn with_parquet_readahead(mut self, parquet_readahead: usize) -> Self {
+        self.parquet_readahead = parquet_readahead;
+        self
+    }
+
+    /// The maximum number of bytes of parquet batches to read ahead of the consumer. See
+    /// [`DefaultParquetHandler::with_memory_budget`](super::parquet::DefaultParquetHandler::with_memory_budget).
+    ///
+    /// Defaults to no limit.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+
+    /// The maximum number of parquet footers to cache, to avoid reading the footer of a file from
+    /// storage again on repeated reads. See
+    /// [`DefaultParquetHandler::with_footer_cache_size`](super::parquet::DefaultParquetHandler::with_footer_cache_size).
+    ///
+    /// Defaults to no cache.
+    pub fn with_parquet_footer_cache_size(mut self, parquet_footer_cache_size: usize) -> Self {
+        self.parquet_footer_cache_size = Some(parquet_footer_cache_size);
+        self
+    }
+
+    /// The maximum number of files the storage handler reads in parallel. See
+    /// [`ObjectStoreStorageHandler::with_readahead`](super::filesystem::ObjectStoreStorageHandler::with_readahead).
+    ///
+    /// Defaults to 10.
+    pub fn with_storage_readahead(mut self, storage_readahead: usize) -> Self {
+        self.storage_readahead = storage_readahead;
+        self
+    }
+
+    /// The maximum number of listed files to buffer ahead of the consumer. See
+    /// [`ObjectStoreStorageHandler::with_list_buffer_size`](super::filesystem::ObjectStoreStorageHandler::with_list_buffer_size).
+    ///
+    /// Defaults to 4000.
+    pub fn with_list_buffer_size(mut self, list_buffer_size: usize) -> Self {
+        self.list_buffer_size = list_buffer_size;
+        self
+    }
+
+    pub fn json_buffer_size(&self) -> usize {
+        self.json_buffer_size
+    }
+
+    pub fn json_batch_size(&self) -> usize {
+        self.json_batch_size
+    }
+
+    pub fn parquet_batch_size(&self) -> usize {
+        self.parquet_batch_size
+    }
+
+    pub fn parquet_readahead(&self) -> usize {
+        self.parquet_readahead
+    }
+
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    pub fn parquet_footer_cache_size(&self) -> Option<usize> {
+        self.parquet_footer_cache_size
+    }
+
+    pub fn storage_readahead(&self) -> usize {
+        self.storage_readahead
+    }
+
+    pub fn list_buffer_size(&self) -> usize {
+        self.list_buffer_size
+    }
+
+    /// Set the value named `key` (one of [`Self::KEYS`]) from its string form, e.g. as read from
+    /// an environment variable or passed through FFI. Fails if the key is unknown or the value is
+    /// not a positive integer.
+    pub fn set(&mut self, key: &str, value: &str) -> DeltaResult<()> {
+        let parsed = value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|value| *value > 0)
+            .ok_or_else(|| {
+                Error::generic(format!(
+                    "Invalid value for engine config {key}: expected a positive integer, got {value:?}"
+                ))
+            });
+        *self = match key {
+            "json_buffer_size" => self.with_json_buffer_size(parsed?),
+            "json_batch_size" => self.with_json_batch_size(parsed?),
+            "parquet_batch_size" => self.with_parquet_batch_size(parsed?),
+            "parquet_readahead" => self.with_parquet_readahead(parsed?),
+            "memory_budget" => self.with_memory_budget(parsed?),
+            "parquet_footer_cache_size" => self.with_parquet_footer_cache_size(parsed?),
+            "storage_readahead" => self.with_storage_readahead(parsed?),
+            "list_buffer_size" => self.with_list_buffer_size(parsed?),
+            _ => {
+                return Err(Error::generic(format!(
+                    "Unknown engine config {key}, expected one of: {}",
+                    Self::KEYS.join(", ")
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Check that every size is non-zero. Zero-sized buffers and batches would stall or panic the
+    /// handlers that use them.
+    pub fn validate(&self) -> DeltaResult<()> {
+        let sizes = [
+            ("json_buffer_size", self.json_buffer_size),
+            ("json_batch_size", self.json_batch_size),
+            ("parquet_batch_size", self.parquet_batch_size),
+            ("parquet_readahead", self.parquet_readahead),
+            ("memory_budget", self.memory_budget.unwrap_or(1)),
+            (
+                "parquet_footer_cache_size",
+                self.parquet_footer_cache_size.unwrap_or(1),
+            ),
+            ("storage_readahead", self.storage_readahead),
+            ("list_buffer_size", self.list_buffer_size),
+        ];
+        match sizes.iter().find(|(_, size)| *size == 0) {
+            Some((key, _)) => Err(Error::generic(format!(
+                "Invalid engine config: {key} must be greater than zero"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_by_name() {
+        let mut config = DefaultEngineConfig::default();
+        for key in DefaultEngineConfig::KEYS {
+            config.set(key, "7").unwrap();
+        }
+        let expected = DefaultEngineConfig::default()
+            .with_json_buffer_size(7)
+            .with_json_batch_size(7)
+            .with_parquet_batch_size(7)
+            .with_parquet_readahead(7)
+            .with_memory_budget(7)
+            .with_parquet_footer_cache_size(7)
+            .with_storage_readahead(7)
+            .with_list_buffer_size(7);
+        assert_eq!(config, expected);
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_set_rejects_invalid() {
+        let mut config = DefaultEngineConfig::default();
+        for value in ["0", "-1", "ten", ""] {
+            assert!(config.set("json_batch_size", value).is_err(), "{value:?}");
+        }
+        assert!(config.set("request_timeout", "10").is_err());
+        assert_eq!(config, DefaultEngineConfig::default());
+    }
+
+    #[test]
+    fn test_validate() {
+        DefaultEngineConfig::default().validate().unwrap();
+        let config = DefaultEngineConfig::default().with_parquet_batch_size(0);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("parquet_batch_size"), "{err}");
+        assert!(DefaultEngineConfig::default()
+            .with_memory_budget(0)
+            .validate()
+            .is_err());
+    }
+}
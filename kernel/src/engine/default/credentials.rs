@@ -0,0 +1,299 @@
+//! Temporary storage credentials which expire, e.g. the table credentials vended by a catalog.
+//!
+//! An engine created with [`DefaultEngine::new_with_credential_provider`] asks its
+//! [`CredentialProvider`] for fresh credentials shortly before the current ones expire, and
+//! rebuilds the table's object store with them. The provider is called on a background thread, so
+//! requests are not blocked while it runs. Every file read, written or listed after the refresh
+//! uses the new credentials, so long scans and streaming readers outlive any single set of
+//! credentials.
+//!
+//! [`DefaultEngine::new_with_credential_provider`]: super::DefaultEngine::new_with_credential_provider
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+
+use tracing::warn;
+use url::Url;
+
+use super::storage::parse_url_opts;
+use crate::clock::KernelClock;
+use crate::object_store::ObjectStore;
+use crate::DeltaResult;
+
+/// Credentials are refreshed this long (in milliseconds) before they expire, so requests started
+/// just before the expiration time don't fail. Credentials valid for less than twice this long are
+/// refreshed halfway through their lifetime instead.
+const REFRESH_MARGIN_MILLIS: i64 = 5 * 60 * 1000;
+
+/// Storage options (see [`parse_url_opts`]) holding temporary credentials, and when they expire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemporaryCredentials {
+    options: HashMap<String, String>,
+    expiration_time: Option<i64>,
+}
+
+impl TemporaryCredentials {
+    /// Credentials given by the object store `options` (e.g. `aws_session_token`), which never
+    /// expire unless an expiration time is set with [`Self::with_expiration_time`].
+    pub fn new<K, V>(options: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let options = options
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.into()))
+            .collect();
+        Self {
+            options,
+            expiration_time: None,
+        }
+    }
+
+    /// Set the time the credentials expire, in milliseconds since the Unix epoch.
+    pub fn with_expiration_time(mut self, expiration_time: i64) -> Self {
+        self.expiration_time = Some(expiration_time);
+        self
+    }
+
+    /// The object store options holding the credentials.
+    pub fn options(&self) -> &HashMap<String, String> {
+        &self.options
+    }
+
+    /// The time the credentials expire, in milliseconds since the Unix epoch, if they do.
+    pub fn expiration_time(&self) -> Option<i64> {
+        self.expiration_time
+    }
+}
+
+/// Fetches (new) [`TemporaryCredentials`] for a table's storage, e.g. from a catalog. Any
+/// `Fn() -> DeltaResult<TemporaryCredentials>` closure is a credential provider.
+pub trait CredentialProvider: Send + Sync {
+    /// Fetch credentials which are valid now.
+    fn fetch_credentials(&self) -> DeltaResult<TemporaryCredentials>;
+}
+
+impl<F> CredentialProvider for F
+where
+    F: Fn() -> DeltaResult<TemporaryCredentials> + Send + Sync,
+{
+    fn fetch_credentials(&self) -> DeltaResult<TemporaryCredentials> {
+        self()
+    }
+}
+
+/// The object store for a table, rebuilt with fresh credentials from a [`CredentialProvider`]
+/// whenever the current credentials are about to expire. Credentials are fetched in the
+/// background, so requests never wait for the provider.
+pub(crate) struct RefreshingObjectStore {
+    shared: Arc<SharedStore>,
+    // the latest background refresh, if any
+    refresh_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct SharedStore {
+    table_root: Url,
+    provider: Arc<dyn CredentialProvider>,
+    clock: Arc<dyn KernelClock>,
+    current: RwLock<CurrentStore>,
+    // whether a background refresh is in progress
+    refreshing: AtomicBool,
+}
+
+struct CurrentStore {
+    store: Arc<dyn ObjectStore>,
+    // when to refresh the credentials, in milliseconds since the Unix epoch
+    refresh_time: Option<i64>,
+}
+
+impl RefreshingObjectStore {
+    /// Fetch the initial credentials from `provider` and build the store for `table_root`.
+    pub(crate) fn try_new(
+        table_root: &Url,
+        provider: Arc<dyn CredentialProvider>,
+        clock: Arc<dyn KernelClock>,
+    ) -> DeltaResult<Self> {
+        let current = build_store(table_root, clock.as_ref(), provider.fetch_credentials()?)?;
+        let shared = SharedStore {
+            table_root: table_root.clone(),
+            provider,
+            clock,
+            current: RwLock::new(current),
+            refreshing: AtomicBool::new(false),
+        };
+        Ok(Self {
+            shared: Arc::new(shared),
+            refresh_task: Mutex::new(None),
+        })
+    }
+
+    /// The store to use for the next request. If the credentials expire soon, a refresh is started
+    /// in the background and the current store is returned. If the refresh fails the current
+    /// store is kept, and requests fail once the credentials have expired.
+    pub(crate) fn store(&self) -> Arc<dyn ObjectStore> {
+        // a poisoned lock only means a refresh panicked; the current store is still usable
+        let current = self
+            .shared
+            .current
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        let store = current.store.clone();
+        let needs_refresh = self.shared.needs_refresh(&current);
+        drop(current);
+
+        // only one refresh runs at a time
+        if needs_refresh && !self.shared.refreshing.swap(true, Ordering::AcqRel) {
+            let shared = self.shared.clone();
+            let task = std::thread::spawn(move || shared.refresh());
+            *self.refresh_task.lock().unwrap_or_else(|e| e.into_inner()) = Some(task);
+        }
+        store
+    }
+
+    /// Wait for the background refresh started by [`Self::store`], if any.
+    #[cfg(test)]
+    fn wait_for_refresh(&self) {
+        let task = self.refresh_task.lock().unwrap().take();
+        if let Some(task) = task {
+            task.join().unwrap();
+        }
+    }
+}
+
+impl SharedStore {
+    fn needs_refresh(&self, current: &CurrentStore) -> bool {
+        let Some(refresh_time) = current.refresh_time else {
+            return false;
+        };
+        // if the clock fails, assume the credentials are still valid rather than refetching them
+        // on every request
+        self.clock.now_millis().is_ok_and(|now| now >= refresh_time)
+    }
+
+    fn refresh(&self) {
+        let refreshed = self.provider.fetch_credentials().and_then(|credentials| {
+            build_store(&self.table_root, self.clock.as_ref(), credentials)
+        });
+        match refreshed {
+            Ok(refreshed) => {
+                *self.current.write().unwrap_or_else(|e| e.into_inner()) = refreshed;
+            }
+            Err(err) => warn!(
+                "Failed to refresh credentials for {}: {err}",
+                self.table_root
+            ),
+        }
+        self.refreshing.store(false, Ordering::Release);
+    }
+}
+
+impl std::fmt::Debug for RefreshingObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RefreshingObjectStore")
+            .field("table_root", &self.shared.table_root)
+            .finish_non_exhaustive()
+    }
+}
+
+fn build_store(
+    table_root: &Url,
+    clock: &dyn KernelClock,
+    credentials: TemporaryCredentials,
+) -> DeltaResult<CurrentStore> {
+    let (store, _path) = parse_url_opts(table_root, credentials.options)?;
+    // credentials valid for less than twice the margin are refreshed halfway through their
+    // lifetime, rather than on every request
+    let refresh_time = credentials.expiration_time.map(|expiration_time| {
+        let fetch_time = clock.now_millis().unwrap_or(expiration_time);
+        let lifetime = expiration_time.saturating_sub(fetch_time).max(0);
+        expiration_time - REFRESH_MARGIN_MILLIS.min(lifetime / 2)
+    });
+    Ok(CurrentStore {
+        store: store.into(),
+        refresh_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::clock::FixedClock;
+
+    const NOW: i64 = 1_000_000_000;
+
+    // A store over `memory:///` whose provider counts its calls and hands out credentials that
+    // expire `expires_in` milliseconds from `NOW`
+    fn refreshing_store(expires_in: i64) -> (RefreshingObjectStore, Arc<AtomicUsize>) {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let provider = {
+            let fetches = fetches.clone();
+            move || {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                let options: [(&str, &str); 0] = [];
+                Ok(TemporaryCredentials::new(options).with_expiration_time(NOW + expires_in))
+            }
+        };
+        let store = RefreshingObjectStore::try_new(
+            &Url::parse("memory:///").unwrap(),
+            Arc::new(provider),
+            Arc::new(FixedClock::new(NOW)),
+        )
+        .unwrap();
+        (store, fetches)
+    }
+
+    #[test]
+    fn test_valid_credentials_are_reused() {
+        let (store, fetches) = refreshing_store(2 * REFRESH_MARGIN_MILLIS);
+        let first = store.store();
+        store.wait_for_refresh();
+        assert!(Arc::ptr_eq(&first, &store.store()));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_short_lived_credentials_are_reused() {
+        // refreshed halfway through their lifetime, not right away
+        let (store, fetches) = refreshing_store(REFRESH_MARGIN_MILLIS / 5);
+        let first = store.store();
+        store.wait_for_refresh();
+        assert!(Arc::ptr_eq(&first, &store.store()));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_expiring_credentials_are_refreshed() {
+        let (store, fetches) = refreshing_store(0);
+        // the refresh runs in the background, so the current store is returned meanwhile
+        let first = store.store();
+        store.wait_for_refresh();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        let second = store.store();
+        store.wait_for_refresh();
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_failed_refresh_keeps_store() {
+        let fetches = AtomicUsize::new(0);
+        let provider = move || match fetches.fetch_add(1, Ordering::SeqCst) {
+            0 => Ok(TemporaryCredentials::new([("k", "v")]).with_expiration_time(NOW)),
+            _ => Err(crate::Error::generic("catalog unavailable")),
+        };
+        let store = RefreshingObjectStore::try_new(
+            &Url::parse("memory:///").unwrap(),
+            Arc::new(provider),
+            Arc::new(FixedClock::new(NOW)),
+        )
+        .unwrap();
+        let first = store.store();
+        store.wait_for_refresh();
+        assert!(Arc::ptr_eq(&first, &store.store()));
+    }
+}
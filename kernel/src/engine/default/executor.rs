@@ -4,7 +4,9 @@
 //!
 //! A generic trait [TaskExecutor] can be implemented with your preferred async
 //! runtime. Behind the `tokio` feature flag, we provide a both a single-threaded
-//! and multi-threaded executor based on Tokio.
+//! and multi-threaded executor based on Tokio. For small (e.g. local) tables
+//! where starting an async runtime is not worth it, [SyncExecutor] runs
+//! futures without any runtime at all.
 use futures::{future::BoxFuture, Future};
 
 use crate::DeltaResult;
@@ -30,30 +32,180 @@ pub trait TaskExecutor: Send + Sync + 'static {
     where
         F: Future<Output = ()> + Send + 'static;
 
+    /// Run the future in the background, unless the executor is overloaded. Executors that shed
+    /// load return a [retryable](crate::Error::is_retryable) error instead of accepting the task.
+    ///
+    /// The default implementation never sheds load and just calls [`Self::spawn`].
+    fn try_spawn<F>(&self, task: F) -> DeltaResult<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn(task);
+        Ok(())
+    }
+
     fn spawn_blocking<T, R>(&self, task: T) -> BoxFuture<'_, DeltaResult<R>>
     where
         T: FnOnce() -> R + Send + 'static,
         R: Send + 'static;
 }
 
+/// A [`TaskExecutor`] that doesn't depend on an async runtime. Futures passed to
+/// `block_on` are driven to completion on the calling thread, and background
+/// tasks passed to `spawn` each get their own OS thread.
+///
+/// This avoids the overhead of starting a runtime, which makes it a good fit for
+/// CLI tools and other short-lived reads of small tables. Note that object stores
+/// which require a tokio runtime (e.g. cloud stores backed by `reqwest`) cannot be
+/// used with this executor; local files and in-memory stores work.
+#[derive(Debug, Default)]
+pub struct SyncExecutor;
+
+impl SyncExecutor {
+    /// Create an executor. No runtime or threads are started until a task is spawned.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TaskExecutor for SyncExecutor {
+    fn block_on<T>(&self, task: T) -> T::Output
+    where
+        T: Future + Send + 'static,
+        T::Output: Send + 'static,
+    {
+        futures::executor::block_on(task)
+    }
+
+    fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        // The handlers feed results back over bounded channels, so spawned tasks
+        // must not run on the caller's thread or they could block forever.
+        std::thread::spawn(move || futures::executor::block_on(task));
+    }
+
+    fn spawn_blocking<T, R>(&self, task: T) -> BoxFuture<'_, DeltaResult<R>>
+    where
+        T: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        // We are never on an async runtime's worker thread, so it is fine to block here.
+        Box::pin(async move { Ok(task()) })
+    }
+}
+
 #[cfg(any(feature = "tokio", test))]
 pub mod tokio {
     use super::TaskExecutor;
     use futures::TryFutureExt;
     use futures::{future::BoxFuture, Future};
     use std::sync::mpsc::channel;
+    use std::sync::{Arc, Condvar, Mutex};
     use tokio::runtime::RuntimeFlavor;
+    use tokio::sync::Semaphore;
 
-    use crate::DeltaResult;
+    use crate::{DeltaResult, Error};
 
     /// A [`TaskExecutor`] that uses the tokio single-threaded runtime in a
     /// background thread to service tasks.
+    ///
+    /// By default every spawned task runs immediately. Use [`Self::builder`] to bound the number
+    /// of tasks that run concurrently and the number of tasks waiting for their turn, e.g. when
+    /// many scans share one engine in a long-running service.
     #[derive(Debug)]
     pub struct TokioBackgroundExecutor {
         sender: tokio::sync::mpsc::Sender<BoxFuture<'static, ()>>,
+        limits: Arc<TaskLimits>,
         _thread: std::thread::JoinHandle<()>,
     }
 
+    #[derive(Debug)]
+    struct TaskLimits {
+        // one permit per task allowed to run concurrently
+        running: Arc<Semaphore>,
+        // tasks that were spawned but have not acquired a permit yet
+        queued: Mutex<usize>,
+        // notified whenever a queued task leaves the queue
+        dequeued: Condvar,
+        max_queued: usize,
+        shed_overload: bool,
+    }
+
+    impl TaskLimits {
+        /// Reserve a slot in the queue, waiting for one to free up unless `shed` is set.
+        fn reserve_queue_slot(&self, shed: bool) -> DeltaResult<()> {
+            let mut queued = self.queued.lock().unwrap();
+            while *queued >= self.max_queued {
+                if shed {
+                    return Err(Error::retryable(format!(
+                        "TokioBackgroundExecutor is overloaded: {queued} tasks are already waiting to run"
+                    )));
+                }
+                // Like `send_future`, we cannot block on an async primitive here.
+                queued = self.dequeued.wait(queued).unwrap();
+            }
+            *queued += 1;
+            Ok(())
+        }
+
+        /// Release a slot reserved by [`Self::reserve_queue_slot`], waking up one waiting spawner.
+        fn release_queue_slot(&self) {
+            *self.queued.lock().unwrap() -= 1;
+            self.dequeued.notify_one();
+        }
+    }
+
+    /// Builder for a [`TokioBackgroundExecutor`] with bounded parallelism.
+    #[derive(Debug, Default)]
+    pub struct TokioBackgroundExecutorBuilder {
+        max_concurrent_tasks: Option<usize>,
+        max_queued_tasks: Option<usize>,
+        shed_overload: bool,
+    }
+
+    impl TokioBackgroundExecutorBuilder {
+        /// Set the maximum number of spawned tasks (e.g. file reads) that run at the same time.
+        /// Further tasks wait in a queue until a running task completes. Unbounded by default.
+        ///
+        /// Note that a read task runs until its results are consumed (or dropped), so a caller
+        /// holding more open read iterators than this limit can wait forever on the last one.
+        pub fn with_max_concurrent_tasks(mut self, max_concurrent_tasks: usize) -> Self {
+            self.max_concurrent_tasks = Some(max_concurrent_tasks.max(1));
+            self
+        }
+
+        /// Set the maximum number of spawned tasks waiting to run. Once the queue is full,
+        /// spawning a task waits for a free slot, or fails if overload shedding is enabled.
+        /// Unbounded by default.
+        pub fn with_max_queued_tasks(mut self, max_queued_tasks: usize) -> Self {
+            self.max_queued_tasks = Some(max_queued_tasks);
+            self
+        }
+
+        /// When enabled, reads started while the task queue is full fail with a
+        /// [retryable](crate::Error::is_retryable) error instead of waiting. Disabled by default.
+        pub fn with_overload_shedding(mut self, shed_overload: bool) -> Self {
+            self.shed_overload = shed_overload;
+            self
+        }
+
+        /// Build the executor, starting its background thread.
+        pub fn build(self) -> TokioBackgroundExecutor {
+            let limits = TaskLimits {
+                running: Arc::new(Semaphore::new(
+                    self.max_concurrent_tasks.unwrap_or(Semaphore::MAX_PERMITS),
+                )),
+                queued: Mutex::new(0),
+                dequeued: Condvar::new(),
+                max_queued: self.max_queued_tasks.unwrap_or(usize::MAX),
+                shed_overload: self.shed_overload,
+            };
+            TokioBackgroundExecutor::with_limits(limits)
+        }
+    }
+
     impl Default for TokioBackgroundExecutor {
         fn default() -> Self {
             Self::new()
@@ -61,7 +213,17 @@ pub mod tokio {
     }
 
     impl TokioBackgroundExecutor {
+        /// Create an executor that runs every spawned task immediately.
         pub fn new() -> Self {
+            Self::builder().build()
+        }
+
+        /// Create a builder for an executor that limits how many tasks run and wait to run.
+        pub fn builder() -> TokioBackgroundExecutorBuilder {
+            TokioBackgroundExecutorBuilder::default()
+        }
+
+        fn with_limits(limits: TaskLimits) -> Self {
             let (sender, mut receiver) = tokio::sync::mpsc::channel::<BoxFuture<'_, ()>>(50);
             let thread = std::thread::spawn(move || {
                 let rt = tokio::runtime::Builder::new_current_thread()
@@ -76,29 +238,50 @@ pub mod tokio {
             });
             Self {
                 sender,
+                limits: Arc::new(limits),
                 _thread: thread,
             }
         }
     }
 
     impl TokioBackgroundExecutor {
-        fn send_future(&self, fut: BoxFuture<'static, ()>) {
+        fn send_future(&self, fut: BoxFuture<'static, ()>) -> DeltaResult<()> {
             // We cannot call `blocking_send()` because that calls `block_on`
             // internally and panics if called within an async context. 🤦
             let mut fut = Some(fut);
             loop {
                 match self.sender.try_send(fut.take().unwrap()) {
-                    Ok(()) => break,
+                    Ok(()) => return Ok(()),
                     Err(tokio::sync::mpsc::error::TrySendError::Full(original)) => {
                         std::thread::yield_now();
                         fut.replace(original);
                     }
                     Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
-                        panic!("TokioBackgroundExecutor channel closed")
+                        return Err(Error::generic("TokioBackgroundExecutor channel closed"))
                     }
                 };
             }
         }
+
+        // Queue a task that only starts running once it gets one of the concurrency permits.
+        fn send_limited<F>(&self, task: F, shed: bool) -> DeltaResult<()>
+        where
+            F: Future<Output = ()> + Send + 'static,
+        {
+            self.limits.reserve_queue_slot(shed)?;
+            let limits = self.limits.clone();
+            let sent = self.send_future(Box::pin(async move {
+                // The semaphore is never closed, so acquiring can't fail
+                let permit = limits.running.clone().acquire_owned().await.ok();
+                limits.release_queue_slot();
+                task.await;
+                drop(permit);
+            }));
+            if sent.is_err() {
+                self.limits.release_queue_slot();
+            }
+            sent
+        }
     }
 
     impl TaskExecutor for TokioBackgroundExecutor {
@@ -121,7 +304,8 @@ pub mod tokio {
                 .unwrap();
             });
 
-            self.send_future(fut);
+            self.send_future(fut)
+                .expect("TokioBackgroundExecutor has crashed");
 
             receiver
                 .recv()
@@ -132,7 +316,16 @@ pub mod tokio {
         where
             F: Future<Output = ()> + Send + 'static,
         {
-            self.send_future(Box::pin(task));
+            // Without shedding, this only fails if the background thread is gone
+            self.send_limited(task, false)
+                .expect("TokioBackgroundExecutor has crashed");
+        }
+
+        fn try_spawn<F>(&self, task: F) -> DeltaResult<()>
+        where
+            F: Future<Output = ()> + Send + 'static,
+        {
+            self.send_limited(task, self.limits.shed_overload)
         }
 
         fn spawn_blocking<T, R>(&self, task: T) -> BoxFuture<'_, DeltaResult<R>>
@@ -236,6 +429,85 @@ pub mod tokio {
             test_executor(executor).await;
         }
 
+        #[test]
+        fn test_tokio_background_executor_sheds_overload() {
+            let executor = TokioBackgroundExecutor::builder()
+                .with_max_concurrent_tasks(1)
+                .with_max_queued_tasks(1)
+                .with_overload_shedding(true)
+                .build();
+
+            // The first task runs and holds the only permit until released
+            let (started_tx, started_rx) = channel::<i32>();
+            let (release_tx, release_rx) = futures::channel::oneshot::channel::<()>();
+            let tx = started_tx.clone();
+            executor
+                .try_spawn(async move {
+                    tx.send(1).unwrap();
+                    release_rx.await.unwrap();
+                })
+                .unwrap();
+            assert_eq!(started_rx.recv().unwrap(), 1);
+
+            // The second task waits in the queue, which is now full
+            let tx = started_tx.clone();
+            executor
+                .try_spawn(async move { tx.send(2).unwrap() })
+                .unwrap();
+            let err = executor.try_spawn(async {}).unwrap_err();
+            assert!(err.is_retryable(), "{err}");
+
+            // Once the first task completes, the queued task runs and frees the queue
+            release_tx.send(()).unwrap();
+            assert_eq!(started_rx.recv().unwrap(), 2);
+            let tx = started_tx.clone();
+            executor
+                .try_spawn(async move { tx.send(3).unwrap() })
+                .unwrap();
+            assert_eq!(started_rx.recv().unwrap(), 3);
+        }
+
+        #[test]
+        fn test_tokio_background_executor_waits_for_queue_slot() {
+            let executor = Arc::new(
+                TokioBackgroundExecutor::builder()
+                    .with_max_concurrent_tasks(1)
+                    .with_max_queued_tasks(1)
+                    .build(),
+            );
+
+            // Fill the only permit and the only queue slot
+            let (started_tx, started_rx) = channel::<i32>();
+            let (release_tx, release_rx) = futures::channel::oneshot::channel::<()>();
+            let tx = started_tx.clone();
+            executor.spawn(async move {
+                tx.send(1).unwrap();
+                release_rx.await.unwrap();
+            });
+            assert_eq!(started_rx.recv().unwrap(), 1);
+            let tx = started_tx.clone();
+            executor.spawn(async move { tx.send(2).unwrap() });
+
+            // Spawning another task waits until the queued task starts running
+            let spawner = std::thread::spawn({
+                let executor = executor.clone();
+                move || executor.spawn(async move { started_tx.send(3).unwrap() })
+            });
+            release_tx.send(()).unwrap();
+            spawner.join().unwrap();
+            assert_eq!(started_rx.recv().unwrap(), 2);
+            assert_eq!(started_rx.recv().unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_tokio_background_executor_bounded() {
+            let executor = TokioBackgroundExecutor::builder()
+                .with_max_concurrent_tasks(2)
+                .with_max_queued_tasks(4)
+                .build();
+            test_executor(executor).await;
+        }
+
         #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
         async fn test_tokio_multi_thread_executor() {
             let executor = TokioMultiThreadExecutor::new(tokio::runtime::Handle::current());
@@ -243,3 +515,32 @@ pub mod tokio {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_sync_executor() {
+        let executor = SyncExecutor::new();
+
+        // Can run a task
+        let result = executor.block_on(async { 2 + 2 });
+        assert_eq!(result, 4);
+
+        // Can spawn a task
+        let (sender, receiver) = channel::<i32>();
+        executor.spawn(async move {
+            sender.send(2 + 2).unwrap();
+        });
+        assert_eq!(receiver.recv().unwrap(), 4);
+
+        // Can run a blocking task
+        let result = executor.block_on(async move {
+            let fut = SyncExecutor.spawn_blocking(|| 2 + 2);
+            fut.await
+        });
+        assert_eq!(result.unwrap(), 4);
+    }
+}
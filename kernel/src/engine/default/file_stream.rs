@@ -2,7 +2,7 @@ use std::collections::VecDeque;
 use std::mem;
 use std::ops::Range;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
 use std::task::{ready, Context, Poll};
 
 use crate::arrow::array::RecordBatch;
@@ -13,7 +13,7 @@ use futures::FutureExt;
 
 use super::executor::TaskExecutor;
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::{DeltaResult, FileDataReadResultIterator, FileMeta};
+use crate::{DeltaResult, EngineData, FileDataReadResultIterator, FileMeta};
 
 /// A fallible future that resolves to a stream of [`RecordBatch`]
 /// cbindgen:ignore
@@ -26,25 +26,22 @@ pub type FileOpenFuture =
 /// [`ObjectStore`]: crate::object_store::ObjectStore
 pub trait FileOpener: Send + Unpin {
     /// Asynchronously open the specified file and return a stream
-    /// of [`RecordBatch`]
+    /// of [`RecordBatch`]. If a byte `range` is given, only the part of the file within it is
+    /// read (for parquet files, the row groups which start within it).
     fn open(&self, file_meta: FileMeta, range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture>;
 }
 
 /// Describes the behavior of the `FileStream` if file opening or scanning fails
 #[allow(missing_debug_implementations)]
+#[derive(Default)]
 pub enum OnError {
     /// Fail the entire stream and return the underlying error
+    #[default]
     Fail,
     /// Continue scanning, ignoring the failed file
     Skip,
 }
 
-impl Default for OnError {
-    fn default() -> Self {
-        Self::Fail
-    }
-}
-
 /// Represents the state of the next `FileOpenFuture`. Since we need to poll
 /// this future while scanning the current file, we need to store the result if it
 /// is ready
@@ -77,11 +74,78 @@ enum FileStreamState {
     Error,
 }
 
+/// Bounds the number of bytes of batches buffered between a background producer and the consumer
+/// of a [`FileStream`]. The producer [acquires](Self::acquire) the size of each batch before
+/// handing it over, and the consumer [releases](Self::release) it once it receives the batch.
+#[derive(Debug)]
+struct MemoryBudget {
+    limit: usize,
+    state: Mutex<MemoryBudgetState>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct MemoryBudgetState {
+    used: usize,
+    closed: bool,
+}
+
+impl MemoryBudget {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: Mutex::default(),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Block until `bytes` fit in the budget, then claim them. Always succeeds immediately when
+    /// nothing is buffered, so that a batch larger than the whole budget cannot stall the stream.
+    /// Returns false if the consumer went away while waiting.
+    fn acquire(&self, bytes: usize) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        while !state.closed && !self.fits(&state, bytes) {
+            state = self
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        state.used = state.used.saturating_add(bytes);
+        !state.closed
+    }
+
+    /// Whether `bytes` can be claimed without waiting for the consumer.
+    fn fits(&self, state: &MemoryBudgetState, bytes: usize) -> bool {
+        state.used == 0 || state.used.saturating_add(bytes) <= self.limit
+    }
+
+    fn release(&self, bytes: usize) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.used = state.used.saturating_sub(bytes);
+        self.changed.notify_all();
+    }
+
+    fn close(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.closed = true;
+        self.changed.notify_all();
+    }
+}
+
+/// Closes the [`MemoryBudget`] when the consumer is dropped.
+struct MemoryBudgetGuard(Arc<MemoryBudget>);
+
+impl Drop for MemoryBudgetGuard {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
 /// A stream that iterates record batch by record batch, file over file.
 #[allow(missing_debug_implementations)]
 pub struct FileStream {
-    /// An iterator over input files.
-    file_iter: VecDeque<FileMeta>,
+    /// An iterator over input files, and the byte range of each to read (if not all of it).
+    file_iter: VecDeque<(FileMeta, Option<Range<i64>>)>,
     /// The stream schema (file schema including partition columns and after
     /// projection).
     #[allow(unused)]
@@ -101,41 +165,78 @@ impl FileStream {
     /// Creates a new `FileStream` from a given schema, `FileOpener`, and files list; the files are
     /// processed asynchronously by the provided `TaskExecutor`. Returns an `Iterator` that consumes
     /// the results.
+    ///
+    /// At most `readahead` batches are buffered ahead of the consumer. If `memory_budget` is set,
+    /// the producer additionally pauses while the buffered batches hold more than that many bytes
+    /// (see [`EngineData::approximate_memory_usage`]). A single batch larger than the budget is
+    /// still produced once nothing else is buffered.
     pub fn new_async_read_iterator<E: TaskExecutor>(
         task_executor: Arc<E>,
         schema: ArrowSchemaRef,
         file_opener: Box<dyn FileOpener>,
         files: &[FileMeta],
         readahead: usize,
+        memory_budget: Option<usize>,
     ) -> DeltaResult<FileDataReadResultIterator> {
-        let mut stream = FileStream::new(files.to_vec(), schema, file_opener)?;
+        FileStream::new(files.to_vec(), schema, file_opener)?.into_async_read_iterator(
+            task_executor,
+            readahead,
+            memory_budget,
+        )
+    }
+
+    /// Process this stream asynchronously on the provided `TaskExecutor`, returning an `Iterator`
+    /// that consumes the results. See [`Self::new_async_read_iterator`].
+    pub fn into_async_read_iterator<E: TaskExecutor>(
+        self,
+        task_executor: Arc<E>,
+        readahead: usize,
+        memory_budget: Option<usize>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let mut stream = self;
 
         // This channel will become the output iterator
         // The stream will execute in the background, and we allow up to `readahead`
         // batches to be buffered in the channel.
         let (sender, receiver) = std::sync::mpsc::sync_channel(readahead);
+        let budget = memory_budget.map(|limit| Arc::new(MemoryBudget::new(limit)));
 
         let executor_for_block = task_executor.clone();
-        task_executor.spawn(async move {
+        let producer_budget = budget.clone();
+        task_executor.try_spawn(async move {
             while let Some(res) = stream.next().await {
                 let sender = sender.clone();
+                let budget = producer_budget.clone();
                 let join_res = executor_for_block
-                    .spawn_blocking(move || sender.send(res))
+                    .spawn_blocking(move || {
+                        let res = res.map(ArrowEngineData::new);
+                        let bytes = res
+                            .as_ref()
+                            .map_or(0, |data| data.approximate_memory_usage());
+                        if budget.is_some_and(|budget| !budget.acquire(bytes)) {
+                            return false;
+                        }
+                        sender.send((res, bytes)).is_ok()
+                    })
                     .await;
                 match join_res {
-                    Ok(send_res) => match send_res {
-                        Ok(()) => continue,
-                        Err(_) => break,
-                    },
+                    Ok(true) => continue,
+                    Ok(false) => break,
                     Err(je) => {
                         panic!("Couldn't join spawned task, runtime is likely in bad state: {je}")
                     }
                 }
             }
-        });
+        })?;
 
-        Ok(Box::new(receiver.into_iter().map(|rbr| {
-            rbr.map(|rb| Box::new(ArrowEngineData::new(rb)) as _)
+        // The guard is owned by the returned iterator, and releases a producer waiting on the
+        // budget once the iterator is dropped.
+        let guard = budget.map(MemoryBudgetGuard);
+        Ok(Box::new(receiver.into_iter().map(move |(res, bytes)| {
+            if let Some(MemoryBudgetGuard(budget)) = &guard {
+                budget.release(bytes);
+            }
+            res.map(|data| Box::new(data) as _)
         })))
     }
 
@@ -144,6 +245,17 @@ impl FileStream {
         files: impl IntoIterator<Item = FileMeta>,
         schema: ArrowSchemaRef,
         file_opener: Box<dyn FileOpener>,
+    ) -> DeltaResult<Self> {
+        let files = files.into_iter().map(|file| (file, None));
+        Self::new_with_ranges(files, schema, file_opener)
+    }
+
+    /// Like [`Self::new`], but only scans the given byte range of each file that has one (see
+    /// [`FileOpener::open`]).
+    pub fn new_with_ranges(
+        files: impl IntoIterator<Item = (FileMeta, Option<Range<i64>>)>,
+        schema: ArrowSchemaRef,
+        file_opener: Box<dyn FileOpener>,
     ) -> DeltaResult<Self> {
         Ok(Self {
             file_iter: files.into_iter().collect(),
@@ -168,8 +280,8 @@ impl FileStream {
     /// Since file opening is mostly IO (and may involve a
     /// bunch of sequential IO), it can be parallelized with decoding.
     fn start_next_file(&mut self) -> Option<DeltaResult<FileOpenFuture>> {
-        let file_meta = self.file_iter.pop_front()?;
-        Some(self.file_opener.open(file_meta, None))
+        let (file_meta, range) = self.file_iter.pop_front()?;
+        Some(self.file_opener.open(file_meta, range))
     }
 
     fn poll_inner(&mut self, cx: &mut Context<'_>) -> Poll<Option<DeltaResult<RecordBatch>>> {
@@ -275,3 +387,41 @@ impl Stream for FileStream {
         self.poll_inner(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn fits(budget: &MemoryBudget, bytes: usize) -> bool {
+        budget.fits(&budget.state.lock().unwrap(), bytes)
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let budget = Arc::new(MemoryBudget::new(100));
+        // a batch larger than the budget is admitted when nothing else is buffered
+        assert!(budget.acquire(150));
+        budget.release(150);
+        assert!(budget.acquire(60));
+        assert!(budget.acquire(40));
+
+        // the next acquire waits until the consumer releases enough bytes
+        assert!(!fits(&budget, 50));
+        let producer = thread::spawn({
+            let budget = budget.clone();
+            move || budget.acquire(50)
+        });
+        budget.release(60);
+        assert!(producer.join().unwrap());
+
+        // closing the budget releases a waiting producer
+        let producer = thread::spawn({
+            let budget = budget.clone();
+            move || budget.acquire(100)
+        });
+        drop(MemoryBudgetGuard(budget));
+        assert!(!producer.join().unwrap());
+    }
+}
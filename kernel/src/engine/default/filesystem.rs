@@ -9,32 +9,63 @@ use url::Url;
 use crate::object_store::path::Path;
 use crate::object_store::{DynObjectStore, ObjectStore};
 
+use super::storage::ObjectStoreRegistry;
 use super::UrlExt;
 use crate::engine::default::executor::TaskExecutor;
 use crate::{DeltaResult, Error, FileMeta, FileSlice, StorageHandler};
 
+/// The default number of listed files to buffer ahead of the consumer in
+/// [`StorageHandler::list_from`].
+pub(crate) const DEFAULT_LIST_BUFFER_SIZE: usize = 4_000;
+
+/// The default maximum number of files to read in parallel in [`StorageHandler::read_files`].
+pub(crate) const DEFAULT_READAHEAD: usize = 10;
+
 #[derive(Debug)]
 pub struct ObjectStoreStorageHandler<E: TaskExecutor> {
-    inner: Arc<DynObjectStore>,
+    inner: Arc<ObjectStoreRegistry>,
     task_executor: Arc<E>,
     readahead: usize,
+    list_buffer_size: usize,
 }
 
 impl<E: TaskExecutor> ObjectStoreStorageHandler<E> {
     #[internal_api]
+    #[allow(dead_code)] // only used in tests and with internal-api
     pub(crate) fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
+        Self::new_with_registry(Arc::new(ObjectStoreRegistry::new(store)), task_executor)
+    }
+
+    pub(crate) fn new_with_registry(
+        stores: Arc<ObjectStoreRegistry>,
+        task_executor: Arc<E>,
+    ) -> Self {
         Self {
-            inner: store,
+            inner: stores,
             task_executor,
-            readahead: 10,
+            readahead: DEFAULT_READAHEAD,
+            list_buffer_size: DEFAULT_LIST_BUFFER_SIZE,
         }
     }
 
     /// Set the maximum number of files to read in parallel.
+    ///
+    /// Defaults to 10.
     pub fn with_readahead(mut self, readahead: usize) -> Self {
         self.readahead = readahead;
         self
     }
+
+    /// Set the maximum number of listed files to buffer ahead of the consumer when listing (e.g.
+    /// the `_delta_log` directory). Listing pages are fetched from storage in the background until
+    /// the buffer is full, so this bounds both memory usage and how far listing runs ahead of log
+    /// replay.
+    ///
+    /// Defaults to 4000.
+    pub fn with_list_buffer_size(mut self, list_buffer_size: usize) -> Self {
+        self.list_buffer_size = list_buffer_size;
+        self
+    }
 }
 
 impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
@@ -59,7 +90,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
             Path::from_iter(parts)
         };
 
-        let store = self.inner.clone();
+        let store = self.inner.get_store(path);
 
         // HACK to check if we're using a LocalFileSystem from ObjectStore. We need this because
         // local filesystem doesn't return a sorted list by default. Although the `object_store`
@@ -82,9 +113,9 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         let has_ordered_listing = path.scheme() != "file";
 
         // This channel will become the iterator
-        let (sender, receiver) = std::sync::mpsc::sync_channel(4_000);
+        let (sender, receiver) = std::sync::mpsc::sync_channel(self.list_buffer_size);
         let url = path.clone();
-        self.task_executor.spawn(async move {
+        self.task_executor.try_spawn(async move {
             let mut stream = store.list_with_offset(Some(&prefix), &offset);
 
             while let Some(meta) = stream.next().await {
@@ -108,7 +139,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
                     }
                 }
             }
-        });
+        })?;
 
         if !has_ordered_listing {
             // This FS doesn't return things in the order we require
@@ -129,15 +160,15 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
     fn read_files(
         &self,
         files: Vec<FileSlice>,
-    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
-        let store = self.inner.clone();
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>> + Send>> {
+        let stores = self.inner.clone();
 
         // This channel will become the output iterator.
         // Because there will already be buffering in the stream, we set the
         // buffer size to 0.
         let (sender, receiver) = std::sync::mpsc::sync_channel(0);
 
-        self.task_executor.spawn(
+        self.task_executor.try_spawn(
             futures::stream::iter(files)
                 .map(move |(url, range)| {
                     // Wasn't checking the scheme before calling to_file_path causing the url path to
@@ -150,7 +181,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
                     } else {
                         Path::from(url.path())
                     };
-                    let store = store.clone();
+                    let store = stores.get_store(&url);
                     async move {
                         if url.is_presigned() {
                             // have to annotate type here or rustc can't figure it out
@@ -179,7 +210,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
                     sender.send(res).ok();
                     futures::future::ready(())
                 }),
-        );
+        )?;
 
         Ok(Box::new(receiver.into_iter()))
     }
@@ -308,4 +339,29 @@ mod tests {
         }
         assert_eq!(len, 10, "list_from should have returned 10 files");
     }
+
+    #[tokio::test]
+    async fn test_listing_with_list_buffer_size() {
+        let store = Arc::new(InMemory::new());
+        let expected_names: Vec<Path> =
+            (0..10).map(|i| delta_path_for_version(i, "json")).collect();
+        for name in &expected_names {
+            store
+                .put(name, Bytes::from("kernel-data").into())
+                .await
+                .unwrap();
+        }
+
+        let url = Url::parse("memory:///_delta_log/0").unwrap();
+        let storage =
+            ObjectStoreStorageHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+                .with_list_buffer_size(1);
+        let names: Vec<_> = storage
+            .list_from(&url)
+            .unwrap()
+            .map_ok(|file| Path::from_url_path(file.location.path()).unwrap())
+            .try_collect()
+            .unwrap();
+        assert_eq!(names, expected_names);
+    }
 }
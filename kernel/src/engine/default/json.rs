@@ -1,38 +1,42 @@
 //! Default Json handler implementation
 
-use std::io::BufReader;
+use std::borrow::Cow;
+use std::io::{BufReader, Cursor};
 use std::ops::Range;
 use std::sync::{mpsc, Arc};
 use std::task::Poll;
 
 use crate::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use crate::arrow::json::reader::Decoder;
 use crate::arrow::json::ReaderBuilder;
 use crate::arrow::record_batch::RecordBatch;
 use crate::object_store::path::Path;
 use crate::object_store::{self, DynObjectStore, GetResultPayload, PutMode};
 use bytes::{Buf, Bytes};
-use futures::stream::{self, BoxStream};
+use futures::stream::{self, BoxStream, Stream};
 use futures::{ready, StreamExt, TryStreamExt};
 use tracing::warn;
 use url::Url;
 
 use super::executor::TaskExecutor;
+use super::storage::ObjectStoreRegistry;
 use crate::engine::arrow_conversion::TryFromKernel as _;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::parse_json as arrow_parse_json;
-use crate::engine::arrow_utils::to_json_bytes;
+use crate::engine::arrow_utils::parse_json_stats as arrow_parse_json_stats;
+use crate::engine::arrow_utils::{skip_utf8_bom, to_json_bytes, UTF8_BOM};
 use crate::schema::SchemaRef;
 use crate::{
     DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, JsonHandler, PredicateRef,
 };
 
-const DEFAULT_BUFFER_SIZE: usize = 1000;
-const DEFAULT_BATCH_SIZE: usize = 1000;
+pub(crate) const DEFAULT_BUFFER_SIZE: usize = 1000;
+pub(crate) const DEFAULT_BATCH_SIZE: usize = 1000;
 
 #[derive(Debug)]
 pub struct DefaultJsonHandler<E: TaskExecutor> {
-    /// The object store to read files from
-    store: Arc<DynObjectStore>,
+    /// The object stores to read files from
+    stores: Arc<ObjectStoreRegistry>,
     /// The executor to run async tasks on
     task_executor: Arc<E>,
     /// The maximum number of read requests to buffer in memory at once. Note that this actually
@@ -42,15 +46,24 @@ pub struct DefaultJsonHandler<E: TaskExecutor> {
     /// Limit the number of rows per batch. That is, for batch_size = N, then each RecordBatch
     /// yielded by the stream will have at most N rows.
     batch_size: usize,
+    /// Whether to replace invalid UTF-8 in files with U+FFFD instead of failing the read.
+    lossy_utf8: bool,
 }
 
 impl<E: TaskExecutor> DefaultJsonHandler<E> {
     pub fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
+        Self::new_with_registry(Arc::new(ObjectStoreRegistry::new(store)), task_executor)
+    }
+
+    /// Create a handler which reads and writes each file using the store registered for its URL
+    /// in `stores`.
+    pub fn new_with_registry(stores: Arc<ObjectStoreRegistry>, task_executor: Arc<E>) -> Self {
         Self {
-            store,
+            stores,
             task_executor,
             buffer_size: DEFAULT_BUFFER_SIZE,
             batch_size: DEFAULT_BATCH_SIZE,
+            lossy_utf8: false,
         }
     }
 
@@ -81,6 +94,46 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
         self.batch_size = batch_size;
         self
     }
+
+    /// Replace invalid UTF-8 sequences in the files read by [Self::read_json_files()] with the
+    /// replacement character (U+FFFD) instead of failing the read. This allows reading logs where
+    /// a writer put e.g. a Latin-1 encoded string in a `commitInfo`, at the risk of silently
+    /// altering string values. Files are read fully into memory in this mode.
+    ///
+    /// Defaults to false.
+    pub fn with_lossy_utf8(mut self, lossy_utf8: bool) -> Self {
+        self.lossy_utf8 = lossy_utf8;
+        self
+    }
+
+    // A stream over the batches read from `files`, opening up to `buffer_size` files at a time.
+    fn read_stream(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<Box<dyn EngineData>>>> {
+        let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
+        let file_opener = Arc::new(
+            JsonOpener::new_with_registry(self.batch_size, schema, self.stores.clone())
+                .with_lossy_utf8(self.lossy_utf8),
+        );
+
+        // an iterator of futures that open each file
+        let files = files.to_vec();
+        let file_futures = files.into_iter().map(move |file| {
+            let file_opener = file_opener.clone();
+            async move { file_opener.open(file, None).await }
+        });
+
+        // create a stream from that iterator which buffers up to `buffer_size` futures at a time
+        let stream = stream::iter(file_futures)
+            .buffered(self.buffer_size)
+            .try_flatten()
+            .map_ok(|record_batch| -> Box<dyn EngineData> {
+                Box::new(ArrowEngineData::new(record_batch))
+            });
+        Ok(stream.boxed())
+    }
 }
 
 impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
@@ -92,6 +145,14 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
         arrow_parse_json(json_strings, output_schema)
     }
 
+    fn parse_json_stats(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        arrow_parse_json_stats(json_strings, output_schema)
+    }
+
     fn read_json_files(
         &self,
         files: &[FileMeta],
@@ -102,32 +163,18 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
             return Ok(Box::new(std::iter::empty()));
         }
 
-        let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
-        let file_opener = JsonOpener::new(self.batch_size, schema.clone(), self.store.clone());
-
+        let mut stream = self.read_stream(files, physical_schema)?;
         let (tx, rx) = mpsc::sync_channel(self.buffer_size);
-        let files = files.to_vec();
-        let buffer_size = self.buffer_size;
-
-        self.task_executor.spawn(async move {
-            // an iterator of futures that open each file
-            let file_futures = files.into_iter().map(|file| file_opener.open(file, None));
-
-            // create a stream from that iterator which buffers up to `buffer_size` futures at a time
-            let mut stream = stream::iter(file_futures)
-                .buffered(buffer_size)
-                .try_flatten()
-                .map_ok(|record_batch| -> Box<dyn EngineData> {
-                    Box::new(ArrowEngineData::new(record_batch))
-                });
 
+        self.task_executor.try_spawn(async move {
             // send each record batch over the channel
             while let Some(item) = stream.next().await {
                 if tx.send(item).is_err() {
                     warn!("read_json receiver end of channel dropped before sending completed");
+                    break;
                 }
             }
-        });
+        })?;
 
         Ok(Box::new(rx.into_iter()))
     }
@@ -146,7 +193,7 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
             PutMode::Create
         };
 
-        let store = self.store.clone(); // cheap Arc
+        let store = self.stores.get_store(path);
         let path = Path::from_url_path(path.path())?;
         let path_str = path.to_string();
         self.task_executor
@@ -159,12 +206,78 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
     }
 }
 
+#[cfg(feature = "async")]
+impl<E: TaskExecutor> crate::AsyncJsonHandler for DefaultJsonHandler<E> {
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<crate::FileDataReadResultStream> {
+        self.read_stream(files, physical_schema)
+    }
+}
+
+/// The compression of a JSON file, based on its extension (`.json.gz` or `.json.zst`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn from_url(url: &Url) -> Option<Self> {
+        let path = url.path();
+        if path.ends_with(".json.gz") {
+            Some(Self::Gzip)
+        } else if path.ends_with(".json.zst") {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "compressed-json")]
+    fn decompress(self, bytes: &[u8]) -> DeltaResult<Vec<u8>> {
+        use std::io::Read as _;
+        match self {
+            Self::Gzip => {
+                let mut decompressed = vec![];
+                flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+                Ok(decompressed)
+            }
+            Self::Zstd => Ok(zstd::decode_all(bytes)?),
+        }
+    }
+
+    #[cfg(not(feature = "compressed-json"))]
+    fn decompress(self, _bytes: &[u8]) -> DeltaResult<Vec<u8>> {
+        Err(Error::unsupported(format!(
+            "Reading {self:?} compressed JSON files requires the `compressed-json` feature"
+        )))
+    }
+}
+
+// Strip the byte order mark from the contents of a JSON file and, if `lossy_utf8`, replace invalid
+// UTF-8 sequences with U+FFFD.
+fn sanitize_json_bytes(bytes: &[u8], lossy_utf8: bool) -> Cow<'_, [u8]> {
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+    if !lossy_utf8 {
+        return Cow::Borrowed(bytes);
+    }
+    match String::from_utf8_lossy(bytes) {
+        Cow::Borrowed(valid) => Cow::Borrowed(valid.as_bytes()),
+        Cow::Owned(replaced) => Cow::Owned(replaced.into_bytes()),
+    }
+}
+
 /// Opens JSON files and returns a stream of record batches
 #[allow(missing_debug_implementations)]
 pub struct JsonOpener {
     batch_size: usize,
     projected_schema: ArrowSchemaRef,
-    object_store: Arc<DynObjectStore>,
+    object_stores: Arc<ObjectStoreRegistry>,
+    lossy_utf8: bool,
 }
 
 impl JsonOpener {
@@ -173,13 +286,31 @@ impl JsonOpener {
         batch_size: usize,
         projected_schema: ArrowSchemaRef,
         object_store: Arc<DynObjectStore>,
+    ) -> Self {
+        let object_stores = Arc::new(ObjectStoreRegistry::new(object_store));
+        Self::new_with_registry(batch_size, projected_schema, object_stores)
+    }
+
+    /// Returns a [`JsonOpener`] which reads each file from the store registered for its URL
+    pub fn new_with_registry(
+        batch_size: usize,
+        projected_schema: ArrowSchemaRef,
+        object_stores: Arc<ObjectStoreRegistry>,
     ) -> Self {
         Self {
             batch_size,
             projected_schema,
-            object_store,
+            object_stores,
+            lossy_utf8: false,
         }
     }
+
+    /// Replace invalid UTF-8 sequences with U+FFFD instead of failing, see
+    /// [`DefaultJsonHandler::with_lossy_utf8`].
+    pub fn with_lossy_utf8(mut self, lossy_utf8: bool) -> Self {
+        self.lossy_utf8 = lossy_utf8;
+        self
+    }
 }
 
 impl JsonOpener {
@@ -188,60 +319,102 @@ impl JsonOpener {
         file_meta: FileMeta,
         _: Option<Range<i64>>,
     ) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
-        let store = self.object_store.clone();
+        let store = self.object_stores.get_store(&file_meta.location);
         let schema = self.projected_schema.clone();
         let batch_size = self.batch_size;
 
         let path = Path::from_url_path(file_meta.location.path())?;
+        let compression = Compression::from_url(&file_meta.location);
+        if compression.is_some() || self.lossy_utf8 {
+            // compressed files are small enough (commits) to decompress fully in memory. Invalid
+            // UTF-8 must also be replaced in memory, as a sequence may span the chunks of a stream.
+            let bytes = store.get(&path).await?.bytes().await?;
+            let bytes = match compression {
+                Some(compression) => compression.decompress(&bytes)?,
+                None => bytes.to_vec(),
+            };
+            let bytes = sanitize_json_bytes(&bytes, self.lossy_utf8).into_owned();
+            let reader = ReaderBuilder::new(schema)
+                .with_batch_size(batch_size)
+                .build(Cursor::new(bytes))?;
+            return Ok(futures::stream::iter(reader).map_err(Error::from).boxed());
+        }
         match store.get(&path).await?.payload {
             GetResultPayload::File(file, _) => {
                 let reader = ReaderBuilder::new(schema)
                     .with_batch_size(batch_size)
-                    .build(BufReader::new(file))?;
+                    .build(skip_utf8_bom(BufReader::new(file))?)?;
                 Ok(futures::stream::iter(reader).map_err(Error::from).boxed())
             }
             GetResultPayload::Stream(s) => {
-                let mut decoder = ReaderBuilder::new(schema)
+                let decoder = ReaderBuilder::new(schema)
                     .with_batch_size(batch_size)
                     .build_decoder()?;
+                Ok(decode_json_stream(decoder, s.map_err(Error::from)))
+            }
+        }
+    }
+}
 
-                let mut input = s.map_err(Error::from);
-                let mut buffered = Bytes::new();
-
-                let s = futures::stream::poll_fn(move |cx| {
-                    loop {
-                        if buffered.is_empty() {
-                            buffered = match ready!(input.poll_next_unpin(cx)) {
-                                Some(Ok(b)) => b,
-                                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
-                                None => break,
-                            };
-                        }
-                        let read = buffered.len();
-
-                        // NB (from Decoder::decode docs):
-                        // Read JSON objects from `buf` (param), returning the number of bytes read
-                        //
-                        // This method returns once `batch_size` objects have been parsed since the
-                        // last call to [`Self::flush`], or `buf` is exhausted. Any remaining bytes
-                        // should be included in the next call to [`Self::decode`]
-                        let decoded = match decoder.decode(buffered.as_ref()) {
-                            Ok(decoded) => decoded,
-                            Err(e) => return Poll::Ready(Some(Err(e.into()))),
-                        };
-
-                        buffered.advance(decoded);
-                        if decoded != read {
-                            break;
-                        }
+// Decode a stream of chunks of a JSON file into record batches, skipping the BOM at the start of
+// the file, if any.
+fn decode_json_stream(
+    mut decoder: Decoder,
+    input: impl Stream<Item = DeltaResult<Bytes>> + Send + 'static,
+) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+    let mut input = Box::pin(input.fuse());
+    let mut buffered = Bytes::new();
+    // the bytes read so far while they may be the start of a BOM, which can be split across
+    // chunks. `None` once past the start of the file.
+    let mut bom_prefix = Some(Vec::new());
+
+    let s = futures::stream::poll_fn(move |cx| {
+        loop {
+            if buffered.is_empty() {
+                buffered = match ready!(input.poll_next_unpin(cx)) {
+                    Some(Ok(b)) => b,
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    // a file too short to hold a BOM is handed to the decoder as is
+                    None => match bom_prefix.take() {
+                        Some(prefix) if !prefix.is_empty() => prefix.into(),
+                        _ => break,
+                    },
+                };
+                if let Some(mut prefix) = bom_prefix.take() {
+                    prefix.extend_from_slice(&buffered);
+                    if prefix.len() < UTF8_BOM.len() && UTF8_BOM.starts_with(&prefix) {
+                        buffered = Bytes::new();
+                        bom_prefix = Some(prefix);
+                        continue;
+                    }
+                    buffered = prefix.into();
+                    if buffered.starts_with(UTF8_BOM) {
+                        buffered.advance(UTF8_BOM.len());
                     }
+                }
+            }
+            let read = buffered.len();
+
+            // NB (from Decoder::decode docs):
+            // Read JSON objects from `buf` (param), returning the number of bytes read
+            //
+            // This method returns once `batch_size` objects have been parsed since the
+            // last call to [`Self::flush`], or `buf` is exhausted. Any remaining bytes
+            // should be included in the next call to [`Self::decode`]
+            let decoded = match decoder.decode(buffered.as_ref()) {
+                Ok(decoded) => decoded,
+                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+            };
 
-                    Poll::Ready(decoder.flush().map_err(Error::from).transpose())
-                });
-                Ok(s.map_err(Error::from).boxed())
+            buffered.advance(decoded);
+            if decoded != read {
+                break;
             }
         }
-    }
+
+        Poll::Ready(decoder.flush().map_err(Error::from).transpose())
+    });
+    s.map_err(Error::from).boxed()
 }
 
 #[cfg(test)]
@@ -566,6 +739,182 @@ mod tests {
         assert_eq!(data[1].num_rows(), 2);
     }
 
+    #[cfg(feature = "compressed-json")]
+    #[tokio::test]
+    async fn test_read_compressed_json_files() {
+        use std::io::Write as _;
+
+        let json =
+            std::fs::read("./tests/data/table-with-dv-small/_delta_log/00000000000000000000.json")
+                .unwrap();
+        let mut gz = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        gz.write_all(&json).unwrap();
+        let gz = gz.finish().unwrap();
+        let zst = zstd::encode_all(json.as_slice(), 0).unwrap();
+
+        let store = Arc::new(InMemory::new());
+        let mut files = vec![];
+        for (name, data) in [
+            ("00000000000000000000.json.gz", gz),
+            ("00000000000000000000.json.zst", zst),
+        ] {
+            let size = data.len() as u64;
+            store.put(&Path::from(name), data.into()).await.unwrap();
+            files.push(FileMeta {
+                location: Url::parse(&format!("memory:///{name}")).unwrap(),
+                last_modified: 0,
+                size,
+            });
+        }
+
+        let handler = DefaultJsonHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let data: Vec<RecordBatch> = handler
+            .read_json_files(&files, get_log_schema().clone(), None)
+            .unwrap()
+            .map_ok(into_record_batch)
+            .try_collect()
+            .unwrap();
+
+        assert_eq!(data.len(), 2);
+        assert!(data.iter().all(|batch| batch.num_rows() == 4));
+    }
+
+    // A BOM-prefixed, CRLF-delimited commit (as written by some Windows-based writers), with a
+    // Latin-1 encoded (invalid UTF-8) operation in its commitInfo
+    fn windows_commit(operation: &[u8]) -> Vec<u8> {
+        let mut commit = UTF8_BOM.to_vec();
+        commit.extend_from_slice(br#"{"commitInfo":{"operation":""#);
+        commit.extend_from_slice(operation);
+        commit.extend_from_slice(b"\"}}\r\n");
+        commit.extend_from_slice(br#"{"txn":{"appId":"app","version":1}}"#);
+        commit.extend_from_slice(b"\r\n");
+        commit
+    }
+
+    fn read_operations(
+        handler: &DefaultJsonHandler<TokioBackgroundExecutor>,
+        files: &[FileMeta],
+    ) -> DeltaResult<Vec<Option<String>>> {
+        let schema = Arc::new(Schema::new([StructField::nullable(
+            "commitInfo",
+            Schema::new([StructField::nullable("operation", DeltaDataType::STRING)]),
+        )]));
+        let batches: Vec<RecordBatch> = handler
+            .read_json_files(files, schema, None)?
+            .map_ok(into_record_batch)
+            .try_collect()?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                let commit_info = batch.column(0).as_struct();
+                let operations = commit_info.column(0).as_string::<i32>();
+                operations
+                    .iter()
+                    .map(|op| op.map(String::from))
+                    .collect_vec()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_read_json_files_with_bom_and_crlf() {
+        let commit = windows_commit(b"WRITE");
+
+        // the in-memory store streams the file, while the local store hands over a file handle
+        let memory_store = Arc::new(InMemory::new());
+        memory_store
+            .put(
+                &Path::from("00000000000000000000.json"),
+                commit.clone().into(),
+            )
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let local_path = dir.path().join("00000000000000000000.json");
+        std::fs::write(&local_path, &commit).unwrap();
+
+        let stores: [(Arc<DynObjectStore>, Url); 2] = [
+            (
+                memory_store,
+                Url::parse("memory:///00000000000000000000.json").unwrap(),
+            ),
+            (
+                Arc::new(LocalFileSystem::new()),
+                Url::from_file_path(local_path).unwrap(),
+            ),
+        ];
+        for (store, location) in stores {
+            let handler = DefaultJsonHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+            let files = &[FileMeta::new(location, 0, commit.len() as u64)];
+            let operations = read_operations(&handler, files).unwrap();
+            assert_eq!(operations, [Some("WRITE".to_string()), None]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_json_stream_with_split_bom() {
+        let commit = windows_commit(b"WRITE");
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new_struct(
+            "commitInfo",
+            vec![Field::new("operation", DataType::Utf8, true)],
+            true,
+        )]));
+        for chunk_size in [1, 2, 4] {
+            let chunks: Vec<_> = commit
+                .chunks(chunk_size)
+                .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+                .collect();
+            let decoder = ReaderBuilder::new(schema.clone()).build_decoder().unwrap();
+            let batches: Vec<_> = decode_json_stream(decoder, stream::iter(chunks))
+                .try_collect()
+                .await
+                .unwrap();
+            let operations: Vec<_> = batches
+                .iter()
+                .flat_map(|batch| {
+                    let commit_info = batch.column(0).as_struct();
+                    commit_info
+                        .column(0)
+                        .as_string::<i32>()
+                        .iter()
+                        .collect_vec()
+                })
+                .collect();
+            assert_eq!(operations, [Some("WRITE"), None], "chunk size {chunk_size}");
+        }
+
+        // a file holding only part of a BOM is not valid JSON
+        let decoder = ReaderBuilder::new(schema).build_decoder().unwrap();
+        let chunks = vec![Ok(Bytes::from_static(&UTF8_BOM[..2]))];
+        let result: DeltaResult<Vec<_>> = decode_json_stream(decoder, stream::iter(chunks))
+            .try_collect()
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_json_files_lossy_utf8() {
+        // "CRÉER" in Latin-1
+        let commit = windows_commit(b"CR\xC9ER");
+        let store = Arc::new(InMemory::new());
+        store
+            .put(
+                &Path::from("00000000000000000000.json"),
+                commit.clone().into(),
+            )
+            .await
+            .unwrap();
+        let location = Url::parse("memory:///00000000000000000000.json").unwrap();
+        let files = &[FileMeta::new(location, 0, commit.len() as u64)];
+
+        let handler = DefaultJsonHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        assert!(read_operations(&handler, files).is_err());
+
+        let handler = handler.with_lossy_utf8(true);
+        let operations = read_operations(&handler, files).unwrap();
+        assert_eq!(operations, [Some("CR\u{FFFD}ER".to_string()), None]);
+    }
+
     #[tokio::test]
     async fn test_ordered_get_store() {
         // note we don't want to go over 1000 since we only buffer 1000 requests at a time
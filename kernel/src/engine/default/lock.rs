@@ -0,0 +1,260 @@
+//! An optional advisory lock for tables with a single writer.
+//!
+//! Commits are safe under concurrent writers without any locking: each commit file is written
+//! with put-if-absent, and a writer that loses the race has to retry its transaction. On tables
+//! with heavy write contention those retries can waste a lot of work. A service that wants to be
+//! the only writer of a table can hold a [`TableLock`] while it writes, so that other cooperating
+//! writers wait for the lock instead of racing it.
+//!
+//! The lock is purely *advisory*, and nothing takes it by default: kernel never checks it when
+//! committing, and writers that don't use it are not blocked by it. It is also best-effort. A
+//! holder that stops calling [`TableLock::heartbeat`] loses the lock once its TTL expires, even if
+//! it is still writing, and clock skew between writers shortens or extends the effective TTL. The
+//! correctness of commits never depends on the lock.
+//!
+//! Every change to an existing lock file is a conditional update ([`PutMode::Update`]) of the
+//! version that was read, so the lock needs an object store that supports them. Releasing a lock
+//! marks it as expired instead of deleting the file, since deletes can't be made conditional.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::executor::TaskExecutor;
+use crate::clock::{KernelClock, SystemClock};
+use crate::object_store::path::Path;
+use crate::object_store::{self, DynObjectStore, PutMode, UpdateVersion};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// The name of the lock file, in the table's `_delta_log` directory.
+const LOCK_FILE_NAME: &str = "_table.lock";
+
+/// How long a lock is held without a heartbeat, by default.
+pub const DEFAULT_LOCK_TTL: Duration = Duration::from_secs(60);
+
+/// The contents of the lock file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockInfo {
+    owner: String,
+    /// Milliseconds since the Unix epoch after which the lock may be taken over.
+    expires_at: i64,
+}
+
+/// An advisory, time-limited lock on a table, held by the owner that created the table's lock
+/// file. See the [module documentation](self) for its guarantees (and lack thereof).
+///
+/// A writer calls [`Self::try_acquire`] before starting a transaction, [`Self::heartbeat`] at
+/// least once per TTL while it keeps writing, and [`Self::release`] when it is done.
+#[derive(Debug)]
+pub struct TableLock<E: TaskExecutor> {
+    store: Arc<DynObjectStore>,
+    path: Path,
+    owner: String,
+    ttl: Duration,
+    clock: Arc<dyn KernelClock>,
+    task_executor: Arc<E>,
+}
+
+impl<E: TaskExecutor> TableLock<E> {
+    /// Create a lock on the table at `table_root`, stored in `store`. `owner` identifies this
+    /// writer and must be unique among the writers of the table.
+    pub fn try_new(
+        store: Arc<DynObjectStore>,
+        table_root: &Url,
+        owner: impl Into<String>,
+        task_executor: Arc<E>,
+    ) -> DeltaResult<Self> {
+        let lock_url = table_root.join("_delta_log/")?.join(LOCK_FILE_NAME)?;
+        Ok(Self {
+            store,
+            path: Path::from_url_path(lock_url.path())?,
+            owner: owner.into(),
+            ttl: DEFAULT_LOCK_TTL,
+            clock: Arc::new(SystemClock),
+            task_executor,
+        })
+    }
+
+    /// Set how long the lock is held after acquiring it or after the last heartbeat. Defaults to
+    /// [`DEFAULT_LOCK_TTL`].
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the [`KernelClock`] used to compute and check lock expiry.
+    pub fn with_clock(mut self, clock: Arc<dyn KernelClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Try to acquire the lock without waiting. Returns `true` if this owner now holds the lock
+    /// (including if it already held it, in which case the lock is refreshed), or `false` if
+    /// another owner holds an unexpired lock. An expired lock of another owner is taken over.
+    pub fn try_acquire(&self) -> DeltaResult<bool> {
+        if self.write(PutMode::Create, self.expires_at()?)? {
+            return Ok(true);
+        }
+        let Some((current, version)) = self.read()? else {
+            // the lock file was removed in the meantime (kernel never removes it)
+            return self.write(PutMode::Create, self.expires_at()?);
+        };
+        if current.owner != self.owner && current.expires_at > self.clock.now_millis()? {
+            return Ok(false);
+        }
+        // Our own lock, or another owner's expired (or released) lock. Another writer may be
+        // taking it over at the same time, in which case only one of the updates succeeds.
+        self.write(PutMode::Update(version), self.expires_at()?)
+    }
+
+    /// Extend the lock held by this owner by another TTL. Fails if this owner no longer holds the
+    /// lock, e.g. because it expired and was taken over by another owner.
+    pub fn heartbeat(&self) -> DeltaResult<()> {
+        let extended = match self.read()? {
+            Some((current, version)) if current.owner == self.owner => {
+                self.write(PutMode::Update(version), self.expires_at()?)?
+            }
+            _ => false,
+        };
+        require!(
+            extended,
+            Error::generic(format!(
+                "Table lock {} is no longer held by {}",
+                self.path, self.owner
+            ))
+        );
+        Ok(())
+    }
+
+    /// Release the lock if this owner holds it, by marking it as expired. Does nothing otherwise,
+    /// including if another owner takes the lock over concurrently.
+    pub fn release(&self) -> DeltaResult<()> {
+        match self.read()? {
+            Some((current, version)) if current.owner == self.owner => {
+                self.write(PutMode::Update(version), i64::MIN)?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // The expiry of a lock acquired or extended now
+    fn expires_at(&self) -> DeltaResult<i64> {
+        let ttl: i64 = self
+            .ttl
+            .as_millis()
+            .try_into()
+            .map_err(|_| Error::generic("Table lock TTL is too large"))?;
+        Ok(self.clock.now_millis()?.saturating_add(ttl))
+    }
+
+    // Write the lock file for this owner with `mode`. Returns false if the write's precondition
+    // failed: the file already exists (for a create) or was changed since it was read (for an
+    // update).
+    fn write(&self, mode: PutMode, expires_at: i64) -> DeltaResult<bool> {
+        let info = LockInfo {
+            owner: self.owner.clone(),
+            expires_at,
+        };
+        let bytes = serde_json::to_vec(&info)?;
+        let store = self.store.clone();
+        let path = self.path.clone();
+        let result = self
+            .task_executor
+            .block_on(async move { store.put_opts(&path, bytes.into(), mode.into()).await });
+        match result {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::AlreadyExists { .. })
+            | Err(object_store::Error::Precondition { .. }) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Read the lock file, with the version to update it conditionally
+    fn read(&self) -> DeltaResult<Option<(LockInfo, UpdateVersion)>> {
+        let store = self.store.clone();
+        let path = self.path.clone();
+        let result = self.task_executor.block_on(async move {
+            let result = store.get(&path).await?;
+            let version = UpdateVersion {
+                e_tag: result.meta.e_tag.clone(),
+                version: result.meta.version.clone(),
+            };
+            Ok((result.bytes().await?, version))
+        });
+        match result {
+            Ok((bytes, version)) => Ok(Some((serde_json::from_slice(&bytes)?, version))),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::object_store::memory::InMemory;
+
+    #[derive(Debug, Default)]
+    struct ManualClock(AtomicI64);
+
+    impl KernelClock for ManualClock {
+        fn now_millis(&self) -> DeltaResult<i64> {
+            Ok(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    fn test_table_lock() {
+        let store = Arc::new(InMemory::new());
+        let table_root = Url::parse("memory:///table/").unwrap();
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let clock = Arc::new(ManualClock::default());
+        let lock = |owner| {
+            TableLock::try_new(store.clone(), &table_root, owner, executor.clone())
+                .unwrap()
+                .with_ttl(Duration::from_secs(10))
+                .with_clock(clock.clone())
+        };
+        let (a, b) = (lock("a"), lock("b"));
+
+        // only one owner can hold the lock, and acquiring again is fine
+        assert!(a.try_acquire().unwrap());
+        assert!(!b.try_acquire().unwrap());
+        assert!(a.try_acquire().unwrap());
+
+        // a heartbeat keeps the lock alive past the original TTL
+        clock.0.store(8_000, Ordering::SeqCst);
+        a.heartbeat().unwrap();
+        clock.0.store(15_000, Ordering::SeqCst);
+        assert!(!b.try_acquire().unwrap());
+        assert!(b.heartbeat().is_err());
+
+        // once the lock expires, it can be taken over
+        clock.0.store(18_001, Ordering::SeqCst);
+        assert!(b.try_acquire().unwrap());
+        assert!(a.heartbeat().is_err());
+
+        // releasing a lock held by someone else does nothing
+        a.release().unwrap();
+        assert!(!a.try_acquire().unwrap());
+        b.release().unwrap();
+        assert!(a.try_acquire().unwrap());
+
+        // an update based on a stale read fails, e.g. when two owners take over the same expired
+        // lock at once
+        clock.0.store(100_000, Ordering::SeqCst);
+        let (_, version) = b.read().unwrap().unwrap();
+        assert!(a.try_acquire().unwrap());
+        let expires_at = b.expires_at().unwrap();
+        assert!(!b.write(PutMode::Update(version), expires_at).unwrap());
+        assert!(b.heartbeat().is_err());
+    }
+}
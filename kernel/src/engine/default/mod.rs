@@ -9,37 +9,55 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use self::storage::parse_url_opts;
+use self::storage::{parse_url_opts, ObjectStoreRegistry};
 use crate::object_store::DynObjectStore;
 use url::Url;
 
+use self::config::DefaultEngineConfig;
+use self::credentials::{CredentialProvider, RefreshingObjectStore};
 use self::executor::TaskExecutor;
 use self::filesystem::ObjectStoreStorageHandler;
 use self::json::DefaultJsonHandler;
+use self::lock::TableLock;
 use self::parquet::DefaultParquetHandler;
 use super::arrow_conversion::TryFromArrow as _;
 use super::arrow_data::ArrowEngineData;
 use super::arrow_expression::ArrowEvaluationHandler;
+use super::arrow_utils::CastPolicy;
+use crate::clock::{IdGenerator, RandomIdGenerator, SystemClock};
+use crate::expressions::Scalar;
 use crate::schema::Schema;
 use crate::transaction::WriteContext;
+#[cfg(feature = "async")]
+use crate::{AsyncJsonHandler, AsyncParquetHandler};
 use crate::{
     DeltaResult, Engine, EngineData, EvaluationHandler, JsonHandler, ParquetHandler, StorageHandler,
 };
 
+pub mod config;
+pub mod credentials;
 pub mod executor;
 pub mod file_stream;
 pub mod filesystem;
 pub mod json;
+pub mod lock;
 pub mod parquet;
 pub mod storage;
 
 #[derive(Debug)]
 pub struct DefaultEngine<E: TaskExecutor> {
-    object_store: Arc<DynObjectStore>,
+    object_stores: Arc<ObjectStoreRegistry>,
     storage: Arc<ObjectStoreStorageHandler<E>>,
     json: Arc<DefaultJsonHandler<E>>,
     parquet: Arc<DefaultParquetHandler<E>>,
     evaluation: Arc<ArrowEvaluationHandler>,
+    task_executor: Arc<E>,
+    cast_policy: CastPolicy,
+    id_generator: Arc<dyn IdGenerator>,
+    config: DefaultEngineConfig,
+    target_file_size: Option<u64>,
+    max_row_group_size: Option<usize>,
+    lossy_json_utf8: bool,
 }
 
 impl<E: TaskExecutor> DefaultEngine<E> {
@@ -71,26 +89,266 @@ impl<E: TaskExecutor> DefaultEngine<E> {
     /// - `object_store`: The object store to use.
     /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
     pub fn new(object_store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
-        Self {
-            storage: Arc::new(ObjectStoreStorageHandler::new(
-                object_store.clone(),
+        Self::new_with_registry(ObjectStoreRegistry::new(object_store), task_executor)
+    }
+
+    /// Create a new [`DefaultEngine`] instance for the table at `table_root`, whose storage
+    /// credentials expire and are refreshed from `credential_provider` shortly before they do. See
+    /// the [`credentials`] module.
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: The URL of the table within storage.
+    /// - `credential_provider`: Fetches the object store options holding the credentials.
+    /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
+    pub fn new_with_credential_provider(
+        table_root: &Url,
+        credential_provider: Arc<dyn CredentialProvider>,
+        task_executor: Arc<E>,
+    ) -> DeltaResult<Self> {
+        let store =
+            RefreshingObjectStore::try_new(table_root, credential_provider, Arc::new(SystemClock))?;
+        Ok(Self::new_with_registry(
+            ObjectStoreRegistry::new_refreshing(store),
+            task_executor,
+        ))
+    }
+
+    fn new_with_registry(object_stores: ObjectStoreRegistry, task_executor: Arc<E>) -> Self {
+        let object_stores = Arc::new(object_stores);
+        let mut engine = Self {
+            storage: Arc::new(ObjectStoreStorageHandler::new_with_registry(
+                object_stores.clone(),
                 task_executor.clone(),
             )),
-            json: Arc::new(DefaultJsonHandler::new(
-                object_store.clone(),
+            json: Arc::new(DefaultJsonHandler::new_with_registry(
+                object_stores.clone(),
                 task_executor.clone(),
             )),
-            parquet: Arc::new(DefaultParquetHandler::new(
-                object_store.clone(),
-                task_executor,
+            parquet: Arc::new(DefaultParquetHandler::new_with_registry(
+                object_stores.clone(),
+                task_executor.clone(),
             )),
-            object_store,
+            object_stores,
+            task_executor,
             evaluation: Arc::new(ArrowEvaluationHandler {}),
+            cast_policy: CastPolicy::default(),
+            id_generator: Arc::new(RandomIdGenerator),
+            config: DefaultEngineConfig::default(),
+            target_file_size: None,
+            max_row_group_size: None,
+            lossy_json_utf8: false,
+        };
+        engine.rebuild_handlers();
+        engine
+    }
+
+    /// Apply the buffer and batch sizes in `config` to this engine's handlers, replacing any set
+    /// previously (e.g. by [`Self::with_memory_budget`]). Fails if the config is invalid; see
+    /// [`DefaultEngineConfig::validate`].
+    pub fn with_config(mut self, config: DefaultEngineConfig) -> DeltaResult<Self> {
+        config.validate()?;
+        self.config = config;
+        self.rebuild_handlers();
+        Ok(self)
+    }
+
+    /// The buffer and batch sizes currently used by this engine's handlers.
+    pub fn config(&self) -> &DefaultEngineConfig {
+        &self.config
+    }
+
+    /// Set the [`CastPolicy`] used when reading parquet files, e.g. to fail on overflow when a
+    /// widened column is cast to the table type instead of returning null.
+    pub fn with_cast_policy(mut self, cast_policy: CastPolicy) -> Self {
+        self.cast_policy = cast_policy;
+        self.rebuild_parquet_handler();
+        self
+    }
+
+    /// Set the [`IdGenerator`] used to name the data files written by [`Self::write_parquet`],
+    /// e.g. a [`SequentialIdGenerator`](crate::clock::SequentialIdGenerator) to produce
+    /// deterministic file names in tests.
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = id_generator;
+        self.rebuild_parquet_handler();
+        self
+    }
+
+    /// Set the maximum number of bytes of data read ahead of the consumer while scanning parquet
+    /// files. See [`DefaultParquetHandler::with_memory_budget`].
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.config = self.config.with_memory_budget(memory_budget);
+        self.rebuild_parquet_handler();
+        self
+    }
+
+    /// Cache the footers of up to `footer_cache_size` parquet files, so that repeated scans of the
+    /// same files don't read their footers from storage again. See
+    /// [`DefaultParquetHandler::with_footer_cache_size`].
+    pub fn with_parquet_footer_cache_size(mut self, footer_cache_size: usize) -> Self {
+        self.config = self
+            .config
+            .with_parquet_footer_cache_size(footer_cache_size);
+        self.rebuild_parquet_handler();
+        self
+    }
+
+    /// Set the target size, in bytes, of the parquet files written by [`Self::write_parquet`].
+    /// Larger writes are split across multiple files. See
+    /// [`DefaultParquetHandler::with_target_file_size`].
+    pub fn with_target_file_size(mut self, target_file_size: u64) -> Self {
+        self.target_file_size = Some(target_file_size);
+        self.rebuild_parquet_handler();
+        self
+    }
+
+    /// Set the maximum number of rows per row group in the parquet files written by
+    /// [`Self::write_parquet`]. See [`DefaultParquetHandler::with_max_row_group_size`].
+    pub fn with_max_row_group_size(mut self, max_row_group_size: usize) -> Self {
+        self.max_row_group_size = Some(max_row_group_size);
+        self.rebuild_parquet_handler();
+        self
+    }
+
+    /// Replace invalid UTF-8 in the JSON files read (e.g. commits) with U+FFFD instead of failing
+    /// the read. See [`DefaultJsonHandler::with_lossy_utf8`].
+    pub fn with_lossy_json_utf8(mut self, lossy_json_utf8: bool) -> Self {
+        self.lossy_json_utf8 = lossy_json_utf8;
+        self.rebuild_json_handler();
+        self
+    }
+
+    fn rebuild_handlers(&mut self) {
+        self.rebuild_storage_handler();
+        self.rebuild_json_handler();
+        self.rebuild_parquet_handler();
+    }
+
+    fn rebuild_json_handler(&mut self) {
+        self.json = Arc::new(
+            DefaultJsonHandler::new_with_registry(
+                self.object_stores.clone(),
+                self.task_executor.clone(),
+            )
+            .with_buffer_size(self.config.json_buffer_size())
+            .with_batch_size(self.config.json_batch_size())
+            .with_lossy_utf8(self.lossy_json_utf8),
+        );
+    }
+
+    fn rebuild_storage_handler(&mut self) {
+        self.storage = Arc::new(
+            ObjectStoreStorageHandler::new_with_registry(
+                self.object_stores.clone(),
+                self.task_executor.clone(),
+            )
+            .with_readahead(self.config.storage_readahead())
+            .with_list_buffer_size(self.config.list_buffer_size()),
+        );
+    }
+
+    fn rebuild_parquet_handler(&mut self) {
+        let mut parquet = DefaultParquetHandler::new_with_registry(
+            self.object_stores.clone(),
+            self.task_executor.clone(),
+        )
+        .with_batch_size(self.config.parquet_batch_size())
+        .with_readahead(self.config.parquet_readahead())
+        .with_cast_policy(self.cast_policy)
+        .with_id_generator(self.id_generator.clone());
+        if let Some(memory_budget) = self.config.memory_budget() {
+            parquet = parquet.with_memory_budget(memory_budget);
+        }
+        if let Some(footer_cache_size) = self.config.parquet_footer_cache_size() {
+            parquet = parquet.with_footer_cache_size(footer_cache_size);
         }
+        if let Some(target_file_size) = self.target_file_size {
+            parquet = parquet.with_target_file_size(target_file_size);
+        }
+        if let Some(max_row_group_size) = self.max_row_group_size {
+            parquet = parquet.with_max_row_group_size(max_row_group_size);
+        }
+        self.parquet = Arc::new(parquet);
+    }
+
+    /// Set the maximum number of listed files to buffer ahead of log replay when listing the
+    /// `_delta_log` directory. See [`ObjectStoreStorageHandler::with_list_buffer_size`].
+    pub fn with_list_buffer_size(mut self, list_buffer_size: usize) -> Self {
+        self.config = self.config.with_list_buffer_size(list_buffer_size);
+        self.rebuild_storage_handler();
+        self
+    }
+
+    /// Read and write all files whose URL starts with `prefix` using `object_store`, instead of
+    /// the store this engine was created with. This allows reading tables whose files live in
+    /// multiple locations (e.g. absolute paths into other buckets for shallow clones).
+    pub fn register_object_store(&self, prefix: Url, object_store: Arc<DynObjectStore>) {
+        self.object_stores.register(prefix, object_store);
+    }
+
+    /// Read and write all files whose URL has the given `scheme` (e.g. `file`) using
+    /// `object_store`, unless a store is registered for a prefix of their URL with
+    /// [`Self::register_object_store`]. This allows one engine to serve tables on several file
+    /// systems. Object stores address files by path only, so `object_store` must serve every
+    /// bucket (or host) of the scheme; register bucket-specific stores by prefix instead.
+    pub fn register_object_store_for_scheme(
+        &self,
+        scheme: impl Into<String>,
+        object_store: Arc<DynObjectStore>,
+    ) {
+        self.object_stores.register_scheme(scheme, object_store);
+    }
+
+    /// Create an object store for `prefix` from the given `options` (e.g. credentials for that
+    /// bucket) and register it with [`Self::register_object_store`].
+    pub fn register_object_store_with_options<K, V>(
+        &self,
+        prefix: Url,
+        options: impl IntoIterator<Item = (K, V)>,
+    ) -> DeltaResult<()>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let (object_store, _path) = parse_url_opts(&prefix, options)?;
+        self.register_object_store(prefix, Arc::new(object_store));
+        Ok(())
     }
 
-    pub fn get_object_store_for_url(&self, _url: &Url) -> Option<Arc<DynObjectStore>> {
-        Some(self.object_store.clone())
+    /// Create an advisory [`TableLock`] on the table at `table_root` for the writer `owner`.
+    /// Nothing takes this lock by default; see the [`lock`] module for when to use it.
+    pub fn table_lock(
+        &self,
+        table_root: &Url,
+        owner: impl Into<String>,
+    ) -> DeltaResult<TableLock<E>> {
+        TableLock::try_new(
+            self.object_stores.get_store(table_root),
+            table_root,
+            owner,
+            self.task_executor.clone(),
+        )
+    }
+
+    /// The JSON handler as an [`AsyncJsonHandler`], whose reads are polled on the caller's runtime
+    /// rather than on this engine's task executor.
+    #[cfg(feature = "async")]
+    pub fn async_json_handler(&self) -> Arc<dyn AsyncJsonHandler> {
+        self.json.clone()
+    }
+
+    /// The parquet handler as an [`AsyncParquetHandler`], whose reads are polled on the caller's
+    /// runtime rather than on this engine's task executor. Pass it to
+    /// [`Scan::execute_async`](crate::scan::Scan::execute_async).
+    #[cfg(feature = "async")]
+    pub fn async_parquet_handler(&self) -> Arc<dyn AsyncParquetHandler> {
+        self.parquet.clone()
+    }
+
+    /// Get the object store used for files at `url`.
+    pub fn get_object_store_for_url(&self, url: &Url) -> Option<Arc<DynObjectStore>> {
+        Some(self.object_stores.get_store(url))
     }
 
     pub async fn write_parquet(
@@ -100,15 +358,17 @@ impl<E: TaskExecutor> DefaultEngine<E> {
         partition_values: HashMap<String, String>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
-        let transform = write_context.logical_to_physical();
-        let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
-        let output_schema = write_context.schema();
-        let logical_to_physical_expr = self.evaluation_handler().new_expression_evaluator(
-            input_schema.into(),
-            transform.clone(),
-            output_schema.clone().into(),
-        );
-        let physical_data = logical_to_physical_expr.evaluate(data)?;
+        // constraints may reference the partition columns, whose values are not part of the data
+        let partition_scalars = partition_values
+            .iter()
+            .filter_map(|(name, value)| {
+                let field = write_context.partition_schema().field(name)?;
+                let value = field.data_type().as_primitive_opt()?.parse_scalar(value);
+                Some(value.map(|value| (name.clone(), value)))
+            })
+            .collect::<DeltaResult<_>>()?;
+        write_context.check_constraints(self, data, &partition_scalars)?;
+        let physical_data = self.logical_to_physical(data, write_context)?;
         self.parquet
             .write_parquet_file(
                 write_context.target_dir(),
@@ -118,6 +378,51 @@ impl<E: TaskExecutor> DefaultEngine<E> {
             )
             .await
     }
+
+    /// Write `data`, all of which belongs to the partition with the given `partition_values`, to
+    /// the partition's `col=value/` directory under the table root, and return the add file
+    /// metadata (see [`Self::write_parquet`]) with the serialized partition values filled in.
+    ///
+    /// The partition values must match the table's partition columns; see
+    /// [`WriteContext::serialize_partition_values`]. A null value is recorded as null and written
+    /// to the `__HIVE_DEFAULT_PARTITION__` directory.
+    pub async fn write_partitioned_parquet(
+        &self,
+        data: &ArrowEngineData,
+        write_context: &WriteContext,
+        partition_values: HashMap<String, Scalar>,
+        data_change: bool,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        write_context.check_constraints(self, data, &partition_values)?;
+        let partition_values = write_context.serialize_partition_values(&partition_values)?;
+        let partition_dir = write_context.partition_dir(&partition_values)?;
+        let physical_data = self.logical_to_physical(data, write_context)?;
+        self.parquet
+            .write_parquet_files(
+                &partition_dir,
+                physical_data,
+                &partition_values,
+                data_change,
+            )
+            .await
+    }
+
+    // Apply the write context's logical-to-physical transform to `data`.
+    fn logical_to_physical(
+        &self,
+        data: &ArrowEngineData,
+        write_context: &WriteContext,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let transform = write_context.logical_to_physical();
+        let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
+        let output_schema = write_context.physical_schema();
+        let logical_to_physical_expr = self.evaluation_handler().new_expression_evaluator(
+            input_schema.into(),
+            transform.clone(),
+            output_schema.clone().into(),
+        );
+        logical_to_physical_expr.evaluate(data)
+    }
 }
 
 impl<E: TaskExecutor> Engine for DefaultEngine<E> {
@@ -170,6 +475,7 @@ impl UrlExt for Url {
 #[cfg(test)]
 mod tests {
     use super::executor::tokio::TokioBackgroundExecutor;
+    use super::executor::SyncExecutor;
     use super::*;
     use crate::engine::tests::test_arrow_engine;
     use crate::object_store::local::LocalFileSystem;
@@ -183,6 +489,148 @@ mod tests {
         test_arrow_engine(&engine, &url);
     }
 
+    #[test]
+    fn test_default_engine_sync_executor() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(tmp.path()).unwrap();
+        let object_store = Arc::new(LocalFileSystem::new());
+        let engine = DefaultEngine::new(object_store, Arc::new(SyncExecutor::new()));
+        test_arrow_engine(&engine, &url);
+    }
+
+    #[test]
+    fn test_sync_executor_scan() {
+        let path = std::fs::canonicalize("./tests/data/table-without-dv-small/").unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(SyncExecutor::new()),
+        ));
+
+        let snapshot = Arc::new(crate::Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+        let scan = snapshot.scan_builder().build().unwrap();
+        let num_rows: usize = scan
+            .execute(engine)
+            .unwrap()
+            .map(|res| res.unwrap().raw_data.unwrap().len())
+            .sum();
+        assert_eq!(num_rows, 10);
+    }
+
+    #[test]
+    fn test_with_config() {
+        let path = std::fs::canonicalize("./tests/data/table-without-dv-small/").unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(SyncExecutor::new()),
+        );
+        let invalid = DefaultEngineConfig::default().with_json_batch_size(0);
+        assert!(engine.with_config(invalid).is_err());
+
+        let config = DefaultEngineConfig::default()
+            .with_parquet_batch_size(3)
+            .with_list_buffer_size(1);
+        let engine = DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(SyncExecutor::new()),
+        )
+        .with_config(config)
+        .unwrap()
+        .with_memory_budget(1 << 20);
+        assert_eq!(
+            engine.config(),
+            &config.with_memory_budget(1 << 20),
+            "per-knob setters update the config"
+        );
+
+        // The 10 row table is read in batches of at most 3 rows
+        let engine = Arc::new(engine);
+        let snapshot = Arc::new(crate::Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+        let scan = snapshot.scan_builder().build().unwrap();
+        let batch_sizes: Vec<_> = scan
+            .execute(engine)
+            .unwrap()
+            .map(|res| res.unwrap().raw_data.unwrap().len())
+            .collect();
+        assert_eq!(batch_sizes, vec![3, 3, 3, 1]);
+    }
+
+    #[test]
+    fn test_credential_provider_scan() {
+        use self::credentials::TemporaryCredentials;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let path = std::fs::canonicalize("./tests/data/table-without-dv-small/").unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        // The credentials have always expired, so they are refetched before every request
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let provider = {
+            let fetches = fetches.clone();
+            move || {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                let options: [(&str, &str); 0] = [];
+                Ok(TemporaryCredentials::new(options).with_expiration_time(0))
+            }
+        };
+        let engine = Arc::new(
+            DefaultEngine::new_with_credential_provider(
+                &url,
+                Arc::new(provider),
+                Arc::new(SyncExecutor::new()),
+            )
+            .unwrap(),
+        );
+
+        let snapshot = Arc::new(crate::Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+        let scan = snapshot.scan_builder().build().unwrap();
+        let num_rows: usize = scan
+            .execute(engine)
+            .unwrap()
+            .map(|res| res.unwrap().raw_data.unwrap().len())
+            .sum();
+        assert_eq!(num_rows, 10);
+        assert!(fetches.load(Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_register_object_store() {
+        use crate::object_store::{memory::InMemory, path::Path, ObjectStore as _};
+
+        let default_store = Arc::new(InMemory::new());
+        let other_store = Arc::new(InMemory::new());
+        for path in ["a/file", "b/file", "bb/file"] {
+            let path = Path::from(path);
+            default_store.put(&path, "default".into()).await.unwrap();
+            other_store.put(&path, "other".into()).await.unwrap();
+        }
+
+        let engine = DefaultEngine::new(default_store, Arc::new(TokioBackgroundExecutor::new()));
+        engine.register_object_store(Url::parse("memory:///b").unwrap(), other_store);
+
+        let read = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            let mut data = engine
+                .storage_handler()
+                .read_files(vec![(url, None)])
+                .unwrap();
+            data.next().unwrap().unwrap()
+        };
+        assert_eq!(read("memory:///a/file"), "default");
+        assert_eq!(read("memory:///b/file"), "other");
+        // the prefix is a directory: `memory:///bb/` is not under `memory:///b/`
+        assert_eq!(read("memory:///bb/file"), "default");
+
+        // A scheme store serves the URLs of its scheme which match no prefix
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("file"), "local").unwrap();
+        engine.register_object_store_for_scheme("file", Arc::new(LocalFileSystem::new()));
+        let url = Url::from_file_path(dir.path().join("file")).unwrap();
+        assert_eq!(read(url.as_str()), "local");
+        assert_eq!(read("memory:///a/file"), "default");
+        assert_eq!(read("memory:///b/file"), "other");
+    }
+
     #[test]
     fn test_pre_signed_url() {
         let url = Url::parse("https://example.com?X-Amz-Signature=foo").unwrap();
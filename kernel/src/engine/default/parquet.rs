@@ -1,39 +1,152 @@
 //! Default Parquet handler implementation
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 
 use crate::arrow::array::builder::{MapBuilder, MapFieldNames, StringBuilder};
-use crate::arrow::array::{BooleanArray, Int64Array, RecordBatch, StringArray};
+use crate::arrow::array::{
+    new_null_array, Array as _, ArrayRef, AsArray as _, BooleanArray, Int64Array, RecordBatch,
+    StringArray, StructArray,
+};
+use crate::arrow::compute::{cast, concat_batches, filter_record_batch};
+use crate::arrow::datatypes::{DataType as ArrowDataType, Fields, SchemaRef as ArrowSchemaRef};
 use crate::object_store::path::Path;
 use crate::object_store::DynObjectStore;
 use crate::parquet::arrow::arrow_reader::{
     ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
 };
 use crate::parquet::arrow::arrow_writer::ArrowWriter;
-use crate::parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use crate::parquet::arrow::async_reader::{
+    AsyncFileReader, ParquetObjectReader, ParquetRecordBatchStreamBuilder,
+};
+use crate::parquet::arrow::async_writer::{AsyncArrowWriter, ParquetObjectWriter};
+use crate::parquet::file::properties::WriterProperties;
 use futures::StreamExt;
-use uuid::Uuid;
+#[cfg(feature = "async")]
+use futures::TryStreamExt;
 
 use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
+use super::storage::ObjectStoreRegistry;
 use super::UrlExt;
+use crate::clock::{IdGenerator, RandomIdGenerator};
 use crate::engine::arrow_conversion::TryIntoArrow as _;
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::engine::arrow_utils::{fixup_parquet_read, generate_mask, get_requested_indices};
+use crate::engine::arrow_utils::{
+    fixup_parquet_read, generate_mask, get_requested_indices, parquet_footer, plan_parquet_splits,
+    CastPolicy, RowIndexes,
+};
 use crate::engine::default::executor::TaskExecutor;
 use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
+use crate::engine_data::FilteredEngineData;
 use crate::schema::SchemaRef;
 use crate::{
-    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, ParquetHandler,
-    PredicateRef,
+    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, ParquetFooter,
+    ParquetHandler, ParquetSplit, PredicateRef,
 };
 
 #[derive(Debug)]
 pub struct DefaultParquetHandler<E: TaskExecutor> {
-    store: Arc<DynObjectStore>,
+    stores: Arc<ObjectStoreRegistry>,
     task_executor: Arc<E>,
+    batch_size: usize,
     readahead: usize,
+    memory_budget: Option<usize>,
+    target_file_size: Option<u64>,
+    max_row_group_size: Option<usize>,
+    cast_policy: CastPolicy,
+    id_generator: Arc<dyn IdGenerator>,
+    footer_cache: Option<Arc<FooterCache>>,
+}
+
+/// The default maximum number of rows per batch read by [`ParquetHandler::read_parquet_files`].
+pub(crate) const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// The default maximum number of batches to read ahead of the consumer in
+/// [`ParquetHandler::read_parquet_files`].
+pub(crate) const DEFAULT_READAHEAD: usize = 10;
+
+/// The number of rows handed to the parquet writer at a time when a target file size is set. The
+/// file size is checked after each chunk, so this bounds how far a file can overshoot the target.
+const WRITE_CHUNK_ROWS: usize = 1024;
+
+/// A least-recently-used cache of the footers (metadata) of parquet files, so that a file whose
+/// footer was already read (e.g. to skip row groups, or by an earlier scan of the same snapshot) is
+/// read without another round trip to the object store. Entries are keyed by the file's location,
+/// size and modification time, so a rewritten file never gets the footer of its previous version.
+#[derive(Debug)]
+struct FooterCache {
+    capacity: usize,
+    state: Mutex<FooterCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct FooterCacheState {
+    // each footer, with the tick it was last used at
+    entries: HashMap<FooterCacheKey, (ArrowReaderMetadata, u64)>,
+    // the key of each entry by the tick it was last used at, least recently used first
+    lru: BTreeMap<u64, FooterCacheKey>,
+    tick: u64,
+}
+
+type FooterCacheKey = (url::Url, u64, i64);
+
+impl FooterCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    fn key(file: &FileMeta) -> FooterCacheKey {
+        (file.location.clone(), file.size, file.last_modified)
+    }
+
+    fn get(&self, file: &FileMeta) -> Option<ArrowReaderMetadata> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.tick += 1;
+        let FooterCacheState { entries, lru, tick } = &mut *state;
+        let (metadata, last_used) = entries.get_mut(&Self::key(file))?;
+        let key = lru.remove(last_used)?;
+        *last_used = *tick;
+        lru.insert(*tick, key);
+        Some(metadata.clone())
+    }
+
+    fn insert(&self, file: &FileMeta, metadata: ArrowReaderMetadata) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.tick += 1;
+        let FooterCacheState { entries, lru, tick } = &mut *state;
+        let key = Self::key(file);
+        if let Some((_, last_used)) = entries.insert(key.clone(), (metadata, *tick)) {
+            lru.remove(&last_used);
+        }
+        lru.insert(*tick, key);
+        while entries.len() > self.capacity {
+            let Some((_, key)) = lru.pop_first() else {
+                break;
+            };
+            entries.remove(&key);
+        }
+    }
+}
+
+/// Get the footer of `file` from `cache` if it has it, or else load it with `reader` (and cache
+/// it).
+async fn load_metadata<R: AsyncFileReader>(
+    cache: Option<&FooterCache>,
+    file: &FileMeta,
+    reader: &mut R,
+) -> DeltaResult<ArrowReaderMetadata> {
+    if let Some(metadata) = cache.and_then(|cache| cache.get(file)) {
+        return Ok(metadata);
+    }
+    let metadata = ArrowReaderMetadata::load_async(reader, Default::default()).await?;
+    if let Some(cache) = cache {
+        cache.insert(file, metadata.clone());
+    }
+    Ok(metadata)
 }
 
 /// Metadata of a data file (typically a parquet file), currently just includes the file metadata
@@ -41,17 +154,28 @@ pub struct DefaultParquetHandler<E: TaskExecutor> {
 #[derive(Debug)]
 pub struct DataFileMetadata {
     file_meta: FileMeta,
+    num_records: Option<u64>,
 }
 
 impl DataFileMetadata {
     pub fn new(file_meta: FileMeta) -> Self {
-        Self { file_meta }
+        Self {
+            file_meta,
+            num_records: None,
+        }
+    }
+
+    /// Set the number of records in the file, which is written as the `numRecords` statistic of
+    /// its add action. Tables with row tracking need it to assign row ids to the file.
+    pub fn with_num_records(mut self, num_records: u64) -> Self {
+        self.num_records = Some(num_records);
+        self
     }
 
     // convert DataFileMetadata into a record batch which matches the 'add_files_schema' schema
     fn as_record_batch(
         &self,
-        partition_values: &HashMap<String, String>,
+        partition_values: &HashMap<String, Option<String>>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
         let DataFileMetadata {
@@ -61,6 +185,7 @@ impl DataFileMetadata {
                     last_modified,
                     size,
                 },
+            num_records,
         } = self;
         let add_files_schema = crate::transaction::add_files_schema();
 
@@ -76,7 +201,7 @@ impl DataFileMetadata {
         let mut builder = MapBuilder::new(Some(names), key_builder, val_builder);
         for (k, v) in partition_values {
             builder.keys().append_value(k);
-            builder.values().append_value(v);
+            builder.values().append_option(v.as_ref());
         }
         builder.append(true).unwrap();
         let partitions = Arc::new(builder.finish());
@@ -87,22 +212,52 @@ impl DataFileMetadata {
         let size = Arc::new(Int64Array::from(vec![size]));
         let data_change = Arc::new(BooleanArray::from(vec![data_change]));
         let modification_time = Arc::new(Int64Array::from(vec![*last_modified]));
+        let stats = num_records.map(|num_records| format!("{{\"numRecords\":{num_records}}}"));
+        let stats = Arc::new(StringArray::from(vec![stats]));
         Ok(Box::new(ArrowEngineData::new(RecordBatch::try_new(
             Arc::new(add_files_schema.as_ref().try_into_arrow()?),
-            vec![path, partitions, size, modification_time, data_change],
+            vec![
+                path,
+                partitions,
+                size,
+                modification_time,
+                data_change,
+                stats,
+            ],
         )?)))
     }
 }
 
 impl<E: TaskExecutor> DefaultParquetHandler<E> {
     pub fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
+        Self::new_with_registry(Arc::new(ObjectStoreRegistry::new(store)), task_executor)
+    }
+
+    /// Create a handler which reads and writes each file using the store registered for its URL
+    /// in `stores`.
+    pub fn new_with_registry(stores: Arc<ObjectStoreRegistry>, task_executor: Arc<E>) -> Self {
         Self {
-            store,
+            stores,
             task_executor,
-            readahead: 10,
+            batch_size: DEFAULT_BATCH_SIZE,
+            readahead: DEFAULT_READAHEAD,
+            memory_budget: None,
+            target_file_size: None,
+            max_row_group_size: None,
+            cast_policy: CastPolicy::default(),
+            id_generator: Arc::new(RandomIdGenerator),
+            footer_cache: None,
         }
     }
 
+    /// Max number of rows per batch yielded by [Self::read_parquet_files()].
+    ///
+    /// Defaults to 1024.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
     /// Max number of batches to read ahead while executing [Self::read_parquet_files()].
     ///
     /// Defaults to 10.
@@ -111,42 +266,186 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         self
     }
 
-    // Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
-    // metadata (where `<uuid>` is a generated UUIDv4).
+    /// Max number of bytes of batches to read ahead while executing [Self::read_parquet_files()].
+    /// Reading pauses while the consumer lags this far behind, in addition to the limit set by
+    /// [Self::with_readahead()].
+    ///
+    /// Defaults to no limit.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+
+    /// The [`CastPolicy`] used to cast columns read from parquet to the requested types.
+    ///
+    /// Defaults to [`CastPolicy::default()`].
+    pub fn with_cast_policy(mut self, cast_policy: CastPolicy) -> Self {
+        self.cast_policy = cast_policy;
+        self
+    }
+
+    /// The [`IdGenerator`] used to name new parquet files.
+    ///
+    /// Defaults to [`RandomIdGenerator`].
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = id_generator;
+        self
+    }
+
+    /// The target size of each parquet file written by [Self::write_parquet_file()], in bytes.
+    /// Data that would exceed the target is rolled over into a new file. Files may exceed the
+    /// target by up to the size of one [write chunk](WRITE_CHUNK_ROWS) of rows.
+    ///
+    /// Defaults to no limit, i.e. each call writes a single file.
+    pub fn with_target_file_size(mut self, target_file_size: u64) -> Self {
+        self.target_file_size = Some(target_file_size);
+        self
+    }
+
+    /// Cache the footers of up to `footer_cache_size` parquet files, so that reading a file again
+    /// (e.g. in repeated scans of the same snapshot, or after [Self::read_parquet_footer()]) does
+    /// not read its footer from storage again. The least recently used footer is evicted first.
+    ///
+    /// Defaults to no cache.
+    pub fn with_footer_cache_size(mut self, footer_cache_size: usize) -> Self {
+        self.footer_cache = Some(Arc::new(FooterCache::new(footer_cache_size)));
+        self
+    }
+
+    /// The maximum number of rows in each row group of the parquet files written by
+    /// [Self::write_parquet_file()].
+    ///
+    /// Defaults to the parquet writer's default.
+    pub fn with_max_row_group_size(mut self, max_row_group_size: usize) -> Self {
+        self.max_row_group_size = Some(max_row_group_size);
+        self
+    }
+
+    // The opener for `files`, which must be non-empty.
+    fn file_opener(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> Box<dyn FileOpener> {
+        // get the first FileMeta to decide how to fetch the file.
+        // NB: This means that every file in `FileMeta` _must_ have the same scheme or things will break
+        // s3://    -> aws   (ParquetOpener)
+        // nothing  -> local (ParquetOpener)
+        // https:// -> assume presigned URL (and fetch without object_store)
+        //   -> reqwest to get data
+        //   -> parse to parquet
+        // SAFETY: callers check that `files` is non-empty.
+        if files[0].location.is_presigned() {
+            Box::new(PresignedUrlOpener::new(
+                self.batch_size,
+                physical_schema,
+                predicate,
+                self.cast_policy,
+            ))
+        } else {
+            Box::new(ParquetOpener::new(
+                self.batch_size,
+                physical_schema,
+                predicate,
+                self.stores.clone(),
+                self.cast_policy,
+                self.footer_cache.clone(),
+            ))
+        }
+    }
+
+    // Write `data` to one or more `{path}/<uuid>.parquet` files as parquet using ArrowWriter and
+    // return the parquet metadata of each file (where `<uuid>` is generated by the handler's
+    // `IdGenerator`). A new file is started whenever the current one reaches the configured target
+    // file size.
     //
-    // Note: after encoding the data as parquet, this issues a PUT followed by a HEAD to storage in
-    // order to obtain metadata about the object just written.
+    // Note: after encoding the data as parquet, this issues a PUT followed by a HEAD to storage for
+    // each file in order to obtain metadata about the object just written.
     async fn write_parquet(
         &self,
         path: &url::Url,
         data: Box<dyn EngineData>,
-    ) -> DeltaResult<DataFileMetadata> {
+    ) -> DeltaResult<Vec<DataFileMetadata>> {
+        // fail if path does not end with a trailing slash
+        if !path.path().ends_with('/') {
+            return Err(Error::generic(format!(
+                "Path must end with a trailing slash: {path}"
+            )));
+        }
         let batch: Box<_> = ArrowEngineData::try_from_engine_data(data)?;
         let record_batch = batch.record_batch();
+        let props = self.writer_properties();
+        let new_writer = || ArrowWriter::try_new(vec![], record_batch.schema(), props.clone());
+
+        let num_rows = record_batch.num_rows();
+        let Some(target_file_size) = self.target_file_size else {
+            let mut writer = new_writer()?;
+            writer.write(record_batch)?;
+            let file = self
+                .put_parquet(path, writer.into_inner()?, num_rows as u64)
+                .await?;
+            return Ok(vec![file]);
+        };
+
+        let mut files = vec![];
+        let mut writer = new_writer()?;
+        let mut offset = 0;
+        // the offset of the first row of the file being written
+        let mut file_offset = 0;
+        while offset < num_rows {
+            let len = WRITE_CHUNK_ROWS.min(num_rows - offset);
+            writer.write(&record_batch.slice(offset, len))?;
+            offset += len;
+            let written = writer.bytes_written() + writer.in_progress_size();
+            if offset < num_rows && written as u64 >= target_file_size {
+                let full = std::mem::replace(&mut writer, new_writer()?);
+                let num_records = (offset - file_offset) as u64;
+                files.push(
+                    self.put_parquet(path, full.into_inner()?, num_records)
+                        .await?,
+                );
+                file_offset = offset;
+            }
+        }
+        let num_records = (num_rows - file_offset) as u64;
+        files.push(
+            self.put_parquet(path, writer.into_inner()?, num_records)
+                .await?,
+        );
+        Ok(files)
+    }
 
-        let mut buffer = vec![];
-        let mut writer = ArrowWriter::try_new(&mut buffer, record_batch.schema(), None)?;
-        writer.write(record_batch)?;
-        writer.close()?; // writer must be closed to write footer
+    // The properties of the parquet files written by this handler
+    fn writer_properties(&self) -> Option<WriterProperties> {
+        self.max_row_group_size.map(|max_row_group_size| {
+            WriterProperties::builder()
+                .set_max_row_group_size(max_row_group_size)
+                .build()
+        })
+    }
 
+    // Write `buffer` (an encoded parquet file of `num_records` rows) to `{path}/<uuid>.parquet` and
+    // return its metadata.
+    async fn put_parquet(
+        &self,
+        path: &url::Url,
+        buffer: Vec<u8>,
+        num_records: u64,
+    ) -> DeltaResult<DataFileMetadata> {
         let size: u64 = buffer
             .len()
             .try_into()
             .map_err(|_| Error::generic("unable to convert usize to u64"))?;
-        let name: String = format!("{}.parquet", Uuid::new_v4());
-        // fail if path does not end with a trailing slash
-        if !path.path().ends_with('/') {
-            return Err(Error::generic(format!(
-                "Path must end with a trailing slash: {path}"
-            )));
-        }
+        let name: String = format!("{}.parquet", self.id_generator.next_id());
         let path = path.join(&name)?;
 
-        self.store
+        let store = self.stores.get_store(&path);
+        store
             .put(&Path::from_url_path(path.path())?, buffer.into())
             .await?;
 
-        let metadata = self.store.head(&Path::from_url_path(path.path())?).await?;
+        let metadata = store.head(&Path::from_url_path(path.path())?).await?;
         let modification_time = metadata.last_modified.timestamp_millis();
         let metadata_size = metadata.size;
         #[cfg(not(feature = "arrow-55"))]
@@ -161,12 +460,14 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         }
 
         let file_meta = FileMeta::new(path, modification_time, size);
-        Ok(DataFileMetadata::new(file_meta))
+        Ok(DataFileMetadata::new(file_meta).with_num_records(num_records))
     }
 
-    /// Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
-    /// metadata as an EngineData batch which matches the [add file metadata] schema (where `<uuid>`
-    /// is a generated UUIDv4).
+    /// Write `data` to one or more `{path}/<uuid>.parquet` files as parquet using ArrowWriter and
+    /// return the parquet metadata as an EngineData batch which matches the [add file metadata]
+    /// schema, with one row per file written (where `<uuid>` is generated by the handler's
+    /// [`IdGenerator`], see [Self::with_id_generator()]). More than one file is only written if a
+    /// target file size is set (see [Self::with_target_file_size()]).
     ///
     /// [add file metadata]: crate::transaction::add_files_schema
     pub async fn write_parquet_file(
@@ -176,8 +477,39 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         partition_values: HashMap<String, String>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
-        let parquet_metadata = self.write_parquet(path, data).await?;
-        parquet_metadata.as_record_batch(&partition_values, data_change)
+        let partition_values = partition_values
+            .into_iter()
+            .map(|(k, v)| (k, Some(v)))
+            .collect();
+        self.write_parquet_files(path, data, &partition_values, data_change)
+            .await
+    }
+
+    // Like `write_parquet_file`, but partition values may be null.
+    pub(crate) async fn write_parquet_files(
+        &self,
+        path: &url::Url,
+        data: Box<dyn EngineData>,
+        partition_values: &HashMap<String, Option<String>>,
+        data_change: bool,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let files = self.write_parquet(path, data).await?;
+        let mut batches = Vec::with_capacity(files.len());
+        for file in &files {
+            let batch = file.as_record_batch(partition_values, data_change)?;
+            batches.push(
+                ArrowEngineData::try_from_engine_data(batch)?
+                    .record_batch()
+                    .clone(),
+            );
+        }
+        let schema = Arc::new(
+            crate::transaction::add_files_schema()
+                .as_ref()
+                .try_into_arrow()?,
+        );
+        let batch = concat_batches(&schema, &batches)?;
+        Ok(Box::new(ArrowEngineData::new(batch)))
     }
 }
 
@@ -192,36 +524,206 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
             return Ok(Box::new(std::iter::empty()));
         }
 
-        // get the first FileMeta to decide how to fetch the file.
-        // NB: This means that every file in `FileMeta` _must_ have the same scheme or things will break
-        // s3://    -> aws   (ParquetOpener)
-        // nothing  -> local (ParquetOpener)
-        // https:// -> assume presigned URL (and fetch without object_store)
-        //   -> reqwest to get data
-        //   -> parse to parquet
-        // SAFETY: we did is_empty check above, this is ok.
-        let file_opener: Box<dyn FileOpener> = if files[0].location.is_presigned() {
-            Box::new(PresignedUrlOpener::new(
-                1024,
-                physical_schema.clone(),
-                predicate,
-            ))
-        } else {
-            Box::new(ParquetOpener::new(
-                1024,
-                physical_schema.clone(),
-                predicate,
-                self.store.clone(),
-            ))
-        };
+        let file_opener = self.file_opener(files, physical_schema.clone(), predicate);
         FileStream::new_async_read_iterator(
             self.task_executor.clone(),
             Arc::new(physical_schema.as_ref().try_into_arrow()?),
             file_opener,
             files,
             self.readahead,
+            self.memory_budget,
         )
     }
+
+    fn read_parquet_footer(&self, file: &FileMeta) -> DeltaResult<ParquetFooter> {
+        parquet_footer(self.read_metadata(file)?.metadata())
+    }
+
+    fn plan_parquet_splits(
+        &self,
+        file: &FileMeta,
+        target_split_size: u64,
+    ) -> DeltaResult<Vec<ParquetSplit>> {
+        let metadata = self.read_metadata(file)?;
+        Ok(plan_parquet_splits(
+            file,
+            metadata.metadata(),
+            target_split_size,
+        ))
+    }
+
+    fn read_parquet_splits(
+        &self,
+        splits: &[ParquetSplit],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        if splits.is_empty() {
+            return Ok(Box::new(std::iter::empty()));
+        }
+
+        let files = splits
+            .iter()
+            .map(|split| {
+                let range = split
+                    .range
+                    .as_ref()
+                    .map(|range| -> DeltaResult<_> {
+                        let start = i64::try_from(range.start).map_err(Error::generic_err)?;
+                        let end = i64::try_from(range.end).map_err(Error::generic_err)?;
+                        Ok(start..end)
+                    })
+                    .transpose()?;
+                Ok((split.file.clone(), range))
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        let first_file = std::slice::from_ref(&files[0].0);
+        let file_opener = self.file_opener(first_file, physical_schema.clone(), predicate);
+        FileStream::new_with_ranges(
+            files,
+            Arc::new(physical_schema.as_ref().try_into_arrow()?),
+            file_opener,
+        )?
+        .into_async_read_iterator(
+            self.task_executor.clone(),
+            self.readahead,
+            self.memory_budget,
+        )
+    }
+
+    fn write_parquet_data(
+        &self,
+        location: &url::Url,
+        schema: SchemaRef,
+        data: Box<dyn Iterator<Item = DeltaResult<FilteredEngineData>> + Send + '_>,
+    ) -> DeltaResult<FileMeta> {
+        let schema: ArrowSchemaRef = Arc::new(schema.as_ref().try_into_arrow()?);
+        let store = self.stores.get_store(location);
+        let path = Path::from_url_path(location.path())?;
+        // The writer only buffers the row group being written, and the object writer uploads the
+        // file in parts once it outgrows a single put.
+        let object_writer = ParquetObjectWriter::new(store.clone(), path.clone());
+        let mut writer =
+            AsyncArrowWriter::try_new(object_writer, schema.clone(), self.writer_properties())?;
+        for data in data {
+            let data = data?;
+            let batch = ArrowEngineData::try_from_engine_data(data.data)?;
+            let batch = filter_record_batch(batch.record_batch(), &data.selection_vector.into())?;
+            let batch = conform_batch(&batch, &schema)?;
+            writer = self.task_executor.block_on(async move {
+                writer.write(&batch).await?;
+                Ok::<_, Error>(writer)
+            })?;
+        }
+
+        let location = location.clone();
+        self.task_executor.block_on(async move {
+            writer.close().await?;
+            let metadata = store.head(&path).await?;
+            let size = metadata.size;
+            #[cfg(not(feature = "arrow-55"))]
+            let size: u64 = size
+                .try_into()
+                .map_err(|_| Error::generic("Failed to convert parquet metadata 'size' to u64"))?;
+            let modification_time = metadata.last_modified.timestamp_millis();
+            Ok(FileMeta::new(location, modification_time, size))
+        })
+    }
+}
+
+// Conform `batch` to `schema`, so that batches with different schemas can be written to the same
+// file: columns and struct fields are matched by name, the ones `batch` doesn't have are filled
+// with nulls, and nullability is taken from `schema`.
+fn conform_batch(batch: &RecordBatch, schema: &ArrowSchemaRef) -> DeltaResult<RecordBatch> {
+    let columns = conform_columns(schema.fields(), batch.num_rows(), |name| {
+        batch.column_by_name(name)
+    })?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+fn conform_columns<'a>(
+    fields: &Fields,
+    len: usize,
+    column_by_name: impl Fn(&str) -> Option<&'a ArrayRef>,
+) -> DeltaResult<Vec<ArrayRef>> {
+    fields
+        .iter()
+        .map(|field| match column_by_name(field.name()) {
+            Some(column) => conform_array(column, field.data_type()),
+            None => Ok(new_null_array(field.data_type(), len)),
+        })
+        .collect()
+}
+
+fn conform_array(array: &ArrayRef, data_type: &ArrowDataType) -> DeltaResult<ArrayRef> {
+    match (array.as_struct_opt(), data_type) {
+        (Some(struct_array), ArrowDataType::Struct(fields)) => {
+            let columns = conform_columns(fields, struct_array.len(), |name| {
+                struct_array.column_by_name(name)
+            })?;
+            let nulls = struct_array.nulls().cloned();
+            Ok(Arc::new(StructArray::try_new(
+                fields.clone(),
+                columns,
+                nulls,
+            )?))
+        }
+        _ => Ok(cast(array, data_type)?),
+    }
+}
+
+impl<E: TaskExecutor> DefaultParquetHandler<E> {
+    // Read the metadata in the footer of the parquet file at `file`
+    fn read_metadata(&self, file: &FileMeta) -> DeltaResult<ArrowReaderMetadata> {
+        if file.location.is_presigned() {
+            return Err(Error::unsupported(format!(
+                "Reading the footer of presigned URL {} is not supported",
+                file.location
+            )));
+        }
+        let store = self.stores.get_store(&file.location);
+        let path = Path::from_url_path(file.location.path())?;
+        let footer_cache = self.footer_cache.clone();
+        let file = file.clone();
+        self.task_executor.block_on(async move {
+            #[cfg(feature = "arrow-55")]
+            let size = file.size;
+            #[cfg(feature = "arrow-55")]
+            let mut reader = match size {
+                0 => ParquetObjectReader::new(store, path),
+                size => ParquetObjectReader::new(store, path).with_file_size(size),
+            };
+            #[cfg(all(feature = "arrow-54", not(feature = "arrow-55")))]
+            let mut reader = {
+                let meta = store.head(&path).await?;
+                ParquetObjectReader::new(store, meta)
+            };
+            load_metadata(footer_cache.as_deref(), &file, &mut reader).await
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<E: TaskExecutor> crate::AsyncParquetHandler for DefaultParquetHandler<E> {
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<crate::FileDataReadResultStream> {
+        if files.is_empty() {
+            return Ok(futures::stream::empty().boxed());
+        }
+        let file_opener = self.file_opener(files, physical_schema.clone(), predicate);
+        let stream = FileStream::new(
+            files.to_vec(),
+            Arc::new(physical_schema.as_ref().try_into_arrow()?),
+            file_opener,
+        )?;
+        Ok(stream
+            .map_ok(|batch| -> Box<dyn EngineData> { Box::new(ArrowEngineData::new(batch)) })
+            .boxed())
+    }
 }
 
 /// Implements [`FileOpener`] for a parquet file
@@ -231,7 +733,9 @@ struct ParquetOpener {
     table_schema: SchemaRef,
     predicate: Option<PredicateRef>,
     limit: Option<usize>,
-    store: Arc<DynObjectStore>,
+    stores: Arc<ObjectStoreRegistry>,
+    cast_policy: CastPolicy,
+    footer_cache: Option<Arc<FooterCache>>,
 }
 
 impl ParquetOpener {
@@ -239,28 +743,34 @@ impl ParquetOpener {
         batch_size: usize,
         table_schema: SchemaRef,
         predicate: Option<PredicateRef>,
-        store: Arc<DynObjectStore>,
+        stores: Arc<ObjectStoreRegistry>,
+        cast_policy: CastPolicy,
+        footer_cache: Option<Arc<FooterCache>>,
     ) -> Self {
         Self {
             batch_size,
             table_schema,
             predicate,
             limit: None,
-            store,
+            stores,
+            cast_policy,
+            footer_cache,
         }
     }
 }
 
 impl FileOpener for ParquetOpener {
-    fn open(&self, file_meta: FileMeta, _range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture> {
+    fn open(&self, file_meta: FileMeta, range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture> {
         let path = Path::from_url_path(file_meta.location.path())?;
-        let store = self.store.clone();
+        let store = self.stores.get_store(&file_meta.location);
 
         let batch_size = self.batch_size;
         // let projection = self.projection.clone();
         let table_schema = self.table_schema.clone();
         let predicate = self.predicate.clone();
         let limit = self.limit;
+        let cast_policy = self.cast_policy;
+        let footer_cache = self.footer_cache.clone();
 
         Ok(Box::pin(async move {
             #[cfg(feature = "arrow-55")]
@@ -293,32 +803,36 @@ impl FileOpener for ParquetOpener {
                 let meta = store.head(&path).await?;
                 ParquetObjectReader::new(store, meta)
             };
-            let metadata = ArrowReaderMetadata::load_async(&mut reader, Default::default()).await?;
-            let parquet_schema = metadata.schema();
+            let metadata = load_metadata(footer_cache.as_deref(), &file_meta, &mut reader).await?;
+            let parquet_schema = metadata.schema().clone();
             let (indices, requested_ordering) =
-                get_requested_indices(&table_schema, parquet_schema)?;
-            let options = ArrowReaderOptions::new(); //.with_page_index(enable_page_index);
-            let mut builder =
-                ParquetRecordBatchStreamBuilder::new_with_options(reader, options).await?;
+                get_requested_indices(&table_schema, &parquet_schema)?;
+            let mut builder = ParquetRecordBatchStreamBuilder::new_with_metadata(reader, metadata);
             if let Some(mask) = generate_mask(
                 &table_schema,
-                parquet_schema,
+                &parquet_schema,
                 builder.parquet_schema(),
                 &indices,
             ) {
                 builder = builder.with_projection(mask)
             }
 
-            if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate);
-            }
+            // row indexes can only be filled in if no row groups are skipped
+            let mut row_indexes = RowIndexes::new(&requested_ordering);
+            let predicate = predicate.filter(|_| !row_indexes.is_requested());
+            let first_row_index;
+            (builder, first_row_index) =
+                builder.with_row_group_selection(range.as_ref(), predicate.as_deref());
+            row_indexes.start_at(first_row_index);
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
 
             let stream = builder.with_batch_size(batch_size).build()?;
 
-            let stream = stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering));
+            let stream = stream.map(move |rbr| {
+                fixup_parquet_read(rbr?, &requested_ordering, &cast_policy, &mut row_indexes)
+            });
             Ok(stream.boxed())
         }))
     }
@@ -331,6 +845,7 @@ struct PresignedUrlOpener {
     limit: Option<usize>,
     table_schema: SchemaRef,
     client: reqwest::Client,
+    cast_policy: CastPolicy,
 }
 
 impl PresignedUrlOpener {
@@ -338,6 +853,7 @@ impl PresignedUrlOpener {
         batch_size: usize,
         schema: SchemaRef,
         predicate: Option<PredicateRef>,
+        cast_policy: CastPolicy,
     ) -> Self {
         Self {
             batch_size,
@@ -345,17 +861,19 @@ impl PresignedUrlOpener {
             predicate,
             limit: None,
             client: reqwest::Client::new(),
+            cast_policy,
         }
     }
 }
 
 impl FileOpener for PresignedUrlOpener {
-    fn open(&self, file_meta: FileMeta, _range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture> {
+    fn open(&self, file_meta: FileMeta, range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture> {
         let batch_size = self.batch_size;
         let table_schema = self.table_schema.clone();
         let predicate = self.predicate.clone();
         let limit = self.limit;
         let client = self.client.clone(); // uses Arc internally according to reqwest docs
+        let cast_policy = self.cast_policy;
 
         Ok(Box::pin(async move {
             // fetch the file from the interweb
@@ -377,9 +895,13 @@ impl FileOpener for PresignedUrlOpener {
                 builder = builder.with_projection(mask)
             }
 
-            if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate);
-            }
+            // row indexes can only be filled in if no row groups are skipped
+            let mut row_indexes = RowIndexes::new(&requested_ordering);
+            let predicate = predicate.filter(|_| !row_indexes.is_requested());
+            let first_row_index;
+            (builder, first_row_index) =
+                builder.with_row_group_selection(range.as_ref(), predicate.as_deref());
+            row_indexes.start_at(first_row_index);
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
@@ -387,7 +909,9 @@ impl FileOpener for PresignedUrlOpener {
             let reader = builder.with_batch_size(batch_size).build()?;
 
             let stream = futures::stream::iter(reader);
-            let stream = stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering));
+            let stream = stream.map(move |rbr| {
+                fixup_parquet_read(rbr?, &requested_ordering, &cast_policy, &mut row_indexes)
+            });
             Ok(stream.boxed())
         }))
     }
@@ -402,9 +926,12 @@ mod tests {
     use crate::object_store::{local::LocalFileSystem, memory::InMemory, ObjectStore};
     use url::Url;
 
+    use crate::arrow::datatypes::Int64Type;
     use crate::engine::arrow_conversion::TryIntoKernel as _;
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::expressions::{column_expr, Expression, Predicate};
+    use crate::schema::{DataType, StructField, StructType};
     use crate::EngineData;
 
     use itertools::Itertools;
@@ -419,6 +946,63 @@ mod tests {
             .map(Into::into)
     }
 
+    #[test]
+    fn test_read_parquet_footer() {
+        let store = Arc::new(LocalFileSystem::new());
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
+        )).unwrap();
+        let file = FileMeta {
+            location: url::Url::from_file_path(path).unwrap(),
+            last_modified: 0,
+            size: 0,
+        };
+        let footer = handler.read_parquet_footer(&file).unwrap();
+        assert_eq!(footer.num_rows, 10);
+    }
+
+    #[test]
+    fn test_footer_cache() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
+        )).unwrap();
+        let file = FileMeta::new(Url::from_file_path(path).unwrap(), 0, 0);
+        let store = Arc::new(LocalFileSystem::new());
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+            .with_footer_cache_size(2);
+        let cache = handler.footer_cache.clone().unwrap();
+        assert!(cache.get(&file).is_none());
+
+        // reading the footer caches it, and reading the file uses the cached footer
+        assert_eq!(handler.read_parquet_footer(&file).unwrap().num_rows, 10);
+        let metadata = cache.get(&file).unwrap();
+        assert_eq!(metadata.metadata().file_metadata().num_rows(), 10);
+        let schema = Arc::new(StructType::new([StructField::nullable(
+            "value",
+            DataType::INTEGER,
+        )]));
+        let num_rows: usize = handler
+            .read_parquet_files(std::slice::from_ref(&file), schema, None)
+            .unwrap()
+            .map(|data| into_record_batch(data).unwrap().num_rows())
+            .sum();
+        assert_eq!(num_rows, 10);
+
+        // a rewritten file (with a new modification time) is not served the old footer
+        let rewritten = FileMeta::new(file.location.clone(), 1, 0);
+        assert!(cache.get(&rewritten).is_none());
+
+        // the least recently used footer is evicted first
+        let other = |name: &str| FileMeta::new(file.location.join(name).unwrap(), 0, 0);
+        cache.insert(&other("a"), metadata.clone());
+        assert!(cache.get(&file).is_some());
+        cache.insert(&other("b"), metadata);
+        assert!(cache.get(&other("a")).is_none());
+        assert!(cache.get(&file).is_some());
+        assert!(cache.get(&other("b")).is_some());
+    }
+
     #[tokio::test]
     async fn test_read_parquet_files() {
         let store = Arc::new(LocalFileSystem::new());
@@ -462,14 +1046,166 @@ mod tests {
         assert_eq!(data[0].num_rows(), 10);
     }
 
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_read_parquet_files_async() {
+        use crate::schema::{DataType, StructField, StructType};
+        use futures::TryStreamExt as _;
+
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
+        )).unwrap();
+        let url = url::Url::from_file_path(path).unwrap();
+        let size = std::fs::metadata(url.path()).unwrap().len();
+        let files = &[FileMeta::new(url, 0, size)];
+        let physical_schema = Arc::new(StructType::new([StructField::nullable(
+            "value",
+            DataType::INTEGER,
+        )]));
+
+        let handler = DefaultParquetHandler::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        );
+        let data: Vec<RecordBatch> =
+            crate::AsyncParquetHandler::read_parquet_files(&handler, files, physical_schema, None)
+                .unwrap()
+                .map(into_record_batch)
+                .try_collect()
+                .await
+                .unwrap();
+
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].num_rows(), 10);
+        assert_eq!(data[0].num_columns(), 1);
+    }
+
+    #[test]
+    fn test_read_parquet_splits() {
+        // a file of 5 row groups with 10 rows each, whose values are their row indexes
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.parquet");
+        let batch = RecordBatch::try_from_iter([(
+            "value",
+            Arc::new(Int64Array::from_iter_values(0..50)) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(10)
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            std::fs::File::create(&path).unwrap(),
+            batch.schema(),
+            Some(props),
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        let file = FileMeta::new(Url::from_file_path(&path).unwrap(), 0, size);
+
+        let store = Arc::new(LocalFileSystem::new());
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let whole_file = handler.plan_parquet_splits(&file, u64::MAX).unwrap();
+        assert_eq!(whole_file.len(), 1);
+        let splits = handler.plan_parquet_splits(&file, 1).unwrap();
+        assert_eq!(splits.len(), 5);
+        let ranges = splits.iter().map(|split| split.range.clone().unwrap());
+        assert!(ranges.tuple_windows().all(|(a, b)| a.end <= b.start));
+
+        let schema = Arc::new(StructType::new([
+            StructField::nullable("value", DataType::LONG),
+            StructField::nullable(crate::scan::ROW_INDEX_COLUMN_NAME, DataType::LONG),
+        ]));
+        let mut values = vec![];
+        for split in &splits {
+            let batches: Vec<RecordBatch> = handler
+                .read_parquet_splits(std::slice::from_ref(split), schema.clone(), None)
+                .unwrap()
+                .map(into_record_batch)
+                .try_collect()
+                .unwrap();
+            let num_rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+            assert_eq!(num_rows, 10);
+            for batch in batches {
+                // row indexes are relative to the file, not the split
+                assert_eq!(batch.column(0), batch.column(1));
+                values.extend(
+                    batch
+                        .column(0)
+                        .as_primitive::<Int64Type>()
+                        .values()
+                        .iter()
+                        .copied(),
+                );
+            }
+        }
+        assert_eq!(values, (0..50).collect_vec());
+
+        // row groups whose stats exclude the predicate are skipped within a split
+        let predicate = Arc::new(Predicate::gt(
+            column_expr!("value"),
+            Expression::literal(35i64),
+        ));
+        let schema = Arc::new(StructType::new([StructField::nullable(
+            "value",
+            DataType::LONG,
+        )]));
+        let num_rows: usize = handler
+            .read_parquet_splits(&whole_file, schema, Some(predicate))
+            .unwrap()
+            .map(|data| into_record_batch(data).unwrap().num_rows())
+            .sum();
+        assert_eq!(num_rows, 20);
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_files_with_memory_budget() {
+        let store = Arc::new(LocalFileSystem::new());
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/table-with-dv-small/part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
+        )).unwrap();
+        let url = url::Url::from_file_path(path).unwrap();
+        let location = Path::from_url_path(url.path()).unwrap();
+        let meta = store.head(&location).await.unwrap();
+        let reader = ParquetObjectReader::new(store.clone(), location);
+        let physical_schema = ParquetRecordBatchStreamBuilder::new(reader)
+            .await
+            .unwrap()
+            .schema()
+            .clone();
+        let meta_size = meta.size;
+        #[cfg(not(feature = "arrow-55"))]
+        let meta_size = meta_size.try_into().unwrap();
+        let files = vec![FileMeta::new(url, meta.last_modified.timestamp(), meta_size); 5];
+
+        // every batch is larger than the budget, so batches are produced one at a time
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+            .with_memory_budget(1);
+        let schema: SchemaRef = Arc::new(physical_schema.try_into_kernel().unwrap());
+        let data: Vec<RecordBatch> = handler
+            .read_parquet_files(&files, schema.clone(), None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+        assert_eq!(data.len(), 5);
+        assert!(data.iter().all(|batch| batch.num_rows() == 10));
+
+        // dropping the iterator early must not leave the producer waiting forever
+        let mut iter = handler.read_parquet_files(&files, schema, None).unwrap();
+        assert!(iter.next().unwrap().is_ok());
+        drop(iter);
+    }
+
     #[test]
     fn test_as_record_batch() {
         let location = Url::parse("file:///test_url").unwrap();
         let size = 1_000_000;
         let last_modified = 10000000000;
         let file_metadata = FileMeta::new(location.clone(), last_modified, size);
-        let data_file_metadata = DataFileMetadata::new(file_metadata);
-        let partition_values = HashMap::from([("partition1".to_string(), "a".to_string())]);
+        let data_file_metadata = DataFileMetadata::new(file_metadata).with_num_records(10);
+        let partition_values = HashMap::from([("partition1".to_string(), Some("a".to_string()))]);
         let data_change = true;
         let actual = data_file_metadata
             .as_record_batch(&partition_values, data_change)
@@ -505,6 +1241,7 @@ mod tests {
                 Arc::new(Int64Array::from(vec![size as i64])),
                 Arc::new(Int64Array::from(vec![last_modified])),
                 Arc::new(BooleanArray::from(vec![data_change])),
+                Arc::new(StringArray::from(vec![r#"{"numRecords":10}"#])),
             ],
         )
         .unwrap();
@@ -526,10 +1263,12 @@ mod tests {
             .unwrap(),
         ));
 
-        let write_metadata = parquet_handler
+        let mut files = parquet_handler
             .write_parquet(&Url::parse("memory:///data/").unwrap(), data)
             .await
             .unwrap();
+        assert_eq!(files.len(), 1);
+        let write_metadata = files.pop().unwrap();
 
         let DataFileMetadata {
             file_meta:
@@ -538,7 +1277,9 @@ mod tests {
                     last_modified,
                     size,
                 },
+            num_records,
         } = write_metadata;
+        assert_eq!(num_records, Some(3));
         let expected_location = Url::parse("memory:///data/").unwrap();
 
         // head the object to get metadata
@@ -572,7 +1313,7 @@ mod tests {
 
         let data: Vec<RecordBatch> = parquet_handler
             .read_parquet_files(
-                &[parquet_file.clone()],
+                std::slice::from_ref(parquet_file),
                 Arc::new(physical_schema.try_into_kernel().unwrap()),
                 None,
             )
@@ -585,6 +1326,150 @@ mod tests {
         assert_eq!(data[0].num_rows(), 3);
     }
 
+    #[tokio::test]
+    async fn test_write_parquet_with_target_file_size() {
+        let store = Arc::new(InMemory::new());
+        let parquet_handler =
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()))
+                .with_target_file_size(16 * 1024)
+                .with_max_row_group_size(500);
+
+        let num_rows = 10 * WRITE_CHUNK_ROWS as i64;
+        let data = Box::new(ArrowEngineData::new(
+            RecordBatch::try_from_iter(vec![(
+                "a",
+                Arc::new(Int64Array::from_iter_values(0..num_rows)) as Arc<dyn Array>,
+            )])
+            .unwrap(),
+        ));
+        let add_files = parquet_handler
+            .write_parquet_file(
+                &Url::parse("memory:///data/").unwrap(),
+                data,
+                HashMap::new(),
+                true,
+            )
+            .await
+            .unwrap();
+        let add_files = ArrowEngineData::try_from_engine_data(add_files).unwrap();
+        let add_files = add_files.record_batch();
+        assert!(add_files.num_rows() > 1);
+
+        let paths = add_files
+            .column_by_name("path")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let sizes = add_files
+            .column_by_name("size")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        let stats = add_files
+            .column_by_name("stats")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let mut next_value = 0;
+        for ((path, size), stats) in paths.iter().zip(sizes.iter()).zip(stats.iter()) {
+            let location = Url::parse(path.unwrap()).unwrap();
+            let path = Path::from_url_path(location.path()).unwrap();
+            let bytes = store.get(&path).await.unwrap().bytes().await.unwrap();
+            assert_eq!(bytes.len() as i64, size.unwrap());
+
+            // each file holds the next contiguous range of rows, in row groups of at most 500
+            let reader = ParquetRecordBatchReaderBuilder::try_new(bytes).unwrap();
+            assert!(reader
+                .metadata()
+                .row_groups()
+                .iter()
+                .all(|row_group| row_group.num_rows() <= 500));
+            let num_records = reader.metadata().file_metadata().num_rows();
+            assert_eq!(stats.unwrap(), format!(r#"{{"numRecords":{num_records}}}"#));
+            for batch in reader.build().unwrap() {
+                let values = batch.unwrap().column(0).clone();
+                let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+                for value in values.iter() {
+                    assert_eq!(value, Some(next_value));
+                    next_value += 1;
+                }
+            }
+        }
+        assert_eq!(next_value, num_rows);
+    }
+
+    #[tokio::test]
+    async fn test_read_nested_leaf_projection() {
+        use crate::arrow::array::StructArray;
+        use crate::arrow::datatypes::{DataType as ArrowDataType, Field};
+        use crate::schema::{DataType, StructField, StructType};
+
+        let store = Arc::new(InMemory::new());
+        let parquet_handler =
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+        // write a file with `id` and `nested: struct<a: long, b: string, c: long>`
+        let nested = StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", ArrowDataType::Int64, true)),
+                Arc::new(Int64Array::from(vec![1, 2, 3])) as Arc<dyn Array>,
+            ),
+            (
+                Arc::new(Field::new("b", ArrowDataType::Utf8, true)),
+                Arc::new(StringArray::from(vec!["x", "y", "z"])) as Arc<dyn Array>,
+            ),
+            (
+                Arc::new(Field::new("c", ArrowDataType::Int64, true)),
+                Arc::new(Int64Array::from(vec![4, 5, 6])) as Arc<dyn Array>,
+            ),
+        ]);
+        let data = Box::new(ArrowEngineData::new(
+            RecordBatch::try_from_iter(vec![
+                (
+                    "id",
+                    Arc::new(Int64Array::from(vec![7, 8, 9])) as Arc<dyn Array>,
+                ),
+                ("nested", Arc::new(nested) as Arc<dyn Array>),
+            ])
+            .unwrap(),
+        ));
+        let mut files = parquet_handler
+            .write_parquet(&Url::parse("memory:///data/").unwrap(), data)
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 1);
+        let write_metadata = files.pop().unwrap();
+
+        // only request `nested.b`: just that leaf should be decoded
+        let read_schema = Arc::new(StructType::new([StructField::nullable(
+            "nested",
+            StructType::new([StructField::nullable("b", DataType::STRING)]),
+        )]));
+        let data: Vec<RecordBatch> = parquet_handler
+            .read_parquet_files(&[write_metadata.file_meta], read_schema, None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+
+        assert_eq!(data.len(), 1);
+        let nested = data[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .unwrap();
+        assert_eq!(nested.column_names(), vec!["b"]);
+        let b = nested
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(b, &StringArray::from(vec!["x", "y", "z"]));
+    }
+
     #[tokio::test]
     async fn test_disallow_non_trailing_slash() {
         let store = Arc::new(InMemory::new());
@@ -3,6 +3,7 @@ use crate::object_store::path::Path;
 use crate::object_store::{Error, ObjectStore};
 use url::Url;
 
+use super::credentials::RefreshingObjectStore;
 use crate::Error as DeltaError;
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock, RwLock};
@@ -60,6 +61,95 @@ where
     parse_url_opts_object_store(url, options)
 }
 
+/// A set of [ObjectStore]s keyed by URL prefix or scheme. Files are read from (and written to)
+/// the store registered for the longest prefix of their URL, else the store registered for their
+/// URL scheme, falling back to a default store. This allows a single engine to read tables whose
+/// files live under multiple buckets (e.g. shallow clones or shared tables) or file systems, each
+/// with their own credentials.
+#[derive(Debug)]
+pub struct ObjectStoreRegistry {
+    default_store: DefaultStore,
+    prefixed_stores: RwLock<Vec<(Url, Arc<dyn ObjectStore>)>>,
+    scheme_stores: RwLock<HashMap<String, Arc<dyn ObjectStore>>>,
+}
+
+#[derive(Debug)]
+enum DefaultStore {
+    Fixed(Arc<dyn ObjectStore>),
+    Refreshing(RefreshingObjectStore),
+}
+
+impl ObjectStoreRegistry {
+    /// Create a registry which routes every URL to `default_store`.
+    pub fn new(default_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            default_store: DefaultStore::Fixed(default_store),
+            prefixed_stores: RwLock::new(vec![]),
+            scheme_stores: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Create a registry which routes every URL to `default_store`, whose credentials are
+    /// refreshed before they expire.
+    pub(crate) fn new_refreshing(default_store: RefreshingObjectStore) -> Self {
+        Self {
+            default_store: DefaultStore::Refreshing(default_store),
+            prefixed_stores: RwLock::new(vec![]),
+            scheme_stores: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Route all URLs starting with `prefix` to `store`, replacing any store already registered
+    /// for the same prefix. The prefix is treated as a directory.
+    pub fn register(&self, mut prefix: Url, store: Arc<dyn ObjectStore>) {
+        if !prefix.path().ends_with('/') {
+            prefix.set_path(&format!("{}/", prefix.path()));
+        }
+        // a poisoned lock only means another registration panicked; the list itself is intact
+        let mut stores = self
+            .prefixed_stores
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        stores.retain(|(existing, _)| *existing != prefix);
+        stores.push((prefix, store));
+    }
+
+    /// Route all URLs with the given `scheme` (e.g. `file` or `s3`) that match no registered
+    /// prefix to `store`, replacing any store already registered for the scheme. Object stores
+    /// address files by path only, so the store must serve every bucket (or host) of the scheme.
+    pub fn register_scheme(&self, scheme: impl Into<String>, store: Arc<dyn ObjectStore>) {
+        // a poisoned lock only means another registration panicked; the map itself is intact
+        let mut stores = self
+            .scheme_stores
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        stores.insert(scheme.into(), store);
+    }
+
+    /// Get the store responsible for `url`.
+    pub fn get_store(&self, url: &Url) -> Arc<dyn ObjectStore> {
+        let stores = self
+            .prefixed_stores
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        let prefixed_store = stores
+            .iter()
+            .filter(|(prefix, _)| url.as_str().starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.as_str().len());
+        if let Some((_, store)) = prefixed_store {
+            return store.clone();
+        }
+        let scheme_stores = self.scheme_stores.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(store) = scheme_stores.get(url.scheme()) {
+            return store.clone();
+        }
+        match &self.default_store {
+            DefaultStore::Fixed(store) => store.clone(),
+            DefaultStore::Refreshing(store) => store.store(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,6 +69,13 @@ impl EnsureDataTypes {
             (&DataType::BOOLEAN, ArrowDataType::Boolean)
             | (&DataType::STRING, ArrowDataType::Utf8)
             | (&DataType::BINARY, ArrowDataType::Binary) => Ok(DataTypeCompat::Identical),
+            // large and view strings and binaries are cast to the types kernel works with
+            (&DataType::STRING, ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View) => {
+                Ok(DataTypeCompat::NeedsCast(ArrowDataType::Utf8))
+            }
+            (&DataType::BINARY, ArrowDataType::LargeBinary | ArrowDataType::BinaryView) => {
+                Ok(DataTypeCompat::NeedsCast(ArrowDataType::Binary))
+            }
             (DataType::Array(inner_type), ArrowDataType::List(arrow_list_field)) => {
                 self.ensure_nullability(
                     "List",
@@ -395,6 +402,30 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn ensure_large_and_view_types() {
+        let needs_cast =
+            |kernel_type: &DataType, arrow_type: ArrowDataType| match ensure_data_types(
+                kernel_type,
+                &arrow_type,
+                true,
+            )
+            .unwrap()
+            {
+                DataTypeCompat::NeedsCast(target) => target,
+                _ => panic!("expected {arrow_type} to need a cast"),
+            };
+        let string = needs_cast(&DataType::STRING, ArrowDataType::LargeUtf8);
+        assert_eq!(string, ArrowDataType::Utf8);
+        let string = needs_cast(&DataType::STRING, ArrowDataType::Utf8View);
+        assert_eq!(string, ArrowDataType::Utf8);
+        let binary = needs_cast(&DataType::BINARY, ArrowDataType::LargeBinary);
+        assert_eq!(binary, ArrowDataType::Binary);
+        let binary = needs_cast(&DataType::BINARY, ArrowDataType::BinaryView);
+        assert_eq!(binary, ArrowDataType::Binary);
+        assert!(ensure_data_types(&DataType::BINARY, &ArrowDataType::LargeUtf8, true).is_err());
+    }
+
     #[test]
     fn ensure_struct() {
         let schema = DataType::struct_type([StructField::nullable(
@@ -9,8 +9,10 @@ pub mod arrow_conversion;
 pub mod arrow_expression;
 #[cfg(feature = "arrow-expression")]
 pub(crate) mod arrow_utils;
+#[cfg(feature = "arrow-expression")]
+pub use self::arrow_utils::CastPolicy;
 #[cfg(feature = "internal-api")]
-pub use self::arrow_utils::{parse_json, to_json_bytes};
+pub use self::arrow_utils::{parse_json, parse_json_stats, to_json_bytes};
 
 #[cfg(feature = "default-engine-base")]
 pub mod default;
@@ -1,4 +1,5 @@
 //! An implementation of parquet row group skipping using data skipping predicates over footer stats.
+use crate::engine::arrow_utils::row_group_byte_range;
 use crate::expressions::{ColumnName, DecimalData, Predicate, Scalar};
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
 use crate::parquet::arrow::arrow_reader::ArrowReaderBuilder;
@@ -8,6 +9,7 @@ use crate::parquet::schema::types::ColumnDescPtr;
 use crate::schema::{DataType, DecimalType, PrimitiveType};
 use chrono::{DateTime, Days};
 use std::collections::HashMap;
+use std::ops::Range;
 use tracing::debug;
 
 #[cfg(test)]
@@ -15,24 +17,47 @@ mod tests;
 
 /// An extension trait for [`ArrowReaderBuilder`] that injects row group skipping capability.
 pub(crate) trait ParquetRowGroupSkipping {
-    /// Instructs the parquet reader to perform row group skipping, eliminating any row group whose
-    /// stats prove that none of the group's rows can satisfy the given `predicate`.
-    fn with_row_group_filter(self, predicate: &Predicate) -> Self;
+    /// Instructs the parquet reader to only read the row groups which start within the byte
+    /// `range` of the file (if any), and to perform row group skipping with `predicate` (if any),
+    /// eliminating any row group whose stats prove that none of the group's rows can satisfy it.
+    /// Also returns the index of the first row of the first row group in `range` (0 without a
+    /// range).
+    fn with_row_group_selection(
+        self,
+        range: Option<&Range<i64>>,
+        predicate: Option<&Predicate>,
+    ) -> (Self, i64)
+    where
+        Self: Sized;
 }
 impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
-    fn with_row_group_filter(self, predicate: &Predicate) -> Self {
-        let indices = self
-            .metadata()
-            .row_groups()
-            .iter()
-            .enumerate()
-            .filter_map(|(index, row_group)| {
-                // If the group survives the filter, return Some(index) so filter_map keeps it.
-                RowGroupFilter::apply(row_group, predicate).then_some(index)
-            })
-            .collect();
-        debug!("with_row_group_filter({predicate:#?}) = {indices:?})");
-        self.with_row_groups(indices)
+    fn with_row_group_selection(
+        self,
+        range: Option<&Range<i64>>,
+        predicate: Option<&Predicate>,
+    ) -> (Self, i64) {
+        if range.is_none() && predicate.is_none() {
+            return (self, 0);
+        }
+        let mut first_row_index = None;
+        let mut rows_before = 0;
+        let mut indices = vec![];
+        for (index, row_group) in self.metadata().row_groups().iter().enumerate() {
+            let in_range = range.is_none_or(|range| {
+                let start = row_group_byte_range(row_group).start;
+                i64::try_from(start).is_ok_and(|start| range.contains(&start))
+            });
+            if in_range {
+                first_row_index.get_or_insert(rows_before);
+                // If the group survives the filter, keep it.
+                if predicate.is_none_or(|predicate| RowGroupFilter::apply(row_group, predicate)) {
+                    indices.push(index);
+                }
+            }
+            rows_before += row_group.num_rows();
+        }
+        debug!("with_row_group_selection({range:?}, {predicate:#?}) = {indices:?})");
+        (self.with_row_groups(indices), first_row_index.unwrap_or(0))
     }
 }
 
@@ -8,7 +8,8 @@ use url::Url;
 use super::read_files;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::parse_json as arrow_parse_json;
-use crate::engine::arrow_utils::to_json_bytes;
+use crate::engine::arrow_utils::parse_json_stats as arrow_parse_json_stats;
+use crate::engine::arrow_utils::{skip_utf8_bom, to_json_bytes};
 use crate::schema::SchemaRef;
 use crate::{
     DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, JsonHandler, PredicateRef,
@@ -23,7 +24,7 @@ fn try_create_from_json(
     _predicate: Option<PredicateRef>,
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<ArrowEngineData>>> {
     let json = ReaderBuilder::new(arrow_schema)
-        .build(BufReader::new(file))?
+        .build(skip_utf8_bom(BufReader::new(file))?)?
         .map(|data| Ok(ArrowEngineData::new(data?)));
     Ok(json)
 }
@@ -46,6 +47,14 @@ impl JsonHandler for SyncJsonHandler {
         arrow_parse_json(json_strings, output_schema)
     }
 
+    fn parse_json_stats(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        arrow_parse_json_stats(json_strings, output_schema)
+    }
+
     // For sync writer we write data to a tmp file then atomically rename it to the final path.
     // This is highly OS-dependent and for now relies on the atomicity of tempfile's
     // `persist_noclobber`.
@@ -126,6 +135,28 @@ mod tests {
         Ok(json)
     }
 
+    #[test]
+    fn test_read_json_files_with_bom_and_crlf() -> DeltaResult<()> {
+        let test_dir = TempDir::new().unwrap();
+        let path = test_dir.path().join("00000000000000000000.json");
+        std::fs::write(
+            &path,
+            "\u{FEFF}{\"dog\":\"remi\"}\r\n{\"dog\":\"wilson\"}\r\n",
+        )?;
+        let url = Url::from_file_path(&path).unwrap();
+        let files = &[FileMeta::new(url, 0, 0)];
+        let schema = Arc::new(crate::schema::StructType::new([
+            crate::schema::StructField::nullable("dog", crate::schema::DataType::STRING),
+        ]));
+
+        let batches: Vec<_> = SyncJsonHandler
+            .read_json_files(files, schema, None)?
+            .collect::<DeltaResult<_>>()?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 2);
+        Ok(())
+    }
+
     #[test]
     fn test_write_json_file_without_overwrite() -> DeltaResult<()> {
         do_test_write_json_file(false)
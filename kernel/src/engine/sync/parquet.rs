@@ -5,10 +5,16 @@ use crate::parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatc
 
 use super::read_files;
 use crate::engine::arrow_data::ArrowEngineData;
-use crate::engine::arrow_utils::{fixup_parquet_read, generate_mask, get_requested_indices};
+use crate::engine::arrow_utils::{
+    fixup_parquet_read, generate_mask, get_requested_indices, parquet_footer, CastPolicy,
+    RowIndexes,
+};
 use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
 use crate::schema::SchemaRef;
-use crate::{DeltaResult, FileDataReadResultIterator, FileMeta, ParquetHandler, PredicateRef};
+use crate::{
+    DeltaResult, Error, FileDataReadResultIterator, FileMeta, ParquetFooter, ParquetHandler,
+    PredicateRef,
+};
 
 pub(crate) struct SyncParquetHandler;
 
@@ -25,11 +31,19 @@ fn try_create_from_parquet(
     if let Some(mask) = generate_mask(&schema, parquet_schema, builder.parquet_schema(), &indices) {
         builder = builder.with_projection(mask);
     }
-    if let Some(predicate) = predicate {
-        builder = builder.with_row_group_filter(predicate.as_ref());
+    let mut row_indexes = RowIndexes::new(&requested_ordering);
+    if let Some(predicate) = predicate.filter(|_| !row_indexes.is_requested()) {
+        (builder, _) = builder.with_row_group_selection(None, Some(&predicate));
     }
     let stream = builder.build()?;
-    Ok(stream.map(move |rbr| fixup_parquet_read(rbr?, &requested_ordering)))
+    Ok(stream.map(move |rbr| {
+        fixup_parquet_read(
+            rbr?,
+            &requested_ordering,
+            &CastPolicy::default(),
+            &mut row_indexes,
+        )
+    }))
 }
 
 impl ParquetHandler for SyncParquetHandler {
@@ -41,4 +55,13 @@ impl ParquetHandler for SyncParquetHandler {
     ) -> DeltaResult<FileDataReadResultIterator> {
         read_files(files, schema, predicate, try_create_from_parquet)
     }
+
+    fn read_parquet_footer(&self, file: &FileMeta) -> DeltaResult<ParquetFooter> {
+        let path = file
+            .location
+            .to_file_path()
+            .map_err(|_| Error::generic("can only read local files"))?;
+        let metadata = ArrowReaderMetadata::load(&File::open(path)?, Default::default())?;
+        parquet_footer(metadata.metadata())
+    }
 }
@@ -56,7 +56,7 @@ impl StorageHandler for SyncStorageHandler {
     fn read_files(
         &self,
         files: Vec<FileSlice>,
-    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>> + Send>> {
         let iter = files.into_iter().map(|(url, _range_opt)| {
             if url.scheme() == "file" {
                 if let Ok(file_path) = url.to_file_path() {
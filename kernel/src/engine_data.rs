@@ -282,4 +282,24 @@ pub trait EngineData: AsAny {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Return the approximate number of bytes of memory held by this data, e.g. to bound how much
+    /// data is buffered ahead of a slow consumer. Implementations that do not track their memory
+    /// usage return 0.
+    fn approximate_memory_usage(&self) -> usize {
+        0
+    }
+
+    /// Return only the rows of this data whose entry in `selection_vector` is `true`, e.g. so
+    /// kernel can write the selected actions of a [`FilteredEngineData`] to a log file. The
+    /// selection vector has one entry per row. Implementations that cannot filter rows return an
+    /// error, which prevents kernel from writing such files with this engine.
+    fn apply_selection_vector(
+        self: Box<Self>,
+        _selection_vector: Vec<bool>,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        Err(Error::unsupported(
+            "This engine's data does not support applying a selection vector",
+        ))
+    }
 }
@@ -163,6 +163,11 @@ pub enum Error {
     #[error("Invalid decimal: {0}")]
     InvalidDecimal(String),
 
+    /// The table's metadata is internally inconsistent, e.g. a partition column is missing from the
+    /// schema
+    #[error("Invalid table metadata: {0}")]
+    InvalidTableMetadata(String),
+
     /// Inconsistent data passed to struct scalar
     #[error("Invalid struct data: {0}")]
     InvalidStructData(String),
@@ -214,6 +219,15 @@ pub enum Error {
     /// Schema mismatch has occurred or invalid schema used somewhere
     #[error("Schema error: {0}")]
     Schema(String),
+
+    /// A transient failure, e.g. an overloaded executor shedding work. The operation can be
+    /// retried later.
+    #[error("Retryable error: {0}")]
+    Retryable(String),
+
+    /// Data to write violates a check constraint or generated column of the table
+    #[error("Constraint violation: {0}")]
+    ConstraintViolation(String),
 }
 
 // Convenience constructors for Error types that take a String argument
@@ -260,6 +274,9 @@ impl Error {
     pub fn invalid_decimal(msg: impl ToString) -> Self {
         Self::InvalidDecimal(msg.to_string())
     }
+    pub fn invalid_table_metadata(msg: impl ToString) -> Self {
+        Self::InvalidTableMetadata(msg.to_string())
+    }
     pub fn invalid_struct_data(msg: impl ToString) -> Self {
         Self::InvalidStructData(msg.to_string())
     }
@@ -299,6 +316,23 @@ impl Error {
         Self::Schema(msg.to_string())
     }
 
+    pub fn retryable(msg: impl ToString) -> Self {
+        Self::Retryable(msg.to_string())
+    }
+
+    pub fn constraint_violation(msg: impl ToString) -> Self {
+        Self::ConstraintViolation(msg.to_string())
+    }
+
+    /// Whether the operation that failed with this error can be retried later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Backtraced { source, .. } => source.is_retryable(),
+            Self::Retryable(_) => true,
+            _ => false,
+        }
+    }
+
     // Capture a backtrace when the error is constructed.
     #[must_use]
     pub fn with_backtrace(self) -> Self {
@@ -5,6 +5,7 @@ use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 pub use self::column_names::{
     column_expr, column_name, column_pred, joined_column_expr, joined_column_name, ColumnName,
@@ -30,14 +31,15 @@ pub type PredicateRef = std::sync::Arc<Predicate>;
 ////////////////////////////////////////////////////////////////////////
 
 /// A unary predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum UnaryPredicateOp {
     /// Unary Is Null
     IsNull,
 }
 
 /// A binary predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum BinaryPredicateOp {
     /// Comparison Less Than
     LessThan,
@@ -49,10 +51,16 @@ pub enum BinaryPredicateOp {
     Distinct,
     /// IN
     In,
+    /// String prefix test, e.g. `STARTS_WITH(<string>, <prefix>)`
+    StartsWith,
+    /// SQL `LIKE` pattern match, e.g. `<string> LIKE <pattern>`. The pattern's `%` matches any
+    /// sequence of characters, `_` matches any single character, and `\` escapes the next
+    /// character.
+    Like,
 }
 
 /// A binary expression operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BinaryExpressionOp {
     /// Arithmetic Plus
     Plus,
@@ -65,7 +73,7 @@ pub enum BinaryExpressionOp {
 }
 
 /// A junction (AND/OR) predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum JunctionPredicateOp {
     /// Conjunction
     And,
@@ -175,7 +183,7 @@ pub type OpaquePredicateOpRef = Arc<dyn OpaquePredicateOp>;
 // Expressions and predicates
 ////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UnaryPredicate {
     /// The operator.
     pub op: UnaryPredicateOp,
@@ -183,7 +191,7 @@ pub struct UnaryPredicate {
     pub expr: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BinaryPredicate {
     /// The operator.
     pub op: BinaryPredicateOp,
@@ -193,7 +201,7 @@ pub struct BinaryPredicate {
     pub right: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BinaryExpression {
     /// The operator.
     pub op: BinaryExpressionOp,
@@ -203,7 +211,7 @@ pub struct BinaryExpression {
     pub right: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct JunctionPredicate {
     /// The operator.
     pub op: JunctionPredicateOp,
@@ -248,7 +256,7 @@ impl OpaqueExpression {
 /// These expressions do not track or validate data types, other than the type
 /// of literals. It is up to the expression evaluator to validate the
 /// expression against a schema and add appropriate casts as required.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     /// A literal value.
     Literal(Scalar),
@@ -261,7 +269,9 @@ pub enum Expression {
     /// An expression that takes two expressions as input.
     Binary(BinaryExpression),
     /// An expression that the engine defines and implements. Kernel interacts with the expression
-    /// only through methods provided by the [`OpaqueExpressionOp`] trait.
+    /// only through methods provided by the [`OpaqueExpressionOp`] trait. Opaque expressions
+    /// cannot be serialized.
+    #[serde(skip)]
     Opaque(OpaqueExpression),
     /// An unknown expression (i.e. one that neither kernel nor engine attempts to evaluate). For
     /// data skipping purposes, kernel treats unknown expressions as if they were literal NULL
@@ -279,7 +289,7 @@ pub enum Expression {
 /// These predicates do not track or validate data types, other than the type
 /// of literals. It is up to the predicate evaluator to validate the
 /// predicate against a schema and add appropriate casts as required.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Predicate {
     /// A boolean-valued expression, useful for e.g. `AND(<boolean_col1>, <boolean_col2>)`.
     BooleanExpression(Expression),
@@ -297,7 +307,9 @@ pub enum Predicate {
     /// A junction operation (AND/OR).
     Junction(JunctionPredicate),
     /// A predicate that the engine defines and implements. Kernel interacts with the predicate
-    /// only through methods provided by the [`OpaquePredicateOp`] trait.
+    /// only through methods provided by the [`OpaquePredicateOp`] trait. Opaque predicates cannot
+    /// be serialized.
+    #[serde(skip)]
     Opaque(OpaquePredicate),
     /// An unknown predicate (i.e. one that neither kernel nor engine attempts to evaluate). For
     /// data skipping purposes, kernel treats unknown predicates as if they were literal NULL values
@@ -310,6 +322,24 @@ pub enum Predicate {
     Unknown(String),
 }
 
+/// An aggregate over all rows of a batch, computed by an [`AggregateEvaluator`] (e.g. to collect
+/// statistics for data written by the engine).
+///
+/// [`AggregateEvaluator`]: crate::AggregateEvaluator
+#[derive(Debug, Clone, PartialEq)]
+pub enum Aggregate {
+    /// The minimum non-null value of a column, or NULL if all values are NULL. The result has the
+    /// same type as the column.
+    Min(ColumnName),
+    /// The maximum non-null value of a column, or NULL if all values are NULL. The result has the
+    /// same type as the column.
+    Max(ColumnName),
+    /// The number of NULL values in a column, as a LONG.
+    NullCount(ColumnName),
+    /// The number of rows, as a LONG.
+    RowCount,
+}
+
 ////////////////////////////////////////////////////////////////////////
 // Struct/Enum impls
 ////////////////////////////////////////////////////////////////////////
@@ -319,7 +349,7 @@ impl BinaryPredicateOp {
     pub(crate) fn is_null_intolerant(&self) -> bool {
         use BinaryPredicateOp::*;
         match self {
-            LessThan | GreaterThan | Equal => true,
+            LessThan | GreaterThan | Equal | StartsWith | Like => true,
             Distinct | In => false, // tolerates NULL input
         }
     }
@@ -456,6 +486,16 @@ impl Expression {
         Predicate::distinct(self, other)
     }
 
+    /// Create a new predicate `STARTS_WITH(self, prefix)`
+    pub fn starts_with(self, prefix: impl Into<Self>) -> Predicate {
+        Predicate::starts_with(self, prefix)
+    }
+
+    /// Create a new predicate `self LIKE pattern`
+    pub fn like(self, pattern: impl Into<Self>) -> Predicate {
+        Predicate::like(self, pattern)
+    }
+
     /// Creates a new binary expression lhs OP rhs
     pub fn binary(
         op: BinaryExpressionOp,
@@ -567,6 +607,17 @@ impl Predicate {
         Self::binary(BinaryPredicateOp::Distinct, a, b)
     }
 
+    /// Create a new predicate `STARTS_WITH(a, prefix)`
+    pub fn starts_with(a: impl Into<Expression>, prefix: impl Into<Expression>) -> Self {
+        Self::binary(BinaryPredicateOp::StartsWith, a, prefix)
+    }
+
+    /// Create a new predicate `a LIKE pattern`. See [`BinaryPredicateOp::Like`] for the pattern
+    /// syntax.
+    pub fn like(a: impl Into<Expression>, pattern: impl Into<Expression>) -> Self {
+        Self::binary(BinaryPredicateOp::Like, a, pattern)
+    }
+
     /// Create a new predicate `self AND other`
     pub fn and(a: impl Into<Self>, b: impl Into<Self>) -> Self {
         Self::and_from([a.into(), b.into()])
@@ -663,6 +714,8 @@ impl Display for BinaryPredicateOp {
             // in our code we take care of this, but theirs might not ...
             Distinct => write!(f, "DISTINCT"),
             In => write!(f, "IN"),
+            StartsWith => write!(f, "STARTS_WITH"),
+            Like => write!(f, "LIKE"),
         }
     }
 }
@@ -700,6 +753,11 @@ impl Display for Predicate {
                 left,
                 right,
             }) => write!(f, "DISTINCT({left}, {right})"),
+            Binary(BinaryPredicate {
+                op: BinaryPredicateOp::StartsWith,
+                left,
+                right,
+            }) => write!(f, "STARTS_WITH({left}, {right})"),
             Binary(BinaryPredicate { op, left, right }) => write!(f, "{left} {op} {right}"),
             Unary(UnaryPredicate { op, expr }) => match op {
                 UnaryPredicateOp::IsNull => write!(f, "{expr} IS NULL"),
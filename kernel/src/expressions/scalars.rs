@@ -3,18 +3,36 @@ use std::fmt::{Display, Formatter};
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::{ArrayType, DataType, DecimalType, MapType, PrimitiveType, StructField};
 use crate::utils::require;
 use crate::{DeltaResult, Error};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedDecimalData")]
 pub struct DecimalData {
     bits: i128,
     ty: DecimalType,
 }
 
+// The scalar data types deserialize through their constructors, so that invalid values (e.g. a
+// decimal that exceeds its precision) are rejected.
+#[derive(Deserialize)]
+struct UncheckedDecimalData {
+    bits: i128,
+    ty: DecimalType,
+}
+
+impl TryFrom<UncheckedDecimalData> for DecimalData {
+    type Error = Error;
+
+    fn try_from(unchecked: UncheckedDecimalData) -> DeltaResult<Self> {
+        Self::try_new(unchecked.bits, unchecked.ty)
+    }
+}
+
 impl DecimalData {
     pub fn try_new(bits: impl Into<i128>, ty: DecimalType) -> DeltaResult<Self> {
         let bits = bits.into();
@@ -44,6 +62,34 @@ impl DecimalData {
     pub fn scale(&self) -> u8 {
         self.ty.scale()
     }
+
+    /// Compares the numeric values of two decimals, aligning their scales first. Precision does
+    /// not affect the result, so e.g. `1.0 DECIMAL(2, 1)` and `1.00 DECIMAL(5, 2)` compare equal.
+    ///
+    /// Rescaling multiplies the lower-scale value by a power of ten. If that overflows, the
+    /// rescaled magnitude exceeds anything the other (unscaled) value can represent, so the sign
+    /// of the overflowing value alone decides the ordering.
+    fn cmp_value(&self, other: &Self) -> Ordering {
+        // Rescales `bits` up by `scale_diff` digits, returning an ordering in place of a value on
+        // overflow (the result is larger in magnitude than any i128).
+        fn rescale(bits: i128, scale_diff: u8) -> Result<i128, Ordering> {
+            10i128
+                .checked_pow(scale_diff as u32)
+                .and_then(|factor| bits.checked_mul(factor))
+                .ok_or(bits.cmp(&0))
+        }
+        match self.scale().cmp(&other.scale()) {
+            Ordering::Equal => self.bits.cmp(&other.bits),
+            Ordering::Less => match rescale(self.bits, other.scale() - self.scale()) {
+                Ok(bits) => bits.cmp(&other.bits),
+                Err(ord) => ord,
+            },
+            Ordering::Greater => match rescale(other.bits, self.scale() - other.scale()) {
+                Ok(bits) => self.bits.cmp(&bits),
+                Err(ord) => ord.reverse(),
+            },
+        }
+    }
 }
 
 /// Computes the decimal precision of a 128-bit number. The largest possible magnitude is i128::MIN
@@ -53,13 +99,28 @@ fn get_decimal_precision(value: i128) -> u8 {
     value.unsigned_abs().checked_ilog10().map_or(0, |p| p + 1) as _
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedArrayData")]
 pub struct ArrayData {
     tpe: ArrayType,
     /// This exists currently for literal list comparisons, but should not be depended on see below
     elements: Vec<Scalar>,
 }
 
+#[derive(Deserialize)]
+struct UncheckedArrayData {
+    tpe: ArrayType,
+    elements: Vec<Scalar>,
+}
+
+impl TryFrom<UncheckedArrayData> for ArrayData {
+    type Error = Error;
+
+    fn try_from(unchecked: UncheckedArrayData) -> DeltaResult<Self> {
+        Self::try_new(unchecked.tpe, unchecked.elements)
+    }
+}
+
 impl ArrayData {
     pub fn try_new(
         tpe: ArrayType,
@@ -101,12 +162,27 @@ impl ArrayData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedMapData")]
 pub struct MapData {
     data_type: MapType,
     pairs: Vec<(Scalar, Scalar)>,
 }
 
+#[derive(Deserialize)]
+struct UncheckedMapData {
+    data_type: MapType,
+    pairs: Vec<(Scalar, Scalar)>,
+}
+
+impl TryFrom<UncheckedMapData> for MapData {
+    type Error = Error;
+
+    fn try_from(unchecked: UncheckedMapData) -> DeltaResult<Self> {
+        Self::try_new(unchecked.data_type, unchecked.pairs)
+    }
+}
+
 impl MapData {
     pub fn try_new(
         data_type: MapType,
@@ -159,12 +235,27 @@ impl MapData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedStructData")]
 pub struct StructData {
     fields: Vec<StructField>,
     values: Vec<Scalar>,
 }
 
+#[derive(Deserialize)]
+struct UncheckedStructData {
+    fields: Vec<StructField>,
+    values: Vec<Scalar>,
+}
+
+impl TryFrom<UncheckedStructData> for StructData {
+    type Error = Error;
+
+    fn try_from(unchecked: UncheckedStructData) -> DeltaResult<Self> {
+        Self::try_new(unchecked.fields, unchecked.values)
+    }
+}
+
 impl StructData {
     /// Try to create a new struct data with the given fields and values.
     ///
@@ -217,7 +308,7 @@ impl StructData {
 
 /// A single value, which can be null. Used for representing literal values
 /// in [Expressions][crate::expressions::Expression].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Scalar {
     /// 32bit integer
     Integer(i32),
@@ -300,6 +391,63 @@ impl Scalar {
         Ok(Self::Timestamp(timestamp.timestamp_micros()))
     }
 
+    /// Serializes this scalar as a partition value string, as it would appear in the
+    /// `partitionValues` map of an `add` action. This is the inverse of
+    /// [`PrimitiveType::parse_scalar`]. Null values serialize to `None`.
+    ///
+    /// See <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#partition-value-serialization>
+    pub fn serialize_partition_value(&self) -> DeltaResult<Option<String>> {
+        use Scalar::*;
+        let value = match self {
+            Null(_) => return Ok(None),
+            String(s) => s.clone(),
+            // Binary partition values are stored as the string whose bytes are the binary value,
+            // matching how `parse_scalar` decodes them.
+            Binary(b) => std::string::String::from_utf8(b.clone()).map_err(|_| {
+                Error::generic("Binary partition value is not valid UTF-8 and cannot be serialized")
+            })?,
+            Boolean(b) => b.to_string(),
+            Byte(v) => v.to_string(),
+            Short(v) => v.to_string(),
+            Integer(v) => v.to_string(),
+            Long(v) => v.to_string(),
+            Float(v) => v.to_string(),
+            Double(v) => v.to_string(),
+            Date(days) => {
+                let date = DateTime::UNIX_EPOCH + chrono::Duration::days((*days).into());
+                date.format("%Y-%m-%d").to_string()
+            }
+            Timestamp(micros) | TimestampNtz(micros) => {
+                let timestamp = DateTime::from_timestamp_micros(*micros).ok_or_else(|| {
+                    Error::generic(format!("Timestamp partition value out of range: {micros}"))
+                })?;
+                timestamp.format("%Y-%m-%d %H:%M:%S%.6f").to_string()
+            }
+            Decimal(d) => {
+                let scale = d.scale() as u32;
+                let magnitude = d.bits().unsigned_abs();
+                let sign = if d.bits() < 0 { "-" } else { "" };
+                let divisor = 10_u128.pow(scale);
+                match scale {
+                    0 => format!("{sign}{magnitude}"),
+                    _ => format!(
+                        "{sign}{}.{:0>scale$}",
+                        magnitude / divisor,
+                        magnitude % divisor,
+                        scale = scale as usize
+                    ),
+                }
+            }
+            Struct(_) | Array(_) | Map(_) => {
+                return Err(Error::generic(format!(
+                    "Cannot serialize non-primitive partition value of type {:?}",
+                    self.data_type()
+                )))
+            }
+        };
+        Ok(Some(value))
+    }
+
     /// Attempts to add two scalars, returning None if they were incompatible.
     pub fn try_add(&self, other: &Scalar) -> Option<Scalar> {
         use Scalar::*;
@@ -461,9 +609,7 @@ impl PartialOrd for Scalar {
             (Date(_), _) => None,
             (Binary(a), Binary(b)) => a.partial_cmp(b),
             (Binary(_), _) => None,
-            (Decimal(d1), Decimal(d2)) => (d1.ty() == d2.ty())
-                .then(|| d1.bits().partial_cmp(&d2.bits()))
-                .flatten(),
+            (Decimal(d1), Decimal(d2)) => Some(d1.cmp_value(d2)),
             (Decimal(_), _) => None,
             (Null(_), _) => None, // NOTE: NULL values are incomparable by definition
             (Struct(_), _) => None, // TODO: Support Struct?
@@ -961,6 +1107,76 @@ mod tests {
         assert_timestamp_fails(&p_type, "1971-07-22");
     }
 
+    #[test]
+    fn test_serialize_partition_value_round_trip() {
+        let cases = [
+            Scalar::Boolean(true),
+            Scalar::Boolean(false),
+            Scalar::Binary(b"\x01\x02abc".to_vec()),
+            Scalar::String("a string".to_string()),
+            Scalar::Byte(-1),
+            Scalar::Short(12),
+            Scalar::Integer(123),
+            Scalar::Long(-1234),
+            Scalar::Float(1.5),
+            Scalar::Double(-10.25),
+            Scalar::Date(19723),
+            Scalar::Timestamp(1294751167123456),
+            Scalar::TimestampNtz(0),
+            Scalar::decimal(12345, 6, 2).unwrap(),
+            Scalar::decimal(-5, 6, 2).unwrap(),
+            Scalar::decimal(-123, 3, 0).unwrap(),
+        ];
+        for scalar in cases {
+            let raw = scalar.serialize_partition_value().unwrap().unwrap();
+            let DataType::Primitive(ptype) = scalar.data_type() else {
+                panic!("expected primitive type");
+            };
+            assert_eq!(ptype.parse_scalar(&raw).unwrap(), scalar, "raw: {raw}");
+        }
+
+        assert_eq!(
+            Scalar::Boolean(true).serialize_partition_value().unwrap(),
+            Some("true".to_string())
+        );
+        assert_eq!(
+            Scalar::Binary(b"abc".to_vec())
+                .serialize_partition_value()
+                .unwrap(),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            Scalar::Date(19723).serialize_partition_value().unwrap(),
+            Some("2024-01-01".to_string())
+        );
+        assert!(Scalar::Null(DataType::BOOLEAN)
+            .serialize_partition_value()
+            .unwrap()
+            .is_none());
+        assert!(Scalar::Binary(vec![0xff, 0xfe])
+            .serialize_partition_value()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_boolean_and_binary_partition_values() {
+        assert_eq!(
+            PrimitiveType::Boolean.parse_scalar("TRUE").unwrap(),
+            Scalar::Boolean(true)
+        );
+        assert_eq!(
+            PrimitiveType::Boolean.parse_scalar("False").unwrap(),
+            Scalar::Boolean(false)
+        );
+        assert!(PrimitiveType::Boolean.parse_scalar("yes").is_err());
+        assert!(PrimitiveType::Boolean.parse_scalar("").unwrap().is_null());
+        assert_eq!(
+            PrimitiveType::Binary.parse_scalar("\u{1}\u{2}").unwrap(),
+            Scalar::Binary(vec![1, 2])
+        );
+        assert!(PrimitiveType::Binary.parse_scalar("").unwrap().is_null());
+    }
+
     #[test]
     fn test_partial_cmp() {
         let a = Scalar::Integer(1);
@@ -978,6 +1194,55 @@ mod tests {
         assert_eq!(null.partial_cmp(&null), None);
     }
 
+    #[test]
+    fn test_decimal_partial_cmp() {
+        let dec = |bits: i128, precision, scale| Scalar::decimal(bits, precision, scale).unwrap();
+
+        // Same value, different precision and scale
+        assert_eq!(
+            dec(100, 5, 2).partial_cmp(&dec(1, 1, 0)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(dec(1000, 4, 3), dec(100, 5, 2));
+
+        // Different scales
+        assert_eq!(
+            dec(100, 5, 2).partial_cmp(&dec(1001, 4, 3)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            dec(1001, 4, 3).partial_cmp(&dec(100, 5, 2)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            dec(-99999, 5, 2).partial_cmp(&dec(-999, 4, 3)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            dec(11111, 5, 2).partial_cmp(&dec(11111, 12, 5)),
+            Some(Ordering::Greater)
+        );
+
+        // Rescaling the low-scale value overflows i128; its sign decides the ordering
+        let max = 10i128.pow(38) - 1;
+        let big = dec(max, 38, 0);
+        let small = dec(1, 38, 38);
+        assert_eq!(big.partial_cmp(&small), Some(Ordering::Greater));
+        assert_eq!(small.partial_cmp(&big), Some(Ordering::Less));
+        assert_eq!(dec(-max, 38, 0).partial_cmp(&small), Some(Ordering::Less));
+        assert_eq!(
+            small.partial_cmp(&dec(-max, 38, 0)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            dec(-max, 38, 0).partial_cmp(&dec(-max, 38, 38)),
+            Some(Ordering::Less)
+        );
+
+        // Decimals are still incomparable with other types
+        assert_eq!(dec(100, 5, 2).partial_cmp(&Scalar::Integer(1)), None);
+    }
+
     #[test]
     fn test_partial_eq() {
         let a = Scalar::Integer(1);
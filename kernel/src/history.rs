@@ -0,0 +1,149 @@
+//! Describes the history of a table: the commits that produced a snapshot, newest first, with
+//! the [`CommitInfo`] each commit recorded. This is the equivalent of Spark's `DESCRIBE HISTORY`.
+//! The entry point for this API is [`Snapshot::history`].
+//!
+//! [`Snapshot::history`]: crate::Snapshot::history
+use crate::actions::visitors::CommitInfoVisitor;
+use crate::actions::{get_log_commit_info_schema, CommitInfo};
+use crate::log_segment::list_log_files;
+use crate::path::ParsedLogPath;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Version};
+
+/// A commit in the history of a table, see [`Snapshot::history`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    version: Version,
+    timestamp: i64,
+    commit_info: Option<CommitInfo>,
+}
+
+impl HistoryEntry {
+    /// The version the commit produced.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The time of the commit, as milliseconds since the epoch: its in-commit timestamp if it has
+    /// one, else the modification time of its commit file.
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// The `commitInfo` action of the commit, or `None` if the writer didn't record one.
+    pub fn commit_info(&self) -> Option<&CommitInfo> {
+        self.commit_info.as_ref()
+    }
+
+    fn try_new(engine: &dyn Engine, commit: ParsedLogPath) -> DeltaResult<Self> {
+        let mut visitor = CommitInfoVisitor::default();
+        let batches = engine.json_handler().read_json_files(
+            std::slice::from_ref(&commit.location),
+            get_log_commit_info_schema().clone(),
+            None,
+        )?;
+        for batch in batches {
+            visitor.visit_commit_infos(engine, batch?.as_ref())?;
+            if !visitor.commit_infos.is_empty() {
+                break;
+            }
+        }
+        let commit_info = visitor.commit_infos.into_iter().next();
+        let timestamp = commit_info
+            .as_ref()
+            .and_then(CommitInfo::in_commit_timestamp)
+            .unwrap_or(commit.location.last_modified);
+        Ok(Self {
+            version: commit.version,
+            timestamp,
+            commit_info,
+        })
+    }
+}
+
+/// The commits of the table up to the version of `snapshot`, newest first. At most `limit`
+/// commits are returned, if given. Commits whose files were removed by log cleanup are missing.
+pub(crate) fn history<'a>(
+    snapshot: &Snapshot,
+    engine: &'a dyn Engine,
+    limit: Option<usize>,
+) -> DeltaResult<impl Iterator<Item = DeltaResult<HistoryEntry>> + 'a> {
+    let end_version = snapshot.version();
+    let start_version =
+        limit.map(|limit| end_version.saturating_sub(limit.saturating_sub(1) as Version));
+    let mut commits: Vec<_> = list_log_files(
+        engine.storage_handler().as_ref(),
+        &snapshot.log_segment().log_root,
+        start_version,
+        end_version,
+    )?
+    .filter_map(|path| match path {
+        Ok(path) if !path.is_commit() => None,
+        path => Some(path),
+    })
+    .collect::<DeltaResult<_>>()?;
+    // commits the catalog has not yet published are only known to the snapshot
+    snapshot
+        .log_segment()
+        .extend_with_unpublished_commits(&mut commits, start_version);
+    commits.reverse();
+    commits.truncate(limit.unwrap_or(usize::MAX));
+    Ok(commits
+        .into_iter()
+        .map(move |commit| HistoryEntry::try_new(engine, commit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::{DeltaResult, Snapshot};
+
+    #[test]
+    fn test_history() -> DeltaResult<()> {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"))?;
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::try_new(url, &engine, None)?;
+
+        let history: Vec<_> = snapshot
+            .history(&engine, None)?
+            .collect::<DeltaResult<_>>()?;
+        let versions: Vec<_> = history.iter().map(|entry| entry.version()).collect();
+        assert_eq!(versions, [1, 0]);
+
+        let commit_info = history[0].commit_info().unwrap();
+        assert_eq!(commit_info.timestamp(), Some(1674611429957));
+        assert_eq!(commit_info.operation(), Some("WRITE"));
+        assert_eq!(
+            commit_info.operation_parameters().unwrap()["mode"],
+            "Append"
+        );
+        let metrics = commit_info.operation_metrics().unwrap();
+        assert_eq!(metrics.num_output_rows(), Some(3));
+        assert!(commit_info
+            .engine_info()
+            .unwrap()
+            .starts_with("Apache-Spark/3.3"));
+        let commit_info = history[1].commit_info().unwrap();
+        assert_eq!(
+            commit_info.operation_parameters().unwrap()["mode"],
+            "ErrorIfExists"
+        );
+
+        let history: Vec<_> = snapshot.history(&engine, 1)?.collect::<DeltaResult<_>>()?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].version(), 1);
+        assert_eq!(snapshot.history(&engine, 0)?.count(), 0);
+
+        // The history of an older snapshot ends at its version
+        let snapshot = Snapshot::try_new(snapshot.table_root().clone(), &engine, Some(0))?;
+        let history: Vec<_> = snapshot
+            .history(&engine, None)?
+            .collect::<DeltaResult<_>>()?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].version(), 0);
+        Ok(())
+    }
+}
@@ -1 +1,274 @@
+//! The history manager translates between the versions and timestamps of a table's commits, e.g.
+//! to time travel to a table as of a timestamp with [`Snapshot::try_new_at_timestamp`].
+//!
+//! The timestamp of a commit is its in-commit timestamp if in-commit timestamps were enabled when
+//! it was written, else the modification time of its commit file.
+//!
+//! [`Snapshot::try_new_at_timestamp`]: crate::Snapshot::try_new_at_timestamp
 pub(crate) mod search;
+
+use search::{binary_search_by_key_with_bounds, Bound, SearchError};
+
+use crate::actions::visitors::InCommitTimestampVisitor;
+use crate::engine_data::RowVisitor as _;
+use crate::log_segment::list_log_files;
+use crate::path::ParsedLogPath;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error, FileMeta, Version};
+
+/// The latest version of the table, up to the version of `snapshot`, whose commit timestamp is at
+/// or before `timestamp` (in milliseconds since the Unix epoch). Fails if `timestamp` is before
+/// the earliest commit still in the log.
+///
+/// Commit file modification times are adjusted to strictly increase with the version, since they
+/// may not if the files were copied. In-commit timestamps are monotonic by definition.
+pub(crate) fn latest_version_as_of(
+    snapshot: &Snapshot,
+    engine: &dyn Engine,
+    timestamp: i64,
+) -> DeltaResult<Version> {
+    let mut commits: Vec<_> = list_log_files(
+        engine.storage_handler().as_ref(),
+        &snapshot.log_segment().log_root,
+        None,
+        snapshot.version(),
+    )?
+    .filter_map(|path| match path {
+        Ok(path) if !path.is_commit() => None,
+        path => Some(path),
+    })
+    .collect::<DeltaResult<_>>()?;
+    // commits the catalog has not yet published are only known to the snapshot
+    snapshot
+        .log_segment()
+        .extend_with_unpublished_commits(&mut commits, None);
+
+    let table_configuration = snapshot.table_configuration();
+    let table_properties = table_configuration.table_properties();
+    let ict_enablement = match (
+        table_properties.in_commit_timestamp_enablement_version,
+        table_properties.in_commit_timestamp_enablement_timestamp,
+    ) {
+        _ if !table_configuration.is_in_commit_timestamps_enabled() => None,
+        // In-commit timestamps were enabled when the table was created
+        (None, None) => Some((0, i64::MIN)),
+        _ => table_configuration.in_commit_timestamp_enablement()?,
+    };
+    let ict_start = match ict_enablement {
+        Some((version, _)) => commits.partition_point(|commit| commit.version < version),
+        None => commits.len(),
+    };
+    let (file_commits, ict_commits) = commits.split_at(ict_start);
+
+    let search_result = match ict_enablement {
+        Some((_, enablement_timestamp)) if timestamp >= enablement_timestamp => {
+            binary_search_by_key_with_bounds(
+                ict_commits,
+                timestamp,
+                |commit| {
+                    read_in_commit_timestamp(engine, &commit.location)?.ok_or_else(|| {
+                        Error::generic(format!(
+                            "In-commit timestamp missing from commit {}",
+                            commit.version
+                        ))
+                    })
+                },
+                Bound::GreatestLower,
+            )
+            .map(|index| ict_commits[index].version)
+        }
+        _ => {
+            let file_timestamps = monotonic_file_timestamps(file_commits);
+            binary_search_by_key_with_bounds(
+                &file_timestamps,
+                timestamp,
+                |(_, file_timestamp)| Ok::<_, Error>(*file_timestamp),
+                Bound::GreatestLower,
+            )
+            .map(|index| file_timestamps[index].0)
+        }
+    };
+    search_result.map_err(|err| match err {
+        SearchError::OutOfRange => Error::generic(format!(
+            "Timestamp {timestamp} is before the earliest available version of the table"
+        )),
+        SearchError::KeyFunctionError(err) => err,
+    })
+}
+
+// The (version, timestamp) of each commit, where the timestamp is the modification time of the
+// commit file, bumped to one millisecond after the previous commit's if it isn't later
+fn monotonic_file_timestamps(commits: &[ParsedLogPath]) -> Vec<(Version, i64)> {
+    let mut previous = i64::MIN;
+    commits
+        .iter()
+        .map(|commit| {
+            previous = commit
+                .location
+                .last_modified
+                .max(previous.saturating_add(1));
+            (commit.version, previous)
+        })
+        .collect()
+}
+
+/// The in-commit timestamp of the commit file `commit_file`, if it has one. The in-commit timestamp
+/// must be in the first action of the commit, so only the first batch of the file is read.
+pub(crate) fn read_in_commit_timestamp(
+    engine: &dyn Engine,
+    commit_file: &FileMeta,
+) -> DeltaResult<Option<i64>> {
+    let mut batches = engine.json_handler().read_json_files(
+        std::slice::from_ref(commit_file),
+        InCommitTimestampVisitor::schema(),
+        None,
+    )?;
+    let mut visitor = InCommitTimestampVisitor::default();
+    if let Some(batch) = batches.next() {
+        visitor.visit_rows_of(batch?.as_ref())?;
+    }
+    Ok(visitor.in_commit_timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use url::Url;
+
+    use super::*;
+    use crate::actions::{CommitInfo, Metadata, Protocol};
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::{Action, LocalMockTable};
+
+    fn protocol_and_metadata(configuration: &[(&str, &str)]) -> [Action; 2] {
+        let schema = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#;
+        let protocol =
+            Protocol::try_new(1, 7, None::<Vec<String>>, Some(["inCommitTimestamp"])).unwrap();
+        let configuration = configuration
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        [
+            Action::Protocol(protocol),
+            Action::Metadata(Metadata {
+                schema_string: schema.to_string(),
+                configuration,
+                ..Default::default()
+            }),
+        ]
+    }
+
+    fn commit_info(in_commit_timestamp: Option<i64>) -> Action {
+        Action::CommitInfo(CommitInfo {
+            in_commit_timestamp,
+            operation: Some("WRITE".to_string()),
+            ..Default::default()
+        })
+    }
+
+    // Set the modification time of the commit at `version` to `file_timestamp`
+    fn set_file_timestamp(table: &LocalMockTable, version: Version, file_timestamp: u64) {
+        let path = table
+            .table_root()
+            .join(format!("_delta_log/{version:020}.json"));
+        let modified = UNIX_EPOCH + Duration::from_millis(file_timestamp);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn versions_as_of(table: &LocalMockTable, timestamps: &[i64]) -> Vec<DeltaResult<Version>> {
+        let engine = SyncEngine::new();
+        let url = Url::from_directory_path(table.table_root()).unwrap();
+        let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
+        timestamps
+            .iter()
+            .map(|timestamp| latest_version_as_of(&snapshot, &engine, *timestamp))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_version_as_of_file_timestamps() {
+        let mut table = LocalMockTable::new();
+        let [protocol, metadata] = protocol_and_metadata(&[]);
+        table.commit([commit_info(None), protocol, metadata]).await;
+        for _ in 1..=3 {
+            table.commit([commit_info(None)]).await;
+        }
+        set_file_timestamp(&table, 0, 1000);
+        set_file_timestamp(&table, 1, 2000);
+        // Not later than version 1, so treated as 2001
+        set_file_timestamp(&table, 2, 1500);
+        set_file_timestamp(&table, 3, 4000);
+
+        let versions = versions_as_of(&table, &[999, 1000, 1999, 2000, 2001, 3999, 5000]);
+        let (errors, versions): (Vec<_>, Vec<_>) = versions.into_iter().partition(Result::is_err);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("before the earliest available version"));
+        let versions: Vec<_> = versions.into_iter().map(Result::unwrap).collect();
+        assert_eq!(versions, [0, 0, 1, 2, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_version_as_of_in_commit_timestamps() {
+        let mut table = LocalMockTable::new();
+        let [protocol, metadata] = protocol_and_metadata(&[]);
+        table.commit([commit_info(None), protocol, metadata]).await;
+        table.commit([commit_info(None)]).await;
+        // In-commit timestamps are enabled at version 2. The file timestamps are ignored from then.
+        let [protocol, metadata] = protocol_and_metadata(&[
+            ("delta.enableInCommitTimestamps", "true"),
+            ("delta.inCommitTimestampEnablementVersion", "2"),
+            ("delta.inCommitTimestampEnablementTimestamp", "10000"),
+        ]);
+        table
+            .commit([commit_info(Some(10000)), protocol, metadata])
+            .await;
+        table.commit([commit_info(Some(20000))]).await;
+        set_file_timestamp(&table, 0, 1000);
+        set_file_timestamp(&table, 1, 2000);
+        set_file_timestamp(&table, 2, 500);
+        set_file_timestamp(&table, 3, 500);
+
+        let versions: Vec<_> = versions_as_of(&table, &[1500, 9999, 10000, 19999, 20000])
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(versions, [0, 1, 2, 2, 3]);
+
+        let engine = SyncEngine::new();
+        let url = Url::from_directory_path(table.table_root()).unwrap();
+        let snapshot = Snapshot::try_new_at_timestamp(url, &engine, 15000).unwrap();
+        assert_eq!(snapshot.version(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_version_as_of_in_commit_timestamps_since_creation() {
+        let mut table = LocalMockTable::new();
+        let [protocol, metadata] =
+            protocol_and_metadata(&[("delta.enableInCommitTimestamps", "true")]);
+        table
+            .commit([commit_info(Some(100)), protocol, metadata])
+            .await;
+        table.commit([commit_info(Some(200))]).await;
+        set_file_timestamp(&table, 0, 5000);
+        set_file_timestamp(&table, 1, 5000);
+
+        let versions: Vec<_> = versions_as_of(&table, &[150, 250])
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(versions, [0, 1]);
+        assert!(versions_as_of(&table, &[99])[0].is_err());
+    }
+}
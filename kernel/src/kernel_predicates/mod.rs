@@ -10,11 +10,13 @@ use crate::expressions::{
     UnaryPredicate, UnaryPredicateOp,
 };
 use crate::schema::DataType;
+use string_patterns::{like_matches, prefix_upper_bound, LikePrefix};
 
 use std::cmp::Ordering;
 use tracing::{debug, warn};
 
 pub(crate) mod parquet_stats_skipping;
+mod string_patterns;
 
 #[cfg(test)]
 mod tests;
@@ -244,6 +246,30 @@ pub trait KernelPredicateEvaluator {
         None // TODO?
     }
 
+    /// A (possibly inverted) string prefix test, e.g. `[NOT] STARTS_WITH(<col>, 'abc')`.
+    ///
+    /// Unsupported by default, but implementations can override it if they wish.
+    fn eval_pred_starts_with(
+        &self,
+        _col: &ColumnName,
+        _val: &Scalar,
+        _inverted: bool,
+    ) -> Option<Self::Output> {
+        None
+    }
+
+    /// A (possibly inverted) SQL pattern match, e.g. `<col> [NOT] LIKE 'abc%'`.
+    ///
+    /// Unsupported by default, but implementations can override it if they wish.
+    fn eval_pred_like(
+        &self,
+        _col: &ColumnName,
+        _val: &Scalar,
+        _inverted: bool,
+    ) -> Option<Self::Output> {
+        None
+    }
+
     /// Dispatches a (possibly inverted) binary expression to each operator's specific implementation.
     ///
     /// NOTE: Only binary operators that produce boolean outputs are supported.
@@ -266,6 +292,8 @@ pub trait KernelPredicateEvaluator {
                 Equal => self.eval_pred_eq(col, val, inverted),
                 Distinct => self.eval_pred_distinct(col, val, inverted),
                 In => self.eval_pred_in(col, val, inverted),
+                StartsWith => self.eval_pred_starts_with(col, val, inverted),
+                Like => self.eval_pred_like(col, val, inverted),
             },
             (Literal(val), Column(col)) => match op {
                 // NOTE: The column has to be on the left, so e.g. `10 < x` becomes `x > 10`
@@ -273,7 +301,7 @@ pub trait KernelPredicateEvaluator {
                 GreaterThan => self.eval_pred_lt(col, val, inverted),
                 Equal => self.eval_pred_eq(col, val, inverted),
                 Distinct => self.eval_pred_distinct(col, val, inverted),
-                In => None, // arg order is semantically important
+                In | StartsWith | Like => None, // arg order is semantically important
             },
             _ => {
                 debug!("Unsupported binary operand(s): {left:?} {op:?} {right:?}");
@@ -527,6 +555,19 @@ impl KernelPredicateEvaluatorDefaults {
             Equal => Self::partial_cmp_scalars(Ordering::Equal, left, right, inverted),
             LessThan => Self::partial_cmp_scalars(Ordering::Less, left, right, inverted),
             GreaterThan => Self::partial_cmp_scalars(Ordering::Greater, left, right, inverted),
+            StartsWith | Like => match (left, right) {
+                (Scalar::String(left), Scalar::String(right)) => {
+                    let matched = match op {
+                        StartsWith => left.starts_with(right.as_str()),
+                        _ => like_matches(left, right),
+                    };
+                    Some(matched != inverted)
+                }
+                _ => {
+                    debug!("Unsupported binary operands: {left:?} {op:?} {right:?}");
+                    None
+                }
+            },
             Distinct | In => {
                 debug!("Unsupported binary operator: {left:?} {op:?} {right:?}");
                 None
@@ -669,6 +710,21 @@ impl<R: ResolveColumnAsScalar> KernelPredicateEvaluator for DefaultKernelPredica
         self.eval_pred_binary_scalars(BinaryPredicateOp::Equal, &col, val, inverted)
     }
 
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<bool> {
+        let col = self.resolve_column(col)?;
+        self.eval_pred_binary_scalars(BinaryPredicateOp::StartsWith, &col, val, inverted)
+    }
+
+    fn eval_pred_like(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<bool> {
+        let col = self.resolve_column(col)?;
+        self.eval_pred_binary_scalars(BinaryPredicateOp::Like, &col, val, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
@@ -899,6 +955,69 @@ pub trait DataSkippingPredicateEvaluator {
         };
         self.finish_eval_pred_junction(op, &mut preds.into_iter(), false)
     }
+
+    /// See [`KernelPredicateEvaluator::eval_pred_starts_with`]
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        let Scalar::String(prefix) = val else {
+            return None;
+        };
+        // The strings that start with `prefix` are exactly those in [prefix, upper), if `prefix`
+        // has an upper bound. Otherwise they are exactly the strings >= prefix.
+        let upper = prefix_upper_bound(prefix).map(Scalar::from);
+        let (op, preds) = if inverted {
+            // Given `NOT STARTS_WITH(col, prefix)`:
+            // Skip if every value in [min, max] starts with `prefix`, implies
+            // Skip if `prefix <= min AND max < upper` implies
+            // Keep if `min < prefix OR NOT(max < upper)`
+            let preds = [
+                Some(self.partial_cmp_min_stat(col, val, Ordering::Less, false)),
+                upper.map(|upper| self.partial_cmp_max_stat(col, &upper, Ordering::Less, true)),
+            ];
+            (JunctionPredicateOp::Or, preds)
+        } else {
+            // Given `STARTS_WITH(col, prefix)`:
+            // Skip if no value in [min, max] is in [prefix, upper), implies
+            // Skip if `max < prefix OR upper <= min` implies
+            // Keep if `NOT(max < prefix) AND min < upper`
+            let preds = [
+                Some(self.partial_cmp_max_stat(col, val, Ordering::Less, true)),
+                upper.map(|upper| self.partial_cmp_min_stat(col, &upper, Ordering::Less, false)),
+            ];
+            (JunctionPredicateOp::And, preds)
+        };
+        let mut preds = preds.into_iter().flatten();
+        self.finish_eval_pred_junction(op, &mut preds, false)
+    }
+
+    /// See [`KernelPredicateEvaluator::eval_pred_like`]. Only the literal prefix of the pattern
+    /// (before its first wildcard) is used for skipping.
+    fn eval_pred_like(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        let Scalar::String(pattern) = val else {
+            return None;
+        };
+        match LikePrefix::parse(pattern) {
+            LikePrefix::Exact(value) => self.eval_pred_eq(col, &value.into(), inverted),
+            LikePrefix::StartsWith(prefix) => {
+                self.eval_pred_starts_with(col, &prefix.into(), inverted)
+            }
+            // Every match starts with the prefix, but not every string that starts with the prefix
+            // matches, so the prefix can only rule out matches.
+            LikePrefix::Partial(prefix) if !inverted => {
+                self.eval_pred_starts_with(col, &prefix.into(), false)
+            }
+            LikePrefix::Partial(_) => None,
+        }
+    }
 }
 
 impl<T: DataSkippingPredicateEvaluator + ?Sized> KernelPredicateEvaluator for T {
@@ -928,6 +1047,24 @@ impl<T: DataSkippingPredicateEvaluator + ?Sized> KernelPredicateEvaluator for T
         self.eval_pred_eq(col, val, inverted)
     }
 
+    fn eval_pred_starts_with(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        self.eval_pred_starts_with(col, val, inverted)
+    }
+
+    fn eval_pred_like(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        self.eval_pred_like(col, val, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
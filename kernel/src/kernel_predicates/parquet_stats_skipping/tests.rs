@@ -196,6 +196,29 @@ fn test_eval_binary_comparisons() {
     do_test(FIVE, FIFTEEN, &[TRUE, TRUE, TRUE, TRUE, TRUE, TRUE]);
 }
 
+#[test]
+fn test_eval_string_prefix_predicates() {
+    let predicates = [
+        Pred::starts_with(column_expr!("x"), Expr::literal("ab")),
+        Pred::not(Pred::starts_with(column_expr!("x"), Expr::literal("ab"))),
+        Pred::like(column_expr!("x"), Expr::literal("ab%")),
+        Pred::like(column_expr!("x"), Expr::literal("%b")),
+    ];
+
+    let do_test = |min: &str, max: &str, expected: &[Option<bool>]| {
+        let filter = MinMaxTestFilter::new(Some(min.into()), Some(max.into()));
+        for (pred, expect) in predicates.iter().zip(expected.iter()) {
+            expect_eq!(filter.eval(pred), *expect, "{pred:#?} with [{min}..{max}]");
+        }
+    };
+
+    // A pattern that starts with a wildcard has no prefix to skip with
+    do_test("aa", "aaz", &[FALSE, TRUE, FALSE, TRUE]);
+    do_test("ab", "abz", &[TRUE, FALSE, TRUE, TRUE]);
+    do_test("abc", "b", &[TRUE, TRUE, TRUE, TRUE]);
+    do_test("ac", "b", &[FALSE, TRUE, FALSE, TRUE]);
+}
+
 struct NullCountTestFilter {
     nullcount: Option<i64>,
     rowcount: i64,
@@ -0,0 +1,184 @@
+//! Helpers for evaluating the string predicates [`BinaryPredicateOp::StartsWith`] and
+//! [`BinaryPredicateOp::Like`] over scalars, and for data skipping over them with min/max stats.
+//!
+//! [`BinaryPredicateOp::StartsWith`]: crate::expressions::BinaryPredicateOp::StartsWith
+//! [`BinaryPredicateOp::Like`]: crate::expressions::BinaryPredicateOp::Like
+
+/// An element of a parsed `LIKE` pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatternToken {
+    /// A character that must match exactly (possibly an escaped wildcard)
+    Char(char),
+    /// `_`: matches any single character
+    AnyChar,
+    /// `%`: matches any sequence of characters, including the empty sequence
+    AnyString,
+}
+
+// Splits a `LIKE` pattern into tokens. A backslash escapes the next character, and a trailing
+// backslash matches itself (as in the arrow `like` kernel).
+fn tokenize(pattern: &str) -> Vec<PatternToken> {
+    let mut chars = pattern.chars();
+    let mut tokens = vec![];
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '\\' => PatternToken::Char(chars.next().unwrap_or('\\')),
+            '_' => PatternToken::AnyChar,
+            '%' => PatternToken::AnyString,
+            c => PatternToken::Char(c),
+        });
+    }
+    tokens
+}
+
+/// Returns true if `value` matches the SQL `LIKE` `pattern`.
+pub(crate) fn like_matches(value: &str, pattern: &str) -> bool {
+    let tokens = tokenize(pattern);
+    let value: Vec<char> = value.chars().collect();
+    let (mut t, mut v) = (0, 0);
+    // The position after the most recent `%`, and the value position it currently matches up to
+    let mut backtrack = None;
+    while v < value.len() {
+        match tokens.get(t) {
+            Some(PatternToken::AnyString) => {
+                t += 1;
+                backtrack = Some((t, v));
+            }
+            Some(PatternToken::AnyChar) => (t, v) = (t + 1, v + 1),
+            Some(PatternToken::Char(c)) if *c == value[v] => (t, v) = (t + 1, v + 1),
+            // Mismatch: let the most recent `%` absorb one more character, if there is one
+            _ => match backtrack {
+                Some((after_any, matched)) => {
+                    (t, v) = (after_any, matched + 1);
+                    backtrack = Some((after_any, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[t..].iter().all(|t| *t == PatternToken::AnyString)
+}
+
+/// The strings a `LIKE` pattern can match, in terms of a literal prefix.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum LikePrefix {
+    /// The pattern has no wildcards, and matches exactly this string
+    Exact(String),
+    /// The pattern matches exactly the strings that start with this prefix, e.g. `abc%`
+    StartsWith(String),
+    /// The pattern only matches strings that start with this prefix, but not all of them, e.g.
+    /// `abc_d%`
+    Partial(String),
+}
+
+impl LikePrefix {
+    /// Analyzes the literal prefix of a `LIKE` pattern (the part before its first wildcard).
+    pub(crate) fn parse(pattern: &str) -> Self {
+        let tokens = tokenize(pattern);
+        let prefix_len = tokens
+            .iter()
+            .take_while(|token| matches!(token, PatternToken::Char(_)))
+            .count();
+        let prefix = tokens[..prefix_len]
+            .iter()
+            .map(|token| match token {
+                PatternToken::Char(c) => *c,
+                _ => unreachable!("the prefix only contains literal characters"),
+            })
+            .collect();
+        let rest = &tokens[prefix_len..];
+        if rest.is_empty() {
+            Self::Exact(prefix)
+        } else if rest.iter().all(|token| *token == PatternToken::AnyString) {
+            Self::StartsWith(prefix)
+        } else {
+            Self::Partial(prefix)
+        }
+    }
+}
+
+/// Returns the smallest string that is greater than every string starting with `prefix`, i.e.
+/// the strings starting with `prefix` are exactly the strings in `[prefix, upper_bound)`. This
+/// increments the last character of `prefix` that can be incremented, dropping the characters
+/// after it. Returns `None` if there is no such string, e.g. for an empty prefix.
+pub(crate) fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        // Skips over the surrogate range, which is not valid in strings
+        let next = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32);
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like_matches() {
+        let cases = [
+            ("abc", "abc", true),
+            ("abc", "ab", false),
+            ("abc", "abcd", false),
+            ("abc", "a%", true),
+            ("abc", "%c", true),
+            ("abc", "%b%", true),
+            ("abc", "%d%", false),
+            ("abc", "a_c", true),
+            ("abc", "a_", false),
+            ("abc", "___", true),
+            ("abc", "%", true),
+            ("", "%", true),
+            ("", "_", false),
+            ("aXbXc", "a%b%c", true),
+            ("abcbc", "a%bc", true),
+            ("abcbd", "a%bc", false),
+            ("a%c", r"a\%c", true),
+            ("abc", r"a\%c", false),
+            ("a_c", r"a\_%", true),
+            (r"a\", r"a\", true),
+            (r"a\b", r"a\\b", true),
+            ("ünï", "_n%", true),
+        ];
+        for (value, pattern, expected) in cases {
+            assert_eq!(
+                like_matches(value, pattern),
+                expected,
+                "{value} LIKE {pattern}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_like_prefix() {
+        let cases = [
+            ("abc", LikePrefix::Exact("abc".into())),
+            ("abc%", LikePrefix::StartsWith("abc".into())),
+            ("abc%%", LikePrefix::StartsWith("abc".into())),
+            ("%", LikePrefix::StartsWith("".into())),
+            (r"a\%c%", LikePrefix::StartsWith("a%c".into())),
+            ("abc_", LikePrefix::Partial("abc".into())),
+            ("ab%c", LikePrefix::Partial("ab".into())),
+            ("%abc", LikePrefix::Partial("".into())),
+        ];
+        for (pattern, expected) in cases {
+            assert_eq!(LikePrefix::parse(pattern), expected, "{pattern}");
+        }
+    }
+
+    #[test]
+    fn test_prefix_upper_bound() {
+        assert_eq!(prefix_upper_bound("abc").as_deref(), Some("abd"));
+        assert_eq!(prefix_upper_bound("ab\u{10FFFF}").as_deref(), Some("ac"));
+        assert_eq!(
+            prefix_upper_bound("a\u{D7FF}").as_deref(),
+            Some("a\u{E000}")
+        );
+        assert_eq!(prefix_upper_bound("\u{10FFFF}"), None);
+        assert_eq!(prefix_upper_bound(""), None);
+    }
+}
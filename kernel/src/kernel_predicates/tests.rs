@@ -559,6 +559,58 @@ fn test_eval_distinct() {
     );
 }
 
+#[test]
+fn test_eval_string_predicates() {
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from("abc"));
+    let col = &column_name!("x");
+    let cases = [
+        ("ab", Some(true), Some(false)),
+        ("abcd", Some(false), Some(false)),
+        ("b", Some(false), Some(false)),
+        ("", Some(true), Some(false)),
+        ("a_c", Some(false), Some(true)),
+        ("%c", Some(false), Some(true)),
+        ("abc", Some(true), Some(true)),
+    ];
+    for (pattern, starts_with, like) in cases {
+        let val = &Scalar::from(pattern);
+        expect_eq!(
+            filter.eval_pred_starts_with(col, val, false),
+            starts_with,
+            "STARTS_WITH(x, {pattern}) (x = abc)"
+        );
+        expect_eq!(
+            filter.eval_pred_starts_with(col, val, true),
+            starts_with.map(|v| !v),
+            "NOT STARTS_WITH(x, {pattern}) (x = abc)"
+        );
+        expect_eq!(
+            filter.eval_pred_like(col, val, false),
+            like,
+            "x LIKE {pattern} (x = abc)"
+        );
+        expect_eq!(
+            filter.eval_pred_like(col, val, true),
+            like.map(|v| !v),
+            "x NOT LIKE {pattern} (x = abc)"
+        );
+    }
+
+    // Non-string and NULL inputs produce NULL
+    let null = &Scalar::Null(DataType::STRING);
+    expect_eq!(
+        filter.eval_pred_like(col, null, false),
+        None,
+        "x LIKE NULL (x = abc)"
+    );
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from(1));
+    expect_eq!(
+        filter.eval_pred_starts_with(col, &Scalar::from("a"), false),
+        None,
+        "STARTS_WITH(x, a) (x = 1)"
+    );
+}
+
 // NOTE: We're testing routing here -- the actual comparisons are already validated by
 // test_eval_binary_scalars.
 #[test]
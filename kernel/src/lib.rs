@@ -71,13 +71,19 @@ use std::{cmp::Ordering, ops::Range};
 use bytes::Bytes;
 use url::Url;
 
+use self::engine_data::FilteredEngineData;
 use self::schema::{DataType, SchemaRef};
 
 pub mod actions;
+pub mod catalog;
 pub mod checkpoint;
+pub mod clock;
+pub mod compat;
 pub mod engine_data;
 pub mod error;
 pub mod expressions;
+pub mod history;
+pub mod log_compaction;
 pub mod scan;
 pub mod schema;
 pub mod snapshot;
@@ -86,16 +92,18 @@ pub mod table_configuration;
 pub mod table_features;
 pub mod table_properties;
 pub mod transaction;
+pub mod vacuum;
 
 mod arrow_compat;
 #[cfg(any(feature = "arrow-54", feature = "arrow-55"))]
 pub use arrow_compat::*;
 
 pub mod kernel_predicates;
+pub(crate) mod row_tracking;
 pub(crate) mod utils;
 
 #[cfg(feature = "internal-api")]
-pub use utils::try_parse_uri;
+pub use utils::{normalize_table_root, try_parse_uri};
 
 // for the below modules, we cannot introduce a macro to clean this up. rustfmt doesn't follow into
 // macros, and so will not format the files associated with these modules if we get too clever. see:
@@ -125,10 +133,11 @@ pub use delta_kernel_derive;
 pub use engine_data::{EngineData, RowVisitor};
 pub use error::{DeltaResult, Error};
 pub use expressions::{Expression, ExpressionRef, Predicate, PredicateRef};
+pub use log_segment::{CommitCover, CommitCoverCost};
 pub use snapshot::Snapshot;
 
 use expressions::literal_expression_transform::LiteralExpressionTransform;
-use expressions::Scalar;
+use expressions::{Aggregate, Scalar};
 use schema::{SchemaTransform, StructField, StructType};
 
 #[cfg(any(feature = "default-engine", feature = "arrow-conversion"))]
@@ -149,6 +158,12 @@ pub type FileDataReadResult = (FileMeta, Box<dyn EngineData>);
 pub type FileDataReadResultIterator =
     Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send>;
 
+/// A stream of data read from specified files, the async counterpart of
+/// [`FileDataReadResultIterator`]
+#[cfg(feature = "async")]
+pub type FileDataReadResultStream =
+    futures::stream::BoxStream<'static, DeltaResult<Box<dyn EngineData>>>;
+
 /// The metadata that describes an object.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FileMeta {
@@ -368,6 +383,18 @@ pub trait PredicateEvaluator: AsAny {
     fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>>;
 }
 
+/// Trait for implementing an aggregate evaluator.
+///
+/// It contains a list of [`Aggregate`]s which can be evaluated on multiple ColumnarBatches.
+/// Connectors can implement this trait to optimize the evaluation using the
+/// connector specific capabilities.
+pub trait AggregateEvaluator: AsAny {
+    /// Evaluate the aggregates on a given EngineData.
+    ///
+    /// Produces a single row, with one column per aggregate.
+    fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>>;
+}
+
 /// Provides expression evaluation capability to Delta Kernel.
 ///
 /// Delta Kernel can use this handler to evaluate predicate on partition filters,
@@ -419,6 +446,33 @@ pub trait EvaluationHandler: AsAny {
     // NOTE: we should probably allow DataType instead of SchemaRef, but can expand that in the
     // future.
     fn null_row(&self, output_schema: SchemaRef) -> DeltaResult<Box<dyn EngineData>>;
+
+    /// Create an [`AggregateEvaluator`] that can compute the given [`Aggregate`]s over all rows
+    /// of columnar batches with the given [`Schema`].
+    ///
+    /// The output is a single row with one column for each aggregate, whose names are given by
+    /// `output_schema`. All output columns are nullable, since e.g. the minimum of an all-null
+    /// column is NULL.
+    ///
+    /// # Parameters
+    ///
+    /// - `input_schema`: Schema of the input data.
+    /// - `aggregates`: Aggregates to compute.
+    /// - `output_schema`: Schema of the result, with one field per aggregate.
+    ///
+    /// The default implementation returns [`Error::Unsupported`].
+    ///
+    /// [`Schema`]: crate::schema::StructType
+    fn new_aggregate_evaluator(
+        &self,
+        _input_schema: SchemaRef,
+        _aggregates: Vec<Aggregate>,
+        _output_schema: SchemaRef,
+    ) -> DeltaResult<Arc<dyn AggregateEvaluator>> {
+        Err(Error::unsupported(
+            "This engine does not support aggregate evaluation",
+        ))
+    }
 }
 
 /// Internal trait to allow us to have a private `create_one` API that's implemented for all
@@ -495,14 +549,23 @@ pub trait StorageHandler: AsAny {
     ///
     /// If the path is directory-like (ends with '/'), the result should contain
     /// all the files in the directory.
+    ///
+    /// The returned [`FileMeta::size`] must be the actual size of each file: empty checkpoint
+    /// files are treated as corrupt.
     fn list_from(&self, path: &Url)
         -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>>;
 
     /// Read data specified by the start and end offset from the file.
+    ///
+    /// Like the [`FileDataReadResultIterator`]s returned by the other handlers, the returned
+    /// iterator must be `Send`: [`Scan::execute`] holds it while reading deletion vectors, and its
+    /// iterator may be handed to another thread.
+    ///
+    /// [`Scan::execute`]: crate::scan::Scan::execute
     fn read_files(
         &self,
         files: Vec<FileSlice>,
-    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>>;
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>> + Send>>;
 }
 
 /// Provides JSON handling functionality to Delta Kernel.
@@ -519,6 +582,21 @@ pub trait JsonHandler: AsAny {
         output_schema: SchemaRef,
     ) -> DeltaResult<Box<dyn EngineData>>;
 
+    /// Parse the given json strings of file statistics (the `stats` of add actions), like
+    /// [`JsonHandler::parse_json`]. Some writers encode integer statistics as floats (`1.0`) or with
+    /// exponents (`1e3`): implementations should accept those that exactly represent an integer,
+    /// and return null for other non-integral values rather than truncating them, since e.g. a
+    /// truncated `maxValues` could incorrectly skip files.
+    ///
+    /// The default implementation calls [`JsonHandler::parse_json`].
+    fn parse_json_stats(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.parse_json(json_strings, output_schema)
+    }
+
     /// Read and parse the JSON format file at given locations and return the data as EngineData with
     /// the columns requested by physical schema. Note: The [`FileDataReadResultIterator`] must emit
     /// data from files in the order that `files` is given. For example if files ["a", "b"] is provided,
@@ -576,6 +654,32 @@ pub trait JsonHandler: AsAny {
     ) -> DeltaResult<()>;
 }
 
+/// Metadata read from the footer of a Parquet file. See [`ParquetHandler::read_parquet_footer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParquetFooter {
+    /// The number of rows in the file.
+    pub num_rows: u64,
+}
+
+/// A part of a Parquet file, planned by [`ParquetHandler::plan_parquet_splits`] and read by
+/// [`ParquetHandler::read_parquet_splits`]. Reading a split reads the row groups of `file` which
+/// start within `range`, or the whole file if there is no range. The splits of a file can be read
+/// independently (e.g. in parallel), and together read each row of the file exactly once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetSplit {
+    /// The file to read from.
+    pub file: FileMeta,
+    /// The byte range of the file whose row groups to read, or `None` to read the whole file.
+    pub range: Option<Range<FileIndex>>,
+}
+
+impl ParquetSplit {
+    /// A split which reads the whole `file`.
+    pub fn whole_file(file: FileMeta) -> Self {
+        Self { file, range: None }
+    }
+}
+
 /// Provides Parquet file related functionalities to Delta Kernel.
 ///
 /// Connectors can leverage this trait to provide their own custom
@@ -585,17 +689,128 @@ pub trait ParquetHandler: AsAny {
     /// the columns requested by physical schema . The ParquetHandler _must_ return exactly the
     /// columns specified in `physical_schema`, and they _must_ be in schema order.
     ///
+    /// If `physical_schema` contains a [`ROW_INDEX_COLUMN_NAME`] column the file doesn't have, the
+    /// ParquetHandler must fill it with the index of each row within its file.
+    ///
     /// # Parameters
     ///
     /// - `files` - File metadata for files to be read.
     /// - `physical_schema` - Select list and order of columns to read from the Parquet file.
     /// - `predicate` - Optional push-down predicate hint (engine is free to ignore it).
+    ///
+    /// [`ROW_INDEX_COLUMN_NAME`]: crate::scan::ROW_INDEX_COLUMN_NAME
     fn read_parquet_files(
         &self,
         files: &[FileMeta],
         physical_schema: SchemaRef,
         predicate: Option<PredicateRef>,
     ) -> DeltaResult<FileDataReadResultIterator>;
+
+    /// Read the footer of the Parquet file at `file`, without reading any of its data. Kernel uses
+    /// this e.g. to find the number of records of files written without statistics. The default
+    /// implementation returns [`Error::Unsupported`].
+    fn read_parquet_footer(&self, file: &FileMeta) -> DeltaResult<ParquetFooter> {
+        Err(Error::unsupported(format!(
+            "Reading the footer of {} is not supported by this ParquetHandler",
+            file.location
+        )))
+    }
+
+    /// Plan how to split the Parquet file at `file` into [`ParquetSplit`]s of about
+    /// `target_split_size` bytes each, e.g. from the row groups listed in its footer, so that an
+    /// engine can read a large file in parallel or with limited memory. A single row group is never
+    /// split, so splits can be larger than the target. The default implementation doesn't split
+    /// the file.
+    fn plan_parquet_splits(
+        &self,
+        file: &FileMeta,
+        target_split_size: u64,
+    ) -> DeltaResult<Vec<ParquetSplit>> {
+        let _ = target_split_size;
+        Ok(vec![ParquetSplit::whole_file(file.clone())])
+    }
+
+    /// Like [`Self::read_parquet_files`], but only reads the row groups of each split (see
+    /// [`ParquetSplit`]). A [`ROW_INDEX_COLUMN_NAME`] column requested in `physical_schema` holds
+    /// the index of each row within its file, not within its split.
+    ///
+    /// The default implementation reads splits of whole files with [`Self::read_parquet_files`],
+    /// and returns [`Error::Unsupported`] for any split with a byte range.
+    ///
+    /// [`ROW_INDEX_COLUMN_NAME`]: crate::scan::ROW_INDEX_COLUMN_NAME
+    fn read_parquet_splits(
+        &self,
+        splits: &[ParquetSplit],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let files = splits
+            .iter()
+            .map(|split| match split.range {
+                None => Ok(split.file.clone()),
+                Some(_) => Err(Error::unsupported(format!(
+                    "Reading a part of {} is not supported by this ParquetHandler",
+                    split.file.location
+                ))),
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+        self.read_parquet_files(&files, physical_schema, predicate)
+    }
+
+    /// Write the selected rows of `data` to a single Parquet file at `location` with the columns of
+    /// `schema`, overwriting any existing file, and return the metadata of the written file. Kernel
+    /// uses this e.g. to write checkpoints, see [`CheckpointWriter::write`].
+    ///
+    /// The batches of `data` need not all have the same schema: columns and struct fields of
+    /// `schema` that a batch doesn't have must be written as nulls. The default implementation
+    /// returns [`Error::Unsupported`].
+    ///
+    /// [`CheckpointWriter::write`]: crate::checkpoint::CheckpointWriter::write
+    fn write_parquet_data(
+        &self,
+        location: &Url,
+        schema: SchemaRef,
+        data: Box<dyn Iterator<Item = DeltaResult<FilteredEngineData>> + Send + '_>,
+    ) -> DeltaResult<FileMeta> {
+        let _ = (schema, data);
+        Err(Error::unsupported(format!(
+            "Writing {location} is not supported by this ParquetHandler"
+        )))
+    }
+}
+
+/// Async variant of [`JsonHandler::read_json_files`], for engines whose IO is natively async.
+///
+/// The sync [`JsonHandler`] returns an iterator, which forces an async engine to drive its IO on
+/// a background executor and hand the results over a channel. Implementing this trait instead lets
+/// the engine return its stream directly; it is polled on the caller's runtime. The same ordering
+/// requirements as for [`JsonHandler::read_json_files`] apply.
+#[cfg(feature = "async")]
+pub trait AsyncJsonHandler: AsAny {
+    /// Read and parse the JSON format files at the given locations, returning a stream of the
+    /// data with the columns requested by `physical_schema`. See [`JsonHandler::read_json_files`].
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream>;
+}
+
+/// Async variant of [`ParquetHandler::read_parquet_files`], for engines whose IO is natively
+/// async. See [`AsyncJsonHandler`] for why this exists, and [`scan::Scan::execute_async`] for a
+/// scan that reads its data files through it.
+#[cfg(feature = "async")]
+pub trait AsyncParquetHandler: AsAny {
+    /// Read and parse the Parquet files at the given locations, returning a stream of the data
+    /// with exactly the columns of `physical_schema`, in schema order. See
+    /// [`ParquetHandler::read_parquet_files`].
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream>;
 }
 
 /// The `Engine` trait encapsulates all the functionality an engine or connector needs to provide
@@ -0,0 +1,200 @@
+//! The [`LogCompactionLogReplayProcessor`] implements the log replay that reconciles the actions of
+//! a range of commits into a log compaction file. It processes the commits in reverse
+//! chronological order (newest to oldest) and selects the latest action for each entity.
+//!
+//! Unlike a checkpoint, a log compaction file only replaces the commits of its range: older
+//! commits are still replayed after it. So it must keep every remove action (tombstone), however
+//! old, as it may cancel an add action from an older commit. For the same reason it keeps domain
+//! metadata removals, and transactions regardless of their age.
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::engine_data::{FilteredEngineData, GetData, RowVisitor};
+use crate::log_replay::{ActionsBatch, FileActionDeduplicator, FileActionKey, LogReplayProcessor};
+use crate::scan::data_skipping::DataSkippingFilter;
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// A [`LogReplayProcessor`] that selects the reconciled actions of a range of commits for a log
+/// compaction file, see the [module-level documentation](self).
+#[derive(Default)]
+pub(crate) struct LogCompactionLogReplayProcessor {
+    /// Tracks file actions that have been seen during log replay to avoid duplicates.
+    seen_file_keys: HashSet<FileActionKey>,
+    /// Indicates whether a protocol action has been seen in the log.
+    seen_protocol: bool,
+    /// Indicates whether a metadata action has been seen in the log.
+    seen_metadata: bool,
+    /// Set of transaction app IDs that have been processed to avoid duplicates.
+    seen_txns: HashSet<String>,
+    /// Set of metadata domains that have been processed to avoid duplicates.
+    seen_domains: HashSet<String>,
+}
+
+impl LogReplayProcessor for LogCompactionLogReplayProcessor {
+    type Output = FilteredEngineData;
+
+    fn process_actions_batch(&mut self, actions_batch: ActionsBatch) -> DeltaResult<Self::Output> {
+        let ActionsBatch {
+            actions,
+            is_log_batch,
+        } = actions_batch;
+        let mut visitor = LogCompactionVisitor {
+            deduplicator: FileActionDeduplicator::new(
+                &mut self.seen_file_keys,
+                is_log_batch,
+                0,
+                4,
+                1,
+                5,
+            ),
+            selection_vector: vec![true; actions.len()],
+            seen_protocol: self.seen_protocol,
+            seen_metadata: self.seen_metadata,
+            seen_txns: &mut self.seen_txns,
+            seen_domains: &mut self.seen_domains,
+        };
+        visitor.visit_rows_of(actions.as_ref())?;
+
+        self.seen_protocol = visitor.seen_protocol;
+        self.seen_metadata = visitor.seen_metadata;
+        Ok(FilteredEngineData {
+            data: actions,
+            selection_vector: visitor.selection_vector,
+        })
+    }
+
+    /// Log compaction reproduces every action of its commits, so never skips data
+    fn data_skipping_filter(&self) -> Option<&DataSkippingFilter> {
+        None
+    }
+}
+
+/// Selects the newest add or remove action for each unique (path, dvId) pair, the newest protocol
+/// and metadata actions, and the newest txn and domainMetadata actions for each app ID and domain.
+/// Any other action (e.g. commitInfo) is dropped.
+struct LogCompactionVisitor<'seen> {
+    deduplicator: FileActionDeduplicator<'seen>,
+    selection_vector: Vec<bool>,
+    seen_protocol: bool,
+    seen_metadata: bool,
+    seen_txns: &'seen mut HashSet<String>,
+    seen_domains: &'seen mut HashSet<String>,
+}
+
+impl LogCompactionVisitor<'_> {
+    fn is_valid_action<'a>(
+        &mut self,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<bool> {
+        if let Some((file_key, _)) = self.deduplicator.extract_file_action(i, getters, false)? {
+            return Ok(!self.deduplicator.check_and_record_seen(file_key));
+        }
+        if getters[8].get_str(i, "metaData.id")?.is_some() {
+            return Ok(!std::mem::replace(&mut self.seen_metadata, true));
+        }
+        if getters[9]
+            .get_int(i, "protocol.minReaderVersion")?
+            .is_some()
+        {
+            return Ok(!std::mem::replace(&mut self.seen_protocol, true));
+        }
+        if let Some(app_id) = getters[10].get_str(i, "txn.appId")? {
+            return Ok(self.seen_txns.insert(app_id.to_string()));
+        }
+        if let Some(domain) = getters[11].get_str(i, "domainMetadata.domain")? {
+            return Ok(self.seen_domains.insert(domain.to_string()));
+        }
+        Ok(false)
+    }
+}
+
+impl RowVisitor for LogCompactionVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        // The file action columns must be in the order the deduplicator expects
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            const STRING: DataType = DataType::STRING;
+            const INTEGER: DataType = DataType::INTEGER;
+            let types_and_names = vec![
+                (STRING, column_name!("add.path")),
+                (STRING, column_name!("add.deletionVector.storageType")),
+                (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("add.deletionVector.offset")),
+                (STRING, column_name!("remove.path")),
+                (STRING, column_name!("remove.deletionVector.storageType")),
+                (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("remove.deletionVector.offset")),
+                (STRING, column_name!("metaData.id")),
+                (INTEGER, column_name!("protocol.minReaderVersion")),
+                (STRING, column_name!("txn.appId")),
+                (STRING, column_name!("domainMetadata.domain")),
+            ];
+            let (types, names) = types_and_names.into_iter().unzip();
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 12,
+            Error::InternalError(format!(
+                "Wrong number of LogCompactionVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if self.selection_vector[i] {
+                self.selection_vector[i] = self.is_valid_action(i, getters)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::arrow::array::StringArray;
+    use crate::utils::test_utils::parse_json_batch;
+
+    #[test]
+    fn test_log_compaction_reconciles_actions() -> DeltaResult<()> {
+        // Commits, newest first
+        let newer = parse_json_batch(StringArray::from(vec![
+            r#"{"commitInfo":{"operation":"WRITE"}}"#,
+            r#"{"add":{"path":"file2","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true}}"#,
+            r#"{"remove":{"path":"file1","deletionTimestamp":0,"dataChange":true}}"#,
+            r#"{"txn":{"appId":"app","version":2}}"#,
+            r#"{"domainMetadata":{"domain":"d","configuration":"","removed":true}}"#,
+        ]));
+        let older = parse_json_batch(StringArray::from(vec![
+            r#"{"commitInfo":{"operation":"WRITE"}}"#,
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+            r#"{"metaData":{"id":"id","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[]}","partitionColumns":[],"configuration":{}}}"#,
+            r#"{"add":{"path":"file1","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true}}"#,
+            r#"{"add":{"path":"file3","partitionValues":{},"size":1,"modificationTime":1,"dataChange":true}}"#,
+            r#"{"txn":{"appId":"app","version":1}}"#,
+            r#"{"txn":{"appId":"other","version":1}}"#,
+            r#"{"domainMetadata":{"domain":"d","configuration":"","removed":false}}"#,
+        ]));
+        let batches = [newer, older].map(|actions| Ok(ActionsBatch::new(actions, true)));
+        let selected: Vec<_> = LogCompactionLogReplayProcessor::default()
+            .process_actions_iter(batches.into_iter())
+            .map_ok(|data| data.selection_vector)
+            .try_collect()?;
+        assert_eq!(
+            selected,
+            [
+                // The remove of file1 is kept even though it is old, and shadows the older add
+                vec![false, true, true, true, true],
+                vec![false, true, true, false, true, false, true, false],
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! This module implements the API for writing log compaction files.
+//!
+//! A log compaction file `<x>.<y>.compacted.json` holds the reconciled actions of the commits `x`
+//! through `y`. Readers that find one can read it instead of those commits, which reduces the
+//! number of files to list and replay. The entry point for this API is
+//! [`Snapshot::log_compaction_writer`].
+//!
+//! For more information, see the following protocol section:
+//! <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#log-compaction-files>
+//!
+//! # Example
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use delta_kernel::{Engine, Error, Snapshot};
+//! # let engine: &dyn Engine = todo!();
+//! let snapshot = Arc::new(Snapshot::try_from_uri("./tests/data/basic_partitioned", engine, None)?);
+//!
+//! // Compact the commits 0 through 1 into `00000000000000000000.00000000000000000001.compacted.json`
+//! let writer = snapshot.log_compaction_writer(0, 1)?;
+//! writer.write(engine)?;
+//! # Ok::<_, Error>(())
+//! ```
+use std::sync::{Arc, LazyLock};
+
+use url::Url;
+
+use crate::actions::{
+    Add, DomainMetadata, Metadata, Protocol, Remove, SetTransaction, Sidecar, ADD_NAME,
+    DOMAIN_METADATA_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME, SET_TRANSACTION_NAME,
+    SIDECAR_NAME,
+};
+use crate::log_replay::LogReplayProcessor as _;
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::schema::{SchemaRef, StructField, StructType, ToSchema as _};
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error, Version};
+use log_replay::LogCompactionLogReplayProcessor;
+
+mod log_replay;
+#[cfg(test)]
+mod tests;
+
+/// Schema of the actions that log compaction files retain. Other actions, such as commitInfo and
+/// cdc, only describe their own commit. Commits never contain sidecar actions, but log replay
+/// requires the column when reading file actions.
+static LOG_COMPACTION_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([
+        StructField::nullable(ADD_NAME, Add::to_schema()),
+        StructField::nullable(REMOVE_NAME, Remove::to_schema()),
+        StructField::nullable(METADATA_NAME, Metadata::to_schema()),
+        StructField::nullable(PROTOCOL_NAME, Protocol::to_schema()),
+        StructField::nullable(SET_TRANSACTION_NAME, SetTransaction::to_schema()),
+        StructField::nullable(DOMAIN_METADATA_NAME, DomainMetadata::to_schema()),
+        StructField::nullable(SIDECAR_NAME, Sidecar::to_schema()),
+    ]))
+});
+
+/// Writes a log compaction file for a range of commits of a table.
+///
+/// The file holds the newest `add` or `remove` action of each file, the newest protocol and
+/// metadata, and the newest `txn` and `domainMetadata` actions of each app ID and domain, among
+/// the commits of the range. All `remove` actions are kept, since they may cancel `add` actions of
+/// commits before the range.
+///
+/// Writing requires the engine's [`EngineData`] to support
+/// [`EngineData::apply_selection_vector`], which the default engine's data does.
+///
+/// [`EngineData`]: crate::EngineData
+/// [`EngineData::apply_selection_vector`]: crate::EngineData::apply_selection_vector
+pub struct LogCompactionWriter {
+    snapshot: Arc<Snapshot>,
+    start_version: Version,
+    end_version: Version,
+}
+
+impl LogCompactionWriter {
+    /// Creates a [`LogCompactionWriter`] for the commits `start_version..=end_version`, which must
+    /// span at least two commits and end no later than the snapshot's version.
+    pub(crate) fn try_new(
+        snapshot: Arc<Snapshot>,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<Self> {
+        if start_version >= end_version {
+            return Err(Error::generic(format!(
+                "Invalid log compaction range {start_version}..={end_version}: the start version \
+                must be less than the end version"
+            )));
+        }
+        if end_version > snapshot.version() {
+            return Err(Error::generic(format!(
+                "Invalid log compaction range {start_version}..={end_version}: the end version \
+                is after the snapshot version {}",
+                snapshot.version()
+            )));
+        }
+        Ok(Self {
+            snapshot,
+            start_version,
+            end_version,
+        })
+    }
+
+    /// Returns the URL of the log compaction file, e.g.
+    /// `<table_root>/_delta_log/00000000000000000008.00000000000000000015.compacted.json`
+    pub fn compaction_path(&self) -> DeltaResult<Url> {
+        ParsedLogPath::new_log_compaction(
+            self.snapshot.table_root(),
+            self.start_version,
+            self.end_version,
+        )
+        .map(|parsed| parsed.location)
+    }
+
+    /// Reads the commits of the range, reconciles their actions and writes them to
+    /// [`Self::compaction_path`] with the engine's [`JsonHandler`].
+    ///
+    /// Returns an error if any commit of the range is missing, or with
+    /// [`Error::FileAlreadyExists`] if the log compaction file already exists.
+    ///
+    /// [`JsonHandler`]: crate::JsonHandler
+    pub fn write(self, engine: &dyn Engine) -> DeltaResult<()> {
+        let log_segment = LogSegment::for_table_changes(
+            engine.storage_handler().as_ref(),
+            self.snapshot.log_segment().log_root.clone(),
+            self.start_version,
+            self.end_version,
+        )?;
+        let actions = log_segment.read_actions(
+            engine,
+            LOG_COMPACTION_ACTIONS_SCHEMA.clone(),
+            LOG_COMPACTION_ACTIONS_SCHEMA.clone(),
+            None,
+        )?;
+        let data = LogCompactionLogReplayProcessor::default()
+            .process_actions_iter(actions)
+            .map(|filtered| {
+                let filtered = filtered?;
+                filtered
+                    .data
+                    .apply_selection_vector(filtered.selection_vector)
+            });
+        engine
+            .json_handler()
+            .write_json_file(&self.compaction_path()?, Box::new(data), false)
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde_json::{json, Value};
+use test_utils::{actions_to_string, add_commit, TestAction};
+use url::Url;
+
+use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::object_store::{memory::InMemory, path::Path, ObjectStore};
+use crate::{DeltaResult, Error, Snapshot};
+
+// Parse newline delimited JSON actions
+fn parse_actions(json: &str) -> Vec<Value> {
+    serde_json::Deserializer::from_str(json)
+        .into_iter()
+        .try_collect()
+        .unwrap()
+}
+
+async fn read_from_store(store: &InMemory, path: &str) -> Vec<Value> {
+    let bytes = store.get(&Path::from(path)).await.unwrap().bytes().await;
+    parse_actions(std::str::from_utf8(&bytes.unwrap()).unwrap())
+}
+
+fn txn(version: i64) -> String {
+    json!({"txn": {"appId": "app", "version": version}}).to_string()
+}
+
+fn commit_info() -> String {
+    json!({"commitInfo": {"timestamp": 1, "operation": "WRITE"}}).to_string()
+}
+
+async fn setup_table() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>) {
+    use TestAction::*;
+    let store = Arc::new(InMemory::new());
+    let commits = [
+        actions_to_string(vec![Metadata, Add("file1".into())]),
+        [
+            commit_info(),
+            actions_to_string(vec![Add("file2".into())]),
+            txn(1),
+        ]
+        .join("\n"),
+        [
+            commit_info(),
+            actions_to_string(vec![Remove("file1".into()), Add("file3".into())]),
+            txn(2),
+        ]
+        .join("\n"),
+        actions_to_string(vec![Add("file4".into())]),
+    ];
+    for (version, commit) in commits.into_iter().enumerate() {
+        add_commit(store.as_ref(), version as u64, commit)
+            .await
+            .unwrap();
+    }
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    (store, engine)
+}
+
+#[tokio::test]
+async fn test_write_log_compaction() -> DeltaResult<()> {
+    let (store, engine) = setup_table().await;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None)?);
+
+    let writer = snapshot.log_compaction_writer(1, 2)?;
+    let path = "_delta_log/00000000000000000001.00000000000000000002.compacted.json";
+    assert_eq!(writer.compaction_path()?, table_root.join(path)?);
+    writer.write(&engine)?;
+
+    // The newest txn is kept, commitInfo is dropped, and the remove of file1 is kept to cancel its
+    // add from commit 0.
+    let expected = [
+        actions_to_string(vec![
+            TestAction::Remove("file1".into()),
+            TestAction::Add("file3".into()),
+        ]),
+        txn(2),
+        actions_to_string(vec![TestAction::Add("file2".into())]),
+    ]
+    .join("\n");
+    let mut expected = parse_actions(&expected);
+    // remove actions have no modificationTime, so it isn't carried over
+    expected[0]["remove"]
+        .as_object_mut()
+        .unwrap()
+        .remove("modificationTime");
+    assert_eq!(read_from_store(&store, path).await, expected);
+
+    // A new snapshot reads the compacted file in place of the commits it covers
+    let snapshot = Snapshot::try_new(table_root, &engine, None)?;
+    assert_eq!(snapshot.log_segment().ascending_compaction_files.len(), 1);
+    let files: HashSet<_> = snapshot
+        .files(&engine)?
+        .map_ok(|file| file.file.location.path().to_string())
+        .try_collect()?;
+    assert_eq!(
+        files,
+        HashSet::from(["/file2", "/file3", "/file4"].map(String::from))
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_log_compaction_errors() -> DeltaResult<()> {
+    let (_, engine) = setup_table().await;
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, Some(2))?);
+
+    // The range must span at least two commits, and end at or before the snapshot version
+    assert!(snapshot.clone().log_compaction_writer(1, 1).is_err());
+    assert!(snapshot.clone().log_compaction_writer(2, 1).is_err());
+    assert!(snapshot.clone().log_compaction_writer(1, 3).is_err());
+
+    snapshot
+        .clone()
+        .log_compaction_writer(0, 2)?
+        .write(&engine)?;
+    let res = snapshot.log_compaction_writer(0, 2)?.write(&engine);
+    assert!(matches!(res, Err(Error::FileAlreadyExists(_))), "{res:?}");
+    Ok(())
+}
@@ -21,7 +21,7 @@ use crate::{
 use delta_kernel_derive::internal_api;
 
 use itertools::Itertools;
-use tracing::{debug, warn};
+use tracing::warn;
 use url::Url;
 
 #[cfg(test)]
@@ -56,6 +56,34 @@ pub(crate) struct LogSegment {
     pub latest_crc_file: Option<ParsedLogPath>,
 }
 
+/// How [`Snapshot::plan_commit_cover`] weighs the commit and compaction files it can choose
+/// from. Engines whose reads are dominated by per-file latency (e.g. object stores) usually prefer
+/// [`CommitCoverCost::FileCount`], while engines limited by bandwidth may prefer
+/// [`CommitCoverCost::Bytes`].
+///
+/// [`Snapshot::plan_commit_cover`]: crate::Snapshot::plan_commit_cover
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitCoverCost {
+    /// Read as few files as possible, breaking ties by reading fewer bytes.
+    #[default]
+    FileCount,
+    /// Read as few bytes as possible (using the size of each file's [`FileMeta`]), breaking ties by
+    /// reading fewer files.
+    Bytes,
+}
+
+/// A set of commit and compaction files that together cover every commit of a snapshot (after its
+/// checkpoint) exactly once. See [`Snapshot::plan_commit_cover`].
+///
+/// [`Snapshot::plan_commit_cover`]: crate::Snapshot::plan_commit_cover
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitCover {
+    /// The selected files, in DESCENDING version order (as log replay expects).
+    pub files: Vec<FileMeta>,
+    /// The total size in bytes of the selected files.
+    pub expected_bytes: u64,
+}
+
 impl LogSegment {
     pub(crate) fn try_new(
         listed_files: ListedLogFiles,
@@ -142,10 +170,30 @@ impl LogSegment {
         log_root: Url,
         checkpoint_hint: impl Into<Option<LastCheckpointHint>>,
         time_travel_version: impl Into<Option<Version>>,
+    ) -> DeltaResult<Self> {
+        Self::for_snapshot_with_log_tail(
+            storage,
+            log_root,
+            checkpoint_hint,
+            vec![],
+            time_travel_version,
+        )
+    }
+
+    /// Like [`LogSegment::for_snapshot`], but the commits in `log_tail` extend the listed ones.
+    /// These are commits that listing the `_delta_log` may not find, e.g. commits a catalog has
+    /// accepted but not yet published. `log_tail` must be commit files in ascending version order.
+    /// A listed commit takes precedence over a log tail commit of the same version.
+    pub(crate) fn for_snapshot_with_log_tail(
+        storage: &dyn StorageHandler,
+        log_root: Url,
+        checkpoint_hint: impl Into<Option<LastCheckpointHint>>,
+        log_tail: Vec<ParsedLogPath>,
+        time_travel_version: impl Into<Option<Version>>,
     ) -> DeltaResult<Self> {
         let time_travel_version = time_travel_version.into();
 
-        let listed_files = match (checkpoint_hint.into(), time_travel_version) {
+        let mut listed_files = match (checkpoint_hint.into(), time_travel_version) {
             (Some(cp), None) => list_log_files_with_checkpoint(&cp, storage, &log_root, None)?,
             (Some(cp), Some(end_version)) if cp.version <= end_version => {
                 list_log_files_with_checkpoint(&cp, storage, &log_root, Some(end_version))?
@@ -153,9 +201,47 @@ impl LogSegment {
             _ => list_log_files_with_version(storage, &log_root, None, time_travel_version)?,
         };
 
+        let listed_version = listed_files
+            .ascending_commit_files
+            .last()
+            .or(listed_files.checkpoint_parts.first())
+            .map(|file| file.version);
+        listed_files
+            .ascending_commit_files
+            .extend(log_tail.into_iter().filter(|commit| {
+                listed_version.is_none_or(|listed| commit.version > listed)
+                    && time_travel_version.is_none_or(|end| commit.version <= end)
+            }));
+
         LogSegment::try_new(listed_files, log_root, time_travel_version)
     }
 
+    /// The commits of this segment that are not published to the `_delta_log`, i.e. that were
+    /// taken from a catalog's log tail (see [`LogSegment::for_snapshot_with_log_tail`]).
+    pub(crate) fn unpublished_commits(&self) -> impl Iterator<Item = &ParsedLogPath> {
+        self.ascending_commit_files.iter().filter(|commit| {
+            self.log_root
+                .join(&commit.filename)
+                .is_ok_and(|published| published != commit.location.location)
+        })
+    }
+
+    /// Extend `commits`, listed from the `_delta_log` in ascending version order starting at
+    /// `start_version`, with the [unpublished commits](Self::unpublished_commits) of this segment
+    /// that the listing cannot find.
+    pub(crate) fn extend_with_unpublished_commits(
+        &self,
+        commits: &mut Vec<ParsedLogPath>,
+        start_version: Option<Version>,
+    ) {
+        let listed_version = commits.last().map(|commit| commit.version);
+        let unpublished = self.unpublished_commits().filter(|commit| {
+            listed_version.is_none_or(|listed| commit.version > listed)
+                && start_version.is_none_or(|start| commit.version >= start)
+        });
+        commits.extend(unpublished.cloned());
+    }
+
     /// Constructs a [`LogSegment`] to be used for `TableChanges`. For a TableChanges between versions
     /// `start_version` and `end_version`: Its LogSegment is made of zero checkpoints and all commits
     /// between versions `start_version` (inclusive) and `end_version` (inclusive). If no `end_version`
@@ -224,11 +310,11 @@ impl LogSegment {
         meta_predicate: Option<PredicateRef>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
         // `replay` expects commit files to be sorted in descending order, so the return value here is correct
-        let commits_and_compactions = self.find_commit_cover();
+        let commits_and_compactions = self.plan_commit_cover(CommitCoverCost::default());
         let commit_stream = engine
             .json_handler()
             .read_json_files(
-                &commits_and_compactions,
+                &commits_and_compactions.files,
                 commit_read_schema,
                 meta_predicate.clone(),
             )?
@@ -240,47 +326,95 @@ impl LogSegment {
         Ok(commit_stream.chain(checkpoint_stream))
     }
 
-    /// find a minimal set to cover the range of commits we want. This is greedy so not always
-    /// optimal, but we assume there are rarely overlapping compactions so this is okay. NB: This
-    /// returns files is DESCENDING ORDER, as that's what `replay` expects. This function assumes
-    /// that all files in `self.ascending_commit_files` and `self.ascending_compaction_files` are in
-    /// range for this log segment. This invariant is maintained by our listing code.
-    fn find_commit_cover(&self) -> Vec<FileMeta> {
-        // Create an iterator sorted in ascending order by (initial version, end version), e.g.
-        // [00.json, 00.09.compacted.json, 00.99.compacted.json, 01.json, 02.json, ..., 10.json,
-        //  10.19.compacted.json, 11.json, ...]
-        let all_files = itertools::Itertools::merge_by(
-            self.ascending_commit_files.iter(),
-            self.ascending_compaction_files.iter(),
-            |path_a, path_b| path_a.version <= path_b.version,
-        );
+    /// Plans a cover of this log segment's commits: a set of commit and compaction files which
+    /// together contain every commit after the checkpoint exactly once, chosen to minimize `cost`.
+    /// The cover is optimal even when compaction files overlap. Returns an empty cover if the
+    /// segment has no commits after its checkpoint. [`LogSegment::read_actions`] reads the cover
+    /// that minimizes [`CommitCoverCost::FileCount`].
+    #[internal_api]
+    pub(crate) fn plan_commit_cover(&self, cost: CommitCoverCost) -> CommitCover {
+        let Some(start) = self
+            .ascending_commit_files
+            .first()
+            .map(|commit| commit.version)
+        else {
+            return CommitCover {
+                files: vec![],
+                expected_bytes: 0,
+            };
+        };
+        // (lo, hi, file) for every candidate file inside [start, end_version], sorted by lo
+        let mut candidates: Vec<_> = self
+            .ascending_commit_files
+            .iter()
+            .chain(&self.ascending_compaction_files)
+            .filter_map(|path| {
+                let hi = match path.file_type {
+                    LogPathFileType::CompactedCommit { hi } => hi,
+                    _ => path.version,
+                };
+                (start <= path.version && hi <= self.end_version).then_some((
+                    path.version,
+                    hi,
+                    path,
+                ))
+            })
+            .collect();
+        candidates.sort_by_key(|(lo, hi, _)| (*lo, *hi));
 
-        let mut last_pushed: Option<&ParsedLogPath> = None;
+        let weight = |file: &FileMeta| match cost {
+            CommitCoverCost::FileCount => (1, file.size),
+            CommitCoverCost::Bytes => (file.size, 1),
+        };
 
-        let mut selected_files = vec![];
-        for next in all_files {
-            match last_pushed {
-                // Resolve version number ties in favor of the later file (it covers a wider range)
-                Some(prev) if prev.version == next.version => {
-                    let removed = selected_files.pop();
-                    debug!("Selecting {next:?} rather than {removed:?}, it covers a wider range");
-                }
-                // Skip later files whose start overlaps with the previous end
-                Some(&ParsedLogPath {
-                    file_type: LogPathFileType::CompactedCommit { hi },
-                    ..
-                }) if next.version <= hi => {
-                    debug!("Skipping log file {next:?}, it's already covered.");
-                    continue;
+        // best[i] is the cheapest known way to cover versions [start, start + i), as its total
+        // cost and the index of the last candidate it reads. Commits are contiguous from `start`
+        // to `end_version`, so a cover always exists.
+        let len = (self.end_version - start + 1) as usize;
+        let mut best: Vec<Option<((u64, u64), usize)>> = vec![None; len + 1];
+        best[0] = Some(((0, 0), usize::MAX));
+        let mut next_candidate = 0;
+        for covered in 0..len {
+            let first = next_candidate;
+            while next_candidate < candidates.len()
+                && candidates[next_candidate].0 == start + covered as u64
+            {
+                next_candidate += 1;
+            }
+            let Some(((primary, secondary), _)) = best[covered] else {
+                continue;
+            };
+            for (index, (_, hi, path)) in candidates
+                .iter()
+                .enumerate()
+                .take(next_candidate)
+                .skip(first)
+            {
+                let (w1, w2) = weight(&path.location);
+                let total = (primary + w1, secondary + w2);
+                let slot = &mut best[(hi - start + 1) as usize];
+                if slot.is_none_or(|(existing, _)| total < existing) {
+                    *slot = Some((total, index));
                 }
-                _ => {} // just fall through
             }
-            debug!("Provisionally selecting {next:?}");
-            last_pushed = Some(next);
-            selected_files.push(next.location.clone());
         }
-        selected_files.reverse();
-        selected_files
+
+        // Walk back from the end of the segment, which yields files in descending order.
+        let mut files = vec![];
+        let mut covered = len;
+        while covered > 0 {
+            let Some((_, index)) = best[covered] else {
+                break;
+            };
+            let (lo, _, path) = candidates[index];
+            files.push(path.location.clone());
+            covered = (lo - start) as usize;
+        }
+        let expected_bytes = files.iter().map(|file| file.size).sum();
+        CommitCover {
+            files,
+            expected_bytes,
+        }
     }
 
     /// Returns an iterator over checkpoint data, processing sidecar files when necessary.
@@ -477,7 +611,7 @@ impl LogSegment {
 /// `end_version` is not specified, files up to the most recent version will be included.
 ///
 /// Note: this calls [`StorageHandler::list_from`] to get the list of log files.
-fn list_log_files(
+pub(crate) fn list_log_files(
     storage: &dyn StorageHandler,
     log_root: &Url,
     start_version: impl Into<Option<Version>>,
@@ -577,8 +711,8 @@ pub(crate) fn list_log_files_with_version(
 
     let log_files = list_log_files(storage, log_root, start_version, end_version)?;
 
-    log_files.process_results(|iter| {
-        let mut ascending_commit_files = Vec::with_capacity(10);
+    log_files.process_results(|iter| -> DeltaResult<_> {
+        let mut ascending_commit_files: Vec<ParsedLogPath> = Vec::with_capacity(10);
         let mut ascending_compaction_files = Vec::with_capacity(2);
         let mut checkpoint_parts = vec![];
         let mut latest_crc_file: Option<ParsedLogPath> = None;
@@ -591,11 +725,32 @@ pub(crate) fn list_log_files_with_version(
             for file in files {
                 use LogPathFileType::*;
                 match file.file_type {
-                    Commit => ascending_commit_files.push(file),
+                    Commit => {
+                        // A version can have a plain and a compressed commit file. Writers only get
+                        // mutual exclusion on one of them, so we can't tell which one is valid.
+                        if let Some(other) = ascending_commit_files
+                            .last()
+                            .filter(|other| other.version == version)
+                        {
+                            return Err(Error::generic(format!(
+                                "Found multiple commit files for version {version}: {} and {}",
+                                other.filename, file.filename
+                            )));
+                        }
+                        ascending_commit_files.push(file)
+                    }
                     CompactedCommit { hi } if end_version.is_none_or(|end| hi <= end) => {
                         ascending_compaction_files.push(file);
                     }
                     CompactedCommit { .. } => (), // Failed the bounds check above
+                    SinglePartCheckpoint | UuidCheckpoint(_) | MultiPartCheckpoint { .. }
+                        if file.location.size == 0 =>
+                    {
+                        return Err(Error::invalid_checkpoint(format!(
+                            "Checkpoint file {} at version {version} is empty",
+                            file.filename
+                        )));
+                    }
                     SinglePartCheckpoint | UuidCheckpoint(_) | MultiPartCheckpoint { .. } => {
                         new_checkpoint_parts.push(file)
                     }
@@ -627,13 +782,13 @@ pub(crate) fn list_log_files_with_version(
             }
         }
 
-        ListedLogFiles::new(
+        Ok(ListedLogFiles::new(
             ascending_commit_files,
             ascending_compaction_files,
             checkpoint_parts,
             latest_crc_file,
-        )
-    })
+        ))
+    })?
 }
 
 /// Groups all checkpoint parts according to the checkpoint they belong to.
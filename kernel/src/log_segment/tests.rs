@@ -237,6 +237,50 @@ fn build_snapshot_with_uuid_checkpoint_parquet() {
     assert_eq!(versions, expected_versions);
 }
 
+#[test]
+fn build_snapshot_with_empty_checkpoint_part_fails() {
+    let store = Arc::new(InMemory::new());
+    let paths = [
+        delta_path_for_version(0, "json"),
+        delta_path_for_version(1, "json"),
+        delta_path_for_version(2, "json"),
+        delta_path_for_version(2, "checkpoint.parquet"),
+        delta_path_for_version(3, "json"),
+        delta_path_for_version(4, "json"),
+        delta_path_for_version(5, "json"),
+        delta_path_for_multipart_checkpoint(5, 1, 2),
+        delta_path_for_version(6, "json"),
+    ];
+    block_on(async {
+        for path in paths {
+            store.put(&path, "kernel-data".into()).await.unwrap();
+        }
+        // an aborted checkpoint job left an empty second part behind
+        let empty_part = delta_path_for_multipart_checkpoint(5, 2, 2);
+        store.put(&empty_part, "".into()).await.unwrap();
+    });
+    let storage = ObjectStoreStorageHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+    let log_root = Url::parse("memory:///_delta_log/").unwrap();
+
+    // with and without a _last_checkpoint hint pointing at it, the empty part is an error
+    let hint = LastCheckpointHint {
+        version: 5,
+        size: 10,
+        parts: Some(2),
+        size_in_bytes: None,
+        num_of_add_files: None,
+        checkpoint_schema: None,
+        checksum: None,
+    };
+    for hint in [None, Some(hint)] {
+        let result = LogSegment::for_snapshot(&storage, log_root.clone(), hint, None);
+        assert!(
+            matches!(result, Err(Error::InvalidCheckpoint(ref msg)) if msg.contains("is empty")),
+            "{result:?}"
+        );
+    }
+}
+
 #[test]
 fn build_snapshot_with_uuid_checkpoint_json() {
     let (storage, log_root) = build_log_with_paths_and_checkpoint(
@@ -1361,6 +1405,23 @@ fn test_list_log_files_with_version() -> DeltaResult<()> {
     Ok(())
 }
 
+#[cfg(feature = "compressed-json")]
+#[test]
+fn test_list_log_files_with_multiple_commit_files_per_version() {
+    let (storage, log_root) = build_log_with_paths_and_checkpoint(
+        &[
+            delta_path_for_version(0, "json"),
+            delta_path_for_version(1, "json"),
+            delta_path_for_version(1, "json.gz"),
+        ],
+        None,
+    );
+    let err = list_log_files_with_version(storage.as_ref(), &log_root, None, None).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Found multiple commit files for version 1"));
+}
+
 fn test_compaction_listing(
     commit_versions: &[u64],
     compaction_versions: &[(u64, u64)],
@@ -1518,7 +1579,9 @@ fn test_commit_cover(
         checkpoint_version,
         version_to_load,
     );
-    let cover = log_segment.find_commit_cover();
+    let cover = log_segment
+        .plan_commit_cover(CommitCoverCost::FileCount)
+        .files;
     // our test-utils include "_delta_log" in the path, which is already in log_segment.log_root, so
     // we don't use them. TODO: Unify this
     let expected_locations = expected_files.iter().map(|ef| match ef {
@@ -1678,10 +1741,9 @@ fn test_commit_cover_minimal_overlap() {
         None, // checkpoint version
         None, // version to load
         &[
-            ExpectedFile::Commit(5),
-            ExpectedFile::Commit(4),
-            ExpectedFile::Commit(3),
-            ExpectedFile::Compaction(0, 2),
+            ExpectedFile::Compaction(2, 5),
+            ExpectedFile::Commit(1),
+            ExpectedFile::Commit(0),
         ],
     );
 }
@@ -1744,3 +1806,38 @@ fn test_debug_assert_listed_log_file_invalid_multipart_checkpoint() {
         None,
     );
 }
+
+#[test]
+fn test_plan_commit_cover_bytes() {
+    let mut log_segment = create_segment_for(&Vec::from_iter(0..6), &[(0, 5)], None, None);
+    let commit_bytes: u64 = log_segment
+        .ascending_commit_files
+        .iter()
+        .map(|commit| commit.location.size)
+        .sum();
+
+    // a compaction smaller than the commits it replaces is preferred either way
+    log_segment.ascending_compaction_files[0].location.size = commit_bytes - 1;
+    for cost in [CommitCoverCost::FileCount, CommitCoverCost::Bytes] {
+        let cover = log_segment.plan_commit_cover(cost);
+        assert_eq!(cover.files.len(), 1);
+        assert_eq!(cover.expected_bytes, commit_bytes - 1);
+    }
+
+    // a larger compaction only wins when minimizing the number of files
+    log_segment.ascending_compaction_files[0].location.size = commit_bytes + 1;
+    let cover = log_segment.plan_commit_cover(CommitCoverCost::FileCount);
+    assert_eq!(cover.files.len(), 1);
+    assert_eq!(cover.expected_bytes, commit_bytes + 1);
+    let cover = log_segment.plan_commit_cover(CommitCoverCost::Bytes);
+    assert_eq!(cover.files.len(), 6);
+    assert_eq!(cover.expected_bytes, commit_bytes);
+}
+
+#[test]
+fn test_plan_commit_cover_checkpoint_only() {
+    let log_segment = create_segment_for(&[0, 1], &[(0, 1)], Some(1), None);
+    let cover = log_segment.plan_commit_cover(CommitCoverCost::FileCount);
+    assert!(cover.files.is_empty());
+    assert_eq!(cover.expected_bytes, 0);
+}
@@ -119,6 +119,9 @@ impl<Location: AsUrl> ParsedLogPath<Location> {
         // Parse the file type, based on the number of remaining parts
         let file_type = match split.as_slice() {
             ["json"] => LogPathFileType::Commit,
+            // Compressed commits are opt-in via the `compressed-json` feature, since engines without
+            // it can't read them. Otherwise they are unknown files, as for any other reader.
+            ["json", "gz" | "zst"] if cfg!(feature = "compressed-json") => LogPathFileType::Commit,
             ["crc"] => LogPathFileType::Crc,
             ["checkpoint", "parquet"] => LogPathFileType::SinglePartCheckpoint,
             ["checkpoint", uuid, "json" | "parquet"] => {
@@ -177,6 +180,47 @@ impl<Location: AsUrl> ParsedLogPath<Location> {
     }
 }
 
+/// Format the filename of a json commit file for `version`, e.g. `00000000000000000010.json`
+#[internal_api]
+pub(crate) fn format_commit(version: Version) -> String {
+    format!("{version:020}.json")
+}
+
+/// Format the filename of a classic single-part parquet checkpoint file for `version`, e.g.
+/// `00000000000000000010.checkpoint.parquet`
+#[internal_api]
+pub(crate) fn format_classic_checkpoint(version: Version) -> String {
+    format!("{version:020}.checkpoint.parquet")
+}
+
+/// Format the filename of a UUID-named parquet checkpoint file for `version`, e.g.
+/// `00000000000000000010.checkpoint.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.parquet`
+#[internal_api]
+pub(crate) fn format_uuid_checkpoint(version: Version, uuid: &Uuid) -> String {
+    format!("{version:020}.checkpoint.{uuid}.parquet")
+}
+
+/// Format the filename of part `part` (1-based) of a `parts`-part checkpoint for `version`, e.g.
+/// `00000000000000000010.checkpoint.0000000001.0000000002.parquet`
+#[internal_api]
+#[allow(dead_code)] // kernel never writes multi-part checkpoints, this is for internal API users
+pub(crate) fn format_multipart(version: Version, part: u32, parts: u32) -> String {
+    format!("{version:020}.checkpoint.{part:010}.{parts:010}.parquet")
+}
+
+/// Format the filename of a log compaction file for the commits `start..=end`, e.g.
+/// `00000000000000000008.00000000000000000015.compacted.json`
+#[internal_api]
+pub(crate) fn format_log_compaction(start: Version, end: Version) -> String {
+    format!("{start:020}.{end:020}.compacted.json")
+}
+
+/// Format the filename of a CRC file for `version`, e.g. `00000000000000000010.crc`
+#[internal_api]
+pub(crate) fn format_crc(version: Version) -> String {
+    format!("{version:020}.crc")
+}
+
 impl ParsedLogPath<Url> {
     const DELTA_LOG_DIR: &'static str = "_delta_log/";
 
@@ -189,9 +233,9 @@ impl ParsedLogPath<Url> {
     }
 
     /// Create a new ParsedCommitPath<Url> for a new json commit file
+    #[internal_api]
     pub(crate) fn new_commit(table_root: &Url, version: Version) -> DeltaResult<Self> {
-        let filename = format!("{version:020}.json");
-        let path = Self::create_path(table_root, filename)?;
+        let path = Self::create_path(table_root, format_commit(version))?;
         if !path.is_commit() {
             return Err(Error::internal_error(
                 "ParsedLogPath::new_commit created a non-commit path",
@@ -201,13 +245,13 @@ impl ParsedLogPath<Url> {
     }
 
     /// Create a new ParsedCheckpointPath<Url> for a classic parquet checkpoint file
+    #[internal_api]
     #[allow(dead_code)] // TODO: Remove this once we have a use case for it
     pub(crate) fn new_classic_parquet_checkpoint(
         table_root: &Url,
         version: Version,
     ) -> DeltaResult<Self> {
-        let filename = format!("{version:020}.checkpoint.parquet");
-        let path = Self::create_path(table_root, filename)?;
+        let path = Self::create_path(table_root, format_classic_checkpoint(version))?;
         if !path.is_checkpoint() {
             return Err(Error::internal_error(
                 "ParsedLogPath::new_classic_parquet_checkpoint created a non-checkpoint path",
@@ -217,12 +261,13 @@ impl ParsedLogPath<Url> {
     }
 
     /// Create a new ParsedCheckpointPath<Url> for a UUID-based parquet checkpoint file
+    #[internal_api]
     #[allow(dead_code)] // TODO: Remove this once we have a use case for it
     pub(crate) fn new_uuid_parquet_checkpoint(
         table_root: &Url,
         version: Version,
     ) -> DeltaResult<Self> {
-        let filename = format!("{:020}.checkpoint.{}.parquet", version, Uuid::new_v4());
+        let filename = format_uuid_checkpoint(version, &Uuid::new_v4());
         let path = Self::create_path(table_root, filename)?;
         if !path.is_checkpoint() {
             return Err(Error::internal_error(
@@ -232,12 +277,26 @@ impl ParsedLogPath<Url> {
         Ok(path)
     }
 
-    // TODO: remove after support for writing CRC files
-    #[allow(unused)]
+    /// Create a new ParsedLogPath<Url> for a log compaction file of the commits `start..=end`
+    #[internal_api]
+    pub(crate) fn new_log_compaction(
+        table_root: &Url,
+        start: Version,
+        end: Version,
+    ) -> DeltaResult<Self> {
+        let path = Self::create_path(table_root, format_log_compaction(start, end))?;
+        if path.file_type != (LogPathFileType::CompactedCommit { hi: end }) {
+            return Err(Error::internal_error(
+                "ParsedLogPath::new_log_compaction created a non-compaction path",
+            ));
+        }
+        Ok(path)
+    }
+
     /// Create a new ParsedCommitPath<Url> for a new CRC file
+    #[internal_api]
     pub(crate) fn new_crc(table_root: &Url, version: Version) -> DeltaResult<Self> {
-        let filename = format!("{version:020}.crc");
-        let path = Self::create_path(table_root, filename)?;
+        let path = Self::create_path(table_root, format_crc(version))?;
         if path.file_type != LogPathFileType::Crc {
             return Err(Error::internal_error(
                 "ParsedLogPath::new_crc created a non-crc path",
@@ -350,6 +409,23 @@ mod tests {
         let log_path = ParsedLogPath::try_from(log_path).unwrap().unwrap();
         assert_eq!(log_path.version, 5);
         assert!(log_path.is_commit());
+
+        // compressed commits are only recognized with the `compressed-json` feature
+        for extension in ["gz", "zst"] {
+            let filename = format!("00000000000000000006.json.{extension}");
+            let log_path = table_log_dir.join(&filename).unwrap();
+            let log_path = ParsedLogPath::try_from(log_path).unwrap().unwrap();
+            assert_eq!(log_path.filename, filename);
+            assert_eq!(log_path.extension, extension);
+            assert_eq!(log_path.version, 6);
+            assert_eq!(log_path.is_commit(), cfg!(feature = "compressed-json"));
+            assert_eq!(log_path.is_unknown(), !cfg!(feature = "compressed-json"));
+        }
+
+        // unknown compression
+        let log_path = table_log_dir.join("00000000000000000006.json.bz2").unwrap();
+        let log_path = ParsedLogPath::try_from(log_path).unwrap().unwrap();
+        assert!(log_path.is_unknown());
     }
 
     #[test]
@@ -671,4 +747,42 @@ mod tests {
         ));
         assert_eq!(log_path.filename, "00000000000000000010.checkpoint.parquet");
     }
+
+    #[test]
+    fn test_format_log_paths() {
+        assert_eq!(format_commit(10), "00000000000000000010.json");
+        assert_eq!(
+            format_classic_checkpoint(10),
+            "00000000000000000010.checkpoint.parquet"
+        );
+        let uuid = Uuid::parse_str("3a0d65cd-4056-49b8-937b-95f9e3ee90e5").unwrap();
+        assert_eq!(
+            format_uuid_checkpoint(10, &uuid),
+            "00000000000000000010.checkpoint.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.parquet"
+        );
+        assert_eq!(
+            format_multipart(10, 1, 2),
+            "00000000000000000010.checkpoint.0000000001.0000000002.parquet"
+        );
+        assert_eq!(format_crc(10), "00000000000000000010.crc");
+        assert_eq!(
+            format_log_compaction(8, 15),
+            "00000000000000000008.00000000000000000015.compacted.json"
+        );
+    }
+
+    #[test]
+    fn test_new_log_compaction() {
+        let table_log_dir = table_log_dir_url();
+        let log_path = ParsedLogPath::new_log_compaction(&table_log_dir, 8, 15).unwrap();
+        assert_eq!(log_path.version, 8);
+        assert_eq!(
+            log_path.file_type,
+            LogPathFileType::CompactedCommit { hi: 15 }
+        );
+        assert_eq!(
+            log_path.filename,
+            "00000000000000000008.00000000000000000015.compacted.json"
+        );
+    }
 }
@@ -0,0 +1,125 @@
+//! Support for writing tables with [row tracking]. Every file added to such a table is assigned a
+//! `baseRowId`, the row id of its first row, and a `defaultRowCommitVersion`, the version of the
+//! commit that added it. Row ids are assigned consecutively from a high water mark (the highest
+//! row id assigned so far), which is stored in the `delta.rowTracking` domain metadata.
+//!
+//! [row tracking]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#row-tracking
+
+use serde::{Deserialize, Serialize};
+
+use crate::actions::DomainMetadata;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error};
+
+/// The domain of the domain metadata which holds the row id high water mark.
+pub(crate) const ROW_TRACKING_DOMAIN_NAME: &str = "delta.rowTracking";
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RowTrackingDomainMetadata {
+    row_id_high_water_mark: i64,
+}
+
+// The part of a file's statistics needed to assign row ids
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NumRecords {
+    num_records: i64,
+}
+
+/// Assigns row ids to the files added by a commit, starting after the row id high water mark of
+/// the table it commits to.
+#[derive(Debug)]
+pub(crate) struct RowIdAssigner {
+    high_water_mark: i64,
+    initial_high_water_mark: i64,
+}
+
+impl RowIdAssigner {
+    /// Create an assigner for a commit to `snapshot`, reading its row id high water mark (-1 if no
+    /// row ids were assigned yet).
+    pub(crate) fn try_new(snapshot: &Snapshot, engine: &dyn Engine) -> DeltaResult<Self> {
+        let high_water_mark =
+            match snapshot.get_domain_metadata_internal(ROW_TRACKING_DOMAIN_NAME, engine)? {
+                Some(configuration) => {
+                    serde_json::from_str::<RowTrackingDomainMetadata>(&configuration)?
+                        .row_id_high_water_mark
+                }
+                None => -1,
+            };
+        Ok(Self {
+            high_water_mark,
+            initial_high_water_mark: high_water_mark,
+        })
+    }
+
+    /// Assign row ids to the rows of the file at `path`, and return its base row id. The number of
+    /// rows is read from the `numRecords` of the file's statistics, which must be present.
+    pub(crate) fn assign(&mut self, path: &str, stats: Option<&str>) -> DeltaResult<i64> {
+        let num_records = stats
+            .and_then(|stats| serde_json::from_str::<NumRecords>(stats).ok())
+            .ok_or_else(|| {
+                Error::generic(format!(
+                    "Cannot assign row ids to file {path}: row tracking requires the numRecords \
+                    statistic of every added file"
+                ))
+            })?
+            .num_records;
+        let base_row_id = self.high_water_mark + 1;
+        self.high_water_mark += num_records;
+        Ok(base_row_id)
+    }
+
+    /// The domain metadata action which records the new row id high water mark, or `None` if no
+    /// row ids were assigned.
+    pub(crate) fn into_domain_metadata(self) -> DeltaResult<Option<DomainMetadata>> {
+        if self.high_water_mark == self.initial_high_water_mark {
+            return Ok(None);
+        }
+        let configuration = serde_json::to_string(&RowTrackingDomainMetadata {
+            row_id_high_water_mark: self.high_water_mark,
+        })?;
+        Ok(Some(DomainMetadata::new(
+            ROW_TRACKING_DOMAIN_NAME.to_string(),
+            configuration,
+            false,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_id_assigner() {
+        let mut assigner = RowIdAssigner {
+            high_water_mark: 9,
+            initial_high_water_mark: 9,
+        };
+        let stats =
+            |num_records: i64| format!(r#"{{"numRecords":{num_records},"minValues":{{}}}}"#);
+        assert_eq!(assigner.assign("a", Some(&stats(5))).unwrap(), 10);
+        assert_eq!(assigner.assign("b", Some(&stats(0))).unwrap(), 15);
+        assert_eq!(assigner.assign("c", Some(&stats(1))).unwrap(), 15);
+        assert!(assigner.assign("d", None).is_err());
+        assert!(assigner.assign("d", Some("{}")).is_err());
+
+        let domain_metadata = assigner.into_domain_metadata().unwrap().unwrap();
+        assert_eq!(
+            domain_metadata,
+            DomainMetadata::new(
+                ROW_TRACKING_DOMAIN_NAME.to_string(),
+                r#"{"rowIdHighWaterMark":15}"#.to_string(),
+                false
+            )
+        );
+
+        // the high water mark is only updated if row ids are assigned
+        let assigner = RowIdAssigner {
+            high_water_mark: -1,
+            initial_high_water_mark: -1,
+        };
+        assert_eq!(assigner.into_domain_metadata().unwrap(), None);
+    }
+}
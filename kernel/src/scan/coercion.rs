@@ -0,0 +1,297 @@
+//! Type coercions for engines whose type systems can't represent every Delta type. See
+//! [`ScanBuilder::with_type_coercions`].
+//!
+//! [`ScanBuilder::with_type_coercions`]: crate::scan::ScanBuilder::with_type_coercions
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::expressions::Expression;
+use crate::schema::{DataType, PrimitiveType, SchemaRef, SchemaTransform, StructField, StructType};
+use crate::{DeltaResult, Error};
+
+/// The number of significant decimal digits a double is guaranteed to round-trip.
+const DOUBLE_DECIMAL_DIGITS: u8 = 15;
+
+/// How a scan returns `decimal` columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecimalCoercion {
+    /// Return decimals as decimals.
+    #[default]
+    None,
+    /// Return decimals as their string representation, e.g. `"-12.30"` for `decimal(4, 2)`. This
+    /// coercion is lossless.
+    String,
+    /// Return decimals as doubles. This coercion is lossy for decimals with a precision above 15.
+    Double,
+}
+
+/// How a scan returns `timestamp` and `timestamp_ntz` columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampCoercion {
+    /// Return timestamps as timestamps.
+    #[default]
+    None,
+    /// Return timestamps as `long` microseconds since the Unix epoch. This coercion is lossless.
+    MicrosInt64,
+}
+
+/// Preferences for the types a scan returns, for engines with limited type systems. Columns of the
+/// affected types are converted when the scan transforms physical data to its logical form, and
+/// [`Scan::logical_schema`] reports the coerced types.
+///
+/// Building a scan fails if a coercion would lose information for a column of the scan, unless
+/// lossy coercions are explicitly allowed with [`Self::with_allow_lossy`].
+///
+/// [`Scan::logical_schema`]: crate::scan::Scan::logical_schema
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeCoercions {
+    decimals: DecimalCoercion,
+    timestamps: TimestampCoercion,
+    allow_lossy: bool,
+}
+
+impl TypeCoercions {
+    /// Set how `decimal` columns are returned.
+    pub fn with_decimals(mut self, decimals: DecimalCoercion) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Set how `timestamp` and `timestamp_ntz` columns are returned.
+    pub fn with_timestamps(mut self, timestamps: TimestampCoercion) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Set whether coercions that lose information are allowed. Defaults to `false`.
+    pub fn with_allow_lossy(mut self, allow_lossy: bool) -> Self {
+        self.allow_lossy = allow_lossy;
+        self
+    }
+
+    /// Apply the coercions to `schema`. Returns `None` if no field of `schema` is affected, or an
+    /// error if a coercion would be lossy and lossy coercions are not allowed.
+    pub(crate) fn coerce_schema(&self, schema: &StructType) -> DeltaResult<Option<SchemaRef>> {
+        let mut coerce = CoerceTypes {
+            coercions: self,
+            path: vec![],
+            lossy: vec![],
+        };
+        let coerced = coerce.transform_struct(schema);
+        if !coerce.lossy.is_empty() {
+            return Err(Error::unsupported(format!(
+                "Coercing column(s) {} would lose information, and lossy coercions are not allowed",
+                coerce.lossy.join(", ")
+            )));
+        }
+        match coerced {
+            Some(Cow::Owned(coerced))
+                if cfg!(all(
+                    feature = "arrow-expression",
+                    feature = "default-engine-base"
+                )) =>
+            {
+                Ok(Some(Arc::new(coerced)))
+            }
+            Some(Cow::Owned(_)) => Err(Error::unsupported(
+                "Type coercions require the default engine's expression evaluation",
+            )),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Wrap `expr`, which produces values of type `from`, in an explicit cast to the coerced type `to`.
+/// The scan uses this for exactly the transform fields whose types it coerces.
+#[cfg(all(feature = "arrow-expression", feature = "default-engine-base"))]
+pub(crate) fn coerce_expr(expr: Expression, from: &DataType, to: &DataType) -> Expression {
+    use crate::engine::arrow_expression::opaque::ArrowOpaqueExpression as _;
+
+    let op = arrow_coerce::CoerceOp {
+        from: from.clone(),
+        to: to.clone(),
+    };
+    Expression::arrow_opaque(op, [expr])
+}
+
+#[cfg(not(all(feature = "arrow-expression", feature = "default-engine-base")))]
+pub(crate) fn coerce_expr(expr: Expression, _from: &DataType, _to: &DataType) -> Expression {
+    // unreachable: `coerce_schema` rejects coercions without arrow expression evaluation
+    expr
+}
+
+#[cfg(all(feature = "arrow-expression", feature = "default-engine-base"))]
+mod arrow_coerce {
+    use crate::arrow::array::{Array as _, ArrayRef, AsArray as _, RecordBatch};
+    use crate::arrow::compute::cast;
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Float64Type, Int64Type};
+    use crate::engine::arrow_conversion::TryFromKernel as _;
+    use crate::engine::arrow_expression::evaluate_expression::evaluate_expression;
+    use crate::engine::arrow_expression::opaque::ArrowOpaqueExpressionOp;
+    use crate::expressions::{Expression, Scalar, ScalarExpressionEvaluator};
+    use crate::schema::DataType;
+    use crate::{DeltaResult, Error};
+
+    /// Casts the values of its only argument from their Delta type to the type they are coerced
+    /// to. Only the coercions of [`super::TypeCoercions`] are expected, but any cast arrow supports
+    /// will succeed.
+    #[derive(Debug, PartialEq)]
+    pub(super) struct CoerceOp {
+        pub(super) from: DataType,
+        pub(super) to: DataType,
+    }
+
+    impl CoerceOp {
+        fn arg<'a>(&self, args: &'a [Expression]) -> DeltaResult<&'a Expression> {
+            match args {
+                [arg] => Ok(arg),
+                _ => Err(Error::generic(format!(
+                    "coerce expects exactly one argument, got {}",
+                    args.len()
+                ))),
+            }
+        }
+
+        fn cast(&self, array: &ArrayRef) -> DeltaResult<ArrayRef> {
+            Ok(cast(array, &ArrowDataType::try_from_kernel(&self.to)?)?)
+        }
+    }
+
+    impl ArrowOpaqueExpressionOp for CoerceOp {
+        fn name(&self) -> &str {
+            "coerce"
+        }
+
+        fn eval_expr(
+            &self,
+            args: &[Expression],
+            batch: &RecordBatch,
+            _result_type: Option<&DataType>,
+        ) -> DeltaResult<ArrayRef> {
+            let array = evaluate_expression(self.arg(args)?, batch, Some(&self.from))?;
+            self.cast(&array)
+        }
+
+        fn eval_expr_scalar(
+            &self,
+            eval_expr: &ScalarExpressionEvaluator<'_>,
+            exprs: &[Expression],
+        ) -> DeltaResult<Scalar> {
+            let Some(scalar) = eval_expr(self.arg(exprs)?) else {
+                return Err(Error::generic("Could not evaluate the argument of coerce"));
+            };
+            let array = self.cast(&scalar.to_array(1)?)?;
+            if array.is_null(0) {
+                return Ok(Scalar::Null(self.to.clone()));
+            }
+            Ok(match &self.to {
+                &DataType::STRING => array.as_string::<i32>().value(0).into(),
+                &DataType::DOUBLE => array.as_primitive::<Float64Type>().value(0).into(),
+                &DataType::LONG => array.as_primitive::<Int64Type>().value(0).into(),
+                to => {
+                    return Err(Error::unsupported(format!(
+                        "Cannot coerce scalar {scalar} to {to}"
+                    )))
+                }
+            })
+        }
+    }
+}
+
+struct CoerceTypes<'c> {
+    coercions: &'c TypeCoercions,
+    path: Vec<String>,
+    lossy: Vec<String>,
+}
+
+impl<'a> SchemaTransform<'a> for CoerceTypes<'_> {
+    fn transform_primitive(&mut self, ptype: &'a PrimitiveType) -> Option<Cow<'a, PrimitiveType>> {
+        let coerced = match (ptype, self.coercions.decimals, self.coercions.timestamps) {
+            (PrimitiveType::Decimal(_), DecimalCoercion::String, _) => PrimitiveType::String,
+            (PrimitiveType::Decimal(dtype), DecimalCoercion::Double, _) => {
+                if dtype.precision() > DOUBLE_DECIMAL_DIGITS && !self.coercions.allow_lossy {
+                    self.lossy.push(self.path.join("."));
+                }
+                PrimitiveType::Double
+            }
+            (
+                PrimitiveType::Timestamp | PrimitiveType::TimestampNtz,
+                _,
+                TimestampCoercion::MicrosInt64,
+            ) => PrimitiveType::Long,
+            _ => return Some(Cow::Borrowed(ptype)),
+        };
+        Some(Cow::Owned(coerced))
+    }
+
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        self.path.push(field.name.clone());
+        let field = self.recurse_into_struct_field(field);
+        self.path.pop();
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ArrayType, DataType, DecimalType};
+
+    #[test]
+    fn test_coerce_schema() {
+        let schema = StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("small", DecimalType::try_new(10, 2).unwrap()),
+            StructField::nullable(
+                "nested",
+                StructType::new([
+                    StructField::nullable("big", DecimalType::try_new(20, 4).unwrap()),
+                    StructField::nullable("ts", DataType::TIMESTAMP),
+                ]),
+            ),
+            StructField::nullable("ntzs", ArrayType::new(DataType::TIMESTAMP_NTZ, true)),
+        ]);
+
+        // no coercions, or coercions that don't apply, leave the schema unchanged
+        assert_eq!(
+            TypeCoercions::default().coerce_schema(&schema).unwrap(),
+            None
+        );
+        let ids = StructType::new([StructField::nullable("id", DataType::LONG)]);
+        let all = TypeCoercions::default()
+            .with_decimals(DecimalCoercion::String)
+            .with_timestamps(TimestampCoercion::MicrosInt64);
+        assert_eq!(all.coerce_schema(&ids).unwrap(), None);
+
+        let expected = StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("small", DataType::STRING),
+            StructField::nullable(
+                "nested",
+                StructType::new([
+                    StructField::nullable("big", DataType::STRING),
+                    StructField::nullable("ts", DataType::LONG),
+                ]),
+            ),
+            StructField::nullable("ntzs", ArrayType::new(DataType::LONG, true)),
+        ]);
+        let coerced = all.coerce_schema(&schema).unwrap().unwrap();
+        assert_eq!(coerced.as_ref(), &expected);
+
+        // decimal(20, 4) doesn't fit in a double
+        let doubles = TypeCoercions::default().with_decimals(DecimalCoercion::Double);
+        let err = doubles.coerce_schema(&schema).unwrap_err().to_string();
+        assert!(err.contains("nested.big"), "{err}");
+        assert!(!err.contains("small"), "{err}");
+        let coerced = doubles
+            .with_allow_lossy(true)
+            .coerce_schema(&schema)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            coerced.field("small").unwrap().data_type(),
+            &DataType::DOUBLE
+        );
+    }
+}
@@ -38,7 +38,6 @@ mod tests;
 ///   are not eligible for data skipping.
 /// - `OR` is rewritten only if all operands are eligible for data skipping. Otherwise, the whole OR
 ///   predicate is dropped.
-#[cfg(test)]
 pub(crate) fn as_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
     DataSkippingPredicateCreator.eval(pred)
 }
@@ -68,6 +67,22 @@ impl DataSkippingFilter {
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
     ) -> Option<Self> {
         static STATS_EXPR: LazyLock<Expr> = LazyLock::new(|| column_expr!("add.stats"));
+        Self::new_with_stats_column(
+            engine,
+            physical_predicate,
+            get_log_add_schema().clone(),
+            STATS_EXPR.clone(),
+        )
+    }
+
+    /// Like [`DataSkippingFilter::new`], but filters batches of the given schema, whose stats are
+    /// selected by `stats_expr` (instead of batches of actions, whose stats are `add.stats`).
+    pub(crate) fn new_with_stats_column(
+        engine: &dyn Engine,
+        physical_predicate: Option<(PredicateRef, SchemaRef)>,
+        input_schema: SchemaRef,
+        stats_expr: Expr,
+    ) -> Option<Self> {
         static FILTER_PRED: LazyLock<Pred> =
             LazyLock::new(|| column_expr!("output").distinct(Expr::literal(false)));
 
@@ -94,6 +109,18 @@ impl DataSkippingFilter {
                 };
                 Some(field)
             }
+
+            // JSON has no binary representation (and Delta never writes min/max stats for binary
+            // columns), so read any binary stats as strings. They are never used for skipping.
+            fn transform_primitive(
+                &mut self,
+                ptype: &'a PrimitiveType,
+            ) -> Option<Cow<'a, PrimitiveType>> {
+                match ptype {
+                    PrimitiveType::Binary => Some(Cow::Owned(PrimitiveType::String)),
+                    _ => Some(Cow::Borrowed(ptype)),
+                }
+            }
         }
 
         // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
@@ -123,7 +150,7 @@ impl DataSkippingFilter {
 
         // Skipping happens in several steps:
         //
-        // 1. The stats selector fetches add.stats from the metadata
+        // 1. The stats selector fetches the stats (usually add.stats) from the metadata
         //
         // 2. The predicate (skipping evaluator) produces false for any file whose stats prove we
         //    can safely skip it. A value of true means the stats say we must keep the file, and
@@ -133,9 +160,8 @@ impl DataSkippingFilter {
         // 3. The selection evaluator does DISTINCT(col(predicate), 'false') to produce true (= keep) when
         //    the predicate is true/null and false (= skip) when the predicate is false.
         let select_stats_evaluator = engine.evaluation_handler().new_expression_evaluator(
-            // safety: kernel is very broken if we don't have the schema for Add actions
-            get_log_add_schema().clone(),
-            STATS_EXPR.clone(),
+            input_schema,
+            stats_expr,
             DataType::STRING,
         );
 
@@ -160,17 +186,8 @@ impl DataSkippingFilter {
     /// Apply the DataSkippingFilter to an EngineData batch of actions. Returns a selection vector
     /// which can be applied to the actions to find those that passed data skipping.
     pub(crate) fn apply(&self, actions: &dyn EngineData) -> DeltaResult<Vec<bool>> {
-        // retrieve and parse stats from actions data
-        let stats = self.select_stats_evaluator.evaluate(actions)?;
-        assert_eq!(stats.len(), actions.len());
-        let parsed_stats = self
-            .json_handler
-            .parse_json(stats, self.stats_schema.clone())?;
-        assert_eq!(parsed_stats.len(), actions.len());
-
         // evaluate the predicate on the parsed stats, then convert to selection vector
-        let skipping_predicate = self.skipping_evaluator.evaluate(&*parsed_stats)?;
-        assert_eq!(skipping_predicate.len(), actions.len());
+        let skipping_predicate = self.evaluate_skipping_predicate(actions)?;
         let selection_vector = self
             .filter_evaluator
             .evaluate(skipping_predicate.as_ref())?;
@@ -188,6 +205,27 @@ impl DataSkippingFilter {
         //     filtered_actions.num_rows()
         // );
     }
+
+    /// Evaluates the skipping predicate on the stats of each row of `actions`. The result has a
+    /// single boolean `output` column, which is false for rows whose stats prove they can be
+    /// skipped, true for rows whose stats say they must be kept, and null for rows whose stats are
+    /// missing.
+    pub(crate) fn evaluate_skipping_predicate(
+        &self,
+        actions: &dyn EngineData,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        // retrieve and parse stats from actions data
+        let stats = self.select_stats_evaluator.evaluate(actions)?;
+        assert_eq!(stats.len(), actions.len());
+        let parsed_stats = self
+            .json_handler
+            .parse_json_stats(stats, self.stats_schema.clone())?;
+        assert_eq!(parsed_stats.len(), actions.len());
+
+        let skipping_predicate = self.skipping_evaluator.evaluate(&*parsed_stats)?;
+        assert_eq!(skipping_predicate.len(), actions.len());
+        Ok(skipping_predicate)
+    }
 }
 
 struct DataSkippingPredicateCreator;
@@ -197,8 +235,12 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
     type ColumnStat = Expr;
 
     /// Retrieves the minimum value of a column, if it exists and has the requested type.
-    fn get_min_stat(&self, col: &ColumnName, _data_type: &DataType) -> Option<Expr> {
-        Some(joined_column_expr!("minValues", col))
+    // NOTE: Delta does not collect min/max stats for binary columns.
+    fn get_min_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Expr> {
+        match data_type {
+            &DataType::BINARY => None,
+            _ => Some(joined_column_expr!("minValues", col)),
+        }
     }
 
     /// Retrieves the maximum value of a column, if it exists and has the requested type.
@@ -206,7 +248,7 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
     // they are truncated to milliseconds in add.stats.
     fn get_max_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Expr> {
         match data_type {
-            &DataType::TIMESTAMP | &DataType::TIMESTAMP_NTZ => None,
+            &DataType::TIMESTAMP | &DataType::TIMESTAMP_NTZ | &DataType::BINARY => None,
             _ => Some(joined_column_expr!("maxValues", col)),
         }
     }
@@ -221,6 +263,8 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
         Some(column_expr!("numRecords"))
     }
 
+    /// Comparisons against a NaN literal are never eligible for data skipping: writers disagree on
+    /// whether NaN participates in min/max stats, so the stats cannot rule out NaN values.
     fn eval_partial_cmp(
         &self,
         ord: Ordering,
@@ -228,6 +272,11 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
         val: &Scalar,
         inverted: bool,
     ) -> Option<Pred> {
+        match val {
+            Scalar::Float(v) if v.is_nan() => return None,
+            Scalar::Double(v) if v.is_nan() => return None,
+            _ => {}
+        }
         let pred_fn = match (ord, inverted) {
             (Ordering::Less, false) => Pred::lt,
             (Ordering::Less, true) => Pred::ge,
@@ -106,6 +106,71 @@ fn test_eval_binary_comparisons() {
     do_test(five, fifteen, &[TRUE, TRUE, TRUE, TRUE, TRUE, TRUE]);
 }
 
+#[test]
+fn test_eval_string_prefix_predicates() {
+    let col = &column_expr!("x");
+    let predicates = [
+        Pred::starts_with(col.clone(), Expr::literal("ab")),
+        Pred::not(Pred::starts_with(col.clone(), Expr::literal("ab"))),
+        Pred::like(col.clone(), Expr::literal("ab%")),
+        Pred::not(Pred::like(col.clone(), Expr::literal("ab%"))),
+        Pred::like(col.clone(), Expr::literal("ab_c")),
+        Pred::not(Pred::like(col.clone(), Expr::literal("ab_c"))),
+        Pred::like(col.clone(), Expr::literal("ab")),
+    ];
+
+    let do_test = |min: &Scalar, max: &Scalar, expected: &[Option<bool>]| {
+        let resolver = HashMap::from_iter([
+            (column_name!("minValues.x"), min.clone()),
+            (column_name!("maxValues.x"), max.clone()),
+        ]);
+        let filter = DefaultKernelPredicateEvaluator::from(resolver);
+        for (pred, expect) in predicates.iter().zip(expected.iter()) {
+            // A predicate that cannot skip has no data skipping predicate
+            let skipping_pred = as_data_skipping_predicate(pred);
+            let result = skipping_pred.as_ref().and_then(|pred| filter.eval(pred));
+            expect_eq!(
+                result,
+                *expect,
+                "{pred:#?} became {skipping_pred:#?} with [{min}..{max}]"
+            );
+        }
+    };
+    let string = |value: &str| Scalar::from(value);
+    let null = &Scalar::Null(DataType::STRING);
+
+    // All values are before the prefix
+    do_test(
+        &string("aa"),
+        &string("aaz"),
+        &[FALSE, TRUE, FALSE, TRUE, FALSE, NULL, FALSE],
+    );
+    // All values start with the prefix
+    do_test(
+        &string("ab"),
+        &string("abz"),
+        &[TRUE, FALSE, TRUE, FALSE, TRUE, NULL, TRUE],
+    );
+    // Some values start with the prefix
+    do_test(
+        &string("abc"),
+        &string("b"),
+        &[TRUE, TRUE, TRUE, TRUE, TRUE, NULL, FALSE],
+    );
+    // All values are after the prefix
+    do_test(
+        &string("ac"),
+        &string("b"),
+        &[FALSE, TRUE, FALSE, TRUE, FALSE, NULL, FALSE],
+    );
+    // Missing stats prevent skipping
+    do_test(
+        null,
+        &string("abz"),
+        &[NULL, NULL, NULL, NULL, NULL, NULL, NULL],
+    );
+}
+
 #[test]
 fn test_eval_junction() {
     let test_cases = &[
@@ -406,3 +471,129 @@ fn test_timestamp_predicates_dont_data_skip() {
         );
     }
 }
+
+/// Parses a predicate from the conformance case format: comparisons and null checks name a
+/// top-level `column` (and comparisons a `value` typed by that column), while `not`, `and`, and
+/// `or` nest further predicates.
+fn parse_conformance_predicate(value: &serde_json::Value, schema: &StructType) -> Pred {
+    let op = value["op"].as_str().expect("predicate must have an op");
+    let column = || {
+        let name = value["column"]
+            .as_str()
+            .expect("predicate must have a column");
+        (
+            schema.field(name).expect("unknown column"),
+            Expr::column([name]),
+        )
+    };
+    let literal = |field: &StructField| {
+        let DataType::Primitive(ptype) = field.data_type() else {
+            panic!("only primitive columns are supported");
+        };
+        match &value["value"] {
+            serde_json::Value::Null => Scalar::Null(field.data_type().clone()),
+            serde_json::Value::String(raw) => ptype.parse_scalar(raw).unwrap(),
+            raw => ptype.parse_scalar(&raw.to_string()).unwrap(),
+        }
+    };
+    let children = || {
+        value["predicates"]
+            .as_array()
+            .expect("junction must have predicates")
+            .iter()
+            .map(|child| parse_conformance_predicate(child, schema))
+    };
+    match op {
+        "lt" | "le" | "eq" | "ne" | "gt" | "ge" => {
+            let (field, col) = column();
+            let cmp = match op {
+                "lt" => Pred::lt,
+                "le" => Pred::le,
+                "eq" => Pred::eq,
+                "ne" => Pred::ne,
+                "gt" => Pred::gt,
+                _ => Pred::ge,
+            };
+            cmp(col, literal(field))
+        }
+        "is_null" => Pred::is_null(column().1),
+        "is_not_null" => Pred::is_not_null(column().1),
+        "not" => Pred::not(parse_conformance_predicate(&value["predicate"], schema)),
+        "and" => Pred::and_from(children()),
+        "or" => Pred::or_from(children()),
+        _ => panic!("unsupported predicate op: {op}"),
+    }
+}
+
+/// Runs the shared conformance cases in `tests/data/data-skipping-conformance.json`, which other
+/// engines can also use to check that they evaluate data skipping predicates the same way kernel
+/// does.
+#[test]
+fn test_data_skipping_conformance() {
+    use crate::arrow::array::StringArray;
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::parse_json_batch;
+
+    let suite: serde_json::Value = serde_json::from_str(include_str!(
+        "../../../tests/data/data-skipping-conformance.json"
+    ))
+    .unwrap();
+    let schema: SchemaRef = Arc::new(serde_json::from_value(suite["schema"].clone()).unwrap());
+
+    let files = suite["files"].as_array().unwrap();
+    let names: Vec<_> = files.iter().map(|f| f["name"].as_str().unwrap()).collect();
+    let adds: Vec<_> = files
+        .iter()
+        .map(|file| {
+            serde_json::json!({
+                "add": {
+                    "path": file["name"],
+                    "partitionValues": {},
+                    "size": 1,
+                    "modificationTime": 0,
+                    "dataChange": true,
+                    "stats": file["stats"],
+                }
+            })
+            .to_string()
+        })
+        .collect();
+    let actions = parse_json_batch(StringArray::from(adds));
+
+    let engine = SyncEngine::new();
+    let mut failures = vec![];
+    for case in suite["cases"].as_array().unwrap() {
+        let name = case["name"].as_str().unwrap();
+        let pred = parse_conformance_predicate(&case["predicate"], &schema);
+        let skip: Vec<_> = case["skip"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f.as_str().unwrap())
+            .collect();
+        for file in &skip {
+            assert!(names.contains(file), "case {name}: unknown file {file}");
+        }
+
+        let selection = match DataSkippingFilter::new(&engine, Some((pred.into(), schema.clone())))
+        {
+            Some(filter) => filter.apply(actions.as_ref()).unwrap(),
+            None => vec![true; names.len()],
+        };
+        let skipped: Vec<_> = names
+            .iter()
+            .zip(selection)
+            .filter_map(|(file, keep)| (!keep).then_some(*file))
+            .collect();
+        if skipped != skip {
+            failures.push(format!(
+                "{name}: expected {skip:?} skipped, got {skipped:?}"
+            ));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "conformance failures:\n{}",
+        failures.join("\n")
+    );
+}
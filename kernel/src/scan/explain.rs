@@ -0,0 +1,477 @@
+//! Explanations of how a scan's predicate skips files. See [`Scan::explain_skipping`].
+//!
+//! [`Scan::explain_skipping`]: crate::scan::Scan::explain_skipping
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use super::data_skipping::{as_data_skipping_predicate, DataSkippingFilter};
+use super::log_replay::SCAN_ROW_SCHEMA;
+use super::{parse_partition_value, PhysicalPredicate, Scan};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{
+    column_expr, column_name, ColumnName, Expression, JunctionPredicate, JunctionPredicateOp,
+    Predicate, PredicateRef, Scalar,
+};
+use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
+use crate::schema::{ColumnNamesAndTypes, DataType, SchemaRef, StructType};
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error};
+
+/// How a conjunct of a scan's predicate is used to skip files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConjunctSkipping {
+    /// The conjunct only references partition columns, and is evaluated against each file's
+    /// partition values.
+    Partition,
+    /// The conjunct is evaluated against each file's statistics (min/max values and null counts).
+    Stats,
+    /// The conjunct cannot be used to skip files, for the given reason.
+    Unsupported(String),
+}
+
+/// One conjunct of a scan's predicate (in terms of physical column names), and how it is used to
+/// skip files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConjunctExplanation {
+    /// The conjunct, in terms of physical column names.
+    pub predicate: Predicate,
+    /// How the conjunct is used to skip files.
+    pub skipping: ConjunctSkipping,
+}
+
+/// The outcome of evaluating one conjunct against one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConjunctOutcome {
+    /// The file may contain rows matching the conjunct, so it is kept.
+    Kept,
+    /// The file cannot contain rows matching the conjunct, so it is skipped.
+    Skipped,
+    /// The file lacks statistics the conjunct needs (e.g. the file has no stats at all, or none for
+    /// a referenced column), so it is kept.
+    MissingStats,
+    /// The conjunct could not be evaluated against the file's partition values, e.g. because of a
+    /// null partition value, so it is kept.
+    Unknown,
+    /// The conjunct is not used for skipping (see [`ConjunctSkipping::Unsupported`]).
+    NotApplied,
+}
+
+/// How a scan's predicate applies to one file of the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSkippingExplanation {
+    /// The path of the file, relative to the table root.
+    pub path: String,
+    /// The outcome of each conjunct of the predicate, in the same order as
+    /// [`SkippingExplanation::conjuncts`].
+    pub outcomes: Vec<ConjunctOutcome>,
+}
+
+impl FileSkippingExplanation {
+    /// Whether the scan skips this file, i.e. whether any conjunct skipped it.
+    pub fn is_skipped(&self) -> bool {
+        self.outcomes.contains(&ConjunctOutcome::Skipped)
+    }
+}
+
+/// An explanation of how a scan's predicate skips (or fails to skip) the files of a table, meant
+/// to help debug predicates which don't prune as many files as expected. Files excluded by a file
+/// filter or sampler are not taken into account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippingExplanation {
+    /// True if the predicate is statically false, in which case every file is skipped without
+    /// consulting partition values or statistics, and no conjuncts or files are listed.
+    pub skips_all_files: bool,
+    /// The conjuncts of the scan's predicate. Empty if the scan has no predicate.
+    pub conjuncts: Vec<ConjunctExplanation>,
+    /// One entry per file of the table.
+    pub files: Vec<FileSkippingExplanation>,
+}
+
+impl SkippingExplanation {
+    /// The number of files skipped by the predicate.
+    pub fn num_skipped_files(&self) -> usize {
+        self.files.iter().filter(|file| file.is_skipped()).count()
+    }
+
+    /// The number of files which had the given outcome for the conjunct at `conjunct_idx`.
+    pub fn count_outcomes(&self, conjunct_idx: usize, outcome: ConjunctOutcome) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.outcomes.get(conjunct_idx) == Some(&outcome))
+            .count()
+    }
+
+    pub(crate) fn try_new(scan: &Scan, engine: &dyn Engine) -> DeltaResult<Self> {
+        let (predicate, referenced_schema) = match &scan.physical_predicate {
+            PhysicalPredicate::Some(predicate, schema) => (predicate.clone(), schema.clone()),
+            PhysicalPredicate::StaticSkipAll => {
+                return Ok(Self {
+                    skips_all_files: true,
+                    conjuncts: vec![],
+                    files: vec![],
+                })
+            }
+            PhysicalPredicate::None => (
+                PredicateRef::new(Predicate::and_from([])),
+                Arc::new(StructType::new([])),
+            ),
+        };
+
+        // partition columns by physical name, with their types
+        let schema = scan.snapshot.schema();
+        let partition_columns: HashMap<ColumnName, DataType> = scan
+            .snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .filter_map(|name| schema.field(name))
+            .map(|field| {
+                let name = ColumnName::new([field.physical_name()]);
+                (name, field.data_type().clone())
+            })
+            .collect();
+
+        let mut conjuncts = vec![];
+        split_conjuncts(&predicate, &mut conjuncts);
+        let (conjuncts, stats_filters): (Vec<_>, Vec<_>) = conjuncts
+            .into_iter()
+            .map(|predicate| {
+                let (skipping, stats_filter) =
+                    classify(engine, predicate, &referenced_schema, &partition_columns);
+                let conjunct = ConjunctExplanation {
+                    predicate: predicate.clone(),
+                    skipping,
+                };
+                (conjunct, stats_filter)
+            })
+            .unzip();
+
+        // list every file of the table, without any skipping
+        let unfiltered = scan.snapshot.clone().scan_builder().build()?;
+        let mut visitor = ExplainVisitor {
+            conjuncts: &conjuncts,
+            partition_columns: &partition_columns,
+            selection_vector: vec![],
+            stats_results: vec![],
+            files: vec![],
+        };
+        for scan_metadata in unfiltered.scan_metadata(engine)? {
+            let scan_files = scan_metadata?.scan_files;
+            let data = scan_files.data.as_ref();
+            visitor.stats_results = stats_filters
+                .iter()
+                .map(|filter| match filter {
+                    Some(filter) => {
+                        let results = filter.evaluate_skipping_predicate(data)?;
+                        let mut results_visitor = SkippingResultVisitor::default();
+                        results_visitor.visit_rows_of(results.as_ref())?;
+                        Ok(results_visitor.results)
+                    }
+                    None => Ok(vec![]),
+                })
+                .collect::<DeltaResult<_>>()?;
+            visitor.selection_vector = scan_files.selection_vector;
+            visitor.visit_rows_of(data)?;
+        }
+        let files = visitor.files;
+
+        Ok(Self {
+            skips_all_files: false,
+            conjuncts,
+            files,
+        })
+    }
+}
+
+// Flatten (nested) ANDs into their conjuncts.
+pub(super) fn split_conjuncts<'p>(predicate: &'p Predicate, conjuncts: &mut Vec<&'p Predicate>) {
+    match predicate {
+        Predicate::Junction(JunctionPredicate {
+            op: JunctionPredicateOp::And,
+            preds,
+        }) => preds
+            .iter()
+            .for_each(|pred| split_conjuncts(pred, conjuncts)),
+        _ => conjuncts.push(predicate),
+    }
+}
+
+// Classifies a conjunct, returning the data skipping filter which evaluates it against the stats
+// of scan files if it is a `Stats` conjunct. This is the same filter scans use to skip files.
+fn classify(
+    engine: &dyn Engine,
+    predicate: &Predicate,
+    referenced_schema: &SchemaRef,
+    partition_columns: &HashMap<ColumnName, DataType>,
+) -> (ConjunctSkipping, Option<DataSkippingFilter>) {
+    static STATS_EXPR: LazyLock<Expression> = LazyLock::new(|| column_expr!("stats"));
+
+    let references = predicate.references();
+    if !references.is_empty()
+        && references
+            .iter()
+            .all(|col| partition_columns.contains_key(*col))
+    {
+        return (ConjunctSkipping::Partition, None);
+    }
+    let unsupported = || {
+        let reason = "the predicate cannot be expressed in terms of file statistics";
+        (ConjunctSkipping::Unsupported(reason.to_string()), None)
+    };
+    if as_data_skipping_predicate(predicate).is_none() {
+        return unsupported();
+    }
+    let stats_filter = DataSkippingFilter::new_with_stats_column(
+        engine,
+        Some((Arc::new(predicate.clone()), referenced_schema.clone())),
+        SCAN_ROW_SCHEMA.clone(),
+        STATS_EXPR.clone(),
+    );
+    match stats_filter {
+        Some(stats_filter) => (ConjunctSkipping::Stats, Some(stats_filter)),
+        None => unsupported(),
+    }
+}
+
+// Outcome of an evaluation which returned `Some(false)` iff the file can be skipped, and `None` if
+// the evaluation failed for the given reason.
+fn outcome(result: Option<bool>, unknown: ConjunctOutcome) -> ConjunctOutcome {
+    match result {
+        Some(false) => ConjunctOutcome::Skipped,
+        Some(true) => ConjunctOutcome::Kept,
+        None => unknown,
+    }
+}
+
+struct ExplainVisitor<'a> {
+    conjuncts: &'a [ConjunctExplanation],
+    partition_columns: &'a HashMap<ColumnName, DataType>,
+    selection_vector: Vec<bool>,
+    // The result of each conjunct's data skipping filter for each row of the current batch. Empty
+    // for conjuncts which are not evaluated against stats.
+    stats_results: Vec<Vec<Option<bool>>>,
+    files: Vec<FileSkippingExplanation>,
+}
+
+impl ExplainVisitor<'_> {
+    fn explain_file(
+        &self,
+        row_index: usize,
+        path: String,
+        partition_values: HashMap<String, String>,
+    ) -> DeltaResult<FileSkippingExplanation> {
+        let partition_values: HashMap<ColumnName, Scalar> = self
+            .partition_columns
+            .iter()
+            .map(|(name, data_type)| {
+                let raw = partition_values.get(name.path()[0].as_str());
+                Ok((name.clone(), parse_partition_value(raw, data_type)?))
+            })
+            .collect::<DeltaResult<_>>()?;
+        let partition_evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
+
+        let outcomes = self
+            .conjuncts
+            .iter()
+            .zip(&self.stats_results)
+            .map(|(conjunct, stats_results)| match &conjunct.skipping {
+                ConjunctSkipping::Partition => outcome(
+                    partition_evaluator.eval_sql_where(&conjunct.predicate),
+                    ConjunctOutcome::Unknown,
+                ),
+                // the data skipping predicate is null iff stats it needs are missing
+                ConjunctSkipping::Stats => outcome(
+                    stats_results.get(row_index).copied().flatten(),
+                    ConjunctOutcome::MissingStats,
+                ),
+                ConjunctSkipping::Unsupported(_) => ConjunctOutcome::NotApplied,
+            })
+            .collect();
+        Ok(FileSkippingExplanation { path, outcomes })
+    }
+}
+
+impl RowVisitor for ExplainVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| SCAN_ROW_SCHEMA.leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 13,
+            Error::InternalError(format!(
+                "Wrong number of ExplainVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for row_index in 0..row_count {
+            // a selection vector shorter than the batch selects the remaining rows
+            if !self
+                .selection_vector
+                .get(row_index)
+                .copied()
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            let Some(path) = getters[0].get_opt(row_index, "scanFile.path")? else {
+                continue;
+            };
+            let partition_values =
+                getters[9].get(row_index, "scanFile.fileConstantValues.partitionValues")?;
+            let file = self.explain_file(row_index, path, partition_values)?;
+            self.files.push(file);
+        }
+        Ok(())
+    }
+}
+
+// Collects the (nullable) results of a data skipping predicate.
+#[derive(Default)]
+struct SkippingResultVisitor {
+    results: Vec<Option<bool>>,
+}
+
+impl RowVisitor for SkippingResultVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("output")], vec![DataType::BOOLEAN]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of SkippingResultVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.results.push(getters[0].get_opt(i, "skipping.output")?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+    use crate::Snapshot;
+
+    use super::*;
+    use ConjunctOutcome::*;
+
+    fn basic_partitioned() -> (Arc<Snapshot>, SyncEngine) {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+        (snapshot, engine)
+    }
+
+    #[test]
+    fn test_explain_skipping() {
+        let (snapshot, engine) = basic_partitioned();
+        let predicate = Pred::and_from([
+            Pred::eq(column_expr!("letter"), Expr::literal("a")),
+            Pred::gt(column_expr!("number"), Expr::literal(3i64)),
+            Pred::gt(
+                column_expr!("number") + Expr::literal(1i64),
+                Expr::literal(4i64),
+            ),
+            Pred::or(
+                Pred::eq(column_expr!("letter"), Expr::literal("a")),
+                Pred::eq(column_expr!("number"), Expr::literal(1i64)),
+            ),
+        ]);
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .unwrap();
+        let mut explanation = scan.explain_skipping(&engine).unwrap();
+        assert!(!explanation.skips_all_files);
+
+        let skipping: Vec<_> = explanation
+            .conjuncts
+            .iter()
+            .map(|conjunct| conjunct.skipping.clone())
+            .collect();
+        assert!(matches!(
+            skipping.as_slice(),
+            [
+                ConjunctSkipping::Partition,
+                ConjunctSkipping::Stats,
+                ConjunctSkipping::Unsupported(_),
+                // references a data column, so it can't be evaluated on partition values alone
+                ConjunctSkipping::Stats,
+            ]
+        ));
+
+        explanation.files.sort_by(|a, b| a.path.cmp(&b.path));
+        let outcomes: Vec<_> = explanation
+            .files
+            .iter()
+            .map(|file| {
+                let dir = file.path.split('/').next().unwrap();
+                (dir, file.outcomes.as_slice())
+            })
+            .collect();
+        // the files of letter=a have number 1 and 4, the others number 2, 3, 5 and 6. There are no
+        // stats for the partition column, so the disjunction is only known to be true for number 1
+        let expected: &[(&str, &[ConjunctOutcome])] = &[
+            (
+                "letter=__HIVE_DEFAULT_PARTITION__",
+                &[Skipped, Kept, NotApplied, MissingStats],
+            ),
+            ("letter=a", &[Kept, Kept, NotApplied, MissingStats]),
+            ("letter=a", &[Kept, Skipped, NotApplied, Kept]),
+            ("letter=b", &[Skipped, Skipped, NotApplied, MissingStats]),
+            ("letter=c", &[Skipped, Skipped, NotApplied, MissingStats]),
+            ("letter=e", &[Skipped, Kept, NotApplied, MissingStats]),
+        ];
+        assert_eq!(outcomes, expected);
+
+        assert_eq!(explanation.num_skipped_files(), 5);
+        assert_eq!(explanation.count_outcomes(0, Skipped), 4);
+        assert_eq!(explanation.count_outcomes(1, Skipped), 3);
+        assert_eq!(explanation.count_outcomes(2, NotApplied), 6);
+        assert_eq!(explanation.count_outcomes(3, MissingStats), 5);
+    }
+
+    #[test]
+    fn test_explain_skipping_without_predicate() {
+        let (snapshot, engine) = basic_partitioned();
+        let scan = snapshot.scan_builder().build().unwrap();
+        let explanation = scan.explain_skipping(&engine).unwrap();
+        assert!(!explanation.skips_all_files);
+        assert!(explanation.conjuncts.is_empty());
+        assert_eq!(explanation.files.len(), 6);
+        assert_eq!(explanation.num_skipped_files(), 0);
+    }
+
+    #[test]
+    fn test_explain_static_skipping() {
+        let (snapshot, engine) = basic_partitioned();
+        let predicate = Pred::and(
+            Pred::eq(column_expr!("letter"), Expr::literal("a")),
+            Pred::literal(false),
+        );
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .unwrap();
+        let explanation = scan.explain_skipping(&engine).unwrap();
+        assert!(explanation.skips_all_files);
+        assert!(explanation.files.is_empty());
+    }
+}
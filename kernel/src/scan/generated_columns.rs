@@ -0,0 +1,418 @@
+//! Partition filters derived from predicates on the source column of a generated partition column.
+//!
+//! Tables are often partitioned by a date generated from a timestamp, e.g. a column `event_date`
+//! with generation expression `CAST(event_time AS DATE)`. Queries filter on the timestamp, which
+//! can't prune partitions by itself. Since the date of a timestamp only grows with the timestamp,
+//! a range on `event_time` implies a range on `event_date`, which can.
+
+use std::str::FromStr as _;
+use std::sync::Arc;
+
+use super::explain::split_conjuncts;
+use crate::expressions::{
+    BinaryPredicate, BinaryPredicateOp, ColumnName, Expression, Predicate, PredicateRef, Scalar,
+};
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, Schema};
+
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+// A partition column holding the date of a timestamp source column
+struct DatePartition {
+    partition: ColumnName,
+    source: ColumnName,
+    // Timestamps are converted to dates in the writer's session time zone, which we don't know.
+    // Any time zone offset is less than a day, so the date is within a day of the UTC date.
+    slack_days: i32,
+}
+
+/// Conjoin `predicate` with the partition filters it implies for the generated date partition
+/// columns of `logical_schema`. Returns `predicate` unchanged if it implies none.
+///
+/// Only partition columns in `logical_schema` are considered, because partition pruning only
+/// applies to partition columns read by the scan.
+pub(crate) fn with_generated_partition_filters(
+    predicate: PredicateRef,
+    logical_schema: &Schema,
+    partition_columns: &[String],
+) -> PredicateRef {
+    let partitions = date_partitions(logical_schema, partition_columns);
+    if partitions.is_empty() {
+        return predicate;
+    }
+    let mut conjuncts = vec![];
+    split_conjuncts(&predicate, &mut conjuncts);
+    let derived: Vec<_> = conjuncts
+        .into_iter()
+        .filter_map(as_comparison)
+        .flat_map(|(column, op, value)| {
+            partitions
+                .iter()
+                .filter(move |partition| &partition.source == column)
+                .filter_map(move |partition| partition.derive_filter(op, value))
+        })
+        .collect();
+    if derived.is_empty() {
+        return predicate;
+    }
+    let predicate = Predicate::clone(&predicate);
+    Arc::new(Predicate::and_from(
+        std::iter::once(predicate).chain(derived),
+    ))
+}
+
+fn date_partitions(logical_schema: &Schema, partition_columns: &[String]) -> Vec<DatePartition> {
+    partition_columns
+        .iter()
+        .filter_map(|name| {
+            let field = logical_schema.field(name)?;
+            if field.data_type() != &DataType::DATE {
+                return None;
+            }
+            let MetadataValue::String(expression) = field
+                .metadata()
+                .get(ColumnMetadataKey::GenerationExpression.as_ref())?
+            else {
+                return None;
+            };
+            let source = parse_date_generation_expression(expression)?;
+            let source_type = resolve_type(logical_schema, &source)?;
+            let slack_days = if source_type == &DataType::TIMESTAMP {
+                1
+            } else if source_type == &DataType::TIMESTAMP_NTZ {
+                0
+            } else {
+                return None;
+            };
+            Some(DatePartition {
+                partition: ColumnName::new([name]),
+                source,
+                slack_days,
+            })
+        })
+        .collect()
+}
+
+fn resolve_type<'a>(schema: &'a Schema, column: &ColumnName) -> Option<&'a DataType> {
+    let (first, rest) = column.path().split_first()?;
+    let mut data_type = schema.field(first)?.data_type();
+    for name in rest {
+        let DataType::Struct(fields) = data_type else {
+            return None;
+        };
+        data_type = fields.field(name)?.data_type();
+    }
+    Some(data_type)
+}
+
+/// Parse a generation expression that takes the date of a column, returning the column. The
+/// supported forms are `CAST(col AS DATE)`, `to_date(col)` and `date(col)` (case insensitive).
+fn parse_date_generation_expression(expression: &str) -> Option<ColumnName> {
+    let (function, args) = expression.trim().split_once('(')?;
+    let args = args.trim_end().strip_suffix(')')?.trim();
+    let column = match function.trim().to_ascii_lowercase().as_str() {
+        "cast" => {
+            // ASCII lowercasing keeps byte offsets, so we can split `args` at offsets into `lower`
+            let lower = args.to_ascii_lowercase();
+            let split = lower.rfind(" as ")?;
+            let target = args[split + " as ".len()..].trim();
+            target
+                .eq_ignore_ascii_case("date")
+                .then_some(&args[..split])?
+        }
+        "to_date" | "date" => args,
+        _ => return None,
+    };
+    ColumnName::from_str(column.trim()).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl Comparison {
+    // The comparison with its operands swapped, e.g. `a < b` <=> `b > a`
+    fn flip(self) -> Self {
+        match self {
+            Self::Lt => Self::Gt,
+            Self::Le => Self::Ge,
+            Self::Gt => Self::Lt,
+            Self::Ge => Self::Le,
+            Self::Eq => Self::Eq,
+        }
+    }
+}
+
+// Match a `<column> <op> <literal>` comparison, in either operand order
+fn as_comparison(predicate: &Predicate) -> Option<(&ColumnName, Comparison, &Scalar)> {
+    let (op, left, right) = match predicate {
+        Predicate::Binary(BinaryPredicate { op, left, right }) => {
+            let op = match op {
+                BinaryPredicateOp::LessThan => Comparison::Lt,
+                BinaryPredicateOp::GreaterThan => Comparison::Gt,
+                BinaryPredicateOp::Equal => Comparison::Eq,
+                _ => return None,
+            };
+            (op, left, right)
+        }
+        Predicate::Not(inner) => match inner.as_ref() {
+            Predicate::Binary(BinaryPredicate { op, left, right }) => {
+                let op = match op {
+                    BinaryPredicateOp::LessThan => Comparison::Ge,
+                    BinaryPredicateOp::GreaterThan => Comparison::Le,
+                    _ => return None,
+                };
+                (op, left, right)
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match (left.as_ref(), right.as_ref()) {
+        (Expression::Column(column), Expression::Literal(value)) => Some((column, op, value)),
+        (Expression::Literal(value), Expression::Column(column)) => {
+            Some((column, op.flip(), value))
+        }
+        _ => None,
+    }
+}
+
+impl DatePartition {
+    fn derive_filter(&self, op: Comparison, value: &Scalar) -> Option<Predicate> {
+        let micros = match value {
+            Scalar::Timestamp(micros) | Scalar::TimestampNtz(micros) => *micros,
+            _ => return None,
+        };
+        let date = i32::try_from(micros.div_euclid(MICROS_PER_DAY)).ok()?;
+        let (min_date, max_date) = (
+            date.checked_sub(self.slack_days)?,
+            date.checked_add(self.slack_days)?,
+        );
+        let partition = || Expression::column(self.partition.clone());
+        let lower = || Predicate::ge(partition(), Scalar::Date(min_date));
+        let upper = || Predicate::le(partition(), Scalar::Date(max_date));
+        let filter = match op {
+            Comparison::Lt | Comparison::Le => upper(),
+            Comparison::Gt | Comparison::Ge => lower(),
+            Comparison::Eq => Predicate::and(lower(), upper()),
+        };
+        Some(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use itertools::Itertools as _;
+    use serde_json::json;
+    use test_utils::add_commit;
+    use url::Url;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::expressions::{column_expr, column_name};
+    use crate::object_store::memory::InMemory;
+    use crate::scan::state::{DvInfo, Stats};
+    use crate::schema::{StructField, StructType};
+    use crate::{DeltaResult, ExpressionRef, Snapshot};
+
+    use super::*;
+
+    // 2024-01-04T00:00:00Z
+    const JAN_4_MICROS: i64 = 19726 * MICROS_PER_DAY;
+
+    fn generated_date_schema(source_type: DataType, expression: &str) -> StructType {
+        StructType::new([
+            StructField::nullable("event_time", source_type),
+            StructField::nullable("event_date", DataType::DATE)
+                .with_metadata([(ColumnMetadataKey::GenerationExpression.as_ref(), expression)]),
+        ])
+    }
+
+    #[test]
+    fn test_parse_date_generation_expression() {
+        let event_time = Some(column_name!("event_time"));
+        assert_eq!(
+            parse_date_generation_expression("CAST(event_time AS DATE)"),
+            event_time
+        );
+        assert_eq!(
+            parse_date_generation_expression(" cast( `event_time`  as date ) "),
+            event_time
+        );
+        assert_eq!(
+            parse_date_generation_expression("to_date(event_time)"),
+            event_time
+        );
+        assert_eq!(
+            parse_date_generation_expression("DATE(event_time)"),
+            event_time
+        );
+        assert_eq!(
+            parse_date_generation_expression("CAST(a.`b c` AS DATE)"),
+            Some(ColumnName::new(["a", "b c"]))
+        );
+        for unsupported in [
+            "CAST(event_time AS STRING)",
+            "year(event_time)",
+            "date_format(event_time, 'yyyy-MM-dd')",
+            "event_time",
+        ] {
+            assert_eq!(parse_date_generation_expression(unsupported), None);
+        }
+    }
+
+    #[test]
+    fn test_with_generated_partition_filters() {
+        let partition_columns = ["event_date".to_string()];
+        let filters = |schema: &StructType, predicate: Predicate| {
+            let predicate = Arc::new(predicate);
+            Predicate::clone(&with_generated_partition_filters(
+                predicate,
+                schema,
+                &partition_columns,
+            ))
+        };
+        let ntz = generated_date_schema(DataType::TIMESTAMP_NTZ, "CAST(event_time AS DATE)");
+        let event_time = || column_expr!("event_time");
+        let event_date = || column_expr!("event_date");
+
+        // ranges on the timestamp imply ranges on the date
+        let predicate = Predicate::and(
+            Predicate::ge(event_time(), Scalar::TimestampNtz(JAN_4_MICROS + 1)),
+            Predicate::lt(Scalar::TimestampNtz(JAN_4_MICROS - 1), event_time()),
+        );
+        let expected = Predicate::and_from([
+            predicate.clone(),
+            Predicate::ge(event_date(), Scalar::Date(19726)),
+            Predicate::ge(event_date(), Scalar::Date(19725)),
+        ]);
+        assert_eq!(filters(&ntz, predicate), expected);
+
+        let predicate = Predicate::eq(event_time(), Scalar::TimestampNtz(JAN_4_MICROS));
+        let expected = Predicate::and(
+            predicate.clone(),
+            Predicate::and(
+                Predicate::ge(event_date(), Scalar::Date(19726)),
+                Predicate::le(event_date(), Scalar::Date(19726)),
+            ),
+        );
+        assert_eq!(filters(&ntz, predicate), expected);
+
+        // the date of a timestamp depends on the writer's time zone, so allow a day either way
+        let tz = generated_date_schema(DataType::TIMESTAMP, "to_date(event_time)");
+        let predicate = Predicate::le(event_time(), Scalar::Timestamp(JAN_4_MICROS));
+        let expected = Predicate::and(
+            predicate.clone(),
+            Predicate::le(event_date(), Scalar::Date(19727)),
+        );
+        assert_eq!(filters(&tz, predicate), expected);
+
+        // predicates which imply nothing about the date are left as they are
+        for predicate in [
+            Predicate::distinct(event_time(), Scalar::Timestamp(JAN_4_MICROS)),
+            Predicate::gt(event_time(), column_expr!("other")),
+            Predicate::or(
+                Predicate::gt(event_time(), Scalar::Timestamp(JAN_4_MICROS)),
+                Predicate::lt(event_time(), Scalar::Timestamp(0)),
+            ),
+        ] {
+            assert_eq!(filters(&tz, predicate.clone()), predicate);
+        }
+
+        // without the generated partition column in the schema, there is nothing to prune
+        let predicate = Predicate::le(event_time(), Scalar::Timestamp(JAN_4_MICROS));
+        let schema = tz.project(&["event_time"]).unwrap();
+        assert_eq!(filters(&schema, predicate.clone()), predicate);
+    }
+
+    async fn scan_files(min_writer_version: i32, predicate: Predicate) -> DeltaResult<Vec<String>> {
+        let url = Url::parse("memory:///")?;
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let schema = generated_date_schema(DataType::TIMESTAMP, "CAST(event_time AS DATE)");
+        let mut commit = vec![
+            json!({
+                "protocol": {
+                    "minReaderVersion": 1,
+                    "minWriterVersion": min_writer_version
+                }
+            }),
+            json!({
+                "metaData": {
+                    "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": { "provider": "parquet", "options": {} },
+                    "schemaString": serde_json::to_string(&schema)?,
+                    "partitionColumns": ["event_date"],
+                    "configuration": {},
+                    "createdTime": 1677811175819u64
+                }
+            }),
+        ];
+        for day in 1..=5 {
+            let date = format!("2024-01-0{day}");
+            commit.push(json!({
+                "add": {
+                    "path": format!("event_date={date}/part-0.parquet"),
+                    "partitionValues": { "event_date": date },
+                    "size": 100,
+                    "modificationTime": 1677811175819u64,
+                    "dataChange": true
+                }
+            }));
+        }
+        let commit = commit.iter().map(|action| action.to_string()).join("\n");
+        add_commit(store.as_ref(), 0, commit).await.unwrap();
+
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()?;
+        fn collect_path(
+            files: &mut Vec<String>,
+            path: &str,
+            _: i64,
+            _: Option<Stats>,
+            _: DvInfo,
+            _: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            files.push(path.to_string());
+        }
+        let mut files = vec![];
+        for scan_metadata in scan.scan_metadata(&engine)? {
+            files = scan_metadata?.visit_scan_files(files, collect_path)?;
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    #[tokio::test]
+    async fn test_generated_partition_pruning() -> DeltaResult<()> {
+        let predicate = Predicate::ge(
+            column_expr!("event_time"),
+            Scalar::Timestamp(JAN_4_MICROS + 1),
+        );
+        // the generated columns feature guarantees the partition values, so we can prune
+        let files = scan_files(4, predicate.clone()).await?;
+        assert_eq!(
+            files,
+            [
+                "event_date=2024-01-03/part-0.parquet",
+                "event_date=2024-01-04/part-0.parquet",
+                "event_date=2024-01-05/part-0.parquet",
+            ]
+        );
+        // without the feature, generation expressions aren't enforced
+        let files = scan_files(2, predicate).await?;
+        assert_eq!(files.len(), 5);
+        Ok(())
+    }
+}
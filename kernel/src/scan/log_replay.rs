@@ -4,17 +4,22 @@ use std::sync::{Arc, LazyLock};
 
 use itertools::Itertools;
 
+use super::coercion::coerce_expr;
 use super::data_skipping::DataSkippingFilter;
-use super::{ScanMetadata, Transform};
+use super::metrics::{ScanMetrics, ScanMetricsHandle};
+use super::row_tracking::coalesce_expr;
+use super::sample::FileSampler;
+use super::{AddInfo, FileFilter, ScanMetadata, Transform};
 use crate::actions::deletion_vector::DeletionVectorDescriptor;
 use crate::actions::get_log_add_schema;
 use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
 use crate::expressions::{
-    column_expr, column_name, ColumnName, Expression, ExpressionRef, PredicateRef,
+    column_expr, column_name, BinaryExpressionOp, ColumnName, Expression, ExpressionRef,
+    PredicateRef,
 };
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
 use crate::log_replay::{ActionsBatch, FileActionDeduplicator, FileActionKey, LogReplayProcessor};
-use crate::scan::{Scalar, TransformExpr};
+use crate::scan::{Scalar, TransformExpr, ROW_INDEX_COLUMN_NAME};
 use crate::schema::ToSchema as _;
 use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType};
 use crate::utils::require;
@@ -43,36 +48,69 @@ use crate::{DeltaResult, Engine, Error, ExpressionEvaluator};
 /// vector indicating which rows are valid, and any row-level transformation expressions that need
 /// to be applied to the selected rows.
 pub(crate) struct ScanLogReplayProcessor {
-    partition_filter: Option<PredicateRef>,
     data_skipping_filter: Option<DataSkippingFilter>,
     add_transform: Arc<dyn ExpressionEvaluator>,
     logical_schema: SchemaRef,
     transform: Option<Arc<Transform>>,
+    options: ScanLogReplayOptions,
     /// A set of (data file path, dv_unique_id) pairs that have been seen thus
     /// far in the log. This is used to filter out files with Remove actions as
     /// well as duplicate entries in the log.
     seen_file_keys: HashSet<FileActionKey>,
+    /// The transforms interned thus far, see [`PartitionTransforms`].
+    partition_transforms: PartitionTransforms,
+}
+
+/// The transform expression for each distinct set of partition values seen thus far, keyed by the
+/// raw values of the transform's partition columns (in transform order). `None` means that files
+/// with these partition values are pruned by the partition filter.
+///
+/// All files of a partition share the same transform, so interning it keeps the memory used by
+/// transforms (and their partition value literals) proportional to the number of partitions rather
+/// than the number of files. It also means partition values are parsed, and the partition filter
+/// evaluated, only once per partition.
+type PartitionTransforms = HashMap<Vec<Option<String>>, Option<ExpressionRef>>;
+
+/// The optional inputs of a scan's log replay. The default replays the log with no predicate, type
+/// coercions, file filter or sampling, and records no metrics.
+#[derive(Clone, Default)]
+pub(crate) struct ScanLogReplayOptions {
+    /// The physical predicate (and the schema of the columns it references) used for data skipping
+    /// and partition pruning.
+    pub(crate) physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    /// The schema the scan coerces the logical schema to. See [`crate::scan::TypeCoercions`].
+    pub(crate) coerced_schema: Option<SchemaRef>,
+    /// The engine's filter on the files that survive log replay. See [`FileFilter`].
+    pub(crate) file_filter: Option<FileFilter>,
+    /// Selects a sample of the files that survive log replay.
+    pub(crate) sampler: Option<Arc<FileSampler>>,
+    /// Where to record the metrics of each replayed batch.
+    pub(crate) metrics: Option<ScanMetricsHandle>,
 }
 
 impl ScanLogReplayProcessor {
     /// Create a new [`ScanLogReplayProcessor`] instance
     fn new(
         engine: &dyn Engine,
-        physical_predicate: Option<(PredicateRef, SchemaRef)>,
         logical_schema: SchemaRef,
         transform: Option<Arc<Transform>>,
+        options: ScanLogReplayOptions,
     ) -> Self {
         Self {
-            partition_filter: physical_predicate.as_ref().map(|(e, _)| e.clone()),
-            data_skipping_filter: DataSkippingFilter::new(engine, physical_predicate),
+            data_skipping_filter: DataSkippingFilter::new(
+                engine,
+                options.physical_predicate.clone(),
+            ),
             add_transform: engine.evaluation_handler().new_expression_evaluator(
                 get_log_add_schema().clone(),
                 get_add_transform_expr(),
                 SCAN_ROW_DATATYPE.clone(),
             ),
             seen_file_keys: Default::default(),
+            partition_transforms: Default::default(),
             logical_schema,
             transform,
+            options,
         }
     }
 }
@@ -83,11 +121,13 @@ impl ScanLogReplayProcessor {
 /// first action for a given file is a remove, then that file does not show up in the result at all.
 struct AddRemoveDedupVisitor<'seen> {
     deduplicator: FileActionDeduplicator<'seen>,
+    partition_transforms: &'seen mut PartitionTransforms,
     selection_vector: Vec<bool>,
     logical_schema: SchemaRef,
     transform: Option<Arc<Transform>>,
-    partition_filter: Option<PredicateRef>,
+    options: &'seen ScanLogReplayOptions,
     row_transform_exprs: Vec<Option<ExpressionRef>>,
+    metrics: ScanMetrics,
 }
 
 impl AddRemoveDedupVisitor<'_> {
@@ -95,18 +135,24 @@ impl AddRemoveDedupVisitor<'_> {
     // `selected_column_names_and_types()`
     const ADD_PATH_INDEX: usize = 0; // Position of "add.path" in getters
     const ADD_PARTITION_VALUES_INDEX: usize = 1; // Position of "add.partitionValues" in getters
-    const ADD_DV_START_INDEX: usize = 2; // Start position of add deletion vector columns
-    const REMOVE_PATH_INDEX: usize = 5; // Position of "remove.path" in getters
-    const REMOVE_DV_START_INDEX: usize = 6; // Start position of remove deletion vector columns
-
-    fn new(
-        seen: &mut HashSet<FileActionKey>,
+    const ADD_SIZE_INDEX: usize = 2; // Position of "add.size" in getters
+    const ADD_MODIFICATION_TIME_INDEX: usize = 3; // Position of "add.modificationTime" in getters
+    const ADD_STATS_INDEX: usize = 4; // Position of "add.stats" in getters
+    const ADD_DV_START_INDEX: usize = 5; // Start position of add deletion vector columns
+    const ADD_BASE_ROW_ID_INDEX: usize = 8; // Position of "add.baseRowId" in getters
+    const ADD_DEFAULT_ROW_COMMIT_VERSION_INDEX: usize = 9; // Position of "add.defaultRowCommitVersion" in getters
+    const REMOVE_PATH_INDEX: usize = 10; // Position of "remove.path" in getters
+    const REMOVE_DV_START_INDEX: usize = 11; // Start position of remove deletion vector columns
+
+    fn new<'seen>(
+        seen: &'seen mut HashSet<FileActionKey>,
+        partition_transforms: &'seen mut PartitionTransforms,
         selection_vector: Vec<bool>,
         logical_schema: SchemaRef,
         transform: Option<Arc<Transform>>,
-        partition_filter: Option<PredicateRef>,
+        options: &'seen ScanLogReplayOptions,
         is_log_batch: bool,
-    ) -> AddRemoveDedupVisitor<'_> {
+    ) -> AddRemoveDedupVisitor<'seen> {
         AddRemoveDedupVisitor {
             deduplicator: FileActionDeduplicator::new(
                 seen,
@@ -116,25 +162,32 @@ impl AddRemoveDedupVisitor<'_> {
                 Self::ADD_DV_START_INDEX,
                 Self::REMOVE_DV_START_INDEX,
             ),
+            partition_transforms,
             selection_vector,
             logical_schema,
             transform,
-            partition_filter,
+            options,
             row_transform_exprs: Vec::new(),
+            metrics: ScanMetrics::default(),
         }
     }
 
-    fn parse_partition_value(
-        &self,
-        field_idx: usize,
-        partition_values: &HashMap<String, String>,
-    ) -> DeltaResult<(usize, (String, Scalar))> {
+    fn partition_field(&self, field_idx: usize) -> DeltaResult<&StructField> {
         let field = self.logical_schema.fields.get_index(field_idx);
         let Some((_, field)) = field else {
             return Err(Error::InternalError(format!(
                 "out of bounds partition column field index {field_idx}"
             )));
         };
+        Ok(field)
+    }
+
+    fn parse_partition_value(
+        &self,
+        field_idx: usize,
+        partition_values: &HashMap<String, String>,
+    ) -> DeltaResult<(usize, (String, Scalar))> {
+        let field = self.partition_field(field_idx)?;
         let name = field.physical_name();
         let partition_value =
             super::parse_partition_value(partition_values.get(name), field.data_type())?;
@@ -152,12 +205,34 @@ impl AddRemoveDedupVisitor<'_> {
                 TransformExpr::Partition(field_idx) => {
                     Some(self.parse_partition_value(*field_idx, partition_values))
                 }
-                TransformExpr::Static(_) => None,
+                TransformExpr::Static(_) | TransformExpr::Metadata(_) => None,
             })
             .try_collect()
     }
 
-    /// Compute an expression that will transform from physical to logical for a given Add file action
+    /// Cast the transform expression of the logical field at `position` to the type the scan coerces
+    /// it to, if any. See [`crate::scan::TypeCoercions`].
+    fn coerce(&self, position: usize, expr: Expression) -> DeltaResult<Expression> {
+        let Some(coerced_schema) = &self.options.coerced_schema else {
+            return Ok(expr);
+        };
+        let fields = self.logical_schema.fields.get_index(position);
+        let coerced_fields = coerced_schema.fields.get_index(position);
+        let (Some((_, field)), Some((_, coerced_field))) = (fields, coerced_fields) else {
+            return Err(Error::InternalError(format!(
+                "out of bounds transform field index {position}"
+            )));
+        };
+        Ok(if field.data_type() == coerced_field.data_type() {
+            expr
+        } else {
+            coerce_expr(expr, field.data_type(), coerced_field.data_type())
+        })
+    }
+
+    /// Compute an expression that will transform from physical to logical for a given Add file
+    /// action. The metadata columns differ from file to file, so they are left out here and filled
+    /// in by [`Self::with_metadata_columns`].
     fn get_transform_expr(
         &self,
         transform: &Transform,
@@ -165,21 +240,136 @@ impl AddRemoveDedupVisitor<'_> {
     ) -> DeltaResult<ExpressionRef> {
         let transforms = transform
             .iter()
-            .map(|transform_expr| match transform_expr {
-                TransformExpr::Partition(field_idx) => {
-                    let Some((_, partition_value)) = partition_values.remove(field_idx) else {
-                        return Err(Error::InternalError(format!(
-                            "missing partition value for field index {field_idx}"
-                        )));
-                    };
-                    Ok(partition_value.into())
-                }
-                TransformExpr::Static(field_expr) => Ok(field_expr.clone()),
+            .enumerate()
+            .filter_map(|(position, transform_expr)| {
+                let expr = match transform_expr {
+                    TransformExpr::Partition(field_idx) => {
+                        let Some((_, partition_value)) = partition_values.remove(field_idx) else {
+                            return Some(Err(Error::InternalError(format!(
+                                "missing partition value for field index {field_idx}"
+                            ))));
+                        };
+                        partition_value.into()
+                    }
+                    TransformExpr::Static(field_expr) => field_expr.clone(),
+                    TransformExpr::Metadata(_) => return None,
+                };
+                Some(self.coerce(position, expr))
             })
             .try_collect()?;
         Ok(Arc::new(Expression::Struct(transforms)))
     }
 
+    /// Fill the metadata columns of the Add file action in row `i` into its (interned) transform
+    /// expression, if the scan returns any. The row id of each row is the file's base row id plus
+    /// the row's index within the file, unless the row has a materialized row id; both row tracking
+    /// columns are null for files without row tracking information or materialized values.
+    fn with_metadata_columns<'a>(
+        &self,
+        transform: &Transform,
+        transform_expr: ExpressionRef,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<ExpressionRef> {
+        let Some((position, metadata_columns)) = transform.iter().enumerate().find_map(
+            |(position, transform_expr)| match transform_expr {
+                TransformExpr::Metadata(metadata_columns) => Some((position, metadata_columns)),
+                _ => None,
+            },
+        ) else {
+            return Ok(transform_expr);
+        };
+        let Expression::Struct(ref exprs) = *transform_expr else {
+            return Err(Error::internal_error(
+                "Transform expression is not a struct",
+            ));
+        };
+        let mut fields = vec![];
+        if let Some(table_root) = &metadata_columns.file_table_root {
+            let path: &str = getters[Self::ADD_PATH_INDEX].get(i, "add.path")?;
+            let size: i64 = getters[Self::ADD_SIZE_INDEX].get(i, "add.size")?;
+            let modification_time: i64 =
+                getters[Self::ADD_MODIFICATION_TIME_INDEX].get(i, "add.modificationTime")?;
+            let modification_time = modification_time
+                .checked_mul(1000)
+                .ok_or_else(|| Error::generic("add.modificationTime out of range"))?;
+            fields.extend([
+                Expression::literal(table_root.join(path)?.to_string()),
+                Expression::literal(size),
+                Expression::literal(Scalar::Timestamp(modification_time)),
+            ]);
+        }
+        if metadata_columns.row_tracking {
+            let base_row_id: Option<i64> =
+                getters[Self::ADD_BASE_ROW_ID_INDEX].get_opt(i, "add.baseRowId")?;
+            let default_row_commit_version: Option<i64> = getters
+                [Self::ADD_DEFAULT_ROW_COMMIT_VERSION_INDEX]
+                .get_opt(i, "add.defaultRowCommitVersion")?;
+            let row_id = match base_row_id {
+                Some(base_row_id) => Expression::binary(
+                    BinaryExpressionOp::Plus,
+                    Expression::literal(base_row_id),
+                    Expression::column([ROW_INDEX_COLUMN_NAME]),
+                ),
+                None => Expression::null_literal(DataType::LONG),
+            };
+            let row_commit_version = match default_row_commit_version {
+                Some(version) => Expression::literal(version),
+                None => Expression::null_literal(DataType::LONG),
+            };
+            // Values materialized by rewrites take precedence over the computed ones
+            let coalesce_materialized = |name: &Option<String>, expr| match name {
+                Some(name) => coalesce_expr([Expression::column([name]), expr]),
+                None => expr,
+            };
+            let row_id = coalesce_materialized(&metadata_columns.materialized_row_id, row_id);
+            let row_commit_version = coalesce_materialized(
+                &metadata_columns.materialized_row_commit_version,
+                row_commit_version,
+            );
+            fields.extend([row_id, row_commit_version]);
+        }
+        let mut exprs = exprs.clone();
+        exprs.insert(
+            position,
+            self.coerce(position, Expression::struct_from(fields))?,
+        );
+        Ok(Arc::new(Expression::Struct(exprs)))
+    }
+
+    /// The (interned) transform expression for an Add file action with the given partition
+    /// values, or `None` if the file is partition-pruned. See [`PartitionTransforms`].
+    fn get_partition_transform(
+        &mut self,
+        transform: &Transform,
+        partition_values: &HashMap<String, String>,
+    ) -> DeltaResult<Option<ExpressionRef>> {
+        let key: Vec<_> = transform
+            .iter()
+            .filter_map(|transform_expr| match transform_expr {
+                TransformExpr::Partition(field_idx) => Some(*field_idx),
+                TransformExpr::Static(_) | TransformExpr::Metadata(_) => None,
+            })
+            .map(|field_idx| {
+                let name = self.partition_field(field_idx)?.physical_name();
+                Ok::<_, Error>(partition_values.get(name).cloned())
+            })
+            .try_collect()?;
+        if let Some(transform_expr) = self.partition_transforms.get(&key) {
+            return Ok(transform_expr.clone());
+        }
+
+        let partition_values = self.parse_partition_values(transform, partition_values)?;
+        let transform_expr = if self.is_file_partition_pruned(&partition_values) {
+            None
+        } else {
+            Some(self.get_transform_expr(transform, partition_values)?)
+        };
+        self.partition_transforms
+            .insert(key, transform_expr.clone());
+        Ok(transform_expr)
+    }
+
     fn is_file_partition_pruned(
         &self,
         partition_values: &HashMap<usize, (String, Scalar)>,
@@ -187,7 +377,7 @@ impl AddRemoveDedupVisitor<'_> {
         if partition_values.is_empty() {
             return false;
         }
-        let Some(partition_filter) = &self.partition_filter else {
+        let Some((partition_filter, _)) = &self.options.physical_predicate else {
             return false;
         };
         let partition_values: HashMap<_, _> = partition_values
@@ -202,8 +392,8 @@ impl AddRemoveDedupVisitor<'_> {
     /// is not an Add action, or the file has already been seen previously.
     fn is_valid_add<'a>(&mut self, i: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<bool> {
         // When processing file actions, we extract path and deletion vector information based on action type:
-        // - For Add actions: path is at index 0, followed by DV fields at indexes 2-4
-        // - For Remove actions (in log batches only): path is at index 5, followed by DV fields at indexes 6-8
+        // - For Add actions: path is at index 0, followed by DV fields at indexes 5-7
+        // - For Remove actions (in log batches only): path is at index 10, followed by DV fields at indexes 11-13
         // The file extraction logic selects the appropriate indexes based on whether we found a valid path.
         // Remove getters are not included when visiting a non-log batch (checkpoint batch), so do
         // not try to extract remove actions in that case.
@@ -215,6 +405,9 @@ impl AddRemoveDedupVisitor<'_> {
         else {
             return Ok(false);
         };
+        if is_add {
+            self.metrics.files_listed += 1;
+        }
 
         // Apply partition pruning (to adds only) before deduplication, so that we don't waste memory
         // tracking pruned files. Removes don't get pruned and we'll still have to track them.
@@ -222,28 +415,51 @@ impl AddRemoveDedupVisitor<'_> {
         // WARNING: It's not safe to partition-prune removes (just like it's not safe to data skip
         // removes), because they are needed to suppress earlier incompatible adds we might
         // encounter if the table's schema was replaced after the most recent checkpoint.
-        let partition_values = match &self.transform {
+        let transform = match self.transform.clone() {
             Some(transform) if is_add => {
                 let partition_values =
                     getters[Self::ADD_PARTITION_VALUES_INDEX].get(i, "add.partitionValues")?;
-                let partition_values = self.parse_partition_values(transform, &partition_values)?;
-                if self.is_file_partition_pruned(&partition_values) {
+                let Some(transform_expr) =
+                    self.get_partition_transform(&transform, &partition_values)?
+                else {
+                    self.metrics.files_pruned_by_partition += 1;
                     return Ok(false);
-                }
-                partition_values
+                };
+                Some(self.with_metadata_columns(&transform, transform_expr, i, getters)?)
             }
-            _ => Default::default(),
+            _ => None,
         };
 
         // Check both adds and removes (skipping already-seen), but only transform and return adds
         if self.deduplicator.check_and_record_seen(file_key) || !is_add {
             return Ok(false);
         }
-        let transform = self
-            .transform
-            .as_ref()
-            .map(|transform| self.get_transform_expr(transform, partition_values))
-            .transpose()?;
+
+        // Apply the engine's file filter only after deduplication, so that excluding a file also
+        // suppresses any older adds of the same file.
+        if let Some(file_filter) = &self.options.file_filter {
+            let partition_values: HashMap<String, String> =
+                getters[Self::ADD_PARTITION_VALUES_INDEX].get(i, "add.partitionValues")?;
+            let add_info = AddInfo {
+                path: getters[Self::ADD_PATH_INDEX].get(i, "add.path")?,
+                size: getters[Self::ADD_SIZE_INDEX].get(i, "add.size")?,
+                partition_values: &partition_values,
+            };
+            if !file_filter(&add_info) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(sampler) = &self.options.sampler {
+            let path: &str = getters[Self::ADD_PATH_INDEX].get(i, "add.path")?;
+            let stats: Option<&str> = getters[Self::ADD_STATS_INDEX].get_opt(i, "add.stats")?;
+            if !sampler.sample(path, stats) {
+                return Ok(false);
+            }
+        }
+        let size: i64 = getters[Self::ADD_SIZE_INDEX].get(i, "add.size")?;
+        self.metrics.files_selected += 1;
+        self.metrics.bytes_scanned += u64::try_from(size).unwrap_or_default();
         if transform.is_some() {
             // fill in any needed `None`s for previous rows
             self.row_transform_exprs.resize_with(i, Default::default);
@@ -259,13 +475,19 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             const STRING: DataType = DataType::STRING;
             const INTEGER: DataType = DataType::INTEGER;
+            const LONG: DataType = DataType::LONG;
             let ss_map: DataType = MapType::new(STRING, STRING, true).into();
             let types_and_names = vec![
                 (STRING, column_name!("add.path")),
                 (ss_map, column_name!("add.partitionValues")),
+                (LONG, column_name!("add.size")),
+                (LONG, column_name!("add.modificationTime")),
+                (STRING, column_name!("add.stats")),
                 (STRING, column_name!("add.deletionVector.storageType")),
                 (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
                 (INTEGER, column_name!("add.deletionVector.offset")),
+                (LONG, column_name!("add.baseRowId")),
+                (LONG, column_name!("add.defaultRowCommitVersion")),
                 (STRING, column_name!("remove.path")),
                 (STRING, column_name!("remove.deletionVector.storageType")),
                 (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
@@ -280,13 +502,13 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
         } else {
             // All checkpoint actions are already reconciled and Remove actions in checkpoint files
             // only serve as tombstones for vacuum jobs. So we only need to examine the adds here.
-            (&names[..5], &types[..5])
+            (&names[..10], &types[..10])
         }
     }
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         let is_log_batch = self.deduplicator.is_log_batch();
-        let expected_getters = if is_log_batch { 9 } else { 5 };
+        let expected_getters = if is_log_batch { 14 } else { 10 };
         require!(
             getters.len() == expected_getters,
             Error::InternalError(format!(
@@ -310,8 +532,13 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
 pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(|| {
     // Note that fields projected out of a nullable struct must be nullable
     let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
-    let file_constant_values =
-        StructType::new([StructField::nullable("partitionValues", partition_values)]);
+    let tags = MapType::new(DataType::STRING, DataType::STRING, false);
+    let file_constant_values = StructType::new([
+        StructField::nullable("partitionValues", partition_values),
+        StructField::nullable("baseRowId", DataType::LONG),
+        StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+        StructField::nullable("tags", tags),
+    ]);
     Arc::new(StructType::new([
         StructField::nullable("path", DataType::STRING),
         StructField::nullable("size", DataType::LONG),
@@ -332,7 +559,12 @@ fn get_add_transform_expr() -> Expression {
         column_expr!("add.modificationTime"),
         column_expr!("add.stats"),
         column_expr!("add.deletionVector"),
-        Expression::Struct(vec![column_expr!("add.partitionValues")]),
+        Expression::Struct(vec![
+            column_expr!("add.partitionValues"),
+            column_expr!("add.baseRowId"),
+            column_expr!("add.defaultRowCommitVersion"),
+            column_expr!("add.tags"),
+        ]),
     ])
 }
 
@@ -346,6 +578,9 @@ pub(crate) fn get_scan_metadata_transform_expr() -> Expression {
         column_expr!("modificationTime"),
         column_expr!("stats"),
         column_expr!("deletionVector"),
+        column_expr!("fileConstantValues.baseRowId"),
+        column_expr!("fileConstantValues.defaultRowCommitVersion"),
+        column_expr!("fileConstantValues.tags"),
     ])])
 }
 
@@ -362,16 +597,26 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
         // rows that are not valid adds.
         let selection_vector = self.build_selection_vector(actions.as_ref())?;
         assert_eq!(selection_vector.len(), actions.len());
+        // Only adds with stats can be skipped, so every unselected row is a skipped file
+        let files_pruned_by_stats = selection_vector.iter().filter(|s| !**s).count() as u64;
 
         let mut visitor = AddRemoveDedupVisitor::new(
             &mut self.seen_file_keys,
+            &mut self.partition_transforms,
             selection_vector,
             self.logical_schema.clone(),
             self.transform.clone(),
-            self.partition_filter.clone(),
+            &self.options,
             is_log_batch,
         );
         visitor.visit_rows_of(actions.as_ref())?;
+        if let Some(metrics) = &self.options.metrics {
+            metrics.record(&ScanMetrics {
+                files_listed: visitor.metrics.files_listed + files_pruned_by_stats,
+                files_pruned_by_stats,
+                ..visitor.metrics
+            });
+        }
 
         // TODO: Teach expression eval to respect the selection vector we just computed so carefully!
         let result = self.add_transform.evaluate(actions.as_ref())?;
@@ -400,9 +645,9 @@ pub(crate) fn scan_action_iter(
     action_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
     logical_schema: SchemaRef,
     transform: Option<Arc<Transform>>,
-    physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    options: ScanLogReplayOptions,
 ) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
-    ScanLogReplayProcessor::new(engine, physical_predicate, logical_schema, transform)
+    ScanLogReplayProcessor::new(engine, logical_schema, transform, options)
         .process_actions_iter(action_iter)
 }
 
@@ -426,7 +671,7 @@ mod tests {
         ExpressionRef,
     };
 
-    use super::scan_action_iter;
+    use super::{scan_action_iter, ScanLogReplayOptions};
 
     // dv-info is more complex to validate, we validate that works in the test for visit_scan_files
     // in state.rs
@@ -485,7 +730,7 @@ mod tests {
                 .map(|batch| Ok(ActionsBatch::new(batch as _, true))),
             logical_schema,
             None,
-            None,
+            Default::default(),
         );
         for res in iter {
             let scan_metadata = res.unwrap();
@@ -513,7 +758,7 @@ mod tests {
                 .map(|batch| Ok(ActionsBatch::new(batch as _, true))),
             schema,
             static_transform,
-            None,
+            Default::default(),
         );
 
         fn validate_transform(transform: Option<&ExpressionRef>, expected_date_offset: i32) {
@@ -550,4 +795,131 @@ mod tests {
             validate_transform(transforms[3].as_ref(), 17510);
         }
     }
+
+    #[test]
+    fn test_boolean_and_binary_partition_pruning() {
+        use crate::arrow::array::StringArray;
+        use crate::engine::arrow_data::ArrowEngineData;
+        use crate::engine::sync::json::SyncJsonHandler;
+        use crate::expressions::column_expr;
+        use crate::utils::test_utils::string_array_to_engine_data;
+        use crate::{JsonHandler, Predicate as Pred};
+
+        let json_strings: StringArray = vec![
+            r#"{"add":{"path":"part-1.parquet","partitionValues":{"flag":"true","bin":"a"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+            r#"{"add":{"path":"part-2.parquet","partitionValues":{"flag":"false","bin":"a"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+            r#"{"add":{"path":"part-3.parquet","partitionValues":{"flag":"true","bin":"b"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+            r#"{"add":{"path":"part-4.parquet","partitionValues":{"flag":null,"bin":null},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+        ]
+        .into();
+        let batch = SyncJsonHandler {}
+            .parse_json(
+                string_array_to_engine_data(json_strings),
+                get_log_schema().clone(),
+            )
+            .unwrap();
+        let batch = ArrowEngineData::try_from_engine_data(batch).unwrap();
+
+        let schema: SchemaRef = Arc::new(StructType::new([
+            StructField::nullable("value", DataType::INTEGER),
+            StructField::nullable("flag", DataType::BOOLEAN),
+            StructField::nullable("bin", DataType::BINARY),
+        ]));
+        let partition_cols = ["flag".to_string(), "bin".to_string()];
+        let state_info = get_state_info(schema.as_ref(), &partition_cols).unwrap();
+        let static_transform = Some(Arc::new(Scan::get_static_transform(&state_info.all_fields)));
+        let predicate = Arc::new(Pred::and(
+            Pred::from_expr(column_expr!("flag")),
+            Pred::eq(column_expr!("bin"), Scalar::Binary(b"a".to_vec())),
+        ));
+        let predicate_schema = Arc::new(StructType::new([
+            StructField::nullable("flag", DataType::BOOLEAN),
+            StructField::nullable("bin", DataType::BINARY),
+        ]));
+        let iter = scan_action_iter(
+            &SyncEngine::new(),
+            std::iter::once(Ok(ActionsBatch::new(batch as _, true))),
+            schema,
+            static_transform,
+            ScanLogReplayOptions {
+                physical_predicate: Some((predicate, predicate_schema)),
+                ..Default::default()
+            },
+        );
+
+        let scan_metadata: Vec<_> = iter.map(|res| res.unwrap()).collect();
+        assert_eq!(scan_metadata.len(), 1);
+        let scan_metadata = &scan_metadata[0];
+        assert_eq!(
+            scan_metadata.scan_files.selection_vector,
+            vec![true, false, false, false]
+        );
+        let Some(transform) = &scan_metadata.scan_file_transforms[0] else {
+            panic!("expected a transform for the selected file");
+        };
+        let Expr::Struct(inner) = transform.as_ref() else {
+            panic!("Transform should always be a struct expr");
+        };
+        assert_eq!(inner[1], Expr::Literal(Scalar::Boolean(true)));
+        assert_eq!(inner[2], Expr::Literal(Scalar::Binary(b"a".to_vec())));
+    }
+
+    #[test]
+    fn test_partition_transforms_are_interned() {
+        use crate::arrow::array::StringArray;
+        use crate::engine::sync::json::SyncJsonHandler;
+        use crate::utils::test_utils::string_array_to_engine_data;
+        use crate::JsonHandler;
+
+        let parse_batch = |json_strings: Vec<&str>| {
+            let json_strings: StringArray = json_strings.into();
+            SyncJsonHandler {}
+                .parse_json(
+                    string_array_to_engine_data(json_strings),
+                    get_log_schema().clone(),
+                )
+                .unwrap()
+        };
+        let batches = vec![
+            parse_batch(vec![
+                r#"{"add":{"path":"part-1.parquet","partitionValues":{"date":"2017-12-10"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+                r#"{"add":{"path":"part-2.parquet","partitionValues":{"date":"2017-12-11"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+                r#"{"add":{"path":"part-3.parquet","partitionValues":{"date":"2017-12-10"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+            ]),
+            parse_batch(vec![
+                r#"{"add":{"path":"part-4.parquet","partitionValues":{"date":"2017-12-11"},"size":635,"modificationTime":1677811178336,"dataChange":true}}"#,
+            ]),
+        ];
+
+        let schema: SchemaRef = Arc::new(StructType::new([
+            StructField::nullable("value", DataType::INTEGER),
+            StructField::nullable("date", DataType::DATE),
+        ]));
+        let partition_cols = ["date".to_string()];
+        let state_info = get_state_info(schema.as_ref(), &partition_cols).unwrap();
+        let static_transform = Some(Arc::new(Scan::get_static_transform(&state_info.all_fields)));
+        let iter = scan_action_iter(
+            &SyncEngine::new(),
+            batches
+                .into_iter()
+                .map(|batch| Ok(ActionsBatch::new(batch, true))),
+            schema,
+            static_transform,
+            Default::default(),
+        );
+        let transforms: Vec<ExpressionRef> = iter
+            .flat_map(|res| res.unwrap().scan_file_transforms)
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(transforms.len(), 4);
+
+        // files of the same partition share a transform, within and across batches
+        assert!(Arc::ptr_eq(&transforms[0], &transforms[2]));
+        assert!(Arc::ptr_eq(&transforms[1], &transforms[3]));
+        assert!(!Arc::ptr_eq(&transforms[0], &transforms[1]));
+        let Expr::Struct(inner) = transforms[1].as_ref() else {
+            panic!("Transform should always be a struct expr");
+        };
+        assert_eq!(inner[1], Expr::Literal(Scalar::Date(17511)));
+    }
 }
@@ -0,0 +1,85 @@
+//! Statistics about the files listed and pruned while producing the metadata of a scan. See
+//! [`Scan::scan_metadata_with_metrics`].
+//!
+//! [`Scan::scan_metadata_with_metrics`]: crate::scan::Scan::scan_metadata_with_metrics
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use super::sample::{FileSampler, SampleReport};
+
+/// Counts the files a scan listed from the log, how many of them were pruned and why, and how
+/// many were selected for reading. See [`Scan::scan_metadata_with_metrics`].
+///
+/// Files are counted per add action, so a file added by several (not yet checkpointed) commits is
+/// listed once per commit. Besides pruning, listed files can be dropped because they were removed
+/// or re-added by a newer commit, or by the scan's file filter or sample, so `files_listed` is
+/// generally greater than the sum of the other file counts.
+///
+/// [`Scan::scan_metadata_with_metrics`]: crate::scan::Scan::scan_metadata_with_metrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanMetrics {
+    /// The number of add actions read from the log.
+    pub files_listed: u64,
+    /// The number of files pruned because their partition values don't satisfy the predicate.
+    pub files_pruned_by_partition: u64,
+    /// The number of files pruned because their statistics show that no row satisfies the
+    /// predicate.
+    pub files_pruned_by_stats: u64,
+    /// The number of files selected for reading.
+    pub files_selected: u64,
+    /// The total size in bytes of the files selected for reading.
+    pub bytes_scanned: u64,
+}
+
+impl ScanMetrics {
+    fn merge(&mut self, other: &ScanMetrics) {
+        self.files_listed += other.files_listed;
+        self.files_pruned_by_partition += other.files_pruned_by_partition;
+        self.files_pruned_by_stats += other.files_pruned_by_stats;
+        self.files_selected += other.files_selected;
+        self.bytes_scanned += other.bytes_scanned;
+    }
+}
+
+/// The [`ScanMetrics`] and [`SampleReport`] of one iteration of a scan's metadata, accumulated as
+/// the batches of actions are replayed. See [`Scan::scan_metadata_with_metrics`].
+///
+/// [`Scan::scan_metadata_with_metrics`]: crate::scan::Scan::scan_metadata_with_metrics
+#[derive(Debug, Clone, Default)]
+pub struct ScanMetricsHandle {
+    metrics: Arc<Mutex<ScanMetrics>>,
+    sampler: Option<Arc<FileSampler>>,
+}
+
+impl ScanMetricsHandle {
+    pub(crate) fn new(sampler: Option<FileSampler>) -> Self {
+        Self {
+            metrics: Default::default(),
+            sampler: sampler.map(Arc::new),
+        }
+    }
+
+    /// The metrics recorded so far. They are only complete once the scan metadata iterator this
+    /// handle belongs to is exhausted.
+    pub fn metrics(&self) -> ScanMetrics {
+        *self.metrics.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Describes the sample taken by the iteration of a scan built with
+    /// [`ScanBuilder::with_sample`], or `None` if the scan is not sampled. Like the metrics, the
+    /// report is only complete once the iterator is exhausted.
+    ///
+    /// [`ScanBuilder::with_sample`]: crate::scan::ScanBuilder::with_sample
+    pub fn sample_report(&self) -> Option<SampleReport> {
+        self.sampler.as_ref().map(|sampler| sampler.report())
+    }
+
+    pub(crate) fn sampler(&self) -> Option<Arc<FileSampler>> {
+        self.sampler.clone()
+    }
+
+    pub(crate) fn record(&self, batch_metrics: &ScanMetrics) {
+        let mut metrics = self.metrics.lock().unwrap_or_else(PoisonError::into_inner);
+        metrics.merge(batch_metrics);
+    }
+}
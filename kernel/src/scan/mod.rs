@@ -1,11 +1,14 @@
 //! Functionality to create and execute scans (reads) over data stored in a delta table
 
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, LazyLock};
 
+use bytes::Bytes;
 use delta_kernel_derive::internal_api;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 use url::Url;
 
@@ -20,7 +23,7 @@ use crate::expressions::{ColumnName, Expression, ExpressionRef, Predicate, Predi
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, EmptyColumnResolver};
 use crate::log_replay::{ActionsBatch, HasSelectionVector};
 use crate::log_segment::{ListedLogFiles, LogSegment};
-use crate::scan::state::{DvInfo, Stats};
+use crate::scan::state::{DvInfo, ScanState, Stats};
 use crate::schema::ToSchema as _;
 use crate::schema::{
     ArrayType, DataType, MapType, PrimitiveType, Schema, SchemaRef, SchemaTransform, StructField,
@@ -28,24 +31,75 @@ use crate::schema::{
 };
 use crate::snapshot::Snapshot;
 use crate::table_features::ColumnMappingMode;
-use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Version};
+use crate::table_properties::DataSkippingNumIndexedCols;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, FileSlice, Version};
 
-use self::log_replay::scan_action_iter;
+use self::log_replay::{scan_action_iter, ScanLogReplayOptions};
 
+mod coercion;
 pub(crate) mod data_skipping;
+mod explain;
+mod generated_columns;
 pub mod log_replay;
+mod metrics;
+mod row_tracking;
+mod sample;
 pub mod state;
 
-static COMMIT_READ_SCHEMA: LazyLock<SchemaRef> =
+pub use self::coercion::{DecimalCoercion, TimestampCoercion, TypeCoercions};
+pub use self::explain::{
+    ConjunctExplanation, ConjunctOutcome, ConjunctSkipping, FileSkippingExplanation,
+    SkippingExplanation,
+};
+pub use self::metrics::{ScanMetrics, ScanMetricsHandle};
+use self::sample::FileSampler;
+pub use self::sample::SampleReport;
+
+pub(crate) static COMMIT_READ_SCHEMA: LazyLock<SchemaRef> =
     LazyLock::new(|| get_log_schema().project(&[ADD_NAME, REMOVE_NAME]).unwrap());
-static CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> =
+pub(crate) static CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> =
     LazyLock::new(|| get_log_schema().project(&[ADD_NAME, SIDECAR_NAME]).unwrap());
 
+/// Information about a file to be scanned, passed to the file filter of a scan (see
+/// [`ScanBuilder::with_file_filter`]).
+#[derive(Debug)]
+pub struct AddInfo<'a> {
+    /// The path of the file, relative to the table root (or an absolute URL).
+    pub path: &'a str,
+    /// The size of the file, in bytes.
+    pub size: i64,
+    /// The raw (string-encoded) partition values of the file, keyed by physical column name.
+    pub partition_values: &'a HashMap<String, String>,
+}
+
+/// A callback that decides whether a file is included in a scan. See
+/// [`ScanBuilder::with_file_filter`].
+pub(crate) type FileFilter = Arc<dyn Fn(&AddInfo<'_>) -> bool + Send + Sync>;
+
+/// The name of the struct column holding the file metadata and row tracking columns of a scan, see
+/// [`ScanBuilder::with_file_metadata_columns`] and [`ScanBuilder::with_row_tracking_columns`].
+pub const METADATA_COLUMN_NAME: &str = "_metadata";
+
+/// The name of a (LONG) column that a [`ParquetHandler`] fills with the index of each row within
+/// its file, if the column is requested but the file doesn't contain it. Scans that return row
+/// tracking columns (see [`ScanBuilder::with_row_tracking_columns`]) include it in their physical
+/// schema.
+///
+/// [`ParquetHandler`]: crate::ParquetHandler
+pub const ROW_INDEX_COLUMN_NAME: &str = "_metadata.row_index";
+
 /// Builder to scan a snapshot of a table.
 pub struct ScanBuilder {
     snapshot: Arc<Snapshot>,
     schema: Option<SchemaRef>,
     predicate: Option<PredicateRef>,
+    file_filter: Option<FileFilter>,
+    sample: Option<(f64, u64)>,
+    type_coercions: TypeCoercions,
+    footer_num_records: Option<usize>,
+    file_metadata_columns: bool,
+    row_tracking_columns: bool,
 }
 
 impl std::fmt::Debug for ScanBuilder {
@@ -53,6 +107,12 @@ impl std::fmt::Debug for ScanBuilder {
         f.debug_struct("ScanBuilder")
             .field("schema", &self.schema)
             .field("predicate", &self.predicate)
+            .field("file_filter", &self.file_filter.is_some())
+            .field("sample", &self.sample)
+            .field("type_coercions", &self.type_coercions)
+            .field("footer_num_records", &self.footer_num_records)
+            .field("file_metadata_columns", &self.file_metadata_columns)
+            .field("row_tracking_columns", &self.row_tracking_columns)
             .finish()
     }
 }
@@ -64,6 +124,12 @@ impl ScanBuilder {
             snapshot: snapshot.into(),
             schema: None,
             predicate: None,
+            file_filter: None,
+            sample: None,
+            type_coercions: TypeCoercions::default(),
+            footer_num_records: None,
+            file_metadata_columns: false,
+            row_tracking_columns: false,
         }
     }
 
@@ -94,11 +160,76 @@ impl ScanBuilder {
     ///
     /// NOTE: The filtering is best-effort and can produce false positives (rows that should should
     /// have been filtered out but were kept).
+    ///
+    /// If the table is partitioned by a generated date column, e.g. `CAST(event_time AS DATE)`,
+    /// comparisons of the source column with a timestamp also prune partitions, as long as the
+    /// partition column is part of the scan's schema.
     pub fn with_predicate(mut self, predicate: impl Into<Option<PredicateRef>>) -> Self {
         self.predicate = predicate.into();
         self
     }
 
+    /// Provide a callback which decides whether each file is part of the scan, e.g. to exclude
+    /// quarantined files. Files for which `file_filter` returns `false` are not returned by
+    /// [`Scan::scan_metadata`] (or read by [`Scan::execute`]). The filter is invoked during log
+    /// replay, after data skipping and partition pruning, at most once per file in the scan.
+    pub fn with_file_filter(
+        mut self,
+        file_filter: impl Fn(&AddInfo<'_>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.file_filter = Some(Arc::new(file_filter));
+        self
+    }
+
+    /// Only scan a deterministic sample of about `fraction` (between 0 and 1) of the files, e.g.
+    /// for approximate queries or data profiling. Files are sampled by hashing their path with
+    /// `seed`, so the same seed selects the same files across scans and table versions. Sampling
+    /// happens during log replay, after data skipping, partition pruning and any file filter. See
+    /// [`ScanMetricsHandle::sample_report`] for the fraction actually achieved.
+    pub fn with_sample(mut self, fraction: f64, seed: u64) -> Self {
+        self.sample = Some((fraction, seed));
+        self
+    }
+
+    /// Return the columns of the scan with the types preferred by `type_coercions`, for engines
+    /// that can't represent some Delta types. Fails to build the scan if a requested coercion would
+    /// lose information and lossy coercions are not allowed.
+    pub fn with_type_coercions(mut self, type_coercions: TypeCoercions) -> Self {
+        self.type_coercions = type_coercions;
+        self
+    }
+
+    /// Allow [`Scan::num_records`] to read the number of records of files without statistics from
+    /// their parquet footers, for at most `max_files` files. Footers are only read on demand, so
+    /// this is meant for small tables written without statistics, where the extra IO is cheap.
+    pub fn with_footer_num_records(mut self, max_files: usize) -> Self {
+        self.footer_num_records = Some(max_files);
+        self
+    }
+
+    /// Also return the file each row was read from, as the fields `file_path` (the file's URL),
+    /// `file_size` and `file_modification_time` of a [`METADATA_COLUMN_NAME`] struct column
+    /// appended to the scan's schema.
+    pub fn with_file_metadata_columns(mut self) -> Self {
+        self.file_metadata_columns = true;
+        self
+    }
+
+    /// Also return the row tracking columns of each row, as the fields `row_id` and
+    /// `row_commit_version` of a [`METADATA_COLUMN_NAME`] struct column appended to the scan's
+    /// schema. Row ids are computed from the base row id of each file plus the index of the row
+    /// within the file, so reading the files of the scan requires a [`ParquetHandler`] that fills
+    /// in [`ROW_INDEX_COLUMN_NAME`]. Row ids and commit versions materialized in data files by
+    /// rewrites (in the columns named by the table's `delta.rowTracking.materializedRowIdColumnName`
+    /// and `delta.rowTracking.materializedRowCommitVersionColumnName` properties) take precedence
+    /// over the computed ones. Fails to build the scan if the table does not support row tracking.
+    ///
+    /// [`ParquetHandler`]: crate::ParquetHandler
+    pub fn with_row_tracking_columns(mut self) -> Self {
+        self.row_tracking_columns = true;
+        self
+    }
+
     /// Build the [`Scan`].
     ///
     /// This does not scan the table at this point, but does do some work to ensure that the
@@ -106,29 +237,144 @@ impl ScanBuilder {
     /// [`Scan`] type itself can be used to fetch the files and associated metadata required to
     /// perform actual data reads.
     pub fn build(self) -> DeltaResult<Scan> {
+        if let Some((fraction, _)) = self.sample {
+            require!(
+                (0.0..=1.0).contains(&fraction),
+                Error::generic(format!(
+                    "Sample fraction must be between 0 and 1, got {fraction}"
+                ))
+            );
+        }
         // if no schema is provided, use snapshot's entire schema (e.g. SELECT *)
         let logical_schema = self.schema.unwrap_or_else(|| self.snapshot.schema());
+        // columns of types kernel doesn't know (see `Snapshot::try_new_with_unknown_types`) can't
+        // be read
+        logical_schema.ensure_supported_types()?;
         let state_info = get_state_info(
             logical_schema.as_ref(),
             &self.snapshot.metadata().partition_columns,
         )?;
 
         let physical_predicate = match self.predicate {
-            Some(predicate) => PhysicalPredicate::try_new(&predicate, &logical_schema)?,
+            Some(mut predicate) => {
+                let table_configuration = self.snapshot.table_configuration();
+                if table_configuration.is_generated_columns_supported() {
+                    predicate = generated_columns::with_generated_partition_filters(
+                        predicate,
+                        &logical_schema,
+                        &self.snapshot.metadata().partition_columns,
+                    );
+                }
+                PhysicalPredicate::try_new(&predicate, &logical_schema)?
+            }
             None => PhysicalPredicate::None,
         };
+        let table_properties = self.snapshot.table_properties();
+        let materialized_column =
+            |name: &Option<String>| name.clone().filter(|_| self.row_tracking_columns);
+        let metadata_columns = MetadataColumns {
+            file_table_root: self
+                .file_metadata_columns
+                .then(|| self.snapshot.table_root().clone()),
+            row_tracking: self.row_tracking_columns,
+            materialized_row_id: materialized_column(
+                &table_properties.materialized_row_id_column_name,
+            ),
+            materialized_row_commit_version: materialized_column(
+                &table_properties.materialized_row_commit_version_column_name,
+            ),
+        };
+        if metadata_columns.row_tracking
+            && !self
+                .snapshot
+                .table_configuration()
+                .is_row_tracking_supported()
+        {
+            return Err(Error::unsupported(
+                "Row tracking columns requested, but the table does not support row tracking",
+            ));
+        }
+        let mut read_fields = state_info.read_fields;
+        let logical_schema = if let Some(metadata_field) = metadata_columns.field() {
+            if logical_schema.field(METADATA_COLUMN_NAME).is_some() {
+                return Err(Error::generic(format!(
+                    "Cannot add metadata columns: schema already has a field named {METADATA_COLUMN_NAME}"
+                )));
+            }
+            if metadata_columns.row_tracking {
+                read_fields.push(StructField::nullable(ROW_INDEX_COLUMN_NAME, DataType::LONG));
+            }
+            let materialized_columns = [
+                &metadata_columns.materialized_row_id,
+                &metadata_columns.materialized_row_commit_version,
+            ];
+            for name in materialized_columns.into_iter().flatten() {
+                if !row_tracking::SUPPORTS_MATERIALIZED_COLUMNS {
+                    return Err(Error::unsupported(
+                        "Reading materialized row tracking columns requires the default engine's expression evaluation",
+                    ));
+                }
+                read_fields.push(StructField::nullable(name, DataType::LONG));
+            }
+            let fields = logical_schema.fields().cloned().chain([metadata_field]);
+            Arc::new(StructType::new(fields))
+        } else {
+            logical_schema
+        };
+        let coerced_schema = self.type_coercions.coerce_schema(&logical_schema)?;
 
         Ok(Scan {
             snapshot: self.snapshot,
             logical_schema,
-            physical_schema: Arc::new(StructType::new(state_info.read_fields)),
+            coerced_schema,
+            physical_schema: Arc::new(StructType::new(read_fields)),
             physical_predicate,
             all_fields: Arc::new(state_info.all_fields),
             have_partition_cols: state_info.have_partition_cols,
+            file_filter: self.file_filter,
+            sample: self.sample,
+            footer_reads_left: self.footer_num_records.map(AtomicUsize::new),
+            metadata_columns,
         })
     }
 }
 
+/// The fields of the [`METADATA_COLUMN_NAME`] column returned by a scan.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct MetadataColumns {
+    /// The table root to resolve file paths against, if the scan returns file metadata columns
+    pub(crate) file_table_root: Option<Url>,
+    /// True if the scan returns row tracking columns
+    pub(crate) row_tracking: bool,
+    /// The physical column holding materialized row ids, if the scan returns row tracking columns
+    pub(crate) materialized_row_id: Option<String>,
+    /// The physical column holding materialized row commit versions, if the scan returns row
+    /// tracking columns
+    pub(crate) materialized_row_commit_version: Option<String>,
+}
+
+impl MetadataColumns {
+    /// The [`METADATA_COLUMN_NAME`] column, or `None` if the scan returns no metadata columns
+    fn field(&self) -> Option<StructField> {
+        let mut fields = vec![];
+        if self.file_table_root.is_some() {
+            fields.extend([
+                StructField::nullable("file_path", DataType::STRING),
+                StructField::nullable("file_size", DataType::LONG),
+                StructField::nullable("file_modification_time", DataType::TIMESTAMP),
+            ]);
+        }
+        if self.row_tracking {
+            fields.extend([
+                StructField::nullable("row_id", DataType::LONG),
+                StructField::nullable("row_commit_version", DataType::LONG),
+            ]);
+        }
+        (!fields.is_empty())
+            .then(|| StructField::nullable(METADATA_COLUMN_NAME, StructType::new(fields)))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum PhysicalPredicate {
     Some(PredicateRef, SchemaRef),
@@ -330,6 +576,8 @@ pub fn get_transform_for_row(
 pub(crate) enum TransformExpr {
     Static(Expression),
     Partition(usize),
+    /// The [`METADATA_COLUMN_NAME`] column of the file
+    Metadata(MetadataColumns),
 }
 
 /// [`ScanMetadata`] contains (1) a batch of [`FilteredEngineData`] specifying data files to be scanned
@@ -378,20 +626,33 @@ impl HasSelectionVector for ScanMetadata {
 
 /// The result of building a scan over a table. This can be used to get the actual data from
 /// scanning the table.
+///
+/// A `Scan` is `Send + Sync`, as are the iterators returned by [`Scan::scan_metadata`] and
+/// [`Scan::execute`], so a scan can be shared by (or its iterators handed to) worker threads.
 pub struct Scan {
     snapshot: Arc<Snapshot>,
     logical_schema: SchemaRef,
+    // the logical schema with the scan's type coercions applied, if they changed it
+    coerced_schema: Option<SchemaRef>,
     physical_schema: SchemaRef,
     physical_predicate: PhysicalPredicate,
     all_fields: Arc<Vec<ColumnType>>,
     have_partition_cols: bool,
+    file_filter: Option<FileFilter>,
+    sample: Option<(f64, u64)>,
+    footer_reads_left: Option<AtomicUsize>,
+    metadata_columns: MetadataColumns,
 }
 
 impl std::fmt::Debug for Scan {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         f.debug_struct("Scan")
             .field("schema", &self.logical_schema)
+            .field("coerced_schema", &self.coerced_schema)
             .field("predicate", &self.physical_predicate)
+            .field("file_filter", &self.file_filter.is_some())
+            .field("sample", &self.sample)
+            .field("footer_reads_left", &self.footer_reads_left)
             .finish()
     }
 }
@@ -407,6 +668,74 @@ impl Scan {
         self.snapshot.table_root()
     }
 
+    /// Whether the scan has to read every file of the table, because neither data skipping nor
+    /// partition pruning can exclude any file. This is the case if the scan has no predicate, or
+    /// if its predicate references no partition column and the table doesn't collect statistics
+    /// (`delta.dataSkippingNumIndexedCols = 0`). Scans with a file filter or a sample are never
+    /// full scans.
+    pub fn requires_full_scan(&self) -> bool {
+        if self.file_filter.is_some() || self.sample.is_some() {
+            return false;
+        }
+        let referenced_schema = match &self.physical_predicate {
+            PhysicalPredicate::None => return true,
+            PhysicalPredicate::StaticSkipAll => return false,
+            PhysicalPredicate::Some(_, referenced_schema) => referenced_schema,
+        };
+        let stats_disabled = matches!(
+            self.snapshot
+                .table_properties()
+                .data_skipping_num_indexed_cols,
+            Some(DataSkippingNumIndexedCols::NumColumns(0))
+        );
+        let partition_columns = &self.snapshot.metadata().partition_columns;
+        let references_partition_column = partition_columns.iter().any(|column| {
+            self.logical_schema
+                .field(column)
+                .is_some_and(|field| referenced_schema.contains(field.physical_name()))
+        });
+        stats_disabled && !references_partition_column
+    }
+
+    /// The number of records in the scan file at `path` (relative to [`Self::table_root`]), given
+    /// the `stats` passed to the [`ScanCallback`] for it. Returns the `numRecords` statistic if
+    /// the file has one. Otherwise, if the scan was built with
+    /// [`ScanBuilder::with_footer_num_records`] and its footer budget is not used up yet, the
+    /// number of records is read from the file's parquet footer. Returns `None` if the number of
+    /// records is unknown.
+    ///
+    /// [`ScanCallback`]: crate::scan::state::ScanCallback
+    pub fn num_records(
+        &self,
+        engine: &dyn Engine,
+        path: &str,
+        size: i64,
+        stats: Option<&Stats>,
+    ) -> DeltaResult<Option<u64>> {
+        if let Some(stats) = stats {
+            return Ok(Some(stats.num_records));
+        }
+        let Some(footer_reads_left) = &self.footer_reads_left else {
+            return Ok(None);
+        };
+        let decrement = |left: usize| left.checked_sub(1);
+        if footer_reads_left
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, decrement)
+            .is_err()
+        {
+            return Ok(None);
+        }
+        let file = FileMeta {
+            location: self.table_root().join(path)?,
+            last_modified: 0,
+            size: size
+                .try_into()
+                .map_err(|_| Error::generic("Unable to convert scan file size into FileSize"))?,
+        };
+        let footer = engine.parquet_handler().read_parquet_footer(&file)?;
+        Ok(Some(footer.num_rows))
+    }
+
     /// Get a shared reference to the [`Snapshot`] of this scan.
     pub fn snapshot(&self) -> &Arc<Snapshot> {
         &self.snapshot
@@ -415,10 +744,12 @@ impl Scan {
     /// Get a shared reference to the logical [`Schema`] of the scan (i.e. the output schema of the
     /// scan). Note that the logical schema can differ from the physical schema due to e.g.
     /// partition columns which are present in the logical schema but not in the physical schema.
+    /// If the scan was built with [`ScanBuilder::with_type_coercions`], the logical schema has the
+    /// coerced types.
     ///
     /// [`Schema`]: crate::schema::Schema
     pub fn logical_schema(&self) -> &SchemaRef {
-        &self.logical_schema
+        self.coerced_schema.as_ref().unwrap_or(&self.logical_schema)
     }
 
     /// Get a shared reference to the physical [`Schema`] of the scan. This represents the schema
@@ -438,6 +769,27 @@ impl Scan {
         }
     }
 
+    /// The state executors need to read this scan's files, which can be serialized to plan the
+    /// scan on one node and read its files on others. See [`ScanState`] for details.
+    pub fn state(&self) -> ScanState {
+        ScanState::new(
+            self.table_root().clone(),
+            self.snapshot.version(),
+            self.logical_schema().clone(),
+            self.physical_schema.clone(),
+            self.snapshot.column_mapping_mode(),
+            self.physical_predicate(),
+        )
+    }
+
+    /// Explain how the scan's predicate skips the files of the table: which conjuncts of the
+    /// predicate are evaluated against partition values or file statistics, which cannot be used
+    /// for skipping, and the outcome of each conjunct for each file. This replays the log without
+    /// any skipping, so it is meant for debugging rather than for use on the hot path.
+    pub fn explain_skipping(&self, engine: &dyn Engine) -> DeltaResult<SkippingExplanation> {
+        SkippingExplanation::try_new(self, engine)
+    }
+
     /// Convert the parts of the transform that can be computed statically into `Expression`s. For
     /// parts that cannot be computed statically, include enough metadata so lower levels of
     /// processing can create and fill in an expression.
@@ -475,8 +827,26 @@ impl Scan {
     pub fn scan_metadata(
         &self,
         engine: &dyn Engine,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
-        self.scan_metadata_inner(engine, self.replay_for_scan_metadata(engine)?)
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>> + Send> {
+        let metrics = self.new_metrics_handle();
+        self.scan_metadata_inner(engine, self.replay_for_scan_metadata(engine)?, metrics)
+    }
+
+    /// Like [`Self::scan_metadata`], but also returns a handle to the [`ScanMetrics`] of the
+    /// returned iterator: the files it listed, pruned and selected, e.g. for engines to report in
+    /// query plans. Each call gets its own metrics, which are only complete once its iterator is
+    /// exhausted.
+    pub fn scan_metadata_with_metrics(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<(
+        impl Iterator<Item = DeltaResult<ScanMetadata>> + Send,
+        ScanMetricsHandle,
+    )> {
+        let metrics = self.new_metrics_handle();
+        let actions = self.replay_for_scan_metadata(engine)?;
+        let scan_metadata = self.scan_metadata_inner(engine, actions, metrics.clone())?;
+        Ok((scan_metadata, metrics))
     }
 
     /// Get an updated iterator of [`ScanMetadata`]s based on an existing iterator of [`EngineData`]s.
@@ -522,15 +892,20 @@ impl Scan {
     /// * `existing_predicate` - The predicate used by the previous scan.
     #[allow(unused)]
     #[internal_api]
-    pub(crate) fn scan_metadata_from(
+    pub(crate) fn scan_metadata_from<I>(
         &self,
         engine: &dyn Engine,
         existing_version: Version,
-        existing_data: impl IntoIterator<Item = Box<dyn EngineData>> + 'static,
+        existing_data: I,
         _existing_predicate: Option<PredicateRef>,
-    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<ScanMetadata>>>> {
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<ScanMetadata>> + Send>>
+    where
+        I: IntoIterator<Item = Box<dyn EngineData>> + 'static,
+        I::IntoIter: Send,
+    {
         static RESTORED_ADD_SCHEMA: LazyLock<DataType> = LazyLock::new(|| {
             let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
+            let tags = MapType::new(DataType::STRING, DataType::STRING, false);
             DataType::struct_type(vec![StructField::nullable(
                 "add",
                 DataType::struct_type(vec![
@@ -540,6 +915,9 @@ impl Scan {
                     StructField::nullable("modificationTime", DataType::LONG),
                     StructField::nullable("stats", DataType::STRING),
                     StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+                    StructField::nullable("baseRowId", DataType::LONG),
+                    StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+                    StructField::nullable("tags", tags),
                 ]),
             )])
         });
@@ -570,7 +948,8 @@ impl Scan {
         // to apply file skipping and provide the required transformations.
         if existing_version == self.snapshot.version() {
             let scan = existing_data.into_iter().map(apply_transform);
-            return Ok(Box::new(self.scan_metadata_inner(engine, scan)?));
+            let metrics = self.new_metrics_handle();
+            return Ok(Box::new(self.scan_metadata_inner(engine, scan, metrics)?));
         }
 
         let log_segment = self.snapshot.log_segment();
@@ -608,20 +987,41 @@ impl Scan {
             )?
             .chain(existing_data.into_iter().map(apply_transform));
 
-        Ok(Box::new(self.scan_metadata_inner(engine, it)?))
+        let metrics = self.new_metrics_handle();
+        Ok(Box::new(self.scan_metadata_inner(engine, it, metrics)?))
+    }
+
+    // The metrics of a new replay of the scan's metadata, with the scan's sampler if it has one.
+    // Each replay samples the files anew.
+    fn new_metrics_handle(&self) -> ScanMetricsHandle {
+        let sampler = self
+            .sample
+            .map(|(fraction, seed)| FileSampler::new(fraction, seed));
+        ScanMetricsHandle::new(sampler)
     }
 
     fn scan_metadata_inner(
         &self,
         engine: &dyn Engine,
-        action_batch_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
+        action_batch_iter: impl Iterator<Item = DeltaResult<ActionsBatch>> + Send,
+        metrics: ScanMetricsHandle,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>> + Send> {
         // Compute the static part of the transformation. This is `None` if no transformation is
-        // needed (currently just means no partition cols AND no column mapping but will be extended
-        // for other transforms as we support them)
+        // needed (currently just means no partition cols AND no column mapping AND no type
+        // coercions AND no metadata columns but will be extended for other transforms as we
+        // support them)
+        let has_metadata_columns = self.metadata_columns != MetadataColumns::default();
         let static_transform = (self.have_partition_cols
-            || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
-            .then(|| Arc::new(Scan::get_static_transform(&self.all_fields)));
+            || self.snapshot.column_mapping_mode() != ColumnMappingMode::None
+            || self.coerced_schema.is_some()
+            || has_metadata_columns)
+            .then(|| {
+                let mut transform = Scan::get_static_transform(&self.all_fields);
+                if has_metadata_columns {
+                    transform.push(TransformExpr::Metadata(self.metadata_columns.clone()));
+                }
+                Arc::new(transform)
+            });
         let physical_predicate = match self.physical_predicate.clone() {
             PhysicalPredicate::StaticSkipAll => return Ok(None.into_iter().flatten()),
             PhysicalPredicate::Some(predicate, schema) => Some((predicate, schema)),
@@ -632,7 +1032,13 @@ impl Scan {
             action_batch_iter,
             self.logical_schema.clone(),
             static_transform,
-            physical_predicate,
+            ScanLogReplayOptions {
+                physical_predicate,
+                coerced_schema: self.coerced_schema.clone(),
+                file_filter: self.file_filter.clone(),
+                sampler: metrics.sampler(),
+                metrics: Some(metrics),
+            },
         );
         Ok(Some(it).into_iter().flatten())
     }
@@ -663,106 +1069,343 @@ impl Scan {
     pub fn execute(
         &self,
         engine: Arc<dyn Engine>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<'_>> {
-        struct ScanFile {
-            path: String,
-            size: i64,
-            dv_info: DvInfo,
-            transform: Option<ExpressionRef>,
-        }
-        fn scan_metadata_callback(
-            batches: &mut Vec<ScanFile>,
-            path: &str,
-            size: i64,
-            _: Option<Stats>,
-            dv_info: DvInfo,
-            transform: Option<ExpressionRef>,
-            _: HashMap<String, String>,
-        ) {
-            batches.push(ScanFile {
-                path: path.to_string(),
-                size,
-                dv_info,
-                transform,
-            });
-        }
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + Send + use<'_>> {
+        let scan_files = self.scan_files(engine.clone())?;
+        Ok(read_scan_files(
+            engine,
+            self.table_root().clone(),
+            self.physical_schema().clone(),
+            self.logical_schema().clone(),
+            scan_files,
+        ))
+    }
 
-        debug!(
-            "Executing scan with logical schema {:#?} and physical schema {:#?}",
-            self.logical_schema, self.physical_schema
+    /// Split the data files of this scan into at most `num_partitions` [`ScanPartition`]s of
+    /// roughly equal size (in bytes), none of them empty. Each partition can be read independently
+    /// of the others, e.g. by a different thread or process, with [`execute_partition`]. Together
+    /// the partitions return the same data as [`Scan::execute`].
+    ///
+    /// This replays the log to find the files of the scan (see [`Scan::scan_metadata`]), and holds
+    /// all of them in memory.
+    pub fn partitions(
+        &self,
+        engine: &dyn Engine,
+        num_partitions: usize,
+    ) -> DeltaResult<Vec<ScanPartition>> {
+        require!(
+            num_partitions > 0,
+            Error::generic("Number of scan partitions must be positive")
         );
+        let mut scan_files = vec![];
+        for scan_metadata in self.scan_metadata(engine)? {
+            scan_files = scan_metadata?.visit_scan_files(scan_files, scan_file_callback)?;
+        }
 
-        let table_root = self.snapshot.table_root().clone();
+        // Assign the largest remaining file to the smallest partition
+        scan_files.sort_by_key(|scan_file| std::cmp::Reverse(scan_file.size));
+        let partition = ScanPartition {
+            table_root: self.table_root().clone(),
+            logical_schema: self.logical_schema().clone(),
+            physical_schema: self.physical_schema().clone(),
+            files: vec![],
+        };
+        let mut partitions = vec![partition; num_partitions.min(scan_files.len())];
+        let mut sizes: BinaryHeap<_> = (0..partitions.len())
+            .map(|index| std::cmp::Reverse((0, index)))
+            .collect();
+        for scan_file in scan_files {
+            let Some(std::cmp::Reverse((size, index))) = sizes.pop() else {
+                break;
+            };
+            sizes.push(std::cmp::Reverse((size + scan_file.size, index)));
+            partitions[index].files.push(scan_file);
+        }
+        Ok(partitions)
+    }
 
-        let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
-        let scan_files_iter = scan_metadata_iter
-            .map(|res| {
-                let scan_metadata = res?;
-                let scan_files = vec![];
-                scan_metadata.visit_scan_files(scan_files, scan_metadata_callback)
-            })
-            // Iterator<DeltaResult<Vec<ScanFile>>> to Iterator<DeltaResult<ScanFile>>
-            .flatten_ok();
+    /// Like [`Scan::execute`], but reads the table data through an [`AsyncParquetHandler`] and
+    /// returns a stream, so an async-native engine's reads are polled directly on the caller's
+    /// runtime instead of being handed over from a background executor.
+    ///
+    /// Log replay and deletion vector loading still go through the (synchronous) handlers of
+    /// `engine`, and run inline whenever the stream needs the next data file.
+    ///
+    /// [`AsyncParquetHandler`]: crate::AsyncParquetHandler
+    #[cfg(feature = "async")]
+    pub fn execute_async(
+        &self,
+        engine: Arc<dyn Engine>,
+        parquet_handler: Arc<dyn crate::AsyncParquetHandler>,
+    ) -> DeltaResult<impl futures::Stream<Item = DeltaResult<ScanResult>> + Send + use<'_>> {
+        use futures::{StreamExt as _, TryStreamExt as _};
 
-        let result = scan_files_iter
+        let table_root = self.snapshot.table_root().clone();
+        let scan_files = self.scan_files(engine.clone())?;
+        let result = futures::stream::iter(scan_files)
             .map(move |scan_file| -> DeltaResult<_> {
-                let scan_file = scan_file?;
-                let file_path = table_root.join(&scan_file.path)?;
-                let mut selection_vector = scan_file
-                    .dv_info
-                    .get_selection_vector(engine.as_ref(), &table_root)?;
-                let meta = FileMeta {
-                    last_modified: 0,
-                    size: scan_file.size.try_into().map_err(|_| {
-                        Error::generic("Unable to convert scan file size into FileSize")
-                    })?,
-                    location: file_path,
-                };
-
-                // WARNING: We validated the physical predicate against a schema that includes
-                // partition columns, but the read schema we use here does _NOT_ include partition
-                // columns. So we cannot safely assume that all column references are valid. See
-                // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
-                //
-                // TODO(#860): we disable predicate pushdown until we support row indexes.
-                let read_result_iter = engine.parquet_handler().read_parquet_files(
+                let (scan_file, mut selection_vector) = scan_file?;
+                let meta = scan_file.file_meta(&table_root)?;
+
+                // See `execute` for why no predicate is pushed down here.
+                let read_result_stream = parquet_handler.read_parquet_files(
                     &[meta],
                     self.physical_schema().clone(),
                     None,
                 )?;
 
-                // Arc clones
                 let engine = engine.clone();
-                Ok(read_result_iter.map(move |read_result| -> DeltaResult<_> {
-                    let read_result = read_result?;
-                    // transform the physical data into the correct logical form
-                    let logical = state::transform_to_logical(
+                Ok(read_result_stream.map(move |read_result| {
+                    scan_result(
                         engine.as_ref(),
-                        read_result,
+                        read_result?,
                         self.physical_schema(),
                         self.logical_schema(),
                         &scan_file.transform,
-                    );
-                    let len = logical.as_ref().map_or(0, |res| res.len());
-                    // need to split the dv_mask. what's left in dv_mask covers this result, and rest
-                    // will cover the following results. we `take()` out of `selection_vector` to avoid
-                    // trying to return a captured variable. We're going to reassign `selection_vector`
-                    // to `rest` in a moment anyway
-                    let mut sv = selection_vector.take();
-                    let rest = split_vector(sv.as_mut(), len, None);
-                    let result = ScanResult {
-                        raw_data: logical,
-                        raw_mask: sv,
-                    };
-                    selection_vector = rest;
-                    Ok(result)
+                        &mut selection_vector,
+                    )
                 }))
             })
-            // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
+            .try_flatten();
+        Ok(result)
+    }
+
+    // The files to read for this scan, each paired with the selection vector from its deletion
+    // vector (if any).
+    fn scan_files(
+        &self,
+        engine: Arc<dyn Engine>,
+    ) -> DeltaResult<
+        impl Iterator<Item = DeltaResult<(ScanFile, Option<Vec<bool>>)>> + Send + use<'_>,
+    > {
+        debug!(
+            "Executing scan with logical schema {:#?} and physical schema {:#?}",
+            self.logical_schema, self.physical_schema
+        );
+
+        let table_root = self.snapshot.table_root().clone();
+        let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
+        let scan_files_iter = scan_metadata_iter
+            .map(|res| {
+                let scan_metadata = res?;
+                let scan_files = vec![];
+                scan_metadata.visit_scan_files(scan_files, scan_file_callback)
+            })
+            .map(move |scan_files| load_deletion_vectors(engine.as_ref(), &table_root, scan_files?))
+            // Iterator<DeltaResult<Iterator<DeltaResult<_>>>> to Iterator<DeltaResult<(ScanFile, _)>>
             .flatten_ok()
-            // Iterator<DeltaResult<DeltaResult<ScanResult>>> to Iterator<DeltaResult<ScanResult>>
             .map(|x| x?);
-        Ok(result)
+        Ok(scan_files_iter)
+    }
+}
+
+// The callback to collect the scan files of scan metadata, see `ScanMetadata::visit_scan_files`
+fn scan_file_callback(
+    batches: &mut Vec<ScanFile>,
+    path: &str,
+    size: i64,
+    _: Option<Stats>,
+    dv_info: DvInfo,
+    transform: Option<ExpressionRef>,
+    _: HashMap<String, String>,
+) {
+    batches.push(ScanFile {
+        path: path.to_string(),
+        size,
+        dv_info,
+        transform,
+    });
+}
+
+// Pair each scan file with its selection vector. All the deletion vectors of a batch that
+// are stored in files are requested from the storage handler up front with a single
+// `read_files` call, so the engine can fetch them concurrently (bounded by its own limits,
+// e.g. the default engine's readahead) while earlier files of the batch are being read.
+fn load_deletion_vectors(
+    engine: &dyn Engine,
+    table_root: &Url,
+    scan_files: Vec<ScanFile>,
+) -> DeltaResult<impl Iterator<Item = DeltaResult<(ScanFile, Option<Vec<bool>>)>>> {
+    let dv_files: Vec<FileSlice> = scan_files
+        .iter()
+        .filter_map(|scan_file| scan_file.dv_info.dv_file_path(table_root).transpose())
+        .map_ok(|path| (path, None))
+        .try_collect()?;
+    let mut dv_data: Box<dyn Iterator<Item = DeltaResult<Bytes>> + Send> = if dv_files.is_empty() {
+        Box::new(std::iter::empty())
+    } else {
+        engine.storage_handler().read_files(dv_files)?
+    };
+    let table_root = table_root.clone();
+    Ok(scan_files.into_iter().map(move |scan_file| {
+        let selection_vector = scan_file
+            .dv_info
+            .get_selection_vector_from(&table_root, &mut dv_data)?;
+        Ok((scan_file, selection_vector))
+    }))
+}
+
+/// Read the data files of `partition`, one of the [`Scan::partitions`] of a scan. Like
+/// [`Scan::execute`], each [`ScanResult`] holds data in the logical schema of the scan and the
+/// selection vector from the file's deletion vector, if any.
+///
+/// The partition carries everything needed to read its files, so this doesn't need the [`Scan`]
+/// (or even its snapshot), e.g. when the partition was deserialized by a different process.
+pub fn execute_partition(
+    engine: Arc<dyn Engine>,
+    partition: ScanPartition,
+) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + Send> {
+    let ScanPartition {
+        table_root,
+        logical_schema,
+        physical_schema,
+        files,
+    } = partition;
+    let scan_files = load_deletion_vectors(engine.as_ref(), &table_root, files)?;
+    Ok(read_scan_files(
+        engine,
+        table_root,
+        physical_schema,
+        logical_schema,
+        scan_files,
+    ))
+}
+
+// Read the data of each scan file, paired with the selection vector from its deletion vector
+fn read_scan_files<'a>(
+    engine: Arc<dyn Engine>,
+    table_root: Url,
+    physical_schema: SchemaRef,
+    logical_schema: SchemaRef,
+    scan_files: impl Iterator<Item = DeltaResult<(ScanFile, Option<Vec<bool>>)>> + Send + 'a,
+) -> impl Iterator<Item = DeltaResult<ScanResult>> + Send + 'a {
+    scan_files
+        .map(move |scan_file| -> DeltaResult<_> {
+            let (scan_file, mut selection_vector) = scan_file?;
+            let meta = scan_file.file_meta(&table_root)?;
+
+            // WARNING: We validated the physical predicate against a schema that includes
+            // partition columns, but the read schema we use here does _NOT_ include partition
+            // columns. So we cannot safely assume that all column references are valid. See
+            // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
+            //
+            // TODO(#860): we disable predicate pushdown until we support row indexes.
+            let read_result_iter = engine.parquet_handler().read_parquet_files(
+                &[meta],
+                physical_schema.clone(),
+                None,
+            )?;
+
+            // Arc clones
+            let engine = engine.clone();
+            let physical_schema = physical_schema.clone();
+            let logical_schema = logical_schema.clone();
+            Ok(read_result_iter.map(move |read_result| {
+                scan_result(
+                    engine.as_ref(),
+                    read_result?,
+                    &physical_schema,
+                    &logical_schema,
+                    &scan_file.transform,
+                    &mut selection_vector,
+                )
+            }))
+        })
+        // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
+        .flatten_ok()
+        // Iterator<DeltaResult<DeltaResult<ScanResult>>> to Iterator<DeltaResult<ScanResult>>
+        .map(|x| x?)
+}
+
+// Transform one batch read from a scan file into its logical form, and split off the part of the
+// file's selection vector that covers it.
+fn scan_result(
+    engine: &dyn Engine,
+    read_result: Box<dyn EngineData>,
+    physical_schema: &SchemaRef,
+    logical_schema: &SchemaRef,
+    transform: &Option<ExpressionRef>,
+    selection_vector: &mut Option<Vec<bool>>,
+) -> DeltaResult<ScanResult> {
+    // transform the physical data into the correct logical form
+    let logical = state::transform_to_logical(
+        engine,
+        read_result,
+        physical_schema,
+        logical_schema,
+        transform,
+    );
+    let len = logical.as_ref().map_or(0, |res| res.len());
+    // need to split the dv_mask. what's left in dv_mask covers this result, and rest will cover
+    // the following results. we `take()` out of `selection_vector` and then reassign it to `rest`.
+    let mut sv = selection_vector.take();
+    let rest = split_vector(sv.as_mut(), len, None);
+    *selection_vector = rest;
+    Ok(ScanResult {
+        raw_data: logical,
+        raw_mask: sv,
+    })
+}
+
+/// A share of the data files of a [`Scan`], to be read with [`execute_partition`]. See
+/// [`Scan::partitions`].
+///
+/// Besides its files, a partition holds the table root and the schemas of its scan, so it can be
+/// [serialized](Self::serialize) to read it in another process. Partitions of scans whose file
+/// transforms contain opaque expressions, e.g. scans with [type coercions] or materialized row
+/// tracking columns, cannot be serialized.
+///
+/// [type coercions]: ScanBuilder::with_type_coercions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanPartition {
+    table_root: Url,
+    logical_schema: SchemaRef,
+    physical_schema: SchemaRef,
+    files: Vec<ScanFile>,
+}
+
+impl ScanPartition {
+    /// Serialize the partition, e.g. to send it to the executor that reads it.
+    pub fn serialize(&self) -> DeltaResult<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Deserialize a partition serialized with [`Self::serialize`].
+    pub fn deserialize(bytes: &[u8]) -> DeltaResult<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// The number of data files in the partition.
+    pub fn num_files(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The total size in bytes of the data files in the partition.
+    pub fn size_in_bytes(&self) -> i64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+}
+
+// A data file to read in [`Scan::execute`], as visited from the scan metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanFile {
+    path: String,
+    size: i64,
+    dv_info: DvInfo,
+    transform: Option<ExpressionRef>,
+}
+
+impl ScanFile {
+    fn file_meta(&self, table_root: &Url) -> DeltaResult<FileMeta> {
+        Ok(FileMeta {
+            last_modified: 0,
+            size: self
+                .size
+                .try_into()
+                .map_err(|_| Error::generic("Unable to convert scan file size into FileSize"))?,
+            location: table_root.join(&self.path)?,
+        })
     }
 }
 
@@ -783,7 +1426,10 @@ impl Scan {
 ///      cardinality: long,
 ///    },
 ///    fileConstantValues: {
-///      partitionValues: map<string, string>
+///      partitionValues: map<string, string>,
+///      baseRowId: long,
+///      defaultRowCommitVersion: long,
+///      tags: map<string, string>,
 ///    }
 /// }
 /// ```
@@ -980,7 +1626,7 @@ pub(crate) mod test_utils {
                 .map(|batch| Ok(ActionsBatch::new(batch as _, true))),
             logical_schema,
             transform,
-            None,
+            Default::default(),
         );
         let mut batch_count = 0;
         for res in iter {
@@ -999,8 +1645,9 @@ pub(crate) mod test_utils {
 mod tests {
     use std::path::PathBuf;
 
-    use crate::arrow::array::BooleanArray;
+    use crate::arrow::array::{Array as _, AsArray as _, BooleanArray};
     use crate::arrow::compute::filter_record_batch;
+    use crate::arrow::datatypes::{Int64Type, TimestampMicrosecondType};
     use crate::arrow::record_batch::RecordBatch;
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::sync::SyncEngine;
@@ -1175,8 +1822,15 @@ mod tests {
         }
     }
 
-    fn get_files_for_scan(scan: Scan, engine: &dyn Engine) -> DeltaResult<Vec<String>> {
-        let scan_metadata_iter = scan.scan_metadata(engine)?;
+    fn get_files_for_scan(scan: &Scan, engine: &dyn Engine) -> DeltaResult<Vec<String>> {
+        Ok(get_files_and_metrics_for_scan(scan, engine)?.0)
+    }
+
+    fn get_files_and_metrics_for_scan(
+        scan: &Scan,
+        engine: &dyn Engine,
+    ) -> DeltaResult<(Vec<String>, ScanMetricsHandle)> {
+        let (scan_metadata_iter, metrics) = scan.scan_metadata_with_metrics(engine)?;
         fn scan_metadata_callback(
             paths: &mut Vec<String>,
             path: &str,
@@ -1194,7 +1848,7 @@ mod tests {
             let scan_metadata = res?;
             files = scan_metadata.visit_scan_files(files, scan_metadata_callback)?;
         }
-        Ok(files)
+        Ok((files, metrics))
     }
 
     #[test]
@@ -1206,7 +1860,7 @@ mod tests {
 
         let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
         let scan = snapshot.into_scan_builder().build().unwrap();
-        let files = get_files_for_scan(scan, &engine).unwrap();
+        let files = get_files_for_scan(&scan, &engine).unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(
             files[0],
@@ -1263,6 +1917,541 @@ mod tests {
         assert_eq!(new_files.len(), 1);
     }
 
+    #[test]
+    fn test_scan_with_file_filter() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+
+        let all_files =
+            get_files_for_scan(&snapshot.clone().scan_builder().build().unwrap(), &engine).unwrap();
+        assert_eq!(all_files.len(), 6);
+
+        // exclude the files of partition `letter=a`, and record which files the filter saw
+        let seen = Arc::new(std::sync::Mutex::new(vec![]));
+        let scan = snapshot
+            .scan_builder()
+            .with_file_filter({
+                let seen = seen.clone();
+                move |add: &AddInfo<'_>| {
+                    assert!(add.size > 0);
+                    seen.lock().unwrap().push(add.path.to_string());
+                    add.partition_values.get("letter").map(String::as_str) != Some("a")
+                }
+            })
+            .build()
+            .unwrap();
+        let files = get_files_for_scan(&scan, &engine).unwrap();
+        assert_eq!(files.len(), 4);
+        assert!(files.iter().all(|path| !path.starts_with("letter=a/")));
+
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        let mut all_files = all_files;
+        all_files.sort();
+        assert_eq!(seen, all_files);
+    }
+
+    #[test]
+    fn test_scan_with_sample() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+        let (_, metrics) = get_files_and_metrics_for_scan(&scan, &engine).unwrap();
+        assert_eq!(metrics.sample_report(), None);
+
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_sample(0.5, 7)
+            .build()
+            .unwrap();
+        let (files, metrics) = get_files_and_metrics_for_scan(&scan, &engine).unwrap();
+        let report = metrics.sample_report().unwrap();
+        assert_eq!(report.total_files, 6);
+        assert_eq!(report.total_rows, 6);
+        assert_eq!(report.files_without_stats, 0);
+        assert_eq!(report.sampled_files, files.len() as u64);
+        assert_eq!(report.sampled_rows, files.len() as u64);
+
+        // sampling is deterministic, and each replay has its own report
+        let (second_files, second) = get_files_and_metrics_for_scan(&scan, &engine).unwrap();
+        assert_eq!(second_files, files);
+        assert_eq!(second.sample_report().unwrap(), report);
+        assert_eq!(metrics.sample_report().unwrap(), report);
+
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_sample(1.0, 7)
+            .build()
+            .unwrap();
+        let (files, metrics) = get_files_and_metrics_for_scan(&scan, &engine).unwrap();
+        assert_eq!(files.len(), 6);
+        assert_eq!(
+            metrics.sample_report().unwrap().achieved_fraction(),
+            Some(1.0)
+        );
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_sample(0.0, 7)
+            .build()
+            .unwrap();
+        assert!(get_files_for_scan(&scan, &engine).unwrap().is_empty());
+
+        assert!(snapshot.scan_builder().with_sample(1.5, 7).build().is_err());
+    }
+
+    #[test]
+    fn test_scan_metrics() {
+        // replay the scan's metadata, returning its metrics
+        fn replay_metrics(scan: &Scan, engine: &dyn Engine) -> ScanMetrics {
+            let (scan_metadata, metrics) = scan.scan_metadata_with_metrics(engine).unwrap();
+            assert_eq!(metrics.metrics(), ScanMetrics::default());
+            scan_metadata.for_each(|res| drop(res.unwrap()));
+            metrics.metrics()
+        }
+
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+        let metrics = replay_metrics(&scan, &engine);
+        let expected = ScanMetrics {
+            files_listed: 6,
+            files_pruned_by_partition: 0,
+            files_pruned_by_stats: 0,
+            files_selected: 6,
+            bytes_scanned: 5 * 751 + 750,
+        };
+        assert_eq!(metrics, expected);
+
+        // the file of partition `letter=a` with `number = 1` is pruned by stats, and the files of
+        // the other partitions by their partition values
+        let predicate = Pred::and(
+            column_expr!("letter").eq(Expr::literal("a")),
+            column_expr!("number").gt(Expr::literal(1i64)),
+        );
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .unwrap();
+        let expected = ScanMetrics {
+            files_listed: 6,
+            files_pruned_by_partition: 4,
+            files_pruned_by_stats: 1,
+            files_selected: 1,
+            bytes_scanned: 751,
+        };
+        assert_eq!(replay_metrics(&scan, &engine), expected);
+
+        // concurrent replays of the same scan each get their own metrics
+        let (first, first_metrics) = scan.scan_metadata_with_metrics(&engine).unwrap();
+        let (second, second_metrics) = scan.scan_metadata_with_metrics(&engine).unwrap();
+        first.for_each(|res| drop(res.unwrap()));
+        assert_eq!(first_metrics.metrics(), expected);
+        assert_eq!(second_metrics.metrics(), ScanMetrics::default());
+        second.for_each(|res| drop(res.unwrap()));
+        assert_eq!(second_metrics.metrics(), expected);
+        assert_eq!(first_metrics.metrics(), expected);
+    }
+
+    #[test]
+    fn test_row_tracking_columns() {
+        // a table with row tracking and two copies of the same 10-row file, only one of which has
+        // row tracking information
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("_delta_log")).unwrap();
+        let parquet_file = "./tests/data/table-without-dv-small/part-00000-517f5d32-9c95-48e8-82b4-0229cc194867-c000.snappy.parquet";
+        std::fs::copy(parquet_file, dir.path().join("a.parquet")).unwrap();
+        std::fs::copy(parquet_file, dir.path().join("b.parquet")).unwrap();
+        let commit = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":7,"writerFeatures":["rowTracking","domainMetadata"]}}"#,
+            r#"{"metaData":{"id":"test","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{"delta.enableRowTracking":"true"},"createdTime":1587968585495}}"#,
+            r#"{"add":{"path":"a.parquet","partitionValues":{},"size":548,"modificationTime":1587968586000,"dataChange":true,"baseRowId":100,"defaultRowCommitVersion":3}}"#,
+            r#"{"add":{"path":"b.parquet","partitionValues":{},"size":548,"modificationTime":1587968586000,"dataChange":true}}"#,
+        ];
+        let log_file = dir.path().join("_delta_log/00000000000000000000.json");
+        std::fs::write(log_file, commit.join("\n")).unwrap();
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+
+        let scan = snapshot
+            .scan_builder()
+            .with_row_tracking_columns()
+            .build()
+            .unwrap();
+        let metadata_field = scan.logical_schema().field(METADATA_COLUMN_NAME).unwrap();
+        let expected_field = MetadataColumns {
+            row_tracking: true,
+            ..Default::default()
+        }
+        .field();
+        assert_eq!(Some(metadata_field), expected_field.as_ref());
+
+        let mut rows = vec![];
+        for res in scan.execute(engine).unwrap() {
+            let data = res.unwrap().raw_data.unwrap();
+            let batch = ArrowEngineData::try_from_engine_data(data).unwrap();
+            let batch = batch.record_batch();
+            let values = batch.column(0).as_primitive::<Int64Type>();
+            let metadata = batch.column(1).as_struct();
+            let row_ids = metadata.column(0).as_primitive::<Int64Type>();
+            let row_commit_versions = metadata.column(1).as_primitive::<Int64Type>();
+            for i in 0..batch.num_rows() {
+                rows.push((
+                    values.value(i),
+                    row_ids.is_valid(i).then(|| row_ids.value(i)),
+                    row_commit_versions
+                        .is_valid(i)
+                        .then(|| row_commit_versions.value(i)),
+                ));
+            }
+        }
+        rows.sort();
+        let expected: Vec<_> = (0..10)
+            .flat_map(|value| [(value, None, None), (value, Some(100 + value), Some(3))])
+            .collect();
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn test_materialized_row_tracking_columns() {
+        use crate::arrow::array::Int64Array;
+        use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+        use crate::parquet::arrow::ArrowWriter;
+
+        // a file rewritten by an update: the first two rows were copied with their row ids and
+        // commit versions, the last row is new
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("_delta_log")).unwrap();
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("value", ArrowDataType::Int64, true),
+            Field::new("_row-id-col", ArrowDataType::Int64, true),
+            Field::new("_row-commit-version-col", ArrowDataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(Int64Array::from(vec![Some(7), Some(8), None])),
+                Arc::new(Int64Array::from(vec![Some(1), Some(2), None])),
+            ],
+        )
+        .unwrap();
+        let file = std::fs::File::create(dir.path().join("a.parquet")).unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let size = std::fs::metadata(dir.path().join("a.parquet"))
+            .unwrap()
+            .len();
+        let commit = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":7,"writerFeatures":["rowTracking","domainMetadata"]}}"#.to_string(),
+            r#"{"metaData":{"id":"test","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{"delta.enableRowTracking":"true","delta.rowTracking.materializedRowIdColumnName":"_row-id-col","delta.rowTracking.materializedRowCommitVersionColumnName":"_row-commit-version-col"},"createdTime":1587968585495}}"#.to_string(),
+            format!(r#"{{"add":{{"path":"a.parquet","partitionValues":{{}},"size":{size},"modificationTime":1587968586000,"dataChange":true,"baseRowId":100,"defaultRowCommitVersion":3}}}}"#),
+        ];
+        let log_file = dir.path().join("_delta_log/00000000000000000000.json");
+        std::fs::write(log_file, commit.join("\n")).unwrap();
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+
+        let scan = snapshot
+            .scan_builder()
+            .with_row_tracking_columns()
+            .build()
+            .unwrap();
+        let mut rows = vec![];
+        for res in scan.execute(engine).unwrap() {
+            let data = res.unwrap().raw_data.unwrap();
+            let batch = ArrowEngineData::try_from_engine_data(data).unwrap();
+            let batch = batch.record_batch();
+            assert_eq!(batch.num_columns(), 2);
+            let values = batch.column(0).as_primitive::<Int64Type>();
+            let metadata = batch.column(1).as_struct();
+            let row_ids = metadata.column(0).as_primitive::<Int64Type>();
+            let row_commit_versions = metadata.column(1).as_primitive::<Int64Type>();
+            for i in 0..batch.num_rows() {
+                rows.push((
+                    values.value(i),
+                    row_ids.value(i),
+                    row_commit_versions.value(i),
+                ));
+            }
+        }
+        assert_eq!(rows, [(1, 7, 1), (2, 8, 2), (3, 102, 3)]);
+    }
+
+    #[test]
+    fn test_file_metadata_columns() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), engine.as_ref(), None).unwrap());
+        let scan = snapshot
+            .scan_builder()
+            .with_file_metadata_columns()
+            .build()
+            .unwrap();
+        let file_url = url
+            .join("part-00000-517f5d32-9c95-48e8-82b4-0229cc194867-c000.snappy.parquet")
+            .unwrap();
+
+        let mut num_rows = 0;
+        for res in scan.execute(engine).unwrap() {
+            let data = res.unwrap().raw_data.unwrap();
+            let batch = ArrowEngineData::try_from_engine_data(data).unwrap();
+            let batch = batch.record_batch();
+            let metadata = batch.column(1).as_struct();
+            let file_paths = metadata.column(0).as_string::<i32>();
+            let file_sizes = metadata.column(1).as_primitive::<Int64Type>();
+            let modification_times = metadata
+                .column(2)
+                .as_primitive::<TimestampMicrosecondType>();
+            for i in 0..batch.num_rows() {
+                assert_eq!(file_paths.value(i), file_url.as_str());
+                assert_eq!(file_sizes.value(i), 548);
+                assert_eq!(modification_times.value(i), 1678020185157000);
+            }
+            num_rows += batch.num_rows();
+        }
+        assert_eq!(num_rows, 10);
+    }
+
+    #[test]
+    fn test_row_tracking_columns_unsupported() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+        let result = snapshot.scan_builder().with_row_tracking_columns().build();
+        assert!(matches!(result, Err(Error::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_scan_without_stats() {
+        // a table that doesn't collect stats, with one file without stats and one with stats that
+        // only has min/max values
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("_delta_log")).unwrap();
+        let commit = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+            r#"{"metaData":{"id":"test","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"a\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"p\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["p"],"configuration":{"delta.dataSkippingNumIndexedCols":"0"},"createdTime":1587968585495}}"#,
+            r#"{"add":{"path":"p=x/a.parquet","partitionValues":{"p":"x"},"size":10,"modificationTime":1587968586000,"dataChange":true}}"#,
+            r#"{"add":{"path":"p=y/b.parquet","partitionValues":{"p":"y"},"size":10,"modificationTime":1587968586000,"dataChange":true,"stats":"{\"minValues\":{\"a\":5}}"}}"#,
+        ];
+        let log_file = dir.path().join("_delta_log/00000000000000000000.json");
+        std::fs::write(log_file, commit.join("\n")).unwrap();
+        let url = url::Url::from_directory_path(dir.path()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+
+        // without stats, a predicate on a data column can't skip anything...
+        let predicate = Arc::new(column_expr!("a").gt(Expr::literal(10)));
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(predicate)
+            .build()
+            .unwrap();
+        assert!(scan.requires_full_scan());
+        fn collect_stats(
+            stats: &mut Vec<Option<Stats>>,
+            _: &str,
+            _: i64,
+            file_stats: Option<Stats>,
+            _: DvInfo,
+            _: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            stats.push(file_stats);
+        }
+        let mut stats = vec![];
+        for res in scan.scan_metadata(&engine).unwrap() {
+            stats = res.unwrap().visit_scan_files(stats, collect_stats).unwrap();
+        }
+        assert_eq!(stats, [None, None]);
+
+        // ... but one on a partition column can, as can a scan without stats
+        let predicate = Arc::new(column_expr!("p").eq(Expr::literal("x")));
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(predicate)
+            .build()
+            .unwrap();
+        assert!(!scan.requires_full_scan());
+        assert!(snapshot
+            .scan_builder()
+            .build()
+            .unwrap()
+            .requires_full_scan());
+
+        // a table with stats can skip files using any column
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+        let predicate = Arc::new(column_expr!("value").gt(Expr::literal(10)));
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(predicate)
+            .build()
+            .unwrap();
+        assert!(!scan.requires_full_scan());
+    }
+
+    #[test]
+    fn test_num_records_from_footer() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None).unwrap());
+        let file = "part-00000-517f5d32-9c95-48e8-82b4-0229cc194867-c000.snappy.parquet";
+        let stats = Stats { num_records: 7 };
+
+        // without footer reads, only stats are used
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+        let num_records = |scan: &Scan, stats| scan.num_records(&engine, file, 0, stats).unwrap();
+        assert_eq!(num_records(&scan, Some(&stats)), Some(7));
+        assert_eq!(num_records(&scan, None), None);
+
+        // footer reads are limited to the given number of files
+        let scan = snapshot
+            .scan_builder()
+            .with_footer_num_records(1)
+            .build()
+            .unwrap();
+        assert_eq!(num_records(&scan, Some(&stats)), Some(7));
+        assert_eq!(num_records(&scan, None), Some(10));
+        assert_eq!(num_records(&scan, None), None);
+    }
+
+    #[test]
+    fn test_scan_with_type_coercions() {
+        use crate::arrow::array::{AsArray as _, Float64Array, Int64Array};
+        use crate::arrow::datatypes::{DataType as ArrowDataType, Float64Type, Int64Type};
+
+        fn read(scan: &Scan, engine: Arc<dyn Engine>) -> Vec<RecordBatch> {
+            scan.execute(engine)
+                .unwrap()
+                .map(|res| {
+                    let data = res.unwrap().raw_data.unwrap();
+                    let data = ArrowEngineData::try_from_engine_data(data).unwrap();
+                    data.record_batch().clone()
+                })
+                .collect()
+        }
+
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic-decimal-table/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+
+        // decimals as strings, including the partition column
+        let coercions = TypeCoercions::default().with_decimals(DecimalCoercion::String);
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_type_coercions(coercions)
+            .build()
+            .unwrap();
+        assert!(scan
+            .logical_schema()
+            .fields()
+            .all(|field| field.data_type() == &DataType::STRING));
+        let mut values: Vec<_> = read(&scan, engine.clone())
+            .iter()
+            .flat_map(|batch| {
+                assert_eq!(batch.num_columns(), 4);
+                let part = batch.column(0).as_string::<i32>();
+                let col1 = batch.column(1).as_string::<i32>();
+                part.iter()
+                    .zip(col1)
+                    .map(|(p, c)| format!("{}/{}", p.unwrap(), c.unwrap()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        values.sort();
+        let expected = [
+            "-2342342.23423/-999.99",
+            "0.00004/0.00",
+            "234.00000/1.00",
+            "2342222.23454/111.11",
+        ];
+        assert_eq!(values, expected);
+
+        // col3 is decimal(20, 10), which doesn't fit in a double
+        let coercions = TypeCoercions::default().with_decimals(DecimalCoercion::Double);
+        let result = snapshot
+            .clone()
+            .scan_builder()
+            .with_type_coercions(coercions)
+            .build();
+        assert!(result.is_err());
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_type_coercions(coercions.with_allow_lossy(true))
+            .build()
+            .unwrap();
+        let mut col1: Vec<f64> = read(&scan, engine.clone())
+            .iter()
+            .flat_map(|batch| {
+                assert_eq!(batch.column(3).data_type(), &ArrowDataType::Float64);
+                let col1: &Float64Array = batch.column(1).as_primitive::<Float64Type>();
+                col1.values().to_vec()
+            })
+            .collect();
+        col1.sort_by(f64::total_cmp);
+        assert_eq!(col1, [-999.99, 0.0, 1.0, 111.11]);
+
+        // timestamps as microseconds
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/data-reader-timestamp_ntz/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let snapshot = Snapshot::try_new(url.clone(), engine.as_ref(), None).unwrap();
+        let coercions = TypeCoercions::default().with_timestamps(TimestampCoercion::MicrosInt64);
+        let scan = snapshot
+            .into_scan_builder()
+            .with_type_coercions(coercions)
+            .build()
+            .unwrap();
+        let micros: Vec<_> = read(&scan, engine.clone())
+            .iter()
+            .flat_map(|batch| {
+                let ts: &Int64Array = batch.column(1).as_primitive::<Int64Type>();
+                let part: &Int64Array = batch.column(2).as_primitive::<Int64Type>();
+                ts.iter().chain(part.iter()).collect::<Vec<_>>()
+            })
+            .flatten()
+            .collect();
+        assert!(micros.contains(&1637202600123456));
+
+        // the file modification time of the metadata column is a timestamp too
+        let scan = Snapshot::try_new(url, engine.as_ref(), None)
+            .unwrap()
+            .into_scan_builder()
+            .with_file_metadata_columns()
+            .with_type_coercions(coercions)
+            .build()
+            .unwrap();
+        for batch in read(&scan, engine.clone()) {
+            let metadata = batch.column_by_name(METADATA_COLUMN_NAME).unwrap();
+            let modification_time = metadata.as_struct().column(2);
+            assert_eq!(modification_time.data_type(), &ArrowDataType::Int64);
+        }
+    }
+
     // reading v0 with 3 files.
     // updating to v1 with 3 more files added.
     #[test_log::test]
@@ -1418,6 +2607,171 @@ mod tests {
         assert_eq!(data.len(), 1);
     }
 
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_execute_async() {
+        use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+        use crate::engine::default::DefaultEngine;
+        use crate::object_store::local::LocalFileSystem;
+        use futures::TryStreamExt as _;
+
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = Arc::new(DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        ));
+        let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+        let scan = snapshot.scan_builder().build().unwrap();
+
+        let expected: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
+        let actual: Vec<_> = scan
+            .execute_async(engine.clone(), engine.async_parquet_handler())
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.into_iter().zip(expected) {
+            assert_eq!(actual.raw_mask(), expected.raw_mask());
+            let actual = actual.raw_data.unwrap();
+            let expected = expected.raw_data.unwrap();
+            assert_eq!(actual.len(), 10);
+            assert_eq!(
+                ArrowEngineData::try_from_engine_data(actual)
+                    .unwrap()
+                    .record_batch(),
+                ArrowEngineData::try_from_engine_data(expected)
+                    .unwrap()
+                    .record_batch()
+            );
+        }
+    }
+
+    #[test]
+    fn test_scan_partitions() {
+        // The number of rows (after applying deletion vectors) read by a scan's results
+        fn count_rows(results: impl Iterator<Item = DeltaResult<ScanResult>>) -> usize {
+            results
+                .map(|result| {
+                    let result = result.unwrap();
+                    let mask = result.full_mask();
+                    let len = result.raw_data.unwrap().len();
+                    mask.map_or(len, |mask| mask.into_iter().filter(|row| *row).count())
+                })
+                .sum()
+        }
+
+        let engine: Arc<dyn Engine> = Arc::new(SyncEngine::new());
+        for (table, num_files) in [("basic_partitioned", 6), ("table-with-dv-small", 1)] {
+            let path = std::fs::canonicalize(PathBuf::from(format!("./tests/data/{table}/")));
+            let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+            let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+            let scan = snapshot.scan_builder().build().unwrap();
+            let expected_rows = count_rows(scan.execute(engine.clone()).unwrap());
+
+            let partitions = scan.partitions(engine.as_ref(), 4).unwrap();
+            assert_eq!(partitions.len(), num_files.min(4));
+            let files: usize = partitions.iter().map(ScanPartition::num_files).sum();
+            assert_eq!(files, num_files);
+            assert!(partitions.iter().all(|partition| partition.num_files() > 0));
+
+            let rows: usize = std::thread::scope(|s| {
+                let handles: Vec<_> = partitions
+                    .into_iter()
+                    .map(|partition| {
+                        // partitions are self-contained, so they can be read in another process
+                        let bytes = partition.serialize().unwrap();
+                        let engine = engine.clone();
+                        s.spawn(move || {
+                            let partition = ScanPartition::deserialize(&bytes).unwrap();
+                            count_rows(execute_partition(engine, partition).unwrap())
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .sum()
+            });
+            assert_eq!(rows, expected_rows);
+        }
+
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let snapshot = Arc::new(Snapshot::try_new(url, engine.as_ref(), None).unwrap());
+        let scan = snapshot.scan_builder().build().unwrap();
+        assert!(scan.partitions(engine.as_ref(), 0).is_err());
+    }
+
+    #[test]
+    fn test_scan_state_round_trip() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None).unwrap());
+        let predicate = Pred::and(
+            Pred::eq(column_expr!("letter"), Expr::literal("a")),
+            Pred::gt(column_expr!("number"), Expr::literal(2i64)),
+        );
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .unwrap();
+
+        let state = scan.state();
+        assert_eq!(state.table_root(), &url);
+        assert_eq!(state.version(), scan.snapshot().version());
+        assert_eq!(state.logical_schema(), scan.logical_schema());
+        assert_eq!(state.physical_schema(), scan.physical_schema());
+        assert_eq!(state.predicate(), scan.physical_predicate().as_ref());
+        assert!(state.predicate().is_some());
+
+        let bytes = state.serialize().unwrap();
+        let state = ScanState::deserialize(&bytes).unwrap();
+        assert_eq!(state, scan.state());
+        assert!(ScanState::deserialize(b"{}").is_err());
+
+        // the deserialized state reads the files visited from the scan metadata like the scan does
+        fn collect_file(
+            files: &mut Vec<(String, i64, DvInfo, Option<ExpressionRef>)>,
+            path: &str,
+            size: i64,
+            _: Option<Stats>,
+            dv_info: DvInfo,
+            transform: Option<ExpressionRef>,
+            _: HashMap<String, String>,
+        ) {
+            files.push((path.to_string(), size, dv_info, transform));
+        }
+        let engine: Arc<dyn Engine> = Arc::new(engine);
+        let mut files = vec![];
+        for scan_metadata in scan.scan_metadata(engine.as_ref()).unwrap() {
+            files = scan_metadata
+                .unwrap()
+                .visit_scan_files(files, collect_file)
+                .unwrap();
+        }
+        assert!(!files.is_empty());
+        let mut rows = 0;
+        for (path, size, dv_info, transform) in files {
+            for result in state
+                .execute_file(engine.clone(), &path, size, dv_info, transform)
+                .unwrap()
+            {
+                rows += result.unwrap().raw_data.unwrap().len();
+            }
+        }
+        let expected: usize = scan
+            .execute(engine)
+            .unwrap()
+            .map(|result| result.unwrap().raw_data.unwrap().len())
+            .sum();
+        assert_eq!(rows, expected);
+    }
+
     #[test]
     fn test_missing_column_row_group_skipping() {
         let path = std::fs::canonicalize(PathBuf::from("./tests/data/parquet_row_group_skipping/"));
@@ -1461,7 +2815,7 @@ mod tests {
 
         let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
         let scan = snapshot.into_scan_builder().build()?;
-        let files = get_files_for_scan(scan, &engine)?;
+        let files = get_files_for_scan(&scan, &engine)?;
         // test case:
         //
         // commit0:     P and M, no add/remove
@@ -0,0 +1,120 @@
+//! Support for reading the row tracking columns of a table. See
+//! [`ScanBuilder::with_row_tracking_columns`].
+//!
+//! Rewrites of data files (e.g. to apply an update) preserve the row ids and row commit versions of
+//! the rows they copy by materializing them in the columns named by the table properties
+//! `delta.rowTracking.materializedRowIdColumnName` and
+//! `delta.rowTracking.materializedRowCommitVersionColumnName`. Where a materialized value is
+//! present it takes precedence over the value computed from the file's `baseRowId` and
+//! `defaultRowCommitVersion`.
+//!
+//! [`ScanBuilder::with_row_tracking_columns`]: crate::scan::ScanBuilder::with_row_tracking_columns
+
+use crate::expressions::Expression;
+
+/// Whether this build can evaluate the expressions returned by [`coalesce_expr`].
+pub(crate) const SUPPORTS_MATERIALIZED_COLUMNS: bool = cfg!(all(
+    feature = "arrow-expression",
+    feature = "default-engine-base"
+));
+
+/// An expression for the first non-null value of `exprs`, all of which produce `long` values.
+#[cfg(all(feature = "arrow-expression", feature = "default-engine-base"))]
+pub(crate) fn coalesce_expr(exprs: impl IntoIterator<Item = Expression>) -> Expression {
+    use crate::engine::arrow_expression::opaque::ArrowOpaqueExpression as _;
+
+    Expression::arrow_opaque(arrow_coalesce::CoalesceOp, exprs)
+}
+
+#[cfg(not(all(feature = "arrow-expression", feature = "default-engine-base")))]
+pub(crate) fn coalesce_expr(_exprs: impl IntoIterator<Item = Expression>) -> Expression {
+    // unreachable: scans of tables with materialized row tracking columns are rejected without
+    // arrow expression evaluation, see `SUPPORTS_MATERIALIZED_COLUMNS`
+    Expression::Unknown("coalesce".to_string())
+}
+
+#[cfg(all(feature = "arrow-expression", feature = "default-engine-base"))]
+mod arrow_coalesce {
+    use crate::arrow::array::{ArrayRef, RecordBatch};
+    use crate::arrow::compute::is_not_null;
+    use crate::arrow::compute::kernels::zip::zip;
+    use crate::engine::arrow_expression::evaluate_expression::evaluate_expression;
+    use crate::engine::arrow_expression::opaque::ArrowOpaqueExpressionOp;
+    use crate::expressions::{Expression, Scalar, ScalarExpressionEvaluator};
+    use crate::schema::DataType;
+    use crate::{DeltaResult, Error};
+
+    /// Returns the first non-null value of its `long` arguments, row by row.
+    #[derive(Debug, PartialEq)]
+    pub(super) struct CoalesceOp;
+
+    impl ArrowOpaqueExpressionOp for CoalesceOp {
+        fn name(&self) -> &str {
+            "coalesce"
+        }
+
+        fn eval_expr(
+            &self,
+            args: &[Expression],
+            batch: &RecordBatch,
+            _result_type: Option<&DataType>,
+        ) -> DeltaResult<ArrayRef> {
+            let mut args = args
+                .iter()
+                .map(|arg| evaluate_expression(arg, batch, Some(&DataType::LONG)));
+            let Some(first) = args.next() else {
+                return Err(Error::generic("coalesce expects at least one argument"));
+            };
+            args.try_fold(first?, |coalesced, arg| {
+                Ok(zip(&is_not_null(&coalesced)?, &coalesced, &arg?)?)
+            })
+        }
+
+        fn eval_expr_scalar(
+            &self,
+            eval_expr: &ScalarExpressionEvaluator<'_>,
+            exprs: &[Expression],
+        ) -> DeltaResult<Scalar> {
+            for expr in exprs {
+                let Some(scalar) = eval_expr(expr) else {
+                    return Err(Error::generic(format!(
+                        "Could not evaluate argument {expr} of coalesce"
+                    )));
+                };
+                if !scalar.is_null() {
+                    return Ok(scalar);
+                }
+            }
+            Ok(Scalar::Null(DataType::LONG))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::Arc;
+
+        use super::super::coalesce_expr;
+        use crate::arrow::array::{AsArray as _, Int64Array, RecordBatch};
+        use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Int64Type, Schema};
+        use crate::engine::arrow_expression::evaluate_expression::evaluate_expression;
+        use crate::expressions::{column_expr, Expression};
+        use crate::schema::DataType;
+
+        #[test]
+        fn test_coalesce() {
+            let schema = Schema::new(vec![Field::new("a", ArrowDataType::Int64, true)]);
+            let values = Int64Array::from(vec![Some(1), None, Some(3), None]);
+            let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+
+            let expr = coalesce_expr([column_expr!("a"), Expression::literal(10i64)]);
+            let result = evaluate_expression(&expr, &batch, Some(&DataType::LONG)).unwrap();
+            let result: Vec<_> = result.as_primitive::<Int64Type>().iter().collect();
+            assert_eq!(result, [Some(1), Some(10), Some(3), Some(10)]);
+
+            let expr = coalesce_expr([column_expr!("a"), Expression::null_literal(DataType::LONG)]);
+            let result = evaluate_expression(&expr, &batch, Some(&DataType::LONG)).unwrap();
+            let result: Vec<_> = result.as_primitive::<Int64Type>().iter().collect();
+            assert_eq!(result, [Some(1), None, Some(3), None]);
+        }
+    }
+}
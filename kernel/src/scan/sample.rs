@@ -0,0 +1,148 @@
+//! Deterministic sampling of the files of a scan. See [`ScanBuilder::with_sample`].
+//!
+//! [`ScanBuilder::with_sample`]: crate::scan::ScanBuilder::with_sample
+
+use std::sync::{Mutex, PoisonError};
+
+use serde::Deserialize;
+
+/// Describes the sample taken by a scan, for the files that remained after data skipping,
+/// partition pruning and the scan's file filter. See [`ScanMetricsHandle::sample_report`].
+///
+/// [`ScanMetricsHandle::sample_report`]: crate::scan::ScanMetricsHandle::sample_report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SampleReport {
+    /// The number of files considered for the sample.
+    pub total_files: u64,
+    /// The number of files included in the sample.
+    pub sampled_files: u64,
+    /// The number of rows in the considered files, according to their stats.
+    pub total_rows: u64,
+    /// The number of rows in the sampled files, according to their stats.
+    pub sampled_rows: u64,
+    /// The number of considered files without a `numRecords` stat, whose rows are not counted in
+    /// `total_rows` or `sampled_rows`.
+    pub files_without_stats: u64,
+}
+
+impl SampleReport {
+    /// The fraction of the table that was sampled: by rows if any considered file has stats, and
+    /// otherwise by files. Returns `None` if no files were considered.
+    pub fn achieved_fraction(&self) -> Option<f64> {
+        if self.total_rows > 0 {
+            Some(self.sampled_rows as f64 / self.total_rows as f64)
+        } else if self.total_files > 0 {
+            Some(self.sampled_files as f64 / self.total_files as f64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decides which files are in a sample by hashing their paths, and records a [`SampleReport`].
+#[derive(Debug)]
+pub(crate) struct FileSampler {
+    fraction: f64,
+    seed: u64,
+    report: Mutex<SampleReport>,
+}
+
+impl FileSampler {
+    pub(crate) fn new(fraction: f64, seed: u64) -> Self {
+        Self {
+            fraction,
+            seed,
+            report: Mutex::default(),
+        }
+    }
+
+    /// Whether the file at `path`, with the given (JSON) `stats`, is in the sample. The decision
+    /// only depends on the path and the seed, so it is stable across scans and table versions.
+    pub(crate) fn sample(&self, path: &str, stats: Option<&str>) -> bool {
+        let sampled = self.is_sampled(path);
+        let num_records = stats.and_then(|stats| {
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct NumRecords {
+                num_records: Option<u64>,
+            }
+            serde_json::from_str::<NumRecords>(stats).ok()?.num_records
+        });
+        let mut report = self.report.lock().unwrap_or_else(PoisonError::into_inner);
+        report.total_files += 1;
+        report.sampled_files += u64::from(sampled);
+        match num_records {
+            Some(num_records) => {
+                report.total_rows += num_records;
+                if sampled {
+                    report.sampled_rows += num_records;
+                }
+            }
+            None => report.files_without_stats += 1,
+        }
+        sampled
+    }
+
+    fn is_sampled(&self, path: &str) -> bool {
+        // FNV-1a over the seed and path, followed by the splitmix64 finalizer to spread the bits.
+        // Unlike std's `DefaultHasher`, this is stable across Rust versions and platforms.
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in self.seed.to_le_bytes().iter().chain(path.as_bytes()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^= hash >> 31;
+        // use the top 53 bits, which convert to f64 exactly, to get a value in [0, 1)
+        ((hash >> 11) as f64 / (1u64 << 53) as f64) < self.fraction
+    }
+
+    pub(crate) fn report(&self) -> SampleReport {
+        *self.report.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_sampler() {
+        let paths: Vec<_> = (0..1000).map(|i| format!("part-{i:05}.parquet")).collect();
+        let stats = r#"{"numRecords":10}"#;
+
+        let sampler = FileSampler::new(0.3, 42);
+        let sampled: Vec<_> = paths
+            .iter()
+            .map(|p| sampler.sample(p, Some(stats)))
+            .collect();
+        let report = sampler.report();
+        assert_eq!(report.total_files, 1000);
+        assert_eq!(report.total_rows, 10_000);
+        assert_eq!(report.sampled_rows, report.sampled_files * 10);
+        let fraction = report.achieved_fraction().unwrap();
+        assert!((0.25..0.35).contains(&fraction), "fraction {fraction}");
+
+        // the same seed picks the same files, a different seed picks different ones
+        let again = FileSampler::new(0.3, 42);
+        assert!(paths
+            .iter()
+            .zip(&sampled)
+            .all(|(p, s)| again.sample(p, None) == *s));
+        assert_eq!(again.report().files_without_stats, 1000);
+        let other = FileSampler::new(0.3, 43);
+        assert!(paths
+            .iter()
+            .zip(&sampled)
+            .any(|(p, s)| other.sample(p, None) != *s));
+
+        // the extremes sample nothing or everything
+        assert!(paths
+            .iter()
+            .all(|p| !FileSampler::new(0.0, 1).is_sampled(p)));
+        assert!(paths.iter().all(|p| FileSampler::new(1.0, 1).is_sampled(p)));
+
+        assert_eq!(SampleReport::default().achieved_fraction(), None);
+    }
+}
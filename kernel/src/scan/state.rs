@@ -1,28 +1,32 @@
 //! This module encapsulates the state of a scan
 
 use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 use crate::actions::deletion_vector::deletion_treemap_to_bools;
 use crate::scan::get_transform_for_row;
 use crate::schema::Schema;
+use crate::table_features::ColumnMappingMode;
 use crate::utils::require;
-use crate::ExpressionRef;
 use crate::{
     actions::{deletion_vector::DeletionVectorDescriptor, visitors::visit_deletion_vector_at},
     engine_data::{GetData, RowVisitor, TypedGetData as _},
     schema::{ColumnName, ColumnNamesAndTypes, DataType, SchemaRef},
     DeltaResult, Engine, EngineData, Error,
 };
+use crate::{ExpressionRef, PredicateRef, Version};
+use bytes::Bytes;
 use roaring::RoaringTreemap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::warn;
+use url::Url;
 
 use super::log_replay::SCAN_ROW_SCHEMA;
-use super::ScanMetadata;
+use super::{load_deletion_vectors, read_scan_files, ScanFile, ScanMetadata, ScanResult};
 
 /// this struct can be used by an engine to materialize a selection vector
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DvInfo {
     pub(crate) deletion_vector: Option<DeletionVectorDescriptor>,
 }
@@ -45,6 +49,23 @@ pub struct Stats {
     pub num_records: u64,
 }
 
+// Parse the stats of a file, if they include `numRecords`. Writers that don't collect statistics
+// omit the stats (or `numRecords`) entirely, so only invalid JSON is worth a warning.
+fn parse_stats(json: &str) -> Option<Stats> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PartialStats {
+        num_records: Option<u64>,
+    }
+    match serde_json::from_str::<PartialStats>(json) {
+        Ok(stats) => stats.num_records.map(|num_records| Stats { num_records }),
+        Err(e) => {
+            warn!("Invalid stats string in Add file {json}: {e}");
+            None
+        }
+    }
+}
+
 impl DvInfo {
     /// Check if this DvInfo contains a Deletion Vector. This is mostly used to know if the
     /// associated [`Stats`] struct has fully accurate information or not.
@@ -75,6 +96,37 @@ impl DvInfo {
         Ok(dv_treemap.map(deletion_treemap_to_bools))
     }
 
+    /// The location of this file's deletion vector, if it has one that isn't stored inline.
+    pub(crate) fn dv_file_path(&self, table_root: &url::Url) -> DeltaResult<Option<url::Url>> {
+        match &self.deletion_vector {
+            Some(dv_descriptor) => dv_descriptor.absolute_path(table_root),
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`Self::get_selection_vector`], but if this file's deletion vector is stored in a file
+    /// (see [`Self::dv_file_path`]), its contents are taken from the next item of `dv_data` instead
+    /// of being read from storage.
+    pub(crate) fn get_selection_vector_from(
+        &self,
+        table_root: &url::Url,
+        dv_data: &mut impl Iterator<Item = DeltaResult<Bytes>>,
+    ) -> DeltaResult<Option<Vec<bool>>> {
+        let Some(dv_descriptor) = &self.deletion_vector else {
+            return Ok(None);
+        };
+        let dv_treemap = match dv_descriptor.absolute_path(table_root)? {
+            None => dv_descriptor.read_inline()?,
+            Some(_) => {
+                let data = dv_data
+                    .next()
+                    .ok_or(Error::missing_data("No deletion vector data"))??;
+                dv_descriptor.read_file_data(data)?
+            }
+        };
+        Ok(Some(deletion_treemap_to_bools(dv_treemap)))
+    }
+
     /// Returns a vector of row indexes that should be *removed* from the result set
     pub fn get_row_indexes(
         &self,
@@ -91,6 +143,145 @@ impl DvInfo {
     }
 }
 
+/// The version of the serialized form of [`ScanState`]. Bumped on any change to the format that
+/// older kernels cannot read.
+pub const SCAN_STATE_FORMAT_VERSION: u32 = 1;
+
+/// The state of a [`Scan`] which executors need to read its files: the table and version it
+/// reads, its logical and physical schemas, its column mapping mode, and its physical predicate. A
+/// driver plans the scan (e.g. with [`Scan::scan_metadata`]) and sends the
+/// [serialized](Self::serialize) state along with the files to read to its executors, which
+/// [deserialize](Self::deserialize) it and [read](Self::execute_file) the files instead of
+/// replaying the log themselves.
+///
+/// The serialized form is JSON, tagged with [`SCAN_STATE_FORMAT_VERSION`]. Deserializing a state of
+/// a different format version fails. Scans whose predicate contains an
+/// [opaque predicate](crate::expressions::Predicate::Opaque) or expression cannot be serialized.
+///
+/// [`Scan`]: super::Scan
+/// [`Scan::scan_metadata`]: super::Scan::scan_metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanState {
+    format_version: u32,
+    table_root: Url,
+    version: Version,
+    logical_schema: SchemaRef,
+    physical_schema: SchemaRef,
+    column_mapping_mode: ColumnMappingMode,
+    predicate: Option<PredicateRef>,
+}
+
+impl ScanState {
+    pub(crate) fn new(
+        table_root: Url,
+        version: Version,
+        logical_schema: SchemaRef,
+        physical_schema: SchemaRef,
+        column_mapping_mode: ColumnMappingMode,
+        predicate: Option<PredicateRef>,
+    ) -> Self {
+        Self {
+            format_version: SCAN_STATE_FORMAT_VERSION,
+            table_root,
+            version,
+            logical_schema,
+            physical_schema,
+            column_mapping_mode,
+            predicate,
+        }
+    }
+
+    /// Serialize the state, e.g. to send it to the executors of a distributed scan.
+    pub fn serialize(&self) -> DeltaResult<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Deserialize a state serialized with [`Self::serialize`]. Fails if the state was serialized
+    /// with a different [format version](SCAN_STATE_FORMAT_VERSION).
+    pub fn deserialize(bytes: &[u8]) -> DeltaResult<Self> {
+        // check the version before the rest of the state, whose format depends on it
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct FormatVersion {
+            format_version: u32,
+        }
+        let FormatVersion { format_version } = serde_json::from_slice(bytes)?;
+        require!(
+            format_version == SCAN_STATE_FORMAT_VERSION,
+            Error::unsupported(format!(
+                "Unsupported scan state format version {format_version}, expected {SCAN_STATE_FORMAT_VERSION}"
+            ))
+        );
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Read the data file at `path` (relative to the [table root](Self::table_root)), one of the
+    /// files of the scan as visited by [`ScanMetadata::visit_scan_files`]. `size`, `dv_info` and
+    /// `transform` are the values passed to the [`ScanCallback`] for the file: the transform holds
+    /// the file's partition values and any other per-file columns, and converts the physical data
+    /// read from the file to the logical schema of the scan.
+    ///
+    /// Like [`Scan::execute`](super::Scan::execute), each [`ScanResult`] holds data in the logical
+    /// schema of the scan and the selection vector from the file's deletion vector, if any.
+    pub fn execute_file(
+        &self,
+        engine: Arc<dyn Engine>,
+        path: &str,
+        size: i64,
+        dv_info: DvInfo,
+        transform: Option<ExpressionRef>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + Send> {
+        let scan_file = ScanFile {
+            path: path.to_string(),
+            size,
+            dv_info,
+            transform,
+        };
+        let scan_files = load_deletion_vectors(engine.as_ref(), &self.table_root, vec![scan_file])?;
+        Ok(read_scan_files(
+            engine,
+            self.table_root.clone(),
+            self.physical_schema.clone(),
+            self.logical_schema.clone(),
+            scan_files,
+        ))
+    }
+
+    /// The URL of the table root, against which the paths of the scan's files are resolved.
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// The version of the table the scan reads.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The output schema of the scan, see [`Scan::logical_schema`](super::Scan::logical_schema).
+    pub fn logical_schema(&self) -> &SchemaRef {
+        &self.logical_schema
+    }
+
+    /// The schema to read the scan's files with, see
+    /// [`Scan::physical_schema`](super::Scan::physical_schema).
+    pub fn physical_schema(&self) -> &SchemaRef {
+        &self.physical_schema
+    }
+
+    /// The column mapping mode of the table, which determines the names of the columns of the
+    /// [physical schema](Self::physical_schema) in the scan's files.
+    pub fn column_mapping_mode(&self) -> ColumnMappingMode {
+        self.column_mapping_mode
+    }
+
+    /// The predicate to read the scan's files with, see
+    /// [`Scan::physical_predicate`](super::Scan::physical_predicate).
+    pub fn predicate(&self) -> Option<&PredicateRef> {
+        self.predicate.as_ref()
+    }
+}
+
 /// utility function for applying a transform expression to convert data from physical to logical
 /// format
 pub fn transform_to_logical(
@@ -179,7 +370,7 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 10,
+            getters.len() == 13,
             Error::InternalError(format!(
                 "Wrong number of ScanFileVisitor getters: {}",
                 getters.len()
@@ -194,14 +385,7 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
             if let Some(path) = getters[0].get_opt(row_index, "scanFile.path")? {
                 let size = getters[1].get(row_index, "scanFile.size")?;
                 let stats: Option<String> = getters[3].get_opt(row_index, "scanFile.stats")?;
-                let stats: Option<Stats> =
-                    stats.and_then(|json| match serde_json::from_str(json.as_str()) {
-                        Ok(stats) => Some(stats),
-                        Err(e) => {
-                            warn!("Invalid stats string in Add file {json}: {}", e);
-                            None
-                        }
-                    });
+                let stats = stats.and_then(|json| parse_stats(&json));
 
                 let dv_index = SCAN_ROW_SCHEMA
                     .index_of("deletionVector")
@@ -228,12 +412,21 @@ impl<T> RowVisitor for ScanFileVisitor<'_, T> {
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::sync::Arc;
 
     use crate::actions::get_log_schema;
+    use crate::expressions::{column_expr, Expression as Expr, Predicate as Pred, Scalar};
     use crate::scan::test_utils::{add_batch_simple, run_with_validate_callback};
+    use crate::schema::{DataType, StructField, StructType};
+    use crate::table_features::ColumnMappingMode;
     use crate::ExpressionRef;
 
-    use super::{DvInfo, Stats};
+    use bytes::Bytes;
+    use url::Url;
+
+    use crate::actions::deletion_vector::DeletionVectorDescriptor;
+
+    use super::{parse_stats, DvInfo, ScanState, Stats};
 
     #[derive(Clone)]
     struct TestContext {
@@ -277,4 +470,189 @@ mod tests {
             validate_visit,
         );
     }
+
+    // The serialized form of a scan state is a stable format: changes to it must bump
+    // `SCAN_STATE_FORMAT_VERSION`.
+    #[test]
+    fn test_scan_state_golden() {
+        let schema = Arc::new(StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("price", DataType::decimal(10, 2).unwrap()),
+        ]));
+        let price = Scalar::decimal(12345, 10, 2).unwrap();
+        let predicate = Pred::and(
+            Pred::gt(column_expr!("id"), Expr::literal(3i64)),
+            Pred::lt(column_expr!("price"), Expr::literal(price)),
+        );
+        let state = ScanState::new(
+            Url::parse("file:///tmp/table/").unwrap(),
+            7,
+            schema.clone(),
+            schema,
+            ColumnMappingMode::None,
+            Some(Arc::new(predicate)),
+        );
+        let golden = r#"
+        {
+            "formatVersion": 1,
+            "tableRoot": "file:///tmp/table/",
+            "version": 7,
+            "logicalSchema": {
+                "type": "struct",
+                "fields": [
+                    {
+                        "name": "id",
+                        "type": "long",
+                        "nullable": true,
+                        "metadata": {}
+                    },
+                    {
+                        "name": "price",
+                        "type": "decimal(10,2)",
+                        "nullable": true,
+                        "metadata": {}
+                    }
+                ]
+            },
+            "physicalSchema": {
+                "type": "struct",
+                "fields": [
+                    {
+                        "name": "id",
+                        "type": "long",
+                        "nullable": true,
+                        "metadata": {}
+                    },
+                    {
+                        "name": "price",
+                        "type": "decimal(10,2)",
+                        "nullable": true,
+                        "metadata": {}
+                    }
+                ]
+            },
+            "columnMappingMode": "none",
+            "predicate": {
+                "Junction": {
+                    "op": "And",
+                    "preds": [
+                        {
+                            "Binary": {
+                                "op": "GreaterThan",
+                                "left": {
+                                    "Column": [
+                                        "id"
+                                    ]
+                                },
+                                "right": {
+                                    "Literal": {
+                                        "Long": 3
+                                    }
+                                }
+                            }
+                        },
+                        {
+                            "Binary": {
+                                "op": "LessThan",
+                                "left": {
+                                    "Column": [
+                                        "price"
+                                    ]
+                                },
+                                "right": {
+                                    "Literal": {
+                                        "Decimal": {
+                                            "bits": 12345,
+                                            "ty": {
+                                                "precision": 10,
+                                                "scale": 2
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    ]
+                }
+            }
+        }"#;
+        let serialized: serde_json::Value =
+            serde_json::from_slice(&state.serialize().unwrap()).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::from_str::<serde_json::Value>(golden).unwrap()
+        );
+        assert_eq!(ScanState::deserialize(golden.as_bytes()).unwrap(), state);
+
+        // other format versions are rejected
+        let other_version = golden.replace(r#""formatVersion": 1"#, r#""formatVersion": 2"#);
+        assert!(ScanState::deserialize(other_version.as_bytes()).is_err());
+
+        // literals are validated when deserialized: 123456789012 does not fit in decimal(10, 2)
+        let invalid = golden.replace("12345", "123456789012");
+        assert!(ScanState::deserialize(invalid.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        let stats = parse_stats(r#"{"numRecords":3,"minValues":{"a":1}}"#);
+        assert_eq!(stats, Some(Stats { num_records: 3 }));
+        // stats without numRecords, or invalid stats, leave the number of records unknown
+        assert_eq!(parse_stats(r#"{"minValues":{"a":1}}"#), None);
+        assert_eq!(parse_stats("{}"), None);
+        assert_eq!(parse_stats("not json"), None);
+    }
+
+    #[test]
+    fn test_get_selection_vector_from() {
+        let path = std::fs::canonicalize("./tests/data/table-with-dv-small/").unwrap();
+        let table_root = url::Url::from_directory_path(path).unwrap();
+        let file_dv: DvInfo = DeletionVectorDescriptor {
+            storage_type: "u".to_string(),
+            path_or_inline_dv: "vBn[lx{q8@P<9BNH/isA".to_string(),
+            offset: Some(1),
+            size_in_bytes: 36,
+            cardinality: 2,
+        }
+        .into();
+        let inline_dv: DvInfo = DeletionVectorDescriptor {
+            storage_type: "i".to_string(),
+            path_or_inline_dv: "^Bg9^0rr910000000000iXQKl0rr91000f55c8Xg0@@D72lkbi5=-{L"
+                .to_string(),
+            offset: None,
+            size_in_bytes: 44,
+            cardinality: 6,
+        }
+        .into();
+        let no_dv = DvInfo {
+            deletion_vector: None,
+        };
+
+        let dv_path = file_dv.dv_file_path(&table_root).unwrap().unwrap();
+        assert!(inline_dv.dv_file_path(&table_root).unwrap().is_none());
+        assert!(no_dv.dv_file_path(&table_root).unwrap().is_none());
+
+        // only the file dv consumes data
+        let dv_bytes = Bytes::from(std::fs::read(dv_path.to_file_path().unwrap()).unwrap());
+        let mut dv_data = std::iter::once(Ok(dv_bytes));
+        let sv = no_dv
+            .get_selection_vector_from(&table_root, &mut dv_data)
+            .unwrap();
+        assert_eq!(sv, None);
+        let sv = inline_dv
+            .get_selection_vector_from(&table_root, &mut dv_data)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sv.iter().filter(|keep| !**keep).count(), 6);
+        let sv = file_dv
+            .get_selection_vector_from(&table_root, &mut dv_data)
+            .unwrap()
+            .unwrap();
+        assert!(!sv[0] && sv[1] && !sv[9]);
+
+        // running out of data is an error
+        assert!(file_dv
+            .get_selection_vector_from(&table_root, &mut dv_data)
+            .is_err());
+    }
 }
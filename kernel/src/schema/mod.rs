@@ -83,6 +83,7 @@ impl From<bool> for MetadataValue {
 
 #[derive(Debug)]
 pub enum ColumnMetadataKey {
+    Comment,
     ColumnMappingId,
     ColumnMappingPhysicalName,
     GenerationExpression,
@@ -96,6 +97,7 @@ pub enum ColumnMetadataKey {
 impl AsRef<str> for ColumnMetadataKey {
     fn as_ref(&self) -> &str {
         match self {
+            Self::Comment => "comment",
             Self::ColumnMappingId => "delta.columnMapping.id",
             Self::ColumnMappingPhysicalName => "delta.columnMapping.physicalName",
             Self::GenerationExpression => "delta.generationExpression",
@@ -351,6 +353,46 @@ impl InvariantChecker {
     }
 }
 
+/// Finds the first (possibly nested) column whose type is [`DataType::Unsupported`].
+#[derive(Debug, Default)]
+struct UnsupportedTypeFinder<'a> {
+    path: Vec<&'a str>,
+    found: Option<(ColumnName, &'a str)>,
+}
+
+impl<'a> SchemaTransform<'a> for UnsupportedTypeFinder<'a> {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        if self.found.is_none() {
+            self.path.push(&field.name);
+            let _ = self.recurse_into_struct_field(field);
+            self.path.pop();
+        }
+        Some(Cow::Borrowed(field))
+    }
+
+    fn transform_unsupported(&mut self, name: &'a String) -> Option<Cow<'a, String>> {
+        if self.found.is_none() {
+            self.found = Some((ColumnName::new(self.path.iter().copied()), name));
+        }
+        Some(Cow::Borrowed(name))
+    }
+}
+
+impl StructType {
+    /// Fails if any column of this schema, including nested ones, has a
+    /// [`DataType::Unsupported`] type.
+    pub(crate) fn ensure_supported_types(&self) -> DeltaResult<()> {
+        let mut finder = UnsupportedTypeFinder::default();
+        let _ = finder.transform_struct(self);
+        match finder.found {
+            Some((column, name)) => Err(Error::unsupported(format!(
+                "Unsupported data type '{name}' for column {column}"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
 /// Helper for RowVisitor implementations
 #[internal_api]
 #[derive(Clone, Default)]
@@ -499,12 +541,28 @@ fn default_true() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedDecimalType")]
 pub struct DecimalType {
     precision: u8,
     scale: u8,
 }
 
+// Deserialize through `DecimalType::try_new`, so that invalid decimal types are rejected
+#[derive(Deserialize)]
+struct UncheckedDecimalType {
+    precision: u8,
+    scale: u8,
+}
+
+impl TryFrom<UncheckedDecimalType> for DecimalType {
+    type Error = Error;
+
+    fn try_from(unchecked: UncheckedDecimalType) -> DeltaResult<Self> {
+        Self::try_new(unchecked.precision, unchecked.scale)
+    }
+}
+
 impl DecimalType {
     /// Check if the given precision and scale are valid for a decimal type.
     pub fn try_new(precision: u8, scale: u8) -> DeltaResult<Self> {
@@ -626,7 +684,7 @@ impl Display for PrimitiveType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
+#[derive(Debug, Serialize, PartialEq, Clone, Eq)]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum DataType {
     /// UTF-8 encoded string of characters
@@ -639,6 +697,62 @@ pub enum DataType {
     /// A map stores an arbitrary length collection of key-value pairs
     /// with a single keyType and a single valueType
     Map(Box<MapType>),
+    /// A leaf type this version of kernel does not know, holding the type name as it appears in
+    /// the schema (and is serialized back as). Columns of this type cannot be read or written.
+    ///
+    /// Deserializing a schema produces this for any unknown type name, but snapshots reject such
+    /// schemas unless opened with [`Snapshot::try_new_with_unknown_types`].
+    ///
+    /// [`Snapshot::try_new_with_unknown_types`]: crate::Snapshot::try_new_with_unknown_types
+    Unsupported(String),
+}
+
+// Hand-written (instead of `#[serde(untagged)]`) to report what is actually wrong with a type,
+// rather than that it "did not match any variant", and to parse unknown leaf types as
+// `DataType::Unsupported`.
+impl<'de> Deserialize<'de> for DataType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error as _, IntoDeserializer as _};
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let type_name = match &value {
+            serde_json::Value::String(name) => {
+                let name_deserializer: serde::de::value::StrDeserializer<'_, D::Error> =
+                    name.as_str().into_deserializer();
+                return match PrimitiveType::deserialize(name_deserializer) {
+                    Ok(ptype) => Ok(ptype.into()),
+                    // A malformed decimal is an error, not a new type
+                    Err(_) if name.starts_with("decimal") => {
+                        deserialize_decimal(name.as_str().into_deserializer()).map(DataType::from)
+                    }
+                    Err(_) => Ok(DataType::Unsupported(name.clone())),
+                };
+            }
+            serde_json::Value::Object(fields) => match fields.get("type") {
+                Some(serde_json::Value::String(type_name)) => type_name.clone(),
+                _ => {
+                    return Err(D::Error::custom(format!(
+                        "Invalid data type {value}: missing string field `type`"
+                    )))
+                }
+            },
+            _ => return Err(D::Error::custom(format!("Invalid data type {value}"))),
+        };
+        let result = match type_name.as_str() {
+            "array" => ArrayType::deserialize(value).map(DataType::from),
+            "struct" => StructType::deserialize(value).map(DataType::from),
+            "map" => MapType::deserialize(value).map(DataType::from),
+            _ => {
+                return Err(D::Error::custom(format!(
+                    "Unsupported complex data type '{type_name}'"
+                )))
+            }
+        };
+        result.map_err(|err| D::Error::custom(format!("Invalid {type_name} type: {err}")))
+    }
 }
 
 impl From<DecimalType> for PrimitiveType {
@@ -738,6 +852,7 @@ impl Display for DataType {
                 write!(f, ">")
             }
             DataType::Map(m) => write!(f, "map<{}, {}>", m.key_type, m.value_type),
+            DataType::Unsupported(name) => write!(f, "{name}"),
         }
     }
 }
@@ -764,6 +879,11 @@ pub trait SchemaTransform<'a> {
         Some(Cow::Borrowed(ptype))
     }
 
+    /// Called for each [`DataType::Unsupported`] type encountered during the schema traversal.
+    fn transform_unsupported(&mut self, name: &'a String) -> Option<Cow<'a, String>> {
+        Some(Cow::Borrowed(name))
+    }
+
     /// Called for each struct encountered during the schema traversal. Implementations can call
     /// [`Self::recurse_into_struct`] if they wish to recursively transform the struct's fields.
     fn transform_struct(&mut self, stype: &'a StructType) -> Option<Cow<'a, StructType>> {
@@ -825,6 +945,9 @@ pub trait SchemaTransform<'a> {
             Map(mtype) => self
                 .transform_map(mtype)?
                 .map_owned_or_else(data_type, DataType::from),
+            Unsupported(name) => self
+                .transform_unsupported(name)?
+                .map_owned_or_else(data_type, DataType::Unsupported),
         };
         Some(result)
     }
@@ -1189,6 +1312,75 @@ mod tests {
         assert!(serde_json::from_str::<StructField>(data).is_err());
     }
 
+    #[test]
+    fn test_unknown_types() {
+        let data = r#"
+        {
+            "name": "a",
+            "type": {
+                "type": "array",
+                "elementType": {
+                    "type": "struct",
+                    "fields": [{"name": "b", "type": "variant_v2", "nullable": true, "metadata": {}}]
+                },
+                "containsNull": true
+            },
+            "nullable": true,
+            "metadata": {}
+        }
+        "#;
+        let field: StructField = serde_json::from_str(data).unwrap();
+        let DataType::Array(array) = field.data_type() else {
+            panic!("expected array, got {}", field.data_type());
+        };
+        let DataType::Struct(element) = array.element_type() else {
+            panic!("expected struct, got {}", array.element_type());
+        };
+        let b = element.field("b").unwrap();
+        assert_eq!(b.data_type(), &DataType::Unsupported("variant_v2".into()));
+
+        // unknown types are written back as they were read
+        let round_trip: StructField =
+            serde_json::from_str(&serde_json::to_string(&field).unwrap()).unwrap();
+        assert_eq!(round_trip, field);
+
+        let schema = StructType::new([StructField::nullable("id", DataType::LONG), field]);
+        let err = schema.ensure_supported_types().unwrap_err().to_string();
+        assert!(
+            err.contains("Unsupported data type 'variant_v2' for column a.b"),
+            "{err}"
+        );
+        StructType::new([StructField::nullable("id", DataType::LONG)])
+            .ensure_supported_types()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_data_type_errors() {
+        let err = |data: &str| {
+            serde_json::from_str::<DataType>(data)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let msg = err(r#""decimal(39, 10)""#);
+        assert!(msg.contains("Invalid decimal"), "{msg}");
+        let msg = err(r#"{"type": "array", "containsNull": true}"#);
+        assert!(
+            msg.contains("Invalid array type") && msg.contains("elementType"),
+            "{msg}"
+        );
+        let msg = err(r#"{"type": "tensor"}"#);
+        assert!(
+            msg.contains("Unsupported complex data type 'tensor'"),
+            "{msg}"
+        );
+        let msg = err(r#"{"elementType": "integer"}"#);
+        assert!(msg.contains("missing string field `type`"), "{msg}");
+        let msg = err("42");
+        assert!(msg.contains("Invalid data type 42"), "{msg}");
+    }
+
     #[test]
     fn test_depth_checker() {
         let schema = DataType::struct_type([
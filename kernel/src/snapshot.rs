@@ -1,27 +1,50 @@
 //! In-memory representation of snapshots of tables (snapshot is a table at given point in time, it
 //! has schema etc.)
 
-use std::sync::Arc;
-
-use crate::actions::domain_metadata::domain_metadata_configuration;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, OnceLock};
+
+use crate::actions::crc::{CrcSummary, CrcTableState};
+use crate::actions::domain_metadata::{
+    all_domain_metadata_configurations, domain_metadata_configuration,
+};
 use crate::actions::set_transaction::SetTransactionScanner;
 use crate::actions::{Metadata, Protocol, INTERNAL_DOMAIN_PREFIX};
+use crate::catalog::Catalog;
 use crate::checkpoint::CheckpointWriter;
-use crate::log_segment::{self, ListedLogFiles, LogSegment};
+use crate::history::{self, HistoryEntry};
+use crate::history_manager;
+use crate::log_compaction::LogCompactionWriter;
+use crate::log_segment::{self, CommitCover, CommitCoverCost, ListedLogFiles, LogSegment};
+use crate::path::ParsedLogPath;
 use crate::scan::ScanBuilder;
 use crate::schema::{Schema, SchemaRef};
-use crate::table_configuration::TableConfiguration;
+use crate::table_configuration::{TableCapabilities, TableConfiguration};
 use crate::table_features::ColumnMappingMode;
 use crate::table_properties::TableProperties;
 use crate::transaction::Transaction;
-use crate::utils::{calculate_transaction_expiration_timestamp, try_parse_uri};
-use crate::{DeltaResult, Engine, Error, StorageHandler, Version};
+use crate::utils::{
+    calculate_transaction_expiration_timestamp, normalize_table_root, require, try_parse_uri,
+};
+use crate::vacuum::VacuumPlanner;
+use crate::{DeltaResult, Engine, Error, FileMeta, StorageHandler, Version};
 use delta_kernel_derive::internal_api;
 
+use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 use url::Url;
 
+mod column_metadata;
+mod config_diff;
+mod files;
+mod group;
+pub use column_metadata::ColumnMetadata;
+pub use config_diff::ConfigDiff;
+pub use files::SnapshotFile;
+pub use group::SnapshotGroup;
+
 /// Name of the _last_checkpoint file that provides metadata about the last checkpoint
 /// created for the table. This file is used as a hint for the engine to quickly locate
 /// the latest checkpoint without a full directory listing.
@@ -31,11 +54,73 @@ pub(crate) const LAST_CHECKPOINT_FILE_NAME: &str = "_last_checkpoint";
 /// In-memory representation of a specific snapshot of a Delta table. While a `DeltaTable` exists
 /// throughout time, `Snapshot`s represent a view of a table at a specific point in time; they
 /// have a defined schema (which may change over time for any given table), specific version, and
-/// frozen log segment.
-#[derive(PartialEq, Eq)]
+/// frozen log segment. A `Snapshot` is `Send + Sync`, so it can be shared across threads.
 pub struct Snapshot {
     log_segment: LogSegment,
     table_configuration: TableConfiguration,
+    /// Lazily computed by [`Snapshot::file_count`] and [`Snapshot::total_size_bytes`].
+    file_stats: OnceLock<FileStats>,
+    /// Lazily read from the version checksum (CRC) file of this snapshot's version, if any.
+    crc: OnceLock<Option<CrcSummary>>,
+}
+
+// The file stats and CRC caches are derived from the log segment, so it does not take part in equality.
+impl PartialEq for Snapshot {
+    fn eq(&self, other: &Self) -> bool {
+        self.log_segment == other.log_segment
+            && self.table_configuration == other.table_configuration
+    }
+}
+
+impl Eq for Snapshot {}
+
+/// The number and total size of the live files in a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStats {
+    num_files: i64,
+    size_bytes: i64,
+}
+
+/// The result of [`Snapshot::verify_checksum`]: the fields on which the version checksum (CRC) file
+/// of a version disagrees with the table state computed by log replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumReport {
+    /// The version whose CRC file was verified.
+    pub version: Version,
+    /// The mismatching fields, empty if the CRC file is correct.
+    pub mismatches: Vec<ChecksumMismatch>,
+}
+
+impl ChecksumReport {
+    /// Whether the CRC file agrees with log replay on every verified field.
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// A field of a version checksum (CRC) file that disagrees with log replay. Counts hold the value
+/// recorded in the CRC file and the value computed by log replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChecksumMismatch {
+    /// The number of live data files (`numFiles`).
+    NumFiles { checksum: i64, computed: i64 },
+    /// The total size in bytes of the live data files (`tableSizeBytes`).
+    TableSizeBytes { checksum: i64, computed: i64 },
+    /// The number of metadata actions (`numMetadata`), which must be 1.
+    NumMetadata { checksum: i64, computed: i64 },
+    /// The number of protocol actions (`numProtocol`), which must be 1.
+    NumProtocol { checksum: i64, computed: i64 },
+    /// The table metadata (`metadata`).
+    Metadata,
+    /// The table protocol (`protocol`).
+    Protocol,
+    /// The configuration of a domain (`domainMetadata`), which may be live according to only one
+    /// of the CRC file and log replay.
+    DomainMetadata { domain: String },
+    /// The version of an application's transaction (`setTransactions`), which may be recorded by
+    /// only one of the CRC file and log replay.
+    SetTransaction { app_id: String },
 }
 
 impl Drop for Snapshot {
@@ -59,6 +144,8 @@ impl Snapshot {
         Self {
             log_segment,
             table_configuration,
+            file_stats: OnceLock::new(),
+            crc: OnceLock::new(),
         }
     }
 
@@ -84,7 +171,8 @@ impl Snapshot {
     ///
     /// # Parameters
     ///
-    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located)
+    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located). A
+    ///   missing trailing slash is added.
     /// - `engine`: Implementation of [`Engine`] apis.
     /// - `version`: target version of the [`Snapshot`]. None will create a snapshot at the latest
     ///   version of the table.
@@ -93,16 +181,114 @@ impl Snapshot {
         engine: &dyn Engine,
         version: Option<Version>,
     ) -> DeltaResult<Self> {
+        let (table_root, log_segment) =
+            Self::list_log_segment(table_root, engine, vec![], version)?;
+        // try_new_from_log_segment will ensure the protocol is supported
+        Self::try_new_from_log_segment(table_root, log_segment, engine)
+    }
+
+    /// Create a new [`Snapshot`] instance of the table as of `timestamp` (in milliseconds since the
+    /// Unix epoch): the latest version committed at or before `timestamp`.
+    ///
+    /// The timestamp of a commit is its in-commit timestamp if the `inCommitTimestamp` table
+    /// feature was enabled when it was written, else the modification time of its commit file.
+    /// Fails if `timestamp` is before the earliest commit still in the log.
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located). A
+    ///   missing trailing slash is added.
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `timestamp`: the point in time of the [`Snapshot`].
+    pub fn try_new_at_timestamp(
+        table_root: Url,
+        engine: &dyn Engine,
+        timestamp: i64,
+    ) -> DeltaResult<Self> {
+        let latest = Self::try_new(table_root, engine, None)?;
+        let version = history_manager::latest_version_as_of(&latest, engine, timestamp)?;
+        if version == latest.version() {
+            return Ok(latest);
+        }
+        Self::try_new(latest.table_root().clone(), engine, Some(version))
+    }
+
+    /// Like [`Snapshot::try_new`], but a table whose schema contains data types this version of
+    /// kernel doesn't know can still be opened. Those types are parsed as
+    /// [`DataType::Unsupported`], and only fail when used: a scan whose schema includes such a
+    /// column fails to build, and the table cannot be written. Scans that project only the other
+    /// columns work as usual.
+    ///
+    /// Snapshots created from this one with [`Snapshot::try_new_from`] also allow unknown types.
+    ///
+    /// [`DataType::Unsupported`]: crate::schema::DataType::Unsupported
+    pub fn try_new_with_unknown_types(
+        table_root: Url,
+        engine: &dyn Engine,
+        version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        let (table_root, log_segment) =
+            Self::list_log_segment(table_root, engine, vec![], version)?;
+        let (metadata, protocol) = log_segment.read_metadata(engine)?;
+        let table_configuration = TableConfiguration::try_new_with_unknown_types(
+            metadata,
+            protocol,
+            table_root,
+            log_segment.end_version,
+        )?;
+        Ok(Self::new(log_segment, table_configuration))
+    }
+
+    /// Create a new [`Snapshot`] instance for the given version of the table named `table_name`
+    /// in `catalog`. Commits the catalog knows of that are not yet in the `_delta_log` (see
+    /// [`CatalogTable::with_log_tail`]) are part of the snapshot.
+    ///
+    /// # Parameters
+    ///
+    /// - `catalog`: The [`Catalog`] to resolve `table_name` with.
+    /// - `table_name`: The name of the table in the catalog.
+    /// - `engine`: Implementation of [`Engine`] apis, with access to the table's storage (see
+    ///   [`CatalogTable::storage_options`]).
+    /// - `version`: target version of the [`Snapshot`]. None will create a snapshot at the latest
+    ///   version of the table.
+    ///
+    /// [`CatalogTable::with_log_tail`]: crate::catalog::CatalogTable::with_log_tail
+    /// [`CatalogTable::storage_options`]: crate::catalog::CatalogTable::storage_options
+    pub fn try_from_catalog(
+        catalog: &dyn Catalog,
+        table_name: &str,
+        engine: &dyn Engine,
+        version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        let table = catalog.resolve_table(table_name)?;
+        let log_tail = table.parsed_log_tail()?;
+        let (table_root, log_segment) =
+            Self::list_log_segment(table.table_root().clone(), engine, log_tail, version)?;
+        Self::try_new_from_log_segment(table_root, log_segment, engine)
+    }
+
+    // Normalize `table_root` and list the log segment of the snapshot at `version`, extended by
+    // the commits in `log_tail`.
+    fn list_log_segment(
+        table_root: Url,
+        engine: &dyn Engine,
+        log_tail: Vec<ParsedLogPath>,
+        version: Option<Version>,
+    ) -> DeltaResult<(Url, LogSegment)> {
+        let table_root = normalize_table_root(table_root)?;
         let storage = engine.storage_handler();
         let log_root = table_root.join("_delta_log/")?;
 
         let checkpoint_hint = read_last_checkpoint(storage.as_ref(), &log_root)?;
 
-        let log_segment =
-            LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_hint, version)?;
-
-        // try_new_from_log_segment will ensure the protocol is supported
-        Self::try_new_from_log_segment(table_root, log_segment, engine)
+        let log_segment = LogSegment::for_snapshot_with_log_tail(
+            storage.as_ref(),
+            log_root,
+            checkpoint_hint,
+            log_tail,
+            version,
+        )?;
+        Ok((table_root, log_segment))
     }
 
     /// Create a new [`Snapshot`] instance from an existing [`Snapshot`]. This is useful when you
@@ -146,19 +332,56 @@ impl Snapshot {
             }
         }
 
-        let log_root = old_log_segment.log_root.clone();
-        let storage = engine.storage_handler();
-
         // Start listing just after the previous segment's checkpoint, if any
         let listing_start = old_log_segment.checkpoint_version.unwrap_or(0) + 1;
+        Self::try_new_from_listing(existing_snapshot, engine, new_version, listing_start)
+    }
+
+    /// Refresh this snapshot to the latest version of the table, or return it unchanged if no new
+    /// version has been committed.
+    ///
+    /// Unlike [`Snapshot::try_new_from`], which lists the log from this snapshot's checkpoint
+    /// onwards, this only lists the log after this snapshot's version. The protocol and metadata
+    /// are updated by replaying just the new commits (or read from a new checkpoint, if one was
+    /// written), and the new snapshot extends this snapshot's log segment. This keeps the cost of
+    /// polling a table for new versions proportional to the number of new commits.
+    ///
+    /// Returns a clone of `self` (the same [`Arc`]) if the table has no new version.
+    pub fn try_refresh(self: &Arc<Self>, engine: &dyn Engine) -> DeltaResult<Arc<Self>> {
+        Self::try_new_from_listing(self.clone(), engine, None, self.version() + 1)
+    }
+
+    // Create the snapshot at `new_version` (or the latest version) from `existing_snapshot`,
+    // listing the log from `listing_start`. The listing must start after the existing snapshot's
+    // checkpoint, if any, and no later than one past its version.
+    fn try_new_from_listing(
+        existing_snapshot: Arc<Snapshot>,
+        engine: &dyn Engine,
+        new_version: Option<Version>,
+        listing_start: Version,
+    ) -> DeltaResult<Arc<Self>> {
+        let old_log_segment = &existing_snapshot.log_segment;
+        let old_version = existing_snapshot.version();
+        let log_root = old_log_segment.log_root.clone();
+        let storage = engine.storage_handler();
 
         // Check for new commits (and CRC)
-        let new_listed_files = log_segment::list_log_files_with_version(
+        let mut new_listed_files = log_segment::list_log_files_with_version(
             storage.as_ref(),
             &log_root,
             Some(listing_start),
             new_version,
         )?;
+        // the listing cannot find the commits the catalog had not yet published, but they are
+        // still part of the table
+        let unpublished_start = match new_listed_files.checkpoint_parts.first() {
+            Some(checkpoint) => listing_start.max(checkpoint.version + 1),
+            None => listing_start,
+        };
+        old_log_segment.extend_with_unpublished_commits(
+            &mut new_listed_files.ascending_commit_files,
+            Some(unpublished_start),
+        );
 
         // NB: we need to check both checkpoints and commits since we filter commits at and below
         // the checkpoint version. Example: if we have a checkpoint + commit at version 1, the log
@@ -198,13 +421,17 @@ impl Snapshot {
         }
 
         if new_log_segment.checkpoint_version.is_some() {
-            // we have a checkpoint in the new LogSegment, just construct a new snapshot from that
-            let snapshot = Self::try_new_from_log_segment(
-                existing_snapshot.table_root().clone(),
-                new_log_segment,
-                engine,
-            );
-            return Ok(Arc::new(snapshot?));
+            // we have a checkpoint in the new LogSegment, just construct a new snapshot from that.
+            // The P+M are re-read in full, but the table configuration is derived from the
+            // existing one so it keeps its settings (e.g. whether unknown types are allowed).
+            let (metadata, protocol) = new_log_segment.read_metadata(engine)?;
+            let table_configuration = TableConfiguration::try_new_from(
+                existing_snapshot.table_configuration(),
+                Some(metadata),
+                Some(protocol),
+                new_log_segment.end_version,
+            )?;
+            return Ok(Arc::new(Self::new(new_log_segment, table_configuration)));
         }
 
         // after this point, we incrementally update the snapshot with the new log segment.
@@ -275,10 +502,7 @@ impl Snapshot {
         let (metadata, protocol) = log_segment.read_metadata(engine)?;
         let table_configuration =
             TableConfiguration::try_new(metadata, protocol, location, log_segment.end_version)?;
-        Ok(Self {
-            log_segment,
-            table_configuration,
-        })
+        Ok(Self::new(log_segment, table_configuration))
     }
 
     /// Creates a [`CheckpointWriter`] for generating a checkpoint from this snapshot.
@@ -289,6 +513,72 @@ impl Snapshot {
         CheckpointWriter::try_new(self)
     }
 
+    /// Creates a [`LogCompactionWriter`] for compacting the commits `start_version..=end_version`
+    /// of this snapshot's table into a single log compaction file.
+    ///
+    /// See the [`crate::log_compaction`] module documentation for more details.
+    pub fn log_compaction_writer(
+        self: Arc<Self>,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<LogCompactionWriter> {
+        LogCompactionWriter::try_new(self, start_version, end_version)
+    }
+
+    /// The commits of this snapshot's table up to its version, newest first, with their
+    /// [`CommitInfo`]. At most `limit` commits are returned, if given. Commits whose files were
+    /// removed by log cleanup are missing.
+    ///
+    /// See the [`crate::history`] module documentation for more details.
+    ///
+    /// [`CommitInfo`]: crate::actions::CommitInfo
+    pub fn history<'a>(
+        &self,
+        engine: &'a dyn Engine,
+        limit: impl Into<Option<usize>>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<HistoryEntry>> + 'a> {
+        history::history(self, engine, limit.into())
+    }
+
+    /// Creates a [`VacuumPlanner`] for finding the files of this snapshot's table that are safe to
+    /// delete.
+    ///
+    /// See the [`crate::vacuum`] module documentation for more details.
+    pub fn vacuum_planner(self: Arc<Self>) -> DeltaResult<VacuumPlanner> {
+        VacuumPlanner::try_new(self)
+    }
+
+    /// The in-commit timestamp of this snapshot's version, or `None` if in-commit timestamps are
+    /// not enabled. Reads the commit file of the version.
+    pub(crate) fn get_in_commit_timestamp(&self, engine: &dyn Engine) -> DeltaResult<Option<i64>> {
+        if !self.table_configuration().is_in_commit_timestamps_enabled() {
+            return Ok(None);
+        }
+        let commit_file = match self.log_segment.ascending_commit_files.last() {
+            Some(commit) if commit.version == self.version() => commit.location.clone(),
+            // the version is a checkpoint, so its commit is not part of the log segment
+            _ => {
+                let commit = ParsedLogPath::new_commit(self.table_root(), self.version())?;
+                // the size and modification time are not needed to read the file
+                FileMeta::new(commit.location, 0, 0)
+            }
+        };
+        let in_commit_timestamp = history_manager::read_in_commit_timestamp(engine, &commit_file)?;
+        in_commit_timestamp.map(Some).ok_or_else(|| {
+            Error::generic(format!(
+                "In-commit timestamps are enabled, but missing from commit {}",
+                self.version()
+            ))
+        })
+    }
+
+    /// Plans which commit and log compaction files to read to replay the commits of this snapshot
+    /// after its checkpoint, minimizing `cost`. Engines can use the returned
+    /// [`CommitCover::expected_bytes`] to estimate the cost of log replay.
+    pub fn plan_commit_cover(&self, cost: CommitCoverCost) -> CommitCover {
+        self.log_segment.plan_commit_cover(cost)
+    }
+
     /// Log segment this snapshot uses
     #[internal_api]
     pub(crate) fn log_segment(&self) -> &LogSegment {
@@ -316,7 +606,6 @@ impl Snapshot {
     }
 
     /// Table [`Protocol`] at this `Snapshot`s version.
-    #[allow(dead_code)]
     #[internal_api]
     pub(crate) fn protocol(&self) -> &Protocol {
         self.table_configuration.protocol()
@@ -327,6 +616,19 @@ impl Snapshot {
         self.table_configuration().table_properties()
     }
 
+    /// Get the [`TableCapabilities`] of this snapshot: which operations (e.g. appends, deletes,
+    /// checkpoint writes) kernel supports on the table at this version.
+    pub fn capabilities(&self) -> TableCapabilities {
+        self.table_configuration().capabilities()
+    }
+
+    /// Compute the table configuration changes (table properties and protocol features) from this
+    /// snapshot to `other`. Typically `other` is a newer snapshot of the same table, e.g. obtained
+    /// by refreshing this one with [`Snapshot::try_new_from`].
+    pub fn config_diff(&self, other: &Snapshot) -> ConfigDiff {
+        ConfigDiff::new(self, other)
+    }
+
     /// Get the [`TableConfiguration`] for this [`Snapshot`].
     #[internal_api]
     pub(crate) fn table_configuration(&self) -> &TableConfiguration {
@@ -351,7 +653,17 @@ impl Snapshot {
     }
 
     /// Create a [`Transaction`] for this `Arc<Snapshot>`.
+    ///
+    /// Fails if the snapshot includes commits its catalog has not yet published to the
+    /// `_delta_log` (see [`Snapshot::try_from_catalog`]): such tables must be written through
+    /// their catalog, which kernel does not support.
     pub fn transaction(self: Arc<Self>) -> DeltaResult<Transaction> {
+        require!(
+            self.log_segment.unpublished_commits().next().is_none(),
+            Error::unsupported(
+                "Cannot write to a table with commits its catalog has not yet published"
+            )
+        );
         Transaction::try_new(self)
     }
 
@@ -375,10 +687,161 @@ impl Snapshot {
         Ok(txn.map(|t| t.version))
     }
 
+    /// The number of live data files in this snapshot.
+    ///
+    /// This is read from the version checksum (CRC) file of this snapshot's version if present, and
+    /// otherwise computed by log replay. The result is cached, so subsequent calls (including
+    /// [`Snapshot::total_size_bytes`]) are free.
+    pub fn file_count(self: Arc<Self>, engine: &dyn Engine) -> DeltaResult<i64> {
+        Ok(self.file_stats(engine)?.num_files)
+    }
+
+    /// The total size in bytes of the live data files in this snapshot.
+    ///
+    /// This is read from the version checksum (CRC) file of this snapshot's version if present, and
+    /// otherwise computed by log replay. The result is cached, so subsequent calls (including
+    /// [`Snapshot::file_count`]) are free.
+    pub fn total_size_bytes(self: Arc<Self>, engine: &dyn Engine) -> DeltaResult<i64> {
+        Ok(self.file_stats(engine)?.size_bytes)
+    }
+
+    fn file_stats(self: Arc<Self>, engine: &dyn Engine) -> DeltaResult<FileStats> {
+        if let Some(file_stats) = self.file_stats.get() {
+            return Ok(*file_stats);
+        }
+        let file_stats = match self.crc(engine) {
+            Some(crc) => FileStats {
+                num_files: crc.num_files,
+                size_bytes: crc.table_size_bytes,
+            },
+            None => self.replay_file_stats(engine)?,
+        };
+        Ok(*self.file_stats.get_or_init(|| file_stats))
+    }
+
+    // The CRC file is only an optimization: if it is missing, stale, or unreadable we fall back to
+    // log replay instead of failing.
+    pub(crate) fn crc(&self, engine: &dyn Engine) -> Option<&CrcSummary> {
+        self.crc
+            .get_or_init(|| {
+                let crc_file = self
+                    .log_segment
+                    .latest_crc_file
+                    .as_ref()
+                    .filter(|crc_file| crc_file.version == self.version())?;
+                CrcSummary::try_read(engine.storage_handler().as_ref(), &crc_file.location)
+                    .inspect_err(|e| {
+                        warn!(
+                            "failed to read CRC file {}: {e}",
+                            crc_file.location.location
+                        )
+                    })
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    fn replay_file_stats(&self, engine: &dyn Engine) -> DeltaResult<FileStats> {
+        let mut file_stats = FileStats {
+            num_files: 0,
+            size_bytes: 0,
+        };
+        for file in self.files(engine)? {
+            file_stats.num_files += 1;
+            file_stats.size_bytes += i64::try_from(file?.file.size)
+                .map_err(|_| Error::generic("Total size of files overflows i64"))?;
+        }
+        Ok(file_stats)
+    }
+
+    /// Verify the version checksum (CRC) file of this snapshot's version against the table state.
+    ///
+    /// Unlike [`Snapshot::file_count`] and [`Snapshot::total_size_bytes`], which trust the CRC file
+    /// when present, this always performs log replay to recompute the file count and table size,
+    /// and compares them, the protocol and metadata, and (if the CRC file records them) the domain
+    /// metadata and application transactions against the CRC file. Every field on which the CRC
+    /// file disagrees with log replay is reported. A mismatch indicates a corrupt log or CRC file.
+    ///
+    /// Returns an error if this snapshot's version has no CRC file, or it cannot be read.
+    pub fn verify_checksum(&self, engine: &dyn Engine) -> DeltaResult<ChecksumReport> {
+        let crc_file = self
+            .log_segment
+            .latest_crc_file
+            .as_ref()
+            .filter(|crc_file| crc_file.version == self.version())
+            .ok_or_else(|| {
+                Error::generic(format!(
+                    "No version checksum file found for version {}",
+                    self.version()
+                ))
+            })?;
+        let crc = CrcTableState::try_read(engine.storage_handler().as_ref(), &crc_file.location)?;
+        let file_stats = self.replay_file_stats(engine)?;
+
+        let mut mismatches = vec![];
+        if crc.num_files != file_stats.num_files {
+            mismatches.push(ChecksumMismatch::NumFiles {
+                checksum: crc.num_files,
+                computed: file_stats.num_files,
+            });
+        }
+        if crc.table_size_bytes != file_stats.size_bytes {
+            mismatches.push(ChecksumMismatch::TableSizeBytes {
+                checksum: crc.table_size_bytes,
+                computed: file_stats.size_bytes,
+            });
+        }
+        // a snapshot always has exactly one (reconciled) metadata and protocol
+        if crc.num_metadata != 1 {
+            mismatches.push(ChecksumMismatch::NumMetadata {
+                checksum: crc.num_metadata,
+                computed: 1,
+            });
+        }
+        if crc.num_protocol != 1 {
+            mismatches.push(ChecksumMismatch::NumProtocol {
+                checksum: crc.num_protocol,
+                computed: 1,
+            });
+        }
+        if &crc.metadata != self.metadata() {
+            mismatches.push(ChecksumMismatch::Metadata);
+        }
+        if &crc.protocol != self.protocol() {
+            mismatches.push(ChecksumMismatch::Protocol);
+        }
+        if let Some(checksum) = crc.domain_configurations() {
+            let computed = all_domain_metadata_configurations(self.log_segment(), engine)?;
+            mismatches.extend(
+                mismatched_keys(&checksum, &computed)
+                    .into_iter()
+                    .map(|domain| ChecksumMismatch::DomainMetadata { domain }),
+            );
+        }
+        if let Some(checksum) = crc.transaction_versions() {
+            let computed: HashMap<_, _> =
+                SetTransactionScanner::get_all(self.log_segment(), engine, None)?
+                    .into_iter()
+                    .map(|(app_id, txn)| (app_id, txn.version))
+                    .collect();
+            mismatches.extend(
+                mismatched_keys(&checksum, &computed)
+                    .into_iter()
+                    .map(|app_id| ChecksumMismatch::SetTransaction { app_id }),
+            );
+        }
+        Ok(ChecksumReport {
+            version: self.version(),
+            mismatches,
+        })
+    }
+
     /// Fetch the domainMetadata for a specific domain in this snapshot. This returns the latest
     /// configuration for the domain, or None if the domain does not exist.
     ///
-    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    /// The domain metadata is read from the version checksum (CRC) file of this snapshot's version
+    /// if it records domain metadata. Otherwise, this method performs log replay (fetches and
+    /// processes metadata from storage).
     pub fn get_domain_metadata(
         &self,
         domain: &str,
@@ -389,9 +852,58 @@ impl Snapshot {
                 "User DomainMetadata are not allowed to use system-controlled 'delta.*' domain",
             ));
         }
+        self.get_domain_metadata_internal(domain, engine)
+    }
 
+    // Like `get_domain_metadata`, but also for system-controlled 'delta.*' domains
+    pub(crate) fn get_domain_metadata_internal(
+        &self,
+        domain: &str,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<String>> {
+        if let Some(configuration) = self
+            .crc(engine)
+            .and_then(|crc| crc.domain_configuration(domain))
+        {
+            return Ok(configuration);
+        }
         domain_metadata_configuration(self.log_segment(), domain, engine)
     }
+
+    /// Fetch the configurations of all domains in this snapshot, as `(domain, configuration)`
+    /// pairs ordered by domain. Removed domains and system-controlled 'delta.*' domains are not
+    /// included.
+    ///
+    /// The domain metadata is read from the version checksum (CRC) file of this snapshot's version
+    /// if it records domain metadata. Otherwise, this method replays the entire log.
+    pub fn get_all_domain_metadata(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<impl Iterator<Item = (String, String)>> {
+        let configurations = match self.crc(engine).and_then(|crc| crc.domain_configurations()) {
+            Some(configurations) => configurations,
+            None => all_domain_metadata_configurations(self.log_segment(), engine)?,
+        };
+        Ok(configurations
+            .into_iter()
+            .filter(|(domain, _)| !domain.starts_with(INTERNAL_DOMAIN_PREFIX))
+            .sorted_unstable_by(|(a, _), (b, _)| a.cmp(b)))
+    }
+}
+
+/// The keys whose values differ between `a` and `b`, including keys present in only one of them,
+/// in sorted order.
+fn mismatched_keys<K: Clone + Eq + Hash + Ord, V: PartialEq>(
+    a: &HashMap<K, V>,
+    b: &HashMap<K, V>,
+) -> Vec<K> {
+    a.keys()
+        .chain(b.keys())
+        .filter(|key| a.get(*key) != b.get(*key))
+        .unique()
+        .sorted()
+        .cloned()
+        .collect()
 }
 
 // Note: Schema can not be derived because the checkpoint schema is only known at runtime.
@@ -466,7 +978,7 @@ mod tests {
     use crate::engine::sync::SyncEngine;
     use crate::path::ParsedLogPath;
     use crate::utils::test_utils::string_array_to_engine_data;
-    use test_utils::{add_commit, delta_path_for_version};
+    use test_utils::{actions_to_string, add_commit, delta_path_for_version, TestAction};
 
     #[test]
     fn test_snapshot_read_metadata() {
@@ -514,6 +1026,66 @@ mod tests {
         add_commit(store, version, commit_data).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_snapshot_with_unknown_types() -> DeltaResult<()> {
+        use crate::schema::DataType;
+
+        let store = Arc::new(InMemory::new());
+        let schema_string = r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}},{"name":"geo","type":"geography(OGC:CRS84)","nullable":true,"metadata":{}}]}"#;
+        let commit0 = vec![
+            json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+            json!({
+                "metaData": {
+                    "id": "5fba94ed-9794-4965-ba6e-6ee3c0d22af9",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": schema_string,
+                    "partitionColumns": [],
+                    "configuration": {},
+                    "createdTime": 1587968585495i64
+                }
+            }),
+        ];
+        commit(store.as_ref(), 0, commit0).await;
+        let url = Url::parse("memory:///")?;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+        let err = Snapshot::try_new(url.clone(), &engine, None).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unsupported data type 'geography(OGC:CRS84)' for column geo"),
+            "{err}"
+        );
+
+        let snapshot = Arc::new(Snapshot::try_new_with_unknown_types(
+            url.clone(),
+            &engine,
+            None,
+        )?);
+        let geo = snapshot.schema().field("geo").unwrap().data_type().clone();
+        assert_eq!(geo, DataType::Unsupported("geography(OGC:CRS84)".into()));
+
+        // reading the unknown column fails, reading the others works
+        assert!(snapshot.clone().scan_builder().build().is_err());
+        let id_schema = snapshot.schema().project(&["id"])?;
+        snapshot
+            .clone()
+            .scan_builder()
+            .with_schema(id_schema)
+            .build()?;
+        assert!(snapshot.clone().transaction().is_err());
+
+        // the setting carries over to newer snapshots
+        commit(
+            store.as_ref(),
+            1,
+            vec![json!({"commitInfo": {"timestamp": 1587968586154i64}})],
+        )
+        .await;
+        let snapshot = Snapshot::try_new_from(snapshot, &engine, None)?;
+        assert_eq!(snapshot.version(), 1);
+        Ok(())
+    }
+
     // interesting cases for testing Snapshot::new_from:
     // 1. new version < existing version
     // 2. new version == existing version
@@ -709,6 +1281,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_snapshot_try_refresh() -> DeltaResult<()> {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("memory:///")?;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        add_commit(
+            store.as_ref(),
+            0,
+            actions_to_string(vec![TestAction::Metadata]),
+        )
+        .await
+        .unwrap();
+        let commit_info =
+            json!({"commitInfo": {"timestamp": 1587968586154i64, "operation": "WRITE"}});
+        add_commit(store.as_ref(), 1, commit_info.to_string())
+            .await
+            .unwrap();
+
+        // No new version: the same snapshot is returned
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None)?);
+        let refreshed = snapshot.try_refresh(&engine)?;
+        assert!(Arc::ptr_eq(&snapshot, &refreshed));
+
+        // New versions, one of which changes the protocol
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 3}});
+        let commit2 = [commit_info.to_string(), protocol.to_string()].join("\n");
+        add_commit(store.as_ref(), 2, commit2).await.unwrap();
+        add_commit(store.as_ref(), 3, commit_info.to_string())
+            .await
+            .unwrap();
+        let refreshed = snapshot.try_refresh(&engine)?;
+        let expected = Snapshot::try_new(url.clone(), &engine, None)?;
+        assert_eq!(refreshed.version(), 3);
+        assert_eq!(refreshed, expected.into());
+
+        // Refreshing an older snapshot catches up to the latest version
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, Some(2))?);
+        assert_eq!(snapshot.try_refresh(&engine)?, refreshed);
+        Ok(())
+    }
+
     // test new CRC in new log segment (old log segment has old CRC)
     #[tokio::test]
     async fn test_snapshot_new_from_crc() -> Result<(), Box<dyn std::error::Error>> {
@@ -824,6 +1437,170 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_file_count_and_total_size_bytes() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("memory:///")?;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let add = |path: &str, size: i64| {
+            json!({
+                "add": {
+                    "path": path,
+                    "partitionValues": {},
+                    "size": size,
+                    "modificationTime": 1587968586000i64,
+                    "dataChange": true
+                }
+            })
+        };
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let metadata = json!({
+            "metaData": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1587968585495i64
+            }
+        });
+        commit(
+            &store,
+            0,
+            vec![protocol, metadata, add("a", 10), add("b", 20)],
+        )
+        .await;
+        let remove = json!({"remove": {"path": "a", "deletionTimestamp": 1587968586000i64, "dataChange": true}});
+        commit(&store, 1, vec![remove, add("c", 5)]).await;
+
+        // no CRC: computed by log replay
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None)?);
+        assert_eq!(snapshot.clone().file_count(&engine)?, 2);
+        assert_eq!(snapshot.clone().total_size_bytes(&engine)?, 25);
+
+        // a CRC for an older version is not used
+        let crc = |num_files: i64, table_size_bytes: i64| {
+            json!({"numFiles": num_files, "tableSizeBytes": table_size_bytes}).to_string()
+        };
+        let path = delta_path_for_version(0, "crc");
+        store.put(&path, crc(100, 1000).into()).await?;
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None)?);
+        assert_eq!(snapshot.clone().file_count(&engine)?, 2);
+
+        // a CRC for the snapshot version is used instead of log replay
+        let path = delta_path_for_version(1, "crc");
+        store.put(&path, crc(7, 700).into()).await?;
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None)?);
+        assert_eq!(snapshot.clone().file_count(&engine)?, 7);
+        assert_eq!(snapshot.clone().total_size_bytes(&engine)?, 700);
+
+        // ... and the result is cached on the snapshot
+        store.put(&path, crc(8, 800).into()).await?;
+        assert_eq!(snapshot.clone().file_count(&engine)?, 7);
+
+        // an invalid CRC falls back to log replay
+        store.put(&path, "{\"numFiles\": 8}".into()).await?;
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        assert_eq!(snapshot.clone().file_count(&engine)?, 2);
+        assert_eq!(snapshot.total_size_bytes(&engine)?, 25);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksum() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(InMemory::new());
+        let url = Url::parse("memory:///")?;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let actions = vec![
+            TestAction::Metadata,
+            TestAction::Add("a".to_string()),
+            TestAction::Add("b".to_string()),
+        ];
+        add_commit(store.as_ref(), 0, actions_to_string(actions)).await?;
+
+        // there is nothing to verify without a CRC file
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None)?;
+        assert!(snapshot.verify_checksum(&engine).is_err());
+
+        let valid_crc = json!({
+            "tableSizeBytes": 524,
+            "numFiles": 2,
+            "numMetadata": 1,
+            "numProtocol": 1,
+            "metadata": snapshot.metadata(),
+            "protocol": snapshot.protocol(),
+            "setTransactions": [],
+            "domainMetadata": [],
+        });
+        let path = delta_path_for_version(0, "crc");
+        store.put(&path, valid_crc.to_string().into()).await?;
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None)?;
+        let report = snapshot.verify_checksum(&engine)?;
+        assert_eq!(report.version, 0);
+        assert!(report.is_valid());
+
+        // every mismatching field is reported, and the CRC file is not trusted for the file stats
+        let mut crc = valid_crc.clone();
+        crc["numFiles"] = json!(3);
+        crc["tableSizeBytes"] = json!(40);
+        crc["numMetadata"] = json!(2);
+        crc["numProtocol"] = json!(0);
+        crc["metadata"]["id"] = json!("other");
+        crc["protocol"]["minWriterVersion"] = json!(3);
+        crc["setTransactions"] = json!([{"appId": "app", "version": 1}]);
+        crc["domainMetadata"] = json!([
+            {"domain": "live", "configuration": "", "removed": false},
+            {"domain": "removed", "configuration": "", "removed": true},
+        ]);
+        store.put(&path, crc.to_string().into()).await?;
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None)?;
+        let report = snapshot.verify_checksum(&engine)?;
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.mismatches,
+            vec![
+                ChecksumMismatch::NumFiles {
+                    checksum: 3,
+                    computed: 2
+                },
+                ChecksumMismatch::TableSizeBytes {
+                    checksum: 40,
+                    computed: 524
+                },
+                ChecksumMismatch::NumMetadata {
+                    checksum: 2,
+                    computed: 1
+                },
+                ChecksumMismatch::NumProtocol {
+                    checksum: 0,
+                    computed: 1
+                },
+                ChecksumMismatch::Metadata,
+                ChecksumMismatch::Protocol,
+                ChecksumMismatch::DomainMetadata {
+                    domain: "live".to_string()
+                },
+                ChecksumMismatch::SetTransaction {
+                    app_id: "app".to_string()
+                },
+            ]
+        );
+
+        // domain metadata and transactions are only verified if the CRC file records them
+        let mut crc = valid_crc;
+        crc.as_object_mut().unwrap().remove("setTransactions");
+        crc.as_object_mut().unwrap().remove("domainMetadata");
+        store.put(&path, crc.to_string().into()).await?;
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None)?;
+        assert!(snapshot.verify_checksum(&engine)?.is_valid());
+
+        // an unreadable CRC file is an error
+        store.put(&path, "{\"numFiles\": 2}".into()).await?;
+        let snapshot = Snapshot::try_new(url, &engine, None)?;
+        assert!(snapshot.verify_checksum(&engine).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_read_table_with_missing_last_checkpoint() {
         // this table doesn't have a _last_checkpoint file
@@ -944,6 +1721,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_snapshot_table_root_without_trailing_slash() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned")).unwrap();
+        let url = url::Url::from_file_path(path).unwrap();
+        assert!(!url.path().ends_with('/'));
+
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::try_new(url.clone(), &engine, None).unwrap();
+        assert_eq!(snapshot.version(), 1);
+        assert_eq!(snapshot.table_root().as_str(), format!("{url}/"));
+    }
+
     #[tokio::test]
     async fn test_domain_metadata() -> DeltaResult<()> {
         let url = Url::parse("memory:///")?;
@@ -1043,6 +1832,43 @@ mod tests {
             .unwrap_err();
         assert!(matches!(err, Error::Generic(msg) if
                 msg == "User DomainMetadata are not allowed to use system-controlled 'delta.*' domain"));
+        assert_eq!(
+            snapshot.get_all_domain_metadata(&engine)?.collect_vec(),
+            vec![
+                ("domain2".to_string(), "domain2_commit1".to_string()),
+                ("domain3".to_string(), "domain3_commit0".to_string()),
+            ]
+        );
+
+        // a CRC file that records domain metadata is used instead of log replay
+        let crc = json!({
+            "tableSizeBytes": 0,
+            "numFiles": 0,
+            "domainMetadata": [
+                {"domain": "domain2", "configuration": "domain2_crc", "removed": false}
+            ]
+        });
+        let path = delta_path_for_version(1, "crc");
+        store.put(&path, crc.to_string().into()).await?;
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None)?);
+        assert_eq!(
+            snapshot.get_domain_metadata("domain2", &engine)?,
+            Some("domain2_crc".to_string())
+        );
+        assert_eq!(snapshot.get_domain_metadata("domain3", &engine)?, None);
+        assert_eq!(
+            snapshot.get_all_domain_metadata(&engine)?.collect_vec(),
+            vec![("domain2".to_string(), "domain2_crc".to_string())]
+        );
+
+        // ... but not if it doesn't record domain metadata
+        let crc = json!({"tableSizeBytes": 0, "numFiles": 0});
+        store.put(&path, crc.to_string().into()).await?;
+        let snapshot = Arc::new(Snapshot::try_new(url, &engine, None)?);
+        assert_eq!(
+            snapshot.get_domain_metadata("domain2", &engine)?,
+            Some("domain2_commit1".to_string())
+        );
         Ok(())
     }
 }
@@ -0,0 +1,282 @@
+//! Typed access to the metadata of the columns of a [`Snapshot`]'s schema, see
+//! [`Snapshot::column_metadata`].
+
+use crate::schema::{ColumnMetadataKey, ColumnName, MetadataValue, StructField, StructType};
+use crate::snapshot::Snapshot;
+use crate::{DataType, DeltaResult, Error};
+
+/// The metadata of a (possibly nested) column of a table, as returned by
+/// [`Snapshot::column_metadata`]. Each field is `None` if the column's metadata does not set it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnMetadata {
+    /// The user-provided description of the column (the `comment` key).
+    pub comment: Option<String>,
+    /// The SQL boolean expression which every value of the column must satisfy, from the column's
+    /// (legacy) invariant (the `delta.invariants` key).
+    pub invariant: Option<String>,
+    /// The SQL expression computing the values of a generated column, i.e. a column defined
+    /// `GENERATED ALWAYS AS (<expression>)` (the `delta.generationExpression` key).
+    pub generation_expression: Option<String>,
+    /// The column mapping id of the column (the `delta.columnMapping.id` key).
+    pub column_mapping_id: Option<i64>,
+    /// The name of the column in the data files, when column mapping is enabled (the
+    /// `delta.columnMapping.physicalName` key).
+    pub physical_name: Option<String>,
+}
+
+impl Snapshot {
+    /// The metadata of every column of this snapshot's schema, including columns nested in
+    /// structs, by (logical) column name. Parents come before their children, and siblings are in
+    /// schema order. Fields of structs in arrays and maps are named through the array's `element`
+    /// and the map's `key` or `value`, e.g. `a.element.b` for the field `b` of the structs in the
+    /// array `a`.
+    ///
+    /// This saves catalog sync tools and engines from traversing the schema and parsing the raw
+    /// metadata maps of its fields (see [`StructField::metadata`]). Fails if a known metadata key
+    /// has a value of the wrong type, e.g. a non-numeric column mapping id.
+    pub fn column_metadata(&self) -> DeltaResult<Vec<(ColumnName, ColumnMetadata)>> {
+        let mut columns = vec![];
+        collect_column_metadata(&self.schema(), &mut vec![], &mut columns)?;
+        Ok(columns)
+    }
+}
+
+fn collect_column_metadata(
+    schema: &StructType,
+    path: &mut Vec<String>,
+    columns: &mut Vec<(ColumnName, ColumnMetadata)>,
+) -> DeltaResult<()> {
+    for field in schema.fields() {
+        path.push(field.name().clone());
+        let column = ColumnName::new(path.iter().cloned());
+        let metadata = ColumnMetadata::try_from_field(field).map_err(|err| {
+            Error::generic(format!("Invalid metadata for column {column}: {err}"))
+        })?;
+        columns.push((column, metadata));
+        collect_nested_column_metadata(field.data_type(), path, columns)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+// Collect the metadata of the struct fields within `data_type`, the type of the column at `path`
+fn collect_nested_column_metadata(
+    data_type: &DataType,
+    path: &mut Vec<String>,
+    columns: &mut Vec<(ColumnName, ColumnMetadata)>,
+) -> DeltaResult<()> {
+    let mut collect_child = |name: &str, data_type| {
+        path.push(name.to_string());
+        let result = collect_nested_column_metadata(data_type, path, columns);
+        path.pop();
+        result
+    };
+    match data_type {
+        DataType::Struct(children) => collect_column_metadata(children, path, columns),
+        DataType::Array(array) => collect_child("element", array.element_type()),
+        DataType::Map(map) => {
+            collect_child("key", map.key_type())?;
+            collect_child("value", map.value_type())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl ColumnMetadata {
+    fn try_from_field(field: &StructField) -> DeltaResult<Self> {
+        let invariant = match string_value(field, &ColumnMetadataKey::Invariants)? {
+            Some(invariant) => Some(parse_invariant(&invariant)?),
+            None => None,
+        };
+        let column_mapping_id = match field.get_config_value(&ColumnMetadataKey::ColumnMappingId) {
+            Some(MetadataValue::Number(id)) => Some(*id),
+            Some(value) => {
+                return Err(Error::generic(format!(
+                    "expected a number for {}, got {value}",
+                    ColumnMetadataKey::ColumnMappingId.as_ref()
+                )))
+            }
+            None => None,
+        };
+        Ok(Self {
+            comment: string_value(field, &ColumnMetadataKey::Comment)?,
+            invariant,
+            generation_expression: string_value(field, &ColumnMetadataKey::GenerationExpression)?,
+            column_mapping_id,
+            physical_name: string_value(field, &ColumnMetadataKey::ColumnMappingPhysicalName)?,
+        })
+    }
+}
+
+fn string_value(field: &StructField, key: &ColumnMetadataKey) -> DeltaResult<Option<String>> {
+    match field.get_config_value(key) {
+        Some(MetadataValue::String(value)) => Ok(Some(value.clone())),
+        Some(value) => Err(Error::generic(format!(
+            "expected a string for {}, got {value}",
+            key.as_ref()
+        ))),
+        None => Ok(None),
+    }
+}
+
+// Invariants are stored as JSON of the form `{"expression": {"expression": "<sql expression>"}}`
+fn parse_invariant(invariant: &str) -> DeltaResult<String> {
+    let invariant: serde_json::Value = serde_json::from_str(invariant)?;
+    invariant["expression"]["expression"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| Error::generic(format!("invalid invariant {invariant}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use url::Url;
+
+    use super::*;
+    use crate::actions::{Metadata, Protocol};
+    use crate::engine::sync::SyncEngine;
+    use crate::schema::{column_name, ArrayType, MapType};
+    use crate::utils::test_utils::{Action, LocalMockTable};
+
+    async fn snapshot_with_schema(schema: &StructType, column_mapping: bool) -> Snapshot {
+        let (protocol, configuration) = match column_mapping {
+            true => (
+                Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>),
+                HashMap::from([("delta.columnMapping.mode".to_string(), "name".to_string())]),
+            ),
+            false => (
+                Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>),
+                HashMap::new(),
+            ),
+        };
+        let mut mock_table = LocalMockTable::new();
+        mock_table
+            .commit([
+                Action::Protocol(protocol.unwrap()),
+                Action::Metadata(Metadata {
+                    schema_string: serde_json::to_string(schema).unwrap(),
+                    configuration,
+                    ..Default::default()
+                }),
+            ])
+            .await;
+        let url = Url::from_directory_path(mock_table.table_root()).unwrap();
+        Snapshot::try_new(url, &SyncEngine::new(), None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_column_metadata() -> DeltaResult<()> {
+        let nested = StructType::new([StructField::nullable("b", DataType::LONG).with_metadata([
+            ("comment", MetadataValue::from("nested column")),
+            ("delta.columnMapping.id", 3.into()),
+            ("delta.columnMapping.physicalName", "col-b".into()),
+        ])]);
+        let schema = StructType::new([
+            StructField::nullable("a", nested).with_metadata([
+                ("delta.columnMapping.id", MetadataValue::from(1)),
+                ("delta.columnMapping.physicalName", "col-a".into()),
+            ]),
+            StructField::nullable("c", DataType::INTEGER).with_metadata([
+                (
+                    "delta.invariants",
+                    MetadataValue::from(r#"{"expression":{"expression":"c > 0"}}"#),
+                ),
+                ("delta.generationExpression", "a.b + 1".into()),
+                ("comment", "generated".into()),
+                ("delta.columnMapping.id", 4.into()),
+                ("delta.columnMapping.physicalName", "col-c".into()),
+            ]),
+        ]);
+        let snapshot = snapshot_with_schema(&schema, true).await;
+        let expected = vec![
+            (
+                column_name!("a"),
+                ColumnMetadata {
+                    column_mapping_id: Some(1),
+                    physical_name: Some("col-a".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                column_name!("a.b"),
+                ColumnMetadata {
+                    comment: Some("nested column".into()),
+                    column_mapping_id: Some(3),
+                    physical_name: Some("col-b".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                column_name!("c"),
+                ColumnMetadata {
+                    comment: Some("generated".into()),
+                    invariant: Some("c > 0".into()),
+                    generation_expression: Some("a.b + 1".into()),
+                    column_mapping_id: Some(4),
+                    physical_name: Some("col-c".into()),
+                },
+            ),
+        ];
+        assert_eq!(snapshot.column_metadata()?, expected);
+
+        // Columns without metadata have empty column metadata
+        let schema = StructType::new([StructField::nullable("d", DataType::STRING)]);
+        let snapshot = snapshot_with_schema(&schema, false).await;
+        let expected = vec![(column_name!("d"), ColumnMetadata::default())];
+        assert_eq!(snapshot.column_metadata()?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_column_metadata_invalid() -> DeltaResult<()> {
+        for (key, value) in [
+            ("comment", MetadataValue::from(1)),
+            ("delta.generationExpression", true.into()),
+            ("delta.invariants", "c > 0".into()),
+        ] {
+            let schema = StructType::new([
+                StructField::nullable("c", DataType::INTEGER).with_metadata([(key, value)])
+            ]);
+            let snapshot = snapshot_with_schema(&schema, false).await;
+            let err = snapshot.column_metadata().unwrap_err().to_string();
+            assert!(err.contains("Invalid metadata for column c"), "{err}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_column_metadata_in_arrays_and_maps() -> DeltaResult<()> {
+        let field = |name: &str| {
+            StructField::nullable(name, DataType::INTEGER)
+                .with_metadata([("comment", MetadataValue::from(name))])
+        };
+        let struct_of = |name: &str| StructType::new([field(name)]);
+        let schema = StructType::new([
+            StructField::nullable("a", ArrayType::new(struct_of("x").into(), true)),
+            StructField::nullable(
+                "m",
+                MapType::new(
+                    struct_of("k"),
+                    ArrayType::new(struct_of("v").into(), true),
+                    true,
+                ),
+            ),
+        ]);
+        let snapshot = snapshot_with_schema(&schema, false).await;
+        let comment = |comment: &str| ColumnMetadata {
+            comment: Some(comment.into()),
+            ..Default::default()
+        };
+        let expected = vec![
+            (column_name!("a"), ColumnMetadata::default()),
+            (column_name!("a.element.x"), comment("x")),
+            (column_name!("m"), ColumnMetadata::default()),
+            (column_name!("m.key.k"), comment("k")),
+            (column_name!("m.value.element.v"), comment("v")),
+        ];
+        assert_eq!(snapshot.column_metadata()?, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Detect table configuration (table properties and protocol features) changes between two
+//! [`Snapshot`]s.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::actions::Protocol;
+use crate::snapshot::Snapshot;
+
+/// The table configuration changes between two snapshots, as computed by
+/// [`Snapshot::config_diff`]. Engines can use this after refreshing a snapshot to cheaply decide
+/// whether configuration-derived caches (e.g. the choice of stats columns, or whether CDF is
+/// enabled) need to be invalidated.
+///
+/// Features are reported by name (e.g. `deletionVectors`), across both reader and writer features.
+/// Note that tables on legacy protocol versions (without explicit feature lists) have no features.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Table properties set in the new snapshot but not the old one, with their new values.
+    pub added_properties: HashMap<String, String>,
+    /// Table properties set in the old snapshot but not the new one, with their old values.
+    pub removed_properties: HashMap<String, String>,
+    /// Table properties set in both snapshots with different values, as `(old, new)`.
+    pub changed_properties: HashMap<String, (String, String)>,
+    /// Features supported by the new snapshot but not the old one.
+    pub added_features: HashSet<String>,
+    /// Features supported by the old snapshot but not the new one.
+    pub removed_features: HashSet<String>,
+}
+
+impl ConfigDiff {
+    pub(crate) fn new(old: &Snapshot, new: &Snapshot) -> Self {
+        let old_properties = old.metadata().configuration();
+        let new_properties = new.metadata().configuration();
+        let mut diff = ConfigDiff::default();
+        for (key, new_value) in new_properties {
+            match old_properties.get(key) {
+                None => {
+                    diff.added_properties.insert(key.clone(), new_value.clone());
+                }
+                Some(old_value) if old_value != new_value => {
+                    diff.changed_properties
+                        .insert(key.clone(), (old_value.clone(), new_value.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed_properties = old_properties
+            .iter()
+            .filter(|(key, _)| !new_properties.contains_key(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        let old_features = feature_names(old.protocol());
+        let new_features = feature_names(new.protocol());
+        diff.added_features = new_features.difference(&old_features).cloned().collect();
+        diff.removed_features = old_features.difference(&new_features).cloned().collect();
+        diff
+    }
+
+    /// Returns true if the table configuration did not change.
+    pub fn is_empty(&self) -> bool {
+        self.added_properties.is_empty()
+            && self.removed_properties.is_empty()
+            && self.changed_properties.is_empty()
+            && self.added_features.is_empty()
+            && self.removed_features.is_empty()
+    }
+}
+
+fn feature_names(protocol: &Protocol) -> HashSet<String> {
+    let reader_features = protocol.reader_features().unwrap_or_default();
+    let writer_features = protocol.writer_features().unwrap_or_default();
+    reader_features
+        .iter()
+        .map(|f| f.to_string())
+        .chain(writer_features.iter().map(|f| f.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::json;
+    use url::Url;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::object_store;
+    use crate::snapshot::Snapshot;
+
+    fn metadata(configuration: serde_json::Value) -> serde_json::Value {
+        json!({
+            "metaData": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                "partitionColumns": [],
+                "configuration": configuration,
+                "createdTime": 1587968585495i64
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_config_diff() {
+        let store = Arc::new(InMemory::new());
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let metadata0 = metadata(json!({
+            "delta.appendOnly": "true",
+            "delta.logRetentionDuration": "interval 30 days",
+            "delta.checkpointInterval": "10"
+        }));
+        let commit0 = format!("{protocol}\n{metadata0}");
+        store
+            .put(
+                &Path::from("table/_delta_log/00000000000000000000.json"),
+                commit0.into(),
+            )
+            .await
+            .unwrap();
+        let protocol = json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors"],
+                "writerFeatures": ["deletionVectors", "changeDataFeed"]
+            }
+        });
+        let metadata1 = metadata(json!({
+            "delta.appendOnly": "true",
+            "delta.logRetentionDuration": "interval 7 days",
+            "delta.enableChangeDataFeed": "true"
+        }));
+        let commit1 = format!("{protocol}\n{metadata1}");
+        store
+            .put(
+                &Path::from("table/_delta_log/00000000000000000001.json"),
+                commit1.into(),
+            )
+            .await
+            .unwrap();
+
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let url = Url::parse("memory:///table/").unwrap();
+        let old = Snapshot::try_new(url.clone(), &engine, Some(0)).unwrap();
+        let new = Snapshot::try_new(url, &engine, Some(1)).unwrap();
+
+        let diff = old.config_diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.added_properties,
+            [("delta.enableChangeDataFeed".into(), "true".into())].into()
+        );
+        assert_eq!(
+            diff.removed_properties,
+            [("delta.checkpointInterval".into(), "10".into())].into()
+        );
+        assert_eq!(
+            diff.changed_properties,
+            [(
+                "delta.logRetentionDuration".into(),
+                ("interval 30 days".into(), "interval 7 days".into())
+            )]
+            .into()
+        );
+        assert_eq!(
+            diff.added_features,
+            ["deletionVectors".into(), "changeDataFeed".into()].into()
+        );
+        assert!(diff.removed_features.is_empty());
+
+        // the reverse diff swaps additions and removals
+        let reverse = new.config_diff(&old);
+        assert_eq!(reverse.added_properties, diff.removed_properties);
+        assert_eq!(reverse.removed_features, diff.added_features);
+
+        assert!(old.config_diff(&old).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+//! Enumerate the live data files of a [`Snapshot`], see [`Snapshot::files`].
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::compat::{Add, AddVisitor};
+use crate::engine_data::RowVisitor as _;
+use crate::scan::log_replay::scan_action_iter;
+use crate::scan::{ScanMetadata, CHECKPOINT_READ_SCHEMA, COMMIT_READ_SCHEMA};
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error, FileMeta};
+
+/// A live data file of a [`Snapshot`], as returned by [`Snapshot::files`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotFile {
+    /// The absolute location, size and modification time of the data file.
+    pub file: FileMeta,
+    /// The partition values of the file, by (physical) partition column name. Columns whose value
+    /// is null are left out.
+    pub partition_values: HashMap<String, String>,
+    /// The descriptor of the file's deletion vector, if it has one. Use
+    /// [`DeletionVectorDescriptor::row_indexes`] to read the deleted rows.
+    pub deletion_vector: Option<DeletionVectorDescriptor>,
+    /// The file's statistics as the raw JSON string from its `add` action, if any.
+    pub stats: Option<String>,
+    /// The tags of the file's `add` action, if any.
+    pub tags: Option<HashMap<String, String>>,
+    /// The row id of the first row of the file, if the table has row tracking.
+    pub base_row_id: Option<i64>,
+    /// The version of the commit which added the file (with this row id assignment), if the
+    /// table has row tracking.
+    pub default_row_commit_version: Option<i64>,
+}
+
+impl Snapshot {
+    /// Enumerate the live data files of this snapshot, with their partition values, deletion
+    /// vectors and statistics.
+    ///
+    /// This is meant for consumers that track the contents of a table rather than read it, such as
+    /// secondary index builders and caches. Unlike a [`Scan`], it needs no schema or predicate,
+    /// applies no data skipping, and works even if the table's schema has columns of types kernel
+    /// can't read. Files are returned in log replay order (newest commit first), which is not a
+    /// stable order across snapshots.
+    ///
+    /// [`Scan`]: crate::scan::Scan
+    pub fn files(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<SnapshotFile>> + Send> {
+        let actions = self.log_segment().read_actions(
+            engine,
+            COMMIT_READ_SCHEMA.clone(),
+            CHECKPOINT_READ_SCHEMA.clone(),
+            None,
+        )?;
+        let table_root = self.table_root().clone();
+        let files = scan_action_iter(engine, actions, self.schema(), None, Default::default())
+            .map(move |scan_metadata| visit_snapshot_files(&table_root, &scan_metadata?))
+            .flatten_ok();
+        Ok(files)
+    }
+}
+
+fn visit_snapshot_files(
+    table_root: &Url,
+    scan_metadata: &ScanMetadata,
+) -> DeltaResult<Vec<SnapshotFile>> {
+    let scan_files = &scan_metadata.scan_files;
+    // no partition columns, so null partition values are left out
+    let mut visitor = AddVisitor {
+        partition_columns: vec![],
+        selection_vector: &scan_files.selection_vector,
+        adds: vec![],
+    };
+    visitor.visit_rows_of(scan_files.data.as_ref())?;
+    visitor
+        .adds
+        .into_iter()
+        .map(|add| SnapshotFile::try_new(table_root, add))
+        .collect()
+}
+
+impl SnapshotFile {
+    fn try_new(table_root: &Url, add: Add) -> DeltaResult<Self> {
+        let size = add.size.try_into().map_err(|_| {
+            Error::generic(format!("Invalid size {} for file {}", add.size, add.path))
+        })?;
+        let file = FileMeta {
+            location: table_root.join(&add.path)?,
+            last_modified: add.modification_time,
+            size,
+        };
+        let partition_values = add
+            .partition_values
+            .into_iter()
+            .filter_map(|(column, value)| Some((column, value?)))
+            .collect();
+        let tags = add.tags.map(|tags| {
+            tags.into_iter()
+                .filter_map(|(key, value)| Some((key, value?)))
+                .collect()
+        });
+        Ok(Self {
+            file,
+            partition_values,
+            deletion_vector: add.deletion_vector,
+            stats: add.stats,
+            tags,
+            base_row_id: add.base_row_id,
+            default_row_commit_version: add.default_row_commit_version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use itertools::Itertools;
+
+    use crate::engine::sync::SyncEngine;
+    use crate::Snapshot;
+
+    #[test]
+    fn test_files() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), &engine, None).unwrap());
+
+        let files: Vec<_> = snapshot.files(&engine).unwrap().try_collect().unwrap();
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(
+            file.file.location,
+            url.join("part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet")
+                .unwrap()
+        );
+        assert_eq!(file.file.size, 635);
+        assert!(file.file.last_modified > 0);
+        assert!(file.partition_values.is_empty());
+        let dv = file.deletion_vector.as_ref().unwrap();
+        assert_eq!(dv.cardinality, 2);
+        let stats = file.stats.as_deref().unwrap();
+        assert!(stats.contains(r#""numRecords":10"#), "{stats}");
+        let tags = file.tags.as_ref().unwrap();
+        assert_eq!(tags["INSERTION_TIME"], "1677811178336000");
+
+        // the version before the deletion vector was added
+        let snapshot = Snapshot::try_new(url, &engine, Some(0)).unwrap();
+        let files: Vec<_> = snapshot.files(&engine).unwrap().try_collect().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].deletion_vector, None);
+    }
+}
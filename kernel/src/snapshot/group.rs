@@ -0,0 +1,217 @@
+//! A [`SnapshotGroup`] resolves snapshots of several tables together, so that a job which must read
+//! all of them (e.g. a fact table and its dimension tables) sees a consistent set of versions.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::checkpoint::DEFAULT_RETENTION_SECS;
+use crate::log_segment::LogSegment;
+use crate::snapshot::Snapshot;
+use crate::utils::normalize_table_root;
+use crate::{DeltaResult, Engine, Error, Version};
+
+/// A set of [`Snapshot`]s of different tables, resolved back-to-back so that the chosen versions
+/// are close in time. The group records the version chosen for each table as well as the time at
+/// which they were resolved, which allows long-running jobs to [`revalidate`] that every snapshot
+/// is still readable before (or while) consuming it.
+///
+/// [`revalidate`]: SnapshotGroup::revalidate
+#[derive(Debug)]
+pub struct SnapshotGroup {
+    snapshots: Vec<Arc<Snapshot>>,
+    resolved_at: SystemTime,
+}
+
+impl SnapshotGroup {
+    /// Resolve the latest [`Snapshot`] of each table in `table_roots`, in the order given.
+    ///
+    /// # Parameters
+    ///
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `table_roots`: urls pointing at each table root (where `_delta_log` folder is located)
+    pub fn resolve(
+        engine: &dyn Engine,
+        table_roots: impl IntoIterator<Item = Url>,
+    ) -> DeltaResult<Self> {
+        let resolved_at = SystemTime::now();
+        let snapshots = table_roots
+            .into_iter()
+            .map(|table_root| Snapshot::try_new(table_root, engine, None).map(Arc::new))
+            .try_collect()?;
+        Ok(Self {
+            snapshots,
+            resolved_at,
+        })
+    }
+
+    /// The snapshots in this group, in the order their tables were passed to [`Self::resolve`].
+    pub fn snapshots(&self) -> &[Arc<Snapshot>] {
+        &self.snapshots
+    }
+
+    /// Get the snapshot resolved for the table at `table_root`, if it is part of this group.
+    pub fn get(&self, table_root: &Url) -> Option<&Arc<Snapshot>> {
+        let table_root = normalize_table_root(table_root.clone()).ok()?;
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.table_root() == &table_root)
+    }
+
+    /// The `(table_root, version)` chosen for each table in this group.
+    pub fn versions(&self) -> impl Iterator<Item = (&Url, Version)> + '_ {
+        self.snapshots
+            .iter()
+            .map(|snapshot| (snapshot.table_root(), snapshot.version()))
+    }
+
+    /// The time at which this group's snapshots were resolved.
+    pub fn resolved_at(&self) -> SystemTime {
+        self.resolved_at
+    }
+
+    /// Check that every snapshot in this group can still be read. This fails if:
+    ///
+    /// - the log files needed to reconstruct a snapshot's version have since been cleaned up, or
+    /// - more time has passed since the group was resolved than a table's
+    ///   `delta.deletedFileRetentionDuration` (7 days by default), in which case a `VACUUM` may
+    ///   have deleted data files that the pinned version still references.
+    ///
+    /// Note that this method lists the `_delta_log` directory of every table in the group.
+    pub fn revalidate(&self, engine: &dyn Engine) -> DeltaResult<()> {
+        self.revalidate_at(engine, SystemTime::now())
+    }
+
+    fn revalidate_at(&self, engine: &dyn Engine, now: SystemTime) -> DeltaResult<()> {
+        let elapsed = now
+            .duration_since(self.resolved_at)
+            .unwrap_or(Duration::ZERO);
+        let storage = engine.storage_handler();
+        for snapshot in &self.snapshots {
+            let table_root = snapshot.table_root();
+            let version = snapshot.version();
+            let retention = snapshot
+                .table_properties()
+                .deleted_file_retention_duration
+                .unwrap_or(Duration::from_secs(DEFAULT_RETENTION_SECS));
+            if elapsed > retention {
+                return Err(Error::generic(format!(
+                    "Snapshot of table {table_root} at version {version} was resolved {}s ago, \
+                    longer than its deleted file retention of {}s; data files may have been vacuumed",
+                    elapsed.as_secs(),
+                    retention.as_secs()
+                )));
+            }
+
+            // Re-list the log at the pinned version. Without a checkpoint, the version can only be
+            // reconstructed if the log still starts at commit 0.
+            let log_root = snapshot.log_segment().log_root.clone();
+            let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, version)
+                .map_err(|err| {
+                    Error::generic(format!(
+                        "Snapshot of table {table_root} at version {version} is no longer readable: {err}"
+                    ))
+                })?;
+            let starts_at_zero = log_segment
+                .ascending_commit_files
+                .first()
+                .is_some_and(|commit| commit.version == 0);
+            if log_segment.checkpoint_version.is_none() && !starts_at_zero {
+                return Err(Error::generic(format!(
+                    "Snapshot of table {table_root} at version {version} is no longer readable: \
+                    log files have been cleaned up"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::json;
+    use url::Url;
+
+    use super::SnapshotGroup;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::object_store;
+
+    fn commit_path(table: &str, version: u64) -> Path {
+        Path::from(format!("{table}/_delta_log/{version:020}.json"))
+    }
+
+    async fn write_commits(store: &InMemory, table: &str, num_commits: u64, configuration: &str) {
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let metadata = json!({
+            "metaData": {
+                "id": table,
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}",
+                "partitionColumns": [],
+                "configuration": {"delta.deletedFileRetentionDuration": configuration},
+                "createdTime": 1587968585495i64
+            }
+        });
+        let commit0 = format!("{protocol}\n{metadata}");
+        store
+            .put(&commit_path(table, 0), commit0.into())
+            .await
+            .unwrap();
+        for version in 1..num_commits {
+            let add = json!({
+                "add": {
+                    "path": format!("file-{version}.parquet"),
+                    "partitionValues": {},
+                    "size": 100,
+                    "modificationTime": 1587968586000i64,
+                    "dataChange": true
+                }
+            });
+            store
+                .put(&commit_path(table, version), add.to_string().into())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_and_revalidate() {
+        let store = Arc::new(InMemory::new());
+        write_commits(&store, "fact", 3, "interval 1 hours").await;
+        write_commits(&store, "dim", 2, "interval 7 days").await;
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+        let fact = Url::parse("memory:///fact/").unwrap();
+        let dim = Url::parse("memory:///dim/").unwrap();
+        let group = SnapshotGroup::resolve(&engine, [fact.clone(), dim.clone()]).unwrap();
+
+        let versions: Vec<_> = group.versions().map(|(url, v)| (url.clone(), v)).collect();
+        assert_eq!(versions, vec![(fact.clone(), 2), (dim.clone(), 1)]);
+        assert_eq!(group.get(&dim).unwrap().version(), 1);
+        assert!(group
+            .get(&Url::parse("memory:///other/").unwrap())
+            .is_none());
+        group.revalidate(&engine).unwrap();
+
+        // new commits don't invalidate the pinned versions
+        write_commits(&store, "dim", 4, "interval 7 days").await;
+        group.revalidate(&engine).unwrap();
+
+        // past the fact table's (short) retention, files may have been vacuumed
+        let later = SystemTime::now() + Duration::from_secs(2 * 60 * 60);
+        assert!(group.revalidate_at(&engine, later).is_err());
+
+        // log cleanup removed the commits needed to reconstruct the pinned fact version
+        store.delete(&commit_path("fact", 0)).await.unwrap();
+        assert!(group.revalidate(&engine).is_err());
+    }
+}
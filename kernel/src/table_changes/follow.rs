@@ -0,0 +1,201 @@
+//! Follows the change data feed of a table as new versions are committed, see
+//! [`TableChangesFollower`].
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use super::{TableChanges, TableChangesBuilder};
+use crate::log_segment::list_log_files;
+use crate::snapshot::Snapshot;
+use crate::utils::normalize_table_root;
+use crate::{DeltaResult, Engine, Version};
+
+/// Reads the change data feed of a table incrementally, for streaming consumers that tail the
+/// table. Each call to [`poll`] returns a [`TableChanges`] for the versions committed since the
+/// previous call, and the follower remembers its position. Created with
+/// [`TableChangesBuilder::follow`], whose options apply to every poll.
+///
+/// Each poll creates its start snapshot incrementally from the end snapshot of the previous poll,
+/// so only the newly committed versions are listed and replayed, rather than the table's whole log.
+///
+/// # Example
+/// ```rust
+/// # use test_utils::DefaultEngineExtension;
+/// # use delta_kernel::engine::default::DefaultEngine;
+/// # use delta_kernel::table_changes::TableChanges;
+/// # use delta_kernel::Error;
+/// # let engine = DefaultEngine::new_local();
+/// # let path = "./tests/data/table-with-cdf";
+/// let url = delta_kernel::try_parse_uri(path)?;
+/// let mut follower = TableChanges::builder(url, 0).with_end_version(1).follow();
+/// while let Some(table_changes) = follower.poll(engine.as_ref())? {
+///     // Scan the changes of versions `table_changes.start_version()..=table_changes.end_version()`
+/// }
+/// assert_eq!(follower.next_version(), 2);
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// [`poll`]: Self::poll
+#[derive(Debug)]
+pub struct TableChangesFollower {
+    builder: TableChangesBuilder,
+    // The end snapshot of the previous poll, if any
+    last_snapshot: Option<Arc<Snapshot>>,
+}
+
+impl TableChangesFollower {
+    pub(crate) fn new(builder: TableChangesBuilder) -> Self {
+        Self {
+            builder,
+            last_snapshot: None,
+        }
+    }
+
+    /// The first version the next call to [`Self::poll`] will return changes for, if it has been
+    /// committed.
+    pub fn next_version(&self) -> Version {
+        let start_version = self.builder.start_version;
+        match self.builder.start_exclusive {
+            true => start_version.saturating_add(1),
+            false => start_version,
+        }
+    }
+
+    /// Returns the changes of the versions committed from [`Self::next_version`] up to the newest
+    /// version of the table (or the builder's end version, if it is older), and moves past them.
+    /// Returns `None` if no such version has been committed yet, in which case the consumer can
+    /// poll again later.
+    ///
+    /// On error, the position is unchanged, so the same versions are retried by the next poll.
+    pub fn poll(&mut self, engine: &dyn Engine) -> DeltaResult<Option<TableChanges>> {
+        let next_version = self.next_version();
+        if self
+            .builder
+            .end_version
+            .is_some_and(|end_version| next_version > end_version)
+        {
+            return Ok(None);
+        }
+        let log_root =
+            normalize_table_root(self.builder.table_root.clone())?.join("_delta_log/")?;
+        let latest_commit = list_log_files(
+            engine.storage_handler().as_ref(),
+            &log_root,
+            next_version,
+            self.builder.end_version,
+        )?
+        .filter_ok(|path| path.is_commit())
+        .last()
+        .transpose()?;
+        let Some(latest_commit) = latest_commit else {
+            return Ok(None);
+        };
+
+        let builder = TableChangesBuilder {
+            end_version: Some(latest_commit.version),
+            ..self.builder.clone()
+        };
+        let table_changes = builder.build_from(engine, self.last_snapshot.clone())?;
+        self.builder.start_version = table_changes.end_version();
+        self.builder.start_exclusive = true;
+        self.last_snapshot = Some(table_changes.end_snapshot.clone());
+        Ok(Some(table_changes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use url::Url;
+
+    use super::*;
+    use crate::actions::{Add, Metadata, Protocol};
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::{Action, LocalMockTable};
+    use crate::Error;
+
+    fn add(path: &str) -> Action {
+        Action::Add(Add {
+            path: path.to_string(),
+            data_change: true,
+            ..Default::default()
+        })
+    }
+
+    fn metadata(enable_cdf: bool) -> Action {
+        let schema = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#;
+        Action::Metadata(Metadata {
+            schema_string: schema.to_string(),
+            configuration: HashMap::from([(
+                "delta.enableChangeDataFeed".to_string(),
+                enable_cdf.to_string(),
+            )]),
+            ..Default::default()
+        })
+    }
+
+    async fn setup_table() -> (LocalMockTable, Url) {
+        let mut mock_table = LocalMockTable::new();
+        let protocol = Protocol::try_new(1, 4, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        mock_table
+            .commit([Action::Protocol(protocol), metadata(true)])
+            .await;
+        mock_table.commit([add("file1")]).await;
+        let table_root = Url::from_directory_path(mock_table.table_root()).unwrap();
+        (mock_table, table_root)
+    }
+
+    fn poll_versions(
+        follower: &mut TableChangesFollower,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<(Version, Version)>> {
+        let table_changes = follower.poll(engine)?;
+        Ok(table_changes.map(|changes| (changes.start_version(), changes.end_version())))
+    }
+
+    #[tokio::test]
+    async fn test_follow_table_changes() -> DeltaResult<()> {
+        let (mut mock_table, table_root) = setup_table().await;
+        let engine = SyncEngine::new();
+        let mut follower = TableChanges::builder(table_root, 0).follow();
+
+        assert_eq!(poll_versions(&mut follower, &engine)?, Some((0, 1)));
+        assert_eq!(follower.next_version(), 2);
+        assert_eq!(poll_versions(&mut follower, &engine)?, None);
+
+        mock_table.commit([add("file2")]).await;
+        mock_table.commit([add("file3")]).await;
+        assert_eq!(poll_versions(&mut follower, &engine)?, Some((2, 3)));
+        assert_eq!(poll_versions(&mut follower, &engine)?, None);
+
+        // A failed poll keeps the position
+        mock_table.commit([metadata(false)]).await;
+        let res = follower.poll(&engine);
+        assert!(
+            matches!(res, Err(Error::ChangeDataFeedUnsupported(4))),
+            "{res:?}"
+        );
+        assert_eq!(follower.next_version(), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_follow_table_changes_bounds() -> DeltaResult<()> {
+        let (mut mock_table, table_root) = setup_table().await;
+        let engine = SyncEngine::new();
+        let mut follower = TableChanges::builder(table_root, 0)
+            .with_start_exclusive(true)
+            .with_end_version(2)
+            .follow();
+        assert_eq!(follower.next_version(), 1);
+        assert_eq!(poll_versions(&mut follower, &engine)?, Some((1, 1)));
+
+        // The follower stops at the end version
+        mock_table.commit([add("file2")]).await;
+        mock_table.commit([add("file3")]).await;
+        assert_eq!(poll_versions(&mut follower, &engine)?, Some((2, 2)));
+        assert_eq!(poll_versions(&mut follower, &engine)?, None);
+        Ok(())
+    }
+}
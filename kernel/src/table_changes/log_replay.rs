@@ -4,15 +4,17 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, LazyLock};
 
-use crate::actions::visitors::{visit_deletion_vector_at, visit_protocol_at};
+use crate::actions::visitors::{visit_deletion_vector_at, visit_metadata_at, visit_protocol_at};
 use crate::actions::{
-    get_log_add_schema, Add, Cdc, Metadata, Protocol, Remove, ADD_NAME, CDC_NAME, METADATA_NAME,
-    PROTOCOL_NAME, REMOVE_NAME,
+    get_log_add_schema, Add, Cdc, Metadata, Protocol, Remove, ADD_NAME, CDC_NAME, COMMIT_INFO_NAME,
+    METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
 };
 use crate::engine_data::{GetData, TypedGetData};
 use crate::expressions::{column_name, ColumnName};
+use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
 use crate::path::ParsedLogPath;
 use crate::scan::data_skipping::DataSkippingFilter;
+use crate::scan::parse_partition_value;
 use crate::scan::state::DvInfo;
 use crate::schema::{
     ArrayType, ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType,
@@ -20,16 +22,62 @@ use crate::schema::{
 };
 use crate::table_changes::scan_file::{cdf_scan_row_expression, cdf_scan_row_schema};
 use crate::table_changes::{check_cdf_table_properties, ensure_cdf_read_supported};
-use crate::table_properties::TableProperties;
+use crate::table_configuration::TableConfiguration;
 use crate::utils::require;
-use crate::{DeltaResult, Engine, EngineData, Error, PredicateRef, RowVisitor};
+use crate::{DeltaResult, Engine, EngineData, Error, PredicateRef, RowVisitor, Version};
 
+use delta_kernel_derive::internal_api;
 use itertools::Itertools;
 
 #[cfg(test)]
 mod tests;
 
+/// The table metadata and protocol in effect at a commit of a Change Data Feed query, i.e. as of
+/// the end of that commit. Consumers can use it to interpret the change data files of the commit,
+/// or to fail with a precise error when the table changed in a way they don't support.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableChangesCommitMetadata {
+    pub(crate) table_configuration: TableConfiguration,
+}
+
+impl TableChangesCommitMetadata {
+    /// The version of the commit.
+    pub fn version(&self) -> Version {
+        self.table_configuration.version()
+    }
+
+    /// The schema of the table in effect at the commit.
+    pub fn schema(&self) -> SchemaRef {
+        self.table_configuration.schema()
+    }
+
+    /// The table configuration (`metaData.configuration`) in effect at the commit.
+    pub fn configuration(&self) -> &HashMap<String, String> {
+        &self.table_configuration.metadata().configuration
+    }
+
+    // The metadata in effect at a commit, given the metadata in effect before it and the metadata
+    // and protocol actions (if any) of the commit.
+    fn apply_commit(
+        &self,
+        version: Version,
+        metadata: Option<Metadata>,
+        protocol: Option<Protocol>,
+    ) -> DeltaResult<Self> {
+        let table_configuration = TableConfiguration::try_new_from(
+            &self.table_configuration,
+            metadata,
+            protocol,
+            version,
+        )?;
+        Ok(Self {
+            table_configuration,
+        })
+    }
+}
+
 /// Scan metadata for a Change Data Feed query. This holds metadata that's needed to read data rows.
+#[internal_api]
 pub(crate) struct TableChangesScanMetadata {
     /// Engine data with the schema defined in [`scan_row_schema`]
     ///
@@ -40,6 +88,16 @@ pub(crate) struct TableChangesScanMetadata {
     pub(crate) selection_vector: Vec<bool>,
     /// A map from a remove action's path to its deletion vector
     pub(crate) remove_dvs: Arc<HashMap<String, DvInfo>>,
+    /// The table metadata and protocol in effect at the commit the `scan_metadata` belongs to.
+    pub(crate) commit_metadata: Arc<TableChangesCommitMetadata>,
+}
+
+impl TableChangesScanMetadata {
+    /// The table metadata and protocol in effect at the commit this scan metadata belongs to.
+    #[internal_api]
+    pub(crate) fn commit_metadata(&self) -> &Arc<TableChangesCommitMetadata> {
+        &self.commit_metadata
+    }
 }
 
 /// Given an iterator of [`ParsedLogPath`] returns an iterator of [`TableChangesScanMetadata`].
@@ -48,25 +106,80 @@ pub(crate) struct TableChangesScanMetadata {
 /// rows _must_ be ignored.
 ///
 /// Note: The [`ParsedLogPath`]s in the `commit_files` iterator must be ordered, contiguous
-/// (JSON) commit files.
+/// (JSON) commit files. `start_metadata` is the metadata in effect at the start version of the
+/// query; its schema is the table schema every schema update in the commits must be compatible
+/// with. File actions whose values for the `partition_columns` can't satisfy the predicate are
+/// pruned.
 pub(crate) fn table_changes_action_iter(
     engine: Arc<dyn Engine>,
     commit_files: impl IntoIterator<Item = ParsedLogPath>,
-    table_schema: SchemaRef,
+    start_metadata: TableChangesCommitMetadata,
     physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    partition_columns: &[String],
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
+    let pruner = PartitionPruner::try_new(
+        physical_predicate.as_ref(),
+        &start_metadata.schema(),
+        partition_columns,
+    )
+    .map(Arc::new);
     let filter = DataSkippingFilter::new(engine.as_ref(), physical_predicate).map(Arc::new);
+    let mut commit_metadata = Arc::new(start_metadata);
     let result = commit_files
         .into_iter()
         .map(move |commit_file| -> DeltaResult<_> {
-            let scanner = LogReplayScanner::try_new(engine.as_ref(), commit_file, &table_schema)?;
-            scanner.into_scan_batches(engine.clone(), filter.clone())
+            let scanner =
+                LogReplayScanner::try_new(engine.as_ref(), commit_file, &commit_metadata)?;
+            commit_metadata = scanner.commit_metadata.clone();
+            scanner.into_scan_batches(engine.clone(), filter.clone(), pruner.clone())
         }) //Iterator-Result-Iterator-Result
         .flatten_ok() // Iterator-Result-Result
         .map(|x| x?); // Iterator-Result
     Ok(result)
 }
 
+/// Prunes the file actions of a commit whose partition values can't satisfy the predicate of the
+/// query, like partition pruning does for regular scans.
+struct PartitionPruner {
+    predicate: PredicateRef,
+    // the table's partition columns
+    partition_fields: Vec<StructField>,
+}
+
+impl PartitionPruner {
+    /// Returns `None` if there is no predicate or the table is not partitioned.
+    fn try_new(
+        physical_predicate: Option<&(PredicateRef, SchemaRef)>,
+        table_schema: &StructType,
+        partition_columns: &[String],
+    ) -> Option<Self> {
+        let (predicate, _) = physical_predicate?;
+        let partition_fields: Vec<_> = partition_columns
+            .iter()
+            .filter_map(|column| table_schema.field(column).cloned())
+            .collect();
+        (!partition_fields.is_empty()).then(|| Self {
+            predicate: predicate.clone(),
+            partition_fields,
+        })
+    }
+
+    /// True if a file with the given (raw) partition values can't satisfy the predicate.
+    fn is_pruned(&self, partition_values: &HashMap<String, String>) -> DeltaResult<bool> {
+        let partition_values: HashMap<_, _> = self
+            .partition_fields
+            .iter()
+            .map(|field| {
+                let name = field.physical_name();
+                let value = parse_partition_value(partition_values.get(name), field.data_type())?;
+                Ok::<_, Error>((ColumnName::new([name]), value))
+            })
+            .try_collect()?;
+        let evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
+        Ok(evaluator.eval_sql_where(&self.predicate) == Some(false))
+    }
+}
+
 /// Processes a single commit file from the log to generate an iterator of
 /// [`TableChangesScanMetadata`]. The scanner operates in two phases that _must_ be performed in the
 /// following order:
@@ -82,9 +195,10 @@ pub(crate) fn table_changes_action_iter(
 ///       phase, so we must perform it ahead of time in phase 1.
 ///     - Ensure that reading is supported on any protocol updates.
 ///     - Ensure that Change Data Feed is enabled for any metadata update. See  [`TableProperties`]
-///     - Ensure that any schema update is compatible with the provided `schema`. Currently, schema
-///       compatibility is checked through schema equality. This will be expanded in the future to
-///       allow limited schema evolution.
+///     - Ensure that any schema update is compatible with the schema in effect before the commit.
+///       Currently, schema compatibility is checked through schema equality. This will be expanded
+///       in the future to allow limited schema evolution.
+///     - Determine the [`TableChangesCommitMetadata`] in effect at the commit.
 ///
 /// Note: We check the protocol, change data feed enablement, and schema compatibility in phase 1
 /// in order to detect errors and fail early.
@@ -96,11 +210,9 @@ pub(crate) fn table_changes_action_iter(
 ///
 /// See https://github.com/delta-io/delta/blob/master/PROTOCOL.md#deletion-vectors
 ///
-/// TODO: When the kernel supports in-commit timestamps, we will also have to inspect CommitInfo
-/// actions to find the timestamp. These are generated when incommit timestamps is enabled.
-/// This must be done in the first phase because the second phase lazily transforms engine data with
-/// an extra timestamp column. Thus, the timestamp must be known ahead of time.
-/// See https://github.com/delta-io/delta-kernel-rs/issues/559
+/// When in-commit timestamps are enabled, the commit timestamp is read from the CommitInfo action
+/// of the commit. This must be done in the first phase because the second phase lazily transforms
+/// engine data with an extra timestamp column. Thus, the timestamp must be known ahead of time.
 ///
 /// 2. Scan file generation phase [`LogReplayScanner::into_scan_batches`]: This iterates over every
 ///    action in the commit, and generates [`TableChangesScanMetadata`]. It does so by transforming the
@@ -120,16 +232,12 @@ struct LogReplayScanner {
     remove_dvs: HashMap<String, DvInfo>,
     // The commit file that this replay scanner will operate on.
     commit_file: ParsedLogPath,
-    // The timestamp associated with this commit. This is the file modification time
-    // from the commit's [`FileMeta`].
-    //
-    //
-    // TODO when incommit timestamps are supported: If there is a [`CommitInfo`] with a timestamp
-    // generated by in-commit timestamps, that timestamp will be used instead.
-    //
-    // Note: This will be used once an expression is introduced to transform the engine data in
-    // [`TableChangesScanMetadata`]
+    // The timestamp associated with this commit. This is the in-commit timestamp of the commit's
+    // CommitInfo if in-commit timestamps are enabled, else the file modification time from the
+    // commit's [`FileMeta`].
     timestamp: i64,
+    // The table metadata and protocol in effect at this commit.
+    commit_metadata: Arc<TableChangesCommitMetadata>,
 }
 
 impl LogReplayScanner {
@@ -139,13 +247,16 @@ impl LogReplayScanner {
     /// 2. Construct a map from path to deletion vector of remove actions that share the same path
     ///    as an add action.
     /// 3. Perform validation on each protocol and metadata action in the commit.
+    /// 4. Apply the protocol and metadata actions to the `prev_metadata` in effect before the
+    ///    commit.
     ///
     /// For more details, see the documentation for [`LogReplayScanner`].
     fn try_new(
         engine: &dyn Engine,
         commit_file: ParsedLogPath,
-        table_schema: &SchemaRef,
+        prev_metadata: &TableChangesCommitMetadata,
     ) -> DeltaResult<Self> {
+        let table_schema = prev_metadata.schema();
         let visitor_schema = PreparePhaseVisitor::schema();
 
         // Note: We do not perform data skipping yet because we need to visit all add and
@@ -158,7 +269,7 @@ impl LogReplayScanner {
         // all of the rows will be filtered by the predicate. Instead, we wait until deletion
         // vectors are resolved so that we can skip both actions in the pair.
         let action_iter = engine.json_handler().read_json_files(
-            &[commit_file.location.clone()],
+            std::slice::from_ref(&commit_file.location),
             visitor_schema,
             None, // not safe to apply data skipping yet
         )?;
@@ -166,6 +277,9 @@ impl LogReplayScanner {
         let mut remove_dvs = HashMap::default();
         let mut add_paths = HashSet::default();
         let mut has_cdc_action = false;
+        let mut new_protocol = None;
+        let mut new_metadata = None;
+        let mut in_commit_timestamp = None;
         for actions in action_iter {
             let actions = actions?;
 
@@ -174,28 +288,33 @@ impl LogReplayScanner {
                 remove_dvs: &mut remove_dvs,
                 has_cdc_action: &mut has_cdc_action,
                 protocol: None,
-                metadata_info: None,
+                metadata: None,
+                in_commit_timestamp: None,
             };
             visitor.visit_rows_of(actions.as_ref())?;
+            in_commit_timestamp = in_commit_timestamp.or(visitor.in_commit_timestamp);
 
             if let Some(protocol) = visitor.protocol {
                 ensure_cdf_read_supported(&protocol)
                     .map_err(|_| Error::change_data_feed_unsupported(commit_file.version))?;
+                new_protocol = Some(protocol);
             }
-            if let Some((schema, configuration)) = visitor.metadata_info {
-                let schema: StructType = serde_json::from_str(&schema)?;
+            if let Some(metadata) = visitor.metadata {
+                let schema = metadata.parse_schema()?;
                 // Currently, schema compatibility is defined as having equal schema types. In the
                 // future, more permisive schema evolution will be supported.
                 // See: https://github.com/delta-io/delta-kernel-rs/issues/523
                 require!(
                     table_schema.as_ref() == &schema,
-                    Error::change_data_feed_incompatible_schema(table_schema, &schema)
+                    Error::change_data_feed_incompatible_schema(&table_schema, &schema)
                 );
-                let table_properties = TableProperties::from(configuration);
-                check_cdf_table_properties(&table_properties)
+                check_cdf_table_properties(&metadata.parse_table_properties())
                     .map_err(|_| Error::change_data_feed_unsupported(commit_file.version))?;
+                new_metadata = Some(metadata);
             }
         }
+        let commit_metadata =
+            prev_metadata.apply_commit(commit_file.version, new_metadata, new_protocol)?;
         // We resolve the remove deletion vector map after visiting the entire commit.
         if has_cdc_action {
             remove_dvs.clear();
@@ -204,11 +323,25 @@ impl LogReplayScanner {
             // same as an `add` action.
             remove_dvs.retain(|rm_path, _| add_paths.contains(rm_path));
         }
+        let timestamp = if commit_metadata
+            .table_configuration
+            .is_in_commit_timestamps_enabled()
+        {
+            in_commit_timestamp.ok_or_else(|| {
+                Error::generic(format!(
+                    "In-commit timestamps are enabled, but missing from commit {}",
+                    commit_file.version
+                ))
+            })?
+        } else {
+            commit_file.location.last_modified
+        };
         Ok(LogReplayScanner {
-            timestamp: commit_file.location.last_modified,
+            timestamp,
             commit_file,
             has_cdc_action,
             remove_dvs,
+            commit_metadata: Arc::new(commit_metadata),
         })
     }
     /// Generates an iterator of [`TableChangesScanMetadata`] by iterating over each action of the
@@ -218,21 +351,23 @@ impl LogReplayScanner {
         self,
         engine: Arc<dyn Engine>,
         filter: Option<Arc<DataSkippingFilter>>,
+        pruner: Option<Arc<PartitionPruner>>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
         let Self {
             has_cdc_action,
             remove_dvs,
             commit_file,
-            // TODO: Add the timestamp as a column with an expression
             timestamp,
+            commit_metadata,
         } = self;
         let remove_dvs = Arc::new(remove_dvs);
 
         let schema = FileActionSelectionVisitor::schema();
-        let action_iter =
-            engine
-                .json_handler()
-                .read_json_files(&[commit_file.location.clone()], schema, None)?;
+        let action_iter = engine.json_handler().read_json_files(
+            std::slice::from_ref(&commit_file.location),
+            schema,
+            None,
+        )?;
         let commit_version = commit_file
             .version
             .try_into()
@@ -254,14 +389,19 @@ impl LogReplayScanner {
                 None => vec![true; actions.len()],
             };
 
-            let mut visitor =
-                FileActionSelectionVisitor::new(&remove_dvs, selection_vector, has_cdc_action);
+            let mut visitor = FileActionSelectionVisitor::new(
+                &remove_dvs,
+                selection_vector,
+                has_cdc_action,
+                pruner.as_deref(),
+            );
             visitor.visit_rows_of(actions.as_ref())?;
             let scan_metadata = evaluator.evaluate(actions.as_ref())?;
             Ok(TableChangesScanMetadata {
                 scan_metadata,
                 selection_vector: visitor.selection_vector,
                 remove_dvs: remove_dvs.clone(),
+                commit_metadata: commit_metadata.clone(),
             })
         });
         Ok(result)
@@ -272,10 +412,11 @@ impl LogReplayScanner {
 // [`LogReplayScanner::try_new`] for details usage.
 struct PreparePhaseVisitor<'a> {
     protocol: Option<Protocol>,
-    metadata_info: Option<(String, HashMap<String, String>)>,
+    metadata: Option<Metadata>,
     has_cdc_action: &'a mut bool,
     add_paths: &'a mut HashSet<String>,
     remove_dvs: &'a mut HashMap<String, DvInfo>,
+    in_commit_timestamp: Option<i64>,
 }
 impl PreparePhaseVisitor<'_> {
     fn schema() -> Arc<StructType> {
@@ -285,6 +426,10 @@ impl PreparePhaseVisitor<'_> {
             StructField::nullable(CDC_NAME, Cdc::to_schema()),
             StructField::nullable(METADATA_NAME, Metadata::to_schema()),
             StructField::nullable(PROTOCOL_NAME, Protocol::to_schema()),
+            StructField::nullable(
+                COMMIT_INFO_NAME,
+                StructType::new([StructField::nullable("inCommitTimestamp", DataType::LONG)]),
+            ),
         ]))
     }
 }
@@ -298,8 +443,7 @@ impl RowVisitor for PreparePhaseVisitor<'_> {
             const LONG: DataType = DataType::LONG;
             const BOOLEAN: DataType = DataType::BOOLEAN;
             let string_list: DataType = ArrayType::new(STRING, false).into();
-            let string_string_map = MapType::new(STRING, STRING, false).into();
-            let types_and_names = vec![
+            let file_actions = vec![
                 (STRING, column_name!("add.path")),
                 (BOOLEAN, column_name!("add.dataChange")),
                 (STRING, column_name!("remove.path")),
@@ -310,22 +454,29 @@ impl RowVisitor for PreparePhaseVisitor<'_> {
                 (INTEGER, column_name!("remove.deletionVector.sizeInBytes")),
                 (LONG, column_name!("remove.deletionVector.cardinality")),
                 (STRING, column_name!("cdc.path")),
-                (STRING, column_name!("metaData.schemaString")),
-                (string_string_map, column_name!("metaData.configuration")),
+            ];
+            let protocol_and_commit_info = vec![
                 (INTEGER, column_name!("protocol.minReaderVersion")),
                 (INTEGER, column_name!("protocol.minWriterVersion")),
                 (string_list.clone(), column_name!("protocol.readerFeatures")),
                 (string_list, column_name!("protocol.writerFeatures")),
+                (LONG, column_name!("commitInfo.inCommitTimestamp")),
             ];
-            let (types, names) = types_and_names.into_iter().unzip();
-            (names, types).into()
+            let to_names_and_types = |types_and_names: Vec<(DataType, ColumnName)>| {
+                let (types, names) = types_and_names.into_iter().unzip();
+                ColumnNamesAndTypes::from((names, types))
+            };
+            let mut names_and_types = to_names_and_types(file_actions);
+            names_and_types.extend(Metadata::to_schema().leaves(METADATA_NAME));
+            names_and_types.extend(to_names_and_types(protocol_and_commit_info));
+            names_and_types
         });
         NAMES_AND_TYPES.as_ref()
     }
 
     fn visit<'b>(&mut self, row_count: usize, getters: &[&'b dyn GetData<'b>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 16,
+            getters.len() == 24,
             Error::InternalError(format!(
                 "Wrong number of PreparePhaseVisitor getters: {}",
                 getters.len()
@@ -346,12 +497,14 @@ impl RowVisitor for PreparePhaseVisitor<'_> {
                 }
             } else if getters[9].get_str(i, "cdc.path")?.is_some() {
                 *self.has_cdc_action = true;
-            } else if let Some(schema) = getters[10].get_str(i, "metaData.schemaString")? {
-                let configuration_map_opt = getters[11].get_opt(i, "metadata.configuration")?;
-                let configuration = configuration_map_opt.unwrap_or_else(HashMap::new);
-                self.metadata_info = Some((schema.to_string(), configuration));
-            } else if let Some(protocol) = visit_protocol_at(i, &getters[12..])? {
+            } else if let Some(metadata) = visit_metadata_at(i, &getters[10..=18])? {
+                self.metadata = Some(metadata);
+            } else if let Some(protocol) = visit_protocol_at(i, &getters[19..=22])? {
                 self.protocol = Some(protocol);
+            } else if let Some(timestamp) =
+                getters[23].get_opt(i, "commitInfo.inCommitTimestamp")?
+            {
+                self.in_commit_timestamp = Some(timestamp);
             }
         }
         Ok(())
@@ -364,6 +517,7 @@ struct FileActionSelectionVisitor<'a> {
     selection_vector: Vec<bool>,
     has_cdc_action: bool,
     remove_dvs: &'a HashMap<String, DvInfo>,
+    pruner: Option<&'a PartitionPruner>,
 }
 
 impl<'a> FileActionSelectionVisitor<'a> {
@@ -371,11 +525,28 @@ impl<'a> FileActionSelectionVisitor<'a> {
         remove_dvs: &'a HashMap<String, DvInfo>,
         selection_vector: Vec<bool>,
         has_cdc_action: bool,
+        pruner: Option<&'a PartitionPruner>,
     ) -> Self {
         FileActionSelectionVisitor {
             selection_vector,
             has_cdc_action,
             remove_dvs,
+            pruner,
+        }
+    }
+
+    // True if the file action in row `i` whose partition values are at `getter` is pruned by
+    // its partition values. Partition values are optional for removes, so files without them are
+    // never pruned.
+    fn is_partition_pruned<'b>(&self, i: usize, getter: &'b dyn GetData<'b>) -> DeltaResult<bool> {
+        let Some(pruner) = self.pruner else {
+            return Ok(false);
+        };
+        let partition_values: Option<HashMap<String, String>> =
+            getter.get_opt(i, "partitionValues")?;
+        match partition_values {
+            Some(partition_values) => pruner.is_pruned(&partition_values),
+            None => Ok(false),
         }
     }
     fn schema() -> Arc<StructType> {
@@ -393,12 +564,16 @@ impl RowVisitor for FileActionSelectionVisitor<'_> {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             const STRING: DataType = DataType::STRING;
             const BOOLEAN: DataType = DataType::BOOLEAN;
+            let ss_map: DataType = MapType::new(STRING, STRING, true).into();
             let types_and_names = vec![
                 (STRING, column_name!("cdc.path")),
+                (ss_map.clone(), column_name!("cdc.partitionValues")),
                 (STRING, column_name!("add.path")),
+                (ss_map.clone(), column_name!("add.partitionValues")),
                 (BOOLEAN, column_name!("add.dataChange")),
                 (STRING, column_name!("remove.path")),
                 (BOOLEAN, column_name!("remove.dataChange")),
+                (ss_map, column_name!("remove.partitionValues")),
             ];
             let (types, names) = types_and_names.into_iter().unzip();
             (names, types).into()
@@ -408,7 +583,7 @@ impl RowVisitor for FileActionSelectionVisitor<'_> {
 
     fn visit<'b>(&mut self, row_count: usize, getters: &[&'b dyn GetData<'b>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 5,
+            getters.len() == 8,
             Error::InternalError(format!(
                 "Wrong number of FileActionSelectionVisitor getters: {}",
                 getters.len()
@@ -422,11 +597,16 @@ impl RowVisitor for FileActionSelectionVisitor<'_> {
 
             if self.has_cdc_action {
                 self.selection_vector[i] = getters[0].get_str(i, "cdc.path")?.is_some()
-            } else if getters[1].get_str(i, "add.path")?.is_some() {
-                self.selection_vector[i] = getters[2].get(i, "add.dataChange")?;
-            } else if let Some(path) = getters[3].get_str(i, "remove.path")? {
-                let data_change: bool = getters[4].get(i, "remove.dataChange")?;
-                self.selection_vector[i] = data_change && !self.remove_dvs.contains_key(path)
+                    && !self.is_partition_pruned(i, getters[1])?
+            } else if getters[2].get_str(i, "add.path")?.is_some() {
+                let data_change: bool = getters[4].get(i, "add.dataChange")?;
+                self.selection_vector[i] =
+                    data_change && !self.is_partition_pruned(i, getters[3])?
+            } else if let Some(path) = getters[5].get_str(i, "remove.path")? {
+                let data_change: bool = getters[6].get(i, "remove.dataChange")?;
+                self.selection_vector[i] = data_change
+                    && !self.remove_dvs.contains_key(path)
+                    && !self.is_partition_pruned(i, getters[7])?
             } else {
                 self.selection_vector[i] = false
             }
@@ -1,7 +1,7 @@
 use super::table_changes_action_iter;
-use super::TableChangesScanMetadata;
+use super::{TableChangesCommitMetadata, TableChangesScanMetadata};
 use crate::actions::deletion_vector::DeletionVectorDescriptor;
-use crate::actions::{Add, Cdc, Metadata, Protocol, Remove};
+use crate::actions::{Add, Cdc, CommitInfo, Metadata, Protocol, Remove};
 use crate::engine::sync::SyncEngine;
 use crate::expressions::{column_expr, BinaryPredicateOp, Scalar};
 use crate::log_segment::LogSegment;
@@ -10,6 +10,7 @@ use crate::scan::state::DvInfo;
 use crate::scan::PhysicalPredicate;
 use crate::schema::{DataType, StructField, StructType};
 use crate::table_changes::log_replay::LogReplayScanner;
+use crate::table_configuration::TableConfiguration;
 use crate::table_features::ReaderFeature;
 use crate::utils::test_utils::{Action, LocalMockTable};
 use crate::Predicate;
@@ -27,6 +28,29 @@ fn get_schema() -> StructType {
     ])
 }
 
+fn commit_metadata_with(
+    version: Version,
+    schema: &StructType,
+    configuration: HashMap<String, String>,
+    protocol: Protocol,
+) -> TableChangesCommitMetadata {
+    let metadata = Metadata {
+        schema_string: serde_json::to_string(schema).unwrap(),
+        configuration,
+        ..Default::default()
+    };
+    let table_root = url::Url::parse("memory:///").unwrap();
+    TableChangesCommitMetadata {
+        table_configuration: TableConfiguration::try_new(metadata, protocol, table_root, version)
+            .unwrap(),
+    }
+}
+
+fn start_metadata(schema: StructType) -> TableChangesCommitMetadata {
+    let protocol = Protocol::try_new(1, 1, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+    commit_metadata_with(0, &schema, HashMap::new(), protocol)
+}
+
 fn get_segment(
     engine: &dyn Engine,
     path: &Path,
@@ -87,10 +111,62 @@ async fn metadata_protocol() {
         .into_iter();
 
     let scan_batches =
-        table_changes_action_iter(engine, commits, get_schema().into(), None).unwrap();
+        table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
+            .unwrap();
     let sv = result_to_sv(scan_batches);
     assert_eq!(sv, &[false, false]);
 }
+#[tokio::test]
+async fn commit_metadata() {
+    let engine = Arc::new(SyncEngine::new());
+    let mut mock_table = LocalMockTable::new();
+    let schema_string = serde_json::to_string(&get_schema()).unwrap();
+    let add = |path: &str| {
+        Action::Add(Add {
+            path: path.into(),
+            data_change: true,
+            ..Default::default()
+        })
+    };
+    let configuration = HashMap::from([
+        ("delta.enableChangeDataFeed".to_string(), "true".to_string()),
+        ("custom".to_string(), "1".to_string()),
+    ]);
+    let protocol = Protocol::try_new(1, 4, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+    mock_table.commit([add("fake_path_1")]).await;
+    mock_table
+        .commit([
+            Action::Metadata(Metadata {
+                schema_string,
+                configuration: configuration.clone(),
+                ..Default::default()
+            }),
+            Action::Protocol(protocol.clone()),
+            add("fake_path_2"),
+        ])
+        .await;
+    mock_table.commit([add("fake_path_3")]).await;
+
+    let commits = get_segment(engine.as_ref(), mock_table.table_root(), 0, None)
+        .unwrap()
+        .into_iter();
+    let start = start_metadata(get_schema());
+    let commit_metadata: Vec<_> =
+        table_changes_action_iter(engine, commits, start.clone(), None, &[])
+            .unwrap()
+            .map_ok(|scan_metadata| scan_metadata.commit_metadata().as_ref().clone())
+            .try_collect()
+            .unwrap();
+
+    // commit 0 keeps the start metadata, and commit 2 keeps the metadata updated by commit 1
+    let expected = [
+        start.clone(),
+        commit_metadata_with(1, &get_schema(), configuration.clone(), protocol.clone()),
+        commit_metadata_with(2, &get_schema(), configuration, protocol),
+    ];
+    assert_eq!(commit_metadata, expected);
+}
+
 #[tokio::test]
 async fn cdf_not_enabled() {
     let engine = Arc::new(SyncEngine::new());
@@ -112,7 +188,7 @@ async fn cdf_not_enabled() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
             .unwrap()
             .try_collect();
 
@@ -140,7 +216,7 @@ async fn unsupported_reader_feature() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
             .unwrap()
             .try_collect();
 
@@ -171,7 +247,7 @@ async fn column_mapping_should_fail() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
             .unwrap()
             .try_collect();
 
@@ -202,7 +278,7 @@ async fn incompatible_schemas_fail() {
             .into_iter();
 
         let res: DeltaResult<Vec<_>> =
-            table_changes_action_iter(engine, commits, cdf_schema.into(), None)
+            table_changes_action_iter(engine, commits, start_metadata(cdf_schema), None, &[])
                 .unwrap()
                 .try_collect();
 
@@ -291,7 +367,7 @@ async fn add_remove() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -341,7 +417,7 @@ async fn filter_data_change() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -387,7 +463,7 @@ async fn cdc_selection() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -453,7 +529,7 @@ async fn dv() {
         },
     )])
     .into();
-    let sv = table_changes_action_iter(engine, commits, get_schema().into(), None)
+    let sv = table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
         .unwrap()
         .flat_map(|scan_metadata| {
             let scan_metadata = scan_metadata.unwrap();
@@ -530,18 +606,115 @@ async fn data_skipping_filter() {
         .unwrap()
         .into_iter();
 
-    let sv = table_changes_action_iter(engine, commits, logical_schema.into(), predicate)
-        .unwrap()
-        .flat_map(|scan_metadata| {
-            let scan_metadata = scan_metadata.unwrap();
-            scan_metadata.selection_vector
-        })
-        .collect_vec();
+    let sv = table_changes_action_iter(
+        engine,
+        commits,
+        start_metadata(logical_schema),
+        predicate,
+        &[],
+    )
+    .unwrap()
+    .flat_map(|scan_metadata| {
+        let scan_metadata = scan_metadata.unwrap();
+        scan_metadata.selection_vector
+    })
+    .collect_vec();
 
     // Note: since the first pair is a dv operation, remove action will always be filtered
     assert_eq!(sv, &[false, true, false, false, true]);
 }
 
+#[tokio::test]
+async fn partition_pruning() {
+    let engine = Arc::new(SyncEngine::new());
+    let mut mock_table = LocalMockTable::new();
+    let partition_values = |value: &str| HashMap::from([("value".to_string(), value.to_string())]);
+    mock_table
+        .commit([
+            Action::Add(Add {
+                path: "fake_path_1".into(),
+                partition_values: partition_values("a"),
+                data_change: true,
+                ..Default::default()
+            }),
+            Action::Add(Add {
+                path: "fake_path_2".into(),
+                partition_values: partition_values("b"),
+                data_change: true,
+                ..Default::default()
+            }),
+        ])
+        .await;
+    mock_table
+        .commit([
+            Action::Remove(Remove {
+                path: "fake_path_1".into(),
+                partition_values: Some(partition_values("a")),
+                data_change: true,
+                ..Default::default()
+            }),
+            Action::Remove(Remove {
+                path: "fake_path_2".into(),
+                partition_values: Some(partition_values("b")),
+                data_change: true,
+                ..Default::default()
+            }),
+            // removes without partition values are never pruned
+            Action::Remove(Remove {
+                path: "fake_path_3".into(),
+                data_change: true,
+                ..Default::default()
+            }),
+        ])
+        .await;
+    mock_table
+        .commit([
+            Action::Cdc(Cdc {
+                path: "fake_path_4".into(),
+                partition_values: partition_values("a"),
+                ..Default::default()
+            }),
+            Action::Cdc(Cdc {
+                path: "fake_path_5".into(),
+                partition_values: partition_values("b"),
+                ..Default::default()
+            }),
+        ])
+        .await;
+
+    let predicate = Predicate::eq(column_expr!("value"), Scalar::from("b"));
+    let logical_schema = get_schema();
+    let predicate = match PhysicalPredicate::try_new(&predicate, &logical_schema) {
+        Ok(PhysicalPredicate::Some(p, s)) => Some((p, s)),
+        other => panic!("Unexpected result: {other:?}"),
+    };
+    let commits = get_segment(engine.as_ref(), mock_table.table_root(), 0, None).unwrap();
+
+    let sv = table_changes_action_iter(
+        engine.clone(),
+        commits.clone(),
+        start_metadata(logical_schema.clone()),
+        predicate.clone(),
+        &["value".to_string()],
+    )
+    .unwrap();
+    assert_eq!(
+        result_to_sv(sv),
+        &[false, true, false, true, true, false, true]
+    );
+
+    // without partition columns, nothing is pruned
+    let sv = table_changes_action_iter(
+        engine,
+        commits,
+        start_metadata(logical_schema),
+        predicate,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(result_to_sv(sv), &[true; 7]);
+}
+
 #[tokio::test]
 async fn failing_protocol() {
     let engine = Arc::new(SyncEngine::new());
@@ -576,7 +749,7 @@ async fn failing_protocol() {
         .into_iter();
 
     let res: DeltaResult<Vec<_>> =
-        table_changes_action_iter(engine, commits, get_schema().into(), None)
+        table_changes_action_iter(engine, commits, start_metadata(get_schema()), None, &[])
             .unwrap()
             .try_collect();
 
@@ -602,6 +775,54 @@ async fn file_meta_timestamp() {
 
     let commit = commits.next().unwrap();
     let file_meta_ts = commit.location.last_modified;
-    let scanner = LogReplayScanner::try_new(engine.as_ref(), commit, &get_schema().into()).unwrap();
+    let scanner =
+        LogReplayScanner::try_new(engine.as_ref(), commit, &start_metadata(get_schema())).unwrap();
     assert_eq!(scanner.timestamp, file_meta_ts);
 }
+
+#[tokio::test]
+async fn in_commit_timestamp() {
+    let engine = Arc::new(SyncEngine::new());
+    let mut mock_table = LocalMockTable::new();
+
+    mock_table
+        .commit([
+            Action::CommitInfo(CommitInfo {
+                in_commit_timestamp: Some(12345),
+                ..Default::default()
+            }),
+            Action::Add(Add {
+                path: "fake_path_1".into(),
+                data_change: true,
+                ..Default::default()
+            }),
+        ])
+        .await;
+
+    let commit = get_segment(engine.as_ref(), mock_table.table_root(), 0, None)
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+
+    // Without in-commit timestamps enabled, the file modification time is used
+    let scanner = LogReplayScanner::try_new(
+        engine.as_ref(),
+        commit.clone(),
+        &start_metadata(get_schema()),
+    )
+    .unwrap();
+    assert_eq!(scanner.timestamp, commit.location.last_modified);
+
+    let ict_metadata = commit_metadata_with(
+        0,
+        &get_schema(),
+        HashMap::from([(
+            "delta.enableInCommitTimestamps".to_string(),
+            "true".to_string(),
+        )]),
+        Protocol::try_new(1, 7, None::<Vec<String>>, Some(["inCommitTimestamp"])).unwrap(),
+    );
+    let scanner = LogReplayScanner::try_new(engine.as_ref(), commit, &ict_metadata).unwrap();
+    assert_eq!(scanner.timestamp, 12345);
+}
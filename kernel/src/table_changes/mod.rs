@@ -31,27 +31,41 @@
 //! let table_change_batches = table_changes_scan.execute(engine.clone())?;
 //! # Ok::<(), Error>(())
 //! ```
+//!
+//! Use [`TableChanges::builder`] to control how the version range is interpreted, e.g. to exclude
+//! the start version or to skip versions before change data feed was enabled, or to follow the
+//! table's changes as they are committed with [`TableChangesBuilder::follow`].
 use std::sync::{Arc, LazyLock};
 
 use scan::TableChangesScanBuilder;
 use url::Url;
 
-use crate::actions::{ensure_supported_features, Protocol};
+use crate::actions::{
+    ensure_supported_features, get_log_schema, Metadata, Protocol, METADATA_NAME, PROTOCOL_NAME,
+};
 use crate::log_segment::LogSegment;
 use crate::path::AsUrl;
 use crate::schema::{DataType, Schema, StructField, StructType};
 use crate::snapshot::Snapshot;
+use crate::table_configuration::TableConfiguration;
 use crate::table_features::{ColumnMappingMode, ReaderFeature};
 use crate::table_properties::TableProperties;
-use crate::utils::require;
+use crate::utils::{normalize_table_root, require};
 use crate::{DeltaResult, Engine, Error, Version};
 
+mod follow;
 mod log_replay;
 mod physical_to_logical;
 mod resolve_dvs;
 pub mod scan;
 mod scan_file;
 
+pub use follow::TableChangesFollower;
+pub use log_replay::TableChangesCommitMetadata;
+#[cfg(feature = "internal-api")]
+pub use log_replay::TableChangesScanMetadata;
+pub use resolve_dvs::DvRowChanges;
+
 static CHANGE_TYPE_COL_NAME: &str = "_change_type";
 static COMMIT_VERSION_COL_NAME: &str = "_commit_version";
 static COMMIT_TIMESTAMP_COL_NAME: &str = "_commit_timestamp";
@@ -70,14 +84,14 @@ static CDF_FIELDS: LazyLock<[StructField; 3]> = LazyLock::new(|| {
 /// - `_change_type`: String representing the type of change that for that commit. This may be one
 ///   of `delete`, `insert`, `update_preimage`, or `update_postimage`.
 /// - `_commit_version`: Long representing the commit the change occurred in.
-/// - `_commit_timestamp`: Time at which the commit occurred. The timestamp is retrieved from the
-///   file modification time of the log file. No timezone is associated with the timestamp.
-///
-///   Currently, in-commit timestamps (ICT) is not supported. In the future when ICT is enabled, the
-///   timestamp will be retrieved from the `inCommitTimestamp` field of the CommitInfo` action.
-///   See issue [#559](https://github.com/delta-io/delta-kernel-rs/issues/559)
+/// - `_commit_timestamp`: Time at which the commit occurred. No timezone is associated with the
+///   timestamp. For commits where in-commit timestamps (ICT) are enabled, the timestamp is the
+///   `inCommitTimestamp` field of the commit's `CommitInfo` action, and reading a commit without
+///   it is an error. For other commits, it is the file modification time of the log file.
 ///   For details on In-Commit Timestamps, see the [Protocol](https://github.com/delta-io/delta/blob/master/PROTOCOL.md#in-commit-timestamps).
 ///
+/// These columns can be renamed or excluded from a scan using
+/// [`TableChangesScanBuilder::with_cdf_columns`].
 ///
 /// Three properties must hold for the entire CDF range:
 /// - Reading must be supported for every commit in the range. Currently the only read feature allowed
@@ -114,48 +128,126 @@ pub struct TableChanges {
     table_root: Url,
     end_snapshot: Arc<Snapshot>,
     start_version: Version,
+    // The metadata in effect at the start version
+    start_metadata: TableChangesCommitMetadata,
     schema: Schema,
 }
 
-impl TableChanges {
-    /// Creates a new [`TableChanges`] instance for the given version range. This function checks
-    /// these properties:
-    /// - The change data feed table feature must be enabled in both the start or end versions.
-    /// - Other than the deletion vector reader feature, no other reader features are enabled for the table.
-    /// - The schemas at the start and end versions are the same.
+/// What to do when change data feed is not enabled at the start version of a [`TableChanges`],
+/// see [`TableChangesBuilder::with_cdf_not_enabled_behavior`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CdfNotEnabledBehavior {
+    /// Fail with [`Error::ChangeDataFeedUnsupported`].
+    #[default]
+    Error,
+    /// Move the start version forward to the first version at which change data feed is enabled.
+    /// Fails with [`Error::ChangeDataFeedUnsupported`] if there is no such version in the range.
+    Skip,
+}
+
+/// Builds a [`TableChanges`], with options for how its version range is interpreted. These follow
+/// the `startingVersion` and `endingVersion` options of delta-spark's change data feed reader: by
+/// default both bounds are inclusive, the end version defaults to the newest table version, and
+/// change data feed must be enabled at the start version.
+///
+/// # Example
+/// ```rust
+/// # use test_utils::DefaultEngineExtension;
+/// # use delta_kernel::engine::default::DefaultEngine;
+/// # use delta_kernel::table_changes::{CdfNotEnabledBehavior, TableChanges};
+/// # use delta_kernel::Error;
+/// # let engine = DefaultEngine::new_local();
+/// # let path = "./tests/data/table-with-cdf";
+/// let url = delta_kernel::try_parse_uri(path)?;
+/// // Change data feed is disabled at version 2 and enabled again at version 3
+/// let table_changes = TableChanges::builder(url, 2)
+///     .with_end_version(3)
+///     .with_cdf_not_enabled_behavior(CdfNotEnabledBehavior::Skip)
+///     .build(engine.as_ref())?;
+/// assert_eq!(table_changes.start_version(), 3);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableChangesBuilder {
+    table_root: Url,
+    start_version: Version,
+    start_exclusive: bool,
+    end_version: Option<Version>,
+    cdf_not_enabled_behavior: CdfNotEnabledBehavior,
+}
+
+impl TableChangesBuilder {
+    /// The end version (inclusive) of the change data feed. Defaults to the newest table version.
+    pub fn with_end_version(mut self, end_version: Version) -> Self {
+        self.end_version = Some(end_version);
+        self
+    }
+
+    /// Whether to exclude the start version from the change data feed, e.g. to continue from the
+    /// last version a previous read returned. Defaults to `false`.
+    pub fn with_start_exclusive(mut self, start_exclusive: bool) -> Self {
+        self.start_exclusive = start_exclusive;
+        self
+    }
+
+    /// What to do when change data feed is not enabled at the start version. Defaults to
+    /// [`CdfNotEnabledBehavior::Error`].
     ///
-    /// Note that this does not check that change data feed is enabled for every commit in the
-    /// range. It also does not check that the schema remains the same for the entire range.
+    /// Only the start of the range is affected: change data feed must still be enabled for every
+    /// version from the (possibly skipped-to) start version to the end version.
+    pub fn with_cdf_not_enabled_behavior(mut self, behavior: CdfNotEnabledBehavior) -> Self {
+        self.cdf_not_enabled_behavior = behavior;
+        self
+    }
+
+    /// Build the [`TableChanges`]. See [`TableChanges::try_new`] for the checks this performs.
+    pub fn build(self, engine: &dyn Engine) -> DeltaResult<TableChanges> {
+        self.build_from(engine, None)
+    }
+
+    /// Build a [`TableChangesFollower`] that returns the changes from the start version onwards in
+    /// increments, as new versions are committed. See [`TableChangesFollower::poll`].
     ///
-    /// # Parameters
-    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located)
-    /// - `engine`: Implementation of [`Engine`] apis.
-    /// - `start_version`: The start version of the change data feed
-    /// - `end_version`: The end version (inclusive) of the change data feed. If this is none, this
-    ///   defaults to the newest table version.
-    pub fn try_new(
-        table_root: Url,
+    /// If an end version is set, the follower stops at it.
+    pub fn follow(self) -> TableChangesFollower {
+        TableChangesFollower::new(self)
+    }
+
+    /// Build the [`TableChanges`], creating the start snapshot incrementally from `base_snapshot`
+    /// if it is not newer than the start version.
+    fn build_from(
+        self,
         engine: &dyn Engine,
-        start_version: Version,
-        end_version: Option<Version>,
-    ) -> DeltaResult<Self> {
+        base_snapshot: Option<Arc<Snapshot>>,
+    ) -> DeltaResult<TableChanges> {
+        let start_version = match self.start_exclusive {
+            true => self.start_version.checked_add(1).ok_or_else(|| {
+                Error::generic("Failed to build TableChanges: start version overflows")
+            })?,
+            false => self.start_version,
+        };
+        let table_root = normalize_table_root(self.table_root)?;
         let log_root = table_root.join("_delta_log/")?;
-        let log_segment = LogSegment::for_table_changes(
+        let mut log_segment = LogSegment::for_table_changes(
             engine.storage_handler().as_ref(),
-            log_root,
+            log_root.clone(),
             start_version,
-            end_version,
+            self.end_version,
         )?;
 
         // Both snapshots ensure that reading is supported at the start and end version using
         // `ensure_read_supported`. Note that we must still verify that reading is
         // supported for every protocol action in the CDF range.
-        let start_snapshot = Arc::new(Snapshot::try_new(
-            table_root.as_url().clone(),
-            engine,
-            Some(start_version),
-        )?);
-        let end_snapshot = Snapshot::try_new_from(start_snapshot.clone(), engine, end_version)?;
+        let mut start_snapshot = match base_snapshot {
+            Some(base) if base.version() <= start_version => {
+                Snapshot::try_new_from(base, engine, start_version)?
+            }
+            _ => Arc::new(Snapshot::try_new(
+                table_root.as_url().clone(),
+                engine,
+                Some(start_version),
+            )?),
+        };
 
         // Verify CDF is enabled at the beginning and end of the interval using
         // [`check_cdf_table_properties`] to fail early. This also ensures that column mapping is
@@ -172,7 +264,26 @@ impl TableChanges {
                 Err(Error::change_data_feed_unsupported(snapshot.version()))
             }
         };
+        if self.cdf_not_enabled_behavior == CdfNotEnabledBehavior::Skip
+            && !start_snapshot.table_configuration().is_cdf_read_supported()
+        {
+            // Find the version that enables CDF from the protocol and metadata changes alone, and
+            // only load the snapshot at that version.
+            if let Some(version) = first_cdf_enabled_version(engine, &start_snapshot, &log_segment)?
+            {
+                start_snapshot = Snapshot::try_new_from(start_snapshot, engine, version)?;
+                log_segment = LogSegment::for_table_changes(
+                    engine.storage_handler().as_ref(),
+                    log_root,
+                    version,
+                    log_segment.end_version,
+                )?;
+            }
+        }
         check_table_config(&start_snapshot)?;
+        let start_version = start_snapshot.version();
+        let end_snapshot =
+            Snapshot::try_new_from(start_snapshot.clone(), engine, log_segment.end_version)?;
         check_table_config(&end_snapshot)?;
 
         // Verify that the start and end schemas are compatible. We must still check schema
@@ -193,14 +304,63 @@ impl TableChanges {
                 .chain(CDF_FIELDS.clone()),
         );
 
+        let start_metadata = TableChangesCommitMetadata {
+            table_configuration: start_snapshot.table_configuration().clone(),
+        };
+
         Ok(TableChanges {
             table_root,
             end_snapshot,
             log_segment,
             start_version,
+            start_metadata,
             schema,
         })
     }
+}
+
+impl TableChanges {
+    /// Creates a new [`TableChanges`] instance for the given version range. This function checks
+    /// these properties:
+    /// - The change data feed table feature must be enabled in both the start or end versions.
+    /// - Other than the deletion vector reader feature, no other reader features are enabled for the table.
+    /// - The schemas at the start and end versions are the same.
+    ///
+    /// Note that this does not check that change data feed is enabled for every commit in the
+    /// range. It also does not check that the schema remains the same for the entire range.
+    ///
+    /// # Parameters
+    /// - `table_root`: url pointing at the table root (where `_delta_log` folder is located). A
+    ///   missing trailing slash is added.
+    /// - `engine`: Implementation of [`Engine`] apis.
+    /// - `start_version`: The start version of the change data feed
+    /// - `end_version`: The end version (inclusive) of the change data feed. If this is none, this
+    ///   defaults to the newest table version.
+    pub fn try_new(
+        table_root: Url,
+        engine: &dyn Engine,
+        start_version: Version,
+        end_version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        let builder = Self::builder(table_root, start_version);
+        match end_version {
+            Some(end_version) => builder.with_end_version(end_version),
+            None => builder,
+        }
+        .build(engine)
+    }
+
+    /// Creates a [`TableChangesBuilder`] for the change data feed starting at `start_version`,
+    /// with options for how the version range is interpreted.
+    pub fn builder(table_root: Url, start_version: Version) -> TableChangesBuilder {
+        TableChangesBuilder {
+            table_root,
+            start_version,
+            start_exclusive: false,
+            end_version: None,
+            cdf_not_enabled_behavior: CdfNotEnabledBehavior::default(),
+        }
+    }
 
     /// The start version of the `TableChanges`.
     pub fn start_version(&self) -> Version {
@@ -236,6 +396,47 @@ impl TableChanges {
     }
 }
 
+/// Returns the first version after `start_snapshot` in `log_segment` at which change data feed can
+/// be read, if any. Only the protocol and metadata actions of each commit are read.
+fn first_cdf_enabled_version(
+    engine: &dyn Engine,
+    start_snapshot: &Snapshot,
+    log_segment: &LogSegment,
+) -> DeltaResult<Option<Version>> {
+    let schema = get_log_schema().project(&[PROTOCOL_NAME, METADATA_NAME])?;
+    let mut table_configuration = start_snapshot.table_configuration().clone();
+    let commits = log_segment
+        .ascending_commit_files
+        .iter()
+        .filter(|commit| commit.version > start_snapshot.version());
+    for commit in commits {
+        let (mut metadata, mut protocol) = (None, None);
+        let files = [commit.location.clone()];
+        for actions in engine
+            .json_handler()
+            .read_json_files(&files, schema.clone(), None)?
+        {
+            let actions = actions?;
+            if let Some(new_metadata) = Metadata::try_new_from_data(actions.as_ref())? {
+                metadata = Some(new_metadata);
+            }
+            if let Some(new_protocol) = Protocol::try_new_from_data(actions.as_ref())? {
+                protocol = Some(new_protocol);
+            }
+        }
+        table_configuration = TableConfiguration::try_new_from(
+            &table_configuration,
+            metadata,
+            protocol,
+            commit.version,
+        )?;
+        if table_configuration.is_cdf_read_supported() {
+            return Ok(Some(commit.version));
+        }
+    }
+    Ok(None)
+}
+
 /// Ensures that change data feed is enabled in `table_properties`. See the documentation
 /// of [`TableChanges`] for more details.
 fn check_cdf_table_properties(table_properties: &TableProperties) -> DeltaResult<()> {
@@ -313,6 +514,59 @@ mod tests {
             assert!(matches!(res, Err(Error::ChangeDataFeedUnsupported(_))))
         }
     }
+
+    #[test]
+    fn table_changes_builder_bounds() {
+        // Table with CDF enabled, then disabled at version 2 and enabled at version 3
+        let path = "./tests/data/table-with-cdf";
+        let engine = Box::new(SyncEngine::new());
+        let url = delta_kernel::try_parse_uri(path).unwrap();
+
+        let table_changes = TableChanges::builder(url.clone(), 0)
+            .with_start_exclusive(true)
+            .with_end_version(1)
+            .build(engine.as_ref())
+            .unwrap();
+        assert_eq!(table_changes.start_version(), 1);
+        assert_eq!(table_changes.end_version(), 1);
+
+        // Excluding the only version leaves an empty range
+        let res = TableChanges::builder(url.clone(), 1)
+            .with_start_exclusive(true)
+            .with_end_version(1)
+            .build(engine.as_ref());
+        assert!(matches!(res, Err(Error::Generic(_))));
+
+        // Skipping moves the start to the version that re-enables CDF
+        let res = TableChanges::builder(url.clone(), 2)
+            .with_end_version(3)
+            .build(engine.as_ref());
+        assert!(matches!(res, Err(Error::ChangeDataFeedUnsupported(2))));
+        let table_changes = TableChanges::builder(url.clone(), 2)
+            .with_end_version(3)
+            .with_cdf_not_enabled_behavior(CdfNotEnabledBehavior::Skip)
+            .build(engine.as_ref())
+            .unwrap();
+        assert_eq!(table_changes.start_version(), 3);
+        assert_eq!(table_changes.end_version(), 3);
+        assert_eq!(table_changes.start_metadata.version(), 3);
+
+        // ... but fails if CDF is never enabled in the range
+        let res = TableChanges::builder(url.clone(), 2)
+            .with_end_version(2)
+            .with_cdf_not_enabled_behavior(CdfNotEnabledBehavior::Skip)
+            .build(engine.as_ref());
+        assert!(matches!(res, Err(Error::ChangeDataFeedUnsupported(2))));
+
+        // Skipping has no effect when CDF is enabled at the start version
+        let table_changes = TableChanges::builder(url, 0)
+            .with_end_version(1)
+            .with_cdf_not_enabled_behavior(CdfNotEnabledBehavior::Skip)
+            .build(engine.as_ref())
+            .unwrap();
+        assert_eq!(table_changes.start_version(), 0);
+    }
+
     #[test]
     fn schema_evolution_fails() {
         let path = "./tests/data/table-with-cdf";
@@ -1,10 +1,80 @@
+use roaring::RoaringTreemap;
 use url::Url;
 
 use super::scan_file::CdfScanFileType;
 use crate::actions::deletion_vector::{deletion_treemap_to_bools, selection_treemap_to_bools};
+use crate::scan::state::DvInfo;
 use crate::table_changes::scan_file::CdfScanFile;
 use crate::{DeltaResult, Engine, Error};
 
+/// The rows of a data file whose deletion state changed within a commit that replaced the file's
+/// deletion vector, i.e. removed the file with `remove_dv` and re-added it with `add_dv`.
+///
+/// In the change data feed, restored rows are emitted as inserts and newly deleted rows as
+/// deletes. Engines that pair these up into `update_preimage`/`update_postimage` rows can use
+/// these sets directly instead of reading and diffing the deletion vectors themselves. See
+/// [`TableChangesScan::dv_row_changes`].
+///
+/// [`TableChangesScan::dv_row_changes`]: super::scan::TableChangesScan::dv_row_changes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DvRowChanges {
+    restored: RoaringTreemap,
+    deleted: RoaringTreemap,
+}
+
+impl DvRowChanges {
+    /// Compute the row changes between the deletion vector the file was removed with
+    /// (`remove_dv`) and the one it was re-added with (`add_dv`). A missing deletion vector means
+    /// no rows are deleted.
+    pub fn try_new(
+        engine: &dyn Engine,
+        table_root: &Url,
+        remove_dv: &DvInfo,
+        add_dv: &DvInfo,
+    ) -> DeltaResult<Self> {
+        let rm_dv = remove_dv.get_treemap(engine, table_root)?;
+        let add_dv = add_dv.get_treemap(engine, table_root)?;
+        let (restored, deleted) = diff_dvs(rm_dv.unwrap_or_default(), add_dv.unwrap_or_default());
+        Ok(Self { restored, deleted })
+    }
+
+    /// Indexes of the rows that were deleted before the commit and are present after it, in
+    /// ascending order.
+    pub fn restored_rows(&self) -> impl Iterator<Item = u64> + '_ {
+        self.restored.iter()
+    }
+
+    /// The number of [restored rows](Self::restored_rows).
+    pub fn num_restored_rows(&self) -> u64 {
+        self.restored.len()
+    }
+
+    /// Indexes of the rows that were present before the commit and are deleted after it, in
+    /// ascending order.
+    pub fn deleted_rows(&self) -> impl Iterator<Item = u64> + '_ {
+        self.deleted.iter()
+    }
+
+    /// The number of [deleted rows](Self::deleted_rows).
+    pub fn num_deleted_rows(&self) -> u64 {
+        self.deleted.len()
+    }
+
+    /// Whether no row changed its deletion state.
+    pub fn is_empty(&self) -> bool {
+        self.restored.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// Split a deletion vector pair into the rows restored (deleted in `rm_dv` but not in `add_dv`)
+/// and the rows deleted (deleted in `add_dv` but not in `rm_dv`). Both results are _selection_
+/// treemaps. See [`resolve_scan_file_dv`] for a worked example.
+fn diff_dvs(rm_dv: RoaringTreemap, add_dv: RoaringTreemap) -> (RoaringTreemap, RoaringTreemap) {
+    let restored = &rm_dv - &add_dv;
+    let deleted = add_dv - rm_dv;
+    (restored, deleted)
+}
+
 /// A [`CdfScanFile`] with its associated `selection_vector`. The `scan_type` is resolved to
 /// match the `_change_type` that its rows will have in the change data feed.
 pub(crate) struct ResolvedCdfScanFile {
@@ -55,8 +125,8 @@ pub(crate) fn resolve_scan_file_dv(
             ));
         }
         (add_dv, Some(rm_dv), CdfScanFileType::Add) => {
-            let add_dv = add_dv.unwrap_or_else(Default::default);
-            let rm_dv = rm_dv.unwrap_or_else(Default::default);
+            let add_dv = add_dv.unwrap_or_default();
+            let rm_dv = rm_dv.unwrap_or_default();
             // Here we show how deletion vectors are resolved. Note that logically the `rm_dv` is the
             // beginning state of the commit, and `add_dv` is the final state of the commit. In
             // other words the dv went from being `rm_dv` to `add_dv`.
@@ -113,8 +183,7 @@ pub(crate) fn resolve_scan_file_dv(
             //      Treemap_s(0) => [true]
             //      Treemap_s(2) => [false, false, true]
             //  All other rows are unselected (false).
-            let adds = &rm_dv - &add_dv;
-            let removes = add_dv - rm_dv;
+            let (adds, removes) = diff_dvs(rm_dv, add_dv);
 
             let adds = (!adds.is_empty()).then_some(adds);
             let removes = (!removes.is_empty()).then_some(removes);
@@ -167,7 +236,7 @@ mod tests {
         Error,
     };
 
-    use super::resolve_scan_file_dv;
+    use super::{resolve_scan_file_dv, DvRowChanges};
 
     fn treemap_to_dv_descriptor(map: RoaringTreemap) -> DeletionVectorDescriptor {
         let buf = Vec::new();
@@ -342,6 +411,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dv_row_changes() {
+        let engine = SyncEngine::new();
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        let table_root = url::Url::from_directory_path(path).unwrap();
+
+        let rm_dv = DvInfo::from(treemap_to_dv_descriptor(RoaringTreemap::from([0, 2, 7])));
+        let add_dv = DvInfo::from(treemap_to_dv_descriptor(RoaringTreemap::from([0, 1, 5])));
+        let changes = DvRowChanges::try_new(&engine, &table_root, &rm_dv, &add_dv).unwrap();
+        assert_eq!(changes.restored_rows().collect_vec(), [2, 7]);
+        assert_eq!(changes.deleted_rows().collect_vec(), [1, 5]);
+        assert_eq!(changes.num_restored_rows(), 2);
+        assert!(!changes.is_empty());
+
+        // A missing deletion vector has no deleted rows
+        let none = DvInfo::default();
+        let changes = DvRowChanges::try_new(&engine, &table_root, &none, &add_dv).unwrap();
+        assert_eq!(changes.num_restored_rows(), 0);
+        assert_eq!(changes.deleted_rows().collect_vec(), [0, 1, 5]);
+        assert_eq!(changes.num_deleted_rows(), 3);
+
+        let changes = DvRowChanges::try_new(&engine, &table_root, &rm_dv, &rm_dv).unwrap();
+        assert!(changes.is_empty());
+    }
+
     #[test]
     fn cdc_and_remove_with_remove_dv_fails() {
         let engine = SyncEngine::new();
@@ -1,21 +1,92 @@
 //! Functionality to create and execute table changes scans over the data in the delta table
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use delta_kernel_derive::internal_api;
 use itertools::Itertools;
 use tracing::debug;
 use url::Url;
 
 use crate::actions::deletion_vector::split_vector;
 use crate::scan::{ColumnType, PhysicalPredicate, ScanResult};
-use crate::schema::{SchemaRef, StructType};
-use crate::{DeltaResult, Engine, FileMeta, PredicateRef};
+use crate::schema::{SchemaRef, StructField, StructType};
+use crate::{DeltaResult, Engine, Error, FileMeta, PredicateRef, Version};
 
 use super::log_replay::{table_changes_action_iter, TableChangesScanMetadata};
 use super::physical_to_logical::{physical_to_logical_expr, scan_file_physical_schema};
-use super::resolve_dvs::{resolve_scan_file_dv, ResolvedCdfScanFile};
+use super::resolve_dvs::{resolve_scan_file_dv, DvRowChanges, ResolvedCdfScanFile};
 use super::scan_file::scan_metadata_to_scan_file;
-use super::{TableChanges, CDF_FIELDS};
+use super::{TableChanges, TableChangesCommitMetadata, CDF_FIELDS};
+
+/// A column generated for the change data feed. See [`TableChanges`] for details on each column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CdfColumn {
+    /// The `_change_type` column.
+    ChangeType,
+    /// The `_commit_version` column.
+    CommitVersion,
+    /// The `_commit_timestamp` column.
+    CommitTimestamp,
+}
+
+/// Configures which [`CdfColumn`]s are included in a [`TableChangesScan`], and under which names.
+/// By default, all of them are included under their default names (e.g. `_change_type`).
+///
+/// # Example
+/// Rename `_change_type` to `op` and drop `_commit_timestamp`
+/// ```rust
+/// # use delta_kernel::table_changes::scan::{CdfColumn, CdfColumns};
+/// let cdf_columns = CdfColumns::default()
+///     .rename(CdfColumn::ChangeType, "op")
+///     .exclude(CdfColumn::CommitTimestamp);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdfColumns {
+    // The output name of each column in `CDF_FIELDS` order, or `None` if the column is excluded
+    names: [Option<String>; 3],
+}
+
+impl Default for CdfColumns {
+    fn default() -> Self {
+        Self {
+            names: CDF_FIELDS
+                .each_ref()
+                .map(|field| Some(field.name().to_string())),
+        }
+    }
+}
+
+impl CdfColumns {
+    /// Output `column` under `name` instead of its default name.
+    pub fn rename(mut self, column: CdfColumn, name: impl Into<String>) -> Self {
+        self.names[column as usize] = Some(name.into());
+        self
+    }
+
+    /// Do not output `column`.
+    pub fn exclude(mut self, column: CdfColumn) -> Self {
+        self.names[column as usize] = None;
+        self
+    }
+
+    /// The included generated fields, with their configured names.
+    pub(crate) fn fields(&self) -> impl Iterator<Item = StructField> + '_ {
+        CDF_FIELDS
+            .iter()
+            .zip(&self.names)
+            .filter_map(|(field, name)| Some(field.with_name(name.as_ref()?)))
+    }
+
+    /// The default name of the generated column which is output as `name`, if any.
+    pub(crate) fn generated_column(&self, name: &str) -> Option<&'static str> {
+        let index = self
+            .names
+            .iter()
+            .position(|output| output.as_deref() == Some(name))?;
+        Some(CDF_FIELDS[index].name())
+    }
+}
 
 /// The result of building a [`TableChanges`] scan over a table. This can be used to get the change
 /// data feed from the table.
@@ -75,6 +146,7 @@ pub struct TableChangesScanBuilder {
     table_changes: Arc<TableChanges>,
     schema: Option<SchemaRef>,
     predicate: Option<PredicateRef>,
+    cdf_columns: CdfColumns,
 }
 
 impl TableChangesScanBuilder {
@@ -84,6 +156,7 @@ impl TableChangesScanBuilder {
             table_changes: table_changes.into(),
             schema: None,
             predicate: None,
+            cdf_columns: CdfColumns::default(),
         }
     }
 
@@ -109,6 +182,14 @@ impl TableChangesScanBuilder {
         self
     }
 
+    /// Configure which columns generated for the change data feed are included in the scan, and
+    /// their names. This determines the default schema of the scan. If a schema is provided with
+    /// [`Self::with_schema`], it must refer to the generated columns by their configured names.
+    pub fn with_cdf_columns(mut self, cdf_columns: CdfColumns) -> Self {
+        self.cdf_columns = cdf_columns;
+        self
+    }
+
     /// Build the [`TableChangesScan`].
     ///
     /// This does not scan the table at this point, but does do some work to ensure that the
@@ -116,10 +197,27 @@ impl TableChangesScanBuilder {
     /// [`TableChangesScan`] type itself can be used to fetch the files and associated metadata required to
     /// perform actual data reads.
     pub fn build(self) -> DeltaResult<TableChangesScan> {
-        // if no schema is provided, use `TableChanges`'s entire (logical) schema (e.g. SELECT *)
-        let logical_schema = self
-            .schema
-            .unwrap_or_else(|| self.table_changes.schema.clone().into());
+        let table_schema = self.table_changes.end_snapshot.schema();
+        let mut names = HashSet::new();
+        for field in table_schema
+            .fields()
+            .cloned()
+            .chain(self.cdf_columns.fields())
+        {
+            if !names.insert(field.name().to_string()) {
+                return Err(Error::generic(format!(
+                    "Change data feed column name {} conflicts with another column",
+                    field.name()
+                )));
+            }
+        }
+
+        // if no schema is provided, use the table schema and all configured CDF columns (e.g.
+        // SELECT *)
+        let logical_schema = self.schema.unwrap_or_else(|| {
+            let fields = table_schema.fields().cloned();
+            StructType::new(fields.chain(self.cdf_columns.fields())).into()
+        });
         let mut read_fields = Vec::with_capacity(logical_schema.fields.len());
 
         // Loop over all selected fields. We produce the following:
@@ -143,14 +241,11 @@ impl TableChangesScanBuilder {
                     // expression in the inner loop, we will index into the schema and get the name and
                     // data type, which we need to properly materialize the column.
                     Ok(ColumnType::Partition(index))
-                } else if CDF_FIELDS
-                    .iter()
-                    .any(|field| field.name() == logical_field.name())
-                {
+                } else if let Some(name) = self.cdf_columns.generated_column(logical_field.name()) {
                     // CDF Columns are generated, so they do not have a column mapping. These will
-                    // be processed separately and used to build an expression when transforming physical
-                    // data to logical.
-                    Ok(ColumnType::Selected(logical_field.name().to_string()))
+                    // be processed separately (by their default name) and used to build an
+                    // expression when transforming physical data to logical.
+                    Ok(ColumnType::Selected(name.to_string()))
                 } else {
                     // Add to read schema, store field so we can build a `Column` expression later
                     // if needed (i.e. if we have partition columns)
@@ -183,8 +278,11 @@ impl TableChangesScan {
     /// necessary to read CDF. Additionally, [`TableChangesScanMetadata`] holds metadata on the
     /// deletion vectors present in the commit. The engine data in each scan metadata is guaranteed
     /// to belong to the same commit. Several [`TableChangesScanMetadata`] may belong to the same
-    /// commit.
-    fn scan_metadata(
+    /// commit, and each carries the [`TableChangesCommitMetadata`] in effect at its commit.
+    ///
+    /// [`TableChangesCommitMetadata`]: super::TableChangesCommitMetadata
+    #[internal_api]
+    pub(crate) fn scan_metadata(
         &self,
         engine: Arc<dyn Engine>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
@@ -199,11 +297,67 @@ impl TableChangesScan {
             PhysicalPredicate::Some(predicate, schema) => Some((predicate, schema)),
             PhysicalPredicate::None => None,
         };
-        let schema = self.table_changes.end_snapshot.schema();
-        let it = table_changes_action_iter(engine, commits, schema, physical_predicate)?;
+        let start_metadata = self.table_changes.start_metadata.clone();
+        let it = table_changes_action_iter(
+            engine,
+            commits,
+            start_metadata,
+            physical_predicate,
+            self.table_changes.partition_columns(),
+        )?;
         Ok(Some(it).into_iter().flatten())
     }
 
+    /// The [`TableChangesCommitMetadata`] of each commit of this scan, in commit order: the table
+    /// schema, configuration and protocol in effect at the commit. Engines can use it to interpret
+    /// the change data of each commit, e.g. across schema changes. This replays the commits of the
+    /// scan, but does not read any data files.
+    pub fn commit_metadata(
+        &self,
+        engine: Arc<dyn Engine>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<Arc<TableChangesCommitMetadata>>>> {
+        let mut last_version = None;
+        let commit_metadata = self
+            .scan_metadata(engine)?
+            .map_ok(|scan_metadata| scan_metadata.commit_metadata().clone())
+            // several scan metadata may belong to the same commit
+            .filter_ok(move |commit_metadata| {
+                let version = Some(commit_metadata.version());
+                std::mem::replace(&mut last_version, version) != version
+            });
+        Ok(commit_metadata)
+    }
+
+    /// The row changes of each data file whose deletion vector was replaced within a commit of
+    /// this scan (i.e. that was removed and re-added with a different deletion vector), as the
+    /// commit version, the path of the file and its [`DvRowChanges`]. Files are visited in commit
+    /// order. This replays the commits of the scan, but does not read any data files.
+    pub fn dv_row_changes(
+        &self,
+        engine: Arc<dyn Engine>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<(Version, String, DvRowChanges)>> + use<'_>>
+    {
+        let scan_files = scan_metadata_to_scan_file(self.scan_metadata(engine.clone())?);
+        let changes = scan_files
+            .filter_map_ok(move |scan_file| {
+                let remove_dv = scan_file.remove_dv?;
+                let changes = || -> DeltaResult<_> {
+                    let version = Version::try_from(scan_file.commit_version)
+                        .map_err(|_| Error::generic("Negative commit version"))?;
+                    let changes = DvRowChanges::try_new(
+                        engine.as_ref(),
+                        self.table_root(),
+                        &remove_dv,
+                        &scan_file.dv_info,
+                    )?;
+                    Ok((version, scan_file.path, changes))
+                };
+                Some(changes())
+            })
+            .map(|x| x?);
+        Ok(changes)
+    }
+
     /// Get a shared reference to the logical [`Schema`] of the table changes scan.
     ///
     /// [`Schema`]: crate::schema::Schema
@@ -238,7 +392,7 @@ impl TableChangesScan {
     pub fn execute(
         &self,
         engine: Arc<dyn Engine>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<'_>> {
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + Send + use<'_>> {
         let scan_metadata = self.scan_metadata(engine.clone())?;
         let scan_files = scan_metadata_to_scan_file(scan_metadata);
 
@@ -362,6 +516,7 @@ mod tests {
     use crate::expressions::{column_expr, Scalar};
     use crate::scan::{ColumnType, PhysicalPredicate};
     use crate::schema::{DataType, StructField, StructType};
+    use crate::table_changes::scan::{CdfColumn, CdfColumns};
     use crate::table_changes::TableChanges;
     use crate::table_changes::COMMIT_VERSION_COL_NAME;
     use crate::Predicate;
@@ -435,4 +590,56 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn table_changes_scan_builder_with_cdf_columns() {
+        let path = "./tests/data/table-with-cdf";
+        let engine = Box::new(SyncEngine::new());
+        let url = delta_kernel::try_parse_uri(path).unwrap();
+        let table_changes =
+            Arc::new(TableChanges::try_new(url, engine.as_ref(), 0, Some(1)).unwrap());
+
+        let cdf_columns = CdfColumns::default()
+            .rename(CdfColumn::CommitVersion, "version")
+            .exclude(CdfColumn::ChangeType);
+        let scan = table_changes
+            .clone()
+            .scan_builder()
+            .with_cdf_columns(cdf_columns)
+            .build()
+            .unwrap();
+        assert_eq!(
+            scan.all_fields,
+            vec![
+                ColumnType::Selected("part".to_string()),
+                ColumnType::Selected("id".to_string()),
+                ColumnType::Selected("_commit_version".to_string()),
+                ColumnType::Selected("_commit_timestamp".to_string()),
+            ]
+            .into()
+        );
+        assert_eq!(
+            scan.logical_schema,
+            StructType::new([
+                StructField::nullable("part", DataType::INTEGER),
+                StructField::nullable("id", DataType::INTEGER),
+                StructField::not_null("version", DataType::LONG),
+                StructField::not_null("_commit_timestamp", DataType::TIMESTAMP),
+            ])
+            .into()
+        );
+
+        // generated columns may not conflict with table columns or each other
+        for cdf_columns in [
+            CdfColumns::default().rename(CdfColumn::ChangeType, "id"),
+            CdfColumns::default().rename(CdfColumn::ChangeType, "_commit_version"),
+        ] {
+            let result = table_changes
+                .clone()
+                .scan_builder()
+                .with_cdf_columns(cdf_columns)
+                .build();
+            assert!(result.is_err());
+        }
+    }
 }
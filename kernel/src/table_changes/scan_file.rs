@@ -244,12 +244,13 @@ mod tests {
 
     use super::{scan_metadata_to_scan_file, CdfScanFile, CdfScanFileType};
     use crate::actions::deletion_vector::DeletionVectorDescriptor;
-    use crate::actions::{Add, Cdc, Remove};
+    use crate::actions::{Add, Cdc, Metadata, Protocol, Remove};
     use crate::engine::sync::SyncEngine;
     use crate::log_segment::LogSegment;
     use crate::scan::state::DvInfo;
     use crate::schema::{DataType, StructField, StructType};
-    use crate::table_changes::log_replay::table_changes_action_iter;
+    use crate::table_changes::log_replay::{table_changes_action_iter, TableChangesCommitMetadata};
+    use crate::table_configuration::TableConfiguration;
     use crate::utils::test_utils::{Action, LocalMockTable};
     use crate::Engine as _;
 
@@ -333,11 +334,26 @@ mod tests {
             StructField::nullable("id", DataType::INTEGER),
             StructField::nullable("value", DataType::STRING),
         ]);
+        let metadata = Metadata {
+            schema_string: serde_json::to_string(&table_schema).unwrap(),
+            ..Default::default()
+        };
+        let protocol = Protocol::try_new(1, 1, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let start_metadata = TableChangesCommitMetadata {
+            table_configuration: TableConfiguration::try_new(
+                metadata,
+                protocol,
+                table_root.clone(),
+                0,
+            )
+            .unwrap(),
+        };
         let scan_metadata = table_changes_action_iter(
             Arc::new(engine),
             log_segment.ascending_commit_files.clone(),
-            table_schema.into(),
+            start_metadata,
             None,
+            &[],
         )
         .unwrap();
         let scan_files: Vec<_> = scan_metadata_to_scan_file(scan_metadata)
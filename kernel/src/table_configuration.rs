@@ -8,15 +8,17 @@
 //! [`TableProperties`].
 //!
 //! [`Schema`]: crate::schema::Schema
+use std::collections::HashSet;
 use std::sync::{Arc, LazyLock};
 
+use itertools::Itertools;
 use url::Url;
 
 use crate::actions::{ensure_supported_features, Metadata, Protocol};
-use crate::schema::{InvariantChecker, SchemaRef};
+use crate::schema::{DataType, InvariantChecker, Schema, SchemaRef};
 use crate::table_features::{
     column_mapping_mode, validate_schema_column_mapping, validate_timestamp_ntz_feature_support,
-    ColumnMappingMode, ReaderFeature, WriterFeature,
+    ColumnMappingMode, ReaderFeature, WriteAction, WriterFeature, WriterFeatureSet,
 };
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error, Version};
@@ -40,8 +42,13 @@ pub(crate) struct TableConfiguration {
     schema: SchemaRef,
     table_properties: TableProperties,
     column_mapping_mode: ColumnMappingMode,
+    /// The writer features the protocol supports, see [`Protocol::supported_writer_features`]
+    supported_writer_features: WriterFeatureSet,
     table_root: Url,
     version: Version,
+    /// Whether the schema may contain types kernel doesn't know. See
+    /// [`Self::try_new_with_unknown_types`].
+    allow_unknown_types: bool,
 }
 
 impl TableConfiguration {
@@ -69,26 +76,59 @@ impl TableConfiguration {
         protocol: Protocol,
         table_root: Url,
         version: Version,
+    ) -> DeltaResult<Self> {
+        Self::try_new_impl(metadata, protocol, table_root, version, false)
+    }
+
+    /// Like [`Self::try_new`], but a schema containing types kernel doesn't know is accepted, with
+    /// those types parsed as [`DataType::Unsupported`]. Columns of such types cannot be read
+    /// (scans that project them fail) and the table cannot be written, but the rest of the table
+    /// stays readable. Partition columns must still have known types.
+    ///
+    /// [`DataType::Unsupported`]: crate::schema::DataType::Unsupported
+    pub(crate) fn try_new_with_unknown_types(
+        metadata: Metadata,
+        protocol: Protocol,
+        table_root: Url,
+        version: Version,
+    ) -> DeltaResult<Self> {
+        Self::try_new_impl(metadata, protocol, table_root, version, true)
+    }
+
+    fn try_new_impl(
+        metadata: Metadata,
+        protocol: Protocol,
+        table_root: Url,
+        version: Version,
+        allow_unknown_types: bool,
     ) -> DeltaResult<Self> {
         protocol.ensure_read_supported()?;
 
         let schema = Arc::new(metadata.parse_schema()?);
+        if !allow_unknown_types {
+            schema.ensure_supported_types()?;
+        }
         let table_properties = metadata.parse_table_properties();
         let column_mapping_mode = column_mapping_mode(&protocol, &table_properties);
 
+        validate_partition_columns(&schema, &metadata.partition_columns)?;
+
         // validate column mapping mode -- all schema fields should be correctly (un)annotated
         validate_schema_column_mapping(&schema, column_mapping_mode)?;
 
         validate_timestamp_ntz_feature_support(&schema, &protocol)?;
 
+        let supported_writer_features = protocol.supported_writer_features();
         Ok(Self {
             schema,
             metadata,
             protocol,
             table_properties,
             column_mapping_mode,
+            supported_writer_features,
             table_root,
             version,
+            allow_unknown_types,
         })
     }
 
@@ -109,11 +149,12 @@ impl TableConfiguration {
         // note that while we could pick apart the protocol/metadata updates and validate them
         // individually, instead we just re-parse so that we can recycle the try_new validation
         // (instead of duplicating it here).
-        Self::try_new(
+        Self::try_new_impl(
             new_metadata.unwrap_or_else(|| table_configuration.metadata.clone()),
             new_protocol.unwrap_or_else(|| table_configuration.protocol.clone()),
             table_configuration.table_root.clone(),
             new_version,
+            table_configuration.allow_unknown_types,
         )
     }
 
@@ -165,6 +206,9 @@ impl TableConfiguration {
     pub(crate) fn ensure_write_supported(&self) -> DeltaResult<()> {
         self.protocol.ensure_write_supported()?;
 
+        // a writer must understand every column it writes
+        self.schema.ensure_supported_types()?;
+
         // for now we don't allow invariants so although we support writer version 2 and the
         // ColumnInvariant TableFeature we _must_ check here that they are not actually in use
         if self.is_invariants_supported()
@@ -178,6 +222,30 @@ impl TableConfiguration {
         Ok(())
     }
 
+    /// Check that the table protocol supports every writer feature required by a write which stages
+    /// `actions` (see [`WriterFeature::required_for`]), returning an error listing the missing
+    /// features otherwise.
+    #[internal_api]
+    pub(crate) fn ensure_write_features_supported(
+        &self,
+        actions: &[WriteAction],
+    ) -> DeltaResult<()> {
+        let missing = WriterFeature::required_for(actions, &self.table_properties)
+            .difference(&self.supported_writer_features);
+        if !missing.is_empty() {
+            return Err(Error::invalid_protocol(format!(
+                "Table protocol does not support writer feature(s) required for this write: {}",
+                missing.iter().join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    // True if the protocol supports the writer feature, see [`Protocol::supported_writer_features`]
+    fn supports_writer_feature(&self, feature: &WriterFeature) -> bool {
+        self.supported_writer_features.contains(feature)
+    }
+
     /// Returns `true` if kernel supports reading Change Data Feed on this table.
     /// See the documentation of [`TableChanges`] for more details.
     ///
@@ -219,10 +287,7 @@ impl TableConfiguration {
             .protocol()
             .has_reader_feature(&ReaderFeature::DeletionVectors)
             && self.protocol.min_reader_version() == 3;
-        let write_supported = self
-            .protocol()
-            .has_writer_feature(&WriterFeature::DeletionVectors)
-            && self.protocol.min_writer_version() == 7;
+        let write_supported = self.supports_writer_feature(&WriterFeature::DeletionVectors);
         read_supported && write_supported
     }
 
@@ -246,25 +311,34 @@ impl TableConfiguration {
     /// - If the table is on writer version 7, it must have the [`WriterFeature::AppendOnly`]
     ///   writer feature.
     pub(crate) fn is_append_only_supported(&self) -> bool {
-        let protocol = &self.protocol;
-        match protocol.min_writer_version() {
-            7 if protocol.has_writer_feature(&WriterFeature::AppendOnly) => true,
-            version => (2..=6).contains(&version),
-        }
+        self.supports_writer_feature(&WriterFeature::AppendOnly)
     }
 
-    #[allow(unused)]
     pub(crate) fn is_append_only_enabled(&self) -> bool {
         self.is_append_only_supported() && self.table_properties.append_only.unwrap_or(false)
     }
 
     /// Returns `true` if the table supports the column invariant table feature.
     pub(crate) fn is_invariants_supported(&self) -> bool {
-        let protocol = &self.protocol;
-        match protocol.min_writer_version() {
-            7 if protocol.has_writer_feature(&WriterFeature::Invariants) => true,
-            version => (2..=6).contains(&version),
-        }
+        self.supports_writer_feature(&WriterFeature::Invariants)
+    }
+
+    /// Returns `true` if the table supports the check constraints table feature, i.e. writers
+    /// guarantee that every row satisfies the table's `delta.constraints.*` expressions.
+    pub(crate) fn is_check_constraints_supported(&self) -> bool {
+        self.supports_writer_feature(&WriterFeature::CheckConstraints)
+    }
+
+    /// Returns `true` if the table supports the generated columns table feature, i.e. writers
+    /// guarantee that generated columns hold the value of their generation expression.
+    pub(crate) fn is_generated_columns_supported(&self) -> bool {
+        self.supports_writer_feature(&WriterFeature::GeneratedColumns)
+    }
+
+    /// Returns `true` if the table supports the row tracking table feature, i.e. writers assign
+    /// a base row id and default row commit version to every file they add.
+    pub(crate) fn is_row_tracking_supported(&self) -> bool {
+        self.supports_writer_feature(&WriterFeature::RowTracking)
     }
 
     /// Returns `true` if V2 checkpoint is supported on this table. To support V2 checkpoint,
@@ -276,28 +350,37 @@ impl TableConfiguration {
         let read_supported = self
             .protocol()
             .has_reader_feature(&ReaderFeature::V2Checkpoint);
-        let write_supported = self
-            .protocol()
-            .has_writer_feature(&WriterFeature::V2Checkpoint);
+        let write_supported = self.supports_writer_feature(&WriterFeature::V2Checkpoint);
         read_supported && write_supported
     }
 
+    /// If the table supports the [`WriterFeature::CheckpointProtection`] writer feature, returns
+    /// the version before which checkpoints and commits are protected
+    /// (`delta.requireCheckpointProtectionBeforeVersion`). Kernel never cleans up log files, but it
+    /// must not rewrite the checkpoint of a protected version.
+    ///
+    /// See: <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#checkpoint-protection>
+    #[internal_api]
+    pub(crate) fn checkpoint_protection_version(&self) -> Option<Version> {
+        if !self.supports_writer_feature(&WriterFeature::CheckpointProtection) {
+            return None;
+        }
+        self.table_properties
+            .require_checkpoint_protection_before_version
+            .filter(|version| *version > 0)
+    }
+
     /// Returns `true` if the table supports writing in-commit timestamps.
     ///
     /// To support this feature the table must:
     /// - Have a min_writer_version of 7
     /// - Have the [`WriterFeature::InCommitTimestamp`] writer feature.
-    #[allow(unused)]
     pub(crate) fn is_in_commit_timestamps_supported(&self) -> bool {
-        self.protocol().min_writer_version() == 7
-            && self
-                .protocol()
-                .has_writer_feature(&WriterFeature::InCommitTimestamp)
+        self.supports_writer_feature(&WriterFeature::InCommitTimestamp)
     }
 
     /// Returns `true` if in-commit timestamps is supported and it is enabled. In-commit timestamps
     /// is enabled when the `delta.enableInCommitTimestamps` configuration is set to `true`.
-    #[allow(unused)]
     pub(crate) fn is_in_commit_timestamps_enabled(&self) -> bool {
         self.is_in_commit_timestamps_supported()
             && self
@@ -312,7 +395,6 @@ impl TableConfiguration {
     /// If in-commit timestamps is not supported, or not enabled, this returns `None`.
     /// If in-commit timestams is enabled, but the enablement version or timestamp is not present,
     /// this returns an error.
-    #[allow(unused)]
     pub(crate) fn in_commit_timestamp_enablement(&self) -> DeltaResult<Option<(Version, i64)>> {
         if !self.is_in_commit_timestamps_enabled() {
             return Ok(None);
@@ -338,6 +420,74 @@ impl TableConfiguration {
             )),
         }
     }
+
+    /// Returns the [`TableCapabilities`] kernel supports for this table, given its protocol,
+    /// features and table properties.
+    pub(crate) fn capabilities(&self) -> TableCapabilities {
+        let write = self.ensure_write_supported().is_ok();
+        let delete = write && !self.is_append_only_enabled();
+        TableCapabilities {
+            // guaranteed by `try_new`
+            read: true,
+            append: write,
+            delete,
+            delete_rows: delete && self.is_deletion_vector_enabled(),
+            // kernel does not yet support updating table metadata
+            metadata_update: false,
+            checkpoint_write: self
+                .checkpoint_protection_version()
+                .is_none_or(|protected| self.version >= protected),
+        }
+    }
+}
+
+/// The operations kernel supports on a table at a specific version. Engines can use this to
+/// disable unsupported operations up front instead of attempting them and failing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableCapabilities {
+    /// Whether the table can be read (always true for a successfully created snapshot).
+    pub read: bool,
+    /// Whether data files can be added to the table.
+    pub append: bool,
+    /// Whether whole files can be removed from the table, e.g. with
+    /// [`Transaction::remove_files_matching`]. This is false for append-only tables.
+    ///
+    /// [`Transaction::remove_files_matching`]: crate::transaction::Transaction::remove_files_matching
+    pub delete: bool,
+    /// Whether rows can be deleted from files by writing deletion vectors, with
+    /// [`Transaction::delete_rows`]. This additionally requires deletion vectors to be enabled
+    /// (`delta.enableDeletionVectors = true`).
+    ///
+    /// [`Transaction::delete_rows`]: crate::transaction::Transaction::delete_rows
+    pub delete_rows: bool,
+    /// Whether the table's metadata (schema, table properties) or protocol can be updated.
+    pub metadata_update: bool,
+    /// Whether a checkpoint can be written for the table.
+    pub checkpoint_write: bool,
+}
+
+// Every partition column must be a distinct top-level field of the schema with a primitive type.
+fn validate_partition_columns(schema: &Schema, partition_columns: &[String]) -> DeltaResult<()> {
+    let mut seen = HashSet::new();
+    for name in partition_columns {
+        if !seen.insert(name) {
+            return Err(Error::invalid_table_metadata(format!(
+                "Partition column '{name}' is listed more than once"
+            )));
+        }
+        let Some(field) = schema.field(name) else {
+            return Err(Error::invalid_table_metadata(format!(
+                "Partition column '{name}' is not a top-level column of the table schema"
+            )));
+        };
+        if !matches!(field.data_type(), DataType::Primitive(_)) {
+            return Err(Error::invalid_table_metadata(format!(
+                "Partition column '{name}' has non-primitive type {}",
+                field.data_type()
+            )));
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -347,11 +497,11 @@ mod test {
     use url::Url;
 
     use crate::actions::{Metadata, Protocol};
-    use crate::table_features::{ReaderFeature, WriterFeature};
+    use crate::table_features::{ReaderFeature, WriteAction, WriterFeature};
     use crate::table_properties::TableProperties;
     use crate::Error;
 
-    use super::TableConfiguration;
+    use super::{TableCapabilities, TableConfiguration};
 
     #[test]
     fn dv_supported_not_enabled() {
@@ -578,6 +728,84 @@ mod test {
         assert_eq!(new_table_config.table_root(), table_config.table_root());
     }
 
+    #[test]
+    fn test_ensure_write_features_supported() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#;
+        let table_config = |configuration: &[(&str, &str)], protocol| {
+            let metadata = Metadata {
+                configuration: configuration
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                schema_string: schema_string.to_string(),
+                ..Default::default()
+            };
+            let table_root = Url::try_from("file:///").unwrap();
+            TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap()
+        };
+        let protocol_37 = || {
+            Protocol::try_new(
+                3,
+                7,
+                Some([ReaderFeature::DeletionVectors]),
+                Some([WriterFeature::DeletionVectors]),
+            )
+            .unwrap()
+        };
+
+        let config = table_config(&[], protocol_37());
+        config
+            .ensure_write_features_supported(&[WriteAction::AddFiles, WriteAction::DeletionVectors])
+            .unwrap();
+        let err = config
+            .ensure_write_features_supported(&[WriteAction::CdcFiles, WriteAction::DomainMetadata])
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("changeDataFeed, domainMetadata"),
+            "{err}"
+        );
+
+        // append-only is implied by legacy writer versions, but must be listed for version 7
+        let append_only = [("delta.appendOnly", "true")];
+        let legacy = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        table_config(&append_only, legacy)
+            .ensure_write_features_supported(&[WriteAction::AddFiles])
+            .unwrap();
+        let err = table_config(&append_only, protocol_37())
+            .ensure_write_features_supported(&[WriteAction::AddFiles])
+            .unwrap_err();
+        assert!(err.to_string().contains("appendOnly"), "{err}");
+    }
+
+    #[test]
+    fn test_partition_column_validation() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}},{"name":"nested","type":{"type":"struct","fields":[{"name":"inner","type":"string","nullable":true,"metadata":{}}]},"nullable":true,"metadata":{}}]}"#;
+        let try_new = |partition_columns: &[&str]| {
+            let metadata = Metadata {
+                schema_string: schema_string.to_string(),
+                partition_columns: partition_columns.iter().map(|c| c.to_string()).collect(),
+                ..Default::default()
+            };
+            let protocol =
+                Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+            let table_root = Url::try_from("file:///").unwrap();
+            TableConfiguration::try_new(metadata, protocol, table_root, 0)
+        };
+
+        try_new(&[]).unwrap();
+        try_new(&["value"]).unwrap();
+        for (partition_columns, msg) in [
+            (&["missing"][..], "'missing' is not a top-level column"),
+            (&["inner"], "'inner' is not a top-level column"),
+            (&["nested"], "'nested' has non-primitive type"),
+            (&["value", "value"], "'value' is listed more than once"),
+        ] {
+            let err = try_new(partition_columns).unwrap_err();
+            assert!(matches!(err, Error::InvalidTableMetadata(_)), "{err}");
+            assert!(err.to_string().contains(msg), "{err}");
+        }
+    }
+
     #[test]
     fn test_timestamp_ntz_validation_integration() {
         // Schema with TIMESTAMP_NTZ column
@@ -628,4 +856,93 @@ mod test {
             "Should succeed when TIMESTAMP_NTZ is used with required features"
         );
     }
+
+    #[test]
+    fn test_capabilities() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#;
+        let capabilities = |configuration: &[(&str, &str)], protocol: Protocol| {
+            let metadata = Metadata {
+                configuration: configuration
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                schema_string: schema_string.to_string(),
+                ..Default::default()
+            };
+            let table_root = Url::try_from("file:///").unwrap();
+            TableConfiguration::try_new(metadata, protocol, table_root, 0)
+                .unwrap()
+                .capabilities()
+        };
+
+        let all = TableCapabilities {
+            read: true,
+            append: true,
+            delete: true,
+            delete_rows: false,
+            metadata_update: false,
+            checkpoint_write: true,
+        };
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        assert_eq!(capabilities(&[], protocol.clone()), all);
+
+        // append-only tables can't be deleted from
+        assert_eq!(
+            capabilities(&[("delta.appendOnly", "true")], protocol),
+            TableCapabilities {
+                delete: false,
+                ..all
+            }
+        );
+
+        // rows can be deleted with deletion vectors if they are enabled, unless the table is
+        // append-only
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some([WriterFeature::DeletionVectors]),
+        )
+        .unwrap();
+        let dvs_enabled = ("delta.enableDeletionVectors", "true");
+        assert_eq!(
+            capabilities(&[dvs_enabled], protocol.clone()),
+            TableCapabilities {
+                delete_rows: true,
+                ..all
+            }
+        );
+        assert_eq!(capabilities(&[], protocol), all);
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some([WriterFeature::DeletionVectors, WriterFeature::AppendOnly]),
+        )
+        .unwrap();
+        assert_eq!(
+            capabilities(&[dvs_enabled, ("delta.appendOnly", "true")], protocol),
+            TableCapabilities {
+                delete: false,
+                ..all
+            }
+        );
+
+        // kernel can read, but not write, tables with type widening
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::TypeWidening]),
+            Some([WriterFeature::TypeWidening]),
+        )
+        .unwrap();
+        assert_eq!(
+            capabilities(&[], protocol),
+            TableCapabilities {
+                append: false,
+                delete: false,
+                ..all
+            }
+        );
+    }
 }
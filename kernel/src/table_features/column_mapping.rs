@@ -1,11 +1,14 @@
 //! Code to handle column mapping, including modes and schema transforms
 use super::ReaderFeature;
 use crate::actions::Protocol;
-use crate::schema::{ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField};
+use crate::schema::{
+    ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField, StructType,
+};
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error};
 
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
@@ -52,6 +55,8 @@ pub fn validate_schema_column_mapping(schema: &Schema, mode: ColumnMappingMode)
     let mut validator = ValidateColumnMappings {
         mode,
         path: vec![],
+        field_ids: HashMap::new(),
+        physical_names: vec![],
         err: None,
     };
     let _ = validator.transform_struct(schema);
@@ -64,6 +69,10 @@ pub fn validate_schema_column_mapping(schema: &Schema, mode: ColumnMappingMode)
 struct ValidateColumnMappings<'a> {
     mode: ColumnMappingMode,
     path: Vec<&'a str>,
+    // field ids must be unique across the whole schema, including nested fields
+    field_ids: HashMap<i64, ColumnName>,
+    // physical names must be unique among the fields of each struct; one entry per enclosing struct
+    physical_names: Vec<HashSet<&'a str>>,
     err: Option<Error>,
 }
 
@@ -80,14 +89,27 @@ impl<'a> ValidateColumnMappings<'a> {
         }
         None
     }
-    fn check_annotations(&mut self, field: &StructField) {
+    fn check_annotations(&mut self, field: &'a StructField) {
         // The iterator yields `&&str` but `ColumnName::new` needs `&str`
         let column_name = || ColumnName::new(self.path.iter().copied());
         let annotation = "delta.columnMapping.physicalName";
         match (self.mode, field.metadata.get(annotation)) {
             // Both Id and Name modes require a physical name annotation; None mode forbids it.
             (ColumnMappingMode::None, None) => {}
-            (ColumnMappingMode::Name | ColumnMappingMode::Id, Some(MetadataValue::String(_))) => {}
+            (
+                ColumnMappingMode::Name | ColumnMappingMode::Id,
+                Some(MetadataValue::String(physical_name)),
+            ) => {
+                let siblings = self.physical_names.last_mut();
+                if siblings.is_some_and(|names| !names.insert(physical_name)) {
+                    self.err = Some(Error::invalid_table_metadata(format!(
+                        "Field '{}' has the same {annotation} as another field of its struct: \
+                        '{physical_name}'",
+                        column_name()
+                    )));
+                    return;
+                }
+            }
             (ColumnMappingMode::Name | ColumnMappingMode::Id, Some(_)) => {
                 self.err = Some(Error::invalid_column_mapping_mode(format!(
                     "The {annotation} annotation on field '{}' must be a string",
@@ -112,7 +134,14 @@ impl<'a> ValidateColumnMappings<'a> {
         match (self.mode, field.metadata.get(annotation)) {
             // Both Id and Name modes require a field ID annotation; None mode forbids it.
             (ColumnMappingMode::None, None) => {}
-            (ColumnMappingMode::Name | ColumnMappingMode::Id, Some(MetadataValue::Number(_))) => {}
+            (ColumnMappingMode::Name | ColumnMappingMode::Id, Some(MetadataValue::Number(id))) => {
+                if let Some(other) = self.field_ids.insert(*id, column_name()) {
+                    self.err = Some(Error::invalid_table_metadata(format!(
+                        "Fields '{other}' and '{}' have the same {annotation}: {id}",
+                        column_name()
+                    )));
+                }
+            }
             (ColumnMappingMode::Name | ColumnMappingMode::Id, Some(_)) => {
                 self.err = Some(Error::invalid_column_mapping_mode(format!(
                     "The {annotation} annotation on field '{}' must be a number",
@@ -136,6 +165,12 @@ impl<'a> ValidateColumnMappings<'a> {
 }
 
 impl<'a> SchemaTransform<'a> for ValidateColumnMappings<'a> {
+    fn transform_struct(&mut self, stype: &'a StructType) -> Option<Cow<'a, StructType>> {
+        self.physical_names.push(HashSet::new());
+        let _ = self.recurse_into_struct(stype);
+        self.physical_names.pop();
+        None
+    }
     // Override array element and map key/value for better error messages
     fn transform_array_element(&mut self, etype: &'a DataType) -> Option<Cow<'a, DataType>> {
         self.transform_inner_type(etype, "<array element>")
@@ -356,4 +391,33 @@ mod tests {
         let schema = create_schema(None, None, None, "\"col-5f422f40\"");
         validate_schema_column_mapping(&schema, ColumnMappingMode::None).expect_err("field name");
     }
+
+    #[test]
+    fn test_column_mapping_consistency() {
+        // a nested field id colliding with its parent's
+        let schema = create_schema("4", "\"col-a7f4159c\"", "4", "\"col-5f422f40\"");
+        let err = validate_schema_column_mapping(&schema, ColumnMappingMode::Name).unwrap_err();
+        assert!(matches!(err, Error::InvalidTableMetadata(_)), "{err}");
+        assert!(
+            err.to_string().contains("'e' and 'e.`<array element>`.d'"),
+            "{err}"
+        );
+
+        // physical names only need to be unique within a struct
+        let schema = create_schema("5", "\"col-a7f4159c\"", "4", "\"col-a7f4159c\"");
+        validate_schema_column_mapping(&schema, ColumnMappingMode::Name).unwrap();
+        let field = |name: &str, id: i64| {
+            StructField::nullable(name, DataType::INTEGER).with_metadata([
+                ("delta.columnMapping.id", MetadataValue::Number(id)),
+                (
+                    "delta.columnMapping.physicalName",
+                    MetadataValue::String("col-a7f4159c".to_string()),
+                ),
+            ])
+        };
+        let schema = StructType::new([field("a", 1), field("b", 2)]);
+        let err = validate_schema_column_mapping(&schema, ColumnMappingMode::Name).unwrap_err();
+        assert!(matches!(err, Error::InvalidTableMetadata(_)), "{err}");
+        assert!(err.to_string().contains("'b'"), "{err}");
+    }
 }
@@ -5,6 +5,7 @@ use strum::{AsRefStr, Display as StrumDisplay, EnumCount, EnumString};
 
 use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::DataType;
+use crate::table_properties::TableProperties;
 use delta_kernel_derive::internal_api;
 
 pub(crate) use column_mapping::column_mapping_mode;
@@ -130,6 +131,9 @@ pub(crate) enum WriterFeature {
     ClusteredTable,
     /// This feature enables support for the variant data type, which stores semi-structured data.
     VariantType,
+    /// checkpointProtection writer feature protects checkpoints and commits before
+    /// `delta.requireCheckpointProtectionBeforeVersion` from being cleaned up or rewritten
+    CheckpointProtection,
     #[serde(untagged)]
     #[strum(default)]
     Unknown(String),
@@ -161,6 +165,216 @@ impl WriterFeature {
     }
 }
 
+/// The kinds of actions a write can stage. See [`WriterFeature::required_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[internal_api]
+pub(crate) enum WriteAction {
+    /// `add` actions for new data files
+    AddFiles,
+    /// `remove` actions for existing data files
+    RemoveFiles,
+    /// deletion vectors on `add` or `remove` actions
+    DeletionVectors,
+    /// `cdc` actions for change data files
+    CdcFiles,
+    /// `domainMetadata` actions
+    DomainMetadata,
+    /// `txn` (set transaction) actions
+    SetTransaction,
+}
+
+impl WriterFeature {
+    /// The minimal set of writer features the table protocol must support for a write which stages
+    /// `actions` to a table with the given properties. These are the features the actions
+    /// themselves need (e.g. writing deletion vectors requires [`WriterFeature::DeletionVectors`])
+    /// plus the features which the table properties enable for every write (e.g.
+    /// `delta.enableInCommitTimestamps` requires [`WriterFeature::InCommitTimestamp`]).
+    #[internal_api]
+    pub(crate) fn required_for(
+        actions: &[WriteAction],
+        table_properties: &TableProperties,
+    ) -> WriterFeatureSet {
+        let enabled = |property: Option<bool>| property.unwrap_or(false);
+        let column_mapping = matches!(
+            table_properties.column_mapping_mode,
+            Some(ColumnMappingMode::Name | ColumnMappingMode::Id)
+        );
+        [
+            (
+                WriterFeature::AppendOnly,
+                enabled(table_properties.append_only),
+            ),
+            (
+                WriterFeature::ChangeDataFeed,
+                actions.contains(&WriteAction::CdcFiles)
+                    || enabled(table_properties.enable_change_data_feed),
+            ),
+            (WriterFeature::ColumnMapping, column_mapping),
+            (
+                WriterFeature::InCommitTimestamp,
+                enabled(table_properties.enable_in_commit_timestamps),
+            ),
+            (
+                WriterFeature::DeletionVectors,
+                actions.contains(&WriteAction::DeletionVectors),
+            ),
+            (
+                WriterFeature::RowTracking,
+                enabled(table_properties.enable_row_tracking),
+            ),
+            (
+                WriterFeature::DomainMetadata,
+                actions.contains(&WriteAction::DomainMetadata),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(feature, required)| required.then_some(feature))
+        .collect()
+    }
+
+    /// The legacy writer version (before table features, i.e. below 7) from which this feature is
+    /// implicitly supported, or `None` if the feature can only be enabled with writer version 7.
+    pub(crate) fn legacy_writer_version(&self) -> Option<i32> {
+        match self {
+            WriterFeature::AppendOnly | WriterFeature::Invariants => Some(2),
+            WriterFeature::CheckConstraints => Some(3),
+            WriterFeature::ChangeDataFeed | WriterFeature::GeneratedColumns => Some(4),
+            WriterFeature::ColumnMapping => Some(5),
+            WriterFeature::IdentityColumns => Some(6),
+            _ => None,
+        }
+    }
+
+    // The bit of this feature in a [`WriterFeatureSet`], or `None` for an unknown feature. Bits
+    // follow the order of KNOWN_WRITER_FEATURES.
+    fn set_bit(&self) -> Option<u32> {
+        let bit = match self {
+            WriterFeature::AppendOnly => 0,
+            WriterFeature::Invariants => 1,
+            WriterFeature::CheckConstraints => 2,
+            WriterFeature::ChangeDataFeed => 3,
+            WriterFeature::GeneratedColumns => 4,
+            WriterFeature::ColumnMapping => 5,
+            WriterFeature::IdentityColumns => 6,
+            WriterFeature::InCommitTimestamp => 7,
+            WriterFeature::DeletionVectors => 8,
+            WriterFeature::RowTracking => 9,
+            WriterFeature::TimestampWithoutTimezone => 10,
+            WriterFeature::TypeWidening => 11,
+            WriterFeature::TypeWideningPreview => 12,
+            WriterFeature::DomainMetadata => 13,
+            WriterFeature::V2Checkpoint => 14,
+            WriterFeature::IcebergCompatV1 => 15,
+            WriterFeature::IcebergCompatV2 => 16,
+            WriterFeature::VacuumProtocolCheck => 17,
+            WriterFeature::ClusteredTable => 18,
+            WriterFeature::VariantType => 19,
+            WriterFeature::CheckpointProtection => 20,
+            WriterFeature::Unknown(_) => return None,
+        };
+        Some(bit)
+    }
+}
+
+/// Every writer feature except [`WriterFeature::Unknown`], in the order of their bits in a
+/// [`WriterFeatureSet`].
+static KNOWN_WRITER_FEATURES: [WriterFeature; WriterFeature::COUNT - 1] = [
+    WriterFeature::AppendOnly,
+    WriterFeature::Invariants,
+    WriterFeature::CheckConstraints,
+    WriterFeature::ChangeDataFeed,
+    WriterFeature::GeneratedColumns,
+    WriterFeature::ColumnMapping,
+    WriterFeature::IdentityColumns,
+    WriterFeature::InCommitTimestamp,
+    WriterFeature::DeletionVectors,
+    WriterFeature::RowTracking,
+    WriterFeature::TimestampWithoutTimezone,
+    WriterFeature::TypeWidening,
+    WriterFeature::TypeWideningPreview,
+    WriterFeature::DomainMetadata,
+    WriterFeature::V2Checkpoint,
+    WriterFeature::IcebergCompatV1,
+    WriterFeature::IcebergCompatV2,
+    WriterFeature::VacuumProtocolCheck,
+    WriterFeature::ClusteredTable,
+    WriterFeature::VariantType,
+    WriterFeature::CheckpointProtection,
+];
+
+/// A set of known writer features, stored as a bitset so that checking whether a feature is a
+/// member doesn't scan a list. [`WriterFeature::Unknown`] features are never members: kernel
+/// can't support or require them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[internal_api]
+pub(crate) struct WriterFeatureSet(u32);
+
+impl WriterFeatureSet {
+    /// The known writer features which a table on the given legacy writer version (below 7)
+    /// implicitly supports. See [`WriterFeature::legacy_writer_version`].
+    pub(crate) fn legacy(writer_version: i32) -> Self {
+        KNOWN_WRITER_FEATURES
+            .iter()
+            .filter(|feature| {
+                feature
+                    .legacy_writer_version()
+                    .is_some_and(|min_version| min_version <= writer_version)
+            })
+            .collect()
+    }
+
+    /// Adds `feature` to the set. Unknown features are ignored.
+    pub(crate) fn insert(&mut self, feature: &WriterFeature) {
+        if let Some(bit) = feature.set_bit() {
+            self.0 |= 1 << bit;
+        }
+    }
+
+    /// True if `feature` is in the set.
+    pub(crate) fn contains(&self, feature: &WriterFeature) -> bool {
+        feature
+            .set_bit()
+            .is_some_and(|bit| self.0 & (1 << bit) != 0)
+    }
+
+    /// The features in this set which are not in `other`.
+    pub(crate) fn difference(&self, other: &WriterFeatureSet) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// True if the set has no features.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The features in the set, in declaration order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &'static WriterFeature> + '_ {
+        KNOWN_WRITER_FEATURES
+            .iter()
+            .filter(|feature| self.contains(feature))
+    }
+}
+
+impl<'a> FromIterator<&'a WriterFeature> for WriterFeatureSet {
+    fn from_iter<T: IntoIterator<Item = &'a WriterFeature>>(iter: T) -> Self {
+        let mut set = Self::default();
+        for feature in iter {
+            set.insert(feature);
+        }
+        set
+    }
+}
+
+impl FromIterator<WriterFeature> for WriterFeatureSet {
+    fn from_iter<T: IntoIterator<Item = WriterFeature>>(iter: T) -> Self {
+        let mut set = Self::default();
+        for feature in iter {
+            set.insert(&feature);
+        }
+        set
+    }
+}
+
 pub(crate) static SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = LazyLock::new(|| {
     vec![
         ReaderFeature::ColumnMapping,
@@ -174,19 +388,33 @@ pub(crate) static SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = Lazy
 });
 
 // note: we 'support' Invariants, but only insofar as we check that they are not present.
-// we support writing to tables that have Invariants enabled but not used. similarly, we only
-// support DeletionVectors in that we never write them (no DML).
+// we support writing to tables that have Invariants enabled but not used. DeletionVectors are
+// written by `Transaction::delete_rows` (and files removed whole keep their deletion vector in the
+// remove action). similarly, we only support CheckpointProtection in that we never clean up log
+// files and refuse to checkpoint protected versions. VacuumProtocolCheck only
+// requires that vacuum checks write support, which the vacuum planner does. CheckConstraints and
+// GeneratedColumns are enforced on write, as long as kernel can parse their SQL expressions.
 pub(crate) static SUPPORTED_WRITER_FEATURES: LazyLock<Vec<WriterFeature>> = LazyLock::new(|| {
     vec![
         WriterFeature::AppendOnly,
+        WriterFeature::CheckConstraints,
+        WriterFeature::CheckpointProtection,
+        WriterFeature::ColumnMapping,
         WriterFeature::DeletionVectors,
+        WriterFeature::DomainMetadata,
+        WriterFeature::GeneratedColumns,
+        WriterFeature::InCommitTimestamp,
         WriterFeature::Invariants,
+        WriterFeature::RowTracking,
         WriterFeature::TimestampWithoutTimezone,
+        WriterFeature::VacuumProtocolCheck,
     ]
 });
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools as _;
+
     use super::*;
 
     #[test]
@@ -275,6 +503,7 @@ mod tests {
             (WriterFeature::VacuumProtocolCheck, "vacuumProtocolCheck"),
             (WriterFeature::ClusteredTable, "clustering"),
             (WriterFeature::VariantType, "variantType"),
+            (WriterFeature::CheckpointProtection, "checkpointProtection"),
             (WriterFeature::unknown("something"), "something"),
         ];
 
@@ -292,4 +521,82 @@ mod tests {
             assert_eq!(from_str, feature);
         }
     }
+
+    #[test]
+    fn test_required_for() {
+        let no_properties = TableProperties::default();
+        assert_eq!(
+            WriterFeature::required_for(&[WriteAction::AddFiles], &no_properties),
+            WriterFeatureSet::default()
+        );
+        assert_eq!(
+            WriterFeature::required_for(
+                &[
+                    WriteAction::RemoveFiles,
+                    WriteAction::DeletionVectors,
+                    WriteAction::DomainMetadata
+                ],
+                &no_properties
+            ),
+            WriterFeatureSet::from_iter([
+                WriterFeature::DeletionVectors,
+                WriterFeature::DomainMetadata
+            ])
+        );
+
+        let properties = TableProperties::from(
+            [
+                ("delta.appendOnly", "true"),
+                ("delta.enableChangeDataFeed", "false"),
+                ("delta.enableInCommitTimestamps", "true"),
+                ("delta.columnMapping.mode", "name"),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(
+            WriterFeature::required_for(&[WriteAction::CdcFiles], &properties),
+            WriterFeatureSet::from_iter([
+                WriterFeature::AppendOnly,
+                WriterFeature::ChangeDataFeed,
+                WriterFeature::ColumnMapping,
+                WriterFeature::InCommitTimestamp,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_writer_feature_set() {
+        // every known feature has its own bit, in declaration order
+        for (bit, feature) in KNOWN_WRITER_FEATURES.iter().enumerate() {
+            assert_eq!(feature.set_bit(), Some(bit as u32));
+        }
+
+        let mut set =
+            WriterFeatureSet::from_iter([WriterFeature::RowTracking, WriterFeature::AppendOnly]);
+        set.insert(&WriterFeature::unknown("cool_feature"));
+        assert!(set.contains(&WriterFeature::AppendOnly));
+        assert!(!set.contains(&WriterFeature::DeletionVectors));
+        assert!(!set.contains(&WriterFeature::unknown("cool_feature")));
+        assert_eq!(
+            set.iter().collect_vec(),
+            [&WriterFeature::AppendOnly, &WriterFeature::RowTracking]
+        );
+
+        let difference = set.difference(&WriterFeatureSet::from_iter([WriterFeature::AppendOnly]));
+        assert_eq!(
+            difference.iter().collect_vec(),
+            [&WriterFeature::RowTracking]
+        );
+        assert!(difference.difference(&set).is_empty());
+        assert_eq!(
+            WriterFeatureSet::legacy(4).iter().collect_vec(),
+            [
+                &WriterFeature::AppendOnly,
+                &WriterFeature::Invariants,
+                &WriterFeature::CheckConstraints,
+                &WriterFeature::ChangeDataFeed,
+                &WriterFeature::GeneratedColumns,
+            ]
+        );
+    }
 }
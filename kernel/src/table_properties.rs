@@ -137,6 +137,14 @@ pub struct TableProperties {
     /// whether to enable row tracking during writes.
     pub enable_row_tracking: Option<bool>,
 
+    /// The name of the physical column that holds the row ids materialized in data files, e.g. by
+    /// rewrites that must preserve the row ids of the rows they copy.
+    pub materialized_row_id_column_name: Option<String>,
+
+    /// The name of the physical column that holds the row commit versions materialized in data
+    /// files.
+    pub materialized_row_commit_version_column_name: Option<String>,
+
     /// Whether to enable [In-Commit Timestamps]. The in-commit timestamps writer feature strongly
     /// associates a monotonically increasing timestamp with each commit by storing it in the
     /// commit's metadata.
@@ -151,6 +159,13 @@ pub struct TableProperties {
     /// as the inCommitTimestamp of the commit when this feature was enabled.
     pub in_commit_timestamp_enablement_timestamp: Option<i64>,
 
+    /// When the [checkpointProtection] writer feature is supported, the version before which
+    /// checkpoints and commits are protected: writers may only clean up that history by truncating
+    /// all of it at once, and must not rewrite checkpoints for those versions.
+    ///
+    /// [checkpointProtection]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#checkpoint-protection
+    pub require_checkpoint_protection_before_version: Option<Version>,
+
     /// any unrecognized properties are passed through and ignored by the parser
     pub unknown_properties: HashMap<String, String>,
 }
@@ -282,9 +297,18 @@ mod tests {
             ("delta.tuneFileSizesForRewrites", "true"),
             ("delta.checkpointPolicy", "v2"),
             ("delta.enableRowTracking", "true"),
+            (
+                "delta.rowTracking.materializedRowIdColumnName",
+                "_row-id-col",
+            ),
+            (
+                "delta.rowTracking.materializedRowCommitVersionColumnName",
+                "_row-commit-version-col",
+            ),
             ("delta.enableInCommitTimestamps", "true"),
             ("delta.inCommitTimestampEnablementVersion", "15"),
             ("delta.inCommitTimestampEnablementTimestamp", "1612345678"),
+            ("delta.requireCheckpointProtectionBeforeVersion", "20"),
         ];
         let actual = TableProperties::from(properties.into_iter());
         let expected = TableProperties {
@@ -310,9 +334,14 @@ mod tests {
             tune_file_sizes_for_rewrites: Some(true),
             checkpoint_policy: Some(CheckpointPolicy::V2),
             enable_row_tracking: Some(true),
+            materialized_row_id_column_name: Some("_row-id-col".to_string()),
+            materialized_row_commit_version_column_name: Some(
+                "_row-commit-version-col".to_string(),
+            ),
             enable_in_commit_timestamps: Some(true),
             in_commit_timestamp_enablement_version: Some(15),
             in_commit_timestamp_enablement_timestamp: Some(1_612_345_678),
+            require_checkpoint_protection_before_version: Some(20),
             unknown_properties: HashMap::new(),
         };
         assert_eq!(actual, expected);
@@ -79,6 +79,12 @@ fn try_parse(props: &mut TableProperties, k: &str, v: &str) -> Option<()> {
         }
         "delta.checkpointPolicy" => props.checkpoint_policy = CheckpointPolicy::try_from(v).ok(),
         "delta.enableRowTracking" => props.enable_row_tracking = Some(parse_bool(v)?),
+        "delta.rowTracking.materializedRowIdColumnName" => {
+            props.materialized_row_id_column_name = Some(v.to_string())
+        }
+        "delta.rowTracking.materializedRowCommitVersionColumnName" => {
+            props.materialized_row_commit_version_column_name = Some(v.to_string())
+        }
         "delta.enableInCommitTimestamps" => {
             props.enable_in_commit_timestamps = Some(parse_bool(v)?)
         }
@@ -88,6 +94,9 @@ fn try_parse(props: &mut TableProperties, k: &str, v: &str) -> Option<()> {
         "delta.inCommitTimestampEnablementTimestamp" => {
             props.in_commit_timestamp_enablement_timestamp = Some(parse_non_negative(v)?)
         }
+        "delta.requireCheckpointProtectionBeforeVersion" => {
+            props.require_checkpoint_protection_before_version = Some(parse_non_negative(v)?)
+        }
         _ => return None,
     }
     Some(())
@@ -1,19 +1,49 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::sync::{Arc, LazyLock};
-use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::actions::SetTransaction;
-use crate::actions::COMMIT_INFO_NAME;
-use crate::actions::{get_log_add_schema, get_log_commit_info_schema, get_log_txn_schema};
+use crate::actions::crc::CrcWriter;
+use crate::actions::deletion_vector::{
+    DeletionVectorDescriptor, DeletionVectorFile, DeletionVectorWriter,
+};
+use crate::actions::visitors::CommitInfoVisitor;
+use crate::actions::{
+    get_log_add_schema, get_log_commit_info_schema, get_log_domain_metadata_schema,
+    get_log_txn_schema,
+};
+use crate::actions::{DomainMetadata, Remove, SetTransaction};
+use crate::actions::{COMMIT_INFO_NAME, REMOVE_NAME};
+use crate::clock::{IdGenerator, KernelClock, RandomIdGenerator, SystemClock};
+use crate::compat::{adds_from_scan_metadata, Add};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
 use crate::error::Error;
-use crate::expressions::{column_expr, Scalar, StructData};
+use crate::expressions::{column_expr, MapData, Scalar, StructData};
+use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
 use crate::path::ParsedLogPath;
-use crate::schema::{MapType, SchemaRef, StructField, StructType};
+use crate::row_tracking::RowIdAssigner;
+use crate::scan::parse_partition_value;
+use crate::schema::{
+    column_name, ColumnName, ColumnNamesAndTypes, MapType, SchemaRef, StructField, StructType,
+    ToSchema as _,
+};
 use crate::snapshot::Snapshot;
-use crate::{DataType, DeltaResult, Engine, EngineData, Expression, IntoEngineData, Version};
-
+use crate::table_features::{ColumnMappingMode, WriteAction};
+use crate::utils::require;
+use crate::{
+    DataType, DeltaResult, Engine, EngineData, EvaluationHandlerExtension as _, Expression,
+    FileMeta, IntoEngineData, PredicateRef, Version,
+};
+
+use roaring::RoaringTreemap;
+use tracing::{debug, warn};
 use url::Url;
+use uuid::Uuid;
+
+mod constraints;
+mod partition;
+mod sink;
+use constraints::{check_constraints, WriteConstraint};
+pub use sink::{AppendResult, StreamingSink};
 
 const KERNEL_VERSION: &str = env!("CARGO_PKG_VERSION");
 const UNKNOWN_OPERATION: &str = "UNKNOWN";
@@ -28,13 +58,16 @@ pub(crate) static ADD_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
         StructField::not_null("size", DataType::LONG),
         StructField::not_null("modificationTime", DataType::LONG),
         StructField::not_null("dataChange", DataType::BOOLEAN),
+        StructField::nullable("stats", DataType::STRING),
     ]))
 });
 
 /// This function specifies the schema for the add_files metadata (and soon remove_files metadata).
 /// Concretely, it is the expected schema for engine data passed to [`add_files`].
 ///
-/// Each row represents metadata about a file to be added to the table.
+/// Each row represents metadata about a file to be added to the table. The `stats` column holds
+/// the file statistics as a JSON string, as in the `stats` of an `add` action. Statistics are
+/// optional, except that tables with row tracking need the `numRecords` of every added file.
 ///
 /// [`add_files`]: crate::transaction::Transaction::add_files
 pub fn add_files_schema() -> &'static SchemaRef {
@@ -58,6 +91,8 @@ pub fn add_files_schema() -> &'static SchemaRef {
 pub struct Transaction {
     read_snapshot: Arc<Snapshot>,
     operation: Option<String>,
+    engine_info: Option<String>,
+    user_metadata: Option<String>,
     commit_info: Option<Arc<dyn EngineData>>,
     add_files_metadata: Vec<Box<dyn EngineData>>,
     // NB: hashmap would require either duplicating the appid or splitting SetTransaction
@@ -66,9 +101,33 @@ pub struct Transaction {
     // would make error messaging unnecessarily difficult. Thus, we keep Vec here and deduplicate in
     // the commit method.
     set_transactions: Vec<SetTransaction>,
+    // domain metadata to set or remove. Like set transactions, each domain may only appear once,
+    // which is checked in the commit method. The configuration of removed domains is only looked
+    // up on commit.
+    domain_metadatas: Vec<DomainMetadata>,
+    // files to remove, and files which may only be removed after the engine confirms them (see
+    // `remove_files_matching` and `confirm_exact`)
+    remove_files: Vec<RemoveFile>,
+    unconfirmed_remove_files: Vec<RemoveFile>,
+    // files re-added with a new deletion vector (see `delete_rows`), which are also in
+    // `remove_files` with their old one
+    deletion_vector_updates: Vec<DeletionVectorUpdate>,
+    // the live files of the read snapshot by location, scanned on the first call to `delete_rows`
+    live_files: Option<HashMap<Url, Add>>,
     // commit-wide timestamp (in milliseconds since epoch) - used in ICT, `txn` action, etc. to
     // keep all timestamps within the same commit consistent.
     commit_timestamp: i64,
+    // written as the `txnId` of the commit info, to recognize our own commit if the outcome of the
+    // commit write is unknown
+    txn_id: Uuid,
+    // generates the names of the deletion vector files written for this transaction
+    id_generator: Arc<dyn IdGenerator>,
+    // whether to write a version checksum (CRC) file after a successful commit
+    write_checksum: bool,
+    // the `dataChange` flag of the files removed by `remove_files_matching`
+    data_change: bool,
+    // the check constraints and generated columns which written data must satisfy
+    constraints: Arc<Vec<WriteConstraint>>,
 }
 
 impl std::fmt::Debug for Transaction {
@@ -95,27 +154,101 @@ impl Transaction {
         read_snapshot
             .table_configuration()
             .ensure_write_supported()?;
+        let constraints =
+            WriteConstraint::try_from_table_configuration(read_snapshot.table_configuration())?;
 
-        // TODO: unify all these into a (safer) `fn current_time_ms()`
-        let commit_timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .ok()
-            .and_then(|d| i64::try_from(d.as_millis()).ok())
-            .ok_or_else(|| Error::generic("Failed to get current time for commit_timestamp"))?;
+        let commit_timestamp = SystemClock.now_millis()?;
 
         Ok(Transaction {
             read_snapshot,
             operation: None,
+            engine_info: None,
+            user_metadata: None,
             commit_info: None,
             add_files_metadata: vec![],
             set_transactions: vec![],
+            domain_metadatas: vec![],
+            remove_files: vec![],
+            unconfirmed_remove_files: vec![],
+            deletion_vector_updates: vec![],
+            live_files: None,
             commit_timestamp,
+            txn_id: RandomIdGenerator.next_id(),
+            id_generator: Arc::new(RandomIdGenerator),
+            write_checksum: false,
+            data_change: true,
+            constraints: Arc::new(constraints),
         })
     }
 
     /// Consume the transaction and commit it to the table. The result is a [CommitResult] which
     /// will include the failed transaction in case of a conflict so the user can retry.
+    ///
+    /// If writing the commit file fails, e.g. because the request timed out or because a retry
+    /// found the file already written, the commit may or may not have landed. In that case the
+    /// commit file is read back: if its `txnId` is this transaction's [`txn_id`], the commit is
+    /// reported as successful, and if it was written by another transaction, as a conflict.
+    /// Otherwise, the original error is returned.
+    ///
+    /// Committing remove actions which change data (`dataChange = true`) to an append-only table
+    /// (`delta.appendOnly = true`) is an error.
+    ///
+    /// [`txn_id`]: Self::txn_id
     pub fn commit(self, engine: &dyn Engine) -> DeltaResult<CommitResult> {
+        if !self.unconfirmed_remove_files.is_empty() {
+            return Err(Error::generic(format!(
+                "Transaction has {} candidate files to remove which were not confirmed with \
+                confirm_exact",
+                self.unconfirmed_remove_files.len()
+            )));
+        }
+        // data must never be removed from an append-only table, however its removal was staged.
+        // Removes which only rearrange data (e.g. compaction) are allowed.
+        let data_change_removes = self.remove_files.iter().filter(|file| file.data_change);
+        let data_change_removes = data_change_removes.count();
+        if data_change_removes > 0
+            && self
+                .read_snapshot
+                .table_configuration()
+                .is_append_only_enabled()
+        {
+            return Err(Error::generic(format!(
+                "Cannot commit {data_change_removes} remove action(s) with dataChange = true to an \
+                append-only table (delta.appendOnly = true)"
+            )));
+        }
+
+        // the table protocol must support the features this write needs. If it supports row
+        // tracking, adding files updates the row id high water mark domain metadata.
+        let updates_deletion_vectors = !self.deletion_vector_updates.is_empty();
+        let row_tracking = self
+            .read_snapshot
+            .table_configuration()
+            .is_row_tracking_supported();
+        let write_actions: Vec<_> = [
+            (
+                WriteAction::AddFiles,
+                !self.add_files_metadata.is_empty() || updates_deletion_vectors,
+            ),
+            (WriteAction::RemoveFiles, !self.remove_files.is_empty()),
+            (WriteAction::DeletionVectors, updates_deletion_vectors),
+            (
+                WriteAction::SetTransaction,
+                !self.set_transactions.is_empty(),
+            ),
+            (
+                WriteAction::DomainMetadata,
+                !self.domain_metadatas.is_empty()
+                    || (row_tracking && !self.add_files_metadata.is_empty()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(action, staged)| staged.then_some(action))
+        .collect();
+        self.read_snapshot
+            .table_configuration()
+            .ensure_write_features_supported(&write_actions)?;
+
         // step 0: if there are txn(app_id, version) actions being committed, ensure that every
         // `app_id` is unique and create a row of `EngineData` for it.
         // TODO(zach): we currently do this in two passes - can we do it in one and still keep refs
@@ -137,35 +270,257 @@ impl Transaction {
             .into_iter()
             .map(|txn| txn.into_engine_data(get_log_txn_schema().clone(), engine));
 
+        // step 0.5: ensure that every domain is unique and user-controlled, and create a row of
+        // `EngineData` for each domain metadata action.
+        let domain_metadata_actions = self.generate_domain_metadata_actions(engine)?;
+
         // step one: construct the iterator of commit info + file actions we want to commit
         let engine_commit_info = self
             .commit_info
             .as_ref()
             .ok_or_else(|| Error::MissingCommitInfo)?;
-        let commit_info_actions = generate_commit_info(
-            engine,
-            self.operation.as_deref(),
-            self.commit_timestamp,
-            engine_commit_info.as_ref(),
-        );
-        let add_actions = generate_adds(engine, self.add_files_metadata.iter().map(|a| a.as_ref()));
+        // in-commit timestamps must increase with each commit, even if the clock doesn't
+        let in_commit_timestamp = self
+            .read_snapshot
+            .get_in_commit_timestamp(engine)?
+            .map(|previous| self.commit_timestamp.max(previous + 1));
+        let txn_id = self.txn_id.to_string();
+        let commit_info = CommitInfoFields {
+            operation: self.operation.as_deref(),
+            engine_info: self.engine_info.as_deref(),
+            user_metadata: self.user_metadata.as_deref(),
+            timestamp: self.commit_timestamp,
+            in_commit_timestamp,
+            txn_id: &txn_id,
+        };
+        let commit_info_actions =
+            generate_commit_info(engine, &commit_info, engine_commit_info.as_ref());
+        // step two: set new commit version (current_version + 1), which row tracking assigns to the
+        // added files
+        let commit_version = self.read_snapshot.version() + 1;
+        let add_actions: Box<dyn Iterator<Item = _> + Send> = if row_tracking {
+            Box::new(
+                self.generate_row_tracking_adds(engine, commit_version)?
+                    .into_iter(),
+            )
+        } else {
+            let partition_columns = self.physical_partition_columns()?;
+            let deletion_vector_add_actions = self
+                .deletion_vector_updates
+                .iter()
+                .map(move |update| update.to_add_action(engine, &partition_columns, None, None));
+            Box::new(
+                generate_adds(engine, self.add_files_metadata.iter().map(|a| a.as_ref()))
+                    .chain(deletion_vector_add_actions),
+            )
+        };
+        let remove_actions = self
+            .remove_files
+            .iter()
+            .map(|file| file.to_remove_action(engine, self.commit_timestamp));
 
         let actions = iter::once(commit_info_actions)
             .chain(add_actions)
-            .chain(set_transaction_actions);
+            .chain(remove_actions)
+            .chain(set_transaction_actions)
+            .chain(domain_metadata_actions);
 
-        // step two: set new commit version (current_version + 1) and path to write
-        let commit_version = self.read_snapshot.version() + 1;
+        // and the path to write
         let commit_path =
             ParsedLogPath::new_commit(self.read_snapshot.table_root(), commit_version)?;
 
         // step three: commit the actions as a json file in the log
         let json_handler = engine.json_handler();
-        match json_handler.write_json_file(&commit_path.location, Box::new(actions), false) {
-            Ok(()) => Ok(CommitResult::Committed(commit_version)),
-            Err(Error::FileAlreadyExists(_)) => Ok(CommitResult::Conflict(self, commit_version)),
-            Err(e) => Err(e),
+        let Err(err) =
+            json_handler.write_json_file(&commit_path.location, Box::new(actions), false)
+        else {
+            self.write_checksum_file(engine, commit_version, in_commit_timestamp);
+            return Ok(CommitResult::Committed(commit_version));
+        };
+
+        // step four: the write failed, but the commit file may still have been written by it (or
+        // by an earlier attempt, if the engine retried). Read it back to find out who wrote it.
+        match read_commit_txn_id(engine, &commit_path.location) {
+            Ok(Some(txn_id)) if txn_id == self.txn_id.to_string() => {
+                self.write_checksum_file(engine, commit_version, in_commit_timestamp);
+                Ok(CommitResult::Committed(commit_version))
+            }
+            Ok(Some(_)) => Ok(CommitResult::Conflict(Box::new(self), commit_version)),
+            _ if matches!(err, Error::FileAlreadyExists(_)) => {
+                Ok(CommitResult::Conflict(Box::new(self), commit_version))
+            }
+            _ => Err(err),
+        }
+    }
+
+    // Create the add actions of a commit to a table with row tracking: the added files are assigned
+    // row ids after the table's high water mark and the commit version, so each add action is
+    // created separately. Files re-added with a new deletion vector keep their row tracking fields.
+    // This includes the domain metadata action which updates the high water mark.
+    fn generate_row_tracking_adds(
+        &self,
+        engine: &dyn Engine,
+        commit_version: Version,
+    ) -> DeltaResult<Vec<DeltaResult<Box<dyn EngineData>>>> {
+        let commit_version = i64::try_from(commit_version)
+            .map_err(|_| Error::generic(format!("Invalid commit version {commit_version}")))?;
+        let physical_partition_columns = self.physical_partition_columns()?;
+        let mut row_ids = RowIdAssigner::try_new(&self.read_snapshot, engine)?;
+        let mut actions = vec![];
+        for add_files in &self.add_files_metadata {
+            let mut visitor = AddFilesVisitor::default();
+            visitor.visit_rows_of(add_files.as_ref())?;
+            for file in visitor.files {
+                let base_row_id = row_ids.assign(&file.path, file.stats.as_deref())?;
+                actions.push(file.to_add_action(
+                    engine,
+                    &physical_partition_columns,
+                    base_row_id,
+                    commit_version,
+                ));
+            }
+        }
+        for update in &self.deletion_vector_updates {
+            let base_row_id = match update.file.base_row_id {
+                Some(base_row_id) => base_row_id,
+                None => row_ids.assign(&update.file.path, update.stats.as_deref())?,
+            };
+            let default_row_commit_version = update
+                .file
+                .default_row_commit_version
+                .unwrap_or(commit_version);
+            actions.push(update.to_add_action(
+                engine,
+                &physical_partition_columns,
+                Some(base_row_id),
+                Some(default_row_commit_version),
+            ));
         }
+        if let Some(domain_metadata) = row_ids.into_domain_metadata()? {
+            actions.push(
+                domain_metadata.into_engine_data(get_log_domain_metadata_schema().clone(), engine),
+            );
+        }
+        Ok(actions)
+    }
+
+    // The physical names of the table's partition columns, which key the partition values of add
+    // and remove actions
+    fn physical_partition_columns(&self) -> DeltaResult<Vec<String>> {
+        let schema = self.read_snapshot.schema();
+        self.read_snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .map(|column| {
+                let field = schema
+                    .field(column)
+                    .ok_or_else(|| Error::missing_column(column))?;
+                Ok(field.physical_name().to_string())
+            })
+            .collect()
+    }
+
+    // Validate the staged domain metadata and convert it to actions. Removing a domain writes a
+    // tombstone with its current configuration, or nothing if the domain does not exist.
+    fn generate_domain_metadata_actions<'a>(
+        &'a self,
+        engine: &'a dyn Engine,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + 'a> {
+        let mut domains = HashSet::new();
+        let mut domain_metadatas = Vec::with_capacity(self.domain_metadatas.len());
+        for domain_metadata in &self.domain_metadatas {
+            let domain = domain_metadata.domain();
+            require!(
+                !domain_metadata.is_internal(),
+                Error::generic(format!(
+                    "Cannot modify domain {domain}: 'delta.*' domains are system-controlled"
+                ))
+            );
+            require!(
+                domains.insert(domain),
+                Error::generic(format!("Domain {domain} already exists in transaction"))
+            );
+            if !domain_metadata.is_removed() {
+                domain_metadatas.push(domain_metadata.clone());
+            } else if let Some(configuration) =
+                self.read_snapshot.get_domain_metadata(domain, engine)?
+            {
+                domain_metadatas.push(DomainMetadata::new(domain.to_string(), configuration, true));
+            }
+        }
+        Ok(domain_metadatas.into_iter().map(|domain_metadata| {
+            domain_metadata.into_engine_data(get_log_domain_metadata_schema().clone(), engine)
+        }))
+    }
+
+    // Write the version checksum (CRC) file of the committed version, if enabled. The commit has
+    // already succeeded, and CRC files are only an optimization for readers, so failing to write
+    // one is logged rather than returned.
+    fn write_checksum_file(
+        &self,
+        engine: &dyn Engine,
+        commit_version: Version,
+        in_commit_timestamp: Option<i64>,
+    ) {
+        if !self.write_checksum {
+            return;
+        }
+        if let Err(e) = self.try_write_checksum_file(engine, commit_version, in_commit_timestamp) {
+            warn!("Failed to write the version checksum file of version {commit_version}: {e}");
+        }
+    }
+
+    fn try_write_checksum_file(
+        &self,
+        engine: &dyn Engine,
+        commit_version: Version,
+        in_commit_timestamp: Option<i64>,
+    ) -> DeltaResult<()> {
+        // the table state after the commit is the read snapshot's, plus the added files and minus
+        // the removed ones. Files whose deletion vector is updated are both removed and re-added.
+        // Without a CRC file of the read snapshot, its state would take a full log replay.
+        let Some(read_crc) = self.read_snapshot.crc(engine) else {
+            debug!(
+                "Not writing the version checksum file of version {commit_version}: the read \
+                snapshot has none"
+            );
+            return Ok(());
+        };
+        let mut num_files = read_crc.num_files;
+        let mut table_size_bytes = read_crc.table_size_bytes;
+        let mut added_files = AddedFilesVisitor::default();
+        for add_files in &self.add_files_metadata {
+            added_files.visit_rows_of(add_files.as_ref())?;
+        }
+        num_files += added_files.num_files;
+        table_size_bytes += added_files.size_bytes;
+        for update in &self.deletion_vector_updates {
+            num_files += 1;
+            table_size_bytes += update.file.size;
+        }
+        for file in &self.remove_files {
+            num_files -= 1;
+            table_size_bytes -= file.size;
+        }
+
+        let crc_path = ParsedLogPath::new_crc(self.read_snapshot.table_root(), commit_version)?;
+        CrcWriter {
+            txn_id: self.txn_id.to_string(),
+            table_size_bytes,
+            num_files,
+            num_metadata: 1,
+            num_protocol: 1,
+            in_commit_timestamp_opt: in_commit_timestamp,
+            metadata: self.read_snapshot.metadata(),
+            protocol: self.read_snapshot.protocol(),
+        }
+        .write(engine, &crc_path.location)
+    }
+
+    /// The unique identifier of this transaction, written as the `txnId` of its commit info.
+    pub fn txn_id(&self) -> Uuid {
+        self.txn_id
     }
 
     /// Set the operation that this transaction is performing. This string will be persisted in the
@@ -175,6 +530,68 @@ impl Transaction {
         self
     }
 
+    /// Set the `engineInfo` of the commit, identifying the engine that wrote it (Spark writes e.g.
+    /// `Apache-Spark/3.5.0 Delta-Lake/3.2.0`). This makes commits attributable in the table history.
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Set the `userMetadata` of the commit: an arbitrary string describing the commit, which is
+    /// visible to anyone who describes the table history.
+    pub fn with_user_metadata(mut self, user_metadata: impl Into<String>) -> Self {
+        self.user_metadata = Some(user_metadata.into());
+        self
+    }
+
+    /// Use `clock` instead of the system clock for the timestamps written by this transaction
+    /// (the commit timestamp, `lastUpdated` of transaction ids and `deletionTimestamp` of removed
+    /// files). This is useful to produce deterministic commits, e.g. in tests.
+    pub fn with_clock(mut self, clock: Arc<dyn KernelClock>) -> DeltaResult<Self> {
+        self.commit_timestamp = clock.now_millis()?;
+        for set_transaction in &mut self.set_transactions {
+            set_transaction.last_updated = Some(self.commit_timestamp);
+        }
+        Ok(self)
+    }
+
+    /// Use `id_generator` instead of random UUIDs to generate the [`txn_id`] of this transaction
+    /// and the names of the deletion vector files it writes. This is useful to produce
+    /// deterministic commits, e.g. in tests.
+    ///
+    /// [`txn_id`]: Self::txn_id
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.txn_id = id_generator.next_id();
+        self.id_generator = id_generator;
+        self
+    }
+
+    /// Write a version checksum (CRC) file for the committed version after a successful commit, so
+    /// that readers of the table can find its protocol, metadata, file count and size without log
+    /// replay. The file count and size of the table are derived from the CRC file of the read
+    /// snapshot, so no CRC file is written if the read snapshot's version has none (computing them
+    /// would need log replay of the whole table). A failure to write the CRC file does not fail the
+    /// commit.
+    ///
+    /// Defaults to `false`.
+    pub fn with_version_checksum(mut self, write_checksum: bool) -> Self {
+        self.write_checksum = write_checksum;
+        self
+    }
+
+    /// Set the `dataChange` flag of the files removed by [`remove_files_matching`]. Set this to
+    /// `false` when the transaction only rearranges data without changing it, e.g. to compact
+    /// files, whose rewritten files are then added with `dataChange = false` as well. Such
+    /// removes are allowed on append-only tables.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// [`remove_files_matching`]: Self::remove_files_matching
+    pub fn with_data_change(mut self, data_change: bool) -> Self {
+        self.data_change = data_change;
+        self
+    }
+
     /// Include a SetTransaction (app_id and version) action for this transaction (with an optional
     /// `last_updated` timestamp).
     /// Note that each app_id can only appear once per transaction. That is, multiple app_ids with
@@ -186,6 +603,32 @@ impl Transaction {
         self
     }
 
+    /// Set the configuration of a metadata `domain` in this transaction, creating the domain if it
+    /// does not exist. This lets engines persist their own per-table state (e.g. streaming
+    /// progress) atomically with the rest of the commit. The table protocol must support the
+    /// `domainMetadata` writer feature.
+    ///
+    /// Each domain can only be set or removed once per transaction, and system-controlled `delta.*`
+    /// domains cannot be modified. Like [`with_transaction_id`], this is not checked until
+    /// `commit`.
+    ///
+    /// [`with_transaction_id`]: Self::with_transaction_id
+    pub fn with_domain_metadata(mut self, domain: String, configuration: String) -> Self {
+        self.domain_metadatas
+            .push(DomainMetadata::new(domain, configuration, false));
+        self
+    }
+
+    /// Remove a metadata `domain` in this transaction. Removing a domain that does not exist is a
+    /// no-op. The same rules apply as for [`with_domain_metadata`].
+    ///
+    /// [`with_domain_metadata`]: Self::with_domain_metadata
+    pub fn with_domain_metadata_removed(mut self, domain: String) -> Self {
+        self.domain_metadatas
+            .push(DomainMetadata::new(domain, String::new(), true));
+        self
+    }
+
     /// WARNING: This is an unstable API and will likely change in the future.
     ///
     /// Add commit info to the transaction. This is commit-wide metadata that is written as the
@@ -218,6 +661,35 @@ impl Transaction {
         Expression::struct_from(fields)
     }
 
+    // The schema of the data files written for the table (the result of the logical-to-physical
+    // transform): the non-partition columns, with their column mapping physical names.
+    fn physical_schema(&self) -> SchemaRef {
+        let partition_columns = &self.read_snapshot.metadata().partition_columns;
+        let schema = self.read_snapshot.schema();
+        let fields = schema
+            .fields()
+            .filter(|f| !partition_columns.contains(f.name()));
+        let fields: Vec<_> = match self.read_snapshot.column_mapping_mode() {
+            ColumnMappingMode::None => fields.cloned().collect(),
+            ColumnMappingMode::Name | ColumnMappingMode::Id => {
+                fields.map(StructField::make_physical).collect()
+            }
+        };
+        Arc::new(StructType::new(fields))
+    }
+
+    // The table's partition columns (in partition column order), with their logical types.
+    fn partition_schema(&self) -> SchemaRef {
+        let schema = self.read_snapshot.schema();
+        let partition_fields = self
+            .read_snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .filter_map(|name| schema.field(name).cloned());
+        Arc::new(StructType::new(partition_fields))
+    }
+
     /// Get the write context for this transaction. At the moment, this is constant for the whole
     /// transaction.
     // Note: after we introduce metadata updates (modify table schema, etc.), we need to make sure
@@ -226,8 +698,17 @@ impl Transaction {
     pub fn get_write_context(&self) -> WriteContext {
         let target_dir = self.read_snapshot.table_root();
         let snapshot_schema = self.read_snapshot.schema();
+        let partition_schema = self.partition_schema();
+        let physical_schema = self.physical_schema();
         let logical_to_physical = self.generate_logical_to_physical();
-        WriteContext::new(target_dir.clone(), snapshot_schema, logical_to_physical)
+        WriteContext::new(
+            target_dir.clone(),
+            snapshot_schema,
+            partition_schema,
+            physical_schema,
+            logical_to_physical,
+            self.constraints.clone(),
+        )
     }
 
     /// Add files to include in this transaction. This API generally enables the engine to
@@ -238,6 +719,517 @@ impl Transaction {
     pub fn add_files(&mut self, add_metadata: Box<dyn EngineData>) {
         self.add_files_metadata.push(add_metadata);
     }
+
+    /// Stage the removal of all files in the read snapshot which may contain rows matching
+    /// `predicate`, as a building block for e.g. `DELETE`. The files are found with a scan of the
+    /// read snapshot, and fall into two groups (see [`MatchingFiles`]):
+    ///
+    /// - If `predicate` only references partition columns and evaluates to true on a file's
+    ///   partition values, every row of the file matches the predicate. These files are removed
+    ///   on commit.
+    /// - Otherwise, e.g. if the predicate evaluates to null on a file's (null) partition values,
+    ///   files are matched based on their statistics and are only _candidates_: they may contain
+    ///   rows which do not match the predicate. The engine must read each candidate,
+    ///   write any non-matching rows to new files (staged with [`add_files`]), and then call
+    ///   [`confirm_exact`]. Committing without confirming the candidates fails.
+    ///
+    /// Removing files from an append-only table (`delta.appendOnly = true`) is an error, unless the
+    /// removes don't change data (see [`with_data_change`]).
+    ///
+    /// [`add_files`]: Self::add_files
+    /// [`confirm_exact`]: Self::confirm_exact
+    /// [`with_data_change`]: Self::with_data_change
+    pub fn remove_files_matching(
+        &mut self,
+        engine: &dyn Engine,
+        predicate: PredicateRef,
+    ) -> DeltaResult<MatchingFiles> {
+        if self.data_change
+            && self
+                .read_snapshot
+                .table_configuration()
+                .is_append_only_enabled()
+        {
+            return Err(Error::generic(
+                "Cannot remove files from an append-only table (delta.appendOnly = true)",
+            ));
+        }
+        let schema = self.read_snapshot.schema();
+        let partition_fields: Vec<_> = self
+            .read_snapshot
+            .metadata()
+            .partition_columns
+            .iter()
+            .map(|column| {
+                schema
+                    .field(column)
+                    .ok_or_else(|| Error::missing_column(column))
+            })
+            .collect::<DeltaResult<_>>()?;
+        let partition_only = predicate.references().into_iter().all(|column| {
+            let path = column.path();
+            path.len() == 1
+                && partition_fields
+                    .iter()
+                    .any(|field| field.name() == &path[0])
+        });
+        // Only a predicate which is known to be true on the partition values matches every row of
+        // a file. If it evaluates to null (e.g. for a null partition value), it matches none.
+        let is_exact = |file: &RemoveFile| -> DeltaResult<bool> {
+            if !partition_only {
+                return Ok(false);
+            }
+            let partition_values: HashMap<_, _> = partition_fields
+                .iter()
+                .map(|field| {
+                    let value = file.partition_values.get(field.physical_name());
+                    let value = parse_partition_value(value, field.data_type())?;
+                    Ok((ColumnName::new([field.name()]), value))
+                })
+                .collect::<DeltaResult<_>>()?;
+            let evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
+            Ok(evaluator.eval_pred(&predicate, false) == Some(true))
+        };
+
+        let scan = self
+            .read_snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(predicate.clone())
+            .build()?;
+        let mut matching = MatchingFiles::default();
+        for scan_metadata in scan.scan_metadata(engine)? {
+            for add in adds_from_scan_metadata(&scan, &scan_metadata?)? {
+                let file = RemoveFile {
+                    data_change: self.data_change,
+                    ..RemoveFile::from(add)
+                };
+                if is_exact(&file)? {
+                    matching.exact.push(file.path.clone());
+                    self.remove_files.push(file);
+                } else {
+                    matching.candidates.push(file.path.clone());
+                    self.unconfirmed_remove_files.push(file);
+                }
+            }
+        }
+        Ok(matching)
+    }
+
+    /// Confirm that every candidate file staged by [`remove_files_matching`] may be removed: the
+    /// engine has verified that all of its rows match the predicate, or has staged the rows which
+    /// do not match in new files with [`add_files`].
+    ///
+    /// [`remove_files_matching`]: Self::remove_files_matching
+    /// [`add_files`]: Self::add_files
+    pub fn confirm_exact(&mut self) {
+        self.remove_files.append(&mut self.unconfirmed_remove_files);
+    }
+
+    /// Stage the deletion of rows from data files of the read snapshot using deletion vectors, as a
+    /// building block for e.g. `DELETE`, `UPDATE` and `MERGE` without rewriting files.
+    /// `deleted_rows` maps the path of each file, as given by its `add` action (e.g. the path of a
+    /// scan file), to the indexes of the rows to delete. These are merged with the file's existing
+    /// deletion vector, if any. Engines implementing `UPDATE` must also write the updated rows to
+    /// new files, staged with [`add_files`].
+    ///
+    /// On commit, each file is removed and added back with its new deletion vector. The new
+    /// deletion vectors are returned as a [`DeletionVectorFile`] which the engine must write
+    /// before committing, or `None` if every row to delete was already deleted.
+    ///
+    /// This requires deletion vectors to be enabled on the table (`delta.enableDeletionVectors =
+    /// true`), and fails if a file is not in the read snapshot or was already staged for removal.
+    /// The files of the read snapshot are only scanned by the first call in a transaction.
+    ///
+    /// [`add_files`]: Self::add_files
+    pub fn delete_rows(
+        &mut self,
+        engine: &dyn Engine,
+        deleted_rows: HashMap<String, RoaringTreemap>,
+    ) -> DeltaResult<Option<DeletionVectorFile>> {
+        let table_configuration = self.read_snapshot.table_configuration();
+        if table_configuration.is_append_only_enabled() {
+            return Err(Error::generic(
+                "Cannot delete rows from an append-only table (delta.appendOnly = true)",
+            ));
+        }
+        if !table_configuration.is_deletion_vector_enabled() {
+            return Err(Error::generic(
+                "Cannot delete rows from a table without deletion vectors enabled \
+                (delta.enableDeletionVectors = true)",
+            ));
+        }
+        let mut staged = self
+            .remove_files
+            .iter()
+            .chain(&self.unconfirmed_remove_files);
+        if let Some(file) = staged.find(|file| deleted_rows.contains_key(&file.path)) {
+            return Err(Error::generic(format!(
+                "File {} is already staged for removal in this transaction",
+                file.path
+            )));
+        }
+
+        // match the files by location, as a path may be relative to the table root or absolute
+        let table_root = self.read_snapshot.table_root();
+        let deleted_rows: HashMap<Url, (String, RoaringTreemap)> = deleted_rows
+            .into_iter()
+            .map(|(path, rows)| Ok((table_root.join(&path)?, (path, rows))))
+            .collect::<DeltaResult<_>>()?;
+        let live_files = match &mut self.live_files {
+            Some(live_files) => live_files,
+            None => self
+                .live_files
+                .insert(scan_live_files(&self.read_snapshot, engine)?),
+        };
+        let mut writer = DeletionVectorWriter::try_new(table_root, self.id_generator.next_id())?;
+        let mut updates = vec![];
+        for (location, (path, mut rows)) in deleted_rows {
+            let Some(add) = live_files.get(&location) else {
+                return Err(Error::generic(format!(
+                    "Cannot delete rows from file {path}, which is not in the table"
+                )));
+            };
+            let mut deleted_before = 0;
+            if let Some(dv) = &add.deletion_vector {
+                rows |= dv.read(engine.storage_handler(), table_root)?;
+                deleted_before = dv.cardinality;
+            }
+            if rows.len() as i64 == deleted_before {
+                continue;
+            }
+            updates.push(DeletionVectorUpdate {
+                file: RemoveFile {
+                    path,
+                    ..RemoveFile::from(add.clone())
+                },
+                modification_time: add.modification_time,
+                stats: add.stats.clone(),
+                deletion_vector: writer.write(&rows)?,
+            });
+        }
+
+        if updates.is_empty() {
+            return Ok(None);
+        }
+        self.remove_files
+            .extend(updates.iter().map(|update| update.file.clone()));
+        self.deletion_vector_updates.extend(updates);
+        Ok(Some(writer.finish()))
+    }
+}
+
+// The live files of `snapshot` by location, for `Transaction::delete_rows`
+fn scan_live_files(
+    snapshot: &Arc<Snapshot>,
+    engine: &dyn Engine,
+) -> DeltaResult<HashMap<Url, Add>> {
+    let scan = snapshot.clone().scan_builder().build()?;
+    let mut live_files = HashMap::new();
+    for scan_metadata in scan.scan_metadata(engine)? {
+        for add in adds_from_scan_metadata(&scan, &scan_metadata?)? {
+            live_files.insert(snapshot.table_root().join(&add.path)?, add);
+        }
+    }
+    Ok(live_files)
+}
+
+/// The files staged for removal by [`Transaction::remove_files_matching`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchingFiles {
+    /// Paths of files in which every row matches the predicate.
+    pub exact: Vec<String>,
+    /// Paths of files which may contain rows matching the predicate, based on file statistics.
+    /// These must be confirmed with [`Transaction::confirm_exact`] before committing.
+    pub candidates: Vec<String>,
+}
+
+// A file to remove from the table, as found by a scan of the read snapshot
+#[derive(Debug, Clone)]
+struct RemoveFile {
+    path: String,
+    size: i64,
+    partition_values: HashMap<String, String>,
+    deletion_vector: Option<DeletionVectorDescriptor>,
+    // the row tracking fields, if known. A file keeps them when it is re-added.
+    base_row_id: Option<i64>,
+    default_row_commit_version: Option<i64>,
+    // copied from the file's add action
+    stats: Option<String>,
+    tags: Option<HashMap<String, String>>,
+    data_change: bool,
+}
+
+impl From<Add> for RemoveFile {
+    fn from(add: Add) -> Self {
+        // a null partition value is the same as a missing one
+        let partition_values = add
+            .partition_values
+            .into_iter()
+            .filter_map(|(column, value)| Some((column, value?)))
+            .collect();
+        // tag values are never null
+        let tags = add.tags.map(|tags| {
+            tags.into_iter()
+                .filter_map(|(key, value)| Some((key, value?)))
+                .collect()
+        });
+        Self {
+            path: add.path,
+            size: add.size,
+            partition_values,
+            deletion_vector: add.deletion_vector,
+            base_row_id: add.base_row_id,
+            default_row_commit_version: add.default_row_commit_version,
+            stats: add.stats,
+            tags,
+            data_change: true,
+        }
+    }
+}
+
+impl RemoveFile {
+    // create a single-row remove action for this file
+    fn to_remove_action(
+        &self,
+        engine: &dyn Engine,
+        deletion_timestamp: i64,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let map_type = MapType::new(DataType::STRING, DataType::STRING, false);
+        let partition_values = MapData::try_new(map_type.clone(), self.partition_values.clone())?;
+        // extendedFileMetadata requires tags to be present
+        let tags = MapData::try_new(map_type, self.tags.clone().unwrap_or_default())?;
+        let dv = self.deletion_vector.as_ref();
+        let values = [
+            self.path.clone().into(),
+            deletion_timestamp.into(),
+            self.data_change.into(),
+            true.into(), // extendedFileMetadata
+            Scalar::Map(partition_values),
+            self.size.into(),
+            Scalar::Map(tags),
+            dv.map_or(Scalar::Null(DataType::STRING), |dv| {
+                dv.storage_type.clone().into()
+            }),
+            dv.map_or(Scalar::Null(DataType::STRING), |dv| {
+                dv.path_or_inline_dv.clone().into()
+            }),
+            dv.and_then(|dv| dv.offset)
+                .map_or(Scalar::Null(DataType::INTEGER), Into::into),
+            dv.map_or(Scalar::Null(DataType::INTEGER), |dv| {
+                dv.size_in_bytes.into()
+            }),
+            dv.map_or(Scalar::Null(DataType::LONG), |dv| dv.cardinality.into()),
+            self.base_row_id
+                .map_or(Scalar::Null(DataType::LONG), Into::into),
+            self.default_row_commit_version
+                .map_or(Scalar::Null(DataType::LONG), Into::into),
+            self.stats
+                .clone()
+                .map_or(Scalar::Null(DataType::STRING), Into::into),
+        ];
+        engine
+            .evaluation_handler()
+            .create_one(LOG_REMOVE_SCHEMA.clone(), &values)
+    }
+}
+
+// A file re-added with a new deletion vector by `Transaction::delete_rows`
+#[derive(Debug)]
+struct DeletionVectorUpdate {
+    // the file as it is removed, with its old deletion vector
+    file: RemoveFile,
+    modification_time: i64,
+    stats: Option<String>,
+    deletion_vector: DeletionVectorDescriptor,
+}
+
+impl DeletionVectorUpdate {
+    // create a single-row add action for the file with its new deletion vector, and the given row
+    // tracking fields. The partition columns are given by their physical names, which key the
+    // partition values.
+    fn to_add_action(
+        &self,
+        engine: &dyn Engine,
+        physical_partition_columns: &[String],
+        base_row_id: Option<i64>,
+        default_row_commit_version: Option<i64>,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        // every partition column must have a value, null values are left out of scan files
+        let partition_values = physical_partition_columns.iter().map(|column| {
+            let value = self.file.partition_values.get(column).cloned();
+            (
+                column.clone(),
+                value.map_or(Scalar::Null(DataType::STRING), Into::into),
+            )
+        });
+        let map_type = MapType::new(DataType::STRING, DataType::STRING, true);
+        let partition_values = MapData::try_new(map_type, partition_values)?;
+        let map_type = MapType::new(DataType::STRING, DataType::STRING, false);
+        let tags = MapData::try_new(map_type, self.file.tags.clone().unwrap_or_default())?;
+        let dv = &self.deletion_vector;
+        let values = [
+            self.file.path.clone().into(),
+            Scalar::Map(partition_values),
+            self.file.size.into(),
+            self.modification_time.into(),
+            true.into(), // dataChange
+            self.stats
+                .as_deref()
+                .and_then(loosen_stats)
+                .map_or(Scalar::Null(DataType::STRING), Into::into),
+            Scalar::Map(tags),
+            dv.storage_type.clone().into(),
+            dv.path_or_inline_dv.clone().into(),
+            dv.offset
+                .map_or(Scalar::Null(DataType::INTEGER), Into::into),
+            dv.size_in_bytes.into(),
+            dv.cardinality.into(),
+            base_row_id.map_or(Scalar::Null(DataType::LONG), Into::into),
+            default_row_commit_version.map_or(Scalar::Null(DataType::LONG), Into::into),
+            Scalar::Null(DataType::STRING), // clusteringProvider
+        ];
+        engine
+            .evaluation_handler()
+            .create_one(get_log_add_schema().clone(), &values)
+    }
+}
+
+// Deleting rows keeps the min/max statistics of a file valid, but they may no longer be tight (the
+// bounds may only be reached by deleted rows). Unparseable statistics are dropped.
+fn loosen_stats(stats: &str) -> Option<String> {
+    let mut stats: serde_json::Map<String, serde_json::Value> = serde_json::from_str(stats).ok()?;
+    stats.insert("tightBounds".to_string(), false.into());
+    Some(serde_json::Value::Object(stats).to_string())
+}
+
+static LOG_REMOVE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        REMOVE_NAME,
+        Remove::to_schema(),
+    )]))
+});
+
+// Counts the files in add_files metadata (see `add_files_schema`) and sums up their sizes
+#[derive(Debug, Default)]
+struct AddedFilesVisitor {
+    num_files: i64,
+    size_bytes: i64,
+}
+
+impl RowVisitor for AddedFilesVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("size")], vec![DataType::LONG]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of AddedFilesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let size: i64 = getters[0].get(i, "size")?;
+            self.num_files += 1;
+            self.size_bytes += size;
+        }
+        Ok(())
+    }
+}
+
+// A file in add_files metadata (see `add_files_schema`)
+#[derive(Debug)]
+struct AddFile {
+    path: String,
+    partition_values: HashMap<String, String>,
+    size: i64,
+    modification_time: i64,
+    data_change: bool,
+    stats: Option<String>,
+}
+
+impl AddFile {
+    // create a single-row add action for this file with the given row tracking fields. The
+    // partition columns are given by their physical names, which key the partition values.
+    fn to_add_action(
+        &self,
+        engine: &dyn Engine,
+        physical_partition_columns: &[String],
+        base_row_id: i64,
+        default_row_commit_version: i64,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        // like `generate_adds`, partition columns with a null value are present in the add action
+        let partition_values = physical_partition_columns.iter().map(|column| {
+            let value = self.partition_values.get(column).cloned();
+            (
+                column.clone(),
+                value.map_or(Scalar::Null(DataType::STRING), Into::into),
+            )
+        });
+        let map_type = MapType::new(DataType::STRING, DataType::STRING, true);
+        let partition_values = MapData::try_new(map_type, partition_values)?;
+        let values = [
+            self.path.clone().into(),
+            Scalar::Map(partition_values),
+            self.size.into(),
+            self.modification_time.into(),
+            self.data_change.into(),
+            self.stats
+                .clone()
+                .map_or(Scalar::Null(DataType::STRING), Into::into),
+            Scalar::Null(MapType::new(DataType::STRING, DataType::STRING, false).into()), // tags
+            Scalar::Null(DataType::STRING), // deletionVector.storageType
+            Scalar::Null(DataType::STRING), // deletionVector.pathOrInlineDv
+            Scalar::Null(DataType::INTEGER), // deletionVector.offset
+            Scalar::Null(DataType::INTEGER), // deletionVector.sizeInBytes
+            Scalar::Null(DataType::LONG),   // deletionVector.cardinality
+            base_row_id.into(),
+            default_row_commit_version.into(),
+            Scalar::Null(DataType::STRING), // clusteringProvider
+        ];
+        engine
+            .evaluation_handler()
+            .create_one(get_log_add_schema().clone(), &values)
+    }
+}
+
+// Collects the files in add_files metadata (see `add_files_schema`)
+#[derive(Debug, Default)]
+struct AddFilesVisitor {
+    files: Vec<AddFile>,
+}
+
+impl RowVisitor for AddFilesVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| add_files_schema().leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 6,
+            Error::InternalError(format!(
+                "Wrong number of AddFilesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.files.push(AddFile {
+                path: getters[0].get(i, "path")?,
+                partition_values: getters[1].get(i, "partitionValues")?,
+                size: getters[2].get(i, "size")?,
+                modification_time: getters[3].get(i, "modificationTime")?,
+                data_change: getters[4].get(i, "dataChange")?,
+                stats: getters[5].get_opt(i, "stats")?,
+            });
+        }
+        Ok(())
+    }
 }
 
 // convert add_files_metadata into add actions using an expression to transform the data in a single
@@ -272,15 +1264,28 @@ fn generate_adds<'a>(
 pub struct WriteContext {
     target_dir: Url,
     schema: SchemaRef,
+    partition_schema: SchemaRef,
+    physical_schema: SchemaRef,
     logical_to_physical: Expression,
+    constraints: Arc<Vec<WriteConstraint>>,
 }
 
 impl WriteContext {
-    fn new(target_dir: Url, schema: SchemaRef, logical_to_physical: Expression) -> Self {
+    fn new(
+        target_dir: Url,
+        schema: SchemaRef,
+        partition_schema: SchemaRef,
+        physical_schema: SchemaRef,
+        logical_to_physical: Expression,
+        constraints: Arc<Vec<WriteConstraint>>,
+    ) -> Self {
         WriteContext {
             target_dir,
             schema,
+            partition_schema,
+            physical_schema,
             logical_to_physical,
+            constraints,
         }
     }
 
@@ -292,9 +1297,126 @@ impl WriteContext {
         &self.schema
     }
 
+    /// The partition columns of the table, in partition column order. Empty if the table is not
+    /// partitioned.
+    pub fn partition_schema(&self) -> &SchemaRef {
+        &self.partition_schema
+    }
+
+    /// The schema of the data to write to data files, i.e. the output schema of
+    /// [`Self::logical_to_physical`]. It has the table's non-partition columns, with their
+    /// physical names if the table uses column mapping.
+    pub fn physical_schema(&self) -> &SchemaRef {
+        &self.physical_schema
+    }
+
     pub fn logical_to_physical(&self) -> &Expression {
         &self.logical_to_physical
     }
+
+    /// Check that every row of `data` satisfies the table's check constraints and that its
+    /// generated columns hold the value of their generation expressions, returning an
+    /// [`Error::ConstraintViolation`] otherwise. Engines must call this on every batch of (logical)
+    /// data before writing it; the default engine's writes do so.
+    ///
+    /// `partition_values` gives the values of the partition columns for data which does not
+    /// include them, e.g. the data of a single partition (see [`Self::partition_dir`]).
+    pub fn check_constraints(
+        &self,
+        engine: &dyn Engine,
+        data: &dyn EngineData,
+        partition_values: &HashMap<String, Scalar>,
+    ) -> DeltaResult<()> {
+        check_constraints(
+            engine,
+            &self.constraints,
+            &self.schema,
+            data,
+            partition_values,
+        )
+    }
+
+    /// Validate `partition_values` against the table's [partition schema] and serialize them as
+    /// they appear in the `partitionValues` of an `add` action. There must be exactly one value per
+    /// partition column, of the column's type. Null values, and empty strings (following Spark),
+    /// serialize to `None`. The serialized values are keyed by the physical names of the partition
+    /// columns, which differ from their names if the table uses column mapping.
+    ///
+    /// [partition schema]: Self::partition_schema
+    pub fn serialize_partition_values(
+        &self,
+        partition_values: &HashMap<String, Scalar>,
+    ) -> DeltaResult<HashMap<String, Option<String>>> {
+        if let Some(name) = partition_values
+            .keys()
+            .find(|name| !self.partition_schema.contains(name.as_str()))
+        {
+            return Err(Error::generic(format!(
+                "Partition value given for non-partition column '{name}'"
+            )));
+        }
+        self.partition_schema
+            .fields()
+            .map(|field| {
+                let value = partition_values.get(field.name()).ok_or_else(|| {
+                    Error::generic(format!(
+                        "Missing value for partition column '{}'",
+                        field.name()
+                    ))
+                })?;
+                if value.data_type() != *field.data_type() {
+                    return Err(Error::generic(format!(
+                        "Partition column '{}' has type {}, but the value has type {}",
+                        field.name(),
+                        field.data_type(),
+                        value.data_type()
+                    )));
+                }
+                let value = value.serialize_partition_value()?.filter(|v| !v.is_empty());
+                if value.is_none() && !field.is_nullable() {
+                    return Err(Error::generic(format!(
+                        "Null value for non-nullable partition column '{}'",
+                        field.name()
+                    )));
+                }
+                Ok((field.physical_name().to_string(), value))
+            })
+            .collect()
+    }
+
+    /// The directory to write the data files of the partition with the given serialized
+    /// `partition_values` (see [`Self::serialize_partition_values`]) to: one `col=value/`
+    /// subdirectory of [`Self::target_dir`] per partition column (by its physical name), with
+    /// Hive-style escaping of special characters and null values. Returns the target dir itself if
+    /// the table is not partitioned.
+    pub fn partition_dir(
+        &self,
+        partition_values: &HashMap<String, Option<String>>,
+    ) -> DeltaResult<Url> {
+        let mut dir = self.target_dir.clone();
+        {
+            let mut segments = dir
+                .path_segments_mut()
+                .map_err(|_| Error::generic(format!("Invalid target dir: {}", self.target_dir)))?;
+            // drop the empty segment after the target dir's trailing slash
+            segments.pop_if_empty();
+            for field in self.partition_schema.fields() {
+                let value = partition_values.get(field.physical_name()).ok_or_else(|| {
+                    Error::generic(format!(
+                        "Missing value for partition column '{}'",
+                        field.name()
+                    ))
+                })?;
+                segments.push(&partition::partition_dir_name(
+                    field.physical_name(),
+                    value.as_deref(),
+                ));
+            }
+            // directories end with a trailing slash
+            segments.push("");
+        }
+        Ok(dir)
+    }
 }
 
 /// Result after committing a transaction. If 'committed', the version is the new version written
@@ -307,16 +1429,34 @@ pub enum CommitResult {
     /// The transaction was successfully committed at the version.
     Committed(Version),
     /// This transaction conflicted with an existing version (at the version given).
-    Conflict(Transaction, Version),
+    Conflict(Box<Transaction>, Version),
+}
+
+// The fields of the commitInfo action which kernel fills in, as opposed to the engine's commit info
+#[derive(Debug, Default)]
+struct CommitInfoFields<'a> {
+    operation: Option<&'a str>,
+    engine_info: Option<&'a str>,
+    user_metadata: Option<&'a str>,
+    timestamp: i64,
+    in_commit_timestamp: Option<i64>,
+    txn_id: &'a str,
 }
 
 // given the engine's commit info we want to create commitInfo action to commit (and append more actions to)
 fn generate_commit_info(
     engine: &dyn Engine,
-    operation: Option<&str>,
-    timestamp: i64,
+    commit_info: &CommitInfoFields<'_>,
     engine_commit_info: &dyn EngineData,
 ) -> DeltaResult<Box<dyn EngineData>> {
+    let CommitInfoFields {
+        operation,
+        engine_info,
+        user_metadata,
+        timestamp,
+        in_commit_timestamp,
+        txn_id,
+    } = *commit_info;
     if engine_commit_info.len() != 1 {
         return Err(Error::InvalidCommitInfo(format!(
             "Engine commit info should have exactly one row, found {}",
@@ -326,6 +1466,7 @@ fn generate_commit_info(
 
     let commit_info_exprs = [
         Expression::literal(timestamp),
+        Expression::literal(in_commit_timestamp.map_or(Scalar::Null(DataType::LONG), Scalar::from)),
         Expression::literal(operation.unwrap_or(UNKNOWN_OPERATION)),
         // HACK (part 1/2): since we don't have proper map support, we create a literal struct with
         // one null field to create data that serializes as "operationParameters": {}
@@ -338,6 +1479,9 @@ fn generate_commit_info(
         )?)),
         Expression::literal(format!("v{KERNEL_VERSION}")),
         column_expr!("engineCommitInfo"),
+        optional_string_literal(user_metadata),
+        optional_string_literal(engine_info),
+        Expression::literal(txn_id),
     ];
     let commit_info_expr = Expression::struct_from([Expression::struct_from(commit_info_exprs)]);
     let commit_info_schema = get_log_commit_info_schema().as_ref();
@@ -367,11 +1511,10 @@ fn generate_commit_info(
         .ok_or_else(|| Error::missing_column("operationParameters"))?
         .data_type = hack_data_type;
 
-    // Since writing in-commit timestamps is not supported, we remove the field so it is not
-    // written to the log
+    // Kernel doesn't collect operation metrics, so we don't write the field either
     commit_info_data_type
         .fields
-        .shift_remove("inCommitTimestamp");
+        .shift_remove("operationMetrics");
     commit_info_field.data_type = DataType::Struct(commit_info_data_type);
 
     let commit_info_evaluator = engine.evaluation_handler().new_expression_evaluator(
@@ -383,6 +1526,30 @@ fn generate_commit_info(
     commit_info_evaluator.evaluate(engine_commit_info)
 }
 
+// The `txnId` of the commit info of the commit file at `location`, if the file exists and has one
+fn read_commit_txn_id(engine: &dyn Engine, location: &Url) -> DeltaResult<Option<String>> {
+    // the size and modification time are not needed to read the file
+    let file = FileMeta::new(location.clone(), 0, 0);
+    let batches = engine.json_handler().read_json_files(
+        &[file],
+        get_log_commit_info_schema().clone(),
+        None,
+    )?;
+    let mut visitor = CommitInfoVisitor::default();
+    for batch in batches {
+        visitor.visit_commit_infos(engine, batch?.as_ref())?;
+    }
+    Ok(visitor
+        .commit_infos
+        .into_iter()
+        .find_map(|commit_info| commit_info.txn_id))
+}
+
+// A string literal, or a null string literal if `value` is absent (which is not written)
+fn optional_string_literal(value: Option<&str>) -> Expression {
+    Expression::literal(value.map_or(Scalar::Null(DataType::STRING), Scalar::from))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,6 +1565,8 @@ mod tests {
     use crate::arrow::json::writer::LineDelimitedWriter;
     use crate::arrow::record_batch::RecordBatch;
 
+    const TEST_TXN_ID: &str = "00000000-0000-0001-0000-000000000000";
+
     struct ExprEngine(Arc<dyn EvaluationHandler>);
 
     impl ExprEngine {
@@ -486,8 +1655,12 @@ mod tests {
 
         let actions = generate_commit_info(
             &engine,
-            Some("test operation"),
-            123456789,
+            &CommitInfoFields {
+                operation: Some("test operation"),
+                timestamp: 123456789,
+                txn_id: TEST_TXN_ID,
+                ..Default::default()
+            },
             &ArrowEngineData::new(commit_info_batch),
         )?;
 
@@ -497,6 +1670,7 @@ mod tests {
                 "operation": "test operation",
                 "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                 "operationParameters": {},
+                "txnId": TEST_TXN_ID,
                 "engineCommitInfo": {
                     "engineInfo": "default engine"
                 }
@@ -547,17 +1721,24 @@ mod tests {
 
         let actions = generate_commit_info(
             &engine,
-            Some("test operation"),
-            123456789,
+            &CommitInfoFields {
+                operation: Some("test operation"),
+                timestamp: 123456789,
+                in_commit_timestamp: Some(123456790),
+                txn_id: TEST_TXN_ID,
+                ..Default::default()
+            },
             &ArrowEngineData::new(commit_info_batch),
         )?;
 
         let expected = serde_json::json!({
             "commitInfo": {
                 "timestamp": 123456789,
+                "inCommitTimestamp": 123456790,
                 "operation": "test operation",
                 "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                 "operationParameters": {},
+                "txnId": TEST_TXN_ID,
                 "engineCommitInfo": {
                     "engineInfo": "default engine"
                 }
@@ -586,8 +1767,12 @@ mod tests {
 
         let _ = generate_commit_info(
             &engine,
-            Some("test operation"),
-            123456789,
+            &CommitInfoFields {
+                operation: Some("test operation"),
+                timestamp: 123456789,
+                txn_id: TEST_TXN_ID,
+                ..Default::default()
+            },
             &ArrowEngineData::new(commit_info_batch),
         )
         .map_err(|e| match e {
@@ -615,8 +1800,12 @@ mod tests {
 
         let _ = generate_commit_info(
             &engine,
-            Some("test operation"),
-            123456789,
+            &CommitInfoFields {
+                operation: Some("test operation"),
+                timestamp: 123456789,
+                txn_id: TEST_TXN_ID,
+                ..Default::default()
+            },
             &ArrowEngineData::new(commit_info_batch),
         )
         .map_err(|e| match e {
@@ -642,6 +1831,7 @@ mod tests {
                     "operation": "test operation",
                     "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                     "operationParameters": {},
+                    "txnId": TEST_TXN_ID,
                     "engineCommitInfo": {}
                 }
             })
@@ -652,6 +1842,7 @@ mod tests {
                     "operation": "test operation",
                     "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                     "operationParameters": {},
+                    "txnId": TEST_TXN_ID,
                 }
             })
         };
@@ -708,8 +1899,12 @@ mod tests {
             let timestamp = 123456;
             let actions = generate_commit_info(
                 &engine,
-                Some("test operation"),
-                timestamp,
+                &CommitInfoFields {
+                    operation: Some("test operation"),
+                    timestamp,
+                    txn_id: TEST_TXN_ID,
+                    ..Default::default()
+                },
                 &ArrowEngineData::new(commit_info_batch),
             )?;
 
@@ -730,7 +1925,164 @@ mod tests {
             StructField::not_null("size", DataType::LONG),
             StructField::not_null("modificationTime", DataType::LONG),
             StructField::not_null("dataChange", DataType::BOOLEAN),
+            StructField::nullable("stats", DataType::STRING),
         ]);
         assert_eq!(*schema, expected.into());
     }
+
+    #[tokio::test]
+    async fn test_commit_remove_to_append_only_table() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::actions::{Add, Metadata, Protocol};
+        use crate::engine::sync::SyncEngine;
+        use crate::utils::test_utils::{Action, LocalMockTable};
+        use crate::Predicate;
+
+        let mut mock_table = LocalMockTable::new();
+        let schema = StructType::new(vec![StructField::nullable("number", DataType::INTEGER)]);
+        mock_table
+            .commit([
+                Action::Protocol(Protocol::try_new(
+                    1,
+                    2,
+                    None::<Vec<String>>,
+                    None::<Vec<String>>,
+                )?),
+                Action::Metadata(Metadata {
+                    schema_string: serde_json::to_string(&schema)?,
+                    configuration: HashMap::from([(
+                        "delta.appendOnly".to_string(),
+                        "true".to_string(),
+                    )]),
+                    ..Default::default()
+                }),
+                Action::Add(Add {
+                    path: "file.parquet".to_string(),
+                    size: 1,
+                    data_change: true,
+                    ..Default::default()
+                }),
+            ])
+            .await;
+        let engine = SyncEngine::new();
+        let table_root = Url::from_directory_path(mock_table.table_root()).unwrap();
+        let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None)?);
+
+        // the public APIs refuse to stage removes which change data, but even if such a remove is
+        // staged, the commit must reject it
+        let mut txn = Transaction::try_new(snapshot.clone())?;
+        assert!(txn
+            .remove_files_matching(&engine, Arc::new(Predicate::literal(true)))
+            .is_err());
+        txn.remove_files.push(RemoveFile {
+            path: "file.parquet".to_string(),
+            size: 1,
+            partition_values: HashMap::new(),
+            deletion_vector: None,
+            base_row_id: None,
+            default_row_commit_version: None,
+            stats: None,
+            tags: None,
+            data_change: true,
+        });
+        let err = txn.commit(&engine).unwrap_err();
+        assert!(err.to_string().contains("append-only"), "{err}");
+
+        // removes which don't change data, e.g. of a compaction, are allowed
+        let commit_info_schema = Arc::new(StructType::new(vec![StructField::nullable(
+            "engineCommitInfo",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        )]));
+        let commit_info = engine.evaluation_handler().null_row(commit_info_schema)?;
+        let mut txn = Transaction::try_new(snapshot)?
+            .with_commit_info(commit_info)
+            .with_data_change(false);
+        let matching = txn.remove_files_matching(&engine, Arc::new(Predicate::literal(true)))?;
+        assert_eq!(matching.exact, ["file.parquet"]);
+        assert!(matches!(txn.commit(&engine)?, CommitResult::Committed(1)));
+        let commit_path = mock_table
+            .table_root()
+            .join("_delta_log/00000000000000000001.json");
+        let commit = std::fs::read_to_string(commit_path)?;
+        let remove = commit
+            .lines()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .find_map(|action| action.ok()?.get("remove").cloned())
+            .unwrap();
+        assert_eq!(remove["dataChange"], false);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_values_and_dir() {
+        let partition_schema = Arc::new(StructType::new(vec![
+            StructField::nullable("p1", DataType::STRING),
+            StructField::not_null("p2", DataType::INTEGER),
+        ]));
+        let write_context = WriteContext::new(
+            Url::parse("memory:///table/").unwrap(),
+            Arc::new(StructType::new(vec![])),
+            partition_schema,
+            Arc::new(StructType::new(vec![])),
+            Expression::literal(1),
+            Arc::new(vec![]),
+        );
+
+        let values = HashMap::from([
+            ("p1".to_string(), Scalar::from("a/b")),
+            ("p2".to_string(), Scalar::from(7)),
+        ]);
+        let serialized = write_context.serialize_partition_values(&values).unwrap();
+        assert_eq!(
+            serialized,
+            HashMap::from([
+                ("p1".to_string(), Some("a/b".to_string())),
+                ("p2".to_string(), Some("7".to_string())),
+            ])
+        );
+        let dir = write_context.partition_dir(&serialized).unwrap();
+        assert_eq!(dir.as_str(), "memory:///table/p1=a%252Fb/p2=7/");
+        assert_eq!(dir.path_segments().unwrap().nth(1), Some("p1=a%252Fb"));
+
+        // nulls and empty strings are written to the default partition
+        for p1 in [Scalar::Null(DataType::STRING), Scalar::from("")] {
+            let values = HashMap::from([("p1".to_string(), p1), ("p2".to_string(), 7.into())]);
+            let serialized = write_context.serialize_partition_values(&values).unwrap();
+            assert_eq!(serialized["p1"], None);
+            let dir = write_context.partition_dir(&serialized).unwrap();
+            assert_eq!(
+                dir.as_str(),
+                "memory:///table/p1=__HIVE_DEFAULT_PARTITION__/p2=7/"
+            );
+        }
+
+        let invalid = [
+            // missing, extra, wrong type, null in a non-nullable column
+            vec![("p1", Scalar::from("a"))],
+            vec![("p1", "a".into()), ("p2", 7.into()), ("p3", 1.into())],
+            vec![("p1", "a".into()), ("p2", 7i64.into())],
+            vec![("p1", "a".into()), ("p2", Scalar::Null(DataType::INTEGER))],
+        ];
+        for values in invalid {
+            let values = values
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect();
+            assert!(write_context.serialize_partition_values(&values).is_err());
+        }
+
+        // unpartitioned tables write to the target dir
+        let write_context = WriteContext::new(
+            Url::parse("memory:///table/").unwrap(),
+            Arc::new(StructType::new(vec![])),
+            Arc::new(StructType::new(vec![])),
+            Arc::new(StructType::new(vec![])),
+            Expression::literal(1),
+            Arc::new(vec![]),
+        );
+        let serialized = write_context
+            .serialize_partition_values(&HashMap::new())
+            .unwrap();
+        let dir = write_context.partition_dir(&serialized).unwrap();
+        assert_eq!(dir.as_str(), "memory:///table/");
+    }
 }
@@ -0,0 +1,879 @@
+//! Write-time enforcement of [check constraints] and [generated columns]. Both are defined by SQL
+//! expressions stored in the table metadata: check constraints in the `delta.constraints.<name>`
+//! table properties, and generation expressions in the `delta.generationExpression` metadata of
+//! generated columns. A writer must reject any data which violates a constraint, or whose
+//! generated columns do not hold the value of their generation expression.
+//!
+//! Kernel parses these expressions into [`Predicate`]s and evaluates them on each batch of data
+//! with the engine's [`EvaluationHandler`]. Only a subset of SQL is understood: column references,
+//! numeric, string, boolean and null literals, arithmetic (`+ - *`, and `/` of floating point
+//! values), comparisons (`= == != <> < <= > >= <=>`), `IS [NOT] NULL`, `[NOT] LIKE`, `AND`, `OR`,
+//! `NOT` and parentheses. Writes to tables with expressions outside of this subset (e.g. function
+//! calls or integer division) are not supported.
+//!
+//! [check constraints]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#check-constraints
+//! [generated columns]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#generated-columns
+//! [`EvaluationHandler`]: crate::EvaluationHandler
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::sync::Arc;
+
+use crate::actions::visitors::SelectionVectorVisitor;
+use crate::engine_data::RowVisitor as _;
+use crate::expressions::{BinaryExpressionOp, ColumnName, Expression, Predicate, Scalar};
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, PrimitiveType, StructType};
+use crate::table_configuration::TableConfiguration;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error};
+
+/// The prefix of the table properties which define check constraints.
+const CHECK_CONSTRAINT_PREFIX: &str = "delta.constraints.";
+
+/// A check constraint or generated column which every row written to a table must satisfy.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum WriteConstraint {
+    /// The check constraint `name`, whose expression must not evaluate to false.
+    Check {
+        name: String,
+        sql: String,
+        expression: SqlExpr,
+    },
+    /// The generated column `column`, whose value must equal its generation expression.
+    Generated {
+        column: String,
+        sql: String,
+        expression: SqlExpr,
+    },
+}
+
+impl Display for WriteConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Check { name, sql, .. } => write!(f, "CHECK constraint {name} ({sql})"),
+            Self::Generated { column, sql, .. } => {
+                write!(f, "generated column {column} (GENERATED ALWAYS AS ({sql}))")
+            }
+        }
+    }
+}
+
+impl WriteConstraint {
+    /// The constraints of the table with the given configuration: its check constraints (if the
+    /// table supports the `checkConstraints` feature) followed by its generated columns (if the
+    /// table supports the `generatedColumns` feature). Fails with [`Error::Unsupported`] if an
+    /// expression cannot be enforced by kernel.
+    pub(crate) fn try_from_table_configuration(
+        table_configuration: &TableConfiguration,
+    ) -> DeltaResult<Vec<Self>> {
+        let schema = table_configuration.schema();
+        let mut constraints = vec![];
+        if table_configuration.is_check_constraints_supported() {
+            let mut checks: Vec<_> = table_configuration
+                .metadata()
+                .configuration()
+                .iter()
+                .filter_map(|(key, sql)| {
+                    let name = key.strip_prefix(CHECK_CONSTRAINT_PREFIX)?;
+                    Some((name.to_string(), sql.clone()))
+                })
+                .collect();
+            checks.sort();
+            for (name, sql) in checks {
+                let expression = SqlExpr::parse(&sql)?;
+                constraints.push(Self::Check {
+                    name,
+                    sql,
+                    expression,
+                });
+            }
+        }
+        if table_configuration.is_generated_columns_supported() {
+            for field in schema.fields() {
+                let sql = match field.get_config_value(&ColumnMetadataKey::GenerationExpression) {
+                    Some(MetadataValue::String(sql)) => sql.clone(),
+                    Some(value) => {
+                        return Err(Error::invalid_table_metadata(format!(
+                            "Invalid generation expression of column {}: {value}",
+                            field.name()
+                        )))
+                    }
+                    None => continue,
+                };
+                constraints.push(Self::Generated {
+                    column: field.name().clone(),
+                    expression: SqlExpr::parse(&sql)?,
+                    sql,
+                });
+            }
+        }
+        // resolve every constraint once, so that e.g. references to missing columns surface
+        // before any data is written
+        let resolver = Resolver {
+            schema: &schema,
+            partition_values: &HashMap::new(),
+        };
+        for constraint in &constraints {
+            constraint
+                .to_predicate(&resolver)
+                .map_err(|err| Error::unsupported(format!("Cannot enforce {constraint}: {err}")))?;
+        }
+        Ok(constraints)
+    }
+
+    // The predicate which is true for the rows satisfying this constraint. It is never null:
+    // a check constraint is only violated if its expression is false (not null), and a generated
+    // column is only valid if its value is not distinct from the generation expression.
+    fn to_predicate(&self, resolver: &Resolver<'_>) -> DeltaResult<Predicate> {
+        match self {
+            Self::Check { expression, .. } => {
+                let expression = Expression::from_pred(resolver.to_predicate(expression)?);
+                Ok(Predicate::distinct(expression, Expression::literal(false)))
+            }
+            Self::Generated {
+                column, expression, ..
+            } => {
+                let column = SqlExpr::Column(vec![column.clone()]);
+                let data_type = resolver.data_type(&column);
+                let column = resolver.to_expression(&column, None)?;
+                let expression = resolver.to_expression(expression, data_type.as_ref())?;
+                Ok(Predicate::not(Predicate::distinct(column, expression)))
+            }
+        }
+    }
+}
+
+/// Check that every row of `data` satisfies all `constraints`, returning an
+/// [`Error::ConstraintViolation`] naming the first violated constraint and row otherwise.
+///
+/// `data` has the logical `schema` of the table, except that it may omit partition columns whose
+/// values are given by `partition_values` (keyed by logical column name).
+pub(crate) fn check_constraints(
+    engine: &dyn Engine,
+    constraints: &[WriteConstraint],
+    schema: &StructType,
+    data: &dyn EngineData,
+    partition_values: &HashMap<String, Scalar>,
+) -> DeltaResult<()> {
+    let resolver = Resolver {
+        schema,
+        partition_values,
+    };
+    let input_schema = Arc::new(schema.clone());
+    for constraint in constraints {
+        let predicate = constraint.to_predicate(&resolver)?;
+        let evaluator = engine
+            .evaluation_handler()
+            .new_predicate_evaluator(input_schema.clone(), predicate);
+        let satisfied = evaluator.evaluate(data)?;
+        let mut visitor = SelectionVectorVisitor::default();
+        visitor.visit_rows_of(satisfied.as_ref())?;
+        if let Some(row) = visitor.selection_vector.iter().position(|ok| !ok) {
+            return Err(Error::constraint_violation(format!(
+                "Row {row} of the data violates {constraint}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+// A parsed SQL expression, before its column references and literals are resolved against the
+// table schema
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SqlExpr {
+    Column(Vec<String>),
+    Literal(SqlLiteral),
+    Negate(Box<SqlExpr>),
+    Arithmetic(BinaryExpressionOp, Box<SqlExpr>, Box<SqlExpr>),
+    Comparison(Comparison, Box<SqlExpr>, Box<SqlExpr>),
+    IsNull(Box<SqlExpr>, bool),
+    Like(Box<SqlExpr>, Box<SqlExpr>, bool),
+    Not(Box<SqlExpr>),
+    And(Box<SqlExpr>, Box<SqlExpr>),
+    Or(Box<SqlExpr>, Box<SqlExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SqlLiteral {
+    // the literal as written, typed on resolution
+    Number(String),
+    String(String),
+    Boolean(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Comparison {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    NullSafeEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    // a backtick-quoted identifier, which is never a keyword
+    QuotedIdentifier(String),
+    Number(String),
+    String(String),
+    Symbol(&'static str),
+}
+
+fn tokenize(sql: &str) -> DeltaResult<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = sql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                Token::Identifier(take_while(&mut chars, |c| c.is_alphanumeric() || c == '_'))
+            }
+            c if c.is_ascii_digit() => Token::Number(take_number(&mut chars)),
+            '`' => Token::QuotedIdentifier(take_quoted(&mut chars, '`')?),
+            '\'' | '"' => Token::String(take_quoted(&mut chars, c)?),
+            _ => {
+                chars.next();
+                let symbol = match c {
+                    '<' if chars.next_if_eq(&'=').is_some() => match chars.next_if_eq(&'>') {
+                        Some(_) => "<=>",
+                        None => "<=",
+                    },
+                    '<' if chars.next_if_eq(&'>').is_some() => "!=",
+                    '!' if chars.next_if_eq(&'=').is_some() => "!=",
+                    '>' if chars.next_if_eq(&'=').is_some() => ">=",
+                    '=' => {
+                        // `==` is a synonym of `=`
+                        chars.next_if_eq(&'=');
+                        "="
+                    }
+                    '<' => "<",
+                    '>' => ">",
+                    '!' => "!",
+                    '+' => "+",
+                    '-' => "-",
+                    '*' => "*",
+                    '/' => "/",
+                    '(' => "(",
+                    ')' => ")",
+                    '.' => ".",
+                    _ => return Err(Error::unsupported(format!("Unsupported character '{c}'"))),
+                };
+                Token::Symbol(symbol)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars<'_>>, pred: impl Fn(char) -> bool) -> String {
+    let mut taken = String::new();
+    while let Some(c) = chars.next_if(|c| pred(*c)) {
+        taken.push(c);
+    }
+    taken
+}
+
+// A number with an optional fraction, exponent and type suffix (e.g. `10L`)
+fn take_number(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut number = take_while(chars, |c| c.is_ascii_digit());
+    if let Some(dot) = chars.next_if_eq(&'.') {
+        number.push(dot);
+        number.push_str(&take_while(chars, |c| c.is_ascii_digit()));
+    }
+    if let Some(e) = chars.next_if(|c| matches!(c, 'e' | 'E')) {
+        number.push(e);
+        if let Some(sign) = chars.next_if(|c| matches!(c, '+' | '-')) {
+            number.push(sign);
+        }
+        number.push_str(&take_while(chars, |c| c.is_ascii_digit()));
+    }
+    number.push_str(&take_while(chars, |c| c.is_alphabetic()));
+    number
+}
+
+// A quoted string or identifier. The quote character is escaped by doubling it, or (in strings)
+// with a backslash.
+fn take_quoted(chars: &mut Peekable<Chars<'_>>, quote: char) -> DeltaResult<String> {
+    chars.next();
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') if quote != '`' => match chars.next() {
+                Some(c) => value.push(c),
+                None => break,
+            },
+            Some(c) if c == quote => match chars.next_if_eq(&quote) {
+                Some(c) => value.push(c),
+                None => return Ok(value),
+            },
+            Some(c) => value.push(c),
+            None => break,
+        }
+    }
+    Err(Error::unsupported(format!(
+        "Unterminated {quote}-quoted text"
+    )))
+}
+
+// A recursive descent parser over the tokens of an expression, from the lowest precedence (OR)
+// to the highest (literals, columns and parentheses)
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl SqlExpr {
+    pub(crate) fn parse(sql: &str) -> DeltaResult<Self> {
+        let parse = || {
+            let mut parser = Parser {
+                tokens: tokenize(sql)?,
+                pos: 0,
+            };
+            let expr = parser.parse_or()?;
+            match parser.tokens.get(parser.pos) {
+                Some(token) => Err(Error::unsupported(format!("Unexpected {token:?}"))),
+                None => Ok(expr),
+            }
+        };
+        parse().map_err(|err| match err {
+            Error::Unsupported(msg) => {
+                Error::unsupported(format!("Cannot parse SQL expression '{sql}': {msg}"))
+            }
+            err => err,
+        })
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // Consume the next token if it is the given (case-insensitive) keyword
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Identifier(id)) if id.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Consume the next token if it is the given symbol
+    fn symbol(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == symbol => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self) -> DeltaResult<SqlExpr> {
+        let mut expr = self.parse_and()?;
+        while self.keyword("OR") {
+            expr = SqlExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> DeltaResult<SqlExpr> {
+        let mut expr = self.parse_not()?;
+        while self.keyword("AND") {
+            expr = SqlExpr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> DeltaResult<SqlExpr> {
+        if self.keyword("NOT") || self.symbol("!") {
+            return Ok(SqlExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> DeltaResult<SqlExpr> {
+        let left = self.parse_additive()?;
+        let comparison = match self.peek() {
+            Some(Token::Symbol("=")) => Comparison::Equal,
+            Some(Token::Symbol("!=")) => Comparison::NotEqual,
+            Some(Token::Symbol("<")) => Comparison::LessThan,
+            Some(Token::Symbol("<=")) => Comparison::LessThanOrEqual,
+            Some(Token::Symbol(">")) => Comparison::GreaterThan,
+            Some(Token::Symbol(">=")) => Comparison::GreaterThanOrEqual,
+            Some(Token::Symbol("<=>")) => Comparison::NullSafeEqual,
+            _ => {
+                if self.keyword("IS") {
+                    let negated = self.keyword("NOT");
+                    if !self.keyword("NULL") {
+                        return Err(Error::unsupported("Expected NULL after IS"));
+                    }
+                    return Ok(SqlExpr::IsNull(Box::new(left), negated));
+                }
+                let negated = self.keyword("NOT");
+                if self.keyword("LIKE") {
+                    let pattern = self.parse_additive()?;
+                    return Ok(SqlExpr::Like(Box::new(left), Box::new(pattern), negated));
+                }
+                if negated {
+                    return Err(Error::unsupported("Expected LIKE after NOT"));
+                }
+                return Ok(left);
+            }
+        };
+        self.pos += 1;
+        let right = self.parse_additive()?;
+        Ok(SqlExpr::Comparison(
+            comparison,
+            Box::new(left),
+            Box::new(right),
+        ))
+    }
+
+    fn parse_additive(&mut self) -> DeltaResult<SqlExpr> {
+        let mut expr = self.parse_multiplicative()?;
+        loop {
+            let op = if self.symbol("+") {
+                BinaryExpressionOp::Plus
+            } else if self.symbol("-") {
+                BinaryExpressionOp::Minus
+            } else {
+                return Ok(expr);
+            };
+            let right = self.parse_multiplicative()?;
+            expr = SqlExpr::Arithmetic(op, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> DeltaResult<SqlExpr> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            let op = if self.symbol("*") {
+                BinaryExpressionOp::Multiply
+            } else if self.symbol("/") {
+                BinaryExpressionOp::Divide
+            } else {
+                return Ok(expr);
+            };
+            let right = self.parse_unary()?;
+            expr = SqlExpr::Arithmetic(op, Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> DeltaResult<SqlExpr> {
+        if self.symbol("-") {
+            return Ok(match self.parse_unary()? {
+                SqlExpr::Literal(SqlLiteral::Number(n)) => {
+                    SqlExpr::Literal(SqlLiteral::Number(format!("-{n}")))
+                }
+                expr => SqlExpr::Negate(Box::new(expr)),
+            });
+        }
+        if self.symbol("+") {
+            return self.parse_unary();
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> DeltaResult<SqlExpr> {
+        let expr = match self.next() {
+            Some(Token::Symbol("(")) => {
+                let expr = self.parse_or()?;
+                if !self.symbol(")") {
+                    return Err(Error::unsupported("Expected )"));
+                }
+                return Ok(expr);
+            }
+            Some(Token::Number(n)) => SqlExpr::Literal(SqlLiteral::Number(n)),
+            Some(Token::String(s)) => SqlExpr::Literal(SqlLiteral::String(s)),
+            Some(Token::Identifier(id)) if id.eq_ignore_ascii_case("TRUE") => {
+                SqlExpr::Literal(SqlLiteral::Boolean(true))
+            }
+            Some(Token::Identifier(id)) if id.eq_ignore_ascii_case("FALSE") => {
+                SqlExpr::Literal(SqlLiteral::Boolean(false))
+            }
+            Some(Token::Identifier(id)) if id.eq_ignore_ascii_case("NULL") => {
+                SqlExpr::Literal(SqlLiteral::Null)
+            }
+            Some(Token::Identifier(id)) | Some(Token::QuotedIdentifier(id)) => {
+                if matches!(self.peek(), Some(Token::Symbol("("))) {
+                    return Err(Error::unsupported(format!("Unsupported function {id}")));
+                }
+                let mut path = vec![id];
+                while self.symbol(".") {
+                    match self.next() {
+                        Some(Token::Identifier(id)) | Some(Token::QuotedIdentifier(id)) => {
+                            path.push(id)
+                        }
+                        _ => return Err(Error::unsupported("Expected a field name after .")),
+                    }
+                }
+                SqlExpr::Column(path)
+            }
+            Some(token) => return Err(Error::unsupported(format!("Unexpected {token:?}"))),
+            None => return Err(Error::unsupported("Unexpected end of expression")),
+        };
+        Ok(expr)
+    }
+}
+
+// Resolves the column references of a parsed expression against the table schema, and types its
+// literals after the columns they are compared to or combined with
+struct Resolver<'a> {
+    schema: &'a StructType,
+    partition_values: &'a HashMap<String, Scalar>,
+}
+
+impl Resolver<'_> {
+    // Resolve a column by (case-insensitive) path, returning its name in the schema and its type
+    fn resolve_column(&self, path: &[String]) -> DeltaResult<(ColumnName, DataType)> {
+        let mut fields = Some(self.schema);
+        let mut names = vec![];
+        let mut data_type = None;
+        for name in path {
+            let field = fields
+                .and_then(|fields| {
+                    fields.field(name).or_else(|| {
+                        fields
+                            .fields()
+                            .find(|f| f.name().eq_ignore_ascii_case(name))
+                    })
+                })
+                .ok_or_else(|| Error::missing_column(path.join(".")))?;
+            names.push(field.name().clone());
+            data_type = Some(field.data_type());
+            fields = match field.data_type() {
+                DataType::Struct(children) => Some(children),
+                _ => None,
+            };
+        }
+        let data_type = data_type.ok_or_else(|| Error::missing_column(path.join(".")))?;
+        Ok((ColumnName::new(names), data_type.clone()))
+    }
+
+    // The type of `expr` if it can be derived without typing literals
+    fn data_type(&self, expr: &SqlExpr) -> Option<DataType> {
+        match expr {
+            SqlExpr::Column(path) => self.resolve_column(path).ok().map(|(_, t)| t),
+            SqlExpr::Literal(_) => None,
+            SqlExpr::Negate(expr) => self.data_type(expr),
+            SqlExpr::Arithmetic(_, left, right) => {
+                self.data_type(left).or_else(|| self.data_type(right))
+            }
+            _ => Some(DataType::BOOLEAN),
+        }
+    }
+
+    // Convert `expr` to an expression, typing its literals as `hint` if possible
+    fn to_expression(&self, expr: &SqlExpr, hint: Option<&DataType>) -> DeltaResult<Expression> {
+        let expr = match expr {
+            SqlExpr::Column(path) => {
+                let (name, _) = self.resolve_column(path)?;
+                // partition values are not part of the data, so we substitute them as literals
+                match self.partition_values.get(&name[0]) {
+                    Some(value) if name.len() == 1 => Expression::literal(value.clone()),
+                    _ => Expression::column(name),
+                }
+            }
+            SqlExpr::Literal(literal) => Expression::literal(literal.to_scalar(hint)?),
+            SqlExpr::Negate(expr) => {
+                let data_type = self.data_type(expr);
+                let data_type = data_type.as_ref().or(hint);
+                let zero = SqlLiteral::Number("0".to_string()).to_scalar(data_type)?;
+                let expr = self.to_expression(expr, data_type)?;
+                Expression::binary(BinaryExpressionOp::Minus, zero, expr)
+            }
+            SqlExpr::Arithmetic(op, left, right) => {
+                let left_type = self.data_type(left);
+                let right_type = self.data_type(right);
+                if *op == BinaryExpressionOp::Divide {
+                    // SQL division is fractional, but kernel divides integers with truncation, so
+                    // only division of floating point operands evaluates as written
+                    let operand_type = left_type.as_ref().or(right_type.as_ref()).or(hint);
+                    let is_fractional = |data_type: Option<&DataType>| {
+                        matches!(data_type, Some(&DataType::FLOAT) | Some(&DataType::DOUBLE))
+                    };
+                    require!(
+                        is_fractional(operand_type)
+                            && [&left_type, &right_type]
+                                .iter()
+                                .all(|t| t.is_none() || is_fractional(t.as_ref())),
+                        Error::unsupported(
+                            "Division is only supported for float and double operands"
+                        )
+                    );
+                }
+                let left = self.to_expression(left, right_type.as_ref().or(hint))?;
+                let right = self.to_expression(right, left_type.as_ref().or(hint))?;
+                Expression::binary(*op, left, right)
+            }
+            _ => Expression::from_pred(self.to_predicate(expr)?),
+        };
+        Ok(expr)
+    }
+
+    fn to_predicate(&self, expr: &SqlExpr) -> DeltaResult<Predicate> {
+        let pred = match expr {
+            SqlExpr::Comparison(comparison, left, right) => {
+                let left_type = self.data_type(left);
+                let right_type = self.data_type(right);
+                let left = self.to_expression(left, right_type.as_ref())?;
+                let right = self.to_expression(right, left_type.as_ref())?;
+                match comparison {
+                    Comparison::Equal => Predicate::eq(left, right),
+                    Comparison::NotEqual => Predicate::ne(left, right),
+                    Comparison::LessThan => Predicate::lt(left, right),
+                    Comparison::LessThanOrEqual => Predicate::le(left, right),
+                    Comparison::GreaterThan => Predicate::gt(left, right),
+                    Comparison::GreaterThanOrEqual => Predicate::ge(left, right),
+                    Comparison::NullSafeEqual => Predicate::not(Predicate::distinct(left, right)),
+                }
+            }
+            SqlExpr::IsNull(expr, negated) => {
+                let expr = self.to_expression(expr, None)?;
+                match negated {
+                    false => Predicate::is_null(expr),
+                    true => Predicate::is_not_null(expr),
+                }
+            }
+            SqlExpr::Like(expr, pattern, negated) => {
+                let like = Predicate::like(
+                    self.to_expression(expr, Some(&DataType::STRING))?,
+                    self.to_expression(pattern, Some(&DataType::STRING))?,
+                );
+                match negated {
+                    false => like,
+                    true => Predicate::not(like),
+                }
+            }
+            SqlExpr::Not(expr) => Predicate::not(self.to_predicate(expr)?),
+            SqlExpr::And(left, right) => {
+                Predicate::and(self.to_predicate(left)?, self.to_predicate(right)?)
+            }
+            SqlExpr::Or(left, right) => {
+                Predicate::or(self.to_predicate(left)?, self.to_predicate(right)?)
+            }
+            expr => Predicate::from_expr(self.to_expression(expr, Some(&DataType::BOOLEAN))?),
+        };
+        Ok(pred)
+    }
+}
+
+impl SqlLiteral {
+    fn to_scalar(&self, hint: Option<&DataType>) -> DeltaResult<Scalar> {
+        let scalar = match self {
+            Self::Number(n) => return parse_number(n, hint),
+            Self::String(s) => Scalar::from(s.clone()),
+            Self::Boolean(b) => Scalar::from(*b),
+            Self::Null => Scalar::Null(hint.cloned().unwrap_or(DataType::STRING)),
+        };
+        Ok(scalar)
+    }
+}
+
+// Parse a numeric literal. A type suffix (`Y`, `S`, `L`, `F` or `D`) determines its type, and
+// otherwise it takes the type of the (numeric) `hint`. Untyped literals default to INTEGER (or LONG
+// if out of range) and DOUBLE for fractional numbers.
+fn parse_number(number: &str, hint: Option<&DataType>) -> DeltaResult<Scalar> {
+    let invalid = || Error::unsupported(format!("Invalid numeric literal {number}"));
+    let (digits, suffix) = number
+        .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+        .map_or((number, ""), |i| number.split_at(i));
+    let suffix_type = match suffix.to_ascii_uppercase().as_str() {
+        "" => None,
+        "Y" => Some(PrimitiveType::Byte),
+        "S" => Some(PrimitiveType::Short),
+        "L" => Some(PrimitiveType::Long),
+        "F" => Some(PrimitiveType::Float),
+        "D" => Some(PrimitiveType::Double),
+        _ => return Err(invalid()),
+    };
+    if let Some(primitive) = suffix_type {
+        return primitive.parse_scalar(digits).map_err(|_| invalid());
+    }
+    let numeric_hint = hint.and_then(|hint| hint.as_primitive_opt()).filter(|p| {
+        matches!(
+            p,
+            PrimitiveType::Byte
+                | PrimitiveType::Short
+                | PrimitiveType::Integer
+                | PrimitiveType::Long
+                | PrimitiveType::Float
+                | PrimitiveType::Double
+                | PrimitiveType::Decimal(_)
+        )
+    });
+    if let Some(scalar) = numeric_hint.and_then(|p| p.parse_scalar(digits).ok()) {
+        return Ok(scalar);
+    }
+    if let Ok(n) = digits.parse::<i32>() {
+        Ok(Scalar::from(n))
+    } else if let Ok(n) = digits.parse::<i64>() {
+        Ok(Scalar::from(n))
+    } else {
+        digits
+            .parse::<f64>()
+            .map(Scalar::from)
+            .map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::column_expr;
+    use crate::schema::StructField;
+
+    fn col(name: &str) -> Box<SqlExpr> {
+        Box::new(SqlExpr::Column(vec![name.to_string()]))
+    }
+
+    fn num(n: &str) -> Box<SqlExpr> {
+        Box::new(SqlExpr::Literal(SqlLiteral::Number(n.to_string())))
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            (
+                "x > 0",
+                SqlExpr::Comparison(Comparison::GreaterThan, col("x"), num("0")),
+            ),
+            (
+                "`my col` <=> -1.5 OR NOT y IS NULL AND z LIKE 'a''%'",
+                SqlExpr::Or(
+                    Box::new(SqlExpr::Comparison(
+                        Comparison::NullSafeEqual,
+                        col("my col"),
+                        num("-1.5"),
+                    )),
+                    Box::new(SqlExpr::And(
+                        Box::new(SqlExpr::Not(Box::new(SqlExpr::IsNull(col("y"), false)))),
+                        Box::new(SqlExpr::Like(
+                            col("z"),
+                            Box::new(SqlExpr::Literal(SqlLiteral::String("a'%".to_string()))),
+                            false,
+                        )),
+                    )),
+                ),
+            ),
+            (
+                "(a + b) * 2L <> s.f",
+                SqlExpr::Comparison(
+                    Comparison::NotEqual,
+                    Box::new(SqlExpr::Arithmetic(
+                        BinaryExpressionOp::Multiply,
+                        Box::new(SqlExpr::Arithmetic(
+                            BinaryExpressionOp::Plus,
+                            col("a"),
+                            col("b"),
+                        )),
+                        num("2L"),
+                    )),
+                    Box::new(SqlExpr::Column(vec!["s".to_string(), "f".to_string()])),
+                ),
+            ),
+            (
+                "a is not null and -b <= 1e3",
+                SqlExpr::And(
+                    Box::new(SqlExpr::IsNull(col("a"), true)),
+                    Box::new(SqlExpr::Comparison(
+                        Comparison::LessThanOrEqual,
+                        Box::new(SqlExpr::Negate(col("b"))),
+                        num("1e3"),
+                    )),
+                ),
+            ),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(SqlExpr::parse(sql).unwrap(), expected, "{sql}");
+        }
+
+        for sql in [
+            "",
+            "x >",
+            "year(d) = 2024",
+            "x IN (1, 2)",
+            "'unterminated",
+            "(x",
+        ] {
+            assert!(
+                matches!(SqlExpr::parse(sql), Err(Error::Unsupported(_))),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let schema = StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("value", DataType::DOUBLE),
+            StructField::nullable("p", DataType::STRING),
+        ]);
+        let partition_values = HashMap::from([("p".to_string(), Scalar::from("a"))]);
+        let resolver = Resolver {
+            schema: &schema,
+            partition_values: &partition_values,
+        };
+        let resolve = |sql| resolver.to_predicate(&SqlExpr::parse(sql).unwrap());
+
+        // literals take the type of the column they are compared with, and columns are resolved
+        // case-insensitively
+        assert_eq!(
+            resolve("ID > 0").unwrap(),
+            column_expr!("id").gt(Expression::literal(0i64))
+        );
+        assert_eq!(
+            resolve("value * 2 <= id").unwrap(),
+            Predicate::le(
+                Expression::binary(
+                    BinaryExpressionOp::Multiply,
+                    column_expr!("value"),
+                    Expression::literal(2f64)
+                ),
+                column_expr!("id")
+            )
+        );
+        // partition columns are replaced by their values
+        assert_eq!(
+            resolve("p != 'b'").unwrap(),
+            Predicate::ne(Expression::literal("a"), Expression::literal("b"))
+        );
+        assert!(resolve("missing = 1").is_err());
+
+        // division is fractional in SQL, so it is only supported for floating point operands
+        assert_eq!(
+            resolve("value / 2 > 1").unwrap(),
+            Predicate::gt(
+                Expression::binary(
+                    BinaryExpressionOp::Divide,
+                    column_expr!("value"),
+                    Expression::literal(2f64)
+                ),
+                Expression::literal(1f64)
+            )
+        );
+        for sql in ["id / 2 > 1", "value / id > 1", "3 / 2 = 1"] {
+            assert!(matches!(resolve(sql), Err(Error::Unsupported(_))), "{sql}");
+        }
+    }
+}
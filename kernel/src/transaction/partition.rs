@@ -0,0 +1,66 @@
+//! Hive-style partition directories (`col=value/`) for the data files of partitioned tables.
+
+use std::fmt::Write as _;
+
+/// The directory name used for null (and empty) partition values.
+pub(crate) const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+// Characters (besides ASCII control characters) that are escaped as `%XX` in partition directory
+// names. This matches the characters escaped by Spark (on non-Windows platforms), so other Delta
+// writers produce the same paths.
+const SPECIAL_CHARS: &str = "\"#%'*/:=?\\{[]^";
+
+fn needs_escape(c: char) -> bool {
+    c.is_ascii_control() || SPECIAL_CHARS.contains(c)
+}
+
+/// Escape a partition column name or value for use in a partition directory name.
+pub(crate) fn escape_partition_path_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if needs_escape(c) {
+            // all escaped characters are ASCII, so they fit in one byte
+            let _ = write!(escaped, "%{:02X}", c as u32);
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// The directory name (`col=value`) for a partition column with the given serialized value.
+pub(crate) fn partition_dir_name(column: &str, value: Option<&str>) -> String {
+    let value = match value {
+        Some(value) if !value.is_empty() => escape_partition_path_name(value),
+        _ => HIVE_DEFAULT_PARTITION.to_string(),
+    };
+    format!("{}={value}", escape_partition_path_name(column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_dir_name() {
+        assert_eq!(partition_dir_name("p", Some("a")), "p=a");
+        assert_eq!(partition_dir_name("p", Some("a b")), "p=a b");
+        assert_eq!(
+            partition_dir_name("p", Some("2024-01-01 10:00:00.000000")),
+            "p=2024-01-01 10%3A00%3A00.000000"
+        );
+        assert_eq!(partition_dir_name("p", Some("a/b=c%d")), "p=a%2Fb%3Dc%25d");
+        assert_eq!(partition_dir_name("p", Some("\u{1}\u{7F}")), "p=%01%7F");
+        assert_eq!(partition_dir_name("p", Some("é")), "p=é");
+        assert_eq!(partition_dir_name("a=b", Some("c")), "a%3Db=c");
+        // nulls and empty strings both go to the default partition
+        assert_eq!(
+            partition_dir_name("p", None),
+            "p=__HIVE_DEFAULT_PARTITION__"
+        );
+        assert_eq!(
+            partition_dir_name("p", Some("")),
+            "p=__HIVE_DEFAULT_PARTITION__"
+        );
+    }
+}
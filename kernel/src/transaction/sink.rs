@@ -0,0 +1,265 @@
+//! A [`StreamingSink`] provides exactly-once appends of micro-batches to a Delta table.
+//!
+//! Each micro-batch is identified by an application id and a monotonically increasing batch
+//! version. The sink records the batch version in a `txn` (SetTransaction) action in the same
+//! commit as the batch's files, so replaying a batch after a failure is a no-op.
+
+use std::sync::{Arc, LazyLock};
+
+use url::Url;
+
+use crate::schema::{MapType, SchemaRef, StructField, StructType};
+use crate::snapshot::Snapshot;
+use crate::transaction::{CommitResult, Transaction, WriteContext};
+use crate::{DataType, DeltaResult, Engine, EngineData, Error, Version};
+
+const STREAMING_UPDATE_OPERATION: &str = "STREAMING UPDATE";
+const DEFAULT_MAX_RETRIES: usize = 10;
+
+static ENGINE_COMMIT_INFO_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(vec![StructField::nullable(
+        "engineCommitInfo",
+        MapType::new(DataType::STRING, DataType::STRING, true),
+    )]))
+});
+
+/// The outcome of a [`StreamingSink::append`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppendResult {
+    /// The batch was committed to the table at the given version.
+    Committed(Version),
+    /// The table already contains this batch (or a later one) for the application id. The
+    /// latest batch version recorded for the application id is included. Nothing was written.
+    AlreadyCommitted(i64),
+}
+
+/// A helper for streaming connectors which need to append micro-batches to a table exactly once.
+///
+/// The sink captures the table schema when it is created. Every [`append`] validates that the
+/// table schema has not changed since then (files written against the old schema must not be
+/// committed), skips batches whose version was already committed for the application id, and
+/// retries the commit on conflicts with concurrent writers.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let mut sink = StreamingSink::try_new(table_root, &engine)?;
+/// let write_context = sink.get_write_context()?;
+/// // ... write parquet files for the batch using `write_context` ...
+/// match sink.append(&engine, "my-stream", batch_id, add_files_metadata)? {
+///     AppendResult::Committed(version) => println!("committed at version {version}"),
+///     AppendResult::AlreadyCommitted(_) => println!("batch {batch_id} was already committed"),
+/// }
+/// ```
+///
+/// [`append`]: StreamingSink::append
+#[derive(Debug)]
+pub struct StreamingSink {
+    snapshot: Arc<Snapshot>,
+    schema: SchemaRef,
+    max_retries: usize,
+}
+
+impl StreamingSink {
+    /// Create a new sink for the table at `table_root`, capturing the table's current schema.
+    pub fn try_new(table_root: Url, engine: &dyn Engine) -> DeltaResult<Self> {
+        let snapshot = Arc::new(Snapshot::try_new(table_root, engine, None)?);
+        let schema = snapshot.schema();
+        Ok(Self {
+            snapshot,
+            schema,
+            max_retries: DEFAULT_MAX_RETRIES,
+        })
+    }
+
+    /// Set the maximum number of times a commit is retried after conflicting with a concurrent
+    /// writer. Defaults to 10.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The table schema this sink validates against.
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Get a [`WriteContext`] which can be used to write the data files of a batch.
+    pub fn get_write_context(&self) -> DeltaResult<WriteContext> {
+        Ok(Transaction::try_new(self.snapshot.clone())?.get_write_context())
+    }
+
+    /// Append the files of batch `batch_version` for application `app_id` to the table.
+    ///
+    /// If the table already records `batch_version` (or a later version) for `app_id`, nothing is
+    /// written and [`AppendResult::AlreadyCommitted`] is returned. Otherwise the files are
+    /// committed together with a `txn` action for `(app_id, batch_version)`, retrying on
+    /// conflicts up to the configured number of retries.
+    ///
+    /// The expected schema for `files` is given by [`add_files_schema`].
+    ///
+    /// Note that this method performs log replay to check the application's latest version.
+    ///
+    /// [`add_files_schema`]: crate::transaction::add_files_schema
+    pub fn append(
+        &mut self,
+        engine: &dyn Engine,
+        app_id: &str,
+        batch_version: i64,
+        files: Box<dyn EngineData>,
+    ) -> DeltaResult<AppendResult> {
+        let engine_commit_info: Arc<dyn EngineData> = engine
+            .evaluation_handler()
+            .null_row(ENGINE_COMMIT_INFO_SCHEMA.clone())?
+            .into();
+        let mut add_files_metadata = vec![files];
+        for _ in 0..=self.max_retries {
+            self.snapshot = Snapshot::try_new_from(self.snapshot.clone(), engine, None)?;
+            if self.snapshot.schema() != self.schema {
+                return Err(Error::Schema(format!(
+                    "Table schema changed at version {} while streaming to it",
+                    self.snapshot.version()
+                )));
+            }
+            let latest = self.snapshot.clone().get_app_id_version(app_id, engine)?;
+            if let Some(latest) = latest.filter(|&v| v >= batch_version) {
+                return Ok(AppendResult::AlreadyCommitted(latest));
+            }
+
+            let mut txn = self
+                .snapshot
+                .clone()
+                .transaction()?
+                .with_operation(STREAMING_UPDATE_OPERATION.to_string())
+                .with_transaction_id(app_id.to_string(), batch_version);
+            txn.commit_info = Some(engine_commit_info.clone());
+            txn.add_files_metadata = add_files_metadata;
+            match txn.commit(engine)? {
+                CommitResult::Committed(version) => return Ok(AppendResult::Committed(version)),
+                // take the files back and retry against the latest snapshot
+                CommitResult::Conflict(txn, _) => add_files_metadata = txn.add_files_metadata,
+            }
+        }
+        Err(Error::generic(format!(
+            "Failed to commit batch {batch_version} for app_id {app_id} after {} retries",
+            self.max_retries
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+    use test_utils::{actions_to_string, add_commit, TestAction};
+
+    use crate::arrow::array::{
+        BooleanArray, Int64Array, MapBuilder, MapFieldNames, RecordBatch, StringArray,
+        StringBuilder,
+    };
+    use crate::arrow::datatypes::Schema as ArrowSchema;
+    use crate::engine::arrow_conversion::TryIntoArrow as _;
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::object_store::memory::InMemory;
+    use crate::transaction::add_files_schema;
+
+    async fn setup() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let commit = actions_to_string(vec![TestAction::Metadata]);
+        add_commit(store.as_ref(), 0, commit).await.unwrap();
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (store, engine, Url::parse("memory:///").unwrap())
+    }
+
+    // build add-files metadata for a single (unpartitioned) file
+    fn add_file(path: &str) -> Box<dyn EngineData> {
+        let schema: ArrowSchema = add_files_schema().as_ref().try_into_arrow().unwrap();
+        let mut partition_values = MapBuilder::new(
+            Some(MapFieldNames {
+                entry: "key_value".to_string(),
+                key: "key".to_string(),
+                value: "value".to_string(),
+            }),
+            StringBuilder::new(),
+            StringBuilder::new(),
+        );
+        partition_values.append(true).unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec![path])),
+                Arc::new(partition_values.finish()),
+                Arc::new(Int64Array::from(vec![1024])),
+                Arc::new(Int64Array::from(vec![0])),
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(StringArray::from(vec![None::<&str>])),
+            ],
+        )
+        .unwrap();
+        Box::new(ArrowEngineData::new(batch))
+    }
+
+    #[tokio::test]
+    async fn test_append_is_idempotent() {
+        let (store, engine, table_root) = setup().await;
+        let mut sink = StreamingSink::try_new(table_root.clone(), &engine).unwrap();
+
+        let result = sink.append(&engine, "app", 0, add_file("a.parquet"));
+        assert_eq!(result.unwrap(), AppendResult::Committed(1));
+
+        // replaying the same batch is a no-op
+        let result = sink.append(&engine, "app", 0, add_file("a.parquet"));
+        assert_eq!(result.unwrap(), AppendResult::AlreadyCommitted(0));
+
+        // a concurrent writer commits in between; the sink picks up the latest version
+        let commit_info = json!({ "commitInfo": {} }).to_string();
+        add_commit(store.as_ref(), 2, commit_info).await.unwrap();
+        let result = sink.append(&engine, "app", 1, add_file("b.parquet"));
+        assert_eq!(result.unwrap(), AppendResult::Committed(3));
+
+        // a different application id is tracked independently
+        let result = sink.append(&engine, "other", 0, add_file("c.parquet"));
+        assert_eq!(result.unwrap(), AppendResult::Committed(4));
+
+        let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None).unwrap());
+        assert_eq!(
+            snapshot.clone().get_app_id_version("app", &engine).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            snapshot.get_app_id_version("other", &engine).unwrap(),
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_rejects_schema_change() {
+        let (store, engine, table_root) = setup().await;
+        let mut sink = StreamingSink::try_new(table_root, &engine).unwrap();
+
+        let new_schema = StructType::new(vec![
+            StructField::nullable("id", DataType::INTEGER),
+            StructField::nullable("val", DataType::STRING),
+            StructField::nullable("name", DataType::STRING),
+        ]);
+        let metadata = json!({
+            "metaData": {
+                "id": "testId",
+                "format": { "provider": "parquet", "options": {} },
+                "schemaString": serde_json::to_string(&new_schema).unwrap(),
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        });
+        add_commit(store.as_ref(), 1, metadata.to_string())
+            .await
+            .unwrap();
+
+        let result = sink.append(&engine, "app", 0, add_file("a.parquet"));
+        assert!(matches!(result, Err(Error::Schema(_))));
+    }
+}
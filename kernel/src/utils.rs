@@ -1,6 +1,5 @@
 //! Various utility functions/macros used throughout the kernel
 use std::borrow::Cow;
-use std::ops::Deref;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -23,9 +22,17 @@ pub(crate) use require;
 
 /// Try to parse string uri into a URL for a table path. This will do it's best to handle things
 /// like `/local/paths`, and even `../relative/paths`.
+///
+/// Strings that parse as a URL (other than windows drive letters like `C:\`) are URLs, and
+/// anything else is a local path. Local paths are taken literally, so `%20` in a local path is
+/// not decoded, while a path with spaces or unicode characters is percent-encoded in the URL. The
+/// returned URL is normalized with [`normalize_table_root`].
 #[internal_api]
 pub(crate) fn try_parse_uri(uri: impl AsRef<str>) -> DeltaResult<Url> {
     let uri = uri.as_ref();
+    if uri.trim().is_empty() {
+        return Err(Error::invalid_table_location("Table location is empty"));
+    }
     let uri_type = resolve_uri_type(uri)?;
     let url = match uri_type {
         UriType::LocalPath(path) => {
@@ -54,6 +61,25 @@ pub(crate) fn try_parse_uri(uri: impl AsRef<str>) -> DeltaResult<Url> {
         }
         UriType::Url(url) => url,
     };
+    normalize_table_root(url)
+}
+
+/// Normalize the URL of a table root, so that paths inside the table can be joined onto it: the
+/// path of the URL must end with a `/`, otherwise joining `_delta_log/` would replace the table
+/// directory instead of descending into it. Any query or fragment is kept as-is.
+///
+/// Fails for URLs which can't have a path, e.g. `mailto:someone@example.com`.
+#[internal_api]
+pub(crate) fn normalize_table_root(mut url: Url) -> DeltaResult<Url> {
+    if url.cannot_be_a_base() {
+        return Err(Error::invalid_table_location(format!(
+            "{url} cannot be a table root, since paths can't be joined onto it"
+        )));
+    }
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
     Ok(url)
 }
 
@@ -69,12 +95,7 @@ enum UriType {
 /// Will return an error if the path is not valid.
 fn resolve_uri_type(table_uri: impl AsRef<str>) -> DeltaResult<UriType> {
     let table_uri = table_uri.as_ref();
-    let table_uri = if table_uri.ends_with('/') {
-        Cow::Borrowed(table_uri)
-    } else {
-        Cow::Owned(format!("{table_uri}/"))
-    };
-    if let Ok(url) = Url::parse(&table_uri) {
+    if let Ok(url) = Url::parse(table_uri) {
         let scheme = url.scheme().to_string();
         if url.scheme() == "file" {
             Ok(UriType::LocalPath(
@@ -84,12 +105,12 @@ fn resolve_uri_type(table_uri: impl AsRef<str>) -> DeltaResult<UriType> {
         } else if scheme.len() == 1 {
             // NOTE this check is required to support absolute windows paths which may properly
             // parse as url we assume here that a single character scheme is a windows drive letter
-            Ok(UriType::LocalPath(PathBuf::from(table_uri.as_ref())))
+            Ok(UriType::LocalPath(PathBuf::from(table_uri)))
         } else {
             Ok(UriType::Url(url))
         }
     } else {
-        Ok(UriType::LocalPath(table_uri.deref().into()))
+        Ok(UriType::LocalPath(table_uri.into()))
     }
 }
 
@@ -181,7 +202,6 @@ pub(crate) mod test_utils {
         Metadata(Metadata),
         #[serde(rename = "protocol")]
         Protocol(Protocol),
-        #[allow(unused)]
         #[serde(rename = "commitInfo")]
         CommitInfo(CommitInfo),
     }
@@ -292,6 +312,17 @@ mod tests {
             "c:/foo/bar",
             "c:/",
             "file:///C:/",
+            // windows drive letters and UNC paths
+            r"C:\foo\bar",
+            r"c:\foo bar\",
+            r"\\server\share\foo",
+            r"\\?\C:\foo",
+            // spaces, percent signs and unicode are taken literally in local paths
+            "/foo bar/baz",
+            "foo bar",
+            "/foo%20bar",
+            "/föö/bär",
+            "relative/path",
         ] {
             match resolve_uri_type(x) {
                 Ok(UriType::LocalPath(_)) => {}
@@ -318,6 +349,92 @@ mod tests {
         resolve_uri_type("file://foo/bar").expect_err("file://foo/bar should not have parsed");
     }
 
+    #[test]
+    fn test_local_path_special_characters() {
+        #[cfg(not(windows))]
+        {
+            let url = resolve_uri_type("file:///foo%20bar/b%C3%A4r");
+            let Ok(UriType::LocalPath(path)) = url else {
+                panic!("Should have parsed as a local path {url:?}");
+            };
+            assert_eq!(path, PathBuf::from("/foo bar/bär"));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["with space", "percent%20sign", "ünïcödé", "trailing"] {
+            let path = dir.path().join(name);
+            std::fs::create_dir(&path).unwrap();
+            let expected = std::fs::canonicalize(&path).unwrap();
+            let path = path.to_str().unwrap();
+            for uri in [path.to_string(), format!("{path}/")] {
+                let url = try_parse_uri(&uri).unwrap();
+                assert!(url.path().ends_with('/'), "{url}");
+                assert_eq!(url.to_file_path().unwrap(), expected);
+                // the URL of the path parses back to the same URL
+                assert_eq!(try_parse_uri(url.as_str()).unwrap(), url);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_uri_errors() {
+        for uri in ["", "  "] {
+            assert!(matches!(
+                try_parse_uri(uri),
+                Err(Error::InvalidTableLocation(_))
+            ));
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        assert!(matches!(
+            try_parse_uri(missing.to_str().unwrap()),
+            Err(Error::InvalidTableLocation(_))
+        ));
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        assert!(matches!(
+            try_parse_uri(file.to_str().unwrap()),
+            Err(Error::InvalidTableLocation(_))
+        ));
+        assert!(matches!(
+            try_parse_uri("mailto:someone@example.com"),
+            Err(Error::InvalidTableLocation(_))
+        ));
+    }
+
+    #[test]
+    fn test_normalize_table_root() {
+        for (url, expected) in [
+            ("s3://bucket", "s3://bucket/"),
+            ("s3://bucket/", "s3://bucket/"),
+            ("s3://bucket/table", "s3://bucket/table/"),
+            ("s3://bucket/table/", "s3://bucket/table/"),
+            ("s3://bucket/my table", "s3://bucket/my%20table/"),
+            ("s3://bucket/my%20table", "s3://bucket/my%20table/"),
+            ("s3://bucket/täble", "s3://bucket/t%C3%A4ble/"),
+            (
+                "abfss://c@a.dfs.core.windows.net/t",
+                "abfss://c@a.dfs.core.windows.net/t/",
+            ),
+            ("https://host/table?sig=abc", "https://host/table/?sig=abc"),
+            ("file:///tmp/table", "file:///tmp/table/"),
+            ("file:///C:/table", "file:///C:/table/"),
+            ("memory:///", "memory:///"),
+        ] {
+            let url = normalize_table_root(Url::parse(url).unwrap()).unwrap();
+            assert_eq!(url.as_str(), expected);
+            // joining a path descends into the table root
+            assert_eq!(
+                url.join("_delta_log/").unwrap().path(),
+                format!("{}_delta_log/", url.path())
+            );
+        }
+        assert!(matches!(
+            normalize_table_root(Url::parse("mailto:someone@example.com").unwrap()),
+            Err(Error::InvalidTableLocation(_))
+        ));
+    }
+
     #[test]
     fn try_from_uri_without_trailing_slash() {
         let location = "s3://foo/__unitystorage/catalogs/cid/tables/tid";
@@ -0,0 +1,332 @@
+//! This module implements the API for planning vacuum operations.
+//!
+//! Vacuum deletes the data files and deletion vector files of a table that are no longer
+//! referenced by its current version, once they have been unreferenced for longer than the
+//! table's deleted file retention (`delta.deletedFileRetentionDuration`, 7 days by default).
+//! Kernel only plans the vacuum: it replays the log to find the files that are still referenced,
+//! or were removed within the retention window, and returns the files of a listing of the table
+//! that are safe to delete. Deleting them is left to the engine. The entry point for this API is
+//! [`Snapshot::vacuum_planner`].
+//!
+//! For more information, see the following protocol section:
+//! <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#vacuum-protocol-check>
+//!
+//! # Example
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use delta_kernel::{Engine, Error, FileMeta, Snapshot};
+//! # let engine: &dyn Engine = todo!();
+//! # let table_files: Vec<FileMeta> = todo!();
+//! let snapshot = Arc::new(Snapshot::try_from_uri("./tests/data/basic_partitioned", engine, None)?);
+//!
+//! // `table_files` are all files under the table root, e.g. as listed by the engine's object store
+//! let plan = snapshot.vacuum_planner()?.plan(engine, table_files.into_iter().map(Ok))?;
+//! for file in plan.files_to_delete() {
+//!     // delete `file.location`
+//! }
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! [`Snapshot::vacuum_planner`]: crate::Snapshot::vacuum_planner
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use url::Url;
+
+use crate::actions::visitors::visit_deletion_vector_at;
+use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
+use crate::checkpoint::DEFAULT_RETENTION_SECS;
+use crate::clock::{KernelClock, SystemClock};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::expressions::{column_name, ColumnName};
+use crate::log_replay::{FileActionDeduplicator, FileActionKey};
+use crate::schema::{ColumnNamesAndTypes, DataType, SchemaRef};
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, FileMeta};
+
+#[cfg(test)]
+mod tests;
+
+static COMMIT_READ_SCHEMA: LazyLock<SchemaRef> =
+    LazyLock::new(|| get_log_schema().project(&[ADD_NAME, REMOVE_NAME]).unwrap());
+static CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    get_log_schema()
+        .project(&[ADD_NAME, REMOVE_NAME, SIDECAR_NAME])
+        .unwrap()
+});
+
+/// The directory holding the change data files of a table. Unlike other hidden directories, its
+/// files are vacuumed once they are older than the retention.
+const CHANGE_DATA_DIR_NAME: &str = "_change_data";
+
+/// Plans a vacuum of a table as of a snapshot, see the [module documentation](self).
+///
+/// Vacuuming a table is a write: the planner fails to build if kernel doesn't support writing to
+/// the table, as required by the `vacuumProtocolCheck` table feature.
+#[derive(Debug)]
+pub struct VacuumPlanner {
+    snapshot: Arc<Snapshot>,
+    retention: Option<Duration>,
+    clock: Arc<dyn KernelClock>,
+}
+
+impl VacuumPlanner {
+    pub(crate) fn try_new(snapshot: Arc<Snapshot>) -> DeltaResult<Self> {
+        snapshot.table_configuration().ensure_write_supported()?;
+        Ok(Self {
+            snapshot,
+            retention: None,
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    /// Keep files removed within `retention` of now, instead of within the table's deleted file
+    /// retention. Planning fails if `retention` is shorter than the table's deleted file
+    /// retention, since concurrent readers of older versions may still need those files.
+    pub fn with_retention_duration(mut self, retention: Duration) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Use `clock` as the source of the current time, instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn KernelClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The timestamp (in milliseconds since the Unix epoch) before which unreferenced files are
+    /// deleted: now minus the retention.
+    pub fn retention_timestamp(&self) -> DeltaResult<i64> {
+        let table_retention = self
+            .snapshot
+            .table_properties()
+            .deleted_file_retention_duration
+            .unwrap_or(Duration::from_secs(DEFAULT_RETENTION_SECS));
+        let retention = match self.retention {
+            Some(retention) if retention < table_retention => {
+                return Err(Error::generic(format!(
+                    "Vacuum retention of {retention:?} is shorter than the table's deleted file \
+                    retention of {table_retention:?}"
+                )));
+            }
+            Some(retention) => retention,
+            None => table_retention,
+        };
+        let retention_ms = i64::try_from(retention.as_millis())
+            .map_err(|_| Error::generic("Retention duration exceeds i64 millisecond range"))?;
+        Ok(self.clock.now_millis()? - retention_ms)
+    }
+
+    /// Plan the vacuum. `table_files` are the files to consider for deletion: all files under the
+    /// table root, listed recursively. Of those, files under hidden directories (whose name
+    /// starts with `_` or `.`, such as `_delta_log`) other than `_change_data`, hidden files,
+    /// files referenced by the snapshot or removed after the retention timestamp, and files
+    /// modified after the retention timestamp are kept.
+    pub fn plan(
+        &self,
+        engine: &dyn Engine,
+        table_files: impl IntoIterator<Item = DeltaResult<FileMeta>>,
+    ) -> DeltaResult<VacuumPlan> {
+        let retention_timestamp = self.retention_timestamp()?;
+        let table_root = self.snapshot.table_root();
+        let retained_files = self.retained_files(engine, retention_timestamp)?;
+        let mut files_to_delete = vec![];
+        for file in table_files {
+            let file = file?;
+            let Some(relative_path) = file.location.as_str().strip_prefix(table_root.as_str())
+            else {
+                continue;
+            };
+            if file.last_modified < retention_timestamp
+                && !is_hidden(relative_path)
+                && !retained_files.contains(file.location.as_str())
+            {
+                files_to_delete.push(file);
+            }
+        }
+        Ok(VacuumPlan {
+            retention_timestamp,
+            files_to_delete,
+        })
+    }
+
+    // The URLs of the data files and deletion vector files that are referenced by the snapshot,
+    // or were removed after `retention_timestamp`.
+    fn retained_files(
+        &self,
+        engine: &dyn Engine,
+        retention_timestamp: i64,
+    ) -> DeltaResult<HashSet<String>> {
+        let actions = self.snapshot.log_segment().read_actions(
+            engine,
+            COMMIT_READ_SCHEMA.clone(),
+            CHECKPOINT_READ_SCHEMA.clone(),
+            None,
+        )?;
+        let mut seen_file_keys = HashSet::new();
+        let mut retained_files = HashSet::new();
+        for actions in actions {
+            let actions = actions?;
+            let mut visitor = RetainedFilesVisitor {
+                deduplicator: FileActionDeduplicator::new(
+                    &mut seen_file_keys,
+                    actions.is_log_batch,
+                    RetainedFilesVisitor::ADD_PATH_INDEX,
+                    RetainedFilesVisitor::REMOVE_PATH_INDEX,
+                    RetainedFilesVisitor::ADD_DV_START_INDEX,
+                    RetainedFilesVisitor::REMOVE_DV_START_INDEX,
+                ),
+                table_root: self.snapshot.table_root(),
+                retention_timestamp,
+                retained_files: &mut retained_files,
+            };
+            visitor.visit_rows_of(actions.actions.as_ref())?;
+        }
+        Ok(retained_files)
+    }
+}
+
+/// The files that a vacuum deletes, see [`VacuumPlanner::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VacuumPlan {
+    retention_timestamp: i64,
+    files_to_delete: Vec<FileMeta>,
+}
+
+impl VacuumPlan {
+    /// The timestamp (in milliseconds since the Unix epoch) before which unreferenced files are
+    /// deleted.
+    pub fn retention_timestamp(&self) -> i64 {
+        self.retention_timestamp
+    }
+
+    /// The unreferenced data files and deletion vector files that are safe to delete.
+    pub fn files_to_delete(&self) -> &[FileMeta] {
+        &self.files_to_delete
+    }
+
+    /// The total size in bytes of the files to delete.
+    pub fn bytes_to_delete(&self) -> u64 {
+        self.files_to_delete.iter().map(|file| file.size).sum()
+    }
+}
+
+// True if the file at `relative_path` (relative to the table root) is hidden, or in a hidden
+// directory. Partition directories (`<column>=<value>`) are never hidden.
+fn is_hidden(relative_path: &str) -> bool {
+    let mut components = relative_path.split('/').peekable();
+    let mut is_top_level = true;
+    while let Some(component) = components.next() {
+        let is_file = components.peek().is_none();
+        let is_hidden_name = component.starts_with('_') || component.starts_with('.');
+        if is_file {
+            return is_hidden_name;
+        }
+        let is_change_data = is_top_level && component == CHANGE_DATA_DIR_NAME;
+        if is_hidden_name && !component.contains('=') && !is_change_data {
+            return true;
+        }
+        is_top_level = false;
+    }
+    false
+}
+
+// Collects the files that a vacuum must keep: the newest action of each (path, deletion vector)
+// pair keeps its data file and deletion vector file, if it is an add or a remove after the
+// retention timestamp. Removes without a deletion timestamp are always kept.
+struct RetainedFilesVisitor<'a> {
+    deduplicator: FileActionDeduplicator<'a>,
+    table_root: &'a Url,
+    retention_timestamp: i64,
+    retained_files: &'a mut HashSet<String>,
+}
+
+impl RetainedFilesVisitor<'_> {
+    // These index positions correspond to the order of columns defined in
+    // `selected_column_names_and_types()`
+    const ADD_PATH_INDEX: usize = 0;
+    const ADD_DV_START_INDEX: usize = 1;
+    const REMOVE_PATH_INDEX: usize = 6;
+    const REMOVE_DELETION_TIMESTAMP_INDEX: usize = 7;
+    const REMOVE_DV_START_INDEX: usize = 8;
+
+    fn retain<'a>(
+        &mut self,
+        path: &str,
+        dv_getters: &[&'a dyn GetData<'a>],
+        i: usize,
+    ) -> DeltaResult<()> {
+        self.retained_files
+            .insert(self.table_root.join(path)?.to_string());
+        if let Some(dv) = visit_deletion_vector_at(i, dv_getters)? {
+            if let Some(dv_path) = dv.absolute_path(self.table_root)? {
+                self.retained_files.insert(dv_path.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RowVisitor for RetainedFilesVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            const STRING: DataType = DataType::STRING;
+            const INTEGER: DataType = DataType::INTEGER;
+            const LONG: DataType = DataType::LONG;
+            let types_and_names = vec![
+                (STRING, column_name!("add.path")),
+                (STRING, column_name!("add.deletionVector.storageType")),
+                (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("add.deletionVector.offset")),
+                (INTEGER, column_name!("add.deletionVector.sizeInBytes")),
+                (LONG, column_name!("add.deletionVector.cardinality")),
+                (STRING, column_name!("remove.path")),
+                (LONG, column_name!("remove.deletionTimestamp")),
+                (STRING, column_name!("remove.deletionVector.storageType")),
+                (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("remove.deletionVector.offset")),
+                (INTEGER, column_name!("remove.deletionVector.sizeInBytes")),
+                (LONG, column_name!("remove.deletionVector.cardinality")),
+            ];
+            let (types, names) = types_and_names.into_iter().unzip();
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 13,
+            Error::InternalError(format!(
+                "Wrong number of RetainedFilesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            // Checkpoint removes are tombstones that vacuum must honor, so never skip removes
+            let Some((file_key, is_add)) =
+                self.deduplicator.extract_file_action(i, getters, false)?
+            else {
+                continue;
+            };
+            let FileActionKey { path, .. } = &file_key;
+            let path = path.clone();
+            if self.deduplicator.check_and_record_seen(file_key) {
+                continue;
+            }
+            if is_add {
+                self.retain(&path, &getters[Self::ADD_DV_START_INDEX..], i)?;
+            } else {
+                let deletion_timestamp: Option<i64> = getters
+                    [Self::REMOVE_DELETION_TIMESTAMP_INDEX]
+                    .get_opt(i, "remove.deletionTimestamp")?;
+                if deletion_timestamp.is_none_or(|ts| ts > self.retention_timestamp) {
+                    self.retain(&path, &getters[Self::REMOVE_DV_START_INDEX..], i)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
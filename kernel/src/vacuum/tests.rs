@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use itertools::Itertools;
+use url::Url;
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::{Add, Metadata, Protocol, Remove};
+use crate::clock::FixedClock;
+use crate::engine::sync::SyncEngine;
+use crate::utils::test_utils::{Action, LocalMockTable};
+use crate::{DeltaResult, FileMeta, Snapshot};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const NOW: i64 = 10 * DAY_MS;
+// The default retention of 7 days puts the retention timestamp at day 3
+const OLD: i64 = DAY_MS;
+const RECENT: i64 = 5 * DAY_MS;
+const SCHEMA: &str = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#;
+
+fn add(path: &str, deletion_vector: Option<DeletionVectorDescriptor>) -> Action {
+    Action::Add(Add {
+        path: path.to_string(),
+        size: 1,
+        modification_time: 1,
+        data_change: true,
+        deletion_vector,
+        ..Default::default()
+    })
+}
+
+fn remove(
+    path: &str,
+    deletion_timestamp: Option<i64>,
+    deletion_vector: Option<DeletionVectorDescriptor>,
+) -> Action {
+    Action::Remove(Remove {
+        path: path.to_string(),
+        deletion_timestamp,
+        data_change: true,
+        deletion_vector,
+        ..Default::default()
+    })
+}
+
+// A test table, whose log lives as long as the table
+struct TestTable {
+    _mock_table: LocalMockTable,
+    root: Url,
+    snapshot: Arc<Snapshot>,
+    engine: SyncEngine,
+}
+
+impl TestTable {
+    async fn try_new(configuration: HashMap<String, String>) -> DeltaResult<Self> {
+        let mut mock_table = LocalMockTable::new();
+        let root = Url::from_directory_path(mock_table.table_root()).unwrap();
+        let dv = |path: &str| DeletionVectorDescriptor {
+            storage_type: "p".to_string(),
+            path_or_inline_dv: root.join(path).unwrap().to_string(),
+            offset: None,
+            size_in_bytes: 1,
+            cardinality: 1,
+        };
+        mock_table
+            .commit([
+                Action::Protocol(Protocol::try_new(
+                    1,
+                    2,
+                    None::<Vec<String>>,
+                    None::<Vec<String>>,
+                )?),
+                Action::Metadata(Metadata {
+                    schema_string: SCHEMA.to_string(),
+                    configuration,
+                    ..Default::default()
+                }),
+                add("file1", None),
+                add("file2", None),
+                add("file3", None),
+                add("file4", Some(dv("dv1.bin"))),
+            ])
+            .await;
+        mock_table
+            .commit([
+                remove("file1", Some(OLD), None),
+                remove("file2", Some(RECENT), None),
+                remove("file3", None, None),
+                remove("file4", Some(OLD), Some(dv("dv1.bin"))),
+                add("file4", Some(dv("dv2.bin"))),
+            ])
+            .await;
+        let engine = SyncEngine::new();
+        let snapshot = Arc::new(Snapshot::try_new(root.clone(), &engine, None)?);
+        Ok(Self {
+            _mock_table: mock_table,
+            root,
+            snapshot,
+            engine,
+        })
+    }
+
+    fn file(&self, path: &str, last_modified: i64) -> DeltaResult<FileMeta> {
+        Ok(FileMeta::new(self.root.join(path)?, last_modified, 1))
+    }
+
+    // The paths (relative to the table root) of the files `plan` deletes
+    fn planned_deletes<'a>(&self, plan: &'a super::VacuumPlan) -> Vec<&'a str> {
+        plan.files_to_delete()
+            .iter()
+            .map(|file| {
+                file.location
+                    .as_str()
+                    .strip_prefix(self.root.as_str())
+                    .unwrap()
+            })
+            .sorted()
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_vacuum_plan() -> DeltaResult<()> {
+    let table = TestTable::try_new(HashMap::new()).await?;
+    let planner = table
+        .snapshot
+        .clone()
+        .vacuum_planner()?
+        .with_clock(Arc::new(FixedClock::new(NOW)));
+    assert_eq!(planner.retention_timestamp()?, 3 * DAY_MS);
+
+    let table_files = [
+        table.file("file1", OLD),
+        table.file("file2", OLD),
+        table.file("file3", OLD),
+        table.file("file4", OLD),
+        table.file("dv1.bin", OLD),
+        table.file("dv2.bin", OLD),
+        table.file("orphan", OLD),
+        table.file("new_orphan", RECENT),
+        table.file("_col=1/orphan", OLD),
+        table.file("col=1/.hidden", OLD),
+        table.file("_change_data/cdc", OLD),
+        table.file("_delta_log/00000000000000000000.json", OLD),
+        table.file("_hidden/orphan", OLD),
+        table.file(".hidden", OLD),
+        Ok(FileMeta::new(Url::parse("memory://other/orphan")?, OLD, 1)),
+    ];
+    let plan = planner.plan(&table.engine, table_files)?;
+    assert_eq!(plan.retention_timestamp(), 3 * DAY_MS);
+    // file1 and the deletion vector of file4 were removed before the retention timestamp. file2
+    // was removed after it, and file3's remove has no deletion timestamp.
+    assert_eq!(
+        table.planned_deletes(&plan),
+        [
+            "_change_data/cdc",
+            "_col=1/orphan",
+            "dv1.bin",
+            "file1",
+            "orphan"
+        ]
+    );
+    assert_eq!(plan.bytes_to_delete(), 5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vacuum_retention() -> DeltaResult<()> {
+    let configuration = HashMap::from([(
+        "delta.deletedFileRetentionDuration".to_string(),
+        "interval 1 days".to_string(),
+    )]);
+    let table = TestTable::try_new(configuration).await?;
+    let table_files = || [table.file("file1", OLD), table.file("file2", OLD)];
+
+    // The table's retention of 1 day puts the retention timestamp at day 9, after both removes
+    let planner = table
+        .snapshot
+        .clone()
+        .vacuum_planner()?
+        .with_clock(Arc::new(FixedClock::new(NOW)));
+    let plan = planner.plan(&table.engine, table_files())?;
+    assert_eq!(table.planned_deletes(&plan), ["file1", "file2"]);
+
+    // A retention of 7 days puts the retention timestamp at day 3, between the removes
+    let planner = planner.with_retention_duration(Duration::from_secs(7 * 24 * 60 * 60));
+    let plan = planner.plan(&table.engine, table_files())?;
+    assert_eq!(table.planned_deletes(&plan), ["file1"]);
+
+    // Retentions shorter than the table's are rejected
+    let planner = table
+        .snapshot
+        .clone()
+        .vacuum_planner()?
+        .with_retention_duration(Duration::from_secs(60 * 60));
+    let err = planner.plan(&table.engine, table_files()).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("shorter than the table's deleted file retention"));
+    Ok(())
+}
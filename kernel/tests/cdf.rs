@@ -7,6 +7,7 @@ use itertools::Itertools;
 
 use delta_kernel::engine::arrow_conversion::TryFromKernel as _;
 use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::table_changes::scan::{CdfColumn, CdfColumns};
 use delta_kernel::table_changes::TableChanges;
 use delta_kernel::{DeltaResult, Error, PredicateRef, Version};
 
@@ -114,6 +115,58 @@ fn cdf_with_deletion_vector() -> Result<(), Box<dyn error::Error>> {
     Ok(())
 }
 
+#[test]
+fn cdf_dv_row_changes() -> Result<(), Box<dyn error::Error>> {
+    let test_dir = load_test_data("tests/data", "cdf-table-with-dv")?;
+    let test_path = test_dir.path().join("cdf-table-with-dv");
+    let test_path = delta_kernel::try_parse_uri(test_path.to_str().expect("table path to string"))?;
+    let engine = DefaultEngine::new_local();
+    let table_changes = TableChanges::try_new(test_path, engine.as_ref(), 0, None)?;
+    let scan = table_changes.into_scan_builder().build()?;
+
+    // The rows restored and deleted by each commit (see `cdf_with_deletion_vector`), by index in
+    // the single data file
+    let changes: Vec<_> = scan
+        .dv_row_changes(engine)?
+        .map_ok(|(version, _, changes)| {
+            let restored = changes.restored_rows().collect_vec();
+            let deleted = changes.deleted_rows().collect_vec();
+            (version, restored, deleted)
+        })
+        .try_collect()?;
+    let expected: Vec<(Version, Vec<u64>, Vec<u64>)> = vec![
+        (1, vec![], vec![0, 9]),
+        (2, vec![0, 9], vec![]),
+        (3, vec![], vec![0, 1, 4, 5]),
+        (4, vec![1, 4], vec![]),
+        (5, vec![0, 5], vec![3]),
+        (6, vec![3], vec![]),
+    ];
+    assert_eq!(changes, expected);
+    Ok(())
+}
+
+#[test]
+fn cdf_commit_metadata() -> Result<(), Box<dyn error::Error>> {
+    let test_dir = load_test_data("tests/data", "cdf-table-simple")?;
+    let test_path = test_dir.path().join("cdf-table-simple");
+    let test_path = delta_kernel::try_parse_uri(test_path.to_str().expect("table path to string"))?;
+    let engine = DefaultEngine::new_local();
+    let table_changes = TableChanges::try_new(test_path, engine.as_ref(), 0, None)?;
+    let scan = table_changes.into_scan_builder().build()?;
+
+    // one entry per commit, each with the table schema in effect at it
+    let commit_metadata: Vec<_> = scan.commit_metadata(engine)?.try_collect()?;
+    let versions = commit_metadata.iter().map(|m| m.version()).collect_vec();
+    assert_eq!(versions, [0, 1, 2]);
+    for metadata in &commit_metadata {
+        assert_eq!(metadata.schema(), commit_metadata[0].schema());
+        // the CDF columns are not part of the table schema
+        assert!(metadata.schema().field("_change_type").is_none());
+    }
+    Ok(())
+}
+
 #[test]
 fn basic_cdf() -> Result<(), Box<dyn error::Error>> {
     let batches = read_cdf_for_table("cdf-table", 0, None, None)?;
@@ -378,6 +431,67 @@ fn update_operations() -> DeltaResult<()> {
     Ok(())
 }
 
+#[test]
+fn renamed_and_excluded_cdf_columns() -> DeltaResult<()> {
+    let test_name = "cdf-table-update-ops";
+    let test_dir = load_test_data("tests/data", test_name).unwrap();
+    let test_path = test_dir.path().join(test_name);
+    let test_path = delta_kernel::try_parse_uri(test_path.to_str().expect("table path to string"))?;
+    let engine = DefaultEngine::new_local();
+    let table_changes = TableChanges::try_new(test_path, engine.as_ref(), 0, Some(2))?;
+
+    // the default schema of the scan reflects the configured columns
+    let cdf_columns = CdfColumns::default()
+        .rename(CdfColumn::ChangeType, "op")
+        .exclude(CdfColumn::CommitTimestamp);
+    let scan = table_changes
+        .into_scan_builder()
+        .with_cdf_columns(cdf_columns)
+        .build()?;
+    let batches: Vec<RecordBatch> = scan
+        .execute(engine)?
+        .map(|scan_result| -> DeltaResult<_> {
+            let scan_result = scan_result?;
+            let mask = scan_result.full_mask();
+            let record_batch = to_arrow(scan_result.raw_data?)?;
+            match mask {
+                Some(mask) => Ok(filter_record_batch(&record_batch, &mask.into())?),
+                None => Ok(record_batch),
+            }
+        })
+        .try_collect()?;
+    // `update_pre` and `update_post` are read from CDC files, the others are generated
+    let mut expected = vec![
+        "+----+-------------+-----------------+",
+        "| id | op          | _commit_version |",
+        "+----+-------------+-----------------+",
+        "| 0  | insert      | 0               |",
+        "| 1  | insert      | 0               |",
+        "| 2  | insert      | 0               |",
+        "| 3  | insert      | 0               |",
+        "| 4  | insert      | 0               |",
+        "| 5  | insert      | 0               |",
+        "| 6  | insert      | 0               |",
+        "| 7  | insert      | 0               |",
+        "| 8  | insert      | 0               |",
+        "| 9  | insert      | 0               |",
+        "| 20 | update_pre  | 1               |",
+        "| 21 | update_pre  | 1               |",
+        "| 22 | update_pre  | 1               |",
+        "| 23 | update_pre  | 1               |",
+        "| 24 | update_pre  | 1               |",
+        "| 30 | update_post | 2               |",
+        "| 31 | update_post | 2               |",
+        "| 32 | update_post | 2               |",
+        "| 33 | update_post | 2               |",
+        "| 34 | update_post | 2               |",
+        "+----+-------------+-----------------+",
+    ];
+    sort_lines!(expected);
+    assert_batches_sorted_eq!(expected, &batches);
+    Ok(())
+}
+
 #[test]
 fn false_data_change_is_ignored() -> DeltaResult<()> {
     let batches = read_cdf_for_table("cdf-table-data-change", 0, 1, None)?;
@@ -15,11 +15,12 @@ use delta_kernel::parquet::file::properties::{EnabledStatistics, WriterPropertie
 use delta_kernel::scan::state::{transform_to_logical, DvInfo, Stats};
 use delta_kernel::scan::Scan;
 use delta_kernel::schema::{DataType, Schema};
-use delta_kernel::{Engine, FileMeta, Snapshot};
+use delta_kernel::{DeltaResult, Engine, FileMeta, Snapshot};
 use itertools::Itertools;
 use test_utils::{
-    actions_to_string, add_commit, generate_batch, generate_simple_batch, into_record_batch,
-    record_batch_to_bytes, record_batch_to_bytes_with_props, IntoArray, TestAction, METADATA,
+    actions_to_string, add_commit, delta_path_for_version, generate_batch, generate_simple_batch,
+    into_record_batch, record_batch_to_bytes, record_batch_to_bytes_with_props, IntoArray,
+    TestAction, METADATA,
 };
 use url::Url;
 
@@ -81,6 +82,77 @@ async fn single_commit_two_add_files() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[tokio::test]
+async fn memory_table_from_batches() -> Result<(), Box<dyn std::error::Error>> {
+    let batch1 = generate_simple_batch()?;
+    let batch2 = generate_batch(vec![
+        ("id", vec![4, 5].into_array()),
+        ("val", vec!["d", "e"].into_array()),
+    ])?;
+    let (engine, table_url) = test_utils::memory_table!(batch1.clone(), batch2.clone()).await?;
+
+    let snapshot = Snapshot::try_new(table_url, engine.as_ref(), None)?;
+    assert_eq!(snapshot.version(), 1);
+    let scan = snapshot.into_scan_builder().build()?;
+    let batches = read_scan(&scan, engine)?;
+    assert_eq!(batches, vec![batch1, batch2]);
+
+    // batches must share a schema
+    let other = generate_batch(vec![("id", vec![1].into_array())])?;
+    assert!(
+        test_utils::memory_table(vec![generate_simple_batch()?, other])
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn compressed_commits() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        actions_to_string(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    // later commits are zstd compressed
+    for (version, action) in [
+        (1, TestAction::Add(PARQUET_FILE2.to_string())),
+        (2, TestAction::Remove(PARQUET_FILE1.to_string())),
+    ] {
+        let commit = zstd::encode_all(actions_to_string(vec![action]).as_bytes(), 0)?;
+        storage
+            .put(&delta_path_for_version(version, "json.zst"), commit.into())
+            .await?;
+    }
+    for file in [PARQUET_FILE1, PARQUET_FILE2] {
+        storage
+            .put(&Path::from(file), record_batch_to_bytes(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let engine = Arc::new(DefaultEngine::new(
+        storage.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+
+    let snapshot = Snapshot::try_new(location, engine.as_ref(), None)?;
+    assert_eq!(snapshot.version(), 2);
+    let scan = snapshot.into_scan_builder().build()?;
+    let batches: Vec<_> = scan
+        .execute(engine)?
+        .map(|data| -> DeltaResult<_> { Ok(into_record_batch(data?.raw_data?)) })
+        .try_collect()?;
+    assert_eq!(batches, vec![batch]);
+    Ok(())
+}
+
 #[tokio::test]
 async fn two_commits() -> Result<(), Box<dyn std::error::Error>> {
     let batch = generate_simple_batch()?;
@@ -304,6 +376,72 @@ async fn stats() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+// Other writers sometimes encode integer stats as floats or with exponents. These must still be
+// usable for data skipping, but must never be truncated into tighter bounds than they describe.
+#[tokio::test]
+async fn stats_with_float_encoded_integers() -> Result<(), Box<dyn std::error::Error>> {
+    fn add(path: &str, stats: &str) -> String {
+        let stats = stats.replace('"', r#"\""#);
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"stats":"{stats}"}}}}"#
+        )
+    }
+    let batch1 = generate_simple_batch()?;
+    let batch2 = generate_batch(vec![
+        ("id", vec![5, 7].into_array()),
+        ("val", vec!["e", "g"].into_array()),
+    ])?;
+    let storage = Arc::new(InMemory::new());
+    let commit = [
+        METADATA.to_string(),
+        add(
+            PARQUET_FILE1,
+            r#"{"numRecords":3.0,"nullCount":{"id":0.0},"minValues":{"id":1.0},"maxValues":{"id":3e0}}"#,
+        ),
+        // a fractional bound for an integer column is unknown, and must not be truncated to 7
+        add(
+            PARQUET_FILE2,
+            r#"{"numRecords":2,"nullCount":{"id":0},"minValues":{"id":5E+0},"maxValues":{"id":7.5}}"#,
+        ),
+    ];
+    add_commit(storage.as_ref(), 0, commit.join("\n")).await?;
+    for (path, batch) in [(PARQUET_FILE1, &batch1), (PARQUET_FILE2, &batch2)] {
+        let data = record_batch_to_bytes(batch);
+        storage.put(&Path::from(path), data.into()).await?;
+    }
+
+    let location = Url::parse("memory:///").unwrap();
+    let engine = Arc::new(DefaultEngine::new(
+        storage.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let snapshot = Arc::new(Snapshot::try_new(location, engine.as_ref(), None)?);
+
+    #[allow(clippy::type_complexity)]
+    let test_cases: Vec<(fn(Expr, Expr) -> _, _, _)> = vec![
+        (Pred::lt, 1i32, vec![]),
+        (Pred::eq, 2, vec![&batch1]),
+        (Pred::eq, 4, vec![]),
+        (Pred::gt, 3, vec![&batch2]),
+        (Pred::eq, 8, vec![&batch2]),
+    ];
+    for (pred_fn, value, expected_batches) in test_cases {
+        let predicate = pred_fn(column_expr!("id"), Expr::literal(value));
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_predicate(Arc::new(predicate.clone()))
+            .build()?;
+        let batches: Vec<_> = scan
+            .execute(engine.clone())?
+            .map(|data| Ok(into_record_batch(data?.raw_data?)))
+            .collect::<Result<_, Box<dyn std::error::Error>>>()?;
+        let expected: Vec<_> = expected_batches.into_iter().cloned().collect();
+        assert_eq!(batches, expected, "{predicate:?}");
+    }
+    Ok(())
+}
+
 fn read_with_execute(
     engine: Arc<dyn Engine>,
     scan: &Scan,
@@ -1176,6 +1314,67 @@ fn basic_decimal() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn basic_decimal_with_predicate() -> Result<(), Box<dyn std::error::Error>> {
+    use delta_kernel::expressions::Scalar;
+    let table = "./tests/data/basic-decimal-table/";
+    let dec =
+        |bits, precision, scale| Expr::literal(Scalar::decimal(bits, precision, scale).unwrap());
+
+    // The literals below deliberately use a different precision and scale than the columns they
+    // are compared against, so that data skipping must align scales to get the right answer.
+
+    // col1 is decimal(5,2), compared against 1.0 as decimal(2,1)
+    let expected = vec![
+        "+---------------+--------+-------------+-----------------------+",
+        "| part          | col1   | col2        | col3                  |",
+        "+---------------+--------+-------------+-----------------------+",
+        "| 2342222.23454 | 111.11 | 22222.22222 | 3333333333.3333333333 |",
+        "+---------------+--------+-------------+-----------------------+",
+    ];
+    let predicate = Pred::gt(column_expr!("col1"), dec(10, 2, 1));
+    read_table_data_str(table, None, Some(predicate), expected)?;
+
+    let expected = vec![
+        "+---------------+--------+-------------+-----------------------+",
+        "| part          | col1   | col2        | col3                  |",
+        "+---------------+--------+-------------+-----------------------+",
+        "| 234.00000     | 1.00   | 2.00000     | 3.0000000000          |",
+        "| 2342222.23454 | 111.11 | 22222.22222 | 3333333333.3333333333 |",
+        "+---------------+--------+-------------+-----------------------+",
+    ];
+    let predicate = Pred::ge(column_expr!("col1"), dec(10, 2, 1));
+    read_table_data_str(table, None, Some(predicate), expected)?;
+
+    // col3 is decimal(20,10), compared against 3 as decimal(1,0)
+    let expected = vec![
+        "+-----------+------+---------+--------------+",
+        "| part      | col1 | col2    | col3         |",
+        "+-----------+------+---------+--------------+",
+        "| 234.00000 | 1.00 | 2.00000 | 3.0000000000 |",
+        "+-----------+------+---------+--------------+",
+    ];
+    let predicate = Pred::eq(column_expr!("col3"), dec(3, 1, 0));
+    read_table_data_str(table, None, Some(predicate), expected)?;
+
+    // col2 is decimal(10,5), compared against -100000 as decimal(38,0)
+    let predicate = Pred::lt(column_expr!("col2"), dec(-100000, 38, 0));
+    read_table_data_str(table, None, Some(predicate), vec![])?;
+
+    // part is a decimal(12,5) partition column, compared against 0.0001 as decimal(5,4)
+    let expected = vec![
+        "+----------------+---------+--------------+------------------------+",
+        "| part           | col1    | col2         | col3                   |",
+        "+----------------+---------+--------------+------------------------+",
+        "| -2342342.23423 | -999.99 | -99999.99999 | -9999999999.9999999999 |",
+        "| 0.00004        | 0.00    | 0.00000      | 0.0000000000           |",
+        "+----------------+---------+--------------+------------------------+",
+    ];
+    let predicate = Pred::lt(column_expr!("part"), dec(1, 5, 4));
+    read_table_data_str(table, None, Some(predicate), expected)?;
+    Ok(())
+}
+
 #[test]
 fn timestamp_ntz() -> Result<(), Box<dyn std::error::Error>> {
     let expected = vec![
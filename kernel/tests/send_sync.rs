@@ -0,0 +1,35 @@
+//! Compile-time checks that the public API can be shared across threads, so multi-threaded engines
+//! can hand snapshots, scans, and their iterators to worker threads.
+
+use std::sync::Arc;
+
+use delta_kernel::scan::{Scan, ScanBuilder, ScanMetadata, ScanResult, SkippingExplanation};
+use delta_kernel::table_changes::scan::{TableChangesScan, TableChangesScanBuilder};
+use delta_kernel::table_changes::{TableChanges, TableChangesCommitMetadata, TableChangesFollower};
+use delta_kernel::transaction::Transaction;
+use delta_kernel::{Engine, Error, Snapshot};
+use static_assertions::assert_impl_all;
+
+assert_impl_all!(Snapshot: Send, Sync);
+assert_impl_all!(ScanBuilder: Send, Sync);
+assert_impl_all!(Scan: Send, Sync);
+assert_impl_all!(ScanMetadata: Send, Sync);
+assert_impl_all!(ScanResult: Send, Sync);
+assert_impl_all!(SkippingExplanation: Send, Sync);
+assert_impl_all!(TableChanges: Send, Sync);
+assert_impl_all!(TableChangesScanBuilder: Send, Sync);
+assert_impl_all!(TableChangesScan: Send, Sync);
+assert_impl_all!(TableChangesCommitMetadata: Send, Sync);
+assert_impl_all!(TableChangesFollower: Send, Sync);
+assert_impl_all!(Transaction: Send, Sync);
+assert_impl_all!(Error: Send, Sync);
+
+fn assert_send<T: Send>(_: &T) {}
+
+// Never called: the iterators are opaque types, so they can only be checked on values.
+#[allow(unused)]
+fn iterators_are_send(scan: &Scan, cdf_scan: &TableChangesScan, engine: Arc<dyn Engine>) {
+    assert_send(&scan.scan_metadata(engine.as_ref()));
+    assert_send(&scan.execute(engine.clone()));
+    assert_send(&cdf_scan.execute(engine));
+}
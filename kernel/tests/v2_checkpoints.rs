@@ -1,7 +1,18 @@
-use delta_kernel::arrow::array::RecordBatch;
+use std::sync::Arc;
+
+use delta_kernel::arrow::array::{
+    new_null_array, Array as _, ArrayRef, AsArray as _, RecordBatch, StructArray,
+};
+use delta_kernel::arrow::compute::{cast, filter_record_batch};
+use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Schema};
+use delta_kernel::arrow::error::ArrowError;
+use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::engine_data::FilteredEngineData;
+use delta_kernel::parquet::arrow::ArrowWriter;
+use delta_kernel::table_properties::CheckpointPolicy;
 
-use delta_kernel::{DeltaResult, Snapshot};
+use delta_kernel::{DeltaResult, Error, FileMeta, Snapshot};
 
 mod common;
 use common::load_test_data;
@@ -227,3 +238,153 @@ fn v2_checkpoints_parquet_with_last_checkpoint() -> DeltaResult<()> {
         get_simple_id_table(),
     )
 }
+
+/// A table with `delta.checkpointPolicy = v2` must not be checkpointed with the `classic` policy.
+#[test]
+fn classic_checkpoint_policy_conflicts_with_v2_table() -> DeltaResult<()> {
+    let table_name = "v2-classic-checkpoint-parquet";
+    let test_dir = load_test_data("tests/data", table_name).unwrap();
+    let test_path = test_dir.path().join(table_name);
+    let table_uri = test_path.to_str().expect("table path to string");
+    let engine = DefaultEngine::new_local();
+
+    let snapshot = Arc::new(Snapshot::try_from_uri(table_uri, engine.as_ref(), None)?);
+    let writer = snapshot.checkpoint()?;
+    assert_eq!(writer.policy(), CheckpointPolicy::V2);
+    let err = writer
+        .with_policy(CheckpointPolicy::Classic)
+        .err()
+        .expect("classic policy should conflict with the table's v2 policy");
+    assert!(matches!(err, Error::CheckpointWrite(_)), "{err}");
+    Ok(())
+}
+
+/// Casts `column` to `data_type`, filling struct fields that `column` lacks (such as the `tags` of
+/// the sidecar actions kernel writes) with nulls.
+fn align_column(column: &ArrayRef, data_type: &ArrowDataType) -> Result<ArrayRef, ArrowError> {
+    match (column.as_struct_opt(), data_type) {
+        (Some(column), ArrowDataType::Struct(fields)) if column.num_columns() != fields.len() => {
+            let children = fields
+                .iter()
+                .map(|field| match column.column_by_name(field.name()) {
+                    Some(child) => align_column(child, field.data_type()),
+                    None => Ok(new_null_array(field.data_type(), column.len())),
+                })
+                .try_collect()?;
+            let nulls = column.nulls().cloned();
+            Ok(Arc::new(StructArray::try_new(
+                fields.clone(),
+                children,
+                nulls,
+            )?))
+        }
+        _ => cast(column, data_type),
+    }
+}
+
+/// Writes the selected rows of `data` to a parquet file at `path`. Batches may hold different
+/// columns, e.g. the sidecar and checkpointMetadata actions of a V2 checkpoint, so each one is
+/// padded with nulls to the union of their columns.
+fn write_parquet_file(
+    path: &std::path::Path,
+    data: impl IntoIterator<Item = DeltaResult<FilteredEngineData>>,
+) -> DeltaResult<u64> {
+    let batches: Vec<_> = data
+        .into_iter()
+        .map(|data| {
+            let data = data?;
+            let batch = ArrowEngineData::try_from_engine_data(data.data)?;
+            Ok(filter_record_batch(
+                batch.record_batch(),
+                &data.selection_vector.into(),
+            )?)
+        })
+        .try_collect::<_, _, Error>()?;
+    let fields = batches
+        .iter()
+        .flat_map(|batch| batch.schema().fields().iter().cloned().collect_vec())
+        .unique_by(|field| field.name().clone())
+        .map(|field| field.as_ref().clone().with_nullable(true))
+        .collect_vec();
+    let schema = Arc::new(Schema::new(fields));
+    let file = std::fs::File::create(path)?;
+    let mut parquet_writer = ArrowWriter::try_new(file, schema.clone(), None)?;
+    for batch in batches {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| match batch.column_by_name(field.name()) {
+                Some(column) => align_column(column, field.data_type()),
+                None => Ok(new_null_array(field.data_type(), batch.num_rows())),
+            })
+            .collect::<Result<_, _>>()?;
+        parquet_writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+    }
+    parquet_writer.close()?;
+    Ok(std::fs::metadata(path)?.len())
+}
+
+#[test]
+fn write_v2_checkpoint_with_sidecars() -> DeltaResult<()> {
+    let table_name = "v2-checkpoints-parquet-with-sidecars";
+    let test_dir = load_test_data("tests/data", table_name).unwrap();
+    let test_path = test_dir.path().join(table_name);
+    let table_uri = test_path.to_str().expect("table path to string");
+    let engine = DefaultEngine::new_local();
+
+    let snapshot = Arc::new(Snapshot::try_from_uri(table_uri, engine.as_ref(), None)?);
+    let writer = snapshot.checkpoint()?.with_sidecars(1)?;
+
+    let mut sidecar_data = writer.sidecar_data(engine.as_ref())?;
+    let mut sidecar_files = vec![];
+    for sidecar in sidecar_data.by_ref() {
+        let sidecar = sidecar?;
+        let size = write_parquet_file(
+            &sidecar.path.to_file_path().unwrap(),
+            sidecar.data.into_iter().map(Ok),
+        )?;
+        sidecar_files.push(FileMeta {
+            location: sidecar.path,
+            last_modified: 0,
+            size,
+        });
+    }
+    assert!(sidecar_files.len() > 1, "{sidecar_files:?}");
+
+    let mut checkpoint_data =
+        writer.checkpoint_data_with_sidecars(engine.as_ref(), sidecar_data, &sidecar_files)?;
+    // The table's existing checkpoint of this version is UUID-named, so ours does not replace it.
+    let checkpoint_path = writer.checkpoint_path()?;
+    let size = write_parquet_file(
+        &checkpoint_path.to_file_path().unwrap(),
+        checkpoint_data.by_ref(),
+    )?;
+    let metadata = FileMeta {
+        location: checkpoint_path,
+        last_modified: 0,
+        size,
+    };
+    writer.finalize(engine.as_ref(), &metadata, checkpoint_data)?;
+
+    let last_checkpoint = std::fs::read(test_path.join("_delta_log/_last_checkpoint"))?;
+    let last_checkpoint: serde_json::Value = serde_json::from_slice(&last_checkpoint)?;
+    assert_eq!(last_checkpoint["version"], 6);
+    assert_eq!(last_checkpoint["sizeInBytes"], size);
+
+    // Remove the existing checkpoint, so that the table is read from ours and its sidecars.
+    for entry in std::fs::read_dir(test_path.join("_delta_log"))? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        if name.starts_with("00000000000000000006.checkpoint.")
+            && name != "00000000000000000006.checkpoint.parquet"
+        {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    let mut expected = generate_sidecar_expected_data();
+    sort_lines!(expected);
+    let snapshot = Snapshot::try_from_uri(table_uri, engine.as_ref(), None)?;
+    let batches = read_scan(&snapshot.into_scan_builder().build()?, engine)?;
+    assert_batches_sorted_eq!(expected, &batches);
+    Ok(())
+}
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use delta_kernel::arrow::array::{
-    Int32Array, MapBuilder, MapFieldNames, StringArray, StringBuilder, TimestampMicrosecondArray,
+    ArrayRef, Int32Array, MapBuilder, MapFieldNames, StringArray, StringBuilder, StructArray,
+    TimestampMicrosecondArray,
 };
 use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
 use delta_kernel::arrow::error::ArrowError;
@@ -13,13 +14,23 @@ use delta_kernel::object_store::ObjectStore;
 use itertools::Itertools;
 use serde_json::json;
 use serde_json::Deserializer;
+use url::Url;
+use uuid::Uuid;
 
-use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
+use delta_kernel::clock::{FixedClock, SequentialIdGenerator};
+use delta_kernel::engine::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
 use delta_kernel::engine::arrow_data::ArrowEngineData;
-use delta_kernel::schema::{DataType, StructField, StructType};
-use delta_kernel::DeltaResult;
-use delta_kernel::Error as KernelError;
+use delta_kernel::expressions::{column_expr, Expression as Expr, Predicate as Pred, Scalar};
+use delta_kernel::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use delta_kernel::schema::SchemaRef;
+use delta_kernel::schema::{DataType, MetadataValue, StructField, StructType};
+use delta_kernel::transaction::CommitResult;
 use delta_kernel::Snapshot;
+use delta_kernel::{DeltaResult, Engine, EngineData, FileMeta, JsonHandler, PredicateRef};
+use delta_kernel::{
+    Error as KernelError, EvaluationHandler, FileDataReadResultIterator, ParquetHandler,
+    StorageHandler,
+};
 
 use test_utils::{create_table, engine_store_setup, setup_test_tables};
 
@@ -99,6 +110,13 @@ async fn test_commit_info() -> Result<(), Box<dyn std::error::Error>> {
             .unwrap()
             .get_mut("timestamp")
             .unwrap() = serde_json::Value::Number(0.into());
+        // the txnId is a random UUID
+        let txn_id = parsed_commit["commitInfo"]
+            .as_object_mut()
+            .unwrap()
+            .remove("txnId")
+            .unwrap();
+        assert!(Uuid::parse_str(txn_id.as_str().unwrap()).is_ok());
 
         let expected_commit = json!({
             "commitInfo": {
@@ -117,6 +135,44 @@ async fn test_commit_info() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_commit_info_engine_info_and_user_metadata() -> Result<(), Box<dyn std::error::Error>>
+{
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a simple table: one int column named 'number'
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in setup_test_tables(schema, &[]).await? {
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let txn = snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_engine_info("test-engine/1.0")
+            .with_user_metadata("nightly backfill");
+        txn.commit(&engine)?;
+
+        let commit1 = store
+            .get(&Path::from(format!(
+                "/{table_name}/_delta_log/00000000000000000001.json"
+            )))
+            .await?;
+        let parsed_commit: serde_json::Value = serde_json::from_slice(&commit1.bytes().await?)?;
+        let commit_info = &parsed_commit["commitInfo"];
+        assert_eq!(commit_info["engineInfo"], "test-engine/1.0");
+        assert_eq!(commit_info["userMetadata"], "nightly backfill");
+        assert_eq!(
+            commit_info["engineCommitInfo"],
+            json!({ "engineInfo": "default engine" })
+        );
+    }
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_empty_commit() -> Result<(), Box<dyn std::error::Error>> {
     // setup tracing
@@ -320,6 +376,7 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
         // set timestamps to 0 and paths to known string values for comparison
         // (otherwise timestamps are non-deterministic and paths are random UUIDs)
         set_value(&mut parsed_commits[0], "commitInfo.timestamp", json!(0))?;
+        set_value(&mut parsed_commits[0], "commitInfo.txnId", json!("txn"))?;
         set_value(&mut parsed_commits[1], "add.modificationTime", json!(0))?;
         set_value(&mut parsed_commits[1], "add.path", json!("first.parquet"))?;
         set_value(&mut parsed_commits[2], "add.modificationTime", json!(0))?;
@@ -332,6 +389,7 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "operation": "UNKNOWN",
                     "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                     "operationParameters": {},
+                    "txnId": "txn",
                     "engineCommitInfo": {
                         "engineInfo": "default engine"
                     }
@@ -343,7 +401,8 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "partitionValues": {},
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": "{\"numRecords\":3}"
                 }
             }),
             json!({
@@ -352,7 +411,8 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "partitionValues": {},
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": "{\"numRecords\":3}"
                 }
             }),
         ];
@@ -457,6 +517,7 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
         // set timestamps to 0 and paths to known string values for comparison
         // (otherwise timestamps are non-deterministic and paths are random UUIDs)
         set_value(&mut parsed_commits[0], "commitInfo.timestamp", json!(0))?;
+        set_value(&mut parsed_commits[0], "commitInfo.txnId", json!("txn"))?;
         set_value(&mut parsed_commits[1], "add.modificationTime", json!(0))?;
         set_value(&mut parsed_commits[1], "add.path", json!("first.parquet"))?;
         set_value(&mut parsed_commits[2], "add.modificationTime", json!(0))?;
@@ -469,6 +530,7 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     "operation": "UNKNOWN",
                     "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                     "operationParameters": {},
+                    "txnId": "txn",
                     "engineCommitInfo": {
                         "engineInfo": "default engine"
                     }
@@ -482,7 +544,8 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     },
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": "{\"numRecords\":3}"
                 }
             }),
             json!({
@@ -493,7 +556,8 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     },
                     "size": size,
                     "modificationTime": 0,
-                    "dataChange": true
+                    "dataChange": true,
+                    "stats": "{\"numRecords\":3}"
                 }
             }),
         ];
@@ -515,6 +579,231 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_append_partitioned_dirs() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let table_schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let data_schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(table_schema.clone(), &["partition"]).await?
+    {
+        let engine = Arc::new(engine);
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let write_context = txn.get_write_context();
+
+        // one partition value which needs escaping, and one null
+        let partition_vals = [Scalar::from("a:b"), Scalar::Null(DataType::STRING)];
+        for (data, partition_val) in [vec![1, 2], vec![3]].into_iter().zip(partition_vals) {
+            let data = RecordBatch::try_new(
+                Arc::new(data_schema.as_ref().try_into_arrow()?),
+                vec![Arc::new(Int32Array::from(data))],
+            )?;
+            let add_files_metadata = engine
+                .write_partitioned_parquet(
+                    &ArrowEngineData::new(data),
+                    &write_context,
+                    HashMap::from([("partition".to_string(), partition_val)]),
+                    true,
+                )
+                .await?;
+            txn.add_files(add_files_metadata);
+        }
+        txn.commit(engine.as_ref())?;
+
+        let commit1 = store
+            .get(&Path::from(format!(
+                "/{table_name}/_delta_log/00000000000000000001.json"
+            )))
+            .await?;
+        let parsed_commits: Vec<serde_json::Value> =
+            Deserializer::from_slice(&commit1.bytes().await?)
+                .into_iter()
+                .try_collect()?;
+        let adds = &parsed_commits[1..];
+        assert_eq!(
+            adds[0]["add"]["partitionValues"],
+            json!({"partition": "a:b"})
+        );
+        assert_eq!(
+            adds[1]["add"]["partitionValues"],
+            json!({"partition": null})
+        );
+        let expected_dirs = [
+            "partition=a%253Ab/",
+            "partition=__HIVE_DEFAULT_PARTITION__/",
+        ];
+        for (add, dir) in adds.iter().zip(expected_dirs) {
+            let path = add["add"]["path"].as_str().unwrap();
+            let expected_prefix = table_url.join(dir)?;
+            assert!(path.starts_with(expected_prefix.as_str()), "{path}");
+        }
+
+        // the escaped directory name is stored as-is
+        let files: Vec<_> = futures::TryStreamExt::try_collect(
+            store.list(Some(&Path::parse(format!("{table_name}/partition=a%3Ab"))?)),
+        )
+        .await?;
+        assert_eq!(files.len(), 1);
+
+        test_read(
+            &ArrowEngineData::new(RecordBatch::try_new(
+                Arc::new(table_schema.as_ref().try_into_arrow()?),
+                vec![
+                    Arc::new(Int32Array::from(vec![1, 2, 3])),
+                    Arc::new(StringArray::from(vec![Some("a:b"), Some("a:b"), None])),
+                ],
+            )?),
+            &table_url,
+            engine.clone(),
+        )?;
+
+        // partition values that don't match the partition schema are rejected
+        let data = RecordBatch::try_new(
+            Arc::new(data_schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(vec![4]))],
+        )?;
+        let res = engine
+            .write_partitioned_parquet(
+                &ArrowEngineData::new(data),
+                &write_context,
+                HashMap::from([("partition".to_string(), Scalar::from(4))]),
+                true,
+            )
+            .await;
+        assert!(res.is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_column_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let field = |name: &str, data_type: DataType, id: i64| {
+        StructField::nullable(name, data_type).with_metadata([
+            ("delta.columnMapping.id", MetadataValue::Number(id)),
+            (
+                "delta.columnMapping.physicalName",
+                MetadataValue::String(format!("col-{id}")),
+            ),
+        ])
+    };
+    let nested_type = StructType::new(vec![field("value", DataType::INTEGER, 4)]);
+    let table_schema = Arc::new(StructType::new(vec![
+        field("number", DataType::INTEGER, 1),
+        field("nested", nested_type.clone().into(), 2),
+        field("partition", DataType::STRING, 3),
+    ]));
+    let data_schema = Arc::new(table_schema.project_as_struct(&["number", "nested"])?);
+
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let commit = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["columnMapping"],
+                "writerFeatures": ["columnMapping"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&table_schema)?,
+                "partitionColumns": ["partition"],
+                "configuration": {
+                    "delta.columnMapping.mode": "name",
+                    "delta.columnMapping.maxColumnId": "4"
+                },
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    let engine = Arc::new(engine);
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let write_context = txn.get_write_context();
+    let number: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+    let nested: ArrayRef = Arc::new(StructArray::try_new(
+        ArrowSchema::try_from_kernel(&nested_type)?.fields().clone(),
+        vec![Arc::new(Int32Array::from(vec![10, 20]))],
+        None,
+    )?);
+    let data = RecordBatch::try_new(
+        Arc::new(data_schema.as_ref().try_into_arrow()?),
+        vec![number.clone(), nested.clone()],
+    )?;
+    let add_files_metadata = engine
+        .write_partitioned_parquet(
+            &ArrowEngineData::new(data),
+            &write_context,
+            HashMap::from([("partition".to_string(), Scalar::from("a"))]),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(engine.as_ref())?;
+
+    // partition values and directories use the physical name of the partition column
+    let commit1 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000001.json",
+        ))
+        .await?;
+    let parsed_commits: Vec<serde_json::Value> = Deserializer::from_slice(&commit1.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    let add = &parsed_commits[1]["add"];
+    assert_eq!(add["partitionValues"], json!({"col-3": "a"}));
+    let path = add["path"].as_str().unwrap();
+    assert!(
+        path.starts_with(table_url.join("col-3=a/")?.as_str()),
+        "{path}"
+    );
+
+    // the data file has the physical column names
+    let file = store
+        .get(&Path::from_url_path(Url::parse(path)?.path())?)
+        .await?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file.bytes().await?)?;
+    let file_schema = reader.schema();
+    let nested_field = file_schema.field_with_name("col-2")?;
+    let ArrowDataType::Struct(nested_fields) = nested_field.data_type() else {
+        panic!("expected a struct, got {nested_field:?}");
+    };
+    assert_eq!(nested_fields[0].name(), "col-4");
+    assert!(file_schema.field_with_name("col-1").is_ok());
+
+    test_read(
+        &ArrowEngineData::new(RecordBatch::try_new(
+            Arc::new(table_schema.as_ref().try_into_arrow()?),
+            vec![number, nested, Arc::new(StringArray::from(vec!["a", "a"]))],
+        )?),
+        &table_url,
+        engine,
+    )?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_append_invalid_schema() -> Result<(), Box<dyn std::error::Error>> {
     // setup tracing
@@ -637,6 +926,7 @@ async fn test_write_txn_actions() -> Result<(), Box<dyn std::error::Error>> {
             .unwrap()
             .get_mut("timestamp")
             .unwrap() = serde_json::Value::Number(0.into());
+        set_value(&mut parsed_commits[0], "commitInfo.txnId", json!("txn"))?;
 
         let time_ms: i64 = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
@@ -682,6 +972,7 @@ async fn test_write_txn_actions() -> Result<(), Box<dyn std::error::Error>> {
                     "operation": "UNKNOWN",
                     "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
                     "operationParameters": {},
+                    "txnId": "txn",
                     "engineCommitInfo": {
                         "engineInfo": "default engine"
                     }
@@ -791,3 +1082,1385 @@ async fn test_append_timestamp_ntz() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_remove_files_matching() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let partition_col = "partition";
+    let table_schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let data_schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(table_schema.clone(), &[partition_col]).await?
+    {
+        // write one file to each of partitions 'a' and 'b'
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let write_context = txn.get_write_context();
+        for (data, partition_val) in [([1, 2, 3], "a"), ([4, 5, 6], "b")] {
+            let data = RecordBatch::try_new(
+                Arc::new(data_schema.as_ref().try_into_arrow()?),
+                vec![Arc::new(Int32Array::from(data.to_vec()))],
+            )?;
+            let add_meta = engine
+                .write_parquet(
+                    &ArrowEngineData::new(data),
+                    &write_context,
+                    HashMap::from([(partition_col.to_string(), partition_val.to_string())]),
+                    true,
+                )
+                .await?;
+            txn.add_files(add_meta);
+        }
+        txn.commit(&engine)?;
+
+        // a predicate on partition columns only removes exactly the files in that partition
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let predicate = Pred::eq(column_expr!("partition"), Expr::literal("a"));
+        let matching = txn.remove_files_matching(&engine, Arc::new(predicate))?;
+        assert_eq!(matching.exact.len(), 1);
+        assert!(matching.candidates.is_empty());
+        txn.commit(&engine)?;
+
+        // the remove action carries over the stats of the removed file
+        let commit = store
+            .get(&Path::from(format!(
+                "{table_name}/_delta_log/00000000000000000002.json"
+            )))
+            .await?;
+        let removes: Vec<_> = Deserializer::from_slice(&commit.bytes().await?)
+            .into_iter::<serde_json::Value>()
+            .filter_map_ok(|action| action.get("remove").cloned())
+            .try_collect()?;
+        assert_eq!(removes.len(), 1);
+        let stats: serde_json::Value = serde_json::from_str(removes[0]["stats"].as_str().unwrap())?;
+        assert_eq!(stats["numRecords"], 3);
+        assert_eq!(removes[0]["tags"], json!({}));
+
+        // a predicate which evaluates to null on the partition values doesn't match every row
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let predicate = Pred::eq(
+            column_expr!("partition"),
+            Expr::null_literal(DataType::STRING),
+        );
+        let matching = txn.remove_files_matching(&engine, Arc::new(predicate))?;
+        assert!(matching.exact.is_empty());
+
+        let engine = Arc::new(engine);
+        test_read(
+            &ArrowEngineData::new(RecordBatch::try_new(
+                Arc::new(table_schema.as_ref().try_into_arrow()?),
+                vec![
+                    Arc::new(Int32Array::from(vec![4, 5, 6])),
+                    Arc::new(StringArray::from(vec!["b", "b", "b"])),
+                ],
+            )?),
+            &table_url,
+            engine.clone(),
+        )?;
+
+        // a predicate on data columns only finds candidates, which must be confirmed
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let predicate = Pred::gt(column_expr!("number"), Expr::literal(5));
+        let matching = txn.remove_files_matching(engine.as_ref(), Arc::new(predicate))?;
+        assert!(matching.exact.is_empty());
+        assert_eq!(matching.candidates.len(), 1);
+        assert!(matches!(
+            txn.commit(engine.as_ref()).unwrap_err(),
+            KernelError::Generic(_)
+        ));
+
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let predicate = Pred::gt(column_expr!("number"), Expr::literal(5));
+        txn.remove_files_matching(engine.as_ref(), Arc::new(predicate))?;
+        txn.confirm_exact();
+        txn.commit(engine.as_ref())?;
+
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let scan = snapshot.scan_builder().build()?;
+        assert_eq!(scan.execute(engine.clone())?.count(), 0);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_version_checksum() -> Result<(), Box<dyn std::error::Error>> {
+    async fn read_crc(
+        store: &dyn ObjectStore,
+        table_name: &str,
+        version: u64,
+    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let path = Path::from(format!("{table_name}/_delta_log/{version:020}.crc"));
+        let crc = store.get(&path).await?;
+        Ok(serde_json::from_slice(&crc.bytes().await?)?)
+    }
+    let partition_col = "partition";
+    let table_schema = Arc::new(StructType::new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("partition", DataType::STRING),
+    ]));
+    let data_schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(table_schema.clone(), &[partition_col]).await?
+    {
+        // checksum files are only written on top of the checksum file of the read snapshot
+        let crc = json!({"tableSizeBytes": 0, "numFiles": 0, "numMetadata": 1, "numProtocol": 1});
+        let path = Path::from(format!("{table_name}/_delta_log/00000000000000000000.crc"));
+        store.put(&path, crc.to_string().into()).await?;
+
+        // write one file to each of partitions 'a' and 'b'
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_version_checksum(true);
+        let write_context = txn.get_write_context();
+        for (data, partition_val) in [([1, 2, 3], "a"), ([4, 5, 6], "b")] {
+            let data = RecordBatch::try_new(
+                Arc::new(data_schema.as_ref().try_into_arrow()?),
+                vec![Arc::new(Int32Array::from(data.to_vec()))],
+            )?;
+            let add_meta = engine
+                .write_parquet(
+                    &ArrowEngineData::new(data),
+                    &write_context,
+                    HashMap::from([(partition_col.to_string(), partition_val.to_string())]),
+                    true,
+                )
+                .await?;
+            txn.add_files(add_meta);
+        }
+        let txn_id = txn.txn_id().to_string();
+        txn.commit(&engine)?;
+
+        // the sizes of the added files, by partition
+        let commit = store
+            .get(&Path::from(format!(
+                "{table_name}/_delta_log/00000000000000000001.json"
+            )))
+            .await?;
+        let sizes: HashMap<String, i64> = Deserializer::from_slice(&commit.bytes().await?)
+            .into_iter::<serde_json::Value>()
+            .filter_map_ok(|action| {
+                let add = action.get("add")?;
+                let partition = add["partitionValues"][partition_col].as_str()?.to_string();
+                Some((partition, add["size"].as_i64()?))
+            })
+            .try_collect()?;
+
+        let crc = read_crc(store.as_ref(), table_name, 1).await?;
+        assert_eq!(crc["txnId"], txn_id);
+        assert_eq!(crc["numFiles"], 2);
+        assert_eq!(crc["tableSizeBytes"], sizes["a"] + sizes["b"]);
+        assert_eq!(crc["numMetadata"], 1);
+        assert_eq!(crc["numProtocol"], 1);
+        assert_eq!(crc["metadata"]["id"], "test_id");
+        assert_eq!(crc["metadata"]["partitionColumns"], json!([partition_col]));
+
+        // remove the file in partition 'a'
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_version_checksum(true);
+        let predicate = Pred::eq(column_expr!("partition"), Expr::literal("a"));
+        txn.remove_files_matching(&engine, Arc::new(predicate))?;
+        txn.commit(&engine)?;
+
+        let crc = read_crc(store.as_ref(), table_name, 2).await?;
+        let size = sizes["b"];
+        assert_eq!(crc["numFiles"], 1);
+        assert_eq!(crc["tableSizeBytes"], size);
+
+        // the new snapshot picks up the file stats from the checksum file
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        assert_eq!(snapshot.clone().file_count(&engine)?, 1);
+        assert_eq!(snapshot.total_size_bytes(&engine)?, size);
+
+        // no checksum file is written by default
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .commit(&engine)?;
+        assert!(read_crc(store.as_ref(), table_name, 3).await.is_err());
+
+        // nor on top of a version without a checksum file
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_version_checksum(true)
+            .commit(&engine)?;
+        assert!(read_crc(store.as_ref(), table_name, 4).await.is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deterministic_commit() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, store, table_name) in setup_test_tables(schema.clone(), &[]).await? {
+        let engine = engine.with_id_generator(Arc::new(SequentialIdGenerator::new(42)));
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot
+            .transaction()?
+            .with_transaction_id("app".to_string(), 1)
+            .with_commit_info(new_commit_info()?)
+            .with_clock(Arc::new(FixedClock::new(1234)))?
+            .with_id_generator(Arc::new(SequentialIdGenerator::new(7)));
+
+        let data = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+        let add_meta = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_meta);
+        txn.commit(&engine)?;
+
+        let commit1 = store
+            .get(&Path::from(format!(
+                "/{table_name}/_delta_log/00000000000000000001.json"
+            )))
+            .await?;
+        let mut parsed_commits: Vec<_> = Deserializer::from_slice(&commit1.bytes().await?)
+            .into_iter::<serde_json::Value>()
+            .try_collect()?;
+
+        // the modification time comes from storage, everything else is deterministic
+        set_value(&mut parsed_commits[1], "add.modificationTime", json!(0))?;
+        let size = parsed_commits[1]["add"]["size"].clone();
+        let expected_commit = vec![
+            json!({
+                "commitInfo": {
+                    "timestamp": 1234,
+                    "operation": "UNKNOWN",
+                    "kernelVersion": format!("v{}", env!("CARGO_PKG_VERSION")),
+                    "operationParameters": {},
+                    "txnId": "00000000-0000-0007-0000-000000000000",
+                    "engineCommitInfo": {
+                        "engineInfo": "default engine"
+                    }
+                }
+            }),
+            json!({
+                "add": {
+                    "path": table_url.join("00000000-0000-002a-0000-000000000000.parquet")?.to_string(),
+                    "partitionValues": {},
+                    "size": size,
+                    "modificationTime": 0,
+                    "dataChange": true,
+                    "stats": "{\"numRecords\":3}"
+                }
+            }),
+            json!({
+                "txn": {
+                    "appId": "app",
+                    "version": 1,
+                    "lastUpdated": 1234
+                }
+            }),
+        ];
+        assert_eq!(parsed_commits, expected_commit);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remove_files_from_append_only_table() -> Result<(), Box<dyn std::error::Error>> {
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = serde_json::to_string(&StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]))?;
+    let commit = [
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema,
+                "partitionColumns": [],
+                "configuration": {"delta.appendOnly": "true"},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+    assert!(!snapshot.capabilities().delete);
+    let mut txn = snapshot.transaction()?;
+    let predicate = Pred::gt(column_expr!("number"), Expr::literal(5));
+    let err = txn
+        .remove_files_matching(&engine, Arc::new(predicate))
+        .unwrap_err();
+    assert!(err.to_string().contains("append-only"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_in_commit_timestamps() -> Result<(), Box<dyn std::error::Error>> {
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = serde_json::to_string(&StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]))?;
+    let commit = [
+        json!({"commitInfo": {"timestamp": 1000, "inCommitTimestamp": 1000}}),
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 7, "writerFeatures": ["inCommitTimestamp"]}}),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema,
+                "partitionColumns": [],
+                "configuration": {"delta.enableInCommitTimestamps": "true"},
+                "createdTime": 1000
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    // The in-commit timestamp is the commit timestamp, or one after the previous in-commit
+    // timestamp if the clock is behind it
+    for (version, now, expected_ict) in [(1, 500, 1001), (2, 5000, 5000)] {
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let txn = snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_clock(Arc::new(FixedClock::new(now)))?;
+        assert!(matches!(
+            txn.commit(&engine)?,
+            CommitResult::Committed(committed) if committed == version
+        ));
+        let commit = store
+            .get(&Path::from(format!(
+                "test_table/_delta_log/{version:020}.json"
+            )))
+            .await?;
+        let commit_info: serde_json::Value = serde_json::from_slice(&commit.bytes().await?)?;
+        assert_eq!(commit_info["commitInfo"]["timestamp"], now);
+        assert_eq!(commit_info["commitInfo"]["inCommitTimestamp"], expected_ict);
+    }
+
+    // Time travel resolves timestamps with the in-commit timestamps
+    let snapshot = Snapshot::try_new_at_timestamp(table_url, &engine, 4999)?;
+    assert_eq!(snapshot.version(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_domain_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    async fn read_actions(
+        store: &dyn ObjectStore,
+        version: u64,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let path = Path::from(format!("test_table/_delta_log/{version:020}.json"));
+        let commit = store.get(&path).await?;
+        Ok(Deserializer::from_slice(&commit.bytes().await?)
+            .into_iter::<serde_json::Value>()
+            .try_collect()?)
+    }
+
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = serde_json::to_string(&StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]))?;
+    let commit = [
+        json!({"commitInfo": {"timestamp": 1000}}),
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 7, "writerFeatures": ["domainMetadata"]}}),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1000
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+    // set two domains
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    snapshot
+        .transaction()?
+        .with_commit_info(new_commit_info()?)
+        .with_domain_metadata("app1".to_string(), "app1_config".to_string())
+        .with_domain_metadata("app2".to_string(), "app2_config".to_string())
+        .commit(&engine)?;
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    assert_eq!(
+        snapshot.get_domain_metadata("app1", &engine)?,
+        Some("app1_config".to_string())
+    );
+    assert_eq!(
+        snapshot.get_domain_metadata("app2", &engine)?,
+        Some("app2_config".to_string())
+    );
+
+    // update one domain and remove the other, which writes a tombstone with its configuration.
+    // Removing a domain that doesn't exist writes nothing.
+    snapshot
+        .transaction()?
+        .with_commit_info(new_commit_info()?)
+        .with_domain_metadata("app1".to_string(), "app1_config_v2".to_string())
+        .with_domain_metadata_removed("app2".to_string())
+        .with_domain_metadata_removed("app3".to_string())
+        .commit(&engine)?;
+    let domain_metadatas: Vec<_> = read_actions(store.as_ref(), 2)
+        .await?
+        .into_iter()
+        .filter_map(|action| action.get("domainMetadata").cloned())
+        .collect();
+    assert_eq!(
+        domain_metadatas,
+        vec![
+            json!({"domain": "app1", "configuration": "app1_config_v2", "removed": false}),
+            json!({"domain": "app2", "configuration": "app2_config", "removed": true}),
+        ]
+    );
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    assert_eq!(
+        snapshot.get_all_domain_metadata(&engine)?.collect_vec(),
+        vec![("app1".to_string(), "app1_config_v2".to_string())]
+    );
+
+    // system-controlled domains can't be modified, and each domain can only appear once
+    let err = snapshot
+        .clone()
+        .transaction()?
+        .with_commit_info(new_commit_info()?)
+        .with_domain_metadata("delta.app".to_string(), "config".to_string())
+        .commit(&engine)
+        .unwrap_err();
+    assert!(matches!(err, KernelError::Generic(msg) if msg.contains("system-controlled")));
+    let err = snapshot
+        .transaction()?
+        .with_commit_info(new_commit_info()?)
+        .with_domain_metadata("app1".to_string(), "config".to_string())
+        .with_domain_metadata_removed("app1".to_string())
+        .commit(&engine)
+        .unwrap_err();
+    assert!(matches!(err, KernelError::Generic(msg) if msg.contains("already exists")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_domain_metadata_requires_feature() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, _store, _table_name) in setup_test_tables(schema, &[]).await? {
+        let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+        let result = snapshot
+            .transaction()?
+            .with_commit_info(new_commit_info()?)
+            .with_domain_metadata("app".to_string(), "config".to_string())
+            .commit(&engine);
+        assert!(matches!(result, Err(KernelError::InvalidProtocol(_))));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_row_tracking() -> Result<(), Box<dyn std::error::Error>> {
+    async fn read_actions(
+        store: &dyn ObjectStore,
+        version: u64,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let path = Path::from(format!("test_table/_delta_log/{version:020}.json"));
+        let commit = store.get(&path).await?;
+        Ok(Deserializer::from_slice(&commit.bytes().await?)
+            .into_iter::<serde_json::Value>()
+            .try_collect()?)
+    }
+    // the (path, baseRowId, defaultRowCommitVersion) of each add action, and the domain metadata
+    fn row_tracking_actions(
+        actions: &[serde_json::Value],
+    ) -> (Vec<(String, i64, i64)>, Vec<serde_json::Value>) {
+        let adds = actions
+            .iter()
+            .filter_map(|action| {
+                let add = action.get("add")?;
+                Some((
+                    add["path"].as_str()?.to_string(),
+                    add["baseRowId"].as_i64()?,
+                    add["defaultRowCommitVersion"].as_i64()?,
+                ))
+            })
+            .collect();
+        let domain_metadata = actions
+            .iter()
+            .filter_map(|action| action.get("domainMetadata").cloned())
+            .collect();
+        (adds, domain_metadata)
+    }
+
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let commit = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors"],
+                "writerFeatures": ["deletionVectors", "domainMetadata", "rowTracking"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {"delta.enableDeletionVectors": "true"},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    let append = |batches: Vec<Vec<i32>>| async {
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        for numbers in batches {
+            let data = RecordBatch::try_new(
+                Arc::new(schema.as_ref().try_into_arrow()?),
+                vec![Arc::new(Int32Array::from(numbers))],
+            )?;
+            let add_meta = engine
+                .write_parquet(
+                    &ArrowEngineData::new(data),
+                    &txn.get_write_context(),
+                    HashMap::new(),
+                    true,
+                )
+                .await?;
+            txn.add_files(add_meta);
+        }
+        txn.commit(&engine)?;
+        Ok::<_, Box<dyn std::error::Error>>(())
+    };
+
+    // the files of the first commit are assigned consecutive row ids from 0
+    append(vec![vec![1, 2, 3], vec![4, 5]]).await?;
+    let (adds, domain_metadata) = row_tracking_actions(&read_actions(store.as_ref(), 1).await?);
+    let (first, second) = (adds[0].0.clone(), adds[1].0.clone());
+    assert_eq!(adds, vec![(first.clone(), 0, 1), (second.clone(), 3, 1)]);
+    assert_eq!(
+        domain_metadata,
+        vec![json!({
+            "domain": "delta.rowTracking",
+            "configuration": "{\"rowIdHighWaterMark\":4}",
+            "removed": false
+        })]
+    );
+
+    // ... and the files of later commits from the high water mark
+    append(vec![vec![6, 7, 8]]).await?;
+    let (adds, domain_metadata) = row_tracking_actions(&read_actions(store.as_ref(), 2).await?);
+    assert_eq!(adds[0].1, 5);
+    assert_eq!(adds[0].2, 2);
+    assert_eq!(
+        domain_metadata[0]["configuration"],
+        "{\"rowIdHighWaterMark\":7}"
+    );
+
+    // a file re-added with a deletion vector keeps its row ids
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let deleted_rows = HashMap::from([(second.clone(), [0].into_iter().collect())]);
+    let dv_file = txn.delete_rows(&engine, deleted_rows)?.unwrap();
+    store
+        .put(
+            &Path::from_url_path(dv_file.path.path())?,
+            dv_file.data.into(),
+        )
+        .await?;
+    txn.commit(&engine)?;
+    let actions = read_actions(store.as_ref(), 3).await?;
+    let (adds, domain_metadata) = row_tracking_actions(&actions);
+    assert_eq!(adds, vec![(second, 3, 1)]);
+    assert!(domain_metadata.is_empty());
+    let remove = actions
+        .iter()
+        .find_map(|action| action.get("remove"))
+        .unwrap();
+    assert_eq!(remove["baseRowId"], 3);
+    assert_eq!(remove["defaultRowCommitVersion"], 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_row_tracking_partitioned_column_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let field = |name: &str, data_type: DataType, id: i64| {
+        StructField::nullable(name, data_type).with_metadata([
+            ("delta.columnMapping.id", MetadataValue::Number(id)),
+            (
+                "delta.columnMapping.physicalName",
+                MetadataValue::String(format!("col-{id}")),
+            ),
+        ])
+    };
+    let table_schema = Arc::new(StructType::new(vec![
+        field("number", DataType::INTEGER, 1),
+        field("partition", DataType::STRING, 2),
+    ]));
+    let data_schema = Arc::new(table_schema.project_as_struct(&["number"])?);
+
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let commit = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["columnMapping"],
+                "writerFeatures": ["columnMapping", "domainMetadata", "rowTracking"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&table_schema)?,
+                "partitionColumns": ["partition"],
+                "configuration": {
+                    "delta.columnMapping.mode": "name",
+                    "delta.columnMapping.maxColumnId": "2"
+                },
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    let engine = Arc::new(engine);
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let write_context = txn.get_write_context();
+    let number: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+    let data = RecordBatch::try_new(
+        Arc::new(data_schema.as_ref().try_into_arrow()?),
+        vec![number.clone()],
+    )?;
+    let add_files_metadata = engine
+        .write_partitioned_parquet(
+            &ArrowEngineData::new(data),
+            &write_context,
+            HashMap::from([("partition".to_string(), Scalar::from("a"))]),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(engine.as_ref())?;
+
+    // the add action is assigned row ids, and keys its partition values by the physical name
+    let commit1 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000001.json",
+        ))
+        .await?;
+    let parsed_commits: Vec<serde_json::Value> = Deserializer::from_slice(&commit1.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    let add = parsed_commits
+        .iter()
+        .find_map(|action| action.get("add"))
+        .unwrap();
+    assert_eq!(add["partitionValues"], json!({"col-2": "a"}));
+    assert_eq!(add["baseRowId"], 0);
+    assert_eq!(add["defaultRowCommitVersion"], 1);
+
+    test_read(
+        &ArrowEngineData::new(RecordBatch::try_new(
+            Arc::new(table_schema.as_ref().try_into_arrow()?),
+            vec![number, Arc::new(StringArray::from(vec!["a", "a"]))],
+        )?),
+        &table_url,
+        engine,
+    )?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_check_constraints_and_generated_columns() -> Result<(), Box<dyn std::error::Error>> {
+    // writes a commit creating a table with the given constraints and generation expression of
+    // the `doubled` column
+    async fn create_constrained_table(
+        store: &dyn ObjectStore,
+        table_name: &str,
+        schema: &StructType,
+        constraints: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let commit = [
+            json!({
+                "protocol": {
+                    "minReaderVersion": 1,
+                    "minWriterVersion": 7,
+                    "writerFeatures": ["checkConstraints", "generatedColumns"]
+                }
+            }),
+            json!({
+                "metaData": {
+                    "id": "test_id",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": serde_json::to_string(schema)?,
+                    "partitionColumns": ["part"],
+                    "configuration": constraints,
+                    "createdTime": 1677811175819u64
+                }
+            }),
+        ]
+        .iter()
+        .map(|action| action.to_string())
+        .join("\n");
+        let path = Path::from(format!("{table_name}/_delta_log/00000000000000000000.json"));
+        store.put(&path, commit.into()).await?;
+        Ok(())
+    }
+
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("id", DataType::INTEGER),
+        StructField::nullable("doubled", DataType::INTEGER).with_metadata([(
+            "delta.generationExpression",
+            MetadataValue::String("ID * 2".to_string()),
+        )]),
+        StructField::nullable("part", DataType::STRING),
+    ]));
+    let data_schema = Arc::new(StructType::new(schema.fields().take(2).cloned()));
+    create_constrained_table(
+        store.as_ref(),
+        "test_table",
+        &schema,
+        json!({
+            "delta.constraints.positive": "id > 0",
+            "delta.constraints.valid_part": "part <> 'invalid'"
+        }),
+    )
+    .await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let write_context = txn.get_write_context();
+    let write = |ids: Vec<Option<i32>>, doubled: Vec<Option<i32>>, part: &str| {
+        let data = RecordBatch::try_new(
+            Arc::new(data_schema.as_ref().try_into_arrow().unwrap()),
+            vec![
+                Arc::new(Int32Array::from(ids)),
+                Arc::new(Int32Array::from(doubled)),
+            ],
+        );
+        let partition_values = HashMap::from([("part".to_string(), Scalar::from(part))]);
+        let engine = &engine;
+        let write_context = &write_context;
+        async move {
+            engine
+                .write_partitioned_parquet(
+                    &ArrowEngineData::new(data?),
+                    write_context,
+                    partition_values,
+                    true,
+                )
+                .await
+        }
+    };
+
+    // null check constraints are satisfied, and null generated values match null expressions
+    let add_meta = write(
+        vec![Some(1), Some(2), None],
+        vec![Some(2), Some(4), None],
+        "a",
+    )
+    .await?;
+    txn.add_files(add_meta);
+
+    let violations = [
+        (
+            vec![Some(1), Some(0)],
+            vec![Some(2), Some(0)],
+            "a",
+            "positive",
+        ),
+        (
+            vec![Some(1), Some(2)],
+            vec![Some(2), Some(5)],
+            "a",
+            "doubled",
+        ),
+        (vec![Some(1), Some(2)], vec![Some(2), None], "a", "doubled"),
+        (vec![Some(1)], vec![Some(2)], "invalid", "valid_part"),
+    ];
+    for (ids, doubled, part, violated) in violations {
+        let result = write(ids, doubled, part).await;
+        assert!(
+            matches!(&result, Err(KernelError::ConstraintViolation(msg)) if msg.contains(violated)),
+            "expected a violation of {violated}, got {:?}",
+            result.err()
+        );
+    }
+    assert!(matches!(txn.commit(&engine)?, CommitResult::Committed(1)));
+
+    // tables with constraints kernel cannot enforce are not writable
+    create_constrained_table(
+        store.as_ref(),
+        "unsupported_table",
+        &schema,
+        json!({"delta.constraints.recent": "year(current_date()) > 2000"}),
+    )
+    .await?;
+    let table_url = table_url.join("../unsupported_table/")?;
+    let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+    assert!(matches!(
+        snapshot.transaction(),
+        Err(KernelError::Unsupported(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_rows() -> Result<(), Box<dyn std::error::Error>> {
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let commit = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors"],
+                "writerFeatures": ["deletionVectors"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {"delta.enableDeletionVectors": "true"},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    // write a single file with the rows 1 to 5
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5]))],
+    )?;
+    let add_meta = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_meta);
+    txn.commit(&engine)?;
+    let commit1 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000001.json",
+        ))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit1.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    let path = actions[1]["add"]["path"].as_str().unwrap().to_string();
+
+    let engine = Arc::new(engine);
+    let expect_numbers = |numbers: Vec<i32>| -> Result<(), Box<dyn std::error::Error>> {
+        let expected = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(numbers))],
+        )?;
+        test_read(&ArrowEngineData::new(expected), &table_url, engine.clone())
+    };
+    let delete_rows = |rows: &[u64]| -> Result<_, Box<dyn std::error::Error>> {
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        let deleted_rows = HashMap::from([(path.clone(), rows.iter().copied().collect())]);
+        let dv_file = txn.delete_rows(engine.as_ref(), deleted_rows)?;
+        Ok((txn, dv_file))
+    };
+
+    // delete the first and fourth rows, and then the second and fourth: the new deletion vector is
+    // merged with the existing one
+    for (rows, expected) in [(vec![0, 3], vec![2, 3, 5]), (vec![1, 3], vec![3, 5])] {
+        let (txn, dv_file) = delete_rows(&rows)?;
+        let dv_file = dv_file.unwrap();
+        store
+            .put(
+                &Path::from_url_path(dv_file.path.path())?,
+                dv_file.data.into(),
+            )
+            .await?;
+        txn.commit(engine.as_ref())?;
+        expect_numbers(expected)?;
+    }
+
+    // the file is removed with its old deletion vector, and added back with the new one
+    let commit3 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000003.json",
+        ))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit3.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[1]["add"]["path"], path);
+    assert_eq!(actions[1]["add"]["deletionVector"]["cardinality"], 3);
+    assert_eq!(actions[2]["remove"]["path"], path);
+    assert_eq!(actions[2]["remove"]["deletionVector"]["cardinality"], 2);
+
+    // deleting deleted rows is a no-op, and only files of the table can be updated
+    let (_, dv_file) = delete_rows(&[0, 1])?;
+    assert!(dv_file.is_none());
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?;
+    let deleted_rows = HashMap::from([("missing.parquet".to_string(), [0].into_iter().collect())]);
+    let err = txn.delete_rows(engine.as_ref(), deleted_rows).unwrap_err();
+    assert!(err.to_string().contains("not in the table"), "{err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_rows_partitioned_column_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let field = |name: &str, data_type: DataType, id: i64| {
+        StructField::nullable(name, data_type).with_metadata([
+            ("delta.columnMapping.id", MetadataValue::Number(id)),
+            (
+                "delta.columnMapping.physicalName",
+                MetadataValue::String(format!("col-{id}")),
+            ),
+        ])
+    };
+    let table_schema = Arc::new(StructType::new(vec![
+        field("number", DataType::INTEGER, 1),
+        field("partition", DataType::STRING, 2),
+    ]));
+    let data_schema = Arc::new(table_schema.project_as_struct(&["number"])?);
+
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let commit = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["columnMapping", "deletionVectors"],
+                "writerFeatures": ["columnMapping", "deletionVectors"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&table_schema)?,
+                "partitionColumns": ["partition"],
+                "configuration": {
+                    "delta.columnMapping.mode": "name",
+                    "delta.columnMapping.maxColumnId": "2",
+                    "delta.enableDeletionVectors": "true"
+                },
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    // write a single file with the rows 1 to 3 in partition "a"
+    let engine = Arc::new(engine);
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let data = RecordBatch::try_new(
+        Arc::new(data_schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let add_files_metadata = engine
+        .write_partitioned_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            HashMap::from([("partition".to_string(), Scalar::from("a"))]),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(engine.as_ref())?;
+    let commit1 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000001.json",
+        ))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit1.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    let path = actions[1]["add"]["path"].as_str().unwrap().to_string();
+
+    // delete the second row
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    let deleted_rows = HashMap::from([(path.clone(), [1].into_iter().collect())]);
+    let dv_file = txn.delete_rows(engine.as_ref(), deleted_rows)?.unwrap();
+    store
+        .put(
+            &Path::from_url_path(dv_file.path.path())?,
+            dv_file.data.into(),
+        )
+        .await?;
+    txn.commit(engine.as_ref())?;
+
+    // the file is added back with its partition value keyed by the physical name
+    let commit2 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000002.json",
+        ))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit2.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    let add = actions.iter().find_map(|action| action.get("add")).unwrap();
+    assert_eq!(add["path"], path);
+    assert_eq!(add["partitionValues"], json!({"col-2": "a"}));
+
+    test_read(
+        &ArrowEngineData::new(RecordBatch::try_new(
+            Arc::new(table_schema.as_ref().try_into_arrow()?),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 3])),
+                Arc::new(StringArray::from(vec!["a", "a"])),
+            ],
+        )?),
+        &table_url,
+        engine,
+    )?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_rows_keeps_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let (store, engine, table_url) = engine_store_setup("test_table", true);
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let add = |path: &str| {
+        json!({
+            "add": {
+                "path": path,
+                "partitionValues": {},
+                "size": 100,
+                "modificationTime": 1677811178336u64,
+                "dataChange": true,
+                "stats": "{\"numRecords\":5}",
+                "tags": {"origin": path}
+            }
+        })
+    };
+    let commit = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors"],
+                "writerFeatures": ["deletionVectors"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {"delta.enableDeletionVectors": "true"},
+                "createdTime": 1677811175819u64
+            }
+        }),
+        add("a.parquet"),
+        add("b.parquet"),
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .join("\n");
+    store
+        .put(
+            &Path::from("test_table/_delta_log/00000000000000000000.json"),
+            commit.into(),
+        )
+        .await?;
+
+    // rows of different files may be deleted by separate calls in one transaction
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+    assert!(snapshot.capabilities().delete_rows);
+    let mut txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+    for path in ["a.parquet", "b.parquet"] {
+        let deleted_rows = HashMap::from([(path.to_string(), [0].into_iter().collect())]);
+        let dv_file = txn.delete_rows(&engine, deleted_rows)?.unwrap();
+        store
+            .put(
+                &Path::from_url_path(dv_file.path.path())?,
+                dv_file.data.into(),
+            )
+            .await?;
+    }
+    txn.commit(&engine)?;
+
+    let commit1 = store
+        .get(&Path::from(
+            "test_table/_delta_log/00000000000000000001.json",
+        ))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit1.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    let adds: Vec<_> = actions
+        .iter()
+        .filter_map(|action| action.get("add"))
+        .collect();
+    assert_eq!(adds.len(), 2);
+    for add in adds {
+        assert_eq!(add["tags"], json!({"origin": add["path"]}));
+        assert_eq!(add["deletionVector"]["cardinality"], 1);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_rows_requires_deletion_vectors() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, _store, _table_name) in setup_test_tables(schema, &[]).await? {
+        let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+        let capabilities = snapshot.capabilities();
+        assert!(capabilities.delete && !capabilities.delete_rows);
+        let mut txn = snapshot.transaction()?;
+        let deleted_rows = HashMap::from([("file.parquet".to_string(), [0].into_iter().collect())]);
+        let err = txn.delete_rows(&engine, deleted_rows).unwrap_err();
+        assert!(
+            err.to_string().contains("delta.enableDeletionVectors"),
+            "{err}"
+        );
+    }
+    Ok(())
+}
+
+// An engine whose commit writes fail with a timeout. If `write` is set, the commit file is written
+// before the timeout, as when the response to a successful request is lost.
+struct TimeoutEngine {
+    inner: Arc<dyn Engine>,
+    write: bool,
+}
+
+struct TimeoutJsonHandler {
+    inner: Arc<dyn JsonHandler>,
+    write: bool,
+}
+
+impl JsonHandler for TimeoutJsonHandler {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.inner.parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        self.inner
+            .read_json_files(files, physical_schema, predicate)
+    }
+
+    fn write_json_file(
+        &self,
+        path: &Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+        overwrite: bool,
+    ) -> DeltaResult<()> {
+        if self.write {
+            self.inner.write_json_file(path, data, overwrite)?;
+        }
+        Err(KernelError::generic("request timed out"))
+    }
+}
+
+impl Engine for TimeoutEngine {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.inner.evaluation_handler()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+        self.inner.storage_handler()
+    }
+
+    fn json_handler(&self) -> Arc<dyn JsonHandler> {
+        Arc::new(TimeoutJsonHandler {
+            inner: self.inner.json_handler(),
+            write: self.write,
+        })
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+        self.inner.parquet_handler()
+    }
+}
+
+#[tokio::test]
+async fn test_commit_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+
+    for (table_url, engine, _store, _table_name) in setup_test_tables(schema, &[]).await? {
+        let engine: Arc<dyn Engine> = Arc::new(engine);
+
+        // the commit was not written: the timeout is reported
+        let timeout_engine = TimeoutEngine {
+            inner: engine.clone(),
+            write: false,
+        };
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let txn = snapshot
+            .clone()
+            .transaction()?
+            .with_commit_info(new_commit_info()?);
+        let err = txn.commit(&timeout_engine).unwrap_err();
+        assert!(err.to_string().contains("request timed out"), "{err}");
+
+        // the commit was written before the timeout: the commit succeeded
+        let timeout_engine = TimeoutEngine {
+            inner: engine.clone(),
+            write: true,
+        };
+        let txn = snapshot
+            .clone()
+            .transaction()?
+            .with_commit_info(new_commit_info()?);
+        let txn_id = txn.txn_id();
+        assert!(matches!(
+            txn.commit(&timeout_engine)?,
+            CommitResult::Committed(1)
+        ));
+        let history = Snapshot::try_new(table_url.clone(), engine.as_ref(), Some(1))?;
+        assert_eq!(history.version(), 1);
+
+        // another transaction wrote the commit: the timeout is a conflict
+        let timeout_engine = TimeoutEngine {
+            inner: engine.clone(),
+            write: false,
+        };
+        let txn = snapshot.transaction()?.with_commit_info(new_commit_info()?);
+        assert_ne!(txn.txn_id(), txn_id);
+        assert!(matches!(
+            txn.commit(&timeout_engine)?,
+            CommitResult::Conflict(_, 1)
+        ));
+    }
+    Ok(())
+}
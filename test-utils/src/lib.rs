@@ -1,5 +1,6 @@
 //! A number of utilities useful for testing that we want to use in multiple crates
 
+use std::borrow::Cow;
 use std::sync::Arc;
 
 use delta_kernel::arrow::array::{
@@ -9,6 +10,7 @@ use delta_kernel::arrow::array::{
 use delta_kernel::arrow::compute::filter_record_batch;
 use delta_kernel::arrow::error::ArrowError;
 use delta_kernel::arrow::util::pretty::pretty_format_batches;
+use delta_kernel::engine::arrow_conversion::TryFromArrow as _;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::object_store::local::LocalFileSystem;
 use delta_kernel::object_store::memory::InMemory;
@@ -16,7 +18,7 @@ use delta_kernel::object_store::{path::Path, ObjectStore};
 use delta_kernel::parquet::arrow::arrow_writer::ArrowWriter;
 use delta_kernel::parquet::file::properties::WriterProperties;
 use delta_kernel::scan::Scan;
-use delta_kernel::schema::SchemaRef;
+use delta_kernel::schema::{PrimitiveType, SchemaRef, SchemaTransform, StructType};
 use delta_kernel::{DeltaResult, Engine, EngineData, Snapshot};
 
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
@@ -330,6 +332,92 @@ pub async fn setup_test_tables(
     ])
 }
 
+/// Schema visitor that checks if any column in the schema uses the TIMESTAMP_NTZ type
+struct UsesTimestampNtz(bool);
+
+impl<'a> SchemaTransform<'a> for UsesTimestampNtz {
+    fn transform_primitive(&mut self, ptype: &'a PrimitiveType) -> Option<Cow<'a, PrimitiveType>> {
+        if *ptype == PrimitiveType::TimestampNtz {
+            self.0 = true;
+        }
+        None
+    }
+}
+
+/// Materialize a complete Delta table in a new in-memory object store, with one parquet file per
+/// batch in `batches` (all of which must have the same schema), and return an engine for the store
+/// along with the table's url. The table is written as a single commit (version 1) after the
+/// protocol and metadata commit, and is not partitioned.
+///
+/// Useful for examples and quick experiments, see also [`memory_table!`].
+pub async fn memory_table(
+    batches: Vec<RecordBatch>,
+) -> Result<(Arc<DefaultEngine<TokioBackgroundExecutor>>, Url), Box<dyn std::error::Error>> {
+    let Some(first) = batches.first() else {
+        return Err("memory_table requires at least one batch".into());
+    };
+    let schema: SchemaRef = Arc::new(StructType::try_from_arrow(first.schema())?);
+    let (store, engine, table_url) = engine_store_setup("memory_table", true);
+    let mut uses_timestamp_ntz = UsesTimestampNtz(false);
+    let _ = uses_timestamp_ntz.transform_struct(&schema);
+    let use_timestamp_ntz = uses_timestamp_ntz.0;
+    create_table(
+        store.clone(),
+        table_url.clone(),
+        schema,
+        &[],
+        use_timestamp_ntz,
+        use_timestamp_ntz,
+    )
+    .await?;
+
+    let mut adds = Vec::with_capacity(batches.len());
+    for (i, batch) in batches.iter().enumerate() {
+        if batch.schema() != first.schema() {
+            return Err(format!("Batch {i} has a different schema than the first batch").into());
+        }
+        let data = record_batch_to_bytes(batch);
+        let file_name = format!("part-{i:05}.parquet");
+        let size = data.len();
+        let path = table_url.join(&file_name)?;
+        store
+            .put(&Path::from_url_path(path.path())?, data.into())
+            .await?;
+        let stats = json!({ "numRecords": batch.num_rows() }).to_string();
+        let add = json!({
+            "add": {
+                "path": file_name,
+                "partitionValues": {},
+                "size": size,
+                "modificationTime": 1677811178336u64,
+                "dataChange": true,
+                "stats": stats,
+            }
+        });
+        adds.push(add.to_string());
+    }
+    let path = table_url.join("_delta_log/00000000000000000001.json")?;
+    store
+        .put(&Path::from_url_path(path.path())?, adds.join("\n").into())
+        .await?;
+
+    Ok((Arc::new(engine), table_url))
+}
+
+/// Materialize an in-memory Delta table from the given arrow `RecordBatch`es. Expands to a future
+/// resolving to `(engine, table_url)`; see [`memory_table()`].
+///
+/// ```ignore
+/// let (engine, table_url) = memory_table!(batch1, batch2).await?;
+/// let snapshot = Snapshot::try_new(table_url, engine.as_ref(), None)?;
+/// ```
+#[macro_export]
+macro_rules! memory_table {
+    ($($batch:expr),+ $(,)?) => {
+        $crate::memory_table(vec![$($batch),+])
+    };
+}
+
 pub fn to_arrow(data: Box<dyn EngineData>) -> DeltaResult<RecordBatch> {
     Ok(data
         .into_any()